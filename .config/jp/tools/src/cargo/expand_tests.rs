@@ -14,6 +14,7 @@ fn test_cargo_expand_success() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let stdout = indoc::indoc! { r#"
@@ -16,6 +16,7 @@ fn test_cargo_test_success() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let stdout = r#"{"type":"test","event":"ok","name":"my_test","stdout":""}"#;
@@ -38,6 +39,7 @@ fn test_cargo_test_with_failure() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let stdout = r#"{"type":"test","event":"failed","name":"my_crate$tests::my_test","stdout":"assertion failed"}"#;
@@ -73,6 +75,7 @@ fn no_tests_ran_error_is_bounded() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     // A panicking proc-macro derive produces one diagnostic per expansion site,
@@ -110,6 +113,7 @@ fn failure_output_is_bounded_across_the_whole_run() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     // A broken shared fixture fails every test in the workspace, each one
@@ -159,6 +163,7 @@ fn failure_blocks_are_bounded_when_captured_output_is_empty() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     // Failing tests that print nothing still cost a serialized block each. If
@@ -244,6 +249,7 @@ fn test_backtrace_disabled_by_default() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let stdout = r#"{"type":"test","event":"ok","name":"my_test","stdout":""}"#;
@@ -269,6 +275,7 @@ fn test_checksum_freshness_disabled_by_default() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let stdout = r#"{"type":"test","event":"ok","name":"my_test","stdout":""}"#;
@@ -293,6 +300,7 @@ fn test_checksum_freshness_enabled() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let stdout = r#"{"type":"test","event":"ok","name":"my_test","stdout":""}"#;
@@ -318,6 +326,7 @@ fn test_backtrace_enabled() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let stdout = r#"{"type":"test","event":"ok","name":"my_test","stdout":""}"#;
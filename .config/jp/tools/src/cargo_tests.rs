@@ -284,6 +284,7 @@ async fn a_non_string_root_option_fails_the_invocation() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
     let tool = Tool {
         name: "cargo_check".to_owned(),
@@ -0,0 +1,111 @@
+use camino::Utf8PathBuf;
+use ignore::gitignore::Gitignore;
+
+use crate::{
+    Context, Tool,
+    fs::{
+        list_files::fs_list_files,
+        utils::{resolve_workspace_path, suppress_matcher},
+    },
+    util::{OneOrMany, ToolResult, unknown_tool},
+};
+
+mod outline;
+mod symbol;
+mod syntax;
+
+use outline::code_outline;
+use symbol::code_symbol;
+use syntax::Lang;
+
+pub async fn run(ctx: Context, t: Tool) -> ToolResult {
+    // Same semantics as the `suppress` option of the `fs_*` tools: the outline
+    // and symbol tools hand back file contents, so they honor it too.
+    let patterns: Vec<String> = match t.options.get("suppress") {
+        None => vec![],
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|error| format!("Invalid `suppress` option for tool '{}': {error}", t.name))?,
+    };
+    let suppress = suppress_matcher(&ctx.root, &patterns)?;
+
+    match t.name.trim_start_matches("code_") {
+        "outline" => code_outline(&ctx, &suppress, t.opt("path")?).await,
+        "symbol" => code_symbol(&ctx, &suppress, &t.req::<String>("name")?, t.opt("path")?).await,
+        _ => unknown_tool(t),
+    }
+}
+
+/// A source file in a supported language.
+struct Source {
+    /// The workspace-relative path of the file.
+    path: Utf8PathBuf,
+    lang: Lang,
+    contents: String,
+}
+
+/// The source files at `path`, a file or directory, or in the whole workspace
+/// when no path is given.
+///
+/// Files are found the way `fs_list_files` finds them, so the access policy
+/// and `suppress` patterns apply the same way.
+/// Paths that were requested but skipped are described in the returned notes.
+async fn sources(
+    ctx: &Context,
+    suppress: &Gitignore,
+    path: Option<String>,
+) -> Result<(Vec<Source>, Vec<String>), String> {
+    let path = path.unwrap_or_default();
+
+    // A named file in an unsupported language would otherwise silently produce
+    // nothing, as the listing filters it out by extension.
+    if !path.is_empty()
+        && let Ok(resolved) = resolve_workspace_path(&ctx.root, &path, ctx.access.as_ref())
+        && resolved.absolute.is_file()
+        && Lang::from_path(&resolved.relative).is_none()
+    {
+        return Err(format!(
+            "Unsupported language for '{path}'. Supported file extensions: {}.",
+            Lang::EXTENSIONS.join(", ")
+        ));
+    }
+
+    let extensions = Lang::EXTENSIONS.iter().map(|&ext| ext.to_owned()).collect();
+    let files = fs_list_files(
+        &ctx.root,
+        ctx.access.as_ref(),
+        Some(OneOrMany::One(path)),
+        Some(OneOrMany::Many(extensions)),
+        suppress,
+    )
+    .await
+    .map_err(|error| error.to_string())?;
+
+    let notes = files.notes();
+    let mut sources = vec![];
+    for file in files.into_files() {
+        let path = Utf8PathBuf::from(file.replace(std::path::MAIN_SEPARATOR, "/"));
+        let Some(lang) = Lang::from_path(&path) else {
+            continue;
+        };
+
+        let resolved = resolve_workspace_path(&ctx.root, path.as_str(), ctx.access.as_ref())?;
+
+        // Not every file with a known extension is text; those are skipped
+        // rather than failing the whole request.
+        let Ok(contents) = std::fs::read_to_string(&resolved.absolute) else {
+            continue;
+        };
+
+        sources.push(Source {
+            path,
+            lang,
+            contents,
+        });
+    }
+
+    Ok((sources, notes))
+}
+
+#[cfg(test)]
+#[path = "code_tests.rs"]
+mod tests;
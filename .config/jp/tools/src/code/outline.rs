@@ -0,0 +1,62 @@
+use ignore::gitignore::Gitignore;
+use jp_tool::Context;
+
+use super::{sources, syntax};
+use crate::util::{ToolResult, error, truncate};
+
+/// The maximum size of an outline, in bytes.
+///
+/// An outline of a large directory can still exceed what fits comfortably in
+/// the assistant's context; narrowing `path` is the way around it.
+const MAX_OUTLINE_LEN: usize = 64 * 1024;
+
+/// List the items in the source files at `path`, with their signatures and
+/// line ranges.
+pub(crate) async fn code_outline(
+    ctx: &Context,
+    suppress: &Gitignore,
+    path: Option<String>,
+) -> ToolResult {
+    let (sources, notes) = match sources(ctx, suppress, path).await {
+        Ok(v) => v,
+        Err(msg) => return error(msg),
+    };
+
+    let mut out = String::new();
+    for source in &sources {
+        let items = match syntax::items(source.lang, &source.contents) {
+            Ok(items) if items.is_empty() => continue,
+            Ok(items) => items,
+            Err(msg) => {
+                out.push_str(&format!("{}: {msg}\n", source.path));
+                continue;
+            }
+        };
+
+        out.push_str(&format!("{}\n", source.path));
+        for item in items {
+            let indent = "  ".repeat(item.depth() + 1);
+            let lines = if item.start_line == item.end_line {
+                item.start_line.to_string()
+            } else {
+                format!("{}-{}", item.start_line, item.end_line)
+            };
+
+            out.push_str(&format!("{indent}{lines}: {}\n", item.signature));
+        }
+    }
+
+    if out.is_empty() {
+        out.push_str("No items found.\n");
+    }
+
+    for note in notes {
+        out.push_str(&format!("\n{note}"));
+    }
+
+    Ok(truncate(out.trim_end(), MAX_OUTLINE_LEN).into())
+}
+
+#[cfg(test)]
+#[path = "outline_tests.rs"]
+mod tests;
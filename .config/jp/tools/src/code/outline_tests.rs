@@ -0,0 +1,68 @@
+use camino_tempfile::tempdir;
+use indoc::indoc;
+use jp_tool::{Action, Outcome};
+use pretty_assertions::assert_eq;
+
+use super::*;
+use crate::fs::utils::suppress_matcher;
+
+fn run(root: &camino::Utf8Path, suppress: &Gitignore, path: Option<&str>) -> String {
+    let ctx = Context {
+        root: root.to_path_buf(),
+        action: Action::Run,
+        access: None,
+        workspace_id: "test".into(),
+        conversation_id: "test".into(),
+        checkpoint: None,
+    };
+
+    let outcome = tokio::runtime::Runtime::new()
+        .unwrap()
+        .block_on(code_outline(&ctx, suppress, path.map(str::to_owned)))
+        .unwrap();
+
+    match outcome {
+        Outcome::Success { content } => content,
+        Outcome::Error { message, .. } => message,
+        Outcome::NeedsInput { .. } | Outcome::Handle { .. } => String::new(),
+    }
+}
+
+#[test]
+fn test_outline_of_a_directory() {
+    let ws = tempdir().unwrap();
+    std::fs::create_dir_all(ws.path().join("src")).unwrap();
+    std::fs::write(ws.path().join("src/lib.rs"), indoc! {"
+        pub struct Config;
+
+        impl Config {
+            pub fn new() -> Self {
+                Self
+            }
+        }
+    "})
+    .unwrap();
+    std::fs::write(ws.path().join("src/main.py"), "def main():\n    pass\n").unwrap();
+    std::fs::write(ws.path().join("src/empty.rs"), "use std::fmt;\n").unwrap();
+
+    assert_eq!(run(ws.path(), &Gitignore::empty(), Some("src")), indoc! {"
+        src/lib.rs
+          1: pub struct Config
+          3-7: impl Config
+            4-6: pub fn new() -> Self
+        src/main.py
+          1-2: def main()"});
+}
+
+#[test]
+fn test_outline_notes_suppressed_paths() {
+    let ws = tempdir().unwrap();
+    std::fs::create_dir_all(ws.path().join("vendor")).unwrap();
+    std::fs::write(ws.path().join("vendor/lib.rs"), "fn a() {}\n").unwrap();
+
+    let suppress = suppress_matcher(ws.path(), &["vendor/".to_owned()]).unwrap();
+    let out = run(ws.path(), &suppress, Some("vendor"));
+
+    assert!(out.starts_with("No items found.\n\n"), "{out}");
+    assert!(out.contains("vendor"), "{out}");
+}
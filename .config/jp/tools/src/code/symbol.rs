@@ -0,0 +1,104 @@
+use ignore::gitignore::Gitignore;
+use jp_tool::Context;
+
+use super::{sources, syntax};
+use crate::util::{ToolResult, error, lang_from_path};
+
+/// The maximum number of matching items returned.
+const MAX_MATCHES: usize = 10;
+
+/// Return the source of the items named `name` in the source files at `path`.
+pub(crate) async fn code_symbol(
+    ctx: &Context,
+    suppress: &Gitignore,
+    name: &str,
+    path: Option<String>,
+) -> ToolResult {
+    let name = name.trim();
+    if name.is_empty() {
+        return error("`name` must not be empty.");
+    }
+
+    let (sources, notes) = match sources(ctx, suppress, path).await {
+        Ok(v) => v,
+        Err(msg) => return error(msg),
+    };
+
+    let mut matches = vec![];
+    for source in &sources {
+        // A file that fails to parse cannot hold the symbol; the outline tool
+        // reports such files.
+        let Ok(items) = syntax::items(source.lang, &source.contents) else {
+            continue;
+        };
+
+        matches.extend(
+            items
+                .into_iter()
+                .filter(|item| item.matches(name))
+                .map(|item| (source, item)),
+        );
+    }
+
+    if matches.is_empty() {
+        let mut msg = format!("No item named '{name}' found.");
+        for note in notes {
+            msg.push_str(&format!(" {note}"));
+        }
+        return error(msg);
+    }
+
+    let mut out = String::new();
+    for (source, item) in matches.iter().take(MAX_MATCHES) {
+        let path = source.path.as_str();
+        let qualified = item
+            .scope
+            .iter()
+            .chain([&item.name])
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("::");
+
+        let width = item.end_line.to_string().len();
+        let body = source
+            .contents
+            .split('\n')
+            .enumerate()
+            .skip(item.doc_line - 1)
+            .take(item.end_line + 1 - item.doc_line)
+            .map(|(idx, line)| format!("{num:>width$}: {line}", num = idx + 1))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        out.push_str(&indoc::formatdoc! {"
+            {path}:{start}-{end} ({kind} {qualified})
+            ```{lang}
+            {body}
+            ```
+
+        ",
+            start = item.doc_line,
+            end = item.end_line,
+            kind = item.kind,
+            lang = lang_from_path(path),
+        });
+    }
+
+    if matches.len() > MAX_MATCHES {
+        out.push_str(&format!(
+            "Showing {MAX_MATCHES} of {} matches. Qualify the name (e.g. `Type::method`) or \
+             narrow `path` to see the others.\n",
+            matches.len()
+        ));
+    }
+
+    for note in notes {
+        out.push_str(&format!("{note}\n"));
+    }
+
+    Ok(out.trim_end().to_owned().into())
+}
+
+#[cfg(test)]
+#[path = "symbol_tests.rs"]
+mod tests;
@@ -0,0 +1,86 @@
+use camino_tempfile::tempdir;
+use indoc::indoc;
+use jp_tool::{Action, Outcome};
+use pretty_assertions::assert_eq;
+
+use super::*;
+
+fn run(root: &camino::Utf8Path, name: &str, path: Option<&str>) -> Result<String, String> {
+    let ctx = Context {
+        root: root.to_path_buf(),
+        action: Action::Run,
+        access: None,
+        workspace_id: "test".into(),
+        conversation_id: "test".into(),
+        checkpoint: None,
+    };
+
+    let outcome = tokio::runtime::Runtime::new()
+        .unwrap()
+        .block_on(code_symbol(
+            &ctx,
+            &Gitignore::empty(),
+            name,
+            path.map(str::to_owned),
+        ))
+        .unwrap();
+
+    match outcome {
+        Outcome::Success { content } => Ok(content),
+        Outcome::Error { message, .. } => Err(message),
+        Outcome::NeedsInput { .. } | Outcome::Handle { .. } => Err(String::new()),
+    }
+}
+
+const SOURCE: &str = indoc! {"
+    pub struct Config;
+
+    impl Config {
+        /// Create a config.
+        pub fn new() -> Self {
+            Self
+        }
+    }
+
+    pub fn new() {}
+"};
+
+#[test]
+fn test_symbol_by_qualified_name() {
+    let ws = tempdir().unwrap();
+    std::fs::write(ws.path().join("lib.rs"), SOURCE).unwrap();
+
+    assert_eq!(run(ws.path(), "Config::new", None).unwrap(), indoc! {"
+        lib.rs:4-7 (fn Config::new)
+        ```rust
+        4:     /// Create a config.
+        5:     pub fn new() -> Self {
+        6:         Self
+        7:     }
+        ```"});
+}
+
+#[test]
+fn test_symbol_by_bare_name_returns_every_match() {
+    let ws = tempdir().unwrap();
+    std::fs::write(ws.path().join("lib.rs"), SOURCE).unwrap();
+
+    let out = run(ws.path(), "new", Some("lib.rs")).unwrap();
+    assert!(out.contains("lib.rs:4-7 (fn Config::new)"), "{out}");
+    assert!(out.contains("lib.rs:10-10 (fn new)"), "{out}");
+}
+
+#[test]
+fn test_symbol_not_found() {
+    let ws = tempdir().unwrap();
+    std::fs::write(ws.path().join("lib.rs"), SOURCE).unwrap();
+
+    assert_eq!(
+        run(ws.path(), "Missing", None).unwrap_err(),
+        "No item named 'Missing' found."
+    );
+    assert_eq!(
+        run(ws.path(), " ", None).unwrap_err(),
+        "`name` must not be empty."
+    );
+}
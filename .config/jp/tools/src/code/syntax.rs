@@ -0,0 +1,408 @@
+//! Extract the items of a source file using tree-sitter grammars.
+
+use camino::Utf8Path;
+use tree_sitter::{Node, Parser};
+
+/// The maximum length of a rendered signature, in characters.
+const MAX_SIGNATURE_LEN: usize = 160;
+
+/// A language with a bundled tree-sitter grammar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Lang {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+}
+
+impl Lang {
+    /// The file extensions of all supported languages.
+    pub(crate) const EXTENSIONS: &[&str] = &[
+        "rs", "py", "pyi", "js", "mjs", "cjs", "jsx", "ts", "mts", "cts", "tsx", "go",
+    ];
+
+    /// The language of `path`, based on its extension.
+    pub(crate) fn from_path(path: &Utf8Path) -> Option<Self> {
+        let lang = match path.extension()? {
+            "rs" => Self::Rust,
+            "py" | "pyi" => Self::Python,
+            "js" | "mjs" | "cjs" | "jsx" => Self::JavaScript,
+            "ts" | "mts" | "cts" => Self::TypeScript,
+            "tsx" => Self::Tsx,
+            "go" => Self::Go,
+            _ => return None,
+        };
+
+        Some(lang)
+    }
+
+    fn grammar(self) -> tree_sitter::Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+
+    /// Nodes that hold items without being items themselves, such as
+    /// `export` statements or decorated definitions.
+    fn is_wrapper(self, kind: &str) -> bool {
+        match self {
+            Self::Rust => false,
+            Self::Python => kind == "decorated_definition",
+            Self::JavaScript | Self::TypeScript | Self::Tsx => {
+                matches!(kind, "export_statement" | "ambient_declaration") || is_declaration(kind)
+            }
+            Self::Go => kind == "type_declaration",
+        }
+    }
+
+    /// The kind of item `node` is, if it is one.
+    fn item_kind(self, node: Node<'_>) -> Option<&'static str> {
+        let kind = match (self, node.kind()) {
+            (Self::Rust, "function_item" | "function_signature_item") => "fn",
+            (Self::Rust, "struct_item") => "struct",
+            (Self::Rust, "enum_item") => "enum",
+            (Self::Rust, "union_item") => "union",
+            (Self::Rust, "trait_item") => "trait",
+            (Self::Rust, "impl_item") => "impl",
+            (Self::Rust, "mod_item") => "mod",
+            (Self::Rust, "type_item") => "type",
+            (Self::Rust, "const_item") => "const",
+            (Self::Rust, "static_item") => "static",
+            (Self::Rust, "macro_definition") => "macro",
+
+            (Self::Python, "function_definition") => "def",
+            (Self::Python, "class_definition") => "class",
+
+            (
+                Self::JavaScript | Self::TypeScript | Self::Tsx,
+                "function_declaration"
+                | "generator_function_declaration"
+                | "function_signature"
+                | "method_definition"
+                | "abstract_method_signature",
+            ) => "function",
+            (
+                Self::JavaScript | Self::TypeScript | Self::Tsx,
+                "class_declaration" | "abstract_class_declaration",
+            ) => "class",
+            // Only `const foo = () => {}` and friends; other bindings are not
+            // items.
+            (Self::JavaScript | Self::TypeScript | Self::Tsx, "variable_declarator") => {
+                let value = node.child_by_field_name("value")?;
+                if !matches!(
+                    value.kind(),
+                    "arrow_function" | "function_expression" | "function" | "generator_function"
+                ) {
+                    return None;
+                }
+                "function"
+            }
+            (Self::TypeScript | Self::Tsx, "interface_declaration") => "interface",
+            (Self::TypeScript | Self::Tsx, "type_alias_declaration") => "type",
+            (Self::TypeScript | Self::Tsx, "enum_declaration") => "enum",
+            (Self::TypeScript | Self::Tsx, "internal_module") => "namespace",
+
+            (Self::Go, "function_declaration" | "method_declaration") => "func",
+            (Self::Go, "type_spec" | "type_alias") => "type",
+
+            _ => return None,
+        };
+
+        Some(kind)
+    }
+
+    /// The body of `node` whose items are nested items, if `node` is a
+    /// container such as an `impl` block or a class.
+    fn container_body(self, node: Node<'_>) -> Option<Node<'_>> {
+        let container = match self {
+            Self::Rust => matches!(node.kind(), "impl_item" | "trait_item" | "mod_item"),
+            Self::Python => node.kind() == "class_definition",
+            Self::JavaScript | Self::TypeScript | Self::Tsx => matches!(
+                node.kind(),
+                "class_declaration" | "abstract_class_declaration" | "internal_module"
+            ),
+            Self::Go => false,
+        };
+
+        if container {
+            node.child_by_field_name("body")
+        } else {
+            None
+        }
+    }
+}
+
+/// A named item in a source file, such as a function, type or `impl` block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Item {
+    /// The kind of item, e.g. `fn` or `class`.
+    pub kind: &'static str,
+
+    /// The name of the item.
+    ///
+    /// For an `impl` block this is the implementing type.
+    pub name: String,
+
+    /// The names of the items this item is nested in, outermost first.
+    pub scope: Vec<String>,
+
+    /// The item's declaration, without its body.
+    pub signature: String,
+
+    /// The 1-based line the item starts at.
+    pub start_line: usize,
+
+    /// The 1-based line the item ends at.
+    pub end_line: usize,
+
+    /// The 1-based line the item's doc comments and attributes start at.
+    ///
+    /// Equal to `start_line` for undocumented items.
+    pub doc_line: usize,
+}
+
+impl Item {
+    /// How deeply the item is nested.
+    pub(crate) fn depth(&self) -> usize {
+        self.scope.len()
+    }
+
+    /// Whether the item is named by `query`.
+    ///
+    /// A query is a name, optionally qualified by the names of enclosing
+    /// items, separated by `::` or `.`: `new`, `Config::new` and `Config.new`
+    /// all name the `new` method of `Config`.
+    pub(crate) fn matches(&self, query: &str) -> bool {
+        let segments = query
+            .split("::")
+            .flat_map(|segment| segment.split('.'))
+            .map(str::trim)
+            .collect::<Vec<_>>();
+
+        if segments.iter().any(|segment| segment.is_empty()) {
+            return false;
+        }
+
+        let path = self
+            .scope
+            .iter()
+            .map(String::as_str)
+            .chain([self.name.as_str()])
+            .collect::<Vec<_>>();
+
+        path.ends_with(&segments)
+    }
+}
+
+/// Parse `source` and return its items in source order.
+pub(crate) fn items(lang: Lang, source: &str) -> Result<Vec<Item>, String> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&lang.grammar())
+        .map_err(|error| format!("Unable to load {lang:?} grammar: {error}"))?;
+
+    let tree = parser
+        .parse(source, None)
+        .ok_or_else(|| "Unable to parse file.".to_owned())?;
+
+    let mut items = vec![];
+    collect(lang, tree.root_node(), source, &mut vec![], &mut items);
+    Ok(items)
+}
+
+fn collect(
+    lang: Lang,
+    node: Node<'_>,
+    source: &str,
+    scope: &mut Vec<String>,
+    items: &mut Vec<Item>,
+) {
+    let mut cursor = node.walk();
+    let children = node.named_children(&mut cursor).collect::<Vec<_>>();
+
+    for child in children {
+        if lang.is_wrapper(child.kind()) {
+            collect(lang, child, source, scope, items);
+            continue;
+        }
+
+        let Some(kind) = lang.item_kind(child) else {
+            continue;
+        };
+
+        let Some(name) = name(child, source) else {
+            continue;
+        };
+
+        let outer = outermost(lang, child);
+        let mut item_scope = scope.clone();
+        item_scope.extend(receiver(child, source));
+
+        items.push(Item {
+            kind,
+            name: name.clone(),
+            scope: item_scope,
+            signature: signature(child, source),
+            start_line: outer.start_position().row + 1,
+            end_line: outer.end_position().row + 1,
+            doc_line: doc_start(outer).start_position().row + 1,
+        });
+
+        if let Some(body) = lang.container_body(child) {
+            scope.push(name);
+            collect(lang, body, source, scope, items);
+            scope.pop();
+        }
+    }
+}
+
+/// The name of an item node.
+fn name(node: Node<'_>, source: &str) -> Option<String> {
+    match node.kind() {
+        // `impl<T> Trait for Foo<T>` is named after `Foo`.
+        "impl_item" => text(node.child_by_field_name("type")?, source).map(base_name),
+        _ => text(node.child_by_field_name("name")?, source).map(str::to_owned),
+    }
+}
+
+/// The receiver type of a Go method, which scopes the method the way an
+/// enclosing `impl` block or class does in other languages.
+fn receiver(node: Node<'_>, source: &str) -> Option<String> {
+    if node.kind() != "method_declaration" {
+        return None;
+    }
+
+    let mut pending = vec![node.child_by_field_name("receiver")?];
+    while let Some(node) = pending.pop() {
+        if node.kind() == "type_identifier" {
+            return text(node, source).map(str::to_owned);
+        }
+
+        let mut cursor = node.walk();
+        pending.extend(
+            node.named_children(&mut cursor)
+                .collect::<Vec<_>>()
+                .into_iter()
+                .rev(),
+        );
+    }
+
+    None
+}
+
+fn text<'a>(node: Node<'_>, source: &'a str) -> Option<&'a str> {
+    node.utf8_text(source.as_bytes()).ok()
+}
+
+/// The bare name of a type: `&'a crate::Foo<T>` becomes `Foo`.
+fn base_name(ty: &str) -> String {
+    let ty = ty.split('<').next().unwrap_or(ty);
+    let ty = ty.rsplit("::").next().unwrap_or(ty);
+    ty.rsplit(' ')
+        .next()
+        .unwrap_or(ty)
+        .trim_start_matches('&')
+        .to_owned()
+}
+
+/// The outermost node that belongs to `node`, such as the `export` statement
+/// or `decorated_definition` that wraps it.
+fn outermost<'a>(lang: Lang, node: Node<'a>) -> Node<'a> {
+    let mut outer = node;
+    while let Some(parent) = outer.parent()
+        && lang.is_wrapper(parent.kind())
+    {
+        // A declaration of several bindings or types belongs to none of them.
+        if (is_declaration(parent.kind()) || parent.kind() == "type_declaration")
+            && parent.named_child_count() > 1
+        {
+            break;
+        }
+
+        outer = parent;
+    }
+
+    outer
+}
+
+/// The first of the comments and attributes directly preceding `node`.
+fn doc_start(node: Node<'_>) -> Node<'_> {
+    let mut start = node;
+    while let Some(prev) = start.prev_named_sibling()
+        && matches!(
+            prev.kind(),
+            "attribute_item" | "line_comment" | "block_comment" | "comment"
+        )
+        && prev.end_position().row + 1 >= start.start_position().row
+    {
+        start = prev;
+    }
+
+    start
+}
+
+/// The declaration of an item, without its body, on a single line.
+fn signature(node: Node<'_>, source: &str) -> String {
+    // A function bound to a variable is declared by the whole binding, and its
+    // body belongs to the function expression.
+    let (start, body) = if node.kind() == "variable_declarator" {
+        let body = node
+            .child_by_field_name("value")
+            .and_then(|value| value.child_by_field_name("body"));
+        (declaration_start(node), body)
+    } else {
+        (node, node.child_by_field_name("body"))
+    };
+
+    let text = match body {
+        Some(body) => &source[start.start_byte()..body.start_byte()],
+
+        // Body-less items can span many lines, e.g. a struct type in Go or a
+        // long constant; what comes before the first brace is declaration
+        // enough.
+        None => {
+            let text = &source[start.start_byte()..node.end_byte()];
+            text.split('{').next().unwrap_or(text)
+        }
+    };
+
+    let mut signature = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let trimmed = signature.trim_end_matches(['{', ':', '=', ';', ' ']).len();
+    signature.truncate(trimmed);
+
+    // Go type specs leave out the keyword that all the other languages carry.
+    if matches!(node.kind(), "type_spec" | "type_alias") {
+        signature.insert_str(0, "type ");
+    }
+
+    if signature.chars().count() > MAX_SIGNATURE_LEN {
+        signature = signature.chars().take(MAX_SIGNATURE_LEN).collect();
+        signature.push('…');
+    }
+
+    signature
+}
+
+/// The `const`, `let` or `var` declaration a variable declarator belongs to,
+/// if it is the only binding in it.
+fn declaration_start(node: Node<'_>) -> Node<'_> {
+    match node.parent() {
+        Some(parent) if is_declaration(parent.kind()) && parent.named_child_count() == 1 => parent,
+        _ => node,
+    }
+}
+
+fn is_declaration(kind: &str) -> bool {
+    matches!(kind, "lexical_declaration" | "variable_declaration")
+}
+
+#[cfg(test)]
+#[path = "syntax_tests.rs"]
+mod tests;
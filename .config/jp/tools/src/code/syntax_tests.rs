@@ -0,0 +1,246 @@
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+use super::*;
+
+/// The `(scope::name, kind, signature, start, end)` of each item.
+fn outline(lang: Lang, source: &str) -> Vec<(String, &'static str, String, usize, usize)> {
+    items(lang, source)
+        .unwrap()
+        .into_iter()
+        .map(|item| {
+            let mut path = item.scope.clone();
+            path.push(item.name);
+            (
+                path.join("::"),
+                item.kind,
+                item.signature,
+                item.start_line,
+                item.end_line,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_rust_items() {
+    let source = indoc! {"
+        use std::fmt;
+
+        /// A point.
+        #[derive(Debug)]
+        pub struct Point {
+            x: i32,
+        }
+
+        impl<T> fmt::Display for Wrapper<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Ok(())
+            }
+        }
+
+        pub(crate) fn distance(
+            a: &Point,
+            b: &Point,
+        ) -> i32 {
+            0
+        }
+
+        const MAX: usize = 10;
+    "};
+
+    assert_eq!(outline(Lang::Rust, source), vec![
+        (
+            "Point".to_owned(),
+            "struct",
+            "pub struct Point".to_owned(),
+            5,
+            7
+        ),
+        (
+            "Wrapper".to_owned(),
+            "impl",
+            "impl<T> fmt::Display for Wrapper<T>".to_owned(),
+            9,
+            13
+        ),
+        (
+            "Wrapper::fmt".to_owned(),
+            "fn",
+            "fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result".to_owned(),
+            10,
+            12
+        ),
+        (
+            "distance".to_owned(),
+            "fn",
+            "pub(crate) fn distance( a: &Point, b: &Point, ) -> i32".to_owned(),
+            15,
+            20
+        ),
+        (
+            "MAX".to_owned(),
+            "const",
+            "const MAX: usize = 10".to_owned(),
+            22,
+            22
+        ),
+    ]);
+}
+
+#[test]
+fn test_rust_doc_line_covers_comments_and_attributes() {
+    let source = indoc! {"
+        fn first() {}
+
+        /// A point.
+        #[derive(Debug)]
+        struct Point;
+    "};
+
+    let items = items(Lang::Rust, source).unwrap();
+    assert_eq!(items[0].doc_line, 1);
+    assert_eq!(items[1].doc_line, 3);
+    assert_eq!(items[1].start_line, 5);
+}
+
+#[test]
+fn test_python_items() {
+    let source = indoc! {"
+        @dataclass
+        class Config:
+            def load(self, path: str) -> None:
+                pass
+
+        def main():
+            pass
+    "};
+
+    assert_eq!(outline(Lang::Python, source), vec![
+        (
+            "Config".to_owned(),
+            "class",
+            "class Config".to_owned(),
+            1,
+            4
+        ),
+        (
+            "Config::load".to_owned(),
+            "def",
+            "def load(self, path: str) -> None".to_owned(),
+            3,
+            4
+        ),
+        ("main".to_owned(), "def", "def main()".to_owned(), 6, 7),
+    ]);
+}
+
+#[test]
+fn test_typescript_items() {
+    let source = indoc! {"
+        export interface Options {
+            verbose: boolean;
+        }
+
+        export const run = async (options: Options) => {
+            return 1;
+        };
+
+        class Runner {
+            start(): void {}
+        }
+    "};
+
+    assert_eq!(outline(Lang::TypeScript, source), vec![
+        (
+            "Options".to_owned(),
+            "interface",
+            "interface Options".to_owned(),
+            1,
+            3
+        ),
+        (
+            "run".to_owned(),
+            "function",
+            "const run = async (options: Options) =>".to_owned(),
+            5,
+            7
+        ),
+        (
+            "Runner".to_owned(),
+            "class",
+            "class Runner".to_owned(),
+            9,
+            11
+        ),
+        (
+            "Runner::start".to_owned(),
+            "function",
+            "start(): void".to_owned(),
+            10,
+            10
+        ),
+    ]);
+}
+
+#[test]
+fn test_go_methods_are_scoped_to_their_receiver() {
+    let source = indoc! {"
+        package main
+
+        type Server struct {
+            addr string
+        }
+
+        func (s *Server) Start() error {
+            return nil
+        }
+    "};
+
+    assert_eq!(outline(Lang::Go, source), vec![
+        (
+            "Server".to_owned(),
+            "type",
+            "type Server struct".to_owned(),
+            3,
+            5
+        ),
+        (
+            "Server::Start".to_owned(),
+            "func",
+            "func (s *Server) Start() error".to_owned(),
+            7,
+            9
+        ),
+    ]);
+}
+
+#[test]
+fn test_item_matches() {
+    let item = Item {
+        kind: "fn",
+        name: "new".to_owned(),
+        scope: vec!["config".to_owned(), "Config".to_owned()],
+        signature: "pub fn new() -> Self".to_owned(),
+        start_line: 1,
+        end_line: 1,
+        doc_line: 1,
+    };
+
+    assert!(item.matches("new"));
+    assert!(item.matches("Config::new"));
+    assert!(item.matches("Config.new"));
+    assert!(item.matches("config::Config::new"));
+    assert!(!item.matches("Other::new"));
+    assert!(!item.matches("Config"));
+    assert!(!item.matches("::new"));
+    assert!(!item.matches(""));
+}
+
+#[test]
+fn test_base_name() {
+    assert_eq!(base_name("Foo"), "Foo");
+    assert_eq!(base_name("Foo<T>"), "Foo");
+    assert_eq!(base_name("crate::config::Foo<'a>"), "Foo");
+    assert_eq!(base_name("&'a Foo"), "Foo");
+}
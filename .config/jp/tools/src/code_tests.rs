@@ -0,0 +1,79 @@
+use camino_tempfile::tempdir;
+use jp_tool::{AccessPolicy, Action, FsRule};
+
+use super::*;
+
+fn context(root: &camino::Utf8Path, access: Option<AccessPolicy>) -> Context {
+    Context {
+        root: root.to_path_buf(),
+        action: Action::Run,
+        access,
+        workspace_id: "test".into(),
+        conversation_id: "test".into(),
+        checkpoint: None,
+    }
+}
+
+#[tokio::test]
+async fn test_sources_skips_unsupported_and_unreadable_files() {
+    let ws = tempdir().unwrap();
+    std::fs::create_dir_all(ws.path().join("src")).unwrap();
+    std::fs::write(ws.path().join("src/lib.rs"), "fn a() {}").unwrap();
+    std::fs::write(ws.path().join("src/notes.txt"), "text").unwrap();
+    std::fs::write(ws.path().join("src/data.rs"), [0xff, 0xfe]).unwrap();
+
+    let ctx = context(ws.path(), None);
+    let (sources, notes) = sources(&ctx, &Gitignore::empty(), Some("src".to_owned()))
+        .await
+        .unwrap();
+
+    let paths = sources.iter().map(|s| s.path.as_str()).collect::<Vec<_>>();
+    assert_eq!(paths, vec!["src/lib.rs"]);
+    assert_eq!(sources[0].lang, Lang::Rust);
+    assert!(notes.is_empty(), "{notes:?}");
+}
+
+#[tokio::test]
+async fn test_sources_rejects_a_named_file_in_an_unsupported_language() {
+    let ws = tempdir().unwrap();
+    std::fs::write(ws.path().join("notes.txt"), "text").unwrap();
+
+    let ctx = context(ws.path(), None);
+    let Err(error) = sources(&ctx, &Gitignore::empty(), Some("notes.txt".to_owned())).await else {
+        panic!("expected an error");
+    };
+
+    assert!(
+        error.starts_with("Unsupported language for 'notes.txt'"),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn test_sources_honors_the_access_policy() {
+    let ws = tempdir().unwrap();
+    std::fs::create_dir_all(ws.path().join("src")).unwrap();
+    std::fs::create_dir_all(ws.path().join("secret")).unwrap();
+    std::fs::write(ws.path().join("src/lib.rs"), "fn a() {}").unwrap();
+    std::fs::write(ws.path().join("secret/lib.rs"), "fn b() {}").unwrap();
+
+    let policy = AccessPolicy {
+        fs: vec![FsRule::new("src").with_read(true)],
+        ..AccessPolicy::default()
+    };
+    let ctx = context(ws.path(), Some(policy));
+
+    let (sources, _) = sources(&ctx, &Gitignore::empty(), None).await.unwrap();
+    let paths = sources.iter().map(|s| s.path.as_str()).collect::<Vec<_>>();
+    assert_eq!(paths, vec!["src/lib.rs"]);
+
+    let (sources, notes) = sources_at(&ctx, "secret").await;
+    assert!(sources.is_empty());
+    assert_eq!(notes.len(), 1, "{notes:?}");
+}
+
+async fn sources_at(ctx: &Context, path: &str) -> (Vec<Source>, Vec<String>) {
+    sources(ctx, &Gitignore::empty(), Some(path.to_owned()))
+        .await
+        .unwrap()
+}
@@ -8,7 +8,7 @@ use crate::{
 mod create_file;
 mod delete_file;
 mod grep_files;
-mod list_files;
+pub(crate) mod list_files;
 mod modify_file;
 mod move_file;
 mod read_file;
@@ -93,13 +93,21 @@ pub async fn run(ctx: Context, t: Tool) -> ToolResult {
         "create_file" => fs_create_file(ctx, &t.answers, t.req("path")?, t.opt("content")?).await,
 
         "delete_file" => {
-            fs_delete_file(&ctx.root, ctx.access.as_ref(), &t.answers, t.req("path")?).await
+            fs_delete_file(
+                &ctx.root,
+                ctx.access.as_ref(),
+                ctx.checkpoint.as_ref(),
+                &t.answers,
+                t.req("path")?,
+            )
+            .await
         }
 
         "move_file" => {
             fs_move_file(
                 &ctx.root,
                 ctx.access.as_ref(),
+                ctx.checkpoint.as_ref(),
                 &t.answers,
                 t.req("source")?,
                 t.req("target")?,
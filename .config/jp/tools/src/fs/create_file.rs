@@ -8,7 +8,7 @@ use jp_md::format::Formatter;
 use jp_tool::{Capability, Outcome, Question};
 use serde_json::{Map, Value};
 
-use super::utils::{EntryKind, authorize, entry_kind, resolve_workspace_entry};
+use super::utils::{EntryKind, authorize, entry_kind, record_pre_image, resolve_workspace_entry};
 use crate::{
     Context,
     util::{ToolResult, error, fail},
@@ -88,6 +88,10 @@ pub(crate) async fn fs_create_file(
         return fail("Path has no parent");
     };
 
+    if let Err(msg) = record_pre_image(ctx.checkpoint.as_ref(), &ctx.root, &resolved.relative) {
+        return error(msg);
+    }
+
     fs::create_dir_all(parent)?;
     let mut file = File::options()
         .write(true)
@@ -11,6 +11,7 @@ fn format_ctx(dir: &camino_tempfile::Utf8TempDir) -> Context {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     }
 }
 
@@ -21,6 +22,7 @@ fn run_ctx(dir: &camino_tempfile::Utf8TempDir) -> Context {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     }
 }
 
@@ -1,17 +1,19 @@
 use std::fs;
 
 use camino::Utf8Path;
-use jp_tool::{AccessPolicy, Capability, Outcome, Question};
+use jp_tool::{AccessPolicy, Capability, Checkpoint, Outcome, Question};
 use serde_json::{Map, Value};
 
 use super::utils::{
-    EntryKind, ResolvedPath, authorize, entry_kind, is_file_dirty, resolve_workspace_entry,
+    EntryKind, ResolvedPath, authorize, entry_kind, is_file_dirty, record_pre_image,
+    resolve_workspace_entry,
 };
 use crate::util::{ToolResult, error};
 
 pub(crate) async fn fs_delete_file(
     root: &Utf8Path,
     access: Option<&AccessPolicy>,
+    checkpoint: Option<&Checkpoint>,
     answers: &Map<String, Value>,
     path: String,
 ) -> ToolResult {
@@ -63,6 +65,10 @@ pub(crate) async fn fs_delete_file(
         }
     }
 
+    if let Err(msg) = record_pre_image(checkpoint, root, &resolved.relative) {
+        return error(msg);
+    }
+
     fs::remove_file(&resolved.absolute)?;
     let mut msg = "File deleted.".to_owned();
 
@@ -58,6 +58,7 @@ async fn external_mount_delete_respects_capability() {
     let result = fs_delete_file(
         workspace.path(),
         Some(&read_only),
+        None,
         &no_answers(),
         "fork/f.txt".to_owned(),
     )
@@ -71,6 +72,7 @@ async fn external_mount_delete_respects_capability() {
     let result = fs_delete_file(
         workspace.path(),
         Some(&read_write),
+        None,
         &no_answers(),
         "fork/f.txt".to_owned(),
     )
@@ -91,7 +93,7 @@ async fn deleting_last_root_level_file_preserves_workspace() {
     let root = dir.path();
     std::fs::write(root.join("only.txt"), "x").unwrap();
 
-    let result = fs_delete_file(root, None, &no_answers(), "only.txt".to_owned())
+    let result = fs_delete_file(root, None, None, &no_answers(), "only.txt".to_owned())
         .await
         .unwrap();
 
@@ -123,6 +125,7 @@ async fn deleting_nested_file_removes_empty_parent() {
     let result = fs_delete_file(
         root,
         None,
+        None,
         &no_answers(),
         "nested/inner/file.txt".to_owned(),
     )
@@ -154,7 +157,7 @@ async fn deleting_symlink_removes_the_link_entry() {
     )
     .unwrap();
 
-    let result = fs_delete_file(root, None, &no_answers(), "link.txt".to_owned())
+    let result = fs_delete_file(root, None, None, &no_answers(), "link.txt".to_owned())
         .await
         .unwrap();
 
@@ -181,7 +184,7 @@ async fn deleting_dangling_symlink_succeeds() {
     )
     .unwrap();
 
-    let result = fs_delete_file(root, None, &no_answers(), "broken".to_owned())
+    let result = fs_delete_file(root, None, None, &no_answers(), "broken".to_owned())
         .await
         .unwrap();
 
@@ -199,6 +202,7 @@ async fn refuses_to_delete_a_path_a_deny_rule_closes() {
     let result = fs_delete_file(
         root,
         Some(&workspace_except(".git")),
+        None,
         &no_answers(),
         ".git/HEAD".to_owned(),
     )
@@ -221,9 +225,15 @@ async fn refuses_to_delete_a_path_a_deny_rule_closes() {
 #[tokio::test]
 async fn deleting_missing_path_errors() {
     let dir = tempdir().unwrap();
-    let result = fs_delete_file(dir.path(), None, &no_answers(), "ghost.txt".to_owned())
-        .await
-        .unwrap();
+    let result = fs_delete_file(
+        dir.path(),
+        None,
+        None,
+        &no_answers(),
+        "ghost.txt".to_owned(),
+    )
+    .await
+    .unwrap();
 
     match result {
         Outcome::Error { message, .. } => {
@@ -249,7 +259,7 @@ async fn deleting_socket_entry_errors() {
     // Bind keeps the file in place; tempdir cleanup will unlink it.
     let _listener = std::os::unix::net::UnixListener::bind(socket_path.as_std_path()).unwrap();
 
-    let result = fs_delete_file(root, None, &no_answers(), "my.sock".to_owned())
+    let result = fs_delete_file(root, None, None, &no_answers(), "my.sock".to_owned())
         .await
         .unwrap();
 
@@ -276,7 +286,7 @@ async fn deleting_directory_errors() {
     let root = dir.path();
     std::fs::create_dir(root.join("subdir")).unwrap();
 
-    let result = fs_delete_file(root, None, &no_answers(), "subdir".to_owned())
+    let result = fs_delete_file(root, None, None, &no_answers(), "subdir".to_owned())
         .await
         .unwrap();
 
@@ -288,3 +298,31 @@ async fn deleting_directory_errors() {
     }
     assert!(root.join("subdir").exists());
 }
+
+#[tokio::test]
+async fn deleting_records_the_file_for_undo() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("workspace");
+    std::fs::create_dir_all(root.join("nested")).unwrap();
+    std::fs::write(root.join("nested/file.txt"), "keep me").unwrap();
+
+    let checkpoint = jp_tool::Checkpoint::new(dir.path().join("checkpoint"));
+    let result = fs_delete_file(
+        &root,
+        None,
+        Some(&checkpoint),
+        &no_answers(),
+        "nested/file.txt".to_owned(),
+    )
+    .await
+    .unwrap();
+
+    unwrap_success(result);
+    assert!(!root.join("nested").exists());
+
+    checkpoint.restore(&root).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(root.join("nested/file.txt")).unwrap(),
+        "keep me"
+    );
+}
@@ -17,7 +17,7 @@ use serde::Deserialize;
 use serde_json::{Map, Value};
 use similar::ChangeTag;
 
-use super::utils::{authorize, is_file_dirty_impl, resolve_workspace_path};
+use super::utils::{authorize, is_file_dirty_impl, record_pre_image, resolve_workspace_path};
 use crate::{
     Context, Error,
     util::{
@@ -125,6 +125,11 @@ fn fs_modify_file_impl<R: ProcessRunner>(
                 if !resolved.absolute.is_file() {
                     return error(format!("Path is not a regular file: {target}"));
                 }
+                if let Err(msg) =
+                    record_pre_image(ctx.checkpoint.as_ref(), &ctx.root, &resolved.relative)
+                {
+                    return error(msg);
+                }
                 let content = fs::read_to_string(&resolved.absolute)?;
                 files.insert(resolved.relative.clone(), (content.clone(), content));
             }
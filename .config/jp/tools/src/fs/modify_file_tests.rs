@@ -18,6 +18,7 @@ fn ctx() -> (Utf8TempDir, Context) {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
     (dir, ctx)
 }
@@ -1169,6 +1170,7 @@ mod fs_modify_file {
                 access: None,
                 workspace_id: "test".into(),
                 conversation_id: "test".into(),
+                checkpoint: None,
             },
             &Map::new(),
             &Map::new(),
@@ -1282,6 +1284,7 @@ mod fs_modify_file {
                 access: None,
                 workspace_id: "test".into(),
                 conversation_id: "test".into(),
+                checkpoint: None,
             },
             &Map::new(),
             &Map::new(),
@@ -1307,6 +1310,7 @@ mod fs_modify_file {
                 access: None,
                 workspace_id: "test".into(),
                 conversation_id: "test".into(),
+                checkpoint: None,
             },
             &answers("broad_replacement", false),
             &Map::new(),
@@ -1377,6 +1381,7 @@ mod fs_modify_file {
                 access: None,
                 workspace_id: "test".into(),
                 conversation_id: "test".into(),
+                checkpoint: None,
             },
             &approved(),
             &Map::new(),
@@ -1404,6 +1409,7 @@ mod fs_modify_file {
                 access: None,
                 workspace_id: "test".into(),
                 conversation_id: "test".into(),
+                checkpoint: None,
             },
             &rejected,
             &Map::new(),
@@ -1432,6 +1438,7 @@ mod fs_modify_file {
                 access: None,
                 workspace_id: "test".into(),
                 conversation_id: "test".into(),
+                checkpoint: None,
             },
             &accepted,
             &Map::new(),
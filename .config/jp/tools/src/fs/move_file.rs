@@ -1,12 +1,12 @@
 use std::fs;
 
 use camino::Utf8Path;
-use jp_tool::{AccessPolicy, Capability, Outcome, Question};
+use jp_tool::{AccessPolicy, Capability, Checkpoint, Outcome, Question};
 use serde_json::{Map, Value};
 
 use super::utils::{
     EntryKind, ResolvedPath, authorize, count_dirty_paths_impl, entry_kind, is_file_dirty_impl,
-    resolve_workspace_entry,
+    record_move, resolve_workspace_entry,
 };
 use crate::{
     Error,
@@ -31,16 +31,26 @@ enum SourceKind {
 pub(crate) async fn fs_move_file(
     root: &Utf8Path,
     access: Option<&AccessPolicy>,
+    checkpoint: Option<&Checkpoint>,
     answers: &Map<String, Value>,
     source: String,
     target: String,
 ) -> ToolResult {
-    fs_move_file_impl(root, access, answers, &source, &target, &DuctProcessRunner)
+    fs_move_file_impl(
+        root,
+        access,
+        checkpoint,
+        answers,
+        &source,
+        &target,
+        &DuctProcessRunner,
+    )
 }
 
 fn fs_move_file_impl<R: ProcessRunner>(
     root: &Utf8Path,
     access: Option<&AccessPolicy>,
+    checkpoint: Option<&Checkpoint>,
     answers: &Map<String, Value>,
     source: &str,
     target: &str,
@@ -142,6 +152,10 @@ fn fs_move_file_impl<R: ProcessRunner>(
         return Ok(outcome);
     }
 
+    if let Err(msg) = record_move(checkpoint, root, &src.relative, &dst.relative) {
+        return error(msg);
+    }
+
     if let Some(parent) = dst.absolute.parent() {
         fs::create_dir_all(parent)?;
     }
@@ -79,6 +79,7 @@ fn moves_file_to_new_path() {
     let result = fs_move_file_impl(
         root,
         None,
+        None,
         &no_answers(),
         "a.txt",
         "b.txt",
@@ -103,8 +104,16 @@ fn moves_directory_with_contents() {
     std::fs::write(root.join("old/a.txt"), "1").unwrap();
     std::fs::write(root.join("old/nested/b.txt"), "2").unwrap();
 
-    let result =
-        fs_move_file_impl(root, None, &no_answers(), "old", "new", &clean_git_runner()).unwrap();
+    let result = fs_move_file_impl(
+        root,
+        None,
+        None,
+        &no_answers(),
+        "old",
+        "new",
+        &clean_git_runner(),
+    )
+    .unwrap();
 
     let msg = unwrap_success(result);
     assert!(msg.contains("Moved directory"), "unexpected message: {msg}");
@@ -129,6 +138,7 @@ fn moves_directory_creates_target_parents() {
     let result = fs_move_file_impl(
         root,
         None,
+        None,
         &no_answers(),
         "src",
         "vendored/upstream/src",
@@ -150,6 +160,7 @@ fn refuses_to_move_out_of_a_path_a_deny_rule_closes() {
     let result = fs_move_file_impl(
         root,
         Some(&workspace_except(".git")),
+        None,
         &no_answers(),
         ".git/HEAD",
         "head.txt",
@@ -179,6 +190,7 @@ fn refuses_to_move_into_a_path_a_deny_rule_closes() {
     let result = fs_move_file_impl(
         root,
         Some(&workspace_except(".git")),
+        None,
         &no_answers(),
         "note.txt",
         ".git/note.txt",
@@ -201,6 +213,7 @@ fn missing_source_errors() {
     let result = fs_move_file_impl(
         dir.path(),
         None,
+        None,
         &no_answers(),
         "ghost.txt",
         "elsewhere.txt",
@@ -218,6 +231,7 @@ fn workspace_escape_rejected() {
     let result = fs_move_file_impl(
         dir.path(),
         None,
+        None,
         &no_answers(),
         "../escape.txt",
         "inside.txt",
@@ -239,6 +253,7 @@ fn file_target_is_directory_errors() {
     let result = fs_move_file_impl(
         root,
         None,
+        None,
         &no_answers(),
         "a.txt",
         "target_dir",
@@ -260,6 +275,7 @@ fn file_target_exists_prompts_for_overwrite() {
     let result = fs_move_file_impl(
         root,
         None,
+        None,
         &no_answers(),
         "a.txt",
         "b.txt",
@@ -280,8 +296,16 @@ fn file_overwrite_approved_succeeds() {
     std::fs::write(root.join("b.txt"), "y").unwrap();
 
     let answers = answers(&[("overwrite_file", json!(true))]);
-    let result =
-        fs_move_file_impl(root, None, &answers, "a.txt", "b.txt", &clean_git_runner()).unwrap();
+    let result = fs_move_file_impl(
+        root,
+        None,
+        None,
+        &answers,
+        "a.txt",
+        "b.txt",
+        &clean_git_runner(),
+    )
+    .unwrap();
 
     unwrap_success(result);
     assert_eq!(std::fs::read_to_string(root.join("b.txt")).unwrap(), "x");
@@ -295,8 +319,16 @@ fn file_overwrite_denied_errors() {
     std::fs::write(root.join("b.txt"), "y").unwrap();
 
     let answers = answers(&[("overwrite_file", json!(false))]);
-    let result =
-        fs_move_file_impl(root, None, &answers, "a.txt", "b.txt", &never_git_runner()).unwrap();
+    let result = fs_move_file_impl(
+        root,
+        None,
+        None,
+        &answers,
+        "a.txt",
+        "b.txt",
+        &never_git_runner(),
+    )
+    .unwrap();
 
     let msg = unwrap_error(result);
     assert!(msg.contains("already exists"), "unexpected: {msg}");
@@ -310,8 +342,16 @@ fn dir_target_exists_errors_without_prompt() {
     std::fs::write(root.join("src/a.rs"), "").unwrap();
     std::fs::create_dir(root.join("dst")).unwrap();
 
-    let result =
-        fs_move_file_impl(root, None, &no_answers(), "src", "dst", &never_git_runner()).unwrap();
+    let result = fs_move_file_impl(
+        root,
+        None,
+        None,
+        &no_answers(),
+        "src",
+        "dst",
+        &never_git_runner(),
+    )
+    .unwrap();
 
     let msg = unwrap_error(result);
     assert!(msg.contains("already exists"), "unexpected: {msg}");
@@ -326,8 +366,16 @@ fn dir_target_exists_as_file_errors() {
     std::fs::create_dir(root.join("src")).unwrap();
     std::fs::write(root.join("dst"), "").unwrap();
 
-    let result =
-        fs_move_file_impl(root, None, &no_answers(), "src", "dst", &never_git_runner()).unwrap();
+    let result = fs_move_file_impl(
+        root,
+        None,
+        None,
+        &no_answers(),
+        "src",
+        "dst",
+        &never_git_runner(),
+    )
+    .unwrap();
 
     unwrap_error(result);
 }
@@ -339,7 +387,8 @@ fn dirty_file_prompts_for_confirmation() {
     std::fs::write(root.join("a.txt"), "x").unwrap();
 
     let runner = dirty_git_runner(" M a.txt\n");
-    let result = fs_move_file_impl(root, None, &no_answers(), "a.txt", "b.txt", &runner).unwrap();
+    let result =
+        fs_move_file_impl(root, None, None, &no_answers(), "a.txt", "b.txt", &runner).unwrap();
 
     let question = unwrap_needs_input(result);
     assert_eq!(question.id, "move_dirty_source");
@@ -355,7 +404,7 @@ fn dirty_file_approved_proceeds() {
 
     let runner = dirty_git_runner(" M a.txt\n");
     let answers = answers(&[("move_dirty_source", json!(true))]);
-    let result = fs_move_file_impl(root, None, &answers, "a.txt", "b.txt", &runner).unwrap();
+    let result = fs_move_file_impl(root, None, None, &answers, "a.txt", "b.txt", &runner).unwrap();
 
     unwrap_success(result);
     assert!(!root.join("a.txt").exists());
@@ -372,7 +421,8 @@ fn dirty_directory_prompts_with_entry_count() {
     std::fs::write(root.join("d/c"), "").unwrap();
 
     let runner = dirty_git_runner(" M d/a\n M d/b\n?? d/c\n");
-    let result = fs_move_file_impl(root, None, &no_answers(), "d", "renamed", &runner).unwrap();
+    let result =
+        fs_move_file_impl(root, None, None, &no_answers(), "d", "renamed", &runner).unwrap();
 
     let question = unwrap_needs_input(result);
     assert_eq!(question.id, "move_dirty_source");
@@ -390,6 +440,7 @@ fn clean_directory_skips_dirty_prompt() {
     let result = fs_move_file_impl(
         root,
         None,
+        None,
         &no_answers(),
         "d",
         "renamed",
@@ -410,6 +461,7 @@ fn source_equals_target_errors() {
     let result = fs_move_file_impl(
         root,
         None,
+        None,
         &no_answers(),
         "a.txt",
         "a.txt",
@@ -431,6 +483,7 @@ fn empty_parent_directory_is_removed() {
     let result = fs_move_file_impl(
         root,
         None,
+        None,
         &no_answers(),
         "nested/dir/file.txt",
         "file.txt",
@@ -463,6 +516,7 @@ fn symlink_source_renames_the_link_entry() {
     let result = fs_move_file_impl(
         root,
         None,
+        None,
         &no_answers(),
         "link.txt",
         "moved.txt",
@@ -497,6 +551,7 @@ fn moving_last_root_level_file_preserves_workspace() {
     let result = fs_move_file_impl(
         root,
         None,
+        None,
         &no_answers(),
         "only.txt",
         "moved.txt",
@@ -535,6 +590,7 @@ fn file_target_is_dangling_symlink_prompts_for_overwrite() {
     let result = fs_move_file_impl(
         root,
         None,
+        None,
         &no_answers(),
         "src.txt",
         "dst.txt",
@@ -569,6 +625,7 @@ fn file_target_is_dangling_symlink_overwrite_denied_errors() {
     let result = fs_move_file_impl(
         root,
         None,
+        None,
         &answers,
         "src.txt",
         "dst.txt",
@@ -603,8 +660,16 @@ fn dir_target_is_dangling_symlink_errors() {
     )
     .unwrap();
 
-    let result =
-        fs_move_file_impl(root, None, &no_answers(), "src", "dst", &never_git_runner()).unwrap();
+    let result = fs_move_file_impl(
+        root,
+        None,
+        None,
+        &no_answers(),
+        "src",
+        "dst",
+        &never_git_runner(),
+    )
+    .unwrap();
 
     let msg = unwrap_error(result);
     assert!(msg.contains("already exists"), "unexpected: {msg}");
@@ -628,6 +693,7 @@ fn dir_into_own_subtree_errors_without_mutation() {
     let result = fs_move_file_impl(
         root,
         None,
+        None,
         &no_answers(),
         "src",
         "src/nested/src",
@@ -61,6 +61,7 @@ async fn test_fs_read_file() {
             access: None,
             workspace_id: "test".into(),
             conversation_id: "test".into(),
+            checkpoint: None,
         };
         let result = fs_read_file(
             &ctx,
@@ -75,7 +76,7 @@ async fn test_fs_read_file() {
         let out = match result {
             Outcome::Success { content } => content,
             Outcome::Error { message, .. } => message,
-            Outcome::NeedsInput { .. } => String::new(),
+            Outcome::NeedsInput { .. } | Outcome::Handle { .. } => String::new(),
         };
 
         assert_eq!(out, expected, "failed test case '{name}'");
@@ -111,6 +112,7 @@ async fn reads_through_approved_external_mount() {
         }),
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let result = fs_read_file(
@@ -159,6 +161,7 @@ async fn read_through_internal_symlink_respects_deny_rule() {
         }),
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     // Direct read of the denied path is rejected.
@@ -208,6 +211,7 @@ async fn refuses_a_suppressed_path() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let result = fs_read_file(
@@ -251,6 +255,7 @@ async fn a_pattern_naming_a_symlink_closes_that_route() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
     let suppress = suppress_matcher(workspace.path(), &["alias/".to_owned()]).unwrap();
 
@@ -293,6 +298,7 @@ async fn denies_in_workspace_path_with_no_matching_grant() {
         }),
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let result = fs_read_file(
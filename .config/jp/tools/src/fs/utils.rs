@@ -3,7 +3,7 @@ use std::{io, path::PathBuf};
 use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
 use clean_path::Clean as _;
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
-use jp_tool::{AccessPolicy, Capability};
+use jp_tool::{AccessPolicy, Capability, Checkpoint};
 
 use crate::{
     Error,
@@ -122,6 +122,44 @@ pub fn authorize(
     ))
 }
 
+/// Record the state of `relative` in the turn's checkpoint, before the tool
+/// changes it.
+///
+/// Call it once [`authorize`] granted the change, so `jp undo` can restore the
+/// file.
+/// Without a checkpoint, nothing is recorded.
+pub fn record_pre_image(
+    checkpoint: Option<&Checkpoint>,
+    root: &Utf8Path,
+    relative: &Utf8Path,
+) -> Result<(), String> {
+    let Some(checkpoint) = checkpoint else {
+        return Ok(());
+    };
+
+    checkpoint
+        .record(root, relative)
+        .map_err(|error| format!("Failed to record '{relative}' for undo: {error}"))
+}
+
+/// Record the files that moving `source` to `target` changes.
+///
+/// See [`record_pre_image`].
+pub fn record_move(
+    checkpoint: Option<&Checkpoint>,
+    root: &Utf8Path,
+    source: &Utf8Path,
+    target: &Utf8Path,
+) -> Result<(), String> {
+    let Some(checkpoint) = checkpoint else {
+        return Ok(());
+    };
+
+    checkpoint
+        .record_move(root, source, target)
+        .map_err(|error| format!("Failed to record '{source}' for undo: {error}"))
+}
+
 /// Workspace-relative form of a resolved path.
 ///
 /// In-workspace paths strip the canonical root; approved external paths cannot
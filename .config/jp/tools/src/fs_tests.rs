@@ -12,6 +12,7 @@ fn list_files_with_suppress(root: &camino::Utf8Path, suppress: Value) -> (Contex
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
     let tool = Tool {
         name: "fs_list_files".to_owned(),
@@ -50,7 +50,7 @@ pub async fn run(ctx: Context, t: Tool) -> ToolResult {
             let path: String = t.req("path")?;
             let patch_id: String = t.req("patch_id")?;
             let lines: Vec<Value> = t.req("lines")?;
-            git_stage_patch_lines(&ctx.root, &path, &patch_id, lines, opts)
+            git_stage_patch_lines(&ctx, &path, &patch_id, lines, opts)
         }
 
         "list_patches" => git_list_patches(&ctx.root, t.opt("files")?, opts),
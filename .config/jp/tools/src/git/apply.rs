@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use camino::Utf8Path;
+use jp_tool::Context;
 
 use crate::util::runner::{ProcessOutput, ProcessRunner};
 
@@ -13,12 +14,19 @@ const BASE_DELAY_MS: u64 = 50;
 /// When multiple tool calls run in parallel, they race for this lock.
 /// This function retries with exponential backoff when it detects lock
 /// contention.
+///
+/// The files the patch touches are checked against the access policy before
+/// the patch is applied. Only the index changes, which the turn's checkpoint
+/// does not cover, so `jp undo` does not unstage the patch.
 pub fn apply_patch_to_index<R: ProcessRunner>(
     patch: &str,
-    root: &Utf8Path,
+    ctx: &Context,
     runner: &R,
     env: &[(&str, &str)],
 ) -> Result<(), String> {
+    check_touched_paths(patch, ctx, runner, env)?;
+
+    let root = &ctx.root;
     let mut last_err = String::new();
 
     for attempt in 0..=MAX_RETRIES {
@@ -53,6 +61,121 @@ pub fn apply_patch_to_index<R: ProcessRunner>(
     ))
 }
 
+/// Check access to every file `patch` touches.
+///
+/// This goes through the same [`Context`] checks the fs tools use, so a
+/// patch can't reach files the policy denies.
+/// The files are not recorded in the checkpoint: their working tree state,
+/// which is what a checkpoint restores, is left as it is.
+/// Without a policy there is nothing to check, and git isn't asked for the
+/// paths.
+fn check_touched_paths<R: ProcessRunner>(
+    patch: &str,
+    ctx: &Context,
+    runner: &R,
+    env: &[(&str, &str)],
+) -> Result<(), String> {
+    if ctx.access.is_none() {
+        return Ok(());
+    }
+
+    let ctx = Context {
+        checkpoint: None,
+        ..ctx.clone()
+    };
+
+    let ProcessOutput {
+        stdout,
+        stderr,
+        status,
+    } = runner
+        .run_with_env_and_stdin(
+            "git",
+            &[
+                "apply",
+                "--cached",
+                "--unidiff-zero",
+                "--numstat",
+                "--summary",
+                "-z",
+                "-",
+            ],
+            &ctx.root,
+            env,
+            Some(patch),
+        )
+        .map_err(|e| format!("Failed to run git apply: {e}"))?;
+
+    if !status.is_success() {
+        return Err(format!("Failed to read patch: {stderr}"));
+    }
+
+    for (path, change) in touched_paths(&stdout) {
+        let path = Utf8Path::new(path);
+        match change {
+            Change::Create => ctx.check_create(path),
+            Change::Update => ctx.check_update(path),
+            Change::Delete => ctx.check_delete(path),
+        }
+        .map_err(|e| format!("Cannot apply patch to '{path}': {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// How a patch changes a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Create,
+    Update,
+    Delete,
+}
+
+/// The paths in the output of `git apply --numstat --summary -z`, and how the
+/// patch changes them.
+///
+/// With `-z`, each numstat entry is `added\tdeleted\tpath\0`, or
+/// `added\tdeleted\t\0from\0to\0` for a rename.
+/// The summary follows as regular lines, such as ` create mode 100644 path`.
+fn touched_paths(output: &str) -> Vec<(&str, Change)> {
+    let (numstat, summary) = output.rsplit_once('\0').unwrap_or(("", output));
+    let created = summary_paths(summary, " create mode ");
+    let deleted = summary_paths(summary, " delete mode ");
+
+    let mut paths = vec![];
+    let mut fields = numstat.split('\0');
+    while let Some(entry) = fields.next() {
+        let Some(path) = entry.splitn(3, '\t').nth(2) else {
+            continue;
+        };
+
+        if path.is_empty() {
+            if let (Some(from), Some(to)) = (fields.next(), fields.next()) {
+                paths.push((from, Change::Delete));
+                paths.push((to, Change::Create));
+            }
+        } else if created.contains(&path) {
+            paths.push((path, Change::Create));
+        } else if deleted.contains(&path) {
+            paths.push((path, Change::Delete));
+        } else {
+            paths.push((path, Change::Update));
+        }
+    }
+
+    paths
+}
+
+/// The paths of the summary lines starting with `prefix`, which is followed by
+/// the file mode.
+fn summary_paths<'a>(summary: &'a str, prefix: &str) -> Vec<&'a str> {
+    summary
+        .lines()
+        .filter_map(|line| line.strip_prefix(prefix))
+        .filter_map(|rest| rest.split_once(' ').map(|(_, path)| path))
+        .collect()
+}
+
 /// Build a full git patch string from a path and hunk content.
 pub fn build_patch(path: &str, hunks: &str) -> String {
     format!("diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n{hunks}")
@@ -1,6 +1,20 @@
+use camino_tempfile::tempdir;
+use jp_tool::{AccessPolicy, Action, Checkpoint};
+
 use super::*;
 use crate::util::runner::MockProcessRunner;
 
+fn ctx(root: &Utf8Path, checkpoint: Option<Checkpoint>) -> Context {
+    Context {
+        root: root.to_owned(),
+        action: Action::Run,
+        access: None,
+        workspace_id: "test".into(),
+        conversation_id: "test".into(),
+        checkpoint,
+    }
+}
+
 #[test]
 fn succeeds_first_try() {
     let runner = MockProcessRunner::builder()
@@ -8,7 +22,7 @@ fn succeeds_first_try() {
         .args(&["apply", "--cached", "--unidiff-zero", "-"])
         .returns_success("");
 
-    let result = apply_patch_to_index("some patch", "/tmp".into(), &runner, &[]);
+    let result = apply_patch_to_index("some patch", &ctx("/tmp".into(), None), &runner, &[]);
     assert!(result.is_ok());
 }
 
@@ -19,7 +33,7 @@ fn non_lock_error_fails_immediately() {
         .args(&["apply", "--cached", "--unidiff-zero", "-"])
         .returns_error("error: patch does not apply");
 
-    let result = apply_patch_to_index("bad patch", "/tmp".into(), &runner, &[]);
+    let result = apply_patch_to_index("bad patch", &ctx("/tmp".into(), None), &runner, &[]);
     assert!(result.unwrap_err().contains("patch does not apply"));
 }
 
@@ -36,6 +50,55 @@ fn retries_on_lock_contention() {
         .args(&["apply", "--cached", "--unidiff-zero", "-"])
         .returns_success("");
 
-    let result = apply_patch_to_index("some patch", "/tmp".into(), &runner, &[]);
+    let result = apply_patch_to_index("some patch", &ctx("/tmp".into(), None), &runner, &[]);
     assert!(result.is_ok());
 }
+
+#[test]
+fn touched_paths_reads_numstat_and_summary() {
+    let output = "1\t1\ta.txt\x001\t0\tb.txt\x000\t1\tc.txt\x000\t0\t\x00d.txt\x00e.txt\x00 \
+                  create mode 100644 b.txt\n delete mode 100644 c.txt\n rename d.txt => e.txt \
+                  (100%)\n";
+
+    assert_eq!(touched_paths(output), vec![
+        ("a.txt", Change::Update),
+        ("b.txt", Change::Create),
+        ("c.txt", Change::Delete),
+        ("d.txt", Change::Delete),
+        ("e.txt", Change::Create),
+    ]);
+}
+
+#[test]
+fn checks_but_does_not_record_the_files_a_patch_touches() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("workspace");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("a.txt"), "two\n").unwrap();
+
+    let runner = MockProcessRunner::builder()
+        .expect("git")
+        .args(&[
+            "apply",
+            "--cached",
+            "--unidiff-zero",
+            "--numstat",
+            "--summary",
+            "-z",
+            "-",
+        ])
+        .returns_success("1\t1\ta.txt\x001\t0\tb.txt\x00 create mode 100644 b.txt\n")
+        .expect("git")
+        .args(&["apply", "--cached", "--unidiff-zero", "-"])
+        .returns_success("");
+
+    let checkpoint = Checkpoint::new(dir.path().join("checkpoint"));
+    let ctx = Context {
+        access: Some(AccessPolicy::default()),
+        ..ctx(&root, Some(checkpoint.clone()))
+    };
+    apply_patch_to_index("some patch", &ctx, &runner, &[]).unwrap();
+
+    // Only the index changed, which the checkpoint can't restore.
+    assert!(checkpoint.entries().unwrap().is_empty());
+}
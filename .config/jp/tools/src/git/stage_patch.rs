@@ -83,7 +83,7 @@ fn git_stage_patch_impl<R: ProcessRunner>(
     let mut staged: Vec<&str> = vec![];
 
     for (path, patch) in &built {
-        match apply_patch_to_index(patch, &ctx.root, runner, env) {
+        match apply_patch_to_index(patch, ctx, runner, env) {
             Ok(()) => staged.push(path),
             Err(error) => errors.push(format!("{path}: {error}")),
         }
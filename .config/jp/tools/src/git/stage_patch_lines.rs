@@ -1,4 +1,5 @@
 use camino::Utf8Path;
+use jp_tool::Context;
 use serde_json::{Map, Value};
 
 use super::{
@@ -11,7 +12,7 @@ use crate::util::{
 };
 
 pub(crate) fn git_stage_patch_lines(
-    root: &Utf8Path,
+    ctx: &Context,
     path: &str,
     patch_id: &str,
     lines: Vec<Value>,
@@ -19,11 +20,11 @@ pub(crate) fn git_stage_patch_lines(
 ) -> ToolResult {
     let lines = parse_line_selectors(lines)?;
     let env = super::env_from_options(options);
-    git_stage_patch_lines_impl(root, path, patch_id, &lines, &DuctProcessRunner, &env)
+    git_stage_patch_lines_impl(ctx, path, patch_id, &lines, &DuctProcessRunner, &env)
 }
 
 fn git_stage_patch_lines_impl<R: ProcessRunner>(
-    root: &Utf8Path,
+    ctx: &Context,
     path: &str,
     patch_id: &str,
     lines: &[usize],
@@ -34,11 +35,11 @@ fn git_stage_patch_lines_impl<R: ProcessRunner>(
         return Err("No lines selected for staging.".into());
     }
 
-    let hunk = fetch_hunk(root, path, patch_id, runner, env)?;
+    let hunk = fetch_hunk(&ctx.root, path, patch_id, runner, env)?;
     let sub_hunk = build_sub_hunk(&hunk, lines)?;
     let patch = build_patch(path, &sub_hunk);
 
-    apply_patch_to_index(&patch, root, runner, env)?;
+    apply_patch_to_index(&patch, ctx, runner, env)?;
     Ok("Patch applied.".into())
 }
 
@@ -20,6 +20,7 @@ fn stage_single_file() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let mut answers = serde_json::Map::new();
@@ -66,6 +67,7 @@ fn stage_non_last_hunk_of_multi_hunk_diff() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let mut answers = serde_json::Map::new();
@@ -107,6 +109,7 @@ fn stale_id_is_rejected_with_helpful_message() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let mut answers = serde_json::Map::new();
@@ -143,6 +146,7 @@ fn partial_failure_stages_what_it_can() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let mut answers = serde_json::Map::new();
@@ -214,6 +218,7 @@ fn ids_resolve_in_file_order_regardless_of_request_order() {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
 
     let mut answers = serde_json::Map::new();
@@ -1,17 +1,23 @@
 #![allow(clippy::print_stdout, clippy::print_stderr)]
 
 mod cargo;
+mod code;
 mod debug_jp;
 mod fs;
 mod git;
 mod github;
+#[cfg(unix)]
+mod lsp;
 mod plan;
+mod test;
 mod ticket;
 mod unix;
 mod util;
 mod web;
 
 use jp_tool::Context;
+#[cfg(unix)]
+pub use lsp::serve_bridge as serve_lsp_bridge;
 use serde::Serialize;
 use serde_json::{Map, Value};
 
@@ -21,12 +27,16 @@ type Result<T> = std::result::Result<T, Error>;
 pub async fn run(ctx: Context, t: Tool) -> util::ToolResult {
     match t.name.as_str() {
         s if s.starts_with("cargo_") => cargo::run(ctx, t).await,
+        s if s.starts_with("code_") => code::run(ctx, t).await,
         s if s.starts_with("github_") => github::run(ctx, t).await,
+        #[cfg(unix)]
+        s if s.starts_with("lsp_") => lsp::run(ctx, t).await,
         s if s.starts_with("fs_") => fs::run(ctx, t).await,
         s if s.starts_with("debug_jp_") => debug_jp::run(ctx, t).await,
         s if s.starts_with("web_") => web::run(ctx, t).await,
         s if s.starts_with("git_") => git::run(ctx, t).await,
         s if s.starts_with("unix_") => unix::run(ctx, t),
+        s if s.starts_with("test_") => test::run(ctx, t),
         s if s.starts_with("ticket_") => ticket::run(ctx, t),
         "plan" => plan::run(ctx, t),
         _ => util::unknown_tool(t),
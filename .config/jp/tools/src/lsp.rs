@@ -0,0 +1,405 @@
+//! Semantic code navigation through language servers.
+//!
+//! The tools start the language server configured for a file on first use, and
+//! keep it running in the background between calls (see [`bridge`]).
+
+use camino::{Utf8Path, Utf8PathBuf};
+use ignore::gitignore::Gitignore;
+use jp_tool::Capability;
+use serde_json::{Value, json};
+
+use crate::{
+    Context, Tool,
+    fs::utils::{
+        authorize, is_suppressed, resolve_workspace_path, suppress_matcher, suppressed_note,
+    },
+    util::{ToolResult, diff, error, unknown_tool},
+};
+
+mod bridge;
+mod jsonrpc;
+mod options;
+mod render;
+mod server;
+
+pub use bridge::serve as serve_bridge;
+use bridge::{BridgeConfig, BridgeRequest};
+use options::LspOptions;
+use render::{Location, char_column, symbol_column, utf16_column};
+
+/// The maximum number of locations listed in a result.
+const MAX_LOCATIONS: usize = 100;
+
+pub async fn run(ctx: Context, t: Tool) -> ToolResult {
+    let options = LspOptions::parse(&t.options)?;
+    let suppress = suppress_matcher(&ctx.root, &options.suppress)?;
+    let session = Session {
+        ctx: &ctx,
+        suppress: &suppress,
+        options: &options,
+    };
+
+    let target = match session.target(&t.req::<String>("path")?) {
+        Ok(target) => target,
+        Err(msg) => return error(msg),
+    };
+
+    let name = t.name.trim_start_matches("lsp_");
+    if name == "diagnostics" {
+        return lsp_diagnostics(&session, &target).await;
+    }
+
+    let position = match target.position(t.req("line")?, t.opt("column")?, t.opt("symbol")?) {
+        Ok(position) => position,
+        Err(msg) => return error(msg),
+    };
+
+    match name {
+        "definition" => lsp_definition(&session, &target, position).await,
+        "references" => {
+            let include_declaration = t.opt("include_declaration")?.unwrap_or(true);
+            lsp_references(&session, &target, position, include_declaration).await
+        }
+        "hover" => lsp_hover(&session, &target, position).await,
+        "rename_preview" => {
+            lsp_rename_preview(&session, &target, position, &t.req::<String>("new_name")?).await
+        }
+        _ => unknown_tool(t),
+    }
+}
+
+async fn lsp_definition(session: &Session<'_>, target: &Target, position: Value) -> ToolResult {
+    let result = match session
+        .call(
+            target,
+            "textDocument/definition",
+            json!({ "position": position }),
+        )
+        .await
+    {
+        Ok(result) => result,
+        Err(msg) => return error(msg),
+    };
+
+    let locations = render::locations(&result);
+    if locations.is_empty() {
+        return error("No definition found.");
+    }
+
+    Ok(session.list_locations(&locations).into())
+}
+
+async fn lsp_references(
+    session: &Session<'_>,
+    target: &Target,
+    position: Value,
+    include_declaration: bool,
+) -> ToolResult {
+    let params = json!({
+        "position": position,
+        "context": { "includeDeclaration": include_declaration },
+    });
+
+    let result = match session
+        .call(target, "textDocument/references", params)
+        .await
+    {
+        Ok(result) => result,
+        Err(msg) => return error(msg),
+    };
+
+    let locations = render::locations(&result);
+    if locations.is_empty() {
+        return error("No references found.");
+    }
+
+    Ok(session.list_locations(&locations).into())
+}
+
+async fn lsp_hover(session: &Session<'_>, target: &Target, position: Value) -> ToolResult {
+    let result = match session
+        .call(
+            target,
+            "textDocument/hover",
+            json!({ "position": position }),
+        )
+        .await
+    {
+        Ok(result) => result,
+        Err(msg) => return error(msg),
+    };
+
+    match render::hover_text(&result) {
+        Some(text) => Ok(text.into()),
+        None => error("No hover information available at this position."),
+    }
+}
+
+async fn lsp_diagnostics(session: &Session<'_>, target: &Target) -> ToolResult {
+    let config = match session.bridge(target) {
+        Ok(config) => config,
+        Err(msg) => return error(msg),
+    };
+
+    let request = BridgeRequest::Diagnostics {
+        path: target.absolute.clone(),
+    };
+
+    let diagnostics = match bridge::call(&config, &request).await {
+        Ok(Value::Array(diagnostics)) => diagnostics,
+        Ok(_) => vec![],
+        Err(msg) => return error(msg),
+    };
+
+    if diagnostics.is_empty() {
+        return Ok(format!("No diagnostics for '{}'.", target.relative).into());
+    }
+
+    let out = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let line = render::diagnostic(diagnostic, |line| target.line(line as usize));
+            format!("{}:{line}", target.relative)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(out.into())
+}
+
+async fn lsp_rename_preview(
+    session: &Session<'_>,
+    target: &Target,
+    position: Value,
+    new_name: &str,
+) -> ToolResult {
+    let params = json!({ "position": position, "newName": new_name });
+    let result = match session.call(target, "textDocument/rename", params).await {
+        Ok(result) => result,
+        Err(msg) => return error(msg),
+    };
+
+    let edit = render::workspace_edit(&result);
+    if edit.edits.is_empty() && edit.operations.is_empty() {
+        return error("The language server proposed no changes for this rename.");
+    }
+
+    let mut out = String::new();
+    let mut hidden = 0;
+    for (path, edits) in &edit.edits {
+        let Some(relative) = session.readable(path) else {
+            hidden += 1;
+            continue;
+        };
+
+        let Ok(before) = std::fs::read_to_string(path) else {
+            out.push_str(&format!("{relative}: {} edits\n\n", edits.len()));
+            continue;
+        };
+
+        let after = render::apply_edits(&before, edits);
+        let text_diff = diff::text_diff(&before, &after);
+        let unified = diff::unified_diff(&text_diff, relative.as_str());
+        out.push_str(&format!("```diff\n{unified}```\n\n"));
+    }
+
+    for operation in &edit.operations {
+        out.push_str(&format!("Would {operation}.\n"));
+    }
+
+    if hidden > 0 {
+        out.push_str(&format!(
+            "The rename also changes {hidden} file(s) that are not readable by this tool.\n"
+        ));
+    }
+
+    out.push_str("\nNothing was changed; this is a preview.");
+    Ok(out.into())
+}
+
+/// The state shared by a single tool call.
+struct Session<'a> {
+    ctx: &'a Context,
+    suppress: &'a Gitignore,
+    options: &'a LspOptions,
+}
+
+impl Session<'_> {
+    /// Resolve and authorize the file a tool call is about.
+    fn target(&self, path: &str) -> Result<Target, String> {
+        let resolved = resolve_workspace_path(&self.ctx.root, path, self.ctx.access.as_ref())?;
+        authorize(
+            self.ctx.access.as_ref(),
+            Capability::Read,
+            &resolved.relative,
+        )?;
+
+        if is_suppressed(self.suppress, &[&resolved.relative, &resolved.lexical]) {
+            return Err(suppressed_note(resolved.relative.as_str()));
+        }
+
+        if !resolved.absolute.is_file() {
+            return Err(format!("'{path}' is not a file."));
+        }
+
+        let content = std::fs::read_to_string(&resolved.absolute)
+            .map_err(|error| format!("Unable to read '{path}': {error}"))?;
+
+        Ok(Target {
+            absolute: resolved.absolute,
+            relative: resolved.relative,
+            content,
+        })
+    }
+
+    /// The bridge configuration for the server that handles `target`.
+    fn bridge(&self, target: &Target) -> Result<BridgeConfig, String> {
+        let (name, server) = self.options.server_for(&target.relative).ok_or_else(|| {
+            format!(
+                "No language server is configured for '{}'. Configure one with the `servers` tool \
+                 option.",
+                target.relative
+            )
+        })?;
+
+        Ok(BridgeConfig::new(
+            &self.root(),
+            name,
+            server,
+            self.options.idle_timeout_secs,
+            self.options.request_timeout_secs,
+        ))
+    }
+
+    async fn call(&self, target: &Target, method: &str, params: Value) -> Result<Value, String> {
+        let config = self.bridge(target)?;
+        let request = BridgeRequest::Call {
+            method: method.to_owned(),
+            path: target.absolute.clone(),
+            params,
+        };
+
+        bridge::call(&config, &request).await
+    }
+
+    /// The canonical workspace root, which the paths the language server
+    /// returns are relative to.
+    fn root(&self) -> Utf8PathBuf {
+        self.ctx
+            .root
+            .canonicalize_utf8()
+            .unwrap_or_else(|_| self.ctx.root.clone())
+    }
+
+    /// The workspace-relative path of `path`, if the tool may show its
+    /// contents.
+    fn readable(&self, path: &Utf8Path) -> Option<Utf8PathBuf> {
+        let relative = path.strip_prefix(self.root()).ok()?;
+        authorize(self.ctx.access.as_ref(), Capability::Read, relative).ok()?;
+        (!is_suppressed(self.suppress, &[relative])).then(|| relative.to_owned())
+    }
+
+    /// List `locations`, one per line, with the source line they point at.
+    ///
+    /// Locations outside the workspace, such as the standard library, are
+    /// listed without their source.
+    /// Locations in the workspace that the tool may not read are left out.
+    fn list_locations(&self, locations: &[Location]) -> String {
+        let root = self.root();
+        let mut lines = vec![];
+        let mut hidden = 0;
+
+        for location in locations {
+            let line = location.line as usize + 1;
+            if !location.path.starts_with(&root) {
+                let column = location.character + 1;
+                lines.push(format!("{}:{line}:{column}", location.path));
+                continue;
+            }
+
+            let Some(relative) = self.readable(&location.path) else {
+                hidden += 1;
+                continue;
+            };
+
+            let text = std::fs::read_to_string(&location.path)
+                .ok()
+                .and_then(|content| {
+                    content
+                        .lines()
+                        .nth(location.line as usize)
+                        .map(str::to_owned)
+                })
+                .unwrap_or_default();
+
+            let column = char_column(&text, location.character) + 1;
+            lines.push(format!("{relative}:{line}:{column}: {}", text.trim()));
+        }
+
+        let total = lines.len();
+        let mut out = lines
+            .into_iter()
+            .take(MAX_LOCATIONS)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if total > MAX_LOCATIONS {
+            out.push_str(&format!("\n... and {} more.", total - MAX_LOCATIONS));
+        }
+
+        if hidden > 0 {
+            out.push_str(&format!(
+                "\n{hidden} location(s) are not readable by this tool and were left out."
+            ));
+        }
+
+        out
+    }
+}
+
+/// The file a tool call is about.
+struct Target {
+    absolute: Utf8PathBuf,
+    relative: Utf8PathBuf,
+    content: String,
+}
+
+impl Target {
+    /// The text of the 0-based `line`.
+    fn line(&self, line: usize) -> Option<String> {
+        self.content.lines().nth(line).map(str::to_owned)
+    }
+
+    /// The LSP position of a 1-based `line`, and a 1-based `column` or the
+    /// first occurrence of `symbol` on that line.
+    fn position(
+        &self,
+        line: usize,
+        column: Option<usize>,
+        symbol: Option<String>,
+    ) -> Result<Value, String> {
+        let text = line
+            .checked_sub(1)
+            .and_then(|line| self.line(line))
+            .ok_or_else(|| {
+                format!(
+                    "`line` must be between 1 and {}.",
+                    self.content.lines().count()
+                )
+            })?;
+
+        let index = match (column, symbol.as_deref()) {
+            (Some(0), _) => return Err("`column` must be greater than 0.".to_owned()),
+            (Some(column), _) => column - 1,
+            (None, Some(symbol)) => symbol_column(&text, symbol)
+                .ok_or_else(|| format!("`{symbol}` does not occur on line {line}."))?,
+            (None, None) => return Err("Either `column` or `symbol` is required.".to_owned()),
+        };
+
+        Ok(json!({ "line": line - 1, "character": utf16_column(&text, index) }))
+    }
+}
+
+#[cfg(test)]
+#[path = "lsp_tests.rs"]
+mod tests;
@@ -0,0 +1,401 @@
+//! The bridge process that keeps a language server warm between tool calls.
+//!
+//! Every tool call runs in a fresh `jp-tools` process, but language servers
+//! take seconds to minutes to index a workspace.
+//! The first `lsp_*` call for a workspace and server therefore starts a bridge
+//! (`jp-tools lsp-bridge <config>`) in the background, which starts the
+//! server and serves requests for it on a Unix socket.
+//! Later calls connect to the same socket, and find the server ready.
+//! The bridge shuts the server down after it has not been used for a while.
+//!
+//! Requests and responses are JSON objects, one per line, one request per
+//! connection.
+//!
+//! Only bridges bind or remove the socket, and only while holding a lock file
+//! next to it, so that two bridges starting at once never remove each other's
+//! socket.
+
+use std::{
+    env, fs, io, os::unix::process::CommandExt as _, process::Stdio, sync::Arc, time::Duration,
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use jp_workspace::user_runtime_dir;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{
+    io::{AsyncBufReadExt as _, AsyncWriteExt as _, BufReader},
+    net::{UnixListener, UnixStream},
+    time::{Instant, sleep, sleep_until},
+};
+
+use super::{options::ServerConfig, server::LanguageServer};
+
+/// How long to wait for a newly started bridge to accept connections.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a bridge whose server failed to start keeps reporting that.
+///
+/// Long enough for the call that started it to learn why.
+const FAILED_LINGER: Duration = Duration::from_secs(5);
+
+/// How often a bridge checks whether its server is still running.
+const EXIT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Extra time a client gives the bridge on top of the request timeout, for
+/// starting and initializing the server.
+const STARTUP_ALLOWANCE: Duration = Duration::from_secs(60);
+
+/// Everything a bridge needs to start and serve a language server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct BridgeConfig {
+    /// The workspace root.
+    pub root: Utf8PathBuf,
+
+    /// The name of the server, as configured.
+    pub name: String,
+
+    pub command: String,
+    pub args: Vec<String>,
+
+    /// Serialized, as JSON values are not `Eq`.
+    pub initialization_options: Option<String>,
+
+    pub idle_timeout_secs: u64,
+    pub request_timeout_secs: u64,
+}
+
+impl BridgeConfig {
+    pub(super) fn new(
+        root: &Utf8Path,
+        name: String,
+        server: ServerConfig,
+        idle_timeout_secs: u64,
+        request_timeout_secs: u64,
+    ) -> Self {
+        Self {
+            root: root.to_owned(),
+            name,
+            command: server.command,
+            args: server.args,
+            initialization_options: server.initialization_options.map(|v| v.to_string()),
+            idle_timeout_secs,
+            request_timeout_secs,
+        }
+    }
+
+    /// The socket the bridge for this configuration listens on.
+    ///
+    /// Lives in JP's runtime directory, next to the sockets of workspace
+    /// daemons.
+    pub(super) fn socket(&self) -> Result<Utf8PathBuf, String> {
+        let dir = user_runtime_dir().map_err(|error| error.to_string())?;
+        Ok(dir.join(self.socket_name()))
+    }
+
+    /// The file name of the socket.
+    ///
+    /// Derived from the whole configuration, so changing how a server is
+    /// started gets a bridge of its own rather than a stale one.
+    fn socket_name(&self) -> String {
+        // 64-bit FNV-1a, which unlike the standard library's hasher is the
+        // same across Rust releases, so a new `jp-tools` build finds the
+        // bridges started by the previous one.
+        let hash = serde_json::to_vec(self)
+            .unwrap_or_default()
+            .into_iter()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+            });
+
+        format!("lsp-{hash:016x}.sock")
+    }
+
+    fn server(&self) -> ServerConfig {
+        ServerConfig {
+            command: self.command.clone(),
+            args: self.args.clone(),
+            extensions: vec![],
+            initialization_options: self
+                .initialization_options
+                .as_deref()
+                .and_then(|v| serde_json::from_str(v).ok()),
+        }
+    }
+}
+
+/// A request to the bridge.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum BridgeRequest {
+    /// Sync the document at `path` and call an LSP method on it.
+    ///
+    /// The document's `textDocument` identifier is added to `params`.
+    Call {
+        method: String,
+        path: Utf8PathBuf,
+        params: Value,
+    },
+
+    /// Sync the document at `path` and return its diagnostics.
+    Diagnostics { path: Utf8PathBuf },
+}
+
+/// The bridge's answer to a [`BridgeRequest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum BridgeResponse {
+    Ok { result: Value },
+    Error { message: String },
+}
+
+/// Send `request` to the bridge for `config`, starting the bridge if it is not
+/// running.
+pub(super) async fn call(config: &BridgeConfig, request: &BridgeRequest) -> Result<Value, String> {
+    let socket = config.socket()?;
+    let stream = match UnixStream::connect(&socket).await {
+        Ok(stream) => stream,
+        Err(_) => {
+            spawn(config, &socket)?;
+            connect(&socket).await?
+        }
+    };
+
+    let timeout = Duration::from_secs(config.request_timeout_secs) + STARTUP_ALLOWANCE;
+    let response = tokio::time::timeout(timeout, exchange(stream, request))
+        .await
+        .map_err(|_| format!("`{}` did not respond in time.", config.name))?
+        .map_err(|error| format!("Lost connection to the `{}` bridge: {error}", config.name))?;
+
+    match response {
+        BridgeResponse::Ok { result } => Ok(result),
+        BridgeResponse::Error { message } => Err(message),
+    }
+}
+
+async fn exchange(stream: UnixStream, request: &BridgeRequest) -> io::Result<BridgeResponse> {
+    let (read, mut write) = stream.into_split();
+
+    let mut line = serde_json::to_vec(request)?;
+    line.push(b'\n');
+    write.write_all(&line).await?;
+    write.flush().await?;
+
+    let mut line = String::new();
+    if BufReader::new(read).read_line(&mut line).await? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    serde_json::from_str(&line).map_err(Into::into)
+}
+
+/// Start a bridge in the background.
+fn spawn(config: &BridgeConfig, socket: &Utf8Path) -> Result<(), String> {
+    let config = serde_json::to_string(config).map_err(|error| error.to_string())?;
+    let exe = env::current_exe().map_err(|error| error.to_string())?;
+
+    // Its own process group keeps signals meant for the foreground `jp`
+    // process (Ctrl-C) from taking the bridge down with it.
+    std::process::Command::new(exe)
+        .arg("lsp-bridge")
+        .arg(config)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()
+        .map(drop)
+        .map_err(|error| format!("Unable to start language server bridge: {error}"))
+}
+
+/// Connect to a bridge that is starting up.
+async fn connect(socket: &Utf8Path) -> Result<UnixStream, String> {
+    let step = Duration::from_millis(50);
+    let mut waited = Duration::ZERO;
+
+    loop {
+        match UnixStream::connect(socket).await {
+            Ok(stream) => return Ok(stream),
+            Err(error) if waited >= STARTUP_TIMEOUT => {
+                return Err(format!("Language server bridge did not start: {error}"));
+            }
+            Err(_) => {
+                sleep(step).await;
+                waited += step;
+            }
+        }
+    }
+}
+
+/// Run a bridge in the current process, until it has been idle for the
+/// configured time, or its server exits.
+pub async fn serve(config: &str) -> Result<(), String> {
+    let config: BridgeConfig =
+        serde_json::from_str(config).map_err(|error| format!("Invalid bridge config: {error}"))?;
+
+    let socket = config.socket()?;
+
+    // Another bridge may have won the race to start.
+    let Some(listener) = bind(&socket)? else {
+        return Ok(());
+    };
+
+    let server = LanguageServer::start(
+        &config.root,
+        &config.server(),
+        Duration::from_secs(config.request_timeout_secs),
+    )
+    .await
+    .map(Arc::new)
+    .map_err(|error| format!("Language server `{}` failed to start: {error}", config.name));
+
+    let idle = match &server {
+        Ok(_) => Duration::from_secs(config.idle_timeout_secs),
+        Err(_) => FAILED_LINGER,
+    };
+
+    let root = Arc::new(config.root.clone());
+    let mut deadline = Instant::now() + idle;
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                if let Ok((stream, _)) = accepted {
+                    deadline = Instant::now() + idle;
+                    drop(tokio::spawn(handle(server.clone(), root.clone(), stream)));
+                }
+            }
+            () = sleep_until(deadline) => break,
+            () = sleep(EXIT_POLL_INTERVAL) => {
+                if server.as_ref().is_ok_and(|server| server.exited()) {
+                    break;
+                }
+            }
+        }
+    }
+
+    // A bridge starting meanwhile either finds this one still listening, or
+    // no socket at all.
+    let lock = lock_socket(&socket);
+    drop(fs::remove_file(&socket));
+    drop(listener);
+    drop(lock);
+
+    if let Ok(server) = server {
+        server.shutdown().await;
+    }
+
+    Ok(())
+}
+
+/// Listen on `socket`, unless another bridge already does.
+fn bind(socket: &Utf8Path) -> Result<Option<UnixListener>, String> {
+    let _lock = lock_socket(socket)?;
+
+    match std::os::unix::net::UnixStream::connect(socket) {
+        Ok(_) => return Ok(None),
+
+        // A socket nobody listens on is left over from a bridge that did not
+        // shut down cleanly, and would keep this one from binding.
+        Err(error) if error.kind() == io::ErrorKind::ConnectionRefused => {
+            drop(fs::remove_file(socket));
+        }
+        Err(_) => {}
+    }
+
+    UnixListener::bind(socket)
+        .map(Some)
+        .map_err(|error| format!("{socket}: {error}"))
+}
+
+/// Lock the lock file of `socket`, until the returned file is dropped.
+fn lock_socket(socket: &Utf8Path) -> Result<fs::File, String> {
+    let path = socket.with_extension("lock");
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|error| format!("{path}: {error}"))?;
+
+    file.lock().map_err(|error| format!("{path}: {error}"))?;
+    Ok(file)
+}
+
+/// Serve a single connection.
+async fn handle(
+    server: Result<Arc<LanguageServer>, String>,
+    root: Arc<Utf8PathBuf>,
+    stream: UnixStream,
+) {
+    let (read, mut write) = stream.into_split();
+
+    let mut line = String::new();
+    if BufReader::new(read).read_line(&mut line).await.is_err() {
+        return;
+    }
+
+    let response = match (server, serde_json::from_str::<BridgeRequest>(&line)) {
+        (Err(message), _) => BridgeResponse::Error { message },
+        (_, Err(error)) => BridgeResponse::Error {
+            message: format!("Invalid bridge request: {error}"),
+        },
+        (Ok(server), Ok(request)) => match dispatch(&server, &root, request).await {
+            Ok(result) => BridgeResponse::Ok { result },
+            Err(message) => BridgeResponse::Error { message },
+        },
+    };
+
+    let Ok(mut line) = serde_json::to_vec(&response) else {
+        return;
+    };
+    line.push(b'\n');
+    drop(write.write_all(&line).await);
+}
+
+async fn dispatch(
+    server: &LanguageServer,
+    root: &Utf8Path,
+    request: BridgeRequest,
+) -> Result<Value, String> {
+    match request {
+        BridgeRequest::Call {
+            method,
+            path,
+            mut params,
+        } => {
+            let path = confine(root, &path)?;
+            let (uri, _) = server.sync(&path).await?;
+            if let Some(params) = params.as_object_mut() {
+                params.insert("textDocument".to_owned(), serde_json::json!({ "uri": uri }));
+            }
+
+            server.request(&method, params).await
+        }
+        BridgeRequest::Diagnostics { path } => {
+            let path = confine(root, &path)?;
+            server.diagnostics(&path).await.map(Value::Array)
+        }
+    }
+}
+
+/// The canonical form of `path`, if it lies inside the (canonical) `root`.
+///
+/// The socket is the bridge's only access control beyond file permissions, so
+/// it serves the documents of its own workspace and nothing else. The check is
+/// made on the canonical path, as `<root>/../x` or a symlink would pass a
+/// lexical one.
+fn confine(root: &Utf8Path, path: &Utf8Path) -> Result<Utf8PathBuf, String> {
+    let canonical = path
+        .canonicalize_utf8()
+        .map_err(|error| format!("Unable to read '{path}': {error}"))?;
+
+    if !canonical.starts_with(root) {
+        return Err(format!("'{path}' is outside the workspace."));
+    }
+
+    Ok(canonical)
+}
+
+#[cfg(test)]
+#[path = "bridge_tests.rs"]
+mod tests;
@@ -0,0 +1,136 @@
+use serde_json::json;
+
+use super::*;
+
+fn config() -> BridgeConfig {
+    BridgeConfig {
+        root: "/workspace".into(),
+        name: "rust-analyzer".to_owned(),
+        command: "rust-analyzer".to_owned(),
+        args: vec![],
+        initialization_options: None,
+        idle_timeout_secs: 600,
+        request_timeout_secs: 60,
+    }
+}
+
+#[test]
+fn test_socket_is_derived_from_the_config() {
+    let config = config();
+    assert_eq!(config.socket_name(), config.clone().socket_name());
+
+    let mut other_root = config.clone();
+    other_root.root = "/other".into();
+    assert_ne!(config.socket_name(), other_root.socket_name());
+
+    let mut other_command = config.clone();
+    other_command.args = vec!["--verbose".to_owned()];
+    assert_ne!(config.socket_name(), other_command.socket_name());
+
+    // The name must not change between builds, or `jp-tools` loses track of
+    // its running bridges.
+    assert_eq!(config.socket_name(), "lsp-0399e13b8698b655.sock");
+
+    // Unix socket paths are limited to around 100 bytes.
+    let socket = config.socket().unwrap();
+    assert!(socket.as_str().len() < 100, "{socket}");
+}
+
+#[test]
+fn test_initialization_options_roundtrip() {
+    let server = ServerConfig {
+        command: "pyright-langserver".to_owned(),
+        args: vec!["--stdio".to_owned()],
+        extensions: vec!["py".to_owned()],
+        initialization_options: Some(json!({ "python": { "analysis": {} } })),
+    };
+
+    let config = BridgeConfig::new(
+        Utf8Path::new("/ws"),
+        "pyright".to_owned(),
+        server.clone(),
+        1,
+        2,
+    );
+    let restored = config.server();
+
+    assert_eq!(restored.command, server.command);
+    assert_eq!(restored.args, server.args);
+    assert_eq!(
+        restored.initialization_options,
+        server.initialization_options
+    );
+}
+
+#[test]
+fn test_request_wire_format() {
+    let request = BridgeRequest::Diagnostics {
+        path: "/ws/src/lib.rs".into(),
+    };
+
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        json!({ "type": "diagnostics", "path": "/ws/src/lib.rs" })
+    );
+}
+
+#[tokio::test]
+async fn test_failed_server_is_reported_to_clients() {
+    let (client, bridge) = UnixStream::pair().unwrap();
+    let task = tokio::spawn(handle(
+        Err("`nope` not found".to_owned()),
+        Arc::new("/ws".into()),
+        bridge,
+    ));
+
+    let response = exchange(client, &BridgeRequest::Diagnostics {
+        path: "/ws/lib.rs".into(),
+    })
+    .await
+    .unwrap();
+    task.await.unwrap();
+
+    assert_eq!(response, BridgeResponse::Error {
+        message: "`nope` not found".to_owned()
+    });
+}
+
+#[test]
+fn test_confine_to_workspace() {
+    let tmp = camino_tempfile::tempdir().unwrap();
+    let tmp = tmp.path().canonicalize_utf8().unwrap();
+    let root = tmp.join("workspace");
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src/lib.rs"), "").unwrap();
+    fs::write(tmp.join("secret"), "").unwrap();
+
+    let path = root.join("src/../src/lib.rs");
+    assert_eq!(confine(&root, &path).unwrap(), root.join("src/lib.rs"));
+
+    // The lexical prefix is the workspace, the file is not.
+    let error = confine(&root, &root.join("../secret")).unwrap_err();
+    assert!(error.contains("outside the workspace"), "{error}");
+
+    std::os::unix::fs::symlink(tmp.join("secret"), root.join("link")).unwrap();
+    let error = confine(&root, &root.join("link")).unwrap_err();
+    assert!(error.contains("outside the workspace"), "{error}");
+}
+
+#[tokio::test]
+async fn test_bind_replaces_only_stale_sockets() {
+    let tmp = camino_tempfile::tempdir().unwrap();
+    let socket = tmp.path().join("bridge.sock");
+
+    let listener = bind(&socket).unwrap().expect("no bridge listens yet");
+
+    // A running bridge keeps its socket.
+    assert!(bind(&socket).unwrap().is_none());
+    assert!(UnixStream::connect(&socket).await.is_ok());
+
+    // A bridge that did not shut down cleanly leaves its socket behind.
+    drop(listener);
+    assert!(socket.exists());
+
+    let _listener = bind(&socket).unwrap().expect("stale socket is replaced");
+    assert!(UnixStream::connect(&socket).await.is_ok());
+}
@@ -0,0 +1,77 @@
+//! JSON-RPC message framing, as spoken by language servers over stdio.
+//!
+//! Every message is a JSON object preceded by a `Content-Length` header and an
+//! empty line.
+
+use std::io;
+
+use serde_json::Value;
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt as _, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _,
+};
+
+/// The JSON-RPC error code for a request the server abandoned because the
+/// document changed while it was working on it.
+pub(super) const CONTENT_MODIFIED: i64 = -32801;
+
+/// The JSON-RPC error code for a request the server cancelled, and that the
+/// client may retry.
+pub(super) const SERVER_CANCELLED: i64 = -32802;
+
+/// Write a single message.
+pub(super) async fn write_message<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    message: &Value,
+) -> io::Result<()> {
+    let body = serde_json::to_vec(message)?;
+    let header = format!("Content-Length: {}\r\n\r\n", body.len());
+
+    writer.write_all(header.as_bytes()).await?;
+    writer.write_all(&body).await?;
+    writer.flush().await
+}
+
+/// Read a single message.
+///
+/// Returns `None` when the peer closed the stream.
+pub(super) async fn read_message<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        // Other headers, such as `Content-Type`, carry nothing we need.
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            length = Some(value.trim().parse::<usize>().map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid length: {error}"),
+                )
+            })?);
+        }
+    }
+
+    let length = length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "message without Content-Length")
+    })?;
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+
+    serde_json::from_slice(&body).map(Some).map_err(Into::into)
+}
+
+#[cfg(test)]
+#[path = "jsonrpc_tests.rs"]
+mod tests;
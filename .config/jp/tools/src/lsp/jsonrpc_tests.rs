@@ -0,0 +1,43 @@
+use serde_json::json;
+use tokio::io::BufReader;
+
+use super::*;
+
+#[tokio::test]
+async fn test_roundtrip() {
+    let first = json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} });
+    let second = json!({ "jsonrpc": "2.0", "method": "initialized", "params": { "ö": "✓" } });
+
+    let mut buf = vec![];
+    write_message(&mut buf, &first).await.unwrap();
+    write_message(&mut buf, &second).await.unwrap();
+
+    let mut reader = BufReader::new(buf.as_slice());
+    assert_eq!(read_message(&mut reader).await.unwrap(), Some(first));
+    assert_eq!(read_message(&mut reader).await.unwrap(), Some(second));
+    assert_eq!(read_message(&mut reader).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_extra_headers_are_ignored() {
+    let body = r#"{"id":1}"#;
+    let raw = format!(
+        "content-length: {}\r\nContent-Type: application/vscode-jsonrpc; \
+         charset=utf-8\r\n\r\n{body}",
+        body.len()
+    );
+
+    let mut reader = BufReader::new(raw.as_bytes());
+    assert_eq!(
+        read_message(&mut reader).await.unwrap(),
+        Some(json!({ "id": 1 }))
+    );
+}
+
+#[tokio::test]
+async fn test_missing_length_is_an_error() {
+    let mut reader = BufReader::new("Content-Type: text\r\n\r\n{}".as_bytes());
+    let error = read_message(&mut reader).await.unwrap_err();
+
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,130 @@
+//! Parsing for the `lsp_*` tools' `options` map.
+//!
+//! See `.jp/mcp/tools/lsp/*.toml` for the user-facing schema.
+
+use std::collections::BTreeMap;
+
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::Error;
+
+/// How to start a language server, and which files it handles.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ServerConfig {
+    /// The program to run.
+    pub command: String,
+
+    /// Arguments passed to `command`.
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// The extensions of the files the server handles, without a leading dot.
+    pub extensions: Vec<String>,
+
+    /// Sent as the `initializationOptions` of the `initialize` request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initialization_options: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct LspOptions {
+    /// Language servers by name, in addition to (or replacing) the built-in
+    /// ones.
+    #[serde(default)]
+    servers: BTreeMap<String, ServerConfig>,
+
+    /// How long a language server is kept running without any tool calls.
+    #[serde(default = "default_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
+
+    /// How long to wait for the language server to answer a request.
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+
+    /// Paths the tools may read but never return.
+    #[serde(default)]
+    pub suppress: Vec<String>,
+}
+
+fn default_idle_timeout_secs() -> u64 {
+    600
+}
+
+fn default_request_timeout_secs() -> u64 {
+    60
+}
+
+impl LspOptions {
+    pub(super) fn parse(options: &Map<String, Value>) -> Result<Self, Error> {
+        serde_json::from_value(Value::Object(options.clone()))
+            .map_err(|e| format!("invalid lsp options: {e}").into())
+    }
+
+    /// The name and configuration of the server that handles `path`.
+    ///
+    /// Configured servers take precedence over the built-in ones, so a
+    /// configured server for `rs` files replaces `rust-analyzer`.
+    pub(super) fn server_for(&self, path: &Utf8Path) -> Option<(String, ServerConfig)> {
+        let ext = path.extension()?;
+        let handles = |config: &ServerConfig| config.extensions.iter().any(|e| e == ext);
+
+        self.servers
+            .iter()
+            .find(|(_, config)| handles(config))
+            .map(|(name, config)| (name.clone(), config.clone()))
+            .or_else(|| {
+                builtin_servers()
+                    .into_iter()
+                    .filter(|(name, _)| !self.servers.contains_key(name))
+                    .find(|(_, config)| handles(config))
+            })
+    }
+}
+
+/// The language servers known without any configuration.
+fn builtin_servers() -> Vec<(String, ServerConfig)> {
+    let server = |command: &str, args: &[&str], extensions: &[&str]| ServerConfig {
+        command: command.to_owned(),
+        args: args.iter().map(|&v| v.to_owned()).collect(),
+        extensions: extensions.iter().map(|&v| v.to_owned()).collect(),
+        initialization_options: None,
+    };
+
+    vec![
+        (
+            "rust-analyzer".to_owned(),
+            server("rust-analyzer", &[], &["rs"]),
+        ),
+        (
+            "typescript-language-server".to_owned(),
+            server("typescript-language-server", &["--stdio"], &[
+                "ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs",
+            ]),
+        ),
+        (
+            "pyright".to_owned(),
+            server("pyright-langserver", &["--stdio"], &["py", "pyi"]),
+        ),
+    ]
+}
+
+/// The LSP language identifier of a file.
+pub(super) fn language_id(path: &Utf8Path) -> &str {
+    match path.extension().unwrap_or_default() {
+        "rs" => "rust",
+        "ts" | "mts" | "cts" => "typescript",
+        "tsx" => "typescriptreact",
+        "js" | "mjs" | "cjs" => "javascript",
+        "jsx" => "javascriptreact",
+        "py" | "pyi" => "python",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+#[path = "options_tests.rs"]
+mod tests;
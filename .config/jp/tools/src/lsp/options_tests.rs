@@ -0,0 +1,81 @@
+use serde_json::json;
+
+use super::*;
+
+fn parse(value: Value) -> LspOptions {
+    let Value::Object(map) = value else {
+        panic!("options must be an object");
+    };
+
+    LspOptions::parse(&map).unwrap()
+}
+
+#[test]
+fn test_defaults() {
+    let options = parse(json!({}));
+
+    assert_eq!(options.idle_timeout_secs, 600);
+    assert_eq!(options.request_timeout_secs, 60);
+    assert!(options.suppress.is_empty());
+}
+
+#[test]
+fn test_builtin_servers() {
+    let options = parse(json!({}));
+
+    let name = |path: &str| {
+        options
+            .server_for(Utf8Path::new(path))
+            .map(|(name, _)| name)
+    };
+    assert_eq!(name("src/lib.rs").as_deref(), Some("rust-analyzer"));
+    assert_eq!(
+        name("web/app.tsx").as_deref(),
+        Some("typescript-language-server")
+    );
+    assert_eq!(name("main.py").as_deref(), Some("pyright"));
+    assert_eq!(name("README.md"), None);
+    assert_eq!(name("Makefile"), None);
+}
+
+#[test]
+fn test_configured_servers_take_precedence() {
+    let options = parse(json!({
+        "servers": {
+            "ra-nightly": { "command": "rust-analyzer-nightly", "extensions": ["rs"] },
+            "pyright": { "command": "basedpyright-langserver", "args": ["--stdio"], "extensions": ["py"] },
+            "gopls": { "command": "gopls", "extensions": ["go"] },
+        }
+    }));
+
+    let server = |path: &str| options.server_for(Utf8Path::new(path)).unwrap();
+
+    let (name, config) = server("lib.rs");
+    assert_eq!(name, "ra-nightly");
+    assert_eq!(config.command, "rust-analyzer-nightly");
+
+    let (name, config) = server("main.py");
+    assert_eq!(name, "pyright");
+    assert_eq!(config.command, "basedpyright-langserver");
+
+    // Replacing a built-in server by name also drops its extensions.
+    assert_eq!(options.server_for(Utf8Path::new("stubs.pyi")), None);
+
+    assert_eq!(server("main.go").0, "gopls");
+}
+
+#[test]
+fn test_unknown_options_are_rejected() {
+    let Value::Object(map) = json!({ "server": {} }) else {
+        unreachable!()
+    };
+
+    assert!(LspOptions::parse(&map).is_err());
+}
+
+#[test]
+fn test_language_id() {
+    assert_eq!(language_id(Utf8Path::new("a.rs")), "rust");
+    assert_eq!(language_id(Utf8Path::new("a.tsx")), "typescriptreact");
+    assert_eq!(language_id(Utf8Path::new("a.go")), "go");
+}
@@ -0,0 +1,321 @@
+//! Conversions between tool arguments, LSP values and tool output.
+//!
+//! LSP positions are 0-based, with columns counted in UTF-16 code units.
+//! Tool arguments and output use 1-based lines and columns counted in
+//! characters, like the rest of the tools.
+
+use std::collections::BTreeMap;
+
+use camino::Utf8PathBuf;
+use serde_json::Value;
+use url::Url;
+
+/// A position in a file, as returned by the language server.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) struct Location {
+    /// The absolute path of the file.
+    pub path: Utf8PathBuf,
+
+    /// The 0-based line.
+    pub line: u32,
+
+    /// The 0-based column, in UTF-16 code units.
+    pub character: u32,
+}
+
+/// A change to the text of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct TextEdit {
+    pub start: (u32, u32),
+    pub end: (u32, u32),
+    pub new_text: String,
+}
+
+/// The edits a rename would make.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(super) struct WorkspaceEdit {
+    /// Text edits, by absolute file path.
+    pub edits: BTreeMap<Utf8PathBuf, Vec<TextEdit>>,
+
+    /// Files that would be created, renamed or deleted, described.
+    pub operations: Vec<String>,
+}
+
+/// The UTF-16 column of the character at `index` in `line`.
+pub(super) fn utf16_column(line: &str, index: usize) -> u32 {
+    let units = line.chars().take(index).map(char::len_utf16).sum::<usize>();
+    u32::try_from(units).unwrap_or(u32::MAX)
+}
+
+/// The character index of a UTF-16 `column` in `line`.
+pub(super) fn char_column(line: &str, column: u32) -> usize {
+    let mut units = 0;
+    for (index, c) in line.chars().enumerate() {
+        if units >= column as usize {
+            return index;
+        }
+        units += c.len_utf16();
+    }
+
+    line.chars().count()
+}
+
+/// The character index at which `symbol` occurs in `line`.
+///
+/// Occurrences that form a whole word are preferred, so `new` finds
+/// `Self::new` rather than the `new` in `renew`.
+pub(super) fn symbol_column(line: &str, symbol: &str) -> Option<usize> {
+    if symbol.is_empty() {
+        return None;
+    }
+
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let whole_word = line.match_indices(symbol).find(|&(start, _)| {
+        let end = start + symbol.len();
+        !line[..start].chars().next_back().is_some_and(is_word)
+            && !line[end..].chars().next().is_some_and(is_word)
+    });
+
+    let (start, _) = whole_word.or_else(|| line.match_indices(symbol).next())?;
+    Some(line[..start].chars().count())
+}
+
+/// The locations in a `definition` or `references` result.
+///
+/// Accepts a single `Location`, an array of `Location`s or `LocationLink`s, or
+/// `null`.
+pub(super) fn locations(value: &Value) -> Vec<Location> {
+    let items = match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(_) => vec![value],
+        _ => vec![],
+    };
+
+    let mut locations = items
+        .into_iter()
+        .filter_map(|item| {
+            // A `LocationLink` points at the whole target, and at the part of
+            // it that names the symbol, which is the part worth showing.
+            let (uri, range) = match item.get("targetUri") {
+                Some(uri) => (uri, item.get("targetSelectionRange")?),
+                None => (item.get("uri")?, item.get("range")?),
+            };
+
+            let (line, character) = position(range.get("start")?)?;
+            Some(Location {
+                path: uri_path(uri.as_str()?)?,
+                line,
+                character,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    locations.sort();
+    locations.dedup();
+    locations
+}
+
+/// The text of a `hover` result, if it has any.
+pub(super) fn hover_text(value: &Value) -> Option<String> {
+    let text = match value.get("contents")? {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(marked_string)
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        contents => marked_string(contents)?,
+    };
+
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_owned())
+}
+
+/// A `MarkedString` or `MarkupContent`, as markdown.
+fn marked_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Object(_) => {
+            let text = value.get("value")?.as_str()?;
+            match value.get("language").and_then(Value::as_str) {
+                Some(language) => Some(format!("```{language}\n{text}\n```")),
+                None => Some(text.to_owned()),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// A diagnostic on a single line: `12:5: error: message [source code]`.
+pub(super) fn diagnostic(value: &Value, line_text: impl Fn(u32) -> Option<String>) -> String {
+    let (line, character) = value
+        .get("range")
+        .and_then(|range| range.get("start"))
+        .and_then(position)
+        .unwrap_or_default();
+
+    let column = line_text(line).map_or(character as usize, |text| char_column(&text, character));
+
+    let severity = match value.get("severity").and_then(Value::as_u64) {
+        Some(1) | None => "error",
+        Some(2) => "warning",
+        Some(3) => "info",
+        Some(_) => "hint",
+    };
+
+    let message = value
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .trim();
+
+    let mut out = format!("{}:{}: {severity}: {message}", line + 1, column + 1);
+
+    let source = value.get("source").and_then(Value::as_str);
+    let code = value.get("code").and_then(|code| match code {
+        Value::String(code) => Some(code.clone()),
+        Value::Number(code) => Some(code.to_string()),
+        _ => None,
+    });
+
+    match (source, code) {
+        (Some(source), Some(code)) => out.push_str(&format!(" [{source} {code}]")),
+        (Some(label), None) => out.push_str(&format!(" [{label}]")),
+        (None, Some(label)) => out.push_str(&format!(" [{label}]")),
+        (None, None) => {}
+    }
+
+    out
+}
+
+/// The edits in a `rename` result.
+///
+/// Accepts both the `changes` and the `documentChanges` form.
+pub(super) fn workspace_edit(value: &Value) -> WorkspaceEdit {
+    let mut edit = WorkspaceEdit::default();
+
+    if let Some(changes) = value.get("changes").and_then(Value::as_object) {
+        for (uri, edits) in changes {
+            if let Some(path) = uri_path(uri) {
+                edit.edits
+                    .entry(path)
+                    .or_default()
+                    .extend(text_edits(edits));
+            }
+        }
+    }
+
+    for change in value
+        .get("documentChanges")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let path = |key: &str| change.get(key).and_then(Value::as_str).and_then(uri_path);
+
+        match change.get("kind").and_then(Value::as_str) {
+            Some("create") => edit
+                .operations
+                .extend(path("uri").map(|p| format!("create {p}"))),
+            Some("delete") => edit
+                .operations
+                .extend(path("uri").map(|p| format!("delete {p}"))),
+            Some("rename") => {
+                if let (Some(from), Some(to)) = (path("oldUri"), path("newUri")) {
+                    edit.operations.push(format!("rename {from} to {to}"));
+                }
+            }
+            _ => {
+                let path = change
+                    .pointer("/textDocument/uri")
+                    .and_then(Value::as_str)
+                    .and_then(uri_path);
+
+                if let (Some(path), Some(edits)) = (path, change.get("edits")) {
+                    edit.edits
+                        .entry(path)
+                        .or_default()
+                        .extend(text_edits(edits));
+                }
+            }
+        }
+    }
+
+    edit
+}
+
+fn text_edits(value: &Value) -> Vec<TextEdit> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|edit| {
+            let range = edit.get("range")?;
+            Some(TextEdit {
+                start: position(range.get("start")?)?,
+                end: position(range.get("end")?)?,
+                new_text: edit.get("newText")?.as_str()?.to_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Apply `edits` to `content`.
+///
+/// Edits must not overlap, as the LSP specification requires.
+pub(super) fn apply_edits(content: &str, edits: &[TextEdit]) -> String {
+    let mut edits = edits.to_vec();
+
+    // Back to front, so earlier offsets stay valid.
+    edits.sort_by(|a, b| b.start.cmp(&a.start));
+
+    let mut content = content.to_owned();
+    for edit in edits {
+        let start = offset(&content, edit.start);
+        let end = offset(&content, edit.end).max(start);
+        content.replace_range(start..end, &edit.new_text);
+    }
+
+    content
+}
+
+/// The byte offset of an LSP position in `content`.
+///
+/// Positions past the end of a line clamp to the end of that line, and
+/// positions past the last line to the end of the content.
+fn offset(content: &str, (line, character): (u32, u32)) -> usize {
+    let mut line_start = 0;
+    for _ in 0..line {
+        match content[line_start..].find('\n') {
+            Some(newline) => line_start += newline + 1,
+            None => return content.len(),
+        }
+    }
+
+    let line_text = content[line_start..].split('\n').next().unwrap_or_default();
+
+    let mut units = 0;
+    for (index, c) in line_text.char_indices() {
+        if units >= character as usize {
+            return line_start + index;
+        }
+        units += c.len_utf16();
+    }
+
+    line_start + line_text.len()
+}
+
+fn position(value: &Value) -> Option<(u32, u32)> {
+    let line = u32::try_from(value.get("line")?.as_u64()?).ok()?;
+    let character = u32::try_from(value.get("character")?.as_u64()?).ok()?;
+    Some((line, character))
+}
+
+/// The path of a `file://` URI.
+pub(super) fn uri_path(uri: &str) -> Option<Utf8PathBuf> {
+    let path = Url::parse(uri).ok()?.to_file_path().ok()?;
+    Utf8PathBuf::from_path_buf(path).ok()
+}
+
+#[cfg(test)]
+#[path = "render_tests.rs"]
+mod tests;
@@ -0,0 +1,183 @@
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+use super::*;
+
+#[test]
+fn test_columns() {
+    let line = "let 𝒙 = \"é\"; x";
+
+    // `𝒙` takes two UTF-16 code units.
+    assert_eq!(utf16_column(line, 4), 4);
+    assert_eq!(utf16_column(line, 5), 6);
+    assert_eq!(char_column(line, 6), 5);
+    assert_eq!(char_column(line, 100), line.chars().count());
+
+    for index in 0..line.chars().count() {
+        assert_eq!(char_column(line, utf16_column(line, index)), index);
+    }
+}
+
+#[test]
+fn test_symbol_column() {
+    assert_eq!(symbol_column("fn renew() { new() }", "new"), Some(13));
+    assert_eq!(symbol_column("fn renew() {}", "new"), Some(5));
+    assert_eq!(symbol_column("let é = foo;", "foo"), Some(8));
+    assert_eq!(symbol_column("let x = 1;", "y"), None);
+    assert_eq!(symbol_column("let x = 1;", ""), None);
+}
+
+#[test]
+fn test_locations() {
+    let location = |uri: &str, line: u32, character: u32| {
+        json!({
+            "uri": uri,
+            "range": {
+                "start": { "line": line, "character": character },
+                "end": { "line": line, "character": character + 3 },
+            },
+        })
+    };
+
+    assert_eq!(locations(&Value::Null), vec![]);
+
+    assert_eq!(locations(&location("file:///ws/a.rs", 2, 4)), vec![
+        Location {
+            path: "/ws/a.rs".into(),
+            line: 2,
+            character: 4,
+        }
+    ]);
+
+    // Sorted and deduplicated.
+    let result = json!([
+        location("file:///ws/b.rs", 1, 0),
+        location("file:///ws/a.rs", 9, 0),
+        location("file:///ws/b.rs", 1, 0),
+    ]);
+    assert_eq!(
+        locations(&result)
+            .into_iter()
+            .map(|l| (l.path.into_string(), l.line))
+            .collect::<Vec<_>>(),
+        vec![("/ws/a.rs".to_owned(), 9), ("/ws/b.rs".to_owned(), 1)]
+    );
+
+    let link = json!([{
+        "targetUri": "file:///ws/c.rs",
+        "targetRange": { "start": { "line": 0, "character": 0 }, "end": { "line": 9, "character": 1 } },
+        "targetSelectionRange": { "start": { "line": 3, "character": 7 }, "end": { "line": 3, "character": 10 } },
+    }]);
+    assert_eq!(locations(&link), vec![Location {
+        path: "/ws/c.rs".into(),
+        line: 3,
+        character: 7,
+    }]);
+}
+
+#[test]
+fn test_hover_text() {
+    assert_eq!(
+        hover_text(&json!({ "contents": { "kind": "markdown", "value": "```rust\nfn a()\n```" } })),
+        Some("```rust\nfn a()\n```".to_owned())
+    );
+    assert_eq!(
+        hover_text(&json!({ "contents": [{ "language": "python", "value": "def a()" }, "Docs."] })),
+        Some("```python\ndef a()\n```\n\nDocs.".to_owned())
+    );
+    assert_eq!(hover_text(&json!({ "contents": "" })), None);
+    assert_eq!(hover_text(&Value::Null), None);
+}
+
+#[test]
+fn test_diagnostic() {
+    let value = json!({
+        "range": { "start": { "line": 1, "character": 6 }, "end": { "line": 1, "character": 7 } },
+        "severity": 2,
+        "source": "rustc",
+        "code": "unused_variables",
+        "message": "unused variable: `x`\n",
+    });
+
+    assert_eq!(
+        diagnostic(&value, |_| Some("let 𝒙x = 1;".to_owned())),
+        "2:6: warning: unused variable: `x` [rustc unused_variables]"
+    );
+
+    assert_eq!(
+        diagnostic(&json!({ "message": "oops", "code": 42 }), |_| None),
+        "1:1: error: oops [42]"
+    );
+}
+
+#[test]
+fn test_workspace_edit() {
+    let range = |line: u32, start: u32, end: u32| json!({ "start": { "line": line, "character": start }, "end": { "line": line, "character": end } });
+
+    let value = json!({
+        "changes": {
+            "file:///ws/a.rs": [{ "range": range(0, 3, 6), "newText": "bar" }],
+        },
+        "documentChanges": [
+            {
+                "textDocument": { "uri": "file:///ws/b.rs", "version": 1 },
+                "edits": [{ "range": range(2, 0, 3), "newText": "bar" }],
+            },
+            { "kind": "rename", "oldUri": "file:///ws/foo.rs", "newUri": "file:///ws/bar.rs" },
+        ],
+    });
+
+    let edit = workspace_edit(&value);
+    assert_eq!(
+        edit.edits
+            .keys()
+            .map(Utf8PathBuf::as_str)
+            .collect::<Vec<_>>(),
+        vec!["/ws/a.rs", "/ws/b.rs"]
+    );
+    assert_eq!(edit.edits["/ws/a.rs"], vec![TextEdit {
+        start: (0, 3),
+        end: (0, 6),
+        new_text: "bar".to_owned(),
+    }]);
+    assert_eq!(edit.operations, vec![
+        "rename /ws/foo.rs to /ws/bar.rs".to_owned()
+    ]);
+}
+
+#[test]
+fn test_apply_edits() {
+    let content = "fn foo() {}\nfn main() {\n    foo();\n}\n";
+    let edits = [
+        TextEdit {
+            start: (2, 4),
+            end: (2, 7),
+            new_text: "bar".to_owned(),
+        },
+        TextEdit {
+            start: (0, 3),
+            end: (0, 6),
+            new_text: "bar".to_owned(),
+        },
+    ];
+
+    assert_eq!(
+        apply_edits(content, &edits),
+        "fn bar() {}\nfn main() {\n    bar();\n}\n"
+    );
+
+    // UTF-16 columns, and insertion at the end of the content.
+    let edits = [
+        TextEdit {
+            start: (0, 2),
+            end: (0, 3),
+            new_text: "y".to_owned(),
+        },
+        TextEdit {
+            start: (9, 0),
+            end: (9, 0),
+            new_text: "!".to_owned(),
+        },
+    ];
+    assert_eq!(apply_edits("𝒙x", &edits), "𝒙y!");
+}
@@ -0,0 +1,459 @@
+//! A client for a language server running as a child process.
+
+use std::{
+    collections::HashMap,
+    process::Stdio,
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use camino::Utf8Path;
+use serde_json::{Value, json};
+use tokio::{
+    io::BufReader,
+    process::{Child, ChildStdin, ChildStdout, Command},
+    sync::{Mutex as AsyncMutex, Notify, oneshot},
+    time::{Instant, sleep, timeout_at},
+};
+use url::Url;
+
+use super::{
+    jsonrpc::{CONTENT_MODIFIED, SERVER_CANCELLED, read_message, write_message},
+    options::{ServerConfig, language_id},
+};
+
+/// How often a request the server abandoned is retried.
+///
+/// Servers that are still indexing answer with `ContentModified` until they
+/// are done, so the retries also wait out a cold start.
+const MAX_RETRIES: usize = 20;
+
+/// The delay between retries of an abandoned request.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+type Response = Result<Value, RpcError>;
+
+/// An error response to a request.
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+/// A running language server.
+pub(super) struct LanguageServer {
+    /// Kept so the server is killed when the client is dropped.
+    _child: Child,
+
+    shared: Arc<Shared>,
+
+    next_id: AtomicI64,
+
+    /// The documents opened on the server, by URI, with their version and
+    /// the content the server last saw.
+    documents: AsyncMutex<HashMap<String, (i32, String)>>,
+
+    /// The capabilities the server announced in its `initialize` response.
+    capabilities: Value,
+
+    /// How long to wait for the server to answer a request.
+    timeout: Duration,
+}
+
+/// State shared with the task reading the server's output.
+struct Shared {
+    stdin: AsyncMutex<ChildStdin>,
+
+    /// Senders for the responses to requests in flight, by request ID.
+    pending: Mutex<HashMap<i64, oneshot::Sender<Response>>>,
+
+    /// The most recently published diagnostics, by document URI, with the
+    /// generation they were published in.
+    diagnostics: Mutex<HashMap<String, (u64, Vec<Value>)>>,
+
+    /// Incremented for every publication of diagnostics.
+    generation: AtomicU64,
+
+    /// Notified whenever diagnostics are published, or the server exits.
+    published: Notify,
+
+    /// Set once the server closed its output.
+    exited: AtomicBool,
+}
+
+impl LanguageServer {
+    /// Start the server described by `config` for the workspace at `root`, and
+    /// wait for it to initialize.
+    pub(super) async fn start(
+        root: &Utf8Path,
+        config: &ServerConfig,
+        timeout: Duration,
+    ) -> Result<Self, String> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|error| format!("Unable to start `{}`: {error}", config.command))?;
+
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err("Language server has no stdio.".to_owned());
+        };
+
+        let shared = Arc::new(Shared {
+            stdin: AsyncMutex::new(stdin),
+            pending: Mutex::new(HashMap::new()),
+            diagnostics: Mutex::new(HashMap::new()),
+            generation: AtomicU64::new(0),
+            published: Notify::new(),
+            exited: AtomicBool::new(false),
+        });
+
+        drop(tokio::spawn(read_loop(shared.clone(), stdout)));
+
+        let mut server = Self {
+            _child: child,
+            shared,
+            next_id: AtomicI64::new(1),
+            documents: AsyncMutex::new(HashMap::new()),
+            capabilities: Value::Null,
+            timeout,
+        };
+
+        let root_uri = file_uri(root)?;
+        let response = server
+            .request("initialize", json!({
+                "processId": std::process::id(),
+                "rootUri": root_uri,
+                "workspaceFolders": [{ "uri": root_uri, "name": root.file_name().unwrap_or("workspace") }],
+                "initializationOptions": config.initialization_options,
+                "capabilities": {
+                    "general": { "positionEncodings": ["utf-16"] },
+                    "textDocument": {
+                        "synchronization": { "dynamicRegistration": false },
+                        "definition": { "linkSupport": true },
+                        "references": {},
+                        "hover": { "contentFormat": ["markdown", "plaintext"] },
+                        "rename": { "prepareSupport": false },
+                        "publishDiagnostics": { "relatedInformation": false },
+                        "diagnostic": { "dynamicRegistration": false },
+                    },
+                    "workspace": {
+                        "workspaceFolders": true,
+                        "configuration": true,
+                        "workspaceEdit": { "documentChanges": true },
+                    },
+                },
+            }))
+            .await?;
+
+        server.capabilities = response.get("capabilities").cloned().unwrap_or(Value::Null);
+
+        server.notify("initialized", json!({})).await?;
+        Ok(server)
+    }
+
+    /// Whether the server process is gone.
+    pub(super) fn exited(&self) -> bool {
+        self.shared.exited.load(Ordering::SeqCst)
+    }
+
+    /// Send a request and wait for its result.
+    ///
+    /// Requests the server abandoned because it was busy are retried.
+    pub(super) async fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        for _ in 0..MAX_RETRIES {
+            match self.request_once(method, &params).await? {
+                Ok(result) => return Ok(result),
+                Err(RpcError { code, .. })
+                    if code == CONTENT_MODIFIED || code == SERVER_CANCELLED =>
+                {
+                    sleep(RETRY_DELAY).await;
+                }
+                Err(RpcError { message, .. }) => {
+                    return Err(format!("Language server failed `{method}`: {message}"));
+                }
+            }
+        }
+
+        Err(format!(
+            "Language server is still busy; `{method}` was abandoned {MAX_RETRIES} times."
+        ))
+    }
+
+    async fn request_once(&self, method: &str, params: &Value) -> Result<Response, String> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.shared
+            .pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(id, tx);
+
+        self.shared
+            .send(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await?;
+
+        match tokio::time::timeout(self.timeout, rx).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err("Language server exited.".to_owned()),
+            Err(_) => {
+                self.shared
+                    .pending
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .remove(&id);
+                drop(self.notify("$/cancelRequest", json!({ "id": id })).await);
+                Err(format!(
+                    "Language server did not answer `{method}` within {} seconds.",
+                    self.timeout.as_secs()
+                ))
+            }
+        }
+    }
+
+    /// Send a notification.
+    pub(super) async fn notify(&self, method: &str, params: Value) -> Result<(), String> {
+        self.shared
+            .send(&json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+            .await
+    }
+
+    /// Send the current content of `path` to the server, opening the document
+    /// if needed, and return its URI.
+    ///
+    /// Returns whether the server's copy of the document changed.
+    pub(super) async fn sync(&self, path: &Utf8Path) -> Result<(String, bool), String> {
+        let uri = file_uri(path)?;
+        let content = std::fs::read_to_string(path)
+            .map_err(|error| format!("Unable to read '{path}': {error}"))?;
+
+        let mut documents = self.documents.lock().await;
+        match documents.get_mut(&uri) {
+            Some((_, known)) if *known == content => return Ok((uri, false)),
+            Some((version, known)) => {
+                *version += 1;
+                self.notify(
+                    "textDocument/didChange",
+                    json!({
+                        "textDocument": { "uri": uri, "version": version },
+                        "contentChanges": [{ "text": content }],
+                    }),
+                )
+                .await?;
+                *known = content;
+            }
+            None => {
+                self.notify(
+                    "textDocument/didOpen",
+                    json!({
+                        "textDocument": {
+                            "uri": uri,
+                            "languageId": language_id(path),
+                            "version": 1,
+                            "text": content,
+                        },
+                    }),
+                )
+                .await?;
+                documents.insert(uri.clone(), (1, content));
+            }
+        }
+
+        Ok((uri, true))
+    }
+
+    /// The diagnostics of `path`.
+    ///
+    /// Servers that support pulling diagnostics are asked for them directly.
+    /// For the others, this waits for the server to publish diagnostics for
+    /// the current content of the document, up to the request timeout.
+    pub(super) async fn diagnostics(&self, path: &Utf8Path) -> Result<Vec<Value>, String> {
+        let generation = self.shared.generation.load(Ordering::SeqCst);
+        let (uri, changed) = self.sync(path).await?;
+
+        if self.capabilities.get("diagnosticProvider").is_some() {
+            let report = self
+                .request(
+                    "textDocument/diagnostic",
+                    json!({ "textDocument": { "uri": uri } }),
+                )
+                .await?;
+
+            return Ok(report
+                .get("items")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default());
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let published = self.shared.published.notified();
+            if let Some((at, diagnostics)) = self.shared.published_for(&uri)
+                && (!changed || at > generation)
+            {
+                return Ok(diagnostics);
+            }
+
+            if self.exited() {
+                return Err("Language server exited.".to_owned());
+            }
+
+            // Servers publish nothing for files they have nothing to say
+            // about, so running out of time is not an error.
+            if timeout_at(deadline, published).await.is_err() {
+                return Ok(self
+                    .shared
+                    .published_for(&uri)
+                    .map(|(_, diagnostics)| diagnostics)
+                    .unwrap_or_default());
+            }
+        }
+    }
+
+    /// Ask the server to shut down, and wait for it to do so.
+    pub(super) async fn shutdown(&self) {
+        if self.exited() {
+            return;
+        }
+
+        if self.request_once("shutdown", &Value::Null).await.is_ok() {
+            drop(self.notify("exit", Value::Null).await);
+        }
+    }
+}
+
+impl Shared {
+    async fn send(&self, message: &Value) -> Result<(), String> {
+        let mut stdin = self.stdin.lock().await;
+        write_message(&mut *stdin, message)
+            .await
+            .map_err(|error| format!("Unable to write to language server: {error}"))
+    }
+
+    fn published_for(&self, uri: &str) -> Option<(u64, Vec<Value>)> {
+        self.diagnostics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(uri)
+            .cloned()
+    }
+
+    /// Handle a message from the server, returning the reply to send if it is
+    /// a request.
+    fn handle(&self, message: &Value) -> Option<Value> {
+        let method = message.get("method").and_then(Value::as_str);
+        let id = message.get("id").cloned();
+
+        match (method, id) {
+            // A response to one of our requests.
+            (None, Some(id)) => {
+                let id = id.as_i64()?;
+
+                let response = match message.get("error") {
+                    Some(error) => Err(RpcError {
+                        code: error
+                            .get("code")
+                            .and_then(Value::as_i64)
+                            .unwrap_or_default(),
+                        message: error
+                            .get("message")
+                            .and_then(Value::as_str)
+                            .unwrap_or("unknown error")
+                            .to_owned(),
+                    }),
+                    None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                };
+
+                let sender = self
+                    .pending
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .remove(&id);
+                if let Some(sender) = sender {
+                    drop(sender.send(response));
+                }
+
+                None
+            }
+
+            (Some("textDocument/publishDiagnostics"), None) => {
+                let params = message.get("params").cloned().unwrap_or_default();
+                let uri = params.get("uri").and_then(Value::as_str)?;
+
+                let diagnostics = params
+                    .get("diagnostics")
+                    .and_then(Value::as_array)
+                    .cloned()
+                    .unwrap_or_default();
+
+                let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+                self.diagnostics
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .insert(uri.to_owned(), (generation, diagnostics));
+                self.published.notify_waiters();
+                None
+            }
+
+            // A request from the server. Servers wait for an answer to some of
+            // these before they continue, so every one of them is answered,
+            // with the client defaults.
+            (Some(method), Some(id)) => {
+                let result = match method {
+                    "workspace/configuration" => {
+                        let items = message
+                            .pointer("/params/items")
+                            .and_then(Value::as_array)
+                            .map_or(0, Vec::len);
+                        Value::Array(vec![Value::Null; items])
+                    }
+                    "workspace/workspaceFolders" => Value::Array(vec![]),
+                    _ => Value::Null,
+                };
+
+                Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+            }
+
+            // Progress, log messages and other notifications.
+            (Some(_), None) | (None, None) => None,
+        }
+    }
+}
+
+async fn read_loop(shared: Arc<Shared>, stdout: ChildStdout) {
+    let mut reader = BufReader::new(stdout);
+    while let Ok(Some(message)) = read_message(&mut reader).await {
+        // Replies are sent from their own task: waiting here for the stdin lock
+        // could deadlock with a request that waits for the server to read its
+        // stdin, while the server waits for us to read its stdout.
+        if let Some(reply) = shared.handle(&message) {
+            let shared = shared.clone();
+            drop(tokio::spawn(async move { drop(shared.send(&reply).await) }));
+        }
+    }
+
+    shared.exited.store(true, Ordering::SeqCst);
+
+    // Dropping the senders fails the requests still waiting for an answer.
+    shared
+        .pending
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+    shared.published.notify_waiters();
+}
+
+/// The `file://` URI of an absolute path.
+pub(super) fn file_uri(path: &Utf8Path) -> Result<String, String> {
+    Url::from_file_path(path)
+        .map(String::from)
+        .map_err(|()| format!("'{path}' is not an absolute path."))
+}
@@ -0,0 +1,139 @@
+use camino_tempfile::tempdir;
+use jp_tool::{AccessPolicy, Action, FsRule};
+use pretty_assertions::assert_eq;
+
+use super::*;
+
+fn context(root: &Utf8Path, access: Option<AccessPolicy>) -> Context {
+    Context {
+        root: root.to_path_buf(),
+        action: Action::Run,
+        access,
+        workspace_id: "test".into(),
+        conversation_id: "test".into(),
+        checkpoint: None,
+    }
+}
+
+fn options() -> LspOptions {
+    LspOptions::parse(&serde_json::Map::new()).unwrap()
+}
+
+#[test]
+fn test_target_position() {
+    let target = Target {
+        absolute: "/ws/lib.rs".into(),
+        relative: "lib.rs".into(),
+        content: "fn main() {\n    let 𝒙 = run();\n}\n".to_owned(),
+    };
+
+    assert_eq!(
+        target.position(2, None, Some("run".to_owned())).unwrap(),
+        json!({ "line": 1, "character": 13 })
+    );
+    assert_eq!(
+        target.position(1, Some(4), None).unwrap(),
+        json!({ "line": 0, "character": 3 })
+    );
+
+    assert_eq!(
+        target
+            .position(2, None, Some("nope".to_owned()))
+            .unwrap_err(),
+        "`nope` does not occur on line 2."
+    );
+    assert_eq!(
+        target.position(2, None, None).unwrap_err(),
+        "Either `column` or `symbol` is required."
+    );
+    assert_eq!(
+        target.position(2, Some(0), None).unwrap_err(),
+        "`column` must be greater than 0."
+    );
+    assert_eq!(
+        target.position(0, Some(1), None).unwrap_err(),
+        "`line` must be between 1 and 3."
+    );
+    assert_eq!(
+        target.position(4, Some(1), None).unwrap_err(),
+        "`line` must be between 1 and 3."
+    );
+}
+
+#[test]
+fn test_target_honors_the_access_policy() {
+    let ws = tempdir().unwrap();
+    std::fs::create_dir_all(ws.path().join("src")).unwrap();
+    std::fs::write(ws.path().join("src/lib.rs"), "fn a() {}\n").unwrap();
+    std::fs::write(ws.path().join("secret.rs"), "fn b() {}\n").unwrap();
+
+    let policy = AccessPolicy {
+        fs: vec![FsRule::new("src").with_read(true)],
+        ..AccessPolicy::default()
+    };
+    let ctx = context(ws.path(), Some(policy));
+    let options = options();
+    let suppress = Gitignore::empty();
+    let session = Session {
+        ctx: &ctx,
+        suppress: &suppress,
+        options: &options,
+    };
+
+    let target = session.target("src/lib.rs").unwrap();
+    assert_eq!(target.relative, "src/lib.rs");
+    assert_eq!(target.content, "fn a() {}\n");
+
+    let error = session.target("secret.rs").err().unwrap();
+    assert!(error.starts_with("Access denied"), "{error}");
+
+    let error = session.target("src").err().unwrap();
+    assert_eq!(error, "'src' is not a file.");
+}
+
+#[test]
+fn test_list_locations() {
+    let ws = tempdir().unwrap();
+    std::fs::create_dir_all(ws.path().join("src")).unwrap();
+    std::fs::create_dir_all(ws.path().join("vendor")).unwrap();
+    std::fs::write(
+        ws.path().join("src/lib.rs"),
+        "use x;\n    pub fn run() {}\n",
+    )
+    .unwrap();
+    std::fs::write(ws.path().join("vendor/lib.rs"), "fn run() {}\n").unwrap();
+
+    let ctx = context(ws.path(), None);
+    let options = options();
+    let suppress = suppress_matcher(ws.path(), &["vendor/".to_owned()]).unwrap();
+    let session = Session {
+        ctx: &ctx,
+        suppress: &suppress,
+        options: &options,
+    };
+
+    let root = session.root();
+    let out = session.list_locations(&[
+        Location {
+            path: root.join("src/lib.rs"),
+            line: 1,
+            character: 11,
+        },
+        Location {
+            path: root.join("vendor/lib.rs"),
+            line: 0,
+            character: 3,
+        },
+        Location {
+            path: "/rustlib/src/core/option.rs".into(),
+            line: 99,
+            character: 0,
+        },
+    ]);
+
+    assert_eq!(
+        out,
+        "src/lib.rs:2:12: pub fn run() {}\n/rustlib/src/core/option.rs:100:1\n1 location(s) are \
+         not readable by this tool and were left out."
+    );
+}
@@ -8,6 +8,17 @@ use tools::{Tool, run};
 
 #[tokio::main]
 async fn main() {
+    // The background process that keeps a language server running between
+    // `lsp_*` tool calls.
+    #[cfg(unix)]
+    if env::args().nth(1).as_deref() == Some("lsp-bridge") {
+        let config = env::args().nth(2).unwrap_or_default();
+        if let Err(error) = tools::serve_lsp_bridge(&config).await {
+            eprintln!("{error}");
+        }
+        return;
+    }
+
     let context = match input::<Context>(1, "context") {
         Ok(workspace) => workspace,
         Err(error) => return println!("{error}"),
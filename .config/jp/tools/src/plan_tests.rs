@@ -30,6 +30,7 @@ fn run_tool(dir: &Utf8TempDir, args: serde_json::Value) -> ToolResult {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
     let arguments = match args {
         serde_json::Value::Object(map) => map,
@@ -0,0 +1,28 @@
+use crate::{
+    Context, Tool,
+    util::{ToolResult, unknown_tool},
+};
+
+mod run;
+
+use run::{TestRun, test_run};
+
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "consistent with other module run fns"
+)]
+pub fn run(ctx: Context, t: Tool) -> ToolResult {
+    match t.name.trim_start_matches("test_") {
+        "run" => test_run(
+            &ctx,
+            TestRun {
+                runner: t.opt("runner")?,
+                tests: t.opt("tests")?.unwrap_or_default(),
+                failed: t.opt("failed")?.unwrap_or(false),
+                args: t.opt("args")?.unwrap_or_default(),
+            },
+            &t.options,
+        ),
+        _ => unknown_tool(t),
+    }
+}
@@ -0,0 +1,288 @@
+//! The `test_run` tool: runs the test suite of any language, and reports the
+//! failures in a compact, structured form.
+//!
+//! Each runner declares the command that runs the tests and the format of its
+//! report. The report is reduced to the failing tests, with their message,
+//! location and (truncated) output; passing tests are only counted.
+//!
+//! The names of the tests that failed in the last run of each runner are
+//! persisted per conversation under
+//! `<workspace>/.jp/mcp/state/test_run/<conversation_id>/<runner>.json`, so the
+//! next call can re-run only those.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    Context, to_simple_xml_with_root,
+    util::{
+        ToolResult, error,
+        runner::{DuctProcessRunner, ProcessOutput, ProcessRunner},
+        truncate,
+    },
+};
+
+mod report;
+mod runner;
+
+use report::{Case, Status};
+use runner::{REPORT, Runner, TestRunOptions};
+
+/// Cap for the runner output shown when no tests ran.
+const MAX_DIAGNOSTIC_BYTES: usize = 32_000;
+
+/// Cap for a single failing test's output.
+const MAX_TEST_OUTPUT_BYTES: usize = 8_000;
+
+/// Cap for the serialized failures of a run, combined.
+///
+/// Failures past this budget are counted, but carry no output.
+const MAX_TEST_OUTPUT_BUDGET_BYTES: usize = 32_000;
+
+/// Approximate size of one serialized failure, without its output.
+const FAILURE_BLOCK_OVERHEAD_BYTES: usize = 120;
+
+/// The arguments of a `test_run` call.
+pub(crate) struct TestRun {
+    /// The runner to use, instead of the configured default.
+    pub runner: Option<String>,
+
+    /// Run only these tests.
+    pub tests: Vec<String>,
+
+    /// Run only the tests that failed in the last run.
+    pub failed: bool,
+
+    /// Extra arguments passed to the runner.
+    pub args: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct TestFailure {
+    name: String,
+    suite: Option<String>,
+    location: Option<String>,
+    message: Option<String>,
+    output: Option<String>,
+}
+
+/// The state persisted between runs.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LastRun {
+    failed: Vec<String>,
+
+    /// A failed test could not be selected by name, so re-running the failed
+    /// tests runs everything.
+    #[serde(default)]
+    unselectable: bool,
+}
+
+pub(crate) fn test_run(
+    ctx: &Context,
+    request: TestRun,
+    options: &Map<String, Value>,
+) -> ToolResult {
+    let options = TestRunOptions::parse(options)?;
+    let runner = match options.runner(request.runner.as_deref()) {
+        Ok(runner) => runner,
+        Err(message) => return error(message),
+    };
+
+    let dir = state_dir(&ctx.root, &ctx.conversation_id);
+    test_run_impl(&ctx.root, &dir, &runner, request, &DuctProcessRunner)
+}
+
+fn test_run_impl<R: ProcessRunner>(
+    root: &Utf8Path,
+    dir: &Utf8Path,
+    runner: &Runner,
+    request: TestRun,
+    process: &R,
+) -> ToolResult {
+    let mut tests = request.tests;
+    if request.failed {
+        let last = load(dir, &runner.name)?;
+        match last {
+            None => {
+                return error(format!(
+                    "No previous run of the `{}` runner in this conversation. Run the tests first.",
+                    runner.name
+                ));
+            }
+            // Only running everything covers a test that can't be selected.
+            Some(last) if last.unselectable => tests.clear(),
+            Some(last) if last.failed.is_empty() => {
+                return error(format!(
+                    "The last run of the `{}` runner had no failing tests.",
+                    runner.name
+                ));
+            }
+            Some(last) => tests.extend(last.failed),
+        }
+    }
+
+    let select = match (&runner.select, tests.is_empty()) {
+        (_, true) => vec![],
+        (Some(select), false) => select.args(&tests),
+        (None, false) => {
+            return error(format!(
+                "The `{}` runner can't run specific tests. Configure its `select` option.",
+                runner.name
+            ));
+        }
+    };
+
+    std::fs::create_dir_all(dir)?;
+    let report_path = dir.join(format!("{}.report", runner.name));
+    if report_path.exists() {
+        std::fs::remove_file(&report_path)?;
+    }
+
+    let (program, args) = runner.command.split_first().expect("non-empty command");
+    let args: Vec<String> = args
+        .iter()
+        .map(|arg| arg.replace(REPORT, report_path.as_str()))
+        .chain(request.args)
+        .chain(select)
+        .collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let env: Vec<(&str, &str)> = runner
+        .env
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect();
+
+    let ProcessOutput {
+        stdout,
+        stderr,
+        status,
+    } = process.run_with_env(program, &args, root, &env)?;
+
+    let report = if runner.writes_report() {
+        std::fs::read_to_string(&report_path).unwrap_or_default()
+    } else {
+        stdout.clone()
+    };
+
+    let cases = match report::parse(runner.format, &report) {
+        Ok(cases) => cases,
+        Err(_) if report.trim().is_empty() => vec![],
+        Err(message) => return error(message),
+    };
+
+    if cases.is_empty() {
+        Err(format!(
+            "Unable to run any tests (exit status {status}). This can be due to compilation \
+             issues, or incorrect arguments or test names:\n\n{}",
+            truncate(&format!("{stderr}\n{stdout}"), MAX_DIAGNOSTIC_BYTES)
+        ))?;
+    }
+
+    let failed: Vec<&Case> = cases
+        .iter()
+        .filter(|case| case.status == Status::Failed)
+        .collect();
+
+    save(dir, &runner.name, &LastRun {
+        failed: failed
+            .iter()
+            .filter(|case| case.selectable)
+            .map(|case| case.name.clone())
+            .collect(),
+        unselectable: failed.iter().any(|case| !case.selectable),
+    })?;
+
+    Ok(render(&cases, &failed)?.into())
+}
+
+fn render(cases: &[Case], failed: &[&Case]) -> crate::Result<String> {
+    let skipped = cases
+        .iter()
+        .filter(|case| case.status == Status::Skipped)
+        .count();
+    let ran = cases.len() - skipped;
+
+    let mut response = format!(
+        "Ran {ran} tests: {} passed, {} failed, {skipped} skipped.\n",
+        ran - failed.len(),
+        failed.len(),
+    );
+
+    if failed.is_empty() {
+        return Ok(response);
+    }
+
+    let mut spent_bytes = 0;
+    let mut omitted = 0;
+    let failures: Vec<TestFailure> = failed
+        .iter()
+        .map(|case| {
+            let output = if spent_bytes < MAX_TEST_OUTPUT_BUDGET_BYTES {
+                // The output adds nothing if it is just the message.
+                Some(truncate(&case.output, MAX_TEST_OUTPUT_BYTES))
+                    .filter(|output| Some(output) != case.message.as_ref() && !output.is_empty())
+            } else {
+                omitted += 1;
+                None
+            };
+
+            spent_bytes += output.as_ref().map_or(0, String::len)
+                + case.name.len()
+                + FAILURE_BLOCK_OVERHEAD_BYTES;
+
+            TestFailure {
+                name: case.name.clone(),
+                suite: case.suite.clone(),
+                location: case.location.clone(),
+                message: case.message.clone(),
+                output,
+            }
+        })
+        .collect();
+
+    let xml = to_simple_xml_with_root(&failures, "results")?;
+    response.push_str("\nWhat follows is an XML representation of the failed tests:\n\n");
+    response.push_str(&format!("```xml\n{xml}\n```"));
+
+    if omitted > 0 {
+        response.push_str(&format!(
+            "\n\nOutput for {omitted} further failing tests was omitted to bound the size of this \
+             response. Re-run with `tests` set to inspect them."
+        ));
+    }
+
+    response.push_str("\n\nSet `failed` to re-run only the failing tests.");
+    Ok(response)
+}
+
+/// Resolve the per-conversation directory that holds the state of the tool,
+/// inside the workspace's `.jp/mcp/state` tree.
+fn state_dir(root: &Utf8Path, conversation_id: &str) -> Utf8PathBuf {
+    root.join(".jp/mcp/state/test_run").join(conversation_id)
+}
+
+fn path_for(dir: &Utf8Path, runner: &str) -> Utf8PathBuf {
+    dir.join(format!("{runner}.json"))
+}
+
+fn save(dir: &Utf8Path, runner: &str, last: &LastRun) -> crate::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let json = serde_json::to_string_pretty(last)?;
+    std::fs::write(path_for(dir, runner), json)?;
+    Ok(())
+}
+
+fn load(dir: &Utf8Path, runner: &str) -> crate::Result<Option<LastRun>> {
+    let path = path_for(dir, runner);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let json = std::fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&json).ok())
+}
+
+#[cfg(test)]
+#[path = "run_tests.rs"]
+mod tests;
@@ -0,0 +1,430 @@
+//! Parsers for the reports of test runners.
+//!
+//! Every format is reduced to a flat list of [`Case`]s. Failures keep their
+//! message, location and output; passing tests keep only their name.
+
+use std::{collections::HashMap, sync::LazyLock};
+
+use fancy_regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::runner::Format;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Status {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// A single test, as reported by the runner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Case {
+    /// The name of the test, as accepted by the runner's test selection.
+    pub name: String,
+
+    /// The file, class, package or crate the test belongs to.
+    pub suite: Option<String>,
+
+    pub status: Status,
+
+    /// The `file:line` of the failure.
+    pub location: Option<String>,
+
+    /// The first line of the failure message.
+    pub message: Option<String>,
+
+    /// The full failure output.
+    pub output: String,
+
+    /// Whether the runner's test selection can run the test by its name.
+    ///
+    /// A jest test file that fails to load is reported by its path, which no
+    /// test name pattern matches.
+    pub selectable: bool,
+}
+
+impl Case {
+    fn new(name: impl Into<String>, suite: Option<String>, status: Status) -> Self {
+        Self {
+            name: name.into(),
+            suite,
+            status,
+            location: None,
+            message: None,
+            output: String::new(),
+            selectable: true,
+        }
+    }
+
+    /// Record the failure output, deriving the message and location from it
+    /// where the report does not provide them.
+    fn with_output(mut self, output: &str) -> Self {
+        let output = strip_ansi_escapes::strip_str(output).trim().to_owned();
+        if self.location.is_none() {
+            self.location = locate(&output);
+        }
+        if self.message.is_none() {
+            self.message = first_line(&output);
+        }
+        self.output = output;
+        self
+    }
+}
+
+/// Parse a report in the given format.
+pub(super) fn parse(format: Format, report: &str) -> Result<Vec<Case>, String> {
+    match format {
+        Format::Junit => junit(report),
+        Format::GoJson => Ok(go_json(report)),
+        Format::JestJson => jest_json(report),
+        Format::LibtestJson => Ok(libtest_json(report)),
+    }
+}
+
+/// A `<testsuites>` or `<testsuite>` element.
+#[derive(Debug, Deserialize)]
+struct JunitSuite {
+    #[serde(rename = "@name", default)]
+    name: Option<String>,
+
+    #[serde(rename = "testsuite", default)]
+    suites: Vec<JunitSuite>,
+
+    #[serde(rename = "testcase", default)]
+    cases: Vec<JunitCase>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JunitCase {
+    #[serde(rename = "@name")]
+    name: String,
+
+    #[serde(rename = "@classname", default)]
+    classname: Option<String>,
+
+    #[serde(rename = "@file", default)]
+    file: Option<String>,
+
+    #[serde(rename = "@line", default)]
+    line: Option<String>,
+
+    #[serde(rename = "failure", default)]
+    failures: Vec<JunitFailure>,
+
+    #[serde(rename = "error", default)]
+    errors: Vec<JunitFailure>,
+
+    #[serde(default)]
+    skipped: Option<JunitMarker>,
+
+    #[serde(rename = "system-out", default)]
+    system_out: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JunitFailure {
+    #[serde(rename = "@message", default)]
+    message: Option<String>,
+
+    #[serde(rename = "$text", default)]
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JunitMarker {}
+
+impl JunitCase {
+    /// The pytest node ID of the test, e.g. `tests/test_x.py::TestX::test_a`.
+    ///
+    /// The classname is the node ID with the file's path in dotted form, and
+    /// the `::` separators replaced by dots.
+    fn node_id(&self) -> Option<String> {
+        let file = self.file.as_deref()?;
+        let module = file.strip_suffix(".py")?.replace(['/', '\\'], ".");
+        let classes = self
+            .classname
+            .as_deref()
+            .and_then(|classname| classname.strip_prefix(&module))
+            .and_then(|classes| classes.strip_prefix('.'))
+            .map(|classes| classes.replace('.', "::"));
+
+        Some(match classes {
+            Some(classes) => format!("{file}::{classes}::{}", self.name),
+            None => format!("{file}::{}", self.name),
+        })
+    }
+}
+
+fn junit(report: &str) -> Result<Vec<Case>, String> {
+    fn collect(suite: JunitSuite, cases: &mut Vec<Case>) {
+        for case in suite.cases {
+            let suite = case.classname.clone().or_else(|| suite.name.clone());
+            let name = case.node_id().unwrap_or_else(|| case.name.clone());
+            let failure = case.failures.into_iter().chain(case.errors).next();
+
+            let Some(failure) = failure else {
+                let status = if case.skipped.is_some() {
+                    Status::Skipped
+                } else {
+                    Status::Passed
+                };
+                cases.push(Case::new(name, suite, status));
+                continue;
+            };
+
+            let mut output = failure.text;
+            if let Some(stdout) = case.system_out.filter(|s| !s.trim().is_empty()) {
+                output = format!("{output}\n\n{stdout}");
+            }
+
+            let mut failed = Case::new(name, suite, Status::Failed);
+            failed.message = failure.message.as_deref().and_then(first_line);
+            failed = failed.with_output(&output);
+            if failed.location.is_none() {
+                failed.location = case.file.map(|file| match case.line {
+                    Some(line) => format!("{file}:{line}"),
+                    None => file,
+                });
+            }
+            cases.push(failed);
+        }
+
+        for child in suite.suites {
+            collect(child, cases);
+        }
+    }
+
+    let suite: JunitSuite =
+        quick_xml::de::from_str(report).map_err(|e| format!("invalid JUnit report: {e}"))?;
+
+    let mut cases = vec![];
+    collect(suite, &mut cases);
+    Ok(cases)
+}
+
+/// A line of `go test -json` output.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GoEvent {
+    action: String,
+    #[serde(default)]
+    package: Option<String>,
+    #[serde(default)]
+    test: Option<String>,
+    #[serde(default)]
+    output: Option<String>,
+}
+
+fn go_json(report: &str) -> Vec<Case> {
+    let mut output: HashMap<(Option<String>, String), String> = HashMap::new();
+    let mut cases = vec![];
+
+    for event in report
+        .lines()
+        .filter_map(|line| serde_json::from_str::<GoEvent>(line).ok())
+    {
+        let Some(test) = event.test else {
+            continue;
+        };
+
+        let status = match event.action.as_str() {
+            "output" => {
+                let line = event.output.unwrap_or_default();
+                // The framing lines of the runner carry no information.
+                let trimmed = line.trim_start();
+                if !["=== ", "--- "].iter().any(|p| trimmed.starts_with(p)) {
+                    output
+                        .entry((event.package, test))
+                        .or_default()
+                        .push_str(&line);
+                }
+                continue;
+            }
+            "pass" => Status::Passed,
+            "fail" => Status::Failed,
+            "skip" => Status::Skipped,
+            _ => continue,
+        };
+
+        let text = output.remove(&(event.package.clone(), test.clone()));
+        let case = Case::new(test, event.package, status);
+        cases.push(match status {
+            Status::Failed => case.with_output(&text.unwrap_or_default()),
+            _ => case,
+        });
+    }
+
+    cases
+}
+
+/// The output of `jest --json`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JestReport {
+    #[serde(default)]
+    test_results: Vec<JestFile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JestFile {
+    name: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    assertion_results: Vec<JestAssertion>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JestAssertion {
+    full_name: String,
+    status: String,
+    #[serde(default)]
+    failure_messages: Vec<String>,
+    #[serde(default)]
+    location: Option<JestLocation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JestLocation {
+    line: u32,
+}
+
+fn jest_json(report: &str) -> Result<Vec<Case>, String> {
+    let report: JestReport =
+        serde_json::from_str(report).map_err(|e| format!("invalid jest report: {e}"))?;
+
+    let mut cases = vec![];
+    for file in report.test_results {
+        // A file that fails to load, e.g. on a syntax error, has no assertions.
+        if file.assertion_results.is_empty() && file.status == "failed" {
+            let mut case = Case::new(&file.name, None, Status::Failed);
+            case.selectable = false;
+            cases.push(case.with_output(&file.message));
+            continue;
+        }
+
+        for assertion in file.assertion_results {
+            let status = match assertion.status.as_str() {
+                "passed" => Status::Passed,
+                "failed" => Status::Failed,
+                _ => Status::Skipped,
+            };
+
+            let mut case = Case::new(assertion.full_name, Some(file.name.clone()), status);
+            if status != Status::Failed {
+                cases.push(case);
+                continue;
+            }
+
+            // The stack trace points at the failing assertion, which is more
+            // precise than the line the test starts at.
+            case = case.with_output(&assertion.failure_messages.join("\n\n"));
+            if case.location.is_none() {
+                case.location = assertion
+                    .location
+                    .map(|location| format!("{}:{}", file.name, location.line));
+            }
+            cases.push(case);
+        }
+    }
+
+    Ok(cases)
+}
+
+fn libtest_json(report: &str) -> Vec<Case> {
+    let mut cases = vec![];
+    for event in report
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+    {
+        let kind = event
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let name = event.get("name").and_then(Value::as_str);
+        let status = match event.get("event").and_then(Value::as_str) {
+            Some("ok") => Status::Passed,
+            Some("failed") => Status::Failed,
+            Some("ignored") => Status::Skipped,
+            _ => continue,
+        };
+
+        let (Some(name), "test") = (name, kind) else {
+            continue;
+        };
+
+        // nextest prefixes the name with the binary, e.g. `my_crate$tests::x`.
+        let (suite, name) = match name.split_once('$') {
+            Some((binary, name)) => (Some(binary.to_owned()), name),
+            None => (None, name),
+        };
+
+        let mut case = Case::new(name, suite, status);
+        if status == Status::Failed {
+            let stdout = event
+                .get("stdout")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+
+            // The panic message follows the `panicked at` line.
+            case.message = stdout
+                .lines()
+                .skip_while(|line| !line.contains("panicked at"))
+                .nth(1)
+                .and_then(first_line);
+            case = case.with_output(stdout);
+        }
+        cases.push(case);
+    }
+
+    cases
+}
+
+/// The first non-empty line of `text`.
+fn first_line(text: &str) -> Option<String> {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_owned)
+}
+
+/// Path fragments of dependencies, whose frames are skipped when locating a
+/// failure.
+const DEPENDENCY_PATHS: &[&str] = &[
+    "node_modules",
+    "site-packages",
+    "/rustc/",
+    ".cargo/registry",
+    "/go/src/",
+];
+
+/// The first `file:line` in `text` that points at project code.
+fn locate(text: &str) -> Option<String> {
+    static LOCATION: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"(?<![\w./\\:-])((?:[A-Za-z]:)?[/\\]?(?:[\w.-]+[/\\])*[\w.-]+\.[A-Za-z]\w*):(\d+)",
+        )
+        .expect("valid regex")
+    });
+
+    LOCATION
+        .captures_iter(text)
+        .filter_map(Result::ok)
+        .map(|captures| (captures[1].to_owned(), captures[2].to_owned()))
+        .find(|(file, _)| {
+            !DEPENDENCY_PATHS
+                .iter()
+                .any(|dependency| file.contains(dependency))
+        })
+        .map(|(file, line)| format!("{file}:{line}"))
+}
+
+#[cfg(test)]
+#[path = "report_tests.rs"]
+mod tests;
@@ -0,0 +1,184 @@
+use indoc::indoc;
+
+use super::*;
+
+#[test]
+fn test_junit_pytest_report() {
+    let report = indoc! {r#"
+        <?xml version="1.0" encoding="utf-8"?>
+        <testsuites>
+          <testsuite name="pytest" tests="3" failures="1" skipped="1">
+            <testcase classname="tests.test_math" name="test_add" file="tests/test_math.py" line="3" />
+            <testcase classname="tests.test_math" name="test_div" file="tests/test_math.py" line="7">
+              <failure message="assert 1 == 2">def test_div():
+        &gt;       assert 1 == 2
+        E       assert 1 == 2
+
+        tests/test_math.py:9: AssertionError</failure>
+            </testcase>
+            <testcase classname="tests.test_math" name="test_skip">
+              <skipped type="pytest.skip" message="not now" />
+            </testcase>
+          </testsuite>
+        </testsuites>
+    "#};
+
+    let cases = parse(Format::Junit, report).unwrap();
+    let statuses: Vec<_> = cases.iter().map(|c| c.status).collect();
+    assert_eq!(statuses, [Status::Passed, Status::Failed, Status::Skipped]);
+
+    let failed = &cases[1];
+    assert_eq!(failed.name, "tests/test_math.py::test_div");
+    assert_eq!(failed.suite.as_deref(), Some("tests.test_math"));
+    assert_eq!(failed.message.as_deref(), Some("assert 1 == 2"));
+    assert_eq!(failed.location.as_deref(), Some("tests/test_math.py:9"));
+}
+
+#[test]
+fn test_junit_pytest_node_ids() {
+    let report = indoc! {r#"
+        <testsuite name="pytest">
+          <testcase classname="tests.test_math.TestX.TestY" name="test_add[1-2]" file="tests/test_math.py" line="3" />
+          <testcase classname="other" name="test_sub" file="tests/test_math.py" line="9" />
+          <testcase classname="tests.test_math" name="test_mul" />
+        </testsuite>
+    "#};
+
+    let names: Vec<_> = parse(Format::Junit, report)
+        .unwrap()
+        .into_iter()
+        .map(|case| case.name)
+        .collect();
+
+    assert_eq!(names, [
+        "tests/test_math.py::TestX::TestY::test_add[1-2]",
+        "tests/test_math.py::test_sub",
+        "test_mul",
+    ]);
+}
+
+#[test]
+fn test_junit_single_suite_falls_back_to_file_attribute() {
+    let report = indoc! {r#"
+        <testsuite name="unit">
+          <testcase name="renders" file="spec/view_spec.rb" line="12">
+            <error message="boom">RuntimeError</error>
+          </testcase>
+        </testsuite>
+    "#};
+
+    let cases = parse(Format::Junit, report).unwrap();
+    assert_eq!(cases.len(), 1);
+    assert_eq!(cases[0].suite.as_deref(), Some("unit"));
+    assert_eq!(cases[0].location.as_deref(), Some("spec/view_spec.rb:12"));
+    assert_eq!(cases[0].message.as_deref(), Some("boom"));
+}
+
+#[test]
+fn test_go_json() {
+    let report = indoc! {r#"
+        {"Action":"run","Package":"example.com/m","Test":"TestAdd"}
+        {"Action":"output","Package":"example.com/m","Test":"TestAdd","Output":"=== RUN   TestAdd\n"}
+        {"Action":"pass","Package":"example.com/m","Test":"TestAdd","Elapsed":0}
+        {"Action":"run","Package":"example.com/m","Test":"TestSub"}
+        {"Action":"output","Package":"example.com/m","Test":"TestSub","Output":"=== RUN   TestSub\n"}
+        {"Action":"output","Package":"example.com/m","Test":"TestSub","Output":"    math_test.go:14: got 1, want 2\n"}
+        {"Action":"output","Package":"example.com/m","Test":"TestSub","Output":"--- FAIL: TestSub (0.00s)\n"}
+        {"Action":"fail","Package":"example.com/m","Test":"TestSub","Elapsed":0}
+        {"Action":"fail","Package":"example.com/m","Elapsed":0.1}
+    "#};
+
+    let cases = parse(Format::GoJson, report).unwrap();
+    assert_eq!(cases.len(), 2);
+    assert_eq!(cases[0].status, Status::Passed);
+
+    let failed = &cases[1];
+    assert_eq!(failed.name, "TestSub");
+    assert_eq!(failed.suite.as_deref(), Some("example.com/m"));
+    assert_eq!(failed.location.as_deref(), Some("math_test.go:14"));
+    assert_eq!(
+        failed.message.as_deref(),
+        Some("math_test.go:14: got 1, want 2")
+    );
+    assert_eq!(failed.output, "math_test.go:14: got 1, want 2");
+}
+
+#[test]
+fn test_jest_json() {
+    let report = r#"{
+        "numTotalTests": 2,
+        "testResults": [
+            {
+                "name": "/repo/src/sum.test.js",
+                "status": "failed",
+                "message": "",
+                "assertionResults": [
+                    { "fullName": "sum adds", "status": "passed", "failureMessages": [] },
+                    {
+                        "fullName": "sum subtracts",
+                        "status": "failed",
+                        "failureMessages": [
+                            "Error: expect(received).toBe(expected)\n\nExpected: 2\nReceived: 1\n    at Object.<anonymous> (/repo/node_modules/expect/build/index.js:30:1)\n    at Object.<anonymous> (/repo/src/sum.test.js:9:17)"
+                        ],
+                        "location": { "line": 8, "column": 3 }
+                    }
+                ]
+            },
+            {
+                "name": "/repo/src/broken.test.js",
+                "status": "failed",
+                "message": "SyntaxError: Unexpected token (3:4)",
+                "assertionResults": []
+            }
+        ]
+    }"#;
+
+    let cases = parse(Format::JestJson, report).unwrap();
+    assert_eq!(cases.len(), 3);
+
+    let failed = &cases[1];
+    assert_eq!(failed.name, "sum subtracts");
+    assert_eq!(failed.location.as_deref(), Some("/repo/src/sum.test.js:9"));
+    assert_eq!(
+        failed.message.as_deref(),
+        Some("Error: expect(received).toBe(expected)")
+    );
+
+    assert_eq!(cases[2].name, "/repo/src/broken.test.js");
+    assert_eq!(cases[2].status, Status::Failed);
+    assert!(cases[1].selectable);
+    assert!(!cases[2].selectable);
+}
+
+#[test]
+fn test_libtest_json() {
+    let report = indoc! {r#"
+        {"type":"suite","event":"started","test_count":2}
+        {"type":"test","event":"started","name":"my_crate$tests::ok"}
+        {"type":"test","event":"ok","name":"my_crate$tests::ok"}
+        {"type":"test","event":"failed","name":"my_crate$tests::bad","stdout":"\nthread 'tests::bad' panicked at src/lib.rs:10:5:\nassertion `left == right` failed\n  left: 1\n right: 2\n"}
+        {"type":"test","event":"ignored","name":"my_crate$tests::slow"}
+    "#};
+
+    let cases = parse(Format::LibtestJson, report).unwrap();
+    let statuses: Vec<_> = cases.iter().map(|c| c.status).collect();
+    assert_eq!(statuses, [Status::Passed, Status::Failed, Status::Skipped]);
+
+    let failed = &cases[1];
+    assert_eq!(failed.name, "tests::bad");
+    assert_eq!(failed.suite.as_deref(), Some("my_crate"));
+    assert_eq!(failed.location.as_deref(), Some("src/lib.rs:10"));
+    assert_eq!(
+        failed.message.as_deref(),
+        Some("assertion `left == right` failed")
+    );
+}
+
+#[test]
+fn test_locate_skips_dependencies_and_urls() {
+    assert_eq!(locate("see https://example.com:8080/x"), None);
+    assert_eq!(
+        locate("at /home/u/.cargo/registry/src/x.rs:1\nat src/main.rs:4:2").as_deref(),
+        Some("src/main.rs:4")
+    );
+}
@@ -0,0 +1,320 @@
+//! Parsing for the `test_run` tool's `options` map.
+//!
+//! See `.jp/mcp/tools/test/run.toml` for the user-facing schema.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::Error;
+
+/// The runners available without configuration.
+const PRESETS: &[&str] = &["go", "jest", "nextest", "pytest"];
+
+/// The placeholder replaced with the path of the report file.
+pub(super) const REPORT: &str = "{report}";
+
+/// The format of the report a runner produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum Format {
+    /// A JUnit XML report.
+    Junit,
+    /// The output of `go test -json`.
+    GoJson,
+    /// The output of `jest --json`.
+    JestJson,
+    /// libtest's JSON output, as printed by `cargo nextest`.
+    LibtestJson,
+}
+
+/// How a runner runs specific tests.
+///
+/// The names of the tests are formatted with `item`, joined with `separator`,
+/// and substituted for `{tests}` in `args`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Select {
+    pub args: Vec<String>,
+
+    #[serde(default = "default_separator")]
+    pub separator: String,
+
+    /// The format of a single test, `{name}` is replaced with its name.
+    #[serde(default = "default_item")]
+    pub item: String,
+
+    /// Escape the names of the tests as regular expressions.
+    #[serde(default)]
+    pub regex: bool,
+
+    /// Pass each test as a separate argument, instead of joining them.
+    #[serde(default)]
+    pub split: bool,
+}
+
+fn default_separator() -> String {
+    " ".to_owned()
+}
+
+fn default_item() -> String {
+    "{name}".to_owned()
+}
+
+impl Select {
+    /// The arguments that run the given tests.
+    pub(super) fn args(&self, tests: &[String]) -> Vec<String> {
+        let tests = tests
+            .iter()
+            .map(|name| {
+                let name = if self.regex {
+                    escape_regex(name)
+                } else {
+                    name.clone()
+                };
+
+                self.item.replace("{name}", &name)
+            })
+            .collect::<Vec<_>>();
+
+        let joined = tests.join(&self.separator);
+        self.args
+            .iter()
+            .flat_map(|arg| {
+                if self.split && arg.contains("{tests}") {
+                    tests
+                        .iter()
+                        .map(|test| arg.replace("{tests}", test))
+                        .collect::<Vec<_>>()
+                } else {
+                    vec![arg.replace("{tests}", &joined)]
+                }
+            })
+            .collect()
+    }
+}
+
+fn escape_regex(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// A resolved test runner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Runner {
+    pub name: String,
+
+    /// The program and its arguments.
+    ///
+    /// An argument containing `{report}` makes the runner write its report to
+    /// a file, otherwise the report is read from stdout.
+    pub command: Vec<String>,
+
+    pub format: Format,
+
+    /// Environment variables set for the command.
+    pub env: BTreeMap<String, String>,
+
+    pub select: Option<Select>,
+}
+
+impl Runner {
+    /// Whether the runner writes its report to a file.
+    pub(super) fn writes_report(&self) -> bool {
+        self.command.iter().any(|arg| arg.contains(REPORT))
+    }
+}
+
+/// A runner as configured, with every field optional so that a configured
+/// runner can override parts of a preset.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RunnerConfig {
+    #[serde(default)]
+    command: Option<Vec<String>>,
+
+    #[serde(default)]
+    format: Option<Format>,
+
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+
+    #[serde(default)]
+    select: Option<Select>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct TestRunOptions {
+    /// The runner used when the call does not name one.
+    #[serde(default)]
+    runner: Option<String>,
+
+    /// Configured runners, by name.
+    #[serde(default)]
+    runners: BTreeMap<String, RunnerConfig>,
+}
+
+impl TestRunOptions {
+    pub(super) fn parse(options: &Map<String, Value>) -> Result<Self, Error> {
+        serde_json::from_value(Value::Object(options.clone()))
+            .map_err(|e| format!("invalid test_run options: {e}").into())
+    }
+
+    /// Resolve the runner with the given name, or the default runner.
+    pub(super) fn runner(&self, name: Option<&str>) -> Result<Runner, String> {
+        let name = match name.or(self.runner.as_deref()) {
+            Some(name) => name,
+            None if self.runners.len() == 1 => {
+                self.runners.keys().next().expect("one runner").as_str()
+            }
+            None => {
+                return Err(format!(
+                    "No test runner selected. Set `runner` to one of: {}.",
+                    self.names().join(", ")
+                ));
+            }
+        };
+
+        if !is_valid_name(name) {
+            return Err(format!(
+                "Invalid runner name `{name}`. Runner names may only contain ASCII letters, \
+                 digits, `-` and `_`."
+            ));
+        }
+
+        let config = self.runners.get(name);
+        let Some(mut runner) = preset(name).or_else(|| {
+            let config = config?;
+            Some(Runner {
+                name: name.to_owned(),
+                command: config.command.clone()?,
+                format: config.format?,
+                env: BTreeMap::new(),
+                select: None,
+            })
+        }) else {
+            return Err(match config {
+                Some(_) => format!("The `{name}` runner must set both `command` and `format`."),
+                None => format!(
+                    "Unknown test runner `{name}`. Available runners: {}.",
+                    self.names().join(", ")
+                ),
+            });
+        };
+
+        if let Some(config) = config {
+            if let Some(command) = &config.command {
+                runner.command.clone_from(command);
+            }
+            if let Some(format) = config.format {
+                runner.format = format;
+            }
+            if let Some(select) = &config.select {
+                runner.select = Some(select.clone());
+            }
+            runner.env.extend(config.env.clone());
+        }
+
+        if runner.command.is_empty() {
+            return Err(format!("The `{name}` runner has an empty `command`."));
+        }
+
+        Ok(runner)
+    }
+
+    /// The names of all available runners.
+    fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = PRESETS.iter().map(|&name| name.to_owned()).collect();
+        names.extend(self.runners.keys().cloned());
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+/// Runner names are used as file names for the state of the tool.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The built-in runner with the given name.
+fn preset(name: &str) -> Option<Runner> {
+    let strings = |args: &[&str]| args.iter().map(|&arg| arg.to_owned()).collect::<Vec<_>>();
+    let select = |args: &[&str], separator: &str, item: &str, regex: bool| Select {
+        args: strings(args),
+        separator: separator.to_owned(),
+        item: item.to_owned(),
+        regex,
+        split: false,
+    };
+
+    let (command, format, env, select) = match name {
+        // The `xunit1` report records the file of each test, from which its
+        // node ID is derived. Node IDs are selected as positional arguments.
+        "pytest" => (
+            strings(&["pytest", "--junitxml={report}", "-o", "junit_family=xunit1"]),
+            Format::Junit,
+            vec![],
+            Select {
+                split: true,
+                ..select(&["{tests}"], " ", "{name}", false)
+            },
+        ),
+        "go" => (
+            strings(&["go", "test", "-json", "./..."]),
+            Format::GoJson,
+            vec![],
+            select(&["-run", "^({tests})$"], "|", "{name}", true),
+        ),
+        "jest" => (
+            strings(&["npx", "jest", "--json", "--outputFile={report}"]),
+            Format::JestJson,
+            vec![],
+            select(&["--testNamePattern", "^({tests})$"], "|", "{name}", true),
+        ),
+        "nextest" => (
+            strings(&[
+                "cargo",
+                "nextest",
+                "run",
+                "--no-fail-fast",
+                "--cargo-quiet",
+                "--hide-progress-bar",
+                "--final-status-level=none",
+                "--status-level=fail",
+                "--message-format=libtest-json-plus",
+            ]),
+            Format::LibtestJson,
+            vec![("NEXTEST_EXPERIMENTAL_LIBTEST_JSON", "1")],
+            select(&["-E", "{tests}"], " | ", "test(={name})", false),
+        ),
+        _ => return None,
+    };
+
+    Some(Runner {
+        name: name.to_owned(),
+        command,
+        format,
+        env: env
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect(),
+        select: Some(select),
+    })
+}
+
+#[cfg(test)]
+#[path = "runner_tests.rs"]
+mod tests;
@@ -0,0 +1,98 @@
+use serde_json::json;
+
+use super::*;
+
+fn parse(value: Value) -> TestRunOptions {
+    let Value::Object(map) = value else {
+        panic!("options must be an object");
+    };
+
+    TestRunOptions::parse(&map).unwrap()
+}
+
+#[test]
+fn test_requires_a_runner_when_ambiguous() {
+    let options = parse(json!({}));
+
+    let error = options.runner(None).unwrap_err();
+    assert!(error.contains("go, jest, nextest, pytest"), "{error}");
+}
+
+#[test]
+fn test_default_runner() {
+    let options = parse(json!({ "runner": "pytest" }));
+
+    let runner = options.runner(None).unwrap();
+    assert_eq!(runner.format, Format::Junit);
+    assert!(runner.writes_report());
+
+    // The call overrides the default.
+    let runner = options.runner(Some("go")).unwrap();
+    assert_eq!(runner.format, Format::GoJson);
+    assert!(!runner.writes_report());
+}
+
+#[test]
+fn test_configured_runner_overrides_preset() {
+    let options = parse(json!({
+        "runners": {
+            "pytest": { "command": ["uv", "run", "pytest", "--junitxml={report}"] }
+        }
+    }));
+
+    // The only configured runner is the default.
+    let runner = options.runner(None).unwrap();
+    assert_eq!(runner.command[..2], ["uv", "run"]);
+    assert_eq!(runner.format, Format::Junit);
+    assert!(runner.select.is_some());
+}
+
+#[test]
+fn test_custom_runner_requires_command_and_format() {
+    let options = parse(json!({
+        "runners": {
+            "e2e": { "command": ["./e2e.sh"] },
+            "unit": { "command": ["./unit.sh", "{report}"], "format": "junit" }
+        }
+    }));
+
+    assert!(
+        options
+            .runner(Some("e2e"))
+            .unwrap_err()
+            .contains("`format`")
+    );
+
+    let runner = options.runner(Some("unit")).unwrap();
+    assert_eq!(runner.select, None);
+    assert!(options.runner(Some("nope")).unwrap_err().contains("e2e"));
+    assert!(options.runner(Some("../x")).is_err());
+}
+
+#[test]
+fn test_select_args() {
+    let go = preset("go").unwrap().select.unwrap();
+    assert_eq!(go.args(&["TestA".to_owned(), "TestB.c".to_owned()]), [
+        "-run",
+        r"^(TestA|TestB\.c)$"
+    ]);
+
+    let nextest = preset("nextest").unwrap().select.unwrap();
+    assert_eq!(nextest.args(&["a::b".to_owned(), "c".to_owned()]), [
+        "-E",
+        "test(=a::b) | test(=c)"
+    ]);
+
+    // Node IDs are passed as separate arguments, brackets and all.
+    let pytest = preset("pytest").unwrap().select.unwrap();
+    assert_eq!(
+        pytest.args(&[
+            "tests/test_math.py::test_add[1-2]".to_owned(),
+            "tests/test_math.py::TestX::test_a".to_owned()
+        ]),
+        [
+            "tests/test_math.py::test_add[1-2]",
+            "tests/test_math.py::TestX::test_a"
+        ]
+    );
+}
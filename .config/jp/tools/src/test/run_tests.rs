@@ -0,0 +1,207 @@
+use camino_tempfile::tempdir;
+use jp_tool::Outcome;
+use serde_json::json;
+
+use super::*;
+use crate::util::runner::MockProcessRunner;
+
+fn runner(name: &str) -> Runner {
+    let Value::Object(options) = json!({}) else {
+        unreachable!()
+    };
+
+    TestRunOptions::parse(&options)
+        .unwrap()
+        .runner(Some(name))
+        .unwrap()
+}
+
+fn error_message(outcome: Outcome) -> String {
+    let Outcome::Error { message, .. } = outcome else {
+        panic!("expected an error outcome");
+    };
+
+    message
+}
+
+fn request() -> TestRun {
+    TestRun {
+        runner: None,
+        tests: vec![],
+        failed: false,
+        args: vec![],
+    }
+}
+
+const GO_REPORT: &str = r#"{"Action":"pass","Package":"m","Test":"TestAdd"}
+{"Action":"output","Package":"m","Test":"TestSub","Output":"    math_test.go:14: got 1, want 2\n"}
+{"Action":"fail","Package":"m","Test":"TestSub"}
+{"Action":"skip","Package":"m","Test":"TestSlow"}"#;
+
+#[test]
+fn test_reports_failures_and_counts_passes() {
+    let dir = tempdir().unwrap();
+    let process = MockProcessRunner::builder()
+        .expect("go")
+        .args(&["test", "-json", "./..."])
+        .returns_success(GO_REPORT);
+
+    let result = test_run_impl(dir.path(), dir.path(), &runner("go"), request(), &process)
+        .unwrap()
+        .into_content()
+        .unwrap();
+
+    assert_eq!(result, indoc::indoc! {"
+        Ran 2 tests: 1 passed, 1 failed, 1 skipped.
+
+        What follows is an XML representation of the failed tests:
+
+        ```xml
+        <results>
+            <test_failure>
+                <name>TestSub</name>
+                <suite>m</suite>
+                <location>math_test.go:14</location>
+                <message>math_test.go:14: got 1, want 2</message>
+            </test_failure>
+        </results>
+        ```
+
+        Set `failed` to re-run only the failing tests."});
+}
+
+#[test]
+fn test_reruns_only_failed_tests() {
+    let dir = tempdir().unwrap();
+    let process = MockProcessRunner::builder()
+        .expect("go")
+        .args(&["test", "-json", "./..."])
+        .returns_success(GO_REPORT)
+        .expect("go")
+        .args(&["test", "-json", "./...", "-run", "^(TestSub)$"])
+        .returns_success(r#"{"Action":"pass","Package":"m","Test":"TestSub"}"#);
+
+    let go = runner("go");
+    test_run_impl(dir.path(), dir.path(), &go, request(), &process).unwrap();
+
+    let rerun = TestRun {
+        failed: true,
+        ..request()
+    };
+    let result = test_run_impl(dir.path(), dir.path(), &go, rerun, &process)
+        .unwrap()
+        .into_content()
+        .unwrap();
+    assert_eq!(result, "Ran 1 tests: 1 passed, 0 failed, 0 skipped.\n");
+
+    // Nothing is left to re-run.
+    let rerun = TestRun {
+        failed: true,
+        ..request()
+    };
+    let result = test_run_impl(dir.path(), dir.path(), &go, rerun, &process).unwrap();
+    assert!(error_message(result).contains("no failing tests"));
+}
+
+#[test]
+fn test_reruns_everything_when_a_failure_cannot_be_selected() {
+    let report = r#"{"testResults": [
+        {"name": "/repo/src/sum.test.js", "status": "failed", "assertionResults": [
+            {"fullName": "sum subtracts", "status": "failed", "failureMessages": ["Error"]}
+        ]},
+        {"name": "/repo/src/broken.test.js", "status": "failed", "message": "SyntaxError"}
+    ]}"#;
+
+    let dir = tempdir().unwrap();
+    let process = MockProcessRunner::builder()
+        .expect("npx")
+        .args(&["jest", "--json"])
+        .returns_success(report)
+        .expect("npx")
+        .args(&["jest", "--json"])
+        .returns_success(report);
+
+    // Read the report from stdout, which the mock can provide.
+    let mut jest = runner("jest");
+    jest.command.truncate(3);
+
+    test_run_impl(dir.path(), dir.path(), &jest, request(), &process).unwrap();
+
+    // The file that failed to load has no test name to select, so the
+    // re-run selects nothing.
+    let rerun = TestRun {
+        failed: true,
+        ..request()
+    };
+    let result = test_run_impl(dir.path(), dir.path(), &jest, rerun, &process)
+        .unwrap()
+        .into_content()
+        .unwrap();
+    assert!(
+        result.starts_with("Ran 2 tests: 0 passed, 2 failed"),
+        "{result}"
+    );
+}
+
+#[test]
+fn test_points_runner_at_report_file() {
+    let dir = tempdir().unwrap();
+    let report = dir.path().join("pytest.report");
+
+    // A report left over from an earlier run is not mistaken for this one.
+    std::fs::write(
+        &report,
+        r#"<testsuite><testcase name="stale" /></testsuite>"#,
+    )
+    .unwrap();
+
+    let process = MockProcessRunner::builder()
+        .expect("pytest")
+        .args(&[
+            &format!("--junitxml={report}"),
+            "-o",
+            "junit_family=xunit1",
+            "tests/",
+        ])
+        .returns_success("noise");
+
+    let result = test_run_impl(
+        dir.path(),
+        dir.path(),
+        &runner("pytest"),
+        TestRun {
+            args: vec!["tests/".to_owned()],
+            ..request()
+        },
+        &process,
+    );
+
+    // The mock writes no report, so no tests are found.
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Unable to run any tests")
+    );
+}
+
+#[test]
+fn test_runner_without_select_rejects_tests() {
+    let dir = tempdir().unwrap();
+    let mut custom = runner("pytest");
+    custom.select = None;
+
+    let result = test_run_impl(
+        dir.path(),
+        dir.path(),
+        &custom,
+        TestRun {
+            tests: vec!["test_a".to_owned()],
+            ..request()
+        },
+        &MockProcessRunner::never_called(),
+    )
+    .unwrap();
+
+    assert!(error_message(result).contains("`select`"));
+}
@@ -53,6 +53,7 @@ fn dispatch(dir: &Utf8TempDir, action: Action, name: &str, args: Value) -> ToolR
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
     let arguments = match args {
         Value::Object(map) => map,
@@ -18,6 +18,7 @@ fn ctx() -> (camino_tempfile::Utf8TempDir, Context) {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
     (dir, ctx)
 }
@@ -4,10 +4,12 @@ use crate::{
 };
 
 mod fetch;
+mod search;
 
 use fetch::web_fetch;
+use search::web_search;
 
-pub async fn run(_: Context, t: Tool) -> ToolResult {
+pub async fn run(ctx: Context, t: Tool) -> ToolResult {
     match t.name.trim_start_matches("web_") {
         "fetch" => {
             web_fetch(
@@ -18,6 +20,15 @@ pub async fn run(_: Context, t: Tool) -> ToolResult {
             )
             .await
         }
+        "search" => {
+            web_search(
+                &ctx,
+                &t.req::<String>("query")?,
+                t.opt("limit")?,
+                &t.options,
+            )
+            .await
+        }
         _ => unknown_tool(t),
     }
 }
@@ -9,7 +9,7 @@ use url::Url;
 
 use crate::util::{ToolResult, error};
 
-mod html;
+pub(super) mod html;
 mod markdown;
 mod options;
 
@@ -114,7 +114,7 @@ struct SectionHeader {
     preview: String,
 }
 
-fn html_to_markdown(html: &str) -> Result<String, Error> {
+pub(crate) fn html_to_markdown(html: &str) -> Result<String, Error> {
     let converter = HtmlToMarkdown::builder()
         .skip_tags(vec!["script", "style", "noscript", "svg", "iframe"])
         .build();
@@ -809,6 +809,15 @@ fn truncate_str(s: &str, max: usize) -> String {
     format!("{}...", &s[..end])
 }
 
+/// Convert an HTML fragment, such as a search result snippet, to a single line
+/// of markdown of at most `max` bytes.
+pub(crate) fn snippet(html: &str, max: usize) -> String {
+    let md = html_to_markdown(html).unwrap_or_else(|_| html.to_owned());
+    let line = md.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    truncate_str(&line, max)
+}
+
 /// Extract multiple sections by anchor ID and combine into one HTML document.
 fn extract_sections(html: &str, ids: &[String]) -> String {
     let doc = Html::parse_document(html);
@@ -1232,3 +1232,21 @@ mod wrapped_headings {
         assert!(!result.contains("Next text"));
     }
 }
+
+mod snippet {
+    use super::*;
+
+    #[test]
+    fn flattens_markup_to_a_single_line() {
+        let html = "The <b>Rust</b>\n  programming language.";
+        assert_eq!(snippet(html, 100), "The **Rust** programming language.");
+    }
+
+    #[test]
+    fn truncates_long_snippets() {
+        let html = format!("<p>{}</p>", "word ".repeat(50));
+        let snippet = snippet(&html, 20);
+
+        assert_eq!(snippet, "word word word word ...");
+    }
+}
@@ -0,0 +1,208 @@
+//! The `web_search` tool.
+//!
+//! Queries the configured search backend, drops duplicate results and results
+//! from hosts the rules exclude, and renders what remains as a markdown list.
+
+use std::collections::HashSet;
+
+use jp_tool::NetRule;
+use reqwest::StatusCode;
+use serde_json::{Map, Value};
+use url::Url;
+
+use super::fetch::{html::snippet, http_client};
+use crate::{
+    Context,
+    util::{ToolResult, error},
+};
+
+mod backend;
+mod options;
+mod rules;
+
+use backend::Hit;
+use options::WebSearchOptions;
+
+/// The most results a single call may ask for.
+const MAX_LIMIT: usize = 50;
+
+/// The maximum length of a result snippet, in bytes.
+const SNIPPET_MAX: usize = 300;
+
+pub(crate) async fn web_search(
+    ctx: &Context,
+    query: &str,
+    limit: Option<usize>,
+    options: &Map<String, Value>,
+) -> ToolResult {
+    let options = WebSearchOptions::parse(options)?;
+    let limit = limit.unwrap_or(options.max_results).clamp(1, MAX_LIMIT);
+
+    if query.trim().is_empty() {
+        return error("The search query must not be empty.");
+    }
+
+    let endpoint = match options.endpoint() {
+        Ok(endpoint) => endpoint,
+        Err(msg) => return error(msg),
+    };
+
+    let net = ctx
+        .access
+        .as_ref()
+        .map(|a| a.net.as_slice())
+        .unwrap_or_default();
+    if !net.is_empty() && rules::evaluate(net, &endpoint) != Some(true) {
+        return error(format!(
+            "Access denied: cannot connect to '{}'. If required, ask the user to grant network \
+             access to the search backend.",
+            endpoint.host_str().unwrap_or_default()
+        ));
+    }
+
+    let api_key = match options.api_key() {
+        Ok(key) => key,
+        Err(msg) => return error(msg),
+    };
+
+    // Ask for more than needed, as some results are dropped below.
+    let response = backend::request(
+        &http_client(),
+        options.backend,
+        endpoint,
+        api_key.as_deref(),
+        query,
+        (limit * 2).min(MAX_LIMIT),
+    )
+    .send()
+    .await?;
+
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        return error(status_message(options.backend.as_str(), status, &body));
+    }
+
+    let hits = backend::parse(options.backend, &body)?;
+    let total = hits.len();
+    let hits = dedup(hits);
+    let duplicates = total - hits.len();
+
+    let (hits, excluded): (Vec<_>, Vec<_>) = hits
+        .into_iter()
+        .partition(|hit| permitted(net, &options.hosts, &hit.url));
+
+    Ok(render(
+        query,
+        &hits[..limit.min(hits.len())],
+        excluded.len(),
+        duplicates,
+    )
+    .into())
+}
+
+/// Whether a result may be returned.
+///
+/// The access policy's network rules deny every host they do not allow.
+/// The tool's own `hosts` rules only do so if at least one of them allows a
+/// host, so that a list of excluded sites keeps every other site.
+fn permitted(net: &[NetRule], hosts: &[NetRule], url: &Url) -> bool {
+    if !net.is_empty() && rules::evaluate(net, url) != Some(true) {
+        return false;
+    }
+
+    match rules::evaluate(hosts, url) {
+        Some(allow) => allow,
+        None => !hosts.iter().any(|rule| rule.allow),
+    }
+}
+
+/// Remove results that point at the same page, keeping the first.
+fn dedup(hits: Vec<Hit>) -> Vec<Hit> {
+    let mut seen = HashSet::new();
+    hits.into_iter()
+        .filter(|hit| seen.insert(dedup_key(&hit.url)))
+        .collect()
+}
+
+/// A key equal for URLs that point at the same page.
+///
+/// Ignores the scheme, a `www.` prefix, trailing slashes, fragments and
+/// `utm_*` tracking parameters.
+fn dedup_key(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let path = url.path().trim_end_matches('/');
+
+    let query = url
+        .query_pairs()
+        .filter(|(key, _)| !key.starts_with("utm_"))
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    format!("{host}{path}?{query}")
+}
+
+fn render(query: &str, hits: &[Hit], excluded: usize, duplicates: usize) -> String {
+    if hits.is_empty() {
+        let mut out = format!("No results found for '{query}'.");
+        if excluded > 0 {
+            out.push_str(&format!(
+                " {excluded} result(s) were left out by the configured host rules."
+            ));
+        }
+        return out;
+    }
+
+    let mut out = String::new();
+    for (i, hit) in hits.iter().enumerate() {
+        let title = match hit.title.trim() {
+            "" => hit.url.as_str(),
+            title => title,
+        };
+
+        out.push_str(&format!("{}. [{title}]({})\n", i + 1, hit.url));
+
+        let snippet = snippet(&hit.snippet, SNIPPET_MAX);
+        if !snippet.is_empty() {
+            out.push_str(&format!("   {snippet}\n"));
+        }
+        out.push('\n');
+    }
+
+    if duplicates > 0 {
+        out.push_str(&format!("{duplicates} duplicate result(s) were merged.\n"));
+    }
+
+    if excluded > 0 {
+        out.push_str(&format!(
+            "{excluded} result(s) were left out by the configured host rules.\n"
+        ));
+    }
+
+    out.trim_end().to_owned()
+}
+
+fn status_message(backend: &str, status: StatusCode, body: &str) -> String {
+    let mut msg = format!("The `{backend}` search backend responded with {status}.");
+
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            msg.push_str(" Check that its API key is valid.");
+        }
+        StatusCode::TOO_MANY_REQUESTS => msg.push_str(" Wait before searching again."),
+        _ => {
+            let body = body.trim();
+            if !body.is_empty() {
+                msg.push_str(&format!("\n\n{}", super::fetch::truncate(body, 1_000)));
+            }
+        }
+    }
+
+    msg
+}
+
+#[cfg(test)]
+#[path = "search_tests.rs"]
+mod tests;
@@ -0,0 +1,153 @@
+//! Requests to, and responses from, the supported search backends.
+
+use reqwest::{RequestBuilder, header::ACCEPT};
+use scraper::{Html, Selector};
+use serde_json::Value;
+use url::Url;
+
+use super::options::Backend;
+use crate::Error;
+
+/// A single search result, as returned by a backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Hit {
+    pub title: String,
+    pub url: Url,
+
+    /// The snippet, as HTML or plain text.
+    pub snippet: String,
+}
+
+/// Build the search request for `query`, asking for up to `count` results
+/// where the backend supports it.
+pub(super) fn request(
+    client: &reqwest::Client,
+    backend: Backend,
+    endpoint: Url,
+    api_key: Option<&str>,
+    query: &str,
+    count: usize,
+) -> RequestBuilder {
+    let count = count.to_string();
+    let key = api_key.unwrap_or_default();
+
+    match backend {
+        Backend::Searxng => client
+            .get(endpoint)
+            .query(&[("q", query), ("format", "json")]),
+        Backend::Brave => client
+            .get(endpoint)
+            .header(ACCEPT, "application/json")
+            .header("X-Subscription-Token", key)
+            .query(&[("q", query), ("count", &count)]),
+        Backend::Kagi => client
+            .get(endpoint)
+            .header("Authorization", format!("Bot {key}"))
+            .query(&[("q", query), ("limit", &count)]),
+        Backend::Duckduckgo => client.get(endpoint).query(&[("q", query)]),
+    }
+}
+
+/// Parse the results out of a backend's response body.
+pub(super) fn parse(backend: Backend, body: &str) -> Result<Vec<Hit>, Error> {
+    match backend {
+        Backend::Searxng => parse_json(body, "/results", "content"),
+        Backend::Brave => parse_json(body, "/web/results", "description"),
+        Backend::Kagi => parse_kagi(body),
+        Backend::Duckduckgo => Ok(parse_duckduckgo(body)),
+    }
+}
+
+/// Parse a JSON array of `{ url, title, <snippet_key> }` objects at `pointer`.
+fn parse_json(body: &str, pointer: &str, snippet_key: &str) -> Result<Vec<Hit>, Error> {
+    let value: Value = serde_json::from_str(body)?;
+    let hits = value
+        .pointer(pointer)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|result| hit(result, snippet_key))
+        .collect();
+
+    Ok(hits)
+}
+
+/// Kagi mixes search results (`t: 0`) with related searches (`t: 1`).
+fn parse_kagi(body: &str) -> Result<Vec<Hit>, Error> {
+    let value: Value = serde_json::from_str(body)?;
+    if let Some(message) = value.pointer("/error/0/msg").and_then(Value::as_str) {
+        return Err(format!("Kagi returned an error: {message}").into());
+    }
+
+    let hits = value
+        .get("data")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|item| item.get("t").and_then(Value::as_u64) == Some(0))
+        .filter_map(|result| hit(result, "snippet"))
+        .collect();
+
+    Ok(hits)
+}
+
+fn hit(result: &Value, snippet_key: &str) -> Option<Hit> {
+    Some(Hit {
+        title: result.get("title")?.as_str()?.to_owned(),
+        url: Url::parse(result.get("url")?.as_str()?).ok()?,
+        snippet: result
+            .get(snippet_key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_owned(),
+    })
+}
+
+/// Parse DuckDuckGo's HTML results page, skipping ads.
+fn parse_duckduckgo(body: &str) -> Vec<Hit> {
+    let doc = Html::parse_document(body);
+    let result = Selector::parse("div.result").expect("valid selector");
+    let link = Selector::parse("a.result__a").expect("valid selector");
+    let snippet = Selector::parse(".result__snippet").expect("valid selector");
+
+    doc.select(&result)
+        .filter(|el| !el.value().classes().any(|class| class == "result--ad"))
+        .filter_map(|el| {
+            let link = el.select(&link).next()?;
+            let url = duckduckgo_target(link.value().attr("href")?)?;
+            let title = link.text().collect::<String>().trim().to_owned();
+            let snippet = el
+                .select(&snippet)
+                .next()
+                .map(|s| s.inner_html())
+                .unwrap_or_default();
+
+            Some(Hit {
+                title,
+                url,
+                snippet,
+            })
+        })
+        .collect()
+}
+
+/// The destination of a DuckDuckGo result link.
+///
+/// Links usually go through a `//duckduckgo.com/l/?uddg=<target>` redirect.
+fn duckduckgo_target(href: &str) -> Option<Url> {
+    let url = Url::options()
+        .base_url(Some(&Url::parse("https://duckduckgo.com/").ok()?))
+        .parse(href)
+        .ok()?;
+
+    if url.host_str() == Some("duckduckgo.com") && url.path() == "/l/" {
+        let (_, target) = url.query_pairs().find(|(key, _)| key == "uddg")?;
+        return Url::parse(&target).ok();
+    }
+
+    Some(url)
+}
+
+#[cfg(test)]
+#[path = "backend_tests.rs"]
+mod tests;
@@ -0,0 +1,130 @@
+use pretty_assertions::assert_eq;
+
+use super::*;
+
+fn urls(hits: &[Hit]) -> Vec<&str> {
+    hits.iter().map(|hit| hit.url.as_str()).collect()
+}
+
+#[test]
+fn test_parse_searxng() {
+    let body = r#"{
+        "query": "rust",
+        "results": [
+            { "url": "https://www.rust-lang.org/", "title": "Rust", "content": "A language." },
+            { "url": "not a url", "title": "Broken" },
+            { "url": "https://doc.rust-lang.org/book/", "title": "The Book" }
+        ]
+    }"#;
+
+    let hits = parse(Backend::Searxng, body).unwrap();
+    assert_eq!(urls(&hits), vec![
+        "https://www.rust-lang.org/",
+        "https://doc.rust-lang.org/book/"
+    ]);
+    assert_eq!(hits[0].snippet, "A language.");
+    assert_eq!(hits[1].snippet, "");
+}
+
+#[test]
+fn test_parse_brave() {
+    let body = r#"{
+        "type": "search",
+        "web": { "results": [
+            { "url": "https://tokio.rs/", "title": "Tokio", "description": "An <strong>async</strong> runtime." }
+        ] }
+    }"#;
+
+    let hits = parse(Backend::Brave, body).unwrap();
+    assert_eq!(hits, vec![Hit {
+        title: "Tokio".to_owned(),
+        url: Url::parse("https://tokio.rs/").unwrap(),
+        snippet: "An <strong>async</strong> runtime.".to_owned(),
+    }]);
+
+    // No web results at all, e.g. for a query that only matched news.
+    assert_eq!(
+        parse(Backend::Brave, r#"{"type":"search"}"#).unwrap(),
+        vec![]
+    );
+}
+
+#[test]
+fn test_parse_kagi() {
+    let body = r#"{
+        "meta": { "id": "1" },
+        "data": [
+            { "t": 0, "url": "https://serde.rs/", "title": "Serde", "snippet": "Serialization." },
+            { "t": 1, "list": ["serde json", "serde derive"] }
+        ]
+    }"#;
+
+    let hits = parse(Backend::Kagi, body).unwrap();
+    assert_eq!(urls(&hits), vec!["https://serde.rs/"]);
+
+    let error = parse(
+        Backend::Kagi,
+        r#"{"error":[{"code":1,"msg":"Unauthorized"}]}"#,
+    )
+    .unwrap_err()
+    .to_string();
+    assert_eq!(error, "Kagi returned an error: Unauthorized");
+}
+
+#[test]
+fn test_parse_duckduckgo() {
+    let body = r##"
+        <html><body>
+        <div class="result results_links result--ad">
+            <h2 class="result__title"><a class="result__a" href="https://duckduckgo.com/y.js?ad=1">Ad</a></h2>
+        </div>
+        <div class="result results_links web-result">
+            <h2 class="result__title">
+                <a class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fdocs.rs%2Ftokio%2F&amp;rut=abc">
+                    tokio - <b>Rust</b>
+                </a>
+            </h2>
+            <a class="result__snippet" href="#">An event-driven, <b>non-blocking</b> I/O platform.</a>
+        </div>
+        <div class="result results_links web-result">
+            <h2 class="result__title"><a class="result__a" href="https://crates.io/crates/tokio">tokio</a></h2>
+        </div>
+        </body></html>
+    "##;
+
+    let hits = parse(Backend::Duckduckgo, body).unwrap();
+    assert_eq!(urls(&hits), vec![
+        "https://docs.rs/tokio/",
+        "https://crates.io/crates/tokio"
+    ]);
+    assert_eq!(hits[0].title, "tokio - Rust");
+    assert_eq!(
+        hits[0].snippet,
+        "An event-driven, <b>non-blocking</b> I/O platform."
+    );
+}
+
+#[test]
+fn test_request() {
+    let client = reqwest::Client::new();
+    let endpoint = Url::parse("https://api.example.com/search").unwrap();
+
+    let brave = request(
+        &client,
+        Backend::Brave,
+        endpoint.clone(),
+        Some("k"),
+        "a b",
+        20,
+    )
+    .build()
+    .unwrap();
+    assert_eq!(brave.url().query(), Some("q=a+b&count=20"));
+    assert_eq!(brave.headers()["X-Subscription-Token"], "k");
+
+    let kagi = request(&client, Backend::Kagi, endpoint, Some("k"), "a b", 5)
+        .build()
+        .unwrap();
+    assert_eq!(kagi.url().query(), Some("q=a+b&limit=5"));
+    assert_eq!(kagi.headers()["Authorization"], "Bot k");
+}
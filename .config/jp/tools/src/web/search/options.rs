@@ -0,0 +1,144 @@
+//! Parsing for the `web_search` tool's `options` map.
+//!
+//! See `.jp/mcp/tools/web/search.toml` for the user-facing schema.
+
+use jp_tool::NetRule;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::Error;
+
+/// The search engine queried by the tool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum Backend {
+    /// A SearXNG instance, through its JSON API.
+    Searxng,
+    /// The Brave Search API.
+    Brave,
+    /// The Kagi Search API.
+    Kagi,
+    /// DuckDuckGo's JavaScript-free HTML results page.
+    #[default]
+    Duckduckgo,
+}
+
+impl Backend {
+    pub(super) const fn as_str(self) -> &'static str {
+        match self {
+            Self::Searxng => "searxng",
+            Self::Brave => "brave",
+            Self::Kagi => "kagi",
+            Self::Duckduckgo => "duckduckgo",
+        }
+    }
+
+    /// The endpoint queried when no `url` is configured.
+    fn default_endpoint(self) -> Option<&'static str> {
+        match self {
+            Self::Searxng => None,
+            Self::Brave => Some("https://api.search.brave.com/res/v1/web/search"),
+            Self::Kagi => Some("https://kagi.com/api/v0/search"),
+            Self::Duckduckgo => Some("https://html.duckduckgo.com/html/"),
+        }
+    }
+
+    /// The environment variable holding the API key, if the backend needs one.
+    fn default_api_key_env(self) -> Option<&'static str> {
+        match self {
+            Self::Brave => Some("BRAVE_API_KEY"),
+            Self::Kagi => Some("KAGI_API_KEY"),
+            Self::Searxng | Self::Duckduckgo => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct WebSearchOptions {
+    #[serde(default)]
+    pub backend: Backend,
+
+    /// The endpoint to query.
+    ///
+    /// Required for SearXNG, where it is the base URL of the instance.
+    /// For the other backends it replaces the public API endpoint.
+    #[serde(default)]
+    url: Option<Url>,
+
+    /// The environment variable holding the backend's API key.
+    #[serde(default)]
+    api_key_env: Option<String>,
+
+    /// The number of results returned when the tool call does not ask for a
+    /// specific number.
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+
+    /// Rules deciding which result hosts are returned.
+    #[serde(default)]
+    pub hosts: Vec<NetRule>,
+}
+
+fn default_max_results() -> usize {
+    10
+}
+
+impl WebSearchOptions {
+    pub(super) fn parse(options: &Map<String, Value>) -> Result<Self, Error> {
+        serde_json::from_value(Value::Object(options.clone()))
+            .map_err(|e| format!("invalid web_search options: {e}").into())
+    }
+
+    /// The URL to send search requests to.
+    pub(super) fn endpoint(&self) -> Result<Url, String> {
+        if self.backend == Backend::Searxng {
+            let mut url = self.url.clone().ok_or(
+                "The `searxng` backend requires the `url` option, pointing at a SearXNG instance.",
+            )?;
+
+            if url.cannot_be_a_base() {
+                return Err(format!("Invalid SearXNG URL: {url}"));
+            }
+
+            url.path_segments_mut()
+                .expect("URL can be a base")
+                .pop_if_empty()
+                .push("search");
+
+            return Ok(url);
+        }
+
+        match (&self.url, self.backend.default_endpoint()) {
+            (Some(url), _) => Ok(url.clone()),
+            (None, Some(endpoint)) => Url::parse(endpoint).map_err(|e| e.to_string()),
+            (None, None) => Err("No search endpoint configured.".to_owned()),
+        }
+    }
+
+    /// The API key of the backend, read from the environment.
+    ///
+    /// Returns `None` for backends that need no key.
+    pub(super) fn api_key(&self) -> Result<Option<String>, String> {
+        let Some(var) = self
+            .api_key_env
+            .as_deref()
+            .or(self.backend.default_api_key_env())
+        else {
+            return Ok(None);
+        };
+
+        match std::env::var(var) {
+            Ok(key) if !key.trim().is_empty() => Ok(Some(key.trim().to_owned())),
+            _ => Err(format!(
+                "The `{}` search backend requires an API key in `${var}`.",
+                self.backend.as_str()
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "options_tests.rs"]
+mod tests;
@@ -0,0 +1,95 @@
+use serde_json::json;
+
+use super::*;
+
+fn parse(value: Value) -> Result<WebSearchOptions, Error> {
+    let Value::Object(map) = value else {
+        panic!("options must be an object");
+    };
+
+    WebSearchOptions::parse(&map)
+}
+
+#[test]
+fn test_defaults() {
+    let options = parse(json!({})).unwrap();
+
+    assert_eq!(options.backend, Backend::Duckduckgo);
+    assert_eq!(options.max_results, 10);
+    assert!(options.hosts.is_empty());
+    assert_eq!(
+        options.endpoint().unwrap().as_str(),
+        "https://html.duckduckgo.com/html/"
+    );
+    assert_eq!(options.api_key().unwrap(), None);
+}
+
+#[test]
+fn test_searxng_requires_a_url() {
+    let options = parse(json!({ "backend": "searxng" })).unwrap();
+    assert!(options.endpoint().unwrap_err().contains("`url`"));
+
+    for url in ["https://searx.example.org", "https://searx.example.org/"] {
+        let options = parse(json!({ "backend": "searxng", "url": url })).unwrap();
+        assert_eq!(
+            options.endpoint().unwrap().as_str(),
+            "https://searx.example.org/search"
+        );
+    }
+
+    let options =
+        parse(json!({ "backend": "searxng", "url": "https://example.org/searx/" })).unwrap();
+    assert_eq!(
+        options.endpoint().unwrap().as_str(),
+        "https://example.org/searx/search"
+    );
+}
+
+#[test]
+fn test_url_replaces_the_public_endpoint() {
+    let options = parse(json!({ "backend": "brave", "url": "http://127.0.0.1:8080/q" })).unwrap();
+    assert_eq!(
+        options.endpoint().unwrap().as_str(),
+        "http://127.0.0.1:8080/q"
+    );
+}
+
+#[test]
+fn test_api_key() {
+    let options = parse(json!({
+        "backend": "kagi",
+        "api_key_env": "JP_TEST_WEB_SEARCH_MISSING_KEY",
+    }))
+    .unwrap();
+
+    assert_eq!(
+        options.api_key().unwrap_err(),
+        "The `kagi` search backend requires an API key in `$JP_TEST_WEB_SEARCH_MISSING_KEY`."
+    );
+
+    unsafe { std::env::set_var("JP_TEST_WEB_SEARCH_KEY", " secret\n") };
+    let options =
+        parse(json!({ "backend": "kagi", "api_key_env": "JP_TEST_WEB_SEARCH_KEY" })).unwrap();
+    assert_eq!(options.api_key().unwrap().as_deref(), Some("secret"));
+}
+
+#[test]
+fn test_host_rules() {
+    let options = parse(json!({
+        "hosts": [
+            { "host": "*.pinterest.com" },
+            { "host": "docs.rs", "allow": true },
+        ],
+    }))
+    .unwrap();
+
+    assert_eq!(options.hosts.len(), 2);
+    assert!(!options.hosts[0].allow);
+    assert!(options.hosts[1].allow);
+}
+
+#[test]
+fn test_unknown_options_are_rejected() {
+    assert!(parse(json!({ "engine": "brave" })).is_err());
+    assert!(parse(json!({ "backend": "google" })).is_err());
+}
@@ -0,0 +1,86 @@
+//! Evaluation of [`NetRule`]s against result and endpoint URLs.
+//!
+//! Follows the structured matching of `access.net` rules: the host must match
+//! exactly (after normalization), while `scheme`, `port` and `path_prefix`
+//! narrow a rule when set.
+//! In addition, a `*.` prefix on the host matches the domain and all of its
+//! subdomains, which is what a rule about a website usually means.
+
+use jp_tool::NetRule;
+use url::{Host, Url};
+
+/// Whether the most specific rule matching `url` allows it.
+///
+/// Returns `None` if no rule matches.
+/// When equally specific rules disagree, the denying one wins.
+pub(super) fn evaluate(rules: &[NetRule], url: &Url) -> Option<bool> {
+    rules
+        .iter()
+        .filter_map(|rule| specificity(rule, url).map(|s| (s, rule.allow)))
+        // `false` sorts before `true`, so `min_by_key` on the inverted
+        // specificity picks the most specific, denying rule.
+        .min_by_key(|&(specificity, allow)| (usize::MAX - specificity, allow))
+        .map(|(_, allow)| allow)
+}
+
+/// How specific `rule` is for `url`, or `None` if it does not match.
+///
+/// The specificity is the number of optional components the rule sets: its
+/// scheme, its port and each segment of its path prefix.
+fn specificity(rule: &NetRule, url: &Url) -> Option<usize> {
+    let host = normalize(url.host_str()?)?;
+    let matches_host = match rule.host.strip_prefix("*.") {
+        Some(domain) => {
+            let domain = normalize(domain)?;
+            host == domain || host.ends_with(&format!(".{domain}"))
+        }
+        None => normalize(&rule.host).is_some_and(|rule_host| rule_host == host),
+    };
+
+    if !matches_host {
+        return None;
+    }
+
+    let mut specificity = 0;
+
+    if let Some(scheme) = &rule.scheme {
+        if !scheme.eq_ignore_ascii_case(url.scheme()) {
+            return None;
+        }
+        specificity += 1;
+    }
+
+    if let Some(port) = rule.port {
+        if url.port_or_known_default() != Some(port) {
+            return None;
+        }
+        specificity += 1;
+    }
+
+    if let Some(prefix) = &rule.path_prefix {
+        let prefix = prefix.trim_end_matches('/');
+        let path = url.path();
+        let segment_match = path == prefix
+            || path
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.starts_with('/'));
+
+        if !prefix.is_empty() && !segment_match {
+            return None;
+        }
+        specificity += prefix.split('/').filter(|s| !s.is_empty()).count();
+    }
+
+    Some(specificity)
+}
+
+/// The ASCII, lowercase form of a host, or `None` if it does not parse.
+fn normalize(host: &str) -> Option<String> {
+    Host::parse(host.trim_end_matches('.'))
+        .ok()
+        .map(|host| host.to_string().to_ascii_lowercase())
+}
+
+#[cfg(test)]
+#[path = "rules_tests.rs"]
+mod tests;
@@ -0,0 +1,82 @@
+use super::*;
+
+fn rule(host: &str, allow: bool) -> NetRule {
+    NetRule {
+        host: host.to_owned(),
+        scheme: None,
+        port: None,
+        path_prefix: None,
+        allow,
+    }
+}
+
+fn eval(rules: &[NetRule], url: &str) -> Option<bool> {
+    evaluate(rules, &Url::parse(url).unwrap())
+}
+
+#[test]
+fn test_exact_host() {
+    let rules = [rule("api.github.com", true)];
+
+    assert_eq!(eval(&rules, "https://api.github.com/repos"), Some(true));
+    assert_eq!(eval(&rules, "https://API.GitHub.com/"), Some(true));
+    assert_eq!(eval(&rules, "https://api.github.com.evil.com/"), None);
+    assert_eq!(eval(&rules, "https://github.com/"), None);
+}
+
+#[test]
+fn test_wildcard_host() {
+    let rules = [rule("*.pinterest.com", false)];
+
+    assert_eq!(eval(&rules, "https://pinterest.com/pin/1"), Some(false));
+    assert_eq!(eval(&rules, "https://www.pinterest.com/pin/1"), Some(false));
+    assert_eq!(eval(&rules, "https://notpinterest.com/"), None);
+}
+
+#[test]
+fn test_unicode_hosts_are_normalized() {
+    let rules = [rule("münchen.de", true)];
+
+    assert_eq!(eval(&rules, "https://xn--mnchen-3ya.de/"), Some(true));
+    assert_eq!(eval(&rules, "https://münchen.de/"), Some(true));
+}
+
+#[test]
+fn test_path_prefix_is_segment_aware() {
+    let rules = [rule("api.github.com", true), NetRule {
+        path_prefix: Some("/admin".to_owned()),
+        ..rule("api.github.com", false)
+    }];
+
+    assert_eq!(eval(&rules, "https://api.github.com/admin"), Some(false));
+    assert_eq!(
+        eval(&rules, "https://api.github.com/admin/users"),
+        Some(false)
+    );
+    assert_eq!(
+        eval(&rules, "https://api.github.com/administration"),
+        Some(true)
+    );
+}
+
+#[test]
+fn test_scheme_and_port() {
+    let rules = [rule("example.com", false), NetRule {
+        scheme: Some("https".to_owned()),
+        port: Some(443),
+        ..rule("example.com", true)
+    }];
+
+    assert_eq!(eval(&rules, "https://example.com/"), Some(true));
+    assert_eq!(eval(&rules, "http://example.com/"), Some(false));
+    assert_eq!(eval(&rules, "https://example.com:8443/"), Some(false));
+}
+
+#[test]
+fn test_deny_wins_ties() {
+    let rules = [rule("example.com", true), rule("example.com", false)];
+    assert_eq!(eval(&rules, "https://example.com/"), Some(false));
+
+    let rules = [rule("example.com", false), rule("example.com", true)];
+    assert_eq!(eval(&rules, "https://example.com/"), Some(false));
+}
@@ -0,0 +1,235 @@
+use indoc::indoc;
+use jp_tool::{AccessPolicy, Action, Outcome};
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tokio::{
+    io::{AsyncReadExt as _, AsyncWriteExt as _},
+    net::TcpListener,
+    task::JoinHandle,
+};
+
+use super::*;
+
+fn context(access: Option<AccessPolicy>) -> Context {
+    Context {
+        root: ".".into(),
+        action: Action::Run,
+        access,
+        workspace_id: "test".into(),
+        conversation_id: "test".into(),
+        checkpoint: None,
+    }
+}
+
+fn options(value: Value) -> Map<String, Value> {
+    let Value::Object(map) = value else {
+        panic!("options must be an object");
+    };
+
+    map
+}
+
+fn rule(host: &str, allow: bool) -> NetRule {
+    NetRule {
+        host: host.to_owned(),
+        scheme: None,
+        port: None,
+        path_prefix: None,
+        allow,
+    }
+}
+
+fn error_message(outcome: Outcome) -> String {
+    let Outcome::Error { message, .. } = outcome else {
+        panic!("expected an error outcome, got {outcome:?}");
+    };
+
+    message
+}
+
+/// A stand-in search server that answers a single request with `body`.
+///
+/// Returns its base URL, and a handle resolving to the head of the request it
+/// received.
+async fn serve(status: u16, content_type: &'static str, body: String) -> (Url, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        let mut head = vec![];
+        let mut buf = [0; 1024];
+        while !head.ends_with(b"\r\n\r\n") {
+            let n = stream.read(&mut buf).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            head.extend_from_slice(&buf[..n]);
+        }
+
+        let response = format!(
+            "HTTP/1.1 {status} Stand-in\r\nContent-Type: {content_type}\r\nContent-Length: \
+             {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+        stream.shutdown().await.unwrap();
+
+        String::from_utf8(head).unwrap()
+    });
+
+    (url, handle)
+}
+
+#[test]
+fn test_dedup_key() {
+    let key = |url: &str| dedup_key(&Url::parse(url).unwrap());
+
+    assert_eq!(
+        key("https://www.example.com/page/"),
+        key("http://example.com/page#intro")
+    );
+    assert_eq!(
+        key("https://example.com/page?id=1&utm_source=feed"),
+        key("https://example.com/page?id=1")
+    );
+    assert_ne!(
+        key("https://example.com/page?id=1"),
+        key("https://example.com/page?id=2")
+    );
+}
+
+#[test]
+fn test_permitted() {
+    let url = |url: &str| Url::parse(url).unwrap();
+
+    // Deny rules alone keep every other host.
+    let hosts = [rule("*.pinterest.com", false)];
+    assert!(!permitted(
+        &[],
+        &hosts,
+        &url("https://www.pinterest.com/pin/1")
+    ));
+    assert!(permitted(&[], &hosts, &url("https://docs.rs/")));
+
+    // An allow rule switches to only keeping the allowed hosts.
+    let hosts = [rule("docs.rs", true)];
+    assert!(permitted(&[], &hosts, &url("https://docs.rs/")));
+    assert!(!permitted(&[], &hosts, &url("https://example.com/")));
+
+    // The access policy always denies what it does not allow.
+    let net = [rule("example.com", true)];
+    assert!(permitted(&net, &[], &url("https://example.com/")));
+    assert!(!permitted(&net, &[], &url("https://docs.rs/")));
+}
+
+#[tokio::test]
+async fn test_searxng() {
+    let body = json!({
+        "results": [
+            { "url": "https://www.rust-lang.org/", "title": "Rust", "content": "A <b>language</b>." },
+            { "url": "https://rust-lang.org", "title": "Rust (again)", "content": "Duplicate." },
+            { "url": "https://www.pinterest.com/rust", "title": "Pins", "content": "" },
+            { "url": "https://doc.rust-lang.org/book/", "title": "", "content": "" }
+        ]
+    });
+    let (url, request) = serve(200, "application/json", body.to_string()).await;
+
+    let options = options(json!({
+        "backend": "searxng",
+        "url": url,
+        "hosts": [{ "host": "*.pinterest.com" }],
+    }));
+
+    let outcome = web_search(&context(None), "rust lang", None, &options)
+        .await
+        .unwrap();
+
+    assert_eq!(outcome.unwrap_content(), indoc! {"
+        1. [Rust](https://www.rust-lang.org/)
+           A **language**.
+
+        2. [https://doc.rust-lang.org/book/](https://doc.rust-lang.org/book/)
+
+        1 duplicate result(s) were merged.
+        1 result(s) were left out by the configured host rules."});
+
+    let request = request.await.unwrap();
+    assert!(
+        request.starts_with("GET /search?q=rust+lang&format=json HTTP/1.1\r\n"),
+        "{request}"
+    );
+}
+
+#[tokio::test]
+async fn test_limit() {
+    let results = (1..=5)
+        .map(|i| json!({ "url": format!("https://example.com/{i}"), "title": format!("Page {i}") }))
+        .collect::<Vec<_>>();
+    let (url, request) = serve(
+        200,
+        "application/json",
+        json!({ "results": results }).to_string(),
+    )
+    .await;
+
+    let options = options(json!({ "backend": "searxng", "url": url }));
+    let outcome = web_search(&context(None), "pages", Some(2), &options)
+        .await
+        .unwrap();
+
+    assert_eq!(outcome.unwrap_content(), indoc! {"
+        1. [Page 1](https://example.com/1)
+
+        2. [Page 2](https://example.com/2)"});
+    request.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_backend_errors_are_reported() {
+    let (url, request) = serve(429, "text/plain", "slow down".to_owned()).await;
+
+    let options = options(json!({ "backend": "duckduckgo", "url": url }));
+    let outcome = web_search(&context(None), "rust", None, &options)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        error_message(outcome),
+        "The `duckduckgo` search backend responded with 429 Too Many Requests. Wait before \
+         searching again."
+    );
+    request.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_access_policy_guards_the_backend() {
+    let options = options(json!({ "backend": "duckduckgo" }));
+    let policy = AccessPolicy {
+        net: vec![rule("docs.rs", true)],
+        ..AccessPolicy::default()
+    };
+
+    let outcome = web_search(&context(Some(policy)), "rust", None, &options)
+        .await
+        .unwrap();
+
+    let error = error_message(outcome);
+    assert!(
+        error.starts_with("Access denied: cannot connect to 'html.duckduckgo.com'."),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn test_empty_query() {
+    let outcome = web_search(&context(None), "  ", None, &Map::new())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        error_message(outcome),
+        "The search query must not be empty."
+    );
+}
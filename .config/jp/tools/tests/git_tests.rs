@@ -67,6 +67,7 @@ fn ctx(root: &Utf8Path) -> Context {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     }
 }
 
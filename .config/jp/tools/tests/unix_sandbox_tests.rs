@@ -29,6 +29,7 @@ fn setup() -> (Utf8TempDir, Context) {
         access: None,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     };
     (dir, ctx)
 }
@@ -177,7 +178,9 @@ async fn sandbox_blocks_reading_users_dir() {
         Outcome::Error { .. } => {
             // Argument validation caught it — also acceptable.
         }
-        other @ Outcome::NeedsInput { .. } => panic!("unexpected outcome: {other:?}"),
+        other @ (Outcome::NeedsInput { .. } | Outcome::Handle { .. }) => {
+            panic!("unexpected outcome: {other:?}")
+        }
     }
 }
 
@@ -218,7 +221,9 @@ async fn sandbox_blocks_reading_tmp() {
         Outcome::Error { .. } => {
             // Argument validation caught it — acceptable.
         }
-        other @ Outcome::NeedsInput { .. } => panic!("unexpected outcome: {other:?}"),
+        other @ (Outcome::NeedsInput { .. } | Outcome::Handle { .. }) => {
+            panic!("unexpected outcome: {other:?}")
+        }
     }
 }
 
@@ -1,21 +1,30 @@
 mod attachment;
+pub(crate) mod checkpoint;
 pub(crate) mod compact_flag;
+mod completions;
 mod config;
 mod conversation;
 pub(crate) mod conversation_id;
+#[cfg(unix)]
+mod daemon;
 mod init;
 pub(crate) mod label;
 mod lock;
+mod man;
 pub(crate) mod plugin;
 mod query;
+mod stats;
 pub(crate) mod target;
+mod template;
 pub(crate) mod time;
 pub(crate) mod turn_range;
+mod undo;
 
 use std::{fmt, num::NonZeroU8};
 
-use jp_config::PartialAppConfig;
+use jp_config::{PartialAppConfig, env::Env};
 use jp_workspace::Workspace;
+pub(crate) use query::read_piped_stdin;
 use serde_json::Value;
 pub(crate) use target::ConversationLoadRequest;
 
@@ -51,6 +60,31 @@ pub(crate) enum Commands {
     /// Manage plugins.
     Plugin(plugin::PluginManagement),
 
+    /// Inspect named query templates.
+    #[command(visible_alias = "tpl", alias = "templates")]
+    Template(template::Template),
+
+    /// Undo the changes that tools made to workspace files.
+    Undo(undo::Undo),
+
+    /// Inspect the changes that tools made to workspace files.
+    #[command(alias = "checkpoints")]
+    Checkpoint(checkpoint::Checkpoints),
+
+    /// Summarize the local usage log.
+    Stats(stats::Stats),
+
+    /// Manage the workspace daemon.
+    #[cfg(unix)]
+    Daemon(daemon::Daemon),
+
+    /// Print the shell completion script for jp.
+    Completions(completions::Completions),
+
+    /// Print the man pages of jp.
+    #[command(alias = "manpage")]
+    Man(man::Man),
+
     /// External plugin subcommand (`jp-<name>` on $PATH or registry).
     #[command(external_subcommand)]
     External(Vec<String>),
@@ -83,8 +117,22 @@ impl Commands {
                 args.run(ctx)
             }
             Commands::Plugin(args) => args.run(ctx).await,
+            Commands::Template(args) => {
+                debug_assert!(handles.is_empty(), "Template commands don't use handles");
+                args.run(ctx)
+            }
+            Commands::Undo(args) => args.run(ctx, handles),
+            Commands::Checkpoint(args) => args.run(ctx, handles),
+            Commands::Stats(args) => {
+                debug_assert!(handles.is_empty(), "Stats commands don't use handles");
+                args.run(ctx)
+            }
             Commands::External(args) => plugin::dispatch::run_external(&args, ctx).await,
-            Commands::Init(_) => unreachable!("handled before workspace initialization"),
+            Commands::Init(_) | Commands::Completions(_) | Commands::Man(_) => {
+                unreachable!("handled before workspace initialization")
+            }
+            #[cfg(unix)]
+            Commands::Daemon(_) => unreachable!("handled before workspace initialization"),
         }
     }
 
@@ -95,11 +143,19 @@ impl Commands {
             Commands::Query(args) => args.conversation_load_request(),
             Commands::Config(args) => args.conversation_load_request(),
             Commands::Conversation(args) => args.conversation_load_request(),
+            Commands::Undo(args) => args.conversation_load_request(),
+            Commands::Checkpoint(args) => args.conversation_load_request(),
             Commands::Init(_)
+            | Commands::Completions(_)
+            | Commands::Man(_)
             | Commands::Attachment(_)
             | Commands::AttachmentAdd(_)
             | Commands::Plugin(_)
+            | Commands::Template(_)
+            | Commands::Stats(_)
             | Commands::External(_) => ConversationLoadRequest::none(),
+            #[cfg(unix)]
+            Commands::Daemon(_) => ConversationLoadRequest::none(),
         }
     }
 
@@ -119,8 +175,16 @@ impl Commands {
             Commands::Attachment(_) => "attachment",
             Commands::AttachmentAdd(_) => "attachment-add",
             Commands::Init(_) => "init",
+            Commands::Completions(_) => "completions",
+            Commands::Man(_) => "man",
             Commands::Conversation(_) => "conversation",
             Commands::Plugin(_) => "plugin",
+            Commands::Template(_) => "template",
+            Commands::Undo(_) => "undo",
+            Commands::Checkpoint(_) => "checkpoint",
+            Commands::Stats(_) => "stats",
+            #[cfg(unix)]
+            Commands::Daemon(_) => "daemon",
             Commands::External(args) => {
                 // Use first arg as the command name (it's the subcommand name).
                 // Clap puts the subcommand name as the first element.
@@ -140,20 +204,31 @@ impl IntoPartialAppConfig for Commands {
         workspace: Option<&Workspace>,
         partial: PartialAppConfig,
         merged_config: Option<&PartialAppConfig>,
+        env: &Env,
     ) -> Result<PartialAppConfig, Box<dyn std::error::Error + Send + Sync>> {
         match self {
-            Commands::Query(args) => args.apply_cli_config(workspace, partial, merged_config),
-            Commands::Attachment(args) => args.apply_cli_config(workspace, partial, merged_config),
+            Commands::Query(args) => args.apply_cli_config(workspace, partial, merged_config, env),
+            Commands::Attachment(args) => {
+                args.apply_cli_config(workspace, partial, merged_config, env)
+            }
             Commands::AttachmentAdd(args) => {
-                args.apply_cli_config(workspace, partial, merged_config)
+                args.apply_cli_config(workspace, partial, merged_config, env)
             }
             Commands::Conversation(args) => {
-                args.apply_cli_config(workspace, partial, merged_config)
+                args.apply_cli_config(workspace, partial, merged_config, env)
             }
             Commands::Config(_)
             | Commands::Init(_)
+            | Commands::Completions(_)
+            | Commands::Man(_)
             | Commands::Plugin(_)
+            | Commands::Template(_)
+            | Commands::Undo(_)
+            | Commands::Checkpoint(_)
+            | Commands::Stats(_)
             | Commands::External(_) => Ok(partial),
+            #[cfg(unix)]
+            Commands::Daemon(_) => Ok(partial),
         }
     }
 
@@ -168,13 +243,23 @@ impl IntoPartialAppConfig for Commands {
             Commands::Query(args) => {
                 args.apply_conversation_config(workspace, partial, merged_config, handle)
             }
-            Commands::Config(_)
-            | Commands::Attachment(_)
+            Commands::Config(args) => {
+                args.apply_conversation_config(workspace, partial, merged_config, handle)
+            }
+            Commands::Attachment(_)
             | Commands::AttachmentAdd(_)
             | Commands::Conversation(_)
             | Commands::Init(_)
+            | Commands::Completions(_)
+            | Commands::Man(_)
             | Commands::Plugin(_)
+            | Commands::Template(_)
+            | Commands::Undo(_)
+            | Commands::Checkpoint(_)
+            | Commands::Stats(_)
             | Commands::External(_) => Ok(partial),
+            #[cfg(unix)]
+            Commands::Daemon(_) => Ok(partial),
         }
     }
 }
@@ -461,6 +546,19 @@ impl From<crate::error::Error> for Error {
                 ("variable", var),
             ]
             .into(),
+            NamedTemplateNotFound(name) => [
+                ("message", format!("Template `{name}` not found")),
+                (
+                    "suggestion",
+                    "Run `jp template ls` to list the available templates.".to_owned(),
+                ),
+            ]
+            .into(),
+            TemplateParam { name, message } => [
+                ("message", format!("Invalid template parameter `{name}`")),
+                ("error", message),
+            ]
+            .into(),
             MissingEditor => [("message", "Missing editor".to_owned())].into(),
             Schema(error) => [("message", "Invalid schema".to_owned()), ("error", error)].into(),
             MissingStructuredData => {
@@ -507,6 +605,19 @@ impl From<crate::error::Error> for Error {
                     .to_owned(),
             )]
             .into(),
+            NothingToResume(id) => [
+                (
+                    "message",
+                    format!("Conversation {id} has no incomplete turn to resume"),
+                ),
+                (
+                    "suggestion",
+                    "Start a new turn with `jp query`, or re-send the last one with `jp query \
+                     --replay`."
+                        .to_owned(),
+                ),
+            ]
+            .into(),
             Compaction(error) => [("message", "Compaction error".into()), ("error", error)].into(),
             Label(error) => [("message", "Label error".into()), ("error", error)].into(),
             Summarize { model, reason } => [
@@ -623,6 +734,7 @@ impl From<jp_llm::Error> for Error {
                 ("variable", variable),
             ]
             .into(),
+            Secret(error) => with_cause(&error, "Unable to resolve secret"),
             InvalidResponse(error) => [
                 ("message", "Invalid response received".into()),
                 ("error", error),
@@ -8,7 +8,7 @@ use jp_attachment_internal::{
     ResolveError, resolve as resolve_internal_attachment, validate as validate_internal_attachment,
 };
 use jp_attachment_mcp_resources as _;
-use jp_config::PartialAppConfig;
+use jp_config::{PartialAppConfig, env::Env};
 use jp_workspace::Workspace;
 use tracing::{trace, warn};
 use url::Url;
@@ -48,10 +48,11 @@ impl IntoPartialAppConfig for Attachment {
         workspace: Option<&Workspace>,
         partial: PartialAppConfig,
         merged_config: Option<&PartialAppConfig>,
+        env: &Env,
     ) -> std::result::Result<PartialAppConfig, Box<dyn std::error::Error + Send + Sync>> {
         match &self.command {
-            Commands::Add(args) => args.apply_cli_config(workspace, partial, merged_config),
-            Commands::Remove(args) => args.apply_cli_config(workspace, partial, merged_config),
+            Commands::Add(args) => args.apply_cli_config(workspace, partial, merged_config, env),
+            Commands::Remove(args) => args.apply_cli_config(workspace, partial, merged_config, env),
             Commands::List(_) | Commands::Print(_) => Ok(partial),
         }
     }
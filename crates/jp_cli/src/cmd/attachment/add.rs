@@ -1,4 +1,4 @@
-use jp_config::PartialAppConfig;
+use jp_config::{PartialAppConfig, env::Env};
 use jp_workspace::Workspace;
 
 use super::validate_attachment;
@@ -31,9 +31,10 @@ impl IntoPartialAppConfig for Add {
         workspace: Option<&Workspace>,
         mut partial: PartialAppConfig,
         _: Option<&PartialAppConfig>,
+        env: &Env,
     ) -> std::result::Result<PartialAppConfig, Box<dyn std::error::Error + Send + Sync>> {
         for uri in &self.attachments {
-            let uri = uri.parse(workspace.map(Workspace::root))?;
+            let uri = uri.parse(workspace.map(Workspace::root), env)?;
             validate_attachment(&uri)?;
 
             partial.conversation.attachments.push(uri.clone().into());
@@ -10,7 +10,9 @@ pub(crate) struct Print {
 
 impl Print {
     pub(crate) async fn run(self, ctx: &mut Ctx) -> Output {
-        let uri = self.attachment.parse(Some(ctx.workspace.root()))?;
+        let uri = self
+            .attachment
+            .parse(Some(ctx.workspace.root()), &ctx.env)?;
         let attachments = register_attachment(ctx, uri).await?;
 
         for (idx, attachment) in attachments.iter().enumerate() {
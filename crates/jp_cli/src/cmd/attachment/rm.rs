@@ -1,4 +1,6 @@
-use jp_config::{Config as _, PartialAppConfig, conversation::attachment::AttachmentConfig};
+use jp_config::{
+    Config as _, PartialAppConfig, conversation::attachment::AttachmentConfig, env::Env,
+};
 use jp_workspace::Workspace;
 
 use crate::{IntoPartialAppConfig, cmd::Output, ctx::Ctx, parser::AttachmentUrlOrPath};
@@ -23,13 +25,14 @@ impl IntoPartialAppConfig for Rm {
         workspace: Option<&Workspace>,
         mut partial: PartialAppConfig,
         _: Option<&PartialAppConfig>,
+        env: &Env,
     ) -> std::result::Result<PartialAppConfig, Box<dyn std::error::Error + Send + Sync>> {
         let mut attachments = vec![];
 
         let to_remove_attachments = self
             .attachments
             .iter()
-            .map(|v| v.parse(workspace.map(Workspace::root)))
+            .map(|v| v.parse(workspace.map(Workspace::root), env))
             .collect::<Result<Vec<_>, _>>()?;
 
         for attachment in partial.conversation.attachments {
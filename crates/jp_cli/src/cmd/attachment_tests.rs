@@ -1,5 +1,5 @@
 use camino_tempfile::tempdir;
-use jp_config::AppConfig;
+use jp_config::{AppConfig, env::Env};
 use jp_conversation::ConversationId;
 use jp_printer::{OutputFormat, Printer};
 use jp_workspace::Workspace;
@@ -31,6 +31,7 @@ fn empty_ctx() -> (Ctx, Runtime) {
         Runtime::new().unwrap(),
         Globals::default(),
         AppConfig::new_test(),
+        Env::process(),
         None,
         printer,
     );
@@ -53,6 +54,7 @@ fn an_empty_listing_is_still_an_array_in_json() {
         Runtime::new().unwrap(),
         Globals::default(),
         AppConfig::new_test(),
+        Env::process(),
         None,
         printer,
     );
@@ -0,0 +1,271 @@
+//! `jp checkpoint` subcommands, and the storage of per-turn checkpoints.
+//!
+//! Before a tool creates, updates or deletes a workspace file, the state of
+//! that file is recorded in the checkpoint of the current turn (see
+//! [`jp_tool::Checkpoint`]).
+//! Checkpoints live in user storage, one directory per conversation turn, and
+//! are independent of any version control.
+//! A turn's directory is named after the checkpoint ID recorded on its
+//! `TurnStart` event (see [`TURN_CHECKPOINT_KEY`]), so removing earlier turns
+//! does not mix up the checkpoints of the later ones.
+//! `jp undo` restores them; `jp checkpoint` inspects them.
+//!
+//! [`TURN_CHECKPOINT_KEY`]: jp_conversation::event::TURN_CHECKPOINT_KEY
+
+mod diff;
+mod ls;
+
+use std::{fs, io};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::Utc;
+use jp_conversation::{ConversationId, ConversationStream, event::TURN_CHECKPOINT_KEY};
+use jp_storage::backend::FsStorageBackend;
+use jp_tool::{Checkpoint, FileState, PreImage};
+use jp_workspace::ConversationHandle;
+use relative_path::RelativePath;
+
+use crate::{
+    cmd::{ConversationLoadRequest, Output},
+    ctx::Ctx,
+};
+
+/// The storage directory that holds the checkpoints of all conversations.
+const CHECKPOINTS_DIR: &str = "checkpoints";
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Checkpoints {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+impl Checkpoints {
+    pub(crate) fn conversation_load_request(&self) -> ConversationLoadRequest {
+        match &self.command {
+            Commands::List(args) => args.conversation_load_request(),
+            Commands::Diff(args) => args.conversation_load_request(),
+        }
+    }
+
+    pub(crate) fn run(self, ctx: &mut Ctx, handles: Vec<ConversationHandle>) -> Output {
+        match self.command {
+            Commands::List(args) => args.run(ctx, handles),
+            Commands::Diff(args) => args.run(ctx, handles),
+        }
+    }
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Commands {
+    /// List the turns that changed workspace files.
+    #[command(name = "ls", alias = "list")]
+    List(ls::Ls),
+
+    /// Show the changes that a turn made to workspace files.
+    #[command(name = "diff")]
+    Diff(diff::Diff),
+}
+
+/// The checkpoints of a single conversation.
+#[derive(Debug, Clone)]
+pub(crate) struct TurnCheckpoints {
+    dir: Utf8PathBuf,
+
+    /// The checkpoint ID of each turn, in turn order.
+    ///
+    /// A turn without an ID has no checkpoint.
+    ids: Vec<Option<String>>,
+}
+
+impl TurnCheckpoints {
+    /// The checkpoints of conversation `id`.
+    ///
+    /// Returns `None` without user storage: checkpoints hold full copies of
+    /// workspace files, which must not end up in the workspace storage that
+    /// users commit.
+    pub(crate) fn new(fs: &FsStorageBackend, id: &ConversationId) -> Option<Self> {
+        let dir = fs
+            .user_storage_with_path(RelativePath::new(CHECKPOINTS_DIR))?
+            .join(id.to_string());
+
+        Some(Self::at(dir))
+    }
+
+    /// Checkpoints stored in `dir`.
+    pub(crate) fn at(dir: impl Into<Utf8PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            ids: vec![],
+        }
+    }
+
+    /// Number the checkpoints after the turns of `stream`.
+    pub(crate) fn with_turns(mut self, stream: &ConversationStream) -> Self {
+        let mut ids = vec![None; stream.turn_count()];
+        for (turn, event) in stream.iter_events_by_turn() {
+            if event.is_turn_start()
+                && let Some(id) = event.metadata.get(TURN_CHECKPOINT_KEY)
+            {
+                ids[turn] = id.as_str().map(str::to_owned);
+            }
+        }
+
+        self.ids = ids;
+        self
+    }
+
+    /// A new checkpoint ID, for a turn that has none yet.
+    pub(crate) fn new_id() -> String {
+        Utc::now().format("%Y%m%dT%H%M%S%.6fZ").to_string()
+    }
+
+    /// The checkpoint ID of the 1-based `turn`, if it has one.
+    pub(crate) fn id(&self, turn: usize) -> Option<&str> {
+        turn.checked_sub(1)
+            .and_then(|index| self.ids.get(index))
+            .and_then(Option::as_deref)
+    }
+
+    /// The checkpoint with the given ID.
+    pub(crate) fn checkpoint(&self, id: &str) -> Checkpoint {
+        Checkpoint::new(self.dir.join(id))
+    }
+
+    /// The checkpoint of the 1-based `turn`, if it has one.
+    pub(crate) fn turn(&self, turn: usize) -> Option<Checkpoint> {
+        self.id(turn).map(|id| self.checkpoint(id))
+    }
+
+    /// The files recorded in the checkpoint of `turn`.
+    pub(crate) fn entries(&self, turn: usize) -> io::Result<Vec<PreImage>> {
+        match self.turn(turn) {
+            Some(checkpoint) => checkpoint.entries(),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// The 1-based turns that recorded changes, in ascending order.
+    pub(crate) fn turns(&self) -> io::Result<Vec<usize>> {
+        let mut turns = vec![];
+        for turn in 1..=self.ids.len() {
+            if let Some(checkpoint) = self.turn(turn)
+                && checkpoint.dir().try_exists()?
+            {
+                turns.push(turn);
+            }
+        }
+
+        Ok(turns)
+    }
+
+    /// Remove the checkpoints of every turn.
+    pub(crate) fn remove_all(&self) -> io::Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Remove the checkpoint of `turn`.
+    pub(crate) fn remove(&self, turn: usize) -> io::Result<()> {
+        let Some(checkpoint) = self.turn(turn) else {
+            return Ok(());
+        };
+
+        match fs::remove_dir_all(checkpoint.dir()) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    /// The changes that `turn` made to the files in `root`.
+    ///
+    /// A file's state after the turn is the state recorded by the first later
+    /// turn that changed it, or its current state.
+    pub(crate) fn changes(&self, root: &Utf8Path, turn: usize) -> io::Result<Vec<FileChange>> {
+        let later = self
+            .turns()?
+            .into_iter()
+            .filter(|&t| t > turn)
+            .map(|t| self.entries(t))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut changes = vec![];
+        for PreImage { path, state } in self.entries(turn)? {
+            let after = match later
+                .iter()
+                .flatten()
+                .find(|entry| entry.path == path)
+                .map(|entry| entry.state.clone())
+            {
+                Some(state) => state,
+                None => FileState::load(&root.join(&path))?.unwrap_or(FileState::Absent),
+            };
+
+            if state != after {
+                changes.push(FileChange {
+                    path,
+                    before: state,
+                    after,
+                });
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Restore the files in `root` to their state before `turn`, undoing the
+    /// changes of that turn and every later turn.
+    ///
+    /// Returns the restored paths, and removes the restored checkpoints.
+    pub(crate) fn undo(&self, root: &Utf8Path, turn: usize) -> io::Result<Vec<Utf8PathBuf>> {
+        let mut restored = vec![];
+
+        // Newest first, so the oldest state of a file is the one that remains.
+        for t in self.turns()?.into_iter().rev().filter(|&t| t >= turn) {
+            if let Some(checkpoint) = self.turn(t) {
+                restored.extend(checkpoint.restore(root)?);
+                self.remove(t)?;
+            }
+        }
+
+        restored.sort();
+        restored.dedup();
+        Ok(restored)
+    }
+}
+
+/// A file that a turn changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FileChange {
+    /// The workspace-relative path of the file.
+    pub path: Utf8PathBuf,
+
+    /// The state of the file before the turn.
+    pub before: FileState,
+
+    /// The state of the file after the turn.
+    pub after: FileState,
+}
+
+/// Resolve the turn a checkpoint command applies to.
+///
+/// Defaults to the most recent turn that changed files.
+pub(crate) fn resolve_turn(
+    checkpoints: &TurnCheckpoints,
+    turn: Option<usize>,
+) -> Result<usize, crate::cmd::Error> {
+    match turn {
+        Some(0) => Err("Turn numbers are 1-based; `0` is not a valid turn.".into()),
+        Some(turn) => Ok(turn),
+        None => checkpoints
+            .turns()
+            .map_err(|error| error.to_string())?
+            .pop()
+            .ok_or_else(|| "No turn in this conversation changed any files.".into()),
+    }
+}
+
+#[cfg(test)]
+#[path = "checkpoint_tests.rs"]
+mod tests;
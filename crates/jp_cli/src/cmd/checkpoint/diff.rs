@@ -0,0 +1,159 @@
+use crossterm::style::Stylize as _;
+use jp_tool::FileState;
+use jp_workspace::ConversationHandle;
+use serde_json::json;
+use similar::TextDiff;
+
+use super::{FileChange, TurnCheckpoints, resolve_turn};
+use crate::{
+    cmd::{ConversationLoadRequest, Output, conversation_id::PositionalIds},
+    ctx::Ctx,
+    output::print_json,
+};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Diff {
+    #[command(flatten)]
+    target: PositionalIds<true, false>,
+
+    /// The 1-based turn to show the changes of.
+    ///
+    /// Defaults to the most recent turn that changed files.
+    #[arg(long)]
+    turn: Option<usize>,
+}
+
+impl Diff {
+    pub(crate) fn conversation_load_request(&self) -> ConversationLoadRequest {
+        ConversationLoadRequest::explicit_or_session(&self.target)
+    }
+
+    pub(crate) fn run(self, ctx: &mut Ctx, handles: Vec<ConversationHandle>) -> Output {
+        let fs = ctx
+            .fs_backend
+            .as_deref()
+            .ok_or("no filesystem storage configured")?;
+
+        let Some(handle) = handles.into_iter().next() else {
+            return Ok(());
+        };
+
+        let checkpoints = TurnCheckpoints::new(fs, &handle.id())
+            .ok_or("no user storage configured, so no checkpoints are kept")?
+            .with_turns(&ctx.workspace.events(&handle)?);
+        let turn = resolve_turn(&checkpoints, self.turn)?;
+        let changes = checkpoints
+            .changes(ctx.workspace.root(), turn)
+            .map_err(|error| error.to_string())?;
+
+        if ctx.printer.format().is_json() {
+            let changes = changes
+                .iter()
+                .map(|change| json!({ "path": change.path, "diff": render(change, false) }))
+                .collect::<Vec<_>>();
+            print_json(&ctx.printer, &json!({ "turn": turn, "changes": changes }));
+            return Ok(());
+        }
+
+        if changes.is_empty() {
+            ctx.printer
+                .println(format!("Turn {turn} left no changes to workspace files."));
+            return Ok(());
+        }
+
+        let pretty = ctx.printer.pretty_printing_enabled();
+        for change in &changes {
+            ctx.printer.print(render(change, pretty));
+        }
+
+        Ok(())
+    }
+}
+
+/// Render a changed file as a unified diff.
+///
+/// Changes that have no textual diff — binary content, symlinks, permissions —
+/// are summarized on a single line.
+pub(super) fn render(change: &FileChange, pretty: bool) -> String {
+    let FileChange {
+        path,
+        before,
+        after,
+    } = change;
+
+    let Some((old, new)) = text(before)
+        .zip(text(after))
+        .filter(|(old, new)| old != new)
+    else {
+        return format!("{path}: {} -> {}\n", describe(before), describe(after));
+    };
+
+    let old_header = match before {
+        FileState::Absent => "/dev/null".to_owned(),
+        _ => format!("a/{path}"),
+    };
+    let new_header = match after {
+        FileState::Absent => "/dev/null".to_owned(),
+        _ => format!("b/{path}"),
+    };
+
+    let mut lines = vec![format!("--- {old_header}"), format!("+++ {new_header}")];
+    for hunk in TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .iter_hunks()
+    {
+        lines.extend(hunk.to_string().lines().map(str::to_owned));
+    }
+
+    let mut out = String::new();
+    for line in lines {
+        let line = if !pretty {
+            line
+        } else if line.starts_with("---") || line.starts_with("+++") {
+            line.bold().to_string()
+        } else if line.starts_with('@') {
+            line.cyan().to_string()
+        } else if line.starts_with('+') {
+            line.green().to_string()
+        } else if line.starts_with('-') {
+            line.red().to_string()
+        } else {
+            line
+        };
+
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// The text content of a file state, if it has one.
+fn text(state: &FileState) -> Option<&str> {
+    match state {
+        FileState::Absent => Some(""),
+        FileState::File { content, .. } => str::from_utf8(content).ok(),
+        FileState::Symlink { .. } => None,
+    }
+}
+
+/// A short description of a file state.
+fn describe(state: &FileState) -> String {
+    match state {
+        FileState::Absent => "absent".to_owned(),
+        FileState::File {
+            content,
+            mode: Some(mode),
+        } => format!("{} bytes, mode {:o}", content.len(), mode & 0o7777),
+        FileState::File {
+            content,
+            mode: None,
+        } => format!("{} bytes", content.len()),
+        FileState::Symlink { target } => format!("symlink to {target}"),
+    }
+}
+
+#[cfg(test)]
+#[path = "diff_tests.rs"]
+mod tests;
@@ -0,0 +1,74 @@
+use jp_tool::FileState;
+
+use super::*;
+
+fn file(content: &str) -> FileState {
+    FileState::File {
+        content: content.as_bytes().to_vec(),
+        mode: Some(0o100_644),
+    }
+}
+
+fn change(before: FileState, after: FileState) -> FileChange {
+    FileChange {
+        path: "src/lib.rs".into(),
+        before,
+        after,
+    }
+}
+
+#[test]
+fn test_render_modified_file() {
+    let out = render(&change(file("a\nb\nc\n"), file("a\nB\nc\n")), false);
+
+    assert_eq!(
+        out,
+        "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"
+    );
+}
+
+#[test]
+fn test_render_created_and_deleted_files() {
+    let created = render(&change(FileState::Absent, file("new\n")), false);
+    assert!(
+        created.starts_with("--- /dev/null\n+++ b/src/lib.rs\n"),
+        "{created}"
+    );
+    assert!(created.ends_with("+new\n"), "{created}");
+
+    let deleted = render(&change(file("old\n"), FileState::Absent), false);
+    assert!(
+        deleted.starts_with("--- a/src/lib.rs\n+++ /dev/null\n"),
+        "{deleted}"
+    );
+    assert!(deleted.ends_with("-old\n"), "{deleted}");
+}
+
+#[test]
+fn test_render_summarizes_changes_without_text_diff() {
+    let binary = FileState::File {
+        content: vec![0xff, 0xfe],
+        mode: None,
+    };
+    assert_eq!(
+        render(&change(binary, FileState::Absent), false),
+        "src/lib.rs: 2 bytes -> absent\n"
+    );
+
+    let symlink = FileState::Symlink {
+        target: "other.rs".into(),
+    };
+    assert_eq!(
+        render(&change(file("x"), symlink), false),
+        "src/lib.rs: 1 bytes, mode 644 -> symlink to other.rs\n"
+    );
+
+    let executable = FileState::File {
+        content: b"x".to_vec(),
+        mode: Some(0o100_755),
+    };
+    assert_eq!(
+        render(&change(file("x"), executable), false),
+        "src/lib.rs: 1 bytes, mode 644 -> 1 bytes, mode 755\n"
+    );
+}
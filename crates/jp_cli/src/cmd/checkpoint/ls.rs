@@ -0,0 +1,65 @@
+use comfy_table::{Cell, Row};
+use jp_workspace::ConversationHandle;
+
+use super::TurnCheckpoints;
+use crate::{
+    cmd::{ConversationLoadRequest, Output, conversation_id::PositionalIds},
+    ctx::Ctx,
+    output::print_table,
+};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Ls {
+    #[command(flatten)]
+    target: PositionalIds<true, false>,
+}
+
+impl Ls {
+    pub(crate) fn conversation_load_request(&self) -> ConversationLoadRequest {
+        ConversationLoadRequest::explicit_or_session(&self.target)
+    }
+
+    pub(crate) fn run(self, ctx: &mut Ctx, handles: Vec<ConversationHandle>) -> Output {
+        let fs = ctx
+            .fs_backend
+            .as_deref()
+            .ok_or("no filesystem storage configured")?;
+
+        let Some(handle) = handles.into_iter().next() else {
+            return Ok(());
+        };
+
+        let checkpoints = TurnCheckpoints::new(fs, &handle.id())
+            .ok_or("no user storage configured, so no checkpoints are kept")?
+            .with_turns(&ctx.workspace.events(&handle)?);
+        let turns = checkpoints.turns().map_err(|error| error.to_string())?;
+
+        if turns.is_empty() && !ctx.printer.format().is_json() {
+            ctx.printer
+                .println("No turn in this conversation changed any files.");
+            return Ok(());
+        }
+
+        let mut header = Row::new();
+        header.add_cell(Cell::new("Turn"));
+        header.add_cell(Cell::new("Files"));
+
+        let mut rows = vec![];
+        for turn in turns {
+            let paths = checkpoints
+                .entries(turn)
+                .map_err(|error| error.to_string())?
+                .into_iter()
+                .map(|entry| entry.path.into_string())
+                .collect::<Vec<_>>();
+
+            let mut row = Row::new();
+            row.add_cell(Cell::new(turn));
+            row.add_cell(Cell::new(paths.join("\n")));
+            rows.push(row);
+        }
+
+        print_table(&ctx.printer, header, rows, false);
+        Ok(())
+    }
+}
@@ -0,0 +1,254 @@
+use std::fs;
+
+use camino_tempfile::{Utf8TempDir, tempdir};
+use jp_conversation::event::ConversationEvent;
+
+use super::*;
+
+struct Setup {
+    _tmp: Utf8TempDir,
+    root: Utf8PathBuf,
+    checkpoints: TurnCheckpoints,
+}
+
+fn setup() -> Setup {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path().join("workspace");
+    fs::create_dir_all(&root).unwrap();
+    let ids = (1..=10).map(|turn| turn.to_string()).collect::<Vec<_>>();
+    let ids = ids.iter().map(String::as_str).collect::<Vec<_>>();
+    let checkpoints =
+        TurnCheckpoints::at(tmp.path().join("checkpoints")).with_turns(&stream_with_ids(&ids));
+
+    Setup {
+        _tmp: tmp,
+        root,
+        checkpoints,
+    }
+}
+
+/// A stream of `turns` turns that have no checkpoint ID recorded.
+fn stream(turns: usize) -> ConversationStream {
+    let mut stream = ConversationStream::new_test();
+    for turn in 1..=turns {
+        stream.start_turn(format!("turn {turn}"));
+    }
+
+    stream
+}
+
+/// A stream with one turn per ID, each with its checkpoint ID recorded.
+fn stream_with_ids(ids: &[&str]) -> ConversationStream {
+    let mut stream = stream(ids.len());
+    let starts = stream
+        .iter_mut()
+        .filter(|event| event.event.is_turn_start())
+        .map(|event| event.event);
+
+    for (event, id) in starts.zip(ids) {
+        event.add_metadata_field(TURN_CHECKPOINT_KEY, *id);
+    }
+
+    stream
+}
+
+/// Record `path` in the checkpoint of `turn`, then write `content` to it.
+fn change(setup: &Setup, turn: usize, path: &str, content: Option<&str>) {
+    setup
+        .checkpoints
+        .turn(turn)
+        .unwrap()
+        .record(&setup.root, path.into())
+        .unwrap();
+
+    match content {
+        Some(content) => fs::write(setup.root.join(path), content).unwrap(),
+        None => fs::remove_file(setup.root.join(path)).unwrap(),
+    }
+}
+
+fn file(content: &str) -> FileState {
+    FileState::File {
+        content: content.as_bytes().to_vec(),
+        mode: None,
+    }
+}
+
+fn without_mode(state: FileState) -> FileState {
+    match state {
+        FileState::File { content, .. } => FileState::File {
+            content,
+            mode: None,
+        },
+        state => state,
+    }
+}
+
+#[test]
+fn test_turns_are_sorted_numerically() {
+    let setup = setup();
+    for turn in [10, 2, 1] {
+        change(&setup, turn, &format!("{turn}.txt"), Some("x"));
+    }
+
+    assert_eq!(setup.checkpoints.turns().unwrap(), vec![1, 2, 10]);
+    assert!(setup._tmp.path().join("checkpoints/10").is_dir());
+}
+
+#[test]
+fn test_turns_keep_their_checkpoint_when_earlier_turns_are_removed() {
+    let setup = setup();
+    let mut stream = stream_with_ids(&["a", "b", "c"]);
+    let checkpoints = setup.checkpoints.clone().with_turns(&stream);
+    assert_eq!(checkpoints.id(2), Some("b"));
+
+    checkpoints
+        .turn(3)
+        .unwrap()
+        .record(&setup.root, "a.txt".into())
+        .unwrap();
+    assert_eq!(checkpoints.turns().unwrap(), vec![3]);
+
+    stream.retain_last_turns(2);
+    let checkpoints = checkpoints.with_turns(&stream);
+    assert_eq!(checkpoints.turns().unwrap(), vec![2]);
+    assert_eq!(checkpoints.entries(2).unwrap().len(), 1);
+}
+
+#[test]
+fn test_turns_ignore_checkpoints_of_removed_turns() {
+    let setup = setup();
+    let mut stream = stream_with_ids(&["a", "b"]);
+    let checkpoints = setup.checkpoints.clone().with_turns(&stream);
+    checkpoints
+        .turn(2)
+        .unwrap()
+        .record(&setup.root, "a.txt".into())
+        .unwrap();
+
+    stream.retain_first_turns(1);
+    assert_eq!(
+        checkpoints.with_turns(&stream).turns().unwrap(),
+        Vec::<usize>::new()
+    );
+}
+
+#[test]
+fn test_turns_without_checkpoint_id_have_no_checkpoint() {
+    let setup = setup();
+    change(&setup, 1, "a.txt", Some("x"));
+
+    let checkpoints = setup.checkpoints.clone().with_turns(&stream(2));
+    assert_eq!(checkpoints.id(1), None);
+    assert!(checkpoints.turn(1).is_none());
+    assert_eq!(checkpoints.turns().unwrap(), Vec::<usize>::new());
+    assert_eq!(checkpoints.entries(1).unwrap(), vec![]);
+    assert_eq!(
+        checkpoints.undo(&setup.root, 1).unwrap(),
+        Vec::<Utf8PathBuf>::new()
+    );
+    assert_eq!(fs::read_to_string(setup.root.join("a.txt")).unwrap(), "x");
+}
+
+#[test]
+fn test_remove_all() {
+    let setup = setup();
+    change(&setup, 1, "a.txt", Some("x"));
+    change(&setup, 2, "b.txt", Some("y"));
+
+    setup.checkpoints.remove_all().unwrap();
+    assert_eq!(setup.checkpoints.turns().unwrap(), Vec::<usize>::new());
+
+    // Removing checkpoints that do not exist is not an error.
+    setup.checkpoints.remove_all().unwrap();
+}
+
+#[test]
+fn test_changes_compare_against_the_next_turn() {
+    let setup = setup();
+    fs::write(setup.root.join("a.txt"), "one").unwrap();
+
+    change(&setup, 1, "a.txt", Some("two"));
+    change(&setup, 1, "b.txt", Some("new"));
+    change(&setup, 2, "a.txt", Some("three"));
+
+    let changes = setup
+        .checkpoints
+        .changes(&setup.root, 1)
+        .unwrap()
+        .into_iter()
+        .map(|change| {
+            (
+                change.path,
+                without_mode(change.before),
+                without_mode(change.after),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(changes, vec![
+        ("a.txt".into(), file("one"), file("two")),
+        ("b.txt".into(), FileState::Absent, file("new")),
+    ]);
+}
+
+#[test]
+fn test_changes_skip_files_restored_to_their_state() {
+    let setup = setup();
+    fs::write(setup.root.join("a.txt"), "one").unwrap();
+
+    change(&setup, 1, "a.txt", Some("two"));
+    fs::write(setup.root.join("a.txt"), "one").unwrap();
+
+    assert_eq!(setup.checkpoints.changes(&setup.root, 1).unwrap(), vec![]);
+}
+
+#[test]
+fn test_undo_restores_the_turn_and_later_turns() {
+    let setup = setup();
+    fs::write(setup.root.join("a.txt"), "one").unwrap();
+    fs::write(setup.root.join("c.txt"), "kept").unwrap();
+
+    change(&setup, 1, "c.txt", Some("changed by turn 1"));
+    change(&setup, 2, "a.txt", Some("two"));
+    change(&setup, 3, "a.txt", Some("three"));
+    change(&setup, 3, "b.txt", Some("new"));
+
+    let restored = setup.checkpoints.undo(&setup.root, 2).unwrap();
+
+    assert_eq!(restored, vec![
+        Utf8PathBuf::from("a.txt"),
+        Utf8PathBuf::from("b.txt")
+    ]);
+    assert_eq!(fs::read_to_string(setup.root.join("a.txt")).unwrap(), "one");
+    assert!(!setup.root.join("b.txt").exists());
+    assert_eq!(
+        fs::read_to_string(setup.root.join("c.txt")).unwrap(),
+        "changed by turn 1"
+    );
+    assert_eq!(setup.checkpoints.turns().unwrap(), vec![1]);
+}
+
+#[test]
+fn test_undo_restores_deleted_files() {
+    let setup = setup();
+    fs::write(setup.root.join("a.txt"), "one").unwrap();
+
+    change(&setup, 1, "a.txt", None);
+    setup.checkpoints.undo(&setup.root, 1).unwrap();
+
+    assert_eq!(fs::read_to_string(setup.root.join("a.txt")).unwrap(), "one");
+    assert_eq!(setup.checkpoints.turns().unwrap(), Vec::<usize>::new());
+}
+
+#[test]
+fn test_resolve_turn() {
+    let setup = setup();
+    assert!(resolve_turn(&setup.checkpoints, None).is_err());
+    assert!(resolve_turn(&setup.checkpoints, Some(0)).is_err());
+    assert_eq!(resolve_turn(&setup.checkpoints, Some(4)).unwrap(), 4);
+
+    change(&setup, 3, "a.txt", Some("x"));
+    change(&setup, 5, "a.txt", Some("y"));
+    assert_eq!(resolve_turn(&setup.checkpoints, None).unwrap(), 5);
+}
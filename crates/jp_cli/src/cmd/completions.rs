@@ -0,0 +1,82 @@
+use std::io::Write as _;
+
+use clap::Command;
+use clap_complete::env::{Bash, EnvCompleter, Fish, Zsh};
+use jp_printer::Printer;
+
+use crate::{cmd::Output, completion::COMPLETE_VAR};
+
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = INSTALL_HELP)]
+pub(crate) struct Completions {
+    /// The shell to print the completion script for.
+    shell: Shell,
+}
+
+/// The shells completion scripts are available for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+const INSTALL_HELP: &str = "\
+The script registers `jp` as its own completer, so completions always match the
+installed version, including conversation IDs, labels, model aliases, tools,
+`--cfg` files and installed plugins.
+
+Print it to the appropriate file for your shell:
+
+  bash:
+    jp completions bash > ~/.local/share/bash-completion/completions/jp
+
+  zsh:
+    jp completions zsh > ~/.zfunc/_jp
+    # Then add to ~/.zshrc: fpath+=~/.zfunc; autoload -Uz compinit; compinit
+
+  fish:
+    jp completions fish > ~/.config/fish/completions/jp.fish";
+
+impl Completions {
+    pub(crate) fn run(&self, cmd: &Command, printer: &Printer) -> Output {
+        let mut script = vec![];
+        registration(self.shell, cmd, &completer(), &mut script)?;
+
+        let mut out = printer.out_writer();
+        out.write_all(&script)?;
+        out.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Write the script that registers `completer` as the completer of `cmd`.
+fn registration(
+    shell: Shell,
+    cmd: &Command,
+    completer: &str,
+    buf: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    let shell: &dyn EnvCompleter = match shell {
+        Shell::Bash => &Bash,
+        Shell::Zsh => &Zsh,
+        Shell::Fish => &Fish,
+    };
+
+    let name = cmd.get_name();
+    let bin = cmd.get_bin_name().unwrap_or(name);
+    shell.write_registration(COMPLETE_VAR, name, bin, completer, buf)
+}
+
+/// The program the shell runs to complete, the same one that printed the
+/// script.
+fn completer() -> String {
+    std::env::args_os()
+        .next()
+        .map_or_else(|| "jp".to_owned(), |arg| arg.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+#[path = "completions_tests.rs"]
+mod tests;
@@ -0,0 +1,18 @@
+use clap::CommandFactory as _;
+
+use super::*;
+use crate::Cli;
+
+#[test]
+fn test_registration_calls_back_into_jp() {
+    let cmd = Cli::command();
+
+    for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+        let mut script = vec![];
+        registration(shell, &cmd, "/usr/local/bin/jp", &mut script).unwrap();
+        let script = String::from_utf8(script).unwrap();
+
+        assert!(script.contains(COMPLETE_VAR), "{shell:?}: {script}");
+        assert!(script.contains("/usr/local/bin/jp"), "{shell:?}: {script}");
+    }
+}
@@ -1,11 +1,19 @@
 use camino::{FromPathBufError, Utf8Path, Utf8PathBuf};
-use jp_config::fs::{ConfigFile, ConfigLoader, ConfigLoaderError, user_global_config_dir};
-use jp_workspace::ConversationHandle;
+use jp_config::{
+    PartialAppConfig,
+    env::Env,
+    fs::{ConfigFile, ConfigLoader, ConfigLoaderError, load_partial, user_global_config_dir},
+};
+use jp_workspace::{ConversationHandle, Workspace};
 
 use super::{ConversationLoadRequest, Output};
-use crate::ctx::Ctx;
+use crate::ctx::{Ctx, IntoPartialAppConfig};
 
+mod edit;
+mod explain;
 mod fmt;
+mod lint;
+mod schema;
 mod set;
 mod show;
 
@@ -18,39 +26,86 @@ pub(crate) struct Config {
 impl Config {
     pub(crate) async fn run(self, ctx: &mut Ctx, handles: Vec<ConversationHandle>) -> Output {
         match self.command {
-            Commands::Show(args) => {
-                debug_assert!(handles.is_empty());
-                args.run(ctx)
-            }
+            Commands::Show(args) => args.run(ctx, &handles),
+            Commands::Explain(args) => args.run(ctx, &handles),
             Commands::Set(args) => args.run(ctx, handles).await,
+            Commands::Edit(args) => args.run(ctx).await,
             Commands::Fmt(args) => {
                 debug_assert!(handles.is_empty());
                 args.run(ctx)
             }
+            Commands::Schema(args) => args.run(ctx),
+            Commands::Lint(args) => args.run(ctx),
         }
     }
 
     pub(crate) fn conversation_load_request(&self) -> ConversationLoadRequest {
         match &self.command {
+            Commands::Show(args) => args.conversation_load_request(),
+            Commands::Explain(args) => args.conversation_load_request(),
             Commands::Set(args) => args.conversation_load_request(),
-            Commands::Show(_) | Commands::Fmt(_) => ConversationLoadRequest::none(),
+            Commands::Edit(_) | Commands::Fmt(_) | Commands::Schema(_) | Commands::Lint(_) => {
+                ConversationLoadRequest::none()
+            }
         }
     }
 }
 
+impl IntoPartialAppConfig for Config {
+    fn apply_cli_config(
+        &self,
+        _: Option<&Workspace>,
+        partial: PartialAppConfig,
+        _: Option<&PartialAppConfig>,
+        _: &Env,
+    ) -> std::result::Result<PartialAppConfig, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(partial)
+    }
+
+    /// `show` and `explain` resolve the configuration of the conversation
+    /// they target, if any.
+    fn apply_conversation_config(
+        &self,
+        workspace: &Workspace,
+        partial: PartialAppConfig,
+        _: Option<&PartialAppConfig>,
+        handle: &ConversationHandle,
+    ) -> std::result::Result<PartialAppConfig, Box<dyn std::error::Error + Send + Sync>> {
+        let config = workspace.events(handle)?.config().map(|c| c.to_partial())?;
+
+        load_partial(partial, config).map_err(Into::into)
+    }
+}
+
 #[derive(Debug, clap::Subcommand)]
 enum Commands {
     /// Show the current configuration.
     #[command(name = "show")]
     Show(show::Show),
 
+    /// Explain where the value of a configuration option comes from.
+    #[command(name = "explain")]
+    Explain(explain::Explain),
+
     /// Set a configuration option.
     #[command(name = "set")]
     Set(set::Set),
 
+    /// Edit a configuration file, in an editor or interactively.
+    #[command(name = "edit")]
+    Edit(edit::Edit),
+
     /// Format a configuration file.
     #[command(name = "fmt")]
     Fmt(fmt::Fmt),
+
+    /// Print the JSON Schema of configuration files.
+    #[command(name = "schema")]
+    Schema(schema::Schema),
+
+    /// Check configuration files for unknown keys and invalid values.
+    #[command(name = "lint")]
+    Lint(lint::Lint),
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, clap::Args)]
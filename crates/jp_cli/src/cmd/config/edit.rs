@@ -0,0 +1,516 @@
+use std::fs;
+
+use jp_config::{
+    AppConfig, PartialAppConfig, env::Env, model::id::ProviderId, providers::llm::LlmProviderConfig,
+};
+use jp_editor::EditOutcome;
+use jp_inquire::prompt::{PromptBackend, TerminalPromptBackend};
+use jp_llm::provider::get_provider;
+use jp_printer::Printer;
+use serde_json::{Map, Value, json};
+
+use super::{
+    Target,
+    explain::{Layer, load_layers, lookup, trace},
+    lint::shapes,
+};
+use crate::{
+    cmd::{Error, Output},
+    ctx::Ctx,
+    editor,
+};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Edit {
+    /// Walk through common settings, instead of opening the file in an editor.
+    ///
+    /// Shows the current value of each setting and the layer it comes from,
+    /// and writes only the changed keys back to the file, keeping its comments
+    /// and formatting.
+    #[arg(short, long)]
+    interactive: bool,
+
+    #[command(flatten)]
+    target: Target,
+}
+
+impl Edit {
+    pub(crate) async fn run(self, ctx: &Ctx) -> Output {
+        let Some(mut file) = self.target.config_file(ctx)? else {
+            return Err("No configuration file found for the given target.".into());
+        };
+
+        if !self.interactive {
+            let editor = editor::build_editor_backend(&ctx.config().editor)
+                .ok_or(crate::error::Error::MissingEditor)?;
+
+            let (outcome, content, mut guard) =
+                editor::open(file.path.clone(), editor::Options::new(), editor.as_ref())?;
+
+            if outcome == EditOutcome::Cancelled {
+                return Err("The editor was cancelled, the file is unchanged.".into());
+            }
+
+            // An invalid file is reverted by the guard, instead of breaking
+            // every later command.
+            file.content = content;
+            file.deserialize_partial()?;
+            guard.disarm();
+            return Ok(());
+        }
+
+        if !ctx.term.is_tty {
+            return Err("Interactive editing requires a terminal.".into());
+        }
+
+        let config = ctx.config();
+        let layers = load_layers(ctx, None)?;
+        let schema = AppConfig::json_schema();
+        let mut wizard = Wizard {
+            prompts: &TerminalPromptBackend,
+            printer: &ctx.printer,
+            schema: &schema,
+            layers: &layers,
+            resolved: serde_json::to_value(config.to_partial())?,
+            llm: &config.providers.llm,
+            env: &ctx.env,
+            providers: PROVIDERS,
+            changes: Value::Object(Map::new()),
+        };
+
+        let Some(changes) = wizard.run().await? else {
+            ctx.printer.println("Discarded changes.");
+            return Ok(());
+        };
+
+        if changes.as_object().is_none_or(Map::is_empty) {
+            ctx.printer.println("No changes to save.");
+            return Ok(());
+        }
+
+        let delta: PartialAppConfig = serde_json::from_value(changes)?;
+        file.merge_delta(&delta)?;
+
+        if let Some(parent) = file.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&file.path, &file.content)?;
+
+        ctx.printer.println(format!("Updated {}", file.path));
+        Ok(())
+    }
+}
+
+/// The providers offered when selecting a model.
+///
+/// Providers that are not implemented yet are left out.
+const PROVIDERS: &[ProviderId] = &[
+    ProviderId::Anthropic,
+    ProviderId::Cerebras,
+    ProviderId::Google,
+    ProviderId::Llamacpp,
+    ProviderId::Ollama,
+    ProviderId::Openai,
+    ProviderId::Openrouter,
+];
+
+const MODEL_KEY: &str = "assistant.model.id";
+const REASONING_KEY: &str = "assistant.model.parameters.reasoning";
+const TOOLS_KEY: &str = "conversation.tools";
+const STYLE_KEY: &str = "style";
+
+/// The tool settings offered for each tool.
+const TOOL_FIELDS: &[&str] = &["enable", "run"];
+
+const SAVE: &str = "Save and exit";
+const DISCARD: &str = "Discard changes";
+const BACK: &str = "Back";
+const CUSTOM: &str = "Custom…";
+const OTHER: &str = "Other (enter manually)";
+
+/// Walks the user through common settings, collecting the changes.
+struct Wizard<'a> {
+    prompts: &'a dyn PromptBackend,
+    printer: &'a Printer,
+
+    /// The JSON Schema of configuration files, used to find the values each
+    /// setting accepts.
+    schema: &'a Value,
+
+    /// The configuration layers, to show where a current value comes from.
+    layers: &'a [Layer],
+
+    /// The resolved configuration.
+    resolved: Value,
+    llm: &'a LlmProviderConfig,
+
+    /// The environment providers are created with.
+    env: &'a Env,
+    providers: &'a [ProviderId],
+
+    /// The changed settings, as a partial configuration.
+    changes: Value,
+}
+
+impl<'a> Wizard<'a> {
+    /// Run the main menu until the user saves or discards the changes.
+    ///
+    /// Returns the changes to save, or `None` if they are discarded.
+    async fn run(&mut self) -> Result<Option<Value>, Error> {
+        loop {
+            let options = vec![
+                format!("Model: {}", self.describe(MODEL_KEY)),
+                format!("Reasoning: {}", self.describe(REASONING_KEY)),
+                "Tools…".to_owned(),
+                "Style…".to_owned(),
+                SAVE.to_owned(),
+                DISCARD.to_owned(),
+            ];
+
+            match self.select("Configure", &options)? {
+                0 => self.edit_model().await?,
+                1 => self.edit(REASONING_KEY, self.property(REASONING_KEY)?)?,
+                2 => self.edit_tools()?,
+                3 => self.edit_fields(STYLE_KEY, self.property(STYLE_KEY)?, None)?,
+                4 => return Ok(Some(self.changes.clone())),
+                _ => return Ok(None),
+            }
+        }
+    }
+
+    /// Select a provider, and one of the models it lists.
+    ///
+    /// Falls back to entering the model name when the provider cannot list
+    /// its models, e.g. because no API key is set.
+    async fn edit_model(&mut self) -> Result<(), Error> {
+        let options: Vec<_> = self
+            .providers
+            .iter()
+            .map(|id| id.as_str().to_owned())
+            .collect();
+        let provider = self.providers[self.select("Provider", &options)?];
+
+        let models = match get_provider(provider, self.llm, self.env) {
+            Ok(provider) => provider.models().await,
+            Err(error) => Err(error),
+        };
+
+        let mut names: Vec<String> = match models {
+            Ok(models) => models.into_iter().map(|model| model.id.name.0).collect(),
+            Err(error) => {
+                self.printer
+                    .eprintln(format!("Unable to list the models of {provider}: {error}"));
+                vec![]
+            }
+        };
+
+        names.sort();
+        names.dedup();
+
+        let name = if names.is_empty() {
+            self.text("Model name", None)?
+        } else {
+            names.push(OTHER.to_owned());
+            match self.select("Model", &names)? {
+                i if i + 1 == names.len() => self.text("Model name", None)?,
+                i => names.swap_remove(i),
+            }
+        };
+
+        if name.is_empty() {
+            return Ok(());
+        }
+
+        self.set(
+            MODEL_KEY,
+            json!({ "provider": provider.as_str(), "name": name }),
+        );
+        Ok(())
+    }
+
+    /// Select a tool, then edit its enable and run settings.
+    fn edit_tools(&mut self) -> Result<(), Error> {
+        let mut tools = vec!["*".to_owned()];
+        if let Some(configured) = lookup(&self.resolved, TOOLS_KEY).and_then(Value::as_object) {
+            tools.extend(configured.keys().filter(|name| *name != "*").cloned());
+        }
+
+        loop {
+            let mut options = tools.clone();
+            options.push(BACK.to_owned());
+
+            let Some(name) = tools.get(self.select("Tool", &options)?) else {
+                return Ok(());
+            };
+
+            let key = format!("{TOOLS_KEY}.{name}");
+            self.edit_fields(&key, self.property(&key)?, Some(TOOL_FIELDS))?;
+        }
+    }
+
+    /// Show the fields of an object, and edit the selected one.
+    ///
+    /// `only` limits the menu to the given fields.
+    fn edit_fields(
+        &mut self,
+        key: &str,
+        schema: &'a Value,
+        only: Option<&[&str]>,
+    ) -> Result<(), Error> {
+        let fields: Vec<(&String, &Value)> = properties(self.schema, schema)
+            .into_iter()
+            .flatten()
+            .filter(|(name, _)| only.is_none_or(|only| only.contains(&name.as_str())))
+            .collect();
+
+        loop {
+            let mut options: Vec<String> = fields
+                .iter()
+                .map(|(name, _)| format!("{name}: {}", self.describe(&format!("{key}.{name}"))))
+                .collect();
+            options.push(BACK.to_owned());
+
+            let Some(&(name, schema)) = fields.get(self.select(key, &options)?) else {
+                return Ok(());
+            };
+
+            self.edit(&format!("{key}.{name}"), schema)?;
+        }
+    }
+
+    /// Edit a single setting, offering the values its schema allows.
+    fn edit(&mut self, key: &str, schema: &'a Value) -> Result<(), Error> {
+        let choices = choices(self.schema, schema);
+        let fields = properties(self.schema, schema);
+
+        if choices.is_empty() {
+            if fields.is_some() {
+                return self.edit_fields(key, schema, None);
+            }
+
+            let current = self.current(key).map(display);
+            let input = self.text(key, current.as_deref())?;
+            if !input.is_empty() {
+                self.set(key, parse(self.schema, schema, &input));
+            }
+
+            return Ok(());
+        }
+
+        let mut options: Vec<String> = choices.iter().map(display).collect();
+        if fields.is_some() {
+            options.push(CUSTOM.to_owned());
+        }
+        options.push(BACK.to_owned());
+
+        let index = self.select(key, &options)?;
+        if let Some(value) = choices.get(index) {
+            self.set(key, value.clone());
+        } else if index == choices.len() && fields.is_some() {
+            self.edit_fields(key, schema, None)?;
+        }
+
+        Ok(())
+    }
+
+    /// Record a change, unless it makes the configuration invalid.
+    fn set(&mut self, key: &str, value: Value) {
+        let mut changes = self.changes.clone();
+        insert(&mut changes, key, value);
+
+        match serde_json::from_value::<PartialAppConfig>(changes.clone()) {
+            Ok(_) => self.changes = changes,
+            Err(error) => self
+                .printer
+                .eprintln(format!("Invalid value for `{key}`: {error}")),
+        }
+    }
+
+    /// The current value of `key`, including unsaved changes.
+    fn current(&self, key: &str) -> Option<&Value> {
+        lookup(&self.changes, key).or_else(|| lookup(&self.resolved, key))
+    }
+
+    /// The current value of `key`, and where it comes from.
+    fn describe(&self, key: &str) -> String {
+        let Some(value) = self.current(key) else {
+            return "not set".to_owned();
+        };
+
+        let value = display(value);
+        if lookup(&self.changes, key).is_some() {
+            return format!("{value} (unsaved)");
+        }
+
+        let source = trace(self.layers, key).ok().and_then(|steps| {
+            let step = steps.iter().rev().find(|step| !step.layer.ignored)?;
+            let source = &step.layer.source;
+            Some(match source.location(key, step.line) {
+                Some(location) => format!("{}, {location}", source.name()),
+                None => source.name().to_owned(),
+            })
+        });
+
+        match source {
+            Some(source) => format!("{value} ({source})"),
+            None => value,
+        }
+    }
+
+    /// The schema of the setting at `key`.
+    fn property(&self, key: &str) -> Result<&'a Value, Error> {
+        let root = self.schema;
+        key.split('.')
+            .try_fold(root, |schema, segment| {
+                let objects = shapes(root, schema, "additionalProperties");
+                objects
+                    .iter()
+                    .find_map(|shape| shape.get("properties")?.get(segment))
+                    .or_else(|| {
+                        objects
+                            .iter()
+                            .find_map(|shape| shape.get("additionalProperties"))
+                            .filter(|schema| schema.is_object())
+                    })
+            })
+            .ok_or_else(|| format!("Unknown configuration key `{key}`.").into())
+    }
+
+    /// Ask to select one of `options`, returning its index.
+    fn select(&self, message: &str, options: &[String]) -> Result<usize, Error> {
+        let mut writer = self.printer.prompt_writer();
+        let answer = self
+            .prompts
+            .select(message, options.to_vec(), Some(0), &mut writer)?;
+
+        // Options can describe the current value after their name, so an
+        // answer matches the first option it is a prefix of.
+        options
+            .iter()
+            .position(|option| *option == answer)
+            .or_else(|| {
+                options
+                    .iter()
+                    .position(|option| option.starts_with(&answer))
+            })
+            .ok_or_else(|| format!("Unknown option `{answer}`.").into())
+    }
+
+    /// Ask for a value, returning the trimmed input.
+    fn text(&self, message: &str, default: Option<&str>) -> Result<String, Error> {
+        let mut writer = self.printer.prompt_writer();
+        let input = self.prompts.text(message, default, &mut writer)?;
+        Ok(input.trim().to_owned())
+    }
+}
+
+/// The fixed values `schema` allows, following `$ref` pointers and unions.
+fn choices(root: &Value, schema: &Value) -> Vec<Value> {
+    let schema = resolve(root, schema);
+    let mut out = vec![];
+
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        out.extend(values.iter().cloned());
+    } else if let Some(value) = schema.get("const") {
+        out.push(value.clone());
+    } else if has_type(schema, "boolean") {
+        out.extend([Value::Bool(true), Value::Bool(false)]);
+    }
+
+    let variants = ["anyOf", "oneOf"]
+        .into_iter()
+        .filter_map(|union| schema.get(union)?.as_array())
+        .flatten();
+
+    for value in variants.flat_map(|variant| choices(root, variant)) {
+        if !out.contains(&value) {
+            out.push(value);
+        }
+    }
+
+    out.retain(|value| !value.is_null());
+    out
+}
+
+/// The fields of the object `schema` describes, if any.
+fn properties<'a>(root: &'a Value, schema: &'a Value) -> Option<&'a Map<String, Value>> {
+    shapes(root, schema, "additionalProperties")
+        .into_iter()
+        .find_map(|shape| shape.get("properties")?.as_object())
+        .filter(|properties| !properties.is_empty())
+}
+
+/// Follow the `$ref` pointer of `schema`, if it has one.
+fn resolve<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|pointer| root.pointer(pointer.trim_start_matches('#')))
+        .unwrap_or(schema)
+}
+
+fn has_type(schema: &Value, name: &str) -> bool {
+    match schema.get("type") {
+        Some(Value::String(ty)) => ty == name,
+        Some(Value::Array(types)) => types.iter().any(|ty| ty == name),
+        _ => false,
+    }
+}
+
+/// Parse entered text as a value of `schema`.
+///
+/// Text is taken as a JSON value, such as a number, unless the setting accepts
+/// strings or the text is not valid JSON.
+fn parse(root: &Value, schema: &Value, input: &str) -> Value {
+    match serde_json::from_str(input) {
+        Ok(value) if !accepts_string(root, schema) => value,
+        _ => Value::String(input.to_owned()),
+    }
+}
+
+/// Whether `schema`, or any of its variants, accepts strings.
+fn accepts_string(root: &Value, schema: &Value) -> bool {
+    let schema = resolve(root, schema);
+    has_type(schema, "string")
+        || ["anyOf", "oneOf"]
+            .into_iter()
+            .filter_map(|union| schema.get(union)?.as_array())
+            .flatten()
+            .any(|variant| accepts_string(root, variant))
+}
+
+/// Insert `value` at the dotted `key`, creating objects along the way.
+fn insert(target: &mut Value, key: &str, value: Value) {
+    let mut target = target;
+    for segment in key.split('.') {
+        if !target.is_object() {
+            *target = Value::Object(Map::new());
+        }
+
+        target = target
+            .as_object_mut()
+            .expect("target is an object")
+            .entry(segment)
+            .or_insert(Value::Null);
+    }
+
+    *target = value;
+}
+
+/// Show a value the way it is written in a configuration file.
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        Value::Object(map) => match (map.get("provider"), map.get("name")) {
+            (Some(Value::String(provider)), Some(Value::String(name))) if map.len() == 2 => {
+                format!("{provider}/{name}")
+            }
+            _ => value.to_string(),
+        },
+        _ => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+#[path = "edit_tests.rs"]
+mod tests;
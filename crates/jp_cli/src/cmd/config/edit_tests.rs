@@ -0,0 +1,206 @@
+use jp_config::{conversation::tool::RunMode, env::Env};
+use jp_inquire::prompt::MockPromptBackend;
+use jp_printer::OutputFormat;
+use pretty_assertions::assert_eq;
+
+use super::*;
+use crate::cmd::config::explain::Source;
+
+struct Setup {
+    prompts: MockPromptBackend,
+    printer: Printer,
+    schema: Value,
+    layers: Vec<Layer>,
+    config: AppConfig,
+    env: Env,
+}
+
+impl Setup {
+    fn new(prompts: MockPromptBackend) -> Self {
+        Self {
+            prompts,
+            printer: Printer::memory(OutputFormat::Text).0,
+            schema: AppConfig::json_schema(),
+            layers: vec![],
+            config: AppConfig::new_test(),
+            env: Env::process(),
+        }
+    }
+
+    fn wizard(&self, resolved: Value) -> Wizard<'_> {
+        Wizard {
+            prompts: &self.prompts,
+            printer: &self.printer,
+            schema: &self.schema,
+            layers: &self.layers,
+            resolved,
+            llm: &self.config.providers.llm,
+            env: &self.env,
+            providers: &[ProviderId::Test],
+            changes: Value::Object(Map::new()),
+        }
+    }
+}
+
+#[test]
+fn test_choices() {
+    let schema = AppConfig::json_schema();
+    let wizard = Setup::new(MockPromptBackend::new());
+    let wizard = wizard.wizard(Value::Null);
+
+    let reasoning = wizard.property(REASONING_KEY).unwrap();
+    assert_eq!(choices(&schema, reasoning), [json!("off"), json!("auto")]);
+    assert!(properties(&schema, reasoning).is_some());
+
+    let run = wizard
+        .property("conversation.tools.fs_read_file.run")
+        .unwrap();
+    assert_eq!(choices(&schema, run), [
+        json!("ask"),
+        json!("unattended"),
+        json!("edit"),
+        json!("skip"),
+    ]);
+
+    let enable = wizard.property("conversation.tools.*.enable").unwrap();
+    assert!(choices(&schema, enable).contains(&json!(true)));
+
+    let name = wizard.property("assistant.name").unwrap();
+    assert!(choices(&schema, name).is_empty());
+    assert_eq!(parse(&schema, name, "42"), json!("42"));
+
+    let max_tokens = wizard
+        .property("assistant.model.parameters.max_tokens")
+        .unwrap();
+    assert_eq!(parse(&schema, max_tokens, "42"), json!(42));
+
+    assert!(wizard.property("assistant.nmae").is_err());
+}
+
+#[test]
+fn test_insert() {
+    let mut value = json!({ "reasoning": "auto" });
+    insert(&mut value, "reasoning.effort", json!("high"));
+    insert(&mut value, "model.id.name", json!("mock-model"));
+
+    assert_eq!(
+        value,
+        json!({
+            "reasoning": { "effort": "high" },
+            "model": { "id": { "name": "mock-model" } },
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_edit_model_lists_provider_models() {
+    let setup = Setup::new(MockPromptBackend::new().with_select_responses([
+        "Model",
+        "test",
+        "mock-model",
+        SAVE,
+    ]));
+
+    // Selecting a model that is not listed fails, but the test provider can't
+    // be configured in a file, so the selected model is rejected.
+    let changes = setup.wizard(json!({})).run().await.unwrap();
+    assert_eq!(changes, Some(json!({})));
+}
+
+#[tokio::test]
+async fn test_edit_custom_reasoning() {
+    let setup = Setup::new(MockPromptBackend::new().with_select_responses([
+        "Reasoning",
+        CUSTOM,
+        "effort",
+        "high",
+        BACK,
+        SAVE,
+    ]));
+
+    let changes = setup.wizard(json!({})).run().await.unwrap();
+
+    assert_eq!(
+        changes,
+        Some(json!({
+            "assistant": { "model": { "parameters": { "reasoning": { "effort": "high" } } } },
+        }))
+    );
+}
+
+#[tokio::test]
+async fn test_edit_tool_run_mode() {
+    let setup = Setup::new(MockPromptBackend::new().with_select_responses([
+        "Tools",
+        "fs_read_file",
+        "run",
+        "unattended",
+        BACK,
+        BACK,
+        SAVE,
+    ]));
+
+    let resolved = json!({
+        "conversation": { "tools": { "*": { "run": "ask" }, "fs_read_file": {} } },
+    });
+    let changes = setup.wizard(resolved).run().await.unwrap();
+
+    assert_eq!(
+        changes,
+        Some(json!({
+            "conversation": { "tools": { "fs_read_file": { "run": "unattended" } } },
+        }))
+    );
+}
+
+#[tokio::test]
+async fn test_discard() {
+    let setup =
+        Setup::new(MockPromptBackend::new().with_select_responses(["Reasoning", "off", DISCARD]));
+
+    assert_eq!(setup.wizard(json!({})).run().await.unwrap(), None);
+}
+
+#[test]
+fn test_set_rejects_invalid_values() {
+    let setup = Setup::new(MockPromptBackend::new());
+    let mut wizard = setup.wizard(json!({}));
+
+    wizard.set("assistant.model.parameters.max_tokens", json!("many"));
+    assert_eq!(wizard.changes, json!({}));
+
+    wizard.set("assistant.model.parameters.max_tokens", json!(1024));
+    assert_eq!(
+        wizard.changes,
+        json!({ "assistant": { "model": { "parameters": { "max_tokens": 1024 } } } })
+    );
+}
+
+#[test]
+fn test_describe() {
+    let mut setup = Setup::new(MockPromptBackend::new());
+    let mut partial = PartialAppConfig::empty();
+    partial.conversation.tools.defaults.run = Some(RunMode::Ask);
+    setup.layers = vec![Layer::new(
+        Source::File {
+            name: "workspace",
+            path: "/project/.jp/config.json".into(),
+        },
+        partial,
+    )];
+
+    let resolved = json!({ "conversation": { "tools": { "*": { "run": "ask" } } } });
+    let mut wizard = setup.wizard(resolved);
+
+    assert_eq!(
+        wizard.describe("conversation.tools.*.run"),
+        "ask (workspace, /project/.jp/config.json)"
+    );
+    assert_eq!(wizard.describe("assistant.name"), "not set");
+
+    wizard.set("conversation.tools.*.run", json!("unattended"));
+    assert_eq!(
+        wizard.describe("conversation.tools.*.run"),
+        "unattended (unsaved)"
+    );
+}
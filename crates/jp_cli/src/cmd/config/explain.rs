@@ -0,0 +1,545 @@
+use std::fs;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use crossterm::style::Stylize as _;
+use jp_config::{
+    AppConfig, PartialAppConfig,
+    activation::Condition,
+    fs::load_partial,
+    secret,
+    template::named::load_template_dir,
+    util::{load_partials_at_path_by_file, recursive_candidate_paths},
+};
+use jp_conversation::{ConversationId, Error as ConversationError};
+use jp_workspace::ConversationHandle;
+use schematic::PartialConfig as _;
+use serde_json::{Value, json};
+
+use crate::{
+    ConfigFiles,
+    cmd::{ConversationLoadRequest, Error, Output, conversation_id::FlagIds},
+    config_pipeline::{find_conditional_files, resolve_cfg_partials},
+    ctx::Ctx,
+    output::print_json,
+};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Explain {
+    /// The configuration key to explain, e.g. `assistant.model.id`.
+    key: String,
+
+    #[command(flatten)]
+    conversation: FlagIds<false, false>,
+}
+
+impl Explain {
+    pub(crate) fn run(self, ctx: &mut Ctx, handles: &[ConversationHandle]) -> Output {
+        let resolved = serde_json::to_value(ctx.config().to_partial())?;
+        let value = lookup(&resolved, &self.key).cloned();
+        if value.is_none() && !is_known_key(&self.key) {
+            return Err(format!("Unknown configuration key `{}`.", self.key).into());
+        }
+
+        let layers = load_layers(ctx, handles.first())?;
+        let steps = trace(&layers, &self.key)?;
+
+        if ctx.printer.format().is_json() {
+            let value = json!({
+                "key": self.key,
+                "value": value.as_ref().map(redacted),
+                "layers": steps.iter().map(|step| step.to_json(&self.key)).collect::<Vec<_>>(),
+            });
+            print_json(&ctx.printer, &value);
+            return Ok(());
+        }
+
+        let pretty = ctx.printer.pretty_printing_enabled();
+        ctx.printer
+            .println(render(&self.key, value.as_ref(), &steps, pretty));
+
+        Ok(())
+    }
+
+    pub(crate) fn conversation_load_request(&self) -> ConversationLoadRequest {
+        ConversationLoadRequest::explicit_with_config_or_none(&self.conversation)
+    }
+}
+
+/// Where a layer of configuration comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Source {
+    /// The default values, used for anything no other layer sets.
+    Defaults,
+
+    /// A configuration file, or a file it extends.
+    File {
+        /// The name of the layer the file belongs to, e.g. `workspace`.
+        name: &'static str,
+        path: Utf8PathBuf,
+    },
+
+    /// The `JP_CFG_*` environment variables.
+    Env,
+
+    /// The configuration of a conversation.
+    Conversation(ConversationId),
+
+    /// A file in `config_load_paths` that declares activation conditions.
+    Activated {
+        path: Utf8PathBuf,
+
+        /// The conditions of the file, and whether they matched.
+        conditions: Vec<Condition>,
+    },
+
+    /// A `--cfg` argument, and the file it was loaded from, if any.
+    Cfg(Option<Utf8PathBuf>),
+}
+
+impl Source {
+    pub(super) fn name(&self) -> &'static str {
+        match self {
+            Self::Defaults => "default",
+            Self::File { name, .. } => *name,
+            Self::Env => "environment",
+            Self::Conversation(_) => "conversation",
+            Self::Activated { .. } => "activated",
+            Self::Cfg(_) => "--cfg",
+        }
+    }
+
+    /// The activation conditions of the layer, if any.
+    pub(super) fn conditions(&self) -> &[Condition] {
+        match self {
+            Self::Activated { conditions, .. } => conditions,
+            _ => &[],
+        }
+    }
+
+    /// Where the layer sets `key`, if it can be pointed at.
+    pub(super) fn location(&self, key: &str, line: Option<usize>) -> Option<String> {
+        match self {
+            Self::Defaults | Self::Cfg(None) => None,
+            Self::File { path, .. } | Self::Activated { path, .. } | Self::Cfg(Some(path)) => {
+                Some(match line {
+                    Some(line) => format!("{path}:{line}"),
+                    None => path.to_string(),
+                })
+            }
+            Self::Env => AppConfig::envs().get(key).cloned(),
+            Self::Conversation(id) => Some(format!("conversation {id}")),
+        }
+    }
+}
+
+/// A layer of configuration, in merge order.
+#[derive(Debug)]
+pub(super) struct Layer {
+    pub(super) source: Source,
+    partial: PartialAppConfig,
+
+    /// Whether the layer is ignored, because an earlier file disabled
+    /// inheritance.
+    pub(super) ignored: bool,
+}
+
+impl Layer {
+    pub(super) const fn new(source: Source, partial: PartialAppConfig) -> Self {
+        Self {
+            source,
+            partial,
+            ignored: false,
+        }
+    }
+}
+
+/// A layer that sets the explained key.
+#[derive(Debug)]
+pub(super) struct Step<'a> {
+    pub(super) layer: &'a Layer,
+
+    /// The value the layer sets.
+    value: Value,
+
+    /// The value after merging the layer, if the merge strategy of the key
+    /// makes it differ from `value`.
+    merged: Option<Value>,
+
+    /// The line of the layer's file that sets the key, if known.
+    pub(super) line: Option<usize>,
+}
+
+impl Step<'_> {
+    fn to_json(&self, key: &str) -> Value {
+        let conditions = self
+            .layer
+            .source
+            .conditions()
+            .iter()
+            .map(|condition| {
+                json!({
+                    "condition": condition.name,
+                    "pattern": condition.pattern,
+                    "matched": condition.matched,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "layer": self.layer.source.name(),
+            "location": self.layer.source.location(key, self.line),
+            "value": redacted(&self.value),
+            "merged": self.merged.as_ref().map(redacted),
+            "ignored": self.layer.ignored,
+            "conditions": conditions,
+        })
+    }
+}
+
+/// Load every layer of configuration, in the order they are merged.
+///
+/// This reloads the same sources as [`crate::load_base_partial`] and
+/// [`ConfigPipeline`], but keeps each file and argument separate.
+///
+/// [`ConfigPipeline`]: crate::config_pipeline::ConfigPipeline
+pub(super) fn load_layers(
+    ctx: &Ctx,
+    conversation: Option<&ConversationHandle>,
+) -> Result<Vec<Layer>, Error> {
+    let defaults = PartialAppConfig::default_values(&())?.unwrap_or_default();
+    let mut layers = vec![Layer::new(Source::Defaults, defaults)];
+
+    let files = ConfigFiles::new(ctx.fs_backend.as_deref(), ctx.env.cwd().ok(), &ctx.env);
+    let mut groups = vec![file_layers("user global", files.user_global.as_deref())?];
+
+    if let Some(dir) = files.templates {
+        let named = load_template_dir(&dir)?;
+        if !named.is_empty() {
+            let mut partial = PartialAppConfig::default();
+            partial.template.named = named;
+            groups.push(vec![Layer::new(
+                Source::File {
+                    name: "templates",
+                    path: dir,
+                },
+                partial,
+            )]);
+        }
+    }
+
+    groups.push(file_layers("workspace", files.workspace.as_deref())?);
+
+    let mut cwd = vec![];
+    if let Some((path, root)) = &files.cwd {
+        for candidate in recursive_candidate_paths(path.as_std_path(), root.as_deref()) {
+            if let Ok(candidate) = Utf8PathBuf::from_path_buf(candidate) {
+                cwd.extend(file_layers("cwd", Some(&candidate))?);
+            }
+        }
+    }
+    groups.push(cwd);
+
+    groups.push(file_layers(
+        "user workspace",
+        files.user_workspace.as_deref(),
+    )?);
+
+    // Mirrors `load_partials_with_inheritance`: once the files merged so far
+    // disable inheritance, the remaining files are ignored.
+    let mut inherit = true;
+    for group in groups {
+        let ignored = !inherit;
+        for mut layer in group {
+            layer.ignored = ignored;
+            if !ignored && let Some(value) = layer.partial.inherit {
+                inherit = value;
+            }
+
+            layers.push(layer);
+        }
+    }
+
+    layers.push(Layer::new(
+        Source::Env,
+        PartialAppConfig::from_envs(&ctx.env)?,
+    ));
+
+    if let Some(handle) = conversation {
+        let config = ctx
+            .workspace
+            .events(handle)?
+            .config()
+            .map_err(ConversationError::from)?;
+
+        layers.push(Layer::new(
+            Source::Conversation(handle.id()),
+            config.to_partial(),
+        ));
+    }
+
+    let base = ctx.config().to_partial();
+    let cfg = resolve_cfg_partials(
+        &ctx.term.args.config,
+        &base,
+        Some(&ctx.workspace),
+        ctx.fs_backend.as_deref(),
+        &ctx.env,
+    )?;
+
+    // Files that don't match their conditions are listed as well, to show why
+    // they are not applied.
+    let explicit = cfg
+        .iter()
+        .filter_map(|(path, _)| path.clone())
+        .collect::<Vec<_>>();
+
+    for file in find_conditional_files(
+        &base,
+        &explicit,
+        Some(ctx.workspace.root()),
+        ctx.fs_backend.as_deref(),
+        &ctx.env,
+    ) {
+        let ignored = !file.is_active();
+        let mut layer = Layer::new(
+            Source::Activated {
+                path: file.path,
+                conditions: file.conditions,
+            },
+            file.partial,
+        );
+        layer.ignored = ignored;
+        layers.push(layer);
+    }
+
+    layers.extend(
+        cfg.into_iter()
+            .map(|(path, partial)| Layer::new(Source::Cfg(path), partial)),
+    );
+
+    Ok(layers)
+}
+
+/// The layers of a configuration file, one for the file and one for each file
+/// it extends.
+fn file_layers(name: &'static str, path: Option<&Utf8Path>) -> Result<Vec<Layer>, Error> {
+    let Some(path) = path else {
+        return Ok(vec![]);
+    };
+
+    Ok(load_partials_at_path_by_file(path.as_std_path())?
+        .into_iter()
+        .filter_map(|(path, partial)| {
+            let path = Utf8PathBuf::from_path_buf(path).ok()?;
+            Some(Layer::new(Source::File { name, path }, partial))
+        })
+        .collect())
+}
+
+/// Find the layers that set `key`, merging them the way the config pipeline
+/// does.
+pub(super) fn trace<'a>(layers: &'a [Layer], key: &str) -> Result<Vec<Step<'a>>, Error> {
+    let mut merged = PartialAppConfig::empty();
+    let mut steps = vec![];
+
+    for layer in layers {
+        // Defaults only fill in what no other layer sets, so they are not
+        // merged in order.
+        let applied = !layer.ignored && layer.source != Source::Defaults;
+        if applied {
+            merged = load_partial(merged, layer.partial.clone())?;
+        }
+
+        let Some(value) = lookup(&serde_json::to_value(&layer.partial)?, key).cloned() else {
+            continue;
+        };
+
+        let merged = if applied {
+            lookup(&serde_json::to_value(&merged)?, key)
+                .filter(|merged| **merged != value)
+                .cloned()
+        } else {
+            None
+        };
+
+        let line = match &layer.source {
+            Source::File { path, .. }
+            | Source::Activated { path, .. }
+            | Source::Cfg(Some(path)) => find_line(path, key),
+            _ => None,
+        };
+
+        steps.push(Step {
+            layer,
+            value,
+            merged,
+            line,
+        });
+    }
+
+    Ok(steps)
+}
+
+fn render(key: &str, value: Option<&Value>, steps: &[Step<'_>], pretty: bool) -> String {
+    let bold = |text: String| {
+        if pretty {
+            text.bold().to_string()
+        } else {
+            text
+        }
+    };
+    let dim = |text: String| if pretty { text.dim().to_string() } else { text };
+
+    let mut out = match value {
+        Some(value) => bold(format!("{key} = {}", redacted(value))),
+        None => bold(format!("{key} is not set")),
+    };
+
+    if steps.is_empty() {
+        out.push_str("\n\nNo configuration layer sets this key.");
+        return out;
+    }
+
+    out.push_str("\n\nSet by, from lowest to highest precedence:\n");
+    for step in steps {
+        let source = &step.layer.source;
+        out.push_str(&format!("\n  {}", source.name()));
+        if let Some(location) = source.location(key, step.line) {
+            out.push_str(&format!(" {}", dim(format!("({location})"))));
+        }
+        if step.layer.ignored {
+            let reason = match source {
+                Source::Activated { .. } => "conditions not matched",
+                _ => "inheritance is disabled",
+            };
+
+            out.push_str(&dim(format!(" ignored, {reason}")));
+        }
+        for condition in source.conditions() {
+            out.push_str(&format!("\n    {}", dim(format!("when {condition}"))));
+        }
+
+        out.push_str(&format!("\n    {}", redacted(&step.value)));
+        if let Some(merged) = &step.merged {
+            let merged = redacted(merged);
+            out.push_str(&format!("\n    {}", dim(format!("merged into {merged}"))));
+        }
+    }
+
+    // Building the configuration can still change the value, e.g. by resolving
+    // a model alias.
+    let last = steps
+        .iter()
+        .rev()
+        .find(|step| !step.layer.ignored)
+        .map(|step| step.merged.as_ref().unwrap_or(&step.value));
+
+    if let Some(value) = value
+        && last.is_some_and(|last| last != value)
+    {
+        let value = redacted(value);
+        out.push_str(&format!(
+            "\n\n{}",
+            dim(format!(
+                "Resolved to {value} when building the configuration."
+            ))
+        ));
+    }
+
+    out
+}
+
+/// A copy of `value` with every secret reference in it redacted.
+fn redacted(value: &Value) -> Value {
+    let mut value = value.clone();
+    secret::redact_json(&mut value);
+    value
+}
+
+/// Get the value at a dotted `key`, treating `null` and empty tables as unset.
+pub(super) fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(value, |value, segment| match value {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+        .filter(|value| !value.is_null() && value.as_object().is_none_or(|map| !map.is_empty()))
+}
+
+/// Whether `key` is a configuration field, a table of fields, or a key inside
+/// a field such as a map of tools.
+fn is_known_key(key: &str) -> bool {
+    AppConfig::fields().iter().any(|field| {
+        field == key
+            || field.starts_with(&format!("{key}."))
+            || key.starts_with(&format!("{field}."))
+    })
+}
+
+/// Find the line of a TOML file that sets `key`.
+///
+/// This tracks table headers and `key = value` lines without parsing the file,
+/// so keys set inside inline tables or arrays of tables point at the line of
+/// their parent.
+pub(super) fn find_line(path: &Utf8Path, key: &str) -> Option<usize> {
+    if path.extension() != Some("toml") {
+        return None;
+    }
+
+    let content = fs::read_to_string(path).ok()?;
+    find_toml_line(&content, key)
+}
+
+fn find_toml_line(content: &str, key: &str) -> Option<usize> {
+    let sets_key = |name: &str| {
+        name == key || key.starts_with(&format!("{name}.")) || name.starts_with(&format!("{key}."))
+    };
+
+    let mut table = String::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header.trim_start_matches('[');
+            table = normalize_key(header.split(']').next().unwrap_or_default());
+            if table == key || table.starts_with(&format!("{key}.")) {
+                return Some(index + 1);
+            }
+
+            continue;
+        }
+
+        let Some((name, _)) = line.split_once('=') else {
+            continue;
+        };
+
+        let name = normalize_key(name);
+        let name = if table.is_empty() {
+            name
+        } else {
+            format!("{table}.{name}")
+        };
+
+        if sets_key(&name) {
+            return Some(index + 1);
+        }
+    }
+
+    None
+}
+
+/// Strip whitespace and quotes from the segments of a dotted TOML key.
+fn normalize_key(key: &str) -> String {
+    key.split('.')
+        .map(|segment| segment.trim().trim_matches(['"', '\'']))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+#[path = "explain_tests.rs"]
+mod tests;
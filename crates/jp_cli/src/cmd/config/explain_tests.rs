@@ -0,0 +1,239 @@
+use camino_tempfile::tempdir;
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+use super::*;
+
+fn partial(toml: &str) -> PartialAppConfig {
+    toml::from_str(toml).unwrap()
+}
+
+fn file(name: &'static str, path: &str, toml: &str) -> Layer {
+    Layer::new(
+        Source::File {
+            name,
+            path: path.into(),
+        },
+        partial(toml),
+    )
+}
+
+#[test]
+fn test_find_toml_line() {
+    let content = indoc! {r#"
+        # assistant.name = "commented"
+        inherit = false
+        assistant.name = "JP"
+
+        [assistant.model]
+        id = "anthropic/claude-sonnet-4-5"
+
+        [providers.llm.aliases]
+        "opus" = "anthropic/claude-opus-4-6"
+
+        [conversation]
+        tools = { fs_read_file = { run = "ask" } }
+    "#};
+
+    let cases = [
+        ("inherit", Some(2)),
+        ("assistant.name", Some(3)),
+        ("assistant.model", Some(5)),
+        ("assistant.model.id", Some(6)),
+        ("providers.llm.aliases.opus", Some(9)),
+        ("conversation.tools.fs_read_file.run", Some(12)),
+        ("style.code.theme", None),
+    ];
+
+    for (key, line) in cases {
+        assert_eq!(find_toml_line(content, key), line, "{key}");
+    }
+}
+
+#[test]
+fn test_lookup_treats_null_and_empty_tables_as_unset() {
+    let value = json!({
+        "a": { "b": null, "c": {}, "d": [1, 2], "e": [] },
+    });
+
+    assert_eq!(lookup(&value, "a.b"), None);
+    assert_eq!(lookup(&value, "a.c"), None);
+    assert_eq!(lookup(&value, "a.d.1"), Some(&json!(2)));
+    assert_eq!(lookup(&value, "a.e"), Some(&json!([])));
+    assert_eq!(lookup(&value, "a.x"), None);
+}
+
+#[test]
+fn test_is_known_key() {
+    assert!(is_known_key("assistant.name"));
+    assert!(is_known_key("assistant"));
+    assert!(!is_known_key("assistant.nope"));
+    assert!(!is_known_key("assist"));
+}
+
+#[test]
+fn test_trace_shows_each_layer_and_merge_effects() {
+    let tmp = tempdir().unwrap();
+    let workspace = tmp.path().join("config.toml");
+    let workspace_toml = indoc! {r#"
+        [providers.llm.aliases]
+        b = "anthropic/b"
+    "#};
+    std::fs::write(&workspace, workspace_toml).unwrap();
+
+    let layers = vec![
+        Layer::new(Source::Defaults, partial(r#"assistant.name = "default""#)),
+        file("user global", "/nonexistent/config.toml", indoc! {r#"
+                assistant.name = "global"
+
+                [providers.llm.aliases]
+                a = "anthropic/a"
+            "#}),
+        file("workspace", workspace.as_str(), workspace_toml),
+        Layer::new(Source::Env, partial(r#"assistant.name = "env""#)),
+    ];
+
+    let steps = trace(&layers, "assistant.name").unwrap();
+    let names: Vec<_> = steps.iter().map(|s| s.layer.source.name()).collect();
+    assert_eq!(names, ["default", "user global", "environment"]);
+    assert!(steps.iter().all(|step| step.merged.is_none()));
+
+    let steps = trace(&layers, "providers.llm.aliases").unwrap();
+    assert_eq!(steps.len(), 2);
+    assert_eq!(steps[0].line, None);
+    assert_eq!(steps[1].line, Some(1));
+
+    // Maps merge key by key, so the workspace adds to the aliases of the
+    // user-global config.
+    let merged = steps[1].merged.as_ref().unwrap();
+    assert!(lookup(merged, "a").is_some());
+    assert!(lookup(merged, "b").is_some());
+}
+
+#[test]
+fn test_trace_ignored_layers_do_not_merge() {
+    let mut ignored = file(
+        "workspace",
+        "/nonexistent/config.toml",
+        "[providers.llm.aliases]\nb = \"anthropic/b\"",
+    );
+    ignored.ignored = true;
+
+    let layers = vec![
+        file(
+            "user global",
+            "/nonexistent/config.toml",
+            "[providers.llm.aliases]\na = \"anthropic/a\"",
+        ),
+        ignored,
+    ];
+
+    let steps = trace(&layers, "providers.llm.aliases").unwrap();
+    assert_eq!(steps.len(), 2);
+    assert!(steps[1].layer.ignored);
+    assert_eq!(steps[1].merged, None);
+}
+
+#[test]
+fn test_render() {
+    let layers = vec![
+        Layer::new(Source::Defaults, partial(r#"assistant.name = "default""#)),
+        file(
+            "workspace",
+            "/repo/.jp/config.toml",
+            r#"assistant.name = "workspace""#,
+        ),
+        Layer::new(Source::Cfg(None), partial(r#"assistant.name = "cli""#)),
+    ];
+
+    let steps = trace(&layers, "assistant.name").unwrap();
+    let out = render("assistant.name", Some(&json!("cli")), &steps, false);
+
+    assert_eq!(out, indoc! {r#"
+        assistant.name = "cli"
+
+        Set by, from lowest to highest precedence:
+
+          default
+            "default"
+          workspace (/repo/.jp/config.toml)
+            "workspace"
+          --cfg
+            "cli""#});
+
+    let out = render("assistant.name", Some(&json!("other")), &steps, false);
+    assert!(out.ends_with("Resolved to \"other\" when building the configuration."));
+
+    let out = render("assistant.name", None, &[], false);
+    assert_eq!(
+        out,
+        "assistant.name is not set\n\nNo configuration layer sets this key."
+    );
+}
+
+#[test]
+fn test_render_and_json_redact_secret_references() {
+    let layers = vec![file(
+        "workspace",
+        "/repo/.jp/config.toml",
+        r#"providers.llm.openai.base_url = "secret:cmd:pass show url""#,
+    )];
+
+    let steps = trace(&layers, "providers.llm.openai.base_url").unwrap();
+    let value = json!("secret:cmd:pass show url");
+    let out = render("providers.llm.openai.base_url", Some(&value), &steps, false);
+
+    assert!(!out.contains("pass show url"), "{out}");
+    assert!(out.contains(r#""secret:cmd:***""#), "{out}");
+
+    let json = steps[0].to_json("providers.llm.openai.base_url");
+    assert_eq!(json["value"], json!("secret:cmd:***"));
+}
+
+#[test]
+fn test_render_activated_layers() {
+    let condition = |name, pattern: &str, matched: Option<&str>| Condition {
+        name,
+        pattern: pattern.to_owned(),
+        matched: matched.map(str::to_owned),
+    };
+
+    let mut inactive = Layer::new(
+        Source::Activated {
+            path: "/repo/.jp/personas/release.toml".into(),
+            conditions: vec![condition("branches", "release/*", None)],
+        },
+        partial(r#"assistant.name = "release""#),
+    );
+    inactive.ignored = true;
+
+    let layers = vec![
+        Layer::new(
+            Source::Activated {
+                path: "/repo/.jp/skills/rust.toml".into(),
+                conditions: vec![
+                    condition("paths", "crates/**", Some("crates/jp_cli")),
+                    condition("languages", "rust", Some("rust")),
+                ],
+            },
+            partial(r#"assistant.name = "rust""#),
+        ),
+        inactive,
+    ];
+
+    let steps = trace(&layers, "assistant.name").unwrap();
+    let out = render("assistant.name", Some(&json!("rust")), &steps, false);
+
+    assert_eq!(out, indoc! {r#"
+        assistant.name = "rust"
+
+        Set by, from lowest to highest precedence:
+
+          activated (/repo/.jp/skills/rust.toml)
+            when paths = crates/** (matched crates/jp_cli)
+            when languages = rust (matched rust)
+            "rust"
+          activated (/repo/.jp/personas/release.toml) ignored, conditions not matched
+            when branches = release/* (not matched)
+            "release""#});
+}
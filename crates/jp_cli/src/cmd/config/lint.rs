@@ -0,0 +1,291 @@
+use std::{collections::BTreeSet, fs};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use jp_config::{
+    AppConfig,
+    fs::{ConfigFile, Format},
+};
+use serde_json::{Value, json};
+
+use super::explain::find_line;
+use crate::{Error, cmd::Output, ctx::Ctx, output::print_json};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Lint {
+    /// The configuration files to check.
+    ///
+    /// Each file is checked on its own, as a partial configuration, so files
+    /// loaded with `--cfg` or `extends` can be checked as well.
+    #[arg(required = true, value_name = "FILE")]
+    files: Vec<Utf8PathBuf>,
+}
+
+impl Lint {
+    pub(crate) fn run(&self, ctx: &Ctx) -> Output {
+        let schema = AppConfig::json_schema();
+        let problems: Vec<_> = self
+            .files
+            .iter()
+            .flat_map(|path| lint(path, &schema))
+            .collect();
+
+        if ctx.printer.format().is_json() {
+            let json = problems.iter().map(Problem::to_json).collect();
+            print_json(&ctx.printer, &Value::Array(json));
+        } else {
+            for problem in &problems {
+                ctx.printer.println(problem.to_string());
+            }
+        }
+
+        if problems.is_empty() {
+            return Ok(());
+        }
+
+        let files = problems
+            .iter()
+            .map(|problem| &problem.path)
+            .collect::<BTreeSet<_>>()
+            .len();
+
+        Err(Error::CliConfig(format!(
+            "Found {} problem(s) in {files} file(s).",
+            problems.len()
+        ))
+        .into())
+    }
+}
+
+/// A problem found in a configuration file.
+#[derive(Debug, PartialEq)]
+struct Problem {
+    path: Utf8PathBuf,
+    line: Option<usize>,
+
+    /// The dotted path of the offending key, if the problem is about a key.
+    key: Option<String>,
+    message: String,
+}
+
+impl Problem {
+    fn to_json(&self) -> Value {
+        json!({
+            "path": self.path.as_str(),
+            "line": self.line,
+            "key": self.key,
+            "message": self.message,
+        })
+    }
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{line}: {}", self.path, self.message),
+            None => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+/// Check a configuration file for unknown keys and invalid values.
+///
+/// Values are only checked once all keys are known, as deserialization stops
+/// at the first unknown key, without suggestions.
+fn lint(path: &Utf8Path, schema: &Value) -> Vec<Problem> {
+    let problem = |message: String| Problem {
+        path: path.to_owned(),
+        line: None,
+        key: None,
+        message,
+    };
+
+    let Some(format) = path.extension().and_then(Format::from_extension) else {
+        return vec![problem("unsupported file extension".to_owned())];
+    };
+
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) => return vec![problem(error.to_string())],
+    };
+
+    let file = ConfigFile {
+        path: path.to_owned(),
+        format,
+        content,
+    };
+
+    let value = match file.deserialize::<Value>() {
+        Ok(value) => value,
+        Err(error) => return vec![problem(format!("invalid {}: {error}", format.as_str()))],
+    };
+
+    let mut keys = vec![];
+    unknown_keys(schema, schema, &value, "", &mut keys);
+    if keys.is_empty() {
+        return file
+            .deserialize_partial()
+            .err()
+            .map(|error| problem(error.to_string()))
+            .into_iter()
+            .collect();
+    }
+
+    keys.into_iter()
+        .map(|(key, suggestion)| {
+            let message = match suggestion {
+                Some(suggestion) => format!("unknown key `{key}`, did you mean `{suggestion}`?"),
+                None => format!("unknown key `{key}`"),
+            };
+
+            // Lines are found by key, which does not include array indices.
+            let line_key = key.split('[').next().unwrap_or(&key);
+
+            Problem {
+                line: find_line(path, line_key),
+                key: Some(key),
+                ..problem(message)
+            }
+        })
+        .collect()
+}
+
+/// Collect the keys in `value` that `schema` does not allow, along with the
+/// closest allowed key, if any is close enough.
+///
+/// `root` is the schema document that `$ref` pointers resolve against.
+fn unknown_keys(
+    root: &Value,
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    out: &mut Vec<(String, Option<String>)>,
+) {
+    match value {
+        Value::Object(map) => {
+            let objects = shapes(root, schema, "additionalProperties");
+            if objects.is_empty() {
+                return;
+            }
+
+            for (key, value) in map {
+                let key_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+
+                let property = objects
+                    .iter()
+                    .find_map(|shape| shape.get("properties")?.get(key))
+                    .or_else(|| {
+                        objects.iter().find_map(|shape| {
+                            shape
+                                .get("additionalProperties")
+                                .filter(|schema| **schema != Value::Bool(false))
+                        })
+                    });
+
+                match property {
+                    Some(schema) => unknown_keys(root, schema, value, &key_path, out),
+                    None => {
+                        let known = objects
+                            .iter()
+                            .filter_map(|shape| shape.get("properties")?.as_object())
+                            .flat_map(|properties| properties.keys());
+
+                        let suggestion = closest(key, known).map(|name| match path {
+                            "" => name.to_owned(),
+                            _ => format!("{path}.{name}"),
+                        });
+
+                        out.push((key_path, suggestion));
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            let Some(schema) = shapes(root, schema, "items")
+                .into_iter()
+                .find_map(|shape| shape.get("items"))
+            else {
+                return;
+            };
+
+            for (index, item) in items.iter().enumerate() {
+                unknown_keys(root, schema, item, &format!("{path}[{index}]"), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The alternatives of `schema` that describe an object or array, following
+/// `$ref` pointers and unions.
+///
+/// `keyword` is `additionalProperties` for objects, which the schema sets for
+/// every object, and `items` for arrays.
+pub(super) fn shapes<'a>(root: &'a Value, schema: &'a Value, keyword: &str) -> Vec<&'a Value> {
+    let schema = match schema.get("$ref").and_then(Value::as_str) {
+        Some(pointer) => match root.pointer(pointer.trim_start_matches('#')) {
+            Some(schema) => schema,
+            None => return vec![],
+        },
+        None => schema,
+    };
+
+    if schema.get(keyword).is_some() {
+        return vec![schema];
+    }
+
+    ["anyOf", "oneOf"]
+        .into_iter()
+        .filter_map(|union| schema.get(union)?.as_array())
+        .flatten()
+        .flat_map(|variant| shapes(root, variant, keyword))
+        .collect()
+}
+
+/// The name in `known` closest to `name`, if it is a likely typo.
+fn closest<'a>(name: &str, known: impl Iterator<Item = &'a String>) -> Option<&'a str> {
+    let max = (name.chars().count() / 3).max(1);
+    let name = name.to_lowercase();
+
+    known
+        .map(|candidate| (distance(&name, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= max)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// The edit distance between two strings, counting insertions, deletions,
+/// substitutions and transpositions of adjacent characters.
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = d[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut best = substitution.min(d[i - 1][j] + 1).min(d[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(d[i - 2][j - 2] + 1);
+            }
+
+            d[i][j] = best;
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+#[path = "lint_tests.rs"]
+mod tests;
@@ -0,0 +1,94 @@
+use camino_tempfile::tempdir;
+use pretty_assertions::assert_eq;
+
+use super::*;
+
+#[test]
+fn test_closest() {
+    let known = [
+        "name".to_owned(),
+        "model".to_owned(),
+        "system_prompt".to_owned(),
+    ];
+
+    assert_eq!(closest("nmae", known.iter()), Some("name"));
+    assert_eq!(closest("mode", known.iter()), Some("model"));
+    assert_eq!(closest("System_Promt", known.iter()), Some("system_prompt"));
+    assert_eq!(closest("temperature", known.iter()), None);
+}
+
+#[test]
+fn test_unknown_keys() {
+    let schema = AppConfig::json_schema();
+    let value: Value = toml::from_str(indoc::indoc! {r#"
+        inherit = false
+
+        [assistant]
+        nmae = "Jean-Pierre"
+
+        [conversation.tools.'*']
+        run = "ask"
+
+        [conversation.tools.fs_read_file]
+        sumary = "Read a file"
+
+        [[extends]]
+        path = "base.toml"
+        stratgy = "after"
+    "#})
+    .unwrap();
+
+    let mut keys = vec![];
+    unknown_keys(&schema, &schema, &value, "", &mut keys);
+
+    assert_eq!(keys, [
+        (
+            "assistant.nmae".to_owned(),
+            Some("assistant.name".to_owned())
+        ),
+        (
+            "conversation.tools.fs_read_file.sumary".to_owned(),
+            Some("conversation.tools.fs_read_file.summary".to_owned())
+        ),
+        (
+            "extends[0].stratgy".to_owned(),
+            Some("extends[0].strategy".to_owned())
+        ),
+    ]);
+}
+
+#[test]
+fn test_lint() {
+    let tmp = tempdir().unwrap();
+    let schema = AppConfig::json_schema();
+
+    let valid = tmp.path().join("valid.toml");
+    fs::write(&valid, "[assistant]\nname = \"Jean-Pierre\"\n").unwrap();
+    assert!(lint(&valid, &schema).is_empty());
+
+    let typo = tmp.path().join("typo.toml");
+    fs::write(
+        &typo,
+        "inherit = true\n\n[assistant]\nnmae = \"Jean-Pierre\"\n",
+    )
+    .unwrap();
+    assert_eq!(lint(&typo, &schema), [Problem {
+        path: typo.clone(),
+        line: Some(4),
+        key: Some("assistant.nmae".to_owned()),
+        message: "unknown key `assistant.nmae`, did you mean `assistant.name`?".to_owned(),
+    }]);
+
+    let invalid = tmp.path().join("invalid.json");
+    fs::write(&invalid, r#"{ "inherit": "yes" }"#).unwrap();
+    let problems = lint(&invalid, &schema);
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].key, None);
+
+    let unsupported = tmp.path().join("notes.md");
+    fs::write(&unsupported, "").unwrap();
+    assert_eq!(
+        lint(&unsupported, &schema)[0].message,
+        "unsupported file extension"
+    );
+}
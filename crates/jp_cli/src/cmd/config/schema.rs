@@ -0,0 +1,32 @@
+use jp_config::AppConfig;
+
+use crate::{cmd::Output, ctx::Ctx, output::print_json};
+
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = EDITOR_HELP)]
+pub(crate) struct Schema {}
+
+const EDITOR_HELP: &str = "\
+The schema describes configuration files, not the resolved configuration, so
+every option is optional.
+
+To validate and complete TOML files in your editor with Taplo (also used by the
+Even Better TOML extension), save the schema and point a file at it, relative
+to the file:
+
+  jp config schema > .jp/schema.json
+
+  # .jp/config/personas/dev.toml
+  #:schema ../../schema.json
+  [assistant]
+  name = \"Jean-Pierre\"
+
+Use `jp config lint <FILE>...` to check files from the command line.";
+
+impl Schema {
+    #[expect(clippy::unused_self, clippy::unnecessary_wraps)]
+    pub(crate) fn run(&self, ctx: &Ctx) -> Output {
+        print_json(&ctx.printer, &AppConfig::json_schema());
+        Ok(())
+    }
+}
@@ -30,6 +30,7 @@ impl Set {
             &base,
             Some(&ctx.workspace),
             ctx.fs_backend.as_deref(),
+            &ctx.env,
         )?;
 
         if handles.is_empty() {
@@ -4,6 +4,7 @@ use camino_tempfile::tempdir;
 use chrono::{DateTime, Utc};
 use jp_config::{
     AppConfig, PartialAppConfig, assignment::KvAssignment, conversation::DefaultConversationId,
+    env::Env,
 };
 use jp_conversation::{Conversation, ConversationId};
 use jp_printer::{OutputFormat, Printer};
@@ -62,6 +63,7 @@ fn setup(
         Runtime::new().unwrap(),
         globals,
         config,
+        Env::process(),
         None,
         printer,
     );
@@ -72,7 +74,7 @@ fn setup(
 #[test]
 fn build_partial_errors_when_no_args() {
     let base = PartialAppConfig::default();
-    let result = build_partial_from_cfg_args(&[], &base, None, None);
+    let result = build_partial_from_cfg_args(&[], &base, None, None, &Env::process());
     assert!(result.is_err());
 }
 
@@ -80,7 +82,7 @@ fn build_partial_errors_when_no_args() {
 fn build_partial_applies_key_value() {
     let base = PartialAppConfig::default();
     let args = vec![kv("conversation.start_local=true")];
-    let partial = build_partial_from_cfg_args(&args, &base, None, None).unwrap();
+    let partial = build_partial_from_cfg_args(&args, &base, None, None, &Env::process()).unwrap();
     assert_eq!(partial.conversation.start_local, Some(true));
 }
 
@@ -91,7 +93,7 @@ fn build_partial_applies_multiple_key_values() {
         kv("conversation.start_local=true"),
         kv("conversation.default_id=last"),
     ];
-    let partial = build_partial_from_cfg_args(&args, &base, None, None).unwrap();
+    let partial = build_partial_from_cfg_args(&args, &base, None, None, &Env::process()).unwrap();
     assert_eq!(partial.conversation.start_local, Some(true));
     assert_eq!(
         partial.conversation.default_id,
@@ -107,7 +109,7 @@ fn build_partial_loads_toml_file() {
 
     let base = PartialAppConfig::default();
     let args = vec![KeyValueOrPath::Path(file)];
-    let partial = build_partial_from_cfg_args(&args, &base, None, None).unwrap();
+    let partial = build_partial_from_cfg_args(&args, &base, None, None, &Env::process()).unwrap();
     assert_eq!(partial.conversation.start_local, Some(true));
 }
 
@@ -122,7 +124,7 @@ fn build_partial_merges_file_and_kv() {
         KeyValueOrPath::Path(file),
         kv("conversation.default_id=last"),
     ];
-    let partial = build_partial_from_cfg_args(&args, &base, None, None).unwrap();
+    let partial = build_partial_from_cfg_args(&args, &base, None, None, &Env::process()).unwrap();
     assert_eq!(partial.conversation.start_local, Some(true));
     assert_eq!(
         partial.conversation.default_id,
@@ -134,7 +136,7 @@ fn build_partial_merges_file_and_kv() {
 fn build_partial_errors_on_missing_file() {
     let base = PartialAppConfig::default();
     let args = vec![KeyValueOrPath::Path("/nonexistent/path.toml".into())];
-    let result = build_partial_from_cfg_args(&args, &base, None, None);
+    let result = build_partial_from_cfg_args(&args, &base, None, None, &Env::process());
     assert!(result.is_err());
 }
 
@@ -1,9 +1,14 @@
 use std::collections::BTreeMap;
 
-use jp_config::{AppConfig, PartialAppConfig};
+use jp_config::{AppConfig, PartialAppConfig, secret};
+use jp_workspace::ConversationHandle;
 use schematic::PartialConfig as _;
 
-use crate::{Ctx, cmd::Output};
+use crate::{
+    Ctx,
+    cmd::{ConversationLoadRequest, Output, conversation_id::FlagIds},
+    output::print_json,
+};
 
 #[derive(Debug, clap::Args)]
 pub(crate) struct Show {
@@ -11,13 +16,40 @@ pub(crate) struct Show {
     #[arg(long)]
     defaults: bool,
 
+    /// Show the fully resolved configuration.
+    ///
+    /// This is the configuration a command runs with, after merging all
+    /// configuration files, environment variables and `--cfg` arguments.
+    /// Use `--id` to include the configuration of a conversation.
+    #[arg(long, conflicts_with_all = ["defaults", "themes"])]
+    effective: bool,
+
     /// List available syntax highlighting themes.
     #[arg(long)]
     themes: bool,
+
+    #[command(flatten)]
+    conversation: FlagIds<false, false>,
 }
 
 impl Show {
-    pub(crate) fn run(self, ctx: &mut Ctx) -> Output {
+    pub(crate) fn run(self, ctx: &mut Ctx, handles: &[ConversationHandle]) -> Output {
+        if self.effective || !handles.is_empty() {
+            // Secret references are redacted, so the output can be shared
+            // without revealing where secrets are kept.
+            let mut config = serde_json::to_value(ctx.config().to_partial())?;
+            secret::redact_json(&mut config);
+
+            if ctx.printer.format().is_json() {
+                print_json(&ctx.printer, &config);
+            } else {
+                let config: PartialAppConfig = serde_json::from_value(config)?;
+                ctx.printer.println(toml::to_string_pretty(&config)?);
+            }
+
+            return Ok(());
+        }
+
         if self.defaults {
             ctx.printer
                 .println(toml::to_string_pretty(&PartialAppConfig::default_values(
@@ -34,6 +66,10 @@ impl Show {
         ctx.printer.println(config_skeleton());
         Ok(())
     }
+
+    pub(crate) fn conversation_load_request(&self) -> ConversationLoadRequest {
+        ConversationLoadRequest::explicit_with_config_or_none(&self.conversation)
+    }
 }
 
 /// Build a commented TOML skeleton showing all available config keys.
@@ -1,4 +1,4 @@
-use jp_config::PartialAppConfig;
+use jp_config::{PartialAppConfig, env::Env};
 use jp_workspace::{ConversationHandle, Workspace};
 
 use super::{ConversationLoadRequest, Output};
@@ -6,6 +6,7 @@ use crate::ctx::{Ctx, IntoPartialAppConfig};
 
 mod archive;
 pub(crate) mod compact;
+mod diff;
 mod edit;
 pub(crate) mod fork;
 mod grep;
@@ -16,6 +17,7 @@ mod print;
 mod rm;
 mod show;
 pub(crate) mod summarize;
+mod tree;
 mod unarchive;
 mod use_;
 
@@ -32,6 +34,8 @@ impl Conversation {
             Commands::Remove(args) => args.run(ctx, handles).await,
             Commands::Edit(args) => args.run(ctx, handles).await,
             Commands::Fork(args) => args.run(ctx, &handles).await,
+            Commands::Tree(args) => args.run(ctx, &handles),
+            Commands::Diff(args) => args.run(ctx, handles),
             Commands::Compact(args) => args.run(ctx, handles).await,
             Commands::Grep(args) => args.run(ctx, handles),
             Commands::Label(args) => args.run(ctx, handles).await,
@@ -50,6 +54,8 @@ impl Conversation {
             Commands::Remove(args) => args.conversation_load_request(),
             Commands::Edit(args) => args.conversation_load_request(),
             Commands::Fork(args) => args.conversation_load_request(),
+            Commands::Tree(args) => args.conversation_load_request(),
+            Commands::Diff(args) => args.conversation_load_request(),
             Commands::Compact(args) => args.conversation_load_request(),
             Commands::Grep(args) => args.conversation_load_request(),
             Commands::Label(args) => args.conversation_load_request(),
@@ -69,13 +75,18 @@ impl IntoPartialAppConfig for Conversation {
         workspace: Option<&Workspace>,
         partial: PartialAppConfig,
         merged_config: Option<&PartialAppConfig>,
+        env: &Env,
     ) -> std::result::Result<PartialAppConfig, Box<dyn std::error::Error + Send + Sync>> {
         match &self.command {
-            Commands::Compact(args) => args.apply_cli_config(workspace, partial, merged_config),
+            Commands::Compact(args) => {
+                args.apply_cli_config(workspace, partial, merged_config, env)
+            }
             Commands::Show(_)
             | Commands::Remove(_)
             | Commands::Edit(_)
             | Commands::Fork(_)
+            | Commands::Tree(_)
+            | Commands::Diff(_)
             | Commands::Grep(_)
             | Commands::Label(_)
             | Commands::Print(_)
@@ -114,6 +125,17 @@ enum Commands {
     #[command(name = "fork", visible_alias = "f")]
     Fork(fork::Fork),
 
+    /// Show how conversations were forked from each other.
+    #[command(name = "tree", visible_alias = "t")]
+    Tree(tree::Tree),
+
+    /// Compare two conversations turn by turn.
+    ///
+    /// With a single conversation, compares it against the conversation it was
+    /// forked from.
+    #[command(name = "diff", visible_alias = "d")]
+    Diff(diff::Diff),
+
     /// Compact a conversation to reduce context size.
     ///
     /// Appends a compaction overlay that instructs the LLM projection layer to
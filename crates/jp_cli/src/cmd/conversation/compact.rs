@@ -7,13 +7,14 @@ use jp_config::{
         CompactionConfig, CompactionRuleConfig, PartialCompactionRuleConfig, PartialSummaryConfig,
         ReasoningMode, RuleBound, ToolCallsMode,
     },
+    env::Env,
 };
 use jp_conversation::{
     Compaction, CompactionRange, ConversationStream, RangeBound, ReasoningPolicy, SummaryPolicy,
     ToolCallPolicy,
     compaction::{extend_summary_range, resolve_range},
 };
-use jp_workspace::{ConversationHandle, ConversationMut, Workspace};
+use jp_workspace::{ConversationHandle, ConversationMut, ScratchRecorder, Workspace};
 use tracing::warn;
 
 use crate::{
@@ -258,6 +259,7 @@ impl IntoPartialAppConfig for Compact {
         _: Option<&Workspace>,
         mut partial: PartialAppConfig,
         merged_config: Option<&PartialAppConfig>,
+        _: &Env,
     ) -> std::result::Result<PartialAppConfig, Box<dyn std::error::Error + Send + Sync>> {
         apply_model(&mut partial, self.model.as_deref(), merged_config);
 
@@ -374,9 +376,11 @@ fn resolve_rule_range(
 async fn build_compaction_for_range(
     events: &ConversationStream,
     cfg: &jp_config::AppConfig,
+    env: &Env,
     rule: &CompactionRuleConfig,
     range: CompactionRange,
     printer: Option<&jp_printer::Printer>,
+    scratch: Option<&ScratchRecorder>,
 ) -> crate::Result<Compaction> {
     let summary_text = if rule.summary.is_some() {
         if let Some(printer) = printer {
@@ -388,6 +392,8 @@ async fn build_compaction_for_range(
             range.to_turn,
             rule.summary.as_ref(),
             cfg,
+            env,
+            scratch,
         )
         .await?;
         Some(text)
@@ -408,13 +414,16 @@ async fn build_compaction_for_range(
 ///
 /// Each rule produces one `Compaction` event.
 /// Runtime range overrides (`--from`/`--to`) apply to every rule.
+/// Summary requests are recorded with `scratch`, if given.
 pub(crate) async fn build_compaction_events(
     events: &ConversationStream,
     cfg: &jp_config::AppConfig,
+    env: &Env,
     rules: &[CompactionRuleConfig],
     from_override: Bound,
     to_override: Bound,
     printer: Option<&jp_printer::Printer>,
+    scratch: Option<&ScratchRecorder>,
 ) -> crate::Result<Vec<Compaction>> {
     // Two distinct baselines:
     //
@@ -439,7 +448,8 @@ pub(crate) async fn build_compaction_events(
         ) else {
             continue;
         };
-        let compaction = build_compaction_for_range(events, cfg, rule, range, printer).await?;
+        let compaction =
+            build_compaction_for_range(events, cfg, env, rule, range, printer, scratch).await?;
         overlap.add_compaction(compaction.clone());
         compactions.push(compaction);
     }
@@ -741,13 +751,20 @@ impl Compact {
             return Ok(());
         }
 
+        let scratch = ScratchRecorder::new(
+            ctx.workspace.conversation_factory(),
+            conv.id(),
+            conv.projection(),
+        );
         let compactions = build_compaction_events(
             &events_snapshot,
             &cfg,
+            &ctx.env,
             &rules,
             from_override,
             to_override,
             Some(&ctx.printer),
+            Some(&scratch),
         )
         .await?;
 
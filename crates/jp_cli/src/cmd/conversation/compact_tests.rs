@@ -7,6 +7,7 @@ use jp_config::{
         CompactionConfig, CompactionRuleConfig, PartialCompactionRuleConfig, PartialSummaryConfig,
         ReasoningMode, RuleBound, ToolCallsMode,
     },
+    env::Env,
     model::{PartialModelConfig, id::PartialModelIdOrAliasConfig},
 };
 use jp_conversation::{
@@ -65,7 +66,9 @@ fn model_flag_targets_the_assistant_model() {
     // fallback: an unset `summary.model` means "use the assistant model".
     let compact = parse_compact(&["--summarize", "--model", "gpt"]);
     let mut partial = PartialAppConfig::new_test();
-    partial = compact.apply_cli_config(None, partial, None).unwrap();
+    partial = compact
+        .apply_cli_config(None, partial, None, &Env::process())
+        .unwrap();
 
     assert_eq!(
         partial.assistant.model.id,
@@ -100,7 +103,9 @@ fn model_alias_reaches_a_configured_summary_model_through_the_pipeline() {
     // No `--summarize`: a policy flag would replace the configured rule with an
     // ad-hoc one, and the configured `summary.model` is what this exercises.
     let compact = parse_compact(&["--model", "gpt"]);
-    let partial = compact.apply_cli_config(None, partial, None).unwrap();
+    let partial = compact
+        .apply_cli_config(None, partial, None, &Env::process())
+        .unwrap();
     let cfg = jp_config::util::build(partial).unwrap();
 
     let rules = compact.effective_rules(&cfg).unwrap();
@@ -327,10 +332,12 @@ fn tool_calls_mode_maps_to_policy() {
             .block_on(build_compaction_events(
                 &stream,
                 &cfg,
+                &Env::process(),
                 std::slice::from_ref(&rule),
                 Bound::Default,
                 Bound::Default,
                 Some(&Printer::sink()),
+                None,
             ))
             .unwrap();
         assert_eq!(compactions.len(), 1, "non-empty range, mode {mode:?}");
@@ -359,10 +366,12 @@ fn keep_last_duration_covering_whole_conversation_compacts_nothing() {
         .block_on(build_compaction_events(
             &stream,
             &cfg,
+            &Env::process(),
             std::slice::from_ref(&rule),
             Bound::Default,
             Bound::Default,
             Some(&Printer::sink()),
+            None,
         ))
         .unwrap();
     assert!(
@@ -403,10 +412,12 @@ fn from_last_resolves_against_original_stream_for_every_rule() {
         .block_on(build_compaction_events(
             &stream,
             &cfg,
+            &Env::process(),
             &rules,
             Bound::At(RangeBound::AfterLastCompaction),
             Bound::Default,
             Some(&Printer::sink()),
+            None,
         ))
         .unwrap();
 
@@ -462,10 +473,12 @@ fn config_rule_strip_requests_blanks_args_through_projection() {
         .block_on(build_compaction_events(
             &stream,
             &cfg,
+            &Env::process(),
             &rules,
             Bound::Default,
             Bound::Default,
             Some(&Printer::sink()),
+            None,
         ))
         .unwrap();
 
@@ -525,10 +538,12 @@ fn keep_first_composes_with_first() {
         .block_on(build_compaction_events(
             &stream,
             &cfg,
+            &Env::process(),
             &rules,
             from,
             to,
             Some(&Printer::sink()),
+            None,
         ))
         .unwrap();
 
@@ -556,10 +571,12 @@ fn keep_last_composes_with_last() {
         .block_on(build_compaction_events(
             &stream,
             &cfg,
+            &Env::process(),
             &rules,
             from,
             to,
             Some(&Printer::sink()),
+            None,
         ))
         .unwrap();
 
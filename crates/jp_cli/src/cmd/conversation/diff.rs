@@ -0,0 +1,308 @@
+use chrono::{DateTime, Utc};
+use crossterm::style::Stylize as _;
+use jp_conversation::{ConversationId, ConversationStream};
+use jp_workspace::ConversationHandle;
+use serde_json::{Value, json};
+use similar::{Algorithm, DiffOp, TextDiff, capture_diff_slices};
+
+use crate::{
+    cmd::{ConversationLoadRequest, Output, conversation_id::PositionalIds},
+    ctx::Ctx,
+    output::print_json,
+    shared::search::{event_lines, event_scope},
+};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Diff {
+    /// The conversations to compare.
+    ///
+    /// With a single conversation, it is compared against the conversation it
+    /// was forked from.
+    #[command(flatten)]
+    target: PositionalIds<false, true>,
+}
+
+impl Diff {
+    pub(crate) fn conversation_load_request(&self) -> ConversationLoadRequest {
+        ConversationLoadRequest::explicit_or_session(&self.target)
+    }
+
+    pub(crate) fn run(self, ctx: &mut Ctx, handles: Vec<ConversationHandle>) -> Output {
+        let mut handles = handles.into_iter();
+        let (Some(a), b, None) = (handles.next(), handles.next(), handles.next()) else {
+            return Err("Expected one or two conversations to compare.".into());
+        };
+
+        let (a, b) = match b {
+            Some(b) => (a, b),
+            None => {
+                let origin = ctx.workspace.metadata(&a)?.forked_from.ok_or_else(|| {
+                    format!(
+                        "Conversation {} was not forked; pass a second conversation to compare \
+                         against.",
+                        a.id()
+                    )
+                })?;
+
+                let source = ctx.workspace.acquire_conversation(&origin.conversation)?;
+                (source, a)
+            }
+        };
+
+        let old = turns(&ctx.workspace.events(&a)?);
+        let new = turns(&ctx.workspace.events(&b)?);
+        let sections = compare(&old, &new);
+
+        if ctx.printer.format().is_json() {
+            let value = json!({
+                "a": a.id().to_string(),
+                "b": b.id().to_string(),
+                "turns": sections.iter().map(Section::to_json).collect::<Vec<_>>(),
+            });
+            print_json(&ctx.printer, &value);
+            return Ok(());
+        }
+
+        let pretty = ctx.printer.pretty_printing_enabled();
+        let out = render(a.id(), b.id(), &sections, pretty);
+        ctx.printer.print(out);
+
+        Ok(())
+    }
+}
+
+/// A turn, as the text it is compared by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct TurnText {
+    /// When the turn started.
+    ///
+    /// Forks keep the timestamps of the events they copy, so turns the two
+    /// conversations share start at the same time.
+    pub started_at: Option<DateTime<Utc>>,
+
+    /// The content of the turn, with a `[scope]` line before each event.
+    pub text: String,
+}
+
+/// The turns of a conversation.
+pub(super) fn turns(events: &ConversationStream) -> Vec<TurnText> {
+    events
+        .iter_turns()
+        .map(|turn| {
+            let mut text = String::new();
+            for event in &turn {
+                let Some(scope) = event_scope(&event.event.kind) else {
+                    continue;
+                };
+
+                text.push_str(&format!("[{scope}]\n"));
+                for line in event_lines(&event.event.kind) {
+                    text.push_str(&line);
+                    text.push('\n');
+                }
+            }
+
+            TurnText {
+                started_at: turn.iter().next().map(|event| event.event.timestamp),
+                text,
+            }
+        })
+        .collect()
+}
+
+/// A part of the comparison of two conversations.
+///
+/// Turn numbers are 1-based.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Section {
+    /// A run of turns that are identical in both conversations.
+    Shared { a: usize, b: usize, len: usize },
+
+    /// A turn that differs between the conversations, or exists in only one
+    /// of them.
+    Changed {
+        a: Option<usize>,
+        b: Option<usize>,
+        diff: String,
+    },
+}
+
+impl Section {
+    fn to_json(&self) -> Value {
+        match self {
+            Self::Shared { a, b, len } => json!({
+                "shared": true,
+                "a": (*a..a + len).collect::<Vec<_>>(),
+                "b": (*b..b + len).collect::<Vec<_>>(),
+            }),
+            Self::Changed { a, b, diff } => json!({
+                "shared": false,
+                "a": a,
+                "b": b,
+                "diff": diff,
+            }),
+        }
+    }
+}
+
+/// Compare the turns of two conversations.
+///
+/// Turns are paired by the time they started, so the turns a fork shares with
+/// its source line up, and the turns after the branch point are compared with
+/// each other.
+pub(super) fn compare(a: &[TurnText], b: &[TurnText]) -> Vec<Section> {
+    let keys = |turns: &[TurnText]| turns.iter().map(|t| t.started_at).collect::<Vec<_>>();
+    let (a_keys, b_keys) = (keys(a), keys(b));
+
+    let mut sections = vec![];
+    let changed = |a_index: Option<usize>, b_index: Option<usize>| {
+        let old = a_index.map_or("", |i| a[i].text.as_str());
+        let new = b_index.map_or("", |i| b[i].text.as_str());
+        Section::Changed {
+            a: a_index.map(|i| i + 1),
+            b: b_index.map(|i| i + 1),
+            diff: unified_diff(old, new, a_index, b_index),
+        }
+    };
+
+    for op in capture_diff_slices(Algorithm::Myers, &a_keys, &b_keys) {
+        match op {
+            DiffOp::Equal {
+                old_index,
+                new_index,
+                len,
+            } => {
+                // Turns that started at the same time can still differ, for
+                // example when one of them was edited after the fork.
+                let mut start = 0;
+                for i in 0..=len {
+                    if i < len && a[old_index + i].text == b[new_index + i].text {
+                        continue;
+                    }
+
+                    if i > start {
+                        sections.push(Section::Shared {
+                            a: old_index + start + 1,
+                            b: new_index + start + 1,
+                            len: i - start,
+                        });
+                    }
+
+                    if i < len {
+                        sections.push(changed(Some(old_index + i), Some(new_index + i)));
+                    }
+                    start = i + 1;
+                }
+            }
+            DiffOp::Delete {
+                old_index, old_len, ..
+            } => {
+                for i in old_index..old_index + old_len {
+                    sections.push(changed(Some(i), None));
+                }
+            }
+            DiffOp::Insert {
+                new_index, new_len, ..
+            } => {
+                for i in new_index..new_index + new_len {
+                    sections.push(changed(None, Some(i)));
+                }
+            }
+            DiffOp::Replace {
+                old_index,
+                old_len,
+                new_index,
+                new_len,
+            } => {
+                for i in 0..old_len.max(new_len) {
+                    let a_index = (i < old_len).then_some(old_index + i);
+                    let b_index = (i < new_len).then_some(new_index + i);
+                    sections.push(changed(a_index, b_index));
+                }
+            }
+        }
+    }
+
+    sections
+}
+
+/// A unified diff between two turns, either of which may be missing.
+fn unified_diff(old: &str, new: &str, a: Option<usize>, b: Option<usize>) -> String {
+    let header = |side: &str, index: Option<usize>| {
+        index.map_or_else(
+            || "/dev/null".to_owned(),
+            |i| format!("{side}/turn {}", i + 1),
+        )
+    };
+
+    let mut out = format!("--- {}\n+++ {}\n", header("a", a), header("b", b));
+    for hunk in TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .iter_hunks()
+    {
+        out.push_str(&hunk.to_string());
+    }
+
+    out
+}
+
+/// Render the comparison of conversations `a` and `b`.
+fn render(a: ConversationId, b: ConversationId, sections: &[Section], pretty: bool) -> String {
+    let mut out = format!("a: {a}\nb: {b}\n");
+    if sections.is_empty() {
+        out.push_str("\nNeither conversation has any turns.\n");
+        return out;
+    }
+
+    for section in sections {
+        out.push('\n');
+        match section {
+            Section::Shared { a, b, len } => {
+                let range = |start: usize| match len {
+                    1 => format!("turn {start}"),
+                    _ => format!("turns {start}-{}", start + len - 1),
+                };
+
+                let line = if a == b {
+                    format!("Shared: {}.", range(*a))
+                } else {
+                    format!("Shared: {} of a, {} of b.", range(*a), range(*b))
+                };
+
+                out.push_str(&if pretty {
+                    line.dark_grey().to_string()
+                } else {
+                    line
+                });
+                out.push('\n');
+            }
+            Section::Changed { diff, .. } => {
+                for line in diff.lines() {
+                    let line = if !pretty {
+                        line.to_owned()
+                    } else if line.starts_with("---") || line.starts_with("+++") {
+                        line.bold().to_string()
+                    } else if line.starts_with('@') {
+                        line.cyan().to_string()
+                    } else if line.starts_with('+') {
+                        line.green().to_string()
+                    } else if line.starts_with('-') {
+                        line.red().to_string()
+                    } else {
+                        line.to_owned()
+                    };
+
+                    out.push_str(&line);
+                    out.push('\n');
+                }
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+#[path = "diff_tests.rs"]
+mod tests;
@@ -0,0 +1,71 @@
+use chrono::TimeZone as _;
+
+use super::*;
+
+fn turn(second: u32, text: &str) -> TurnText {
+    TurnText {
+        started_at: Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, second).unwrap()),
+        text: format!("[user]\n{text}\n"),
+    }
+}
+
+#[test]
+fn compare_collapses_shared_turns() {
+    let source = vec![turn(1, "hello"), turn(2, "approach A")];
+    let fork = vec![turn(1, "hello"), turn(3, "approach B")];
+
+    let sections = compare(&source, &fork);
+
+    assert_eq!(sections[0], Section::Shared { a: 1, b: 1, len: 1 });
+    assert_eq!(sections.len(), 2);
+
+    let Section::Changed { a, b, diff } = &sections[1] else {
+        panic!("expected a changed turn, got {:?}", sections[1]);
+    };
+    assert_eq!((*a, *b), (Some(2), Some(2)));
+    assert!(diff.starts_with("--- a/turn 2\n+++ b/turn 2\n"), "{diff}");
+    assert!(diff.contains("-approach A\n"), "{diff}");
+    assert!(diff.contains("+approach B\n"), "{diff}");
+}
+
+#[test]
+fn compare_lists_turns_only_in_one_conversation() {
+    let source = vec![turn(1, "hello"), turn(2, "more")];
+    let fork = vec![turn(1, "hello")];
+
+    let sections = compare(&source, &fork);
+
+    assert_eq!(sections.len(), 2);
+    let Section::Changed { a, b, diff } = &sections[1] else {
+        panic!("expected a changed turn, got {:?}", sections[1]);
+    };
+    assert_eq!((*a, *b), (Some(2), None));
+    assert!(diff.starts_with("--- a/turn 2\n+++ /dev/null\n"), "{diff}");
+}
+
+#[test]
+fn compare_reports_edited_turns_that_started_together() {
+    let source = vec![turn(1, "hello"), turn(2, "same"), turn(3, "tail")];
+    let fork = vec![turn(1, "hello, edited"), turn(2, "same"), turn(3, "tail")];
+
+    let sections = compare(&source, &fork);
+
+    assert!(matches!(sections[0], Section::Changed {
+        a: Some(1),
+        b: Some(1),
+        ..
+    }));
+    assert_eq!(sections[1], Section::Shared { a: 2, b: 2, len: 2 });
+    assert_eq!(sections.len(), 2);
+}
+
+#[test]
+fn compare_identical_conversations_is_one_shared_run() {
+    let turns = vec![turn(1, "a"), turn(2, "b")];
+
+    assert_eq!(compare(&turns, &turns), vec![Section::Shared {
+        a: 1,
+        b: 1,
+        len: 2
+    }]);
+}
@@ -5,7 +5,7 @@ use chrono::Utc;
 use crossterm::style::Stylize as _;
 use inquire::Confirm;
 use jp_config::{
-    AppConfig, PartialAppConfig, ToPartial as _, model::id::PartialModelIdOrAliasConfig,
+    AppConfig, PartialAppConfig, ToPartial as _, env::Env, model::id::PartialModelIdOrAliasConfig,
 };
 use jp_conversation::{
     ConversationEvent, ConversationId, ConversationStream,
@@ -276,8 +276,14 @@ impl Edit {
                 let title = match title {
                     Some(title) => title.clone(),
                     None => {
-                        generate_titles(&ctx.config(), ctx.printer.out_writer(), events, vec![])
-                            .await?
+                        generate_titles(
+                            &ctx.config(),
+                            &ctx.env,
+                            ctx.printer.out_writer(),
+                            events,
+                            vec![],
+                        )
+                        .await?
                     }
                 };
 
@@ -310,6 +316,7 @@ impl Edit {
 
 async fn generate_titles(
     config: &AppConfig,
+    env: &Env,
     mut writer: PrinterWriter<'_>,
     mut events: ConversationStream,
     mut rejected: Vec<String>,
@@ -329,7 +336,7 @@ async fn generate_titles(
     partial.assistant.model.id = PartialModelIdOrAliasConfig::Id(model_id.to_partial());
     events.add_config_delta(partial);
 
-    let provider = provider::get_provider(model_id.provider, &config.providers.llm)?;
+    let provider = provider::get_provider(model_id.provider, &config.providers.llm, env)?;
     let model_details = provider.model_details(&model_id.name).await?;
 
     let sections = title::title_instructions(count, &rejected);
@@ -408,7 +415,7 @@ async fn generate_titles(
     match result.as_str() {
         "More..." => {
             rejected.extend(titles);
-            Box::pin(generate_titles(config, writer, events, rejected)).await
+            Box::pin(generate_titles(config, env, writer, events, rejected)).await
         }
         "Manually enter a title" => {
             let title = inquire::Text::new("Title").prompt_with_writer(&mut writer)?;
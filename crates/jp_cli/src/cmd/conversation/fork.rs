@@ -1,9 +1,9 @@
 use std::sync::Arc;
 
-use jp_conversation::{ConversationStream, Error as ConversationError};
+use jp_conversation::{ConversationStream, Error as ConversationError, ForkOrigin};
 use jp_inquire::prompt::TerminalPromptBackend;
 use jp_storage::backend::Projection;
-use jp_workspace::{ConversationHandle, ConversationLock};
+use jp_workspace::{ConversationHandle, ConversationLock, ScratchRecorder};
 use tracing::debug;
 
 use crate::{
@@ -147,15 +147,22 @@ impl Fork {
                     .compact
                     .effective_rules(&cfg.conversation.compaction.rules)
                     .map_err(|e| crate::error::Error::Compaction(e.to_string()))?;
+                let scratch = ScratchRecorder::new(
+                    ctx.workspace.conversation_factory(),
+                    lock.id(),
+                    lock.projection(),
+                );
                 let compactions = super::compact::build_compaction_events(
                     &events_snapshot,
                     &cfg,
+                    &ctx.env,
                     &rules,
                     crate::cmd::turn_range::Bound::Default,
                     crate::cmd::turn_range::Bound::Default,
                     // Compaction during a fork is an implicit adjunct; only an
                     // explicit `jp c compact` reports compaction details.
                     None,
+                    Some(&scratch),
                 )
                 .await?;
                 for compaction in compactions {
@@ -216,19 +223,29 @@ pub(crate) async fn fork_conversation(
         &ctx.printer,
         &prompts,
     )
+    .with_env(ctx.env.clone())
     .automatic(Trigger::Fork)
     .await?;
 
     let mut new_conversation = ctx.workspace.metadata(source)?.clone();
     new_conversation.last_activated_at = now;
     new_conversation.expires_at = None;
+    // A fork is the user's own, even when forked from a scratch conversation.
+    new_conversation.hidden = false;
     new_conversation.labels.extend(resolved);
 
-    let mut new_events = ctx.workspace.events(source)?.clone().with_created_at(now);
+    let source_events = ctx.workspace.events(source)?;
+    let mut new_events = source_events.clone().with_created_at(now);
 
     filter(&mut new_events);
     new_events.sanitize();
 
+    new_conversation.forked_from = Some(ForkOrigin {
+        conversation: source.id(),
+        turn: branch_turn(&source_events, &new_events),
+    });
+    drop(source_events);
+
     // Inherit the source's storage locality so forking a `--local` conversation
     // doesn't project the fork into the workspace.
     let projection = ctx
@@ -255,6 +272,26 @@ pub(crate) async fn fork_conversation(
     Ok(lock)
 }
 
+/// The number of the source's turns that precede the point a fork branches off.
+///
+/// That is the turn after the last source turn the fork kept: a fork of the
+/// first three turns branches at turn 3, and a fork of the whole conversation,
+/// or of its last turns, at the source's end.
+/// A fork without turns branches at the start.
+fn branch_turn(source: &ConversationStream, fork: &ConversationStream) -> usize {
+    let Some(start) = fork
+        .iter_turns()
+        .last()
+        .and_then(|turn| turn.iter().next().map(|e| e.event.timestamp))
+    else {
+        return 0;
+    };
+
+    source
+        .turn_at_time(start)
+        .map_or(0, |turn| turn.index() + 1)
+}
+
 #[cfg(test)]
 #[path = "fork_tests.rs"]
 mod tests;
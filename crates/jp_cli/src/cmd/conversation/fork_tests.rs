@@ -7,7 +7,7 @@ use std::{
 
 use camino_tempfile::tempdir;
 use chrono::{DateTime, TimeZone as _, Utc};
-use jp_config::{AppConfig, PartialAppConfig};
+use jp_config::{AppConfig, PartialAppConfig, env::Env};
 use jp_conversation::{
     Conversation, ConversationEvent, ConversationId, ConversationStream,
     event::{ChatRequest, ChatResponse, TurnStart},
@@ -75,6 +75,13 @@ fn test_conversation_fork() {
                 }
 
                 assert!(convs[0].0.timestamp() < convs[1].0.timestamp());
+                assert_eq!(
+                    convs[1].1.forked_from.take(),
+                    Some(ForkOrigin {
+                        conversation: source_id,
+                        turn: 0,
+                    })
+                );
                 assert_eq!(convs[0].1, convs[1].1);
                 assert_eq!(convs[0].2, convs[1].2);
             },
@@ -130,6 +137,13 @@ fn test_conversation_fork() {
                 }
 
                 assert!(convs[0].0.timestamp() < convs[1].0.timestamp());
+                assert_eq!(
+                    convs[1].1.forked_from.take(),
+                    Some(ForkOrigin {
+                        conversation: source_id,
+                        turn: 1,
+                    })
+                );
                 assert_eq!(convs[0].1, convs[1].1);
                 convs[0].2.sanitize();
                 assert_eq!(convs[0].2, convs[1].2);
@@ -183,8 +197,10 @@ fn test_conversation_fork() {
                 let source = &convs[0].2;
                 let fork = &convs[1].2;
 
-                // Source keeps its turns; the fork has none.
+                // Source keeps its turns; the fork has none, and so branches
+                // off at the start.
                 assert_eq!(source.iter().count(), 2);
+                assert_eq!(convs[1].1.forked_from.map(|origin| origin.turn), Some(0));
                 assert!(fork.is_empty());
                 assert_eq!(fork.iter().count(), 0);
 
@@ -247,6 +263,13 @@ fn test_conversation_fork() {
                 }
 
                 assert!(convs[0].0.timestamp() < convs[1].0.timestamp());
+                assert_eq!(
+                    convs[1].1.forked_from.take(),
+                    Some(ForkOrigin {
+                        conversation: source_id,
+                        turn: 1,
+                    })
+                );
                 assert_eq!(convs[0].1, convs[1].1);
                 convs[0].2.sanitize();
                 assert_eq!(convs[0].2, convs[1].2);
@@ -699,6 +722,15 @@ fn test_conversation_fork() {
                     convs[1].2.last().unwrap().event.timestamp,
                     Utc.with_ymd_and_hms(2020, 1, 1, 0, 2, 0).unwrap(),
                 );
+
+                // The fork branches off after the one turn it kept.
+                assert_eq!(
+                    convs[1].1.forked_from,
+                    Some(ForkOrigin {
+                        conversation: convs[0].0,
+                        turn: 1,
+                    })
+                );
             },
         }),
         ("with first 2 and last 1", TestCase {
@@ -1008,6 +1040,7 @@ fn test_conversation_fork() {
             Runtime::new().unwrap(),
             Globals::default(),
             config,
+            Env::process(),
             None,
             printer,
         );
@@ -1104,6 +1137,7 @@ fn fork_reresolves_apply_on_fork_rules() {
         Runtime::new().unwrap(),
         Globals::default(),
         config,
+        Env::process(),
         None,
         printer,
     );
@@ -1201,6 +1235,7 @@ fn a_failing_fork_rule_creates_no_conversation() {
         Runtime::new().unwrap(),
         Globals::default(),
         config,
+        Env::process(),
         None,
         printer,
     );
@@ -1267,6 +1302,7 @@ fn fork_targets_correct_source() {
         Runtime::new().unwrap(),
         Globals::default(),
         config,
+        Env::process(),
         None,
         printer,
     );
@@ -1400,6 +1436,7 @@ fn fork_inherits_local_only_projection() {
         Runtime::new().unwrap(),
         Globals::default(),
         config,
+        Env::process(),
         None,
         printer,
     );
@@ -1,6 +1,7 @@
 use std::{collections::HashSet, fmt::Write as _, num::NonZeroUsize, ops::Range};
 
 use chrono::{DateTime, Utc};
+use clap_complete::engine::ArgValueCompleter;
 use crossterm::style::Stylize as _;
 use jp_conversation::ConversationId;
 use jp_term::{
@@ -18,6 +19,7 @@ use crate::{
         conversation_id::FlagIds,
         label::{self, LabelSelector},
     },
+    completion,
     ctx::Ctx,
     output::print_json,
     shared::search::{
@@ -121,7 +123,11 @@ pub(crate) struct Grep {
     ///
     /// `key=value` matches the exact value, a bare `key` matches any value.
     /// Repeat the flag to require several; every selector must match.
-    #[arg(long = "label", value_name = "KEY[=VALUE]")]
+    #[arg(
+        long = "label",
+        value_name = "KEY[=VALUE]",
+        add = ArgValueCompleter::new(completion::labels)
+    )]
     labels: Vec<LabelSelector>,
 
     /// Restrict the search to specific parts of the conversation.
@@ -160,9 +166,14 @@ impl Grep {
 
         let wanted = expand_scopes(&self.scopes);
 
-        // If handles were provided, search only those. Otherwise search all.
+        // If handles were provided, search only those. Otherwise search all
+        // but the hidden ones.
         let mut ids: Vec<_> = if handles.is_empty() {
-            ctx.workspace.conversations().map(|(id, _)| *id).collect()
+            ctx.workspace
+                .conversations()
+                .filter(|(_, c)| !c.hidden)
+                .map(|(id, _)| *id)
+                .collect()
         } else {
             handles.iter().map(ConversationHandle::id).collect()
         };
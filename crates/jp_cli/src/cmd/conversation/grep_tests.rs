@@ -3,7 +3,7 @@ use std::time::Duration;
 use camino_tempfile::tempdir;
 use chrono::{TimeZone as _, Utc};
 use clap::Parser as _;
-use jp_config::AppConfig;
+use jp_config::{AppConfig, env::Env};
 use jp_conversation::{
     Conversation, ConversationEvent, ConversationId,
     event::{ChatRequest, ChatResponse, ToolCallRequest, ToolCallResponse, TurnStart},
@@ -90,6 +90,7 @@ fn setup_conversations_with(
         tokio::runtime::Runtime::new().unwrap(),
         Globals::default(),
         config,
+        Env::process(),
         None,
         printer,
     );
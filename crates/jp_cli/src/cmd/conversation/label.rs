@@ -5,6 +5,7 @@
 //! The conversation is named with `--id`, which is accepted on either side of
 //! the verb.
 
+use clap_complete::engine::{ArgValueCandidates, ArgValueCompleter};
 use crossterm::style::Stylize as _;
 use jp_conversation::{Conversation, ConversationId};
 use jp_inquire::prompt::TerminalPromptBackend;
@@ -19,6 +20,7 @@ use crate::{
         label::{self, LabelDirective, resolve::Resolver},
         lock::{LockOutcome, LockRequest, acquire_lock},
     },
+    completion,
     ctx::Ctx,
     error::Error,
     format::{label_detail_item, label_text},
@@ -63,7 +65,11 @@ pub(crate) struct Add {
     /// `:name` resolves the `conversation.labels.name` rule and applies
     /// whatever it produces.
     /// Values are taken literally, so they may contain commas.
-    #[arg(value_name = "KEY[=VALUE]|:NAME", required = true)]
+    #[arg(
+        value_name = "KEY[=VALUE]|:NAME",
+        required = true,
+        add = ArgValueCompleter::new(completion::labels)
+    )]
     labels: Vec<String>,
 }
 
@@ -74,7 +80,7 @@ pub(crate) struct Rm {
     ///
     /// Removing a key the conversation doesn't carry is not an error, but it is
     /// reported.
-    #[arg(value_name = "KEY")]
+    #[arg(value_name = "KEY", add = ArgValueCandidates::new(completion::label_keys))]
     keys: Vec<String>,
 }
 
@@ -163,7 +169,8 @@ impl Label {
                 ctx.term.is_tty,
                 &ctx.printer,
                 &prompts,
-            );
+            )
+            .with_env(ctx.env.clone());
             let directives = label::expand_aliases(&directives, &resolver).await?;
 
             let applied = lock
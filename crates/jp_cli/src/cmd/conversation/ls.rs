@@ -1,4 +1,5 @@
 use chrono::{DateTime, FixedOffset, Local, Utc};
+use clap_complete::engine::ArgValueCompleter;
 use comfy_table::{Cell, CellAlignment, Row};
 use crossterm::style::{Color, Stylize as _};
 use jp_conversation::{Conversation, ConversationId};
@@ -10,12 +11,14 @@ use jp_term::{
 };
 use jp_workspace::ConversationHandle;
 
+use super::tree::tree_order;
 use crate::{
     cmd::{
         ConversationLoadRequest, Output,
         conversation_id::PositionalIds,
         label::{self, LabelSelector},
     },
+    completion,
     ctx::Ctx,
     output::print_table,
 };
@@ -56,8 +59,23 @@ pub(crate) struct Ls {
     ///
     /// `key=value` matches the exact value, a bare `key` matches any value.
     /// Repeat the flag to require several; every selector must match.
-    #[arg(long = "label", value_name = "KEY[=VALUE]")]
+    #[arg(
+        long = "label",
+        value_name = "KEY[=VALUE]",
+        add = ArgValueCompleter::new(completion::labels)
+    )]
     labels: Vec<LabelSelector>,
+
+    /// Group forks under the conversation they were forked from.
+    #[arg(long)]
+    tree: bool,
+
+    /// Include hidden conversations.
+    ///
+    /// These record the LLM requests JP makes on its own behalf, such as
+    /// generating a title or a compaction summary.
+    #[arg(long)]
+    hidden: bool,
 }
 
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
@@ -104,6 +122,11 @@ struct Details {
     expires_at: Option<DateTime<Utc>>,
     local: bool,
     external: bool,
+    parent: Option<ConversationId>,
+    forked_from: Option<ConversationId>,
+
+    /// The tree-drawing prefix of the ID cell, with `--tree`.
+    tree_prefix: String,
 }
 
 impl Ls {
@@ -137,11 +160,18 @@ impl Ls {
                 expires_at: c.expires_at,
                 local,
                 external,
+                parent: c.parent,
+                forked_from: c.forked_from.map(|f| f.conversation),
+                tree_prefix: String::new(),
                 id,
             };
 
+        // Hidden conversations are listed when asked for, either by `--hidden`
+        // or by ID.
         let matches_filters = |id: &ConversationId, c: &Conversation, local: bool| -> bool {
-            filter_ids.as_ref().is_none_or(|f| f.contains(id))
+            filter_ids
+                .as_ref()
+                .map_or(self.hidden || !c.hidden, |f| f.contains(id))
                 && (!self.local || local)
                 && label::matches(&c.labels, &self.labels)
         };
@@ -200,12 +230,16 @@ impl Ls {
             if self.descending { ord.reverse() } else { ord }
         });
 
-        let conversations: Vec<_> = conversations.into_iter().skip(skip).collect();
+        let mut conversations: Vec<_> = conversations.into_iter().skip(skip).collect();
+        if self.tree {
+            conversations = tree(conversations);
+        }
         let hidden = if count > limit { skip } else { 0 };
 
         let mut columns = Columns {
             expires_at: conversations.iter().any(|d| d.expires_at.is_some()),
             local: conversations.iter().any(|d| d.local || d.external),
+            parent: conversations.iter().any(|d| d.parent.is_some()),
             title: conversations.iter().any(|d| d.title.is_some()),
         };
 
@@ -289,7 +323,7 @@ impl Ls {
         };
 
         let mut row = Row::new();
-        row.add_cell(Cell::new(id_fmt));
+        row.add_cell(Cell::new(format!("{}{id_fmt}", details.tree_prefix)));
         row.add_cell(Cell::new(messages_fmt).set_alignment(CellAlignment::Right));
         row.add_cell(Cell::new(last_message_at_fmt).set_alignment(CellAlignment::Right));
 
@@ -311,6 +345,11 @@ impl Ls {
             row.add_cell(Cell::new(cell).set_alignment(CellAlignment::Center));
         }
 
+        if columns.parent {
+            let parent = details.parent.map(|id| id.to_string()).unwrap_or_default();
+            row.add_cell(Cell::new(parent));
+        }
+
         if columns.title {
             let title = details.title.clone().unwrap_or_default();
             let title = match title_budget {
@@ -379,6 +418,10 @@ fn build_header_row(columns: Columns, marker: Option<SortMarker>) -> Row {
         );
     }
 
+    if columns.parent {
+        header.add_cell(Cell::new("Parent"));
+    }
+
     if columns.title {
         header.add_cell(Cell::new(TITLE_HEADER).set_alignment(CellAlignment::Left));
     }
@@ -407,6 +450,25 @@ fn sort_marker(sort: Option<Sort>, archived: bool, descending: bool) -> Option<S
     Some(SortMarker { column, descending })
 }
 
+/// Reorder `conversations` so forks follow the conversation they were forked
+/// from, keeping the sort order among siblings.
+fn tree(conversations: Vec<Details>) -> Vec<Details> {
+    let links: Vec<_> = conversations
+        .iter()
+        .map(|d| (d.id, d.forked_from))
+        .collect();
+
+    let mut slots: Vec<_> = conversations.into_iter().map(Some).collect();
+    tree_order(&links)
+        .into_iter()
+        .filter_map(|(index, prefix)| {
+            let mut details = slots[index].take()?;
+            details.tree_prefix = prefix;
+            Some(details)
+        })
+        .collect()
+}
+
 /// Render the storage-locality cell for a conversation row.
 ///
 /// - `Y` (blue): user-local only, no workspace projection.
@@ -428,12 +490,13 @@ const TITLE_HEADER: &str = "Title";
 
 /// Which optional columns the conversation table renders.
 ///
-/// `ID`, `#`, and `Activity` are always present; these three appear only when
+/// `ID`, `#`, and `Activity` are always present; these four appear only when
 /// at least one listed conversation carries the corresponding value.
 #[derive(Clone, Copy)]
 struct Columns {
     expires_at: bool,
     local: bool,
+    parent: bool,
     title: bool,
 }
 
@@ -58,6 +58,7 @@ fn header_marks_only_the_sorted_column() {
     let columns = Columns {
         expires_at: false,
         local: false,
+        parent: false,
         title: true,
     };
     let rendered = list(
@@ -69,6 +70,18 @@ fn header_marks_only_the_sorted_column() {
     assert!(!rendered.contains("ID ↑"), "got:\n{rendered}");
 }
 
+#[test]
+fn header_shows_parent_column_for_child_conversations() {
+    let columns = Columns {
+        expires_at: false,
+        local: false,
+        parent: true,
+        title: true,
+    };
+    let rendered = list(build_header_row(columns, None), vec![], false);
+    assert!(rendered.contains("Parent"), "got:\n{rendered}");
+}
+
 #[test]
 fn local_cell_marks_external_distinctly() {
     assert_eq!(strip_str(local_cell(false, false)), "N");
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use camino_tempfile::{Utf8TempDir, tempdir};
-use jp_config::AppConfig;
+use jp_config::{AppConfig, env::Env};
 use jp_conversation::{Conversation, ConversationId};
 use jp_printer::{OutputFormat, Printer, SharedBuffer};
 use jp_storage::backend::FsStorageBackend;
@@ -38,6 +38,7 @@ fn setup(id: ConversationId) -> (Ctx, SharedBuffer, Utf8TempDir) {
         Runtime::new().unwrap(),
         Globals::default(),
         config,
+        Env::process(),
         None,
         printer,
     );
@@ -174,6 +174,8 @@ impl Print {
         let invocation = InvocationContext {
             workspace_id: ctx.workspace.id().to_string(),
             conversation_id: handle.id().to_string(),
+            env: ctx.env.clone(),
+            ..Default::default()
         };
 
         let mut renderer = TurnRenderer::new(
@@ -5,6 +5,7 @@ use chrono::{DateTime, TimeZone as _, Utc};
 use jp_config::{
     AppConfig, PartialAppConfig,
     conversation::tool::style::{InlineResults, LinkStyle, ParametersStyle},
+    env::Env,
     style::reasoning::{ReasoningDisplayConfig, TruncateChars},
 };
 use jp_conversation::{
@@ -56,6 +57,7 @@ fn setup_ctx_with_config(
         Runtime::new().unwrap(),
         Globals::default(),
         config,
+        Env::process(),
         None,
         printer,
     );
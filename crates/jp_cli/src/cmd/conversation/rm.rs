@@ -9,6 +9,7 @@ use jp_workspace::{ConversationHandle, Workspace};
 use crate::{
     cmd::{
         ConversationLoadRequest, Output,
+        checkpoint::TurnCheckpoints,
         conversation_id::PositionalIds,
         lock::{LockOutcome, LockRequest, acquire_lock},
         time::CreationRange,
@@ -57,6 +58,11 @@ impl Rm {
         // explicit `--no-confirm` / `--yes` skips it.
         let force = self.confirm.preference() == Some(false);
         for handle in handles {
+            // Already removed together with its parent.
+            if ctx.workspace.acquire_conversation(&handle.id()).is_err() {
+                continue;
+            }
+
             remove(ctx, handle, active_id, force).await?;
         }
 
@@ -104,8 +110,45 @@ async fn remove(
         LockOutcome::ForkConversation(_) => unreachable!("fork not allowed"),
     };
 
-    confirm_and_remove(ctx, id, &lock, active_id, force)?;
+    // Child conversations, such as those of sub-agents, are removed with
+    // their parent.
+    // All of them are locked before anything is removed, and removed before
+    // the parent, so a failure never leaves children without a parent.
+    let mut children = vec![];
+    for child in ctx.workspace.child_conversation_ids(&id) {
+        let handle = ctx.workspace.acquire_conversation(&child)?;
+        match acquire_lock(LockRequest::from_ctx(handle, ctx)).await? {
+            LockOutcome::Acquired(lock) => children.push(lock),
+            LockOutcome::NewConversation => unreachable!("new conversation not allowed"),
+            LockOutcome::ForkConversation(_) => unreachable!("fork not allowed"),
+        }
+    }
+
+    confirm_and_remove(ctx, id, &lock, active_id, children.len(), force)?;
+
+    for lock in children {
+        let child = lock.id();
+        ctx.workspace.remove_conversation_with_lock(lock.into_mut());
+        remove_checkpoints(ctx, &child)?;
+    }
+
     ctx.workspace.remove_conversation_with_lock(lock.into_mut());
+    remove_checkpoints(ctx, &id)?;
+
+    Ok(())
+}
+
+fn remove_checkpoints(ctx: &Ctx, id: &ConversationId) -> Output {
+    if let Some(checkpoints) = ctx
+        .fs_backend
+        .as_deref()
+        .and_then(|fs| TurnCheckpoints::new(fs, id))
+    {
+        checkpoints
+            .remove_all()
+            .map_err(|error| error.to_string())?;
+    }
+
     Ok(())
 }
 
@@ -114,6 +157,7 @@ fn confirm_and_remove(
     id: ConversationId,
     lock: &jp_workspace::ConversationLock,
     active_id: Option<ConversationId>,
+    children: usize,
     force: bool,
 ) -> Output {
     let conversation = lock.metadata();
@@ -134,6 +178,12 @@ fn confirm_and_remove(
         ));
 
         writeln!(ctx.printer.prompt_writer(), "{details}\n")?;
+        if children > 0 {
+            writeln!(
+                ctx.printer.prompt_writer(),
+                "Its {children} child conversation(s) are removed with it.\n"
+            )?;
+        }
 
         let confirm = Confirm::new("Are you sure?")
             .with_default(false)
@@ -1,13 +1,13 @@
 //! LLM-assisted conversation summarization for compaction.
 
 use jp_config::{
-    AppConfig, PartialAppConfig, ToPartial as _, conversation::compaction::SummaryConfig,
+    AppConfig, PartialAppConfig, ToPartial as _, conversation::compaction::SummaryConfig, env::Env,
     model::id::ModelIdConfig,
 };
 use jp_conversation::{
     ConversationEvent, ConversationStream,
     event::{ChatRequest, ChatResponse},
-    thread::ThreadBuilder,
+    thread::{Thread, ThreadBuilder},
 };
 use jp_llm::{
     Provider,
@@ -17,6 +17,7 @@ use jp_llm::{
     provider,
     retry::{RetryConfig, collect_with_retry},
 };
+use jp_workspace::ScratchRecorder;
 use tracing::debug;
 
 use crate::error::{Error, Result};
@@ -40,12 +41,17 @@ Be concise but thorough. The reader should be able to continue the conversation
 /// The summary is a plain text string suitable for storing in a
 /// `SummaryPolicy`.
 /// The summarizer reads the raw (non-compacted) events.
+///
+/// With a `scratch` recorder, the request and the summary are recorded as a
+/// scratch conversation, also when summarizing fails.
 pub async fn generate_summary(
     events: &ConversationStream,
     range_from: usize,
     range_to: usize,
     summary_cfg: Option<&SummaryConfig>,
     app_cfg: &AppConfig,
+    env: &Env,
+    scratch: Option<&ScratchRecorder>,
 ) -> Result<String> {
     let model = summary_cfg
         .and_then(|c| c.model.clone())
@@ -86,10 +92,23 @@ pub async fn generate_summary(
         None => "Summarize the conversation above.".to_owned(),
     };
 
-    let provider = provider::get_provider(model_id.provider, &app_cfg.providers.llm)?;
+    let provider = provider::get_provider(model_id.provider, &app_cfg.providers.llm, env)?;
     let model_details = provider.model_details(&model_id.name).await?;
 
-    summarize_stream(
+    let request = scratch.map(|recorder| {
+        let mut events = stream.clone();
+        events.start_turn(ChatRequest::from(user_message.clone()));
+        let thread = Thread {
+            system_prompt: Some(instructions.to_owned()),
+            sections: vec![],
+            attachments: vec![],
+            events,
+        };
+
+        (recorder, thread.to_stream(&model_id))
+    });
+
+    let summary = summarize_stream(
         provider.as_ref(),
         &model_details,
         &model_id,
@@ -98,7 +117,16 @@ pub async fn generate_summary(
         &user_message,
         app_cfg.assistant.request.max_response_bytes,
     )
-    .await
+    .await;
+
+    if let Some((recorder, mut events)) = request {
+        if let Ok(summary) = &summary {
+            events.extend([ConversationEvent::now(ChatResponse::message(summary))]);
+        }
+        recorder.record("Compaction summary", events);
+    }
+
+    summary
 }
 
 /// Request a summary of `stream`, honouring provider rebuild requests.
@@ -0,0 +1,236 @@
+use std::collections::{HashMap, HashSet};
+
+use crossterm::style::Stylize as _;
+use jp_conversation::{Conversation, ConversationId, ForkOrigin};
+use jp_term::osc::hyperlink;
+use jp_workspace::ConversationHandle;
+use serde_json::{Value, json};
+
+use crate::{
+    cmd::{ConversationLoadRequest, Output, conversation_id::PositionalIds},
+    ctx::Ctx,
+    output::print_json,
+};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Tree {
+    /// Only show the fork tree that contains this conversation.
+    #[command(flatten)]
+    target: PositionalIds<false, false>,
+}
+
+impl Tree {
+    pub(crate) fn conversation_load_request(&self) -> ConversationLoadRequest {
+        ConversationLoadRequest::explicit_or_none(&self.target)
+    }
+
+    #[expect(clippy::unnecessary_wraps)]
+    pub(crate) fn run(&self, ctx: &mut Ctx, handles: &[ConversationHandle]) -> Output {
+        let active = ctx
+            .session
+            .as_ref()
+            .and_then(|s| ctx.workspace.session_active_conversation(s));
+
+        let mut conversations: Vec<(ConversationId, Conversation)> = ctx
+            .workspace
+            .conversations()
+            .map(|(id, c)| (*id, c.clone()))
+            .collect();
+        conversations.sort_by_key(|(id, _)| *id);
+
+        let links: Vec<_> = conversations
+            .iter()
+            .map(|(id, c)| (*id, c.forked_from.map(|f| f.conversation)))
+            .collect();
+
+        // Keep the trees that contain the requested conversation, or every
+        // tree with at least one fork.
+        let roots = roots(&links);
+        let keep: HashSet<ConversationId> = match handles.first() {
+            Some(handle) => roots
+                .get(&handle.id())
+                .map(|root| HashSet::from([*root]))
+                .unwrap_or_default(),
+            None => links
+                .iter()
+                .filter(|(_, source)| source.is_some())
+                .filter_map(|(id, _)| roots.get(id).copied())
+                .collect(),
+        };
+
+        let rows: Vec<_> = tree_order(&links)
+            .into_iter()
+            .filter(|(index, _)| {
+                roots
+                    .get(&links[*index].0)
+                    .is_some_and(|r| keep.contains(r))
+            })
+            .collect();
+
+        if ctx.printer.format().is_json() {
+            let value = rows
+                .iter()
+                .map(|(index, _)| {
+                    let (id, c) = &conversations[*index];
+                    node_json(*id, c)
+                })
+                .collect::<Vec<_>>();
+            print_json(&ctx.printer, &Value::Array(value));
+            return Ok(());
+        }
+
+        if rows.is_empty() {
+            ctx.printer.println("No forked conversations.");
+            return Ok(());
+        }
+
+        let pretty = ctx.printer.pretty_printing_enabled();
+        for (index, prefix) in rows {
+            let (id, c) = &conversations[index];
+            let line = render_node(*id, c, active == Some(*id), pretty);
+            ctx.printer.println(format!("{prefix}{line}"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Render a single conversation in the tree.
+fn render_node(id: ConversationId, c: &Conversation, active: bool, pretty: bool) -> String {
+    let mut line = if active {
+        id.to_string().bold().yellow().to_string()
+    } else {
+        id.to_string()
+    };
+
+    if pretty {
+        line = hyperlink(format!("jp://show-metadata/{id}"), line);
+    }
+
+    if let Some(title) = &c.title {
+        line.push_str(&format!("  {title}"));
+    }
+
+    if let Some(ForkOrigin { turn, .. }) = c.forked_from {
+        let note = format!("(forked after turn {turn})");
+        line.push_str("  ");
+        line.push_str(&if pretty {
+            note.dark_grey().to_string()
+        } else {
+            note
+        });
+    }
+
+    line
+}
+
+fn node_json(id: ConversationId, c: &Conversation) -> Value {
+    json!({
+        "id": id.to_string(),
+        "title": c.title,
+        "forked_from": c.forked_from.map(|f| json!({
+            "id": f.conversation.to_string(),
+            "turn": f.turn,
+        })),
+    })
+}
+
+/// The root of the fork tree each conversation belongs to.
+///
+/// A conversation whose source no longer exists is the root of its own tree.
+fn roots(
+    links: &[(ConversationId, Option<ConversationId>)],
+) -> HashMap<ConversationId, ConversationId> {
+    let sources: HashMap<_, _> = links
+        .iter()
+        .filter_map(|(id, source)| source.map(|s| (*id, s)))
+        .collect();
+    let known: HashSet<_> = links.iter().map(|(id, _)| *id).collect();
+
+    links
+        .iter()
+        .map(|(id, _)| {
+            let mut root = *id;
+            let mut seen = HashSet::from([root]);
+            while let Some(source) = sources.get(&root).filter(|s| known.contains(s)) {
+                // Guard against cycles in hand-edited metadata.
+                if !seen.insert(*source) {
+                    break;
+                }
+                root = *source;
+            }
+            (*id, root)
+        })
+        .collect()
+}
+
+/// Order conversations so that forks follow their source, depth first.
+///
+/// `links` pairs each conversation with the conversation it was forked from.
+/// Siblings, and the roots, keep their order in `links`.
+///
+/// Returns the index into `links` of each conversation, with the tree-drawing
+/// prefix to print before it.
+pub(super) fn tree_order(
+    links: &[(ConversationId, Option<ConversationId>)],
+) -> Vec<(usize, String)> {
+    let index: HashMap<_, _> = links
+        .iter()
+        .enumerate()
+        .map(|(i, (id, _))| (*id, i))
+        .collect();
+
+    let mut children: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut roots = vec![];
+    for (i, (_, source)) in links.iter().enumerate() {
+        match source.and_then(|s| index.get(&s)) {
+            Some(&parent) if parent != i => children.entry(parent).or_default().push(i),
+            _ => roots.push(i),
+        }
+    }
+
+    let mut out = vec![];
+    let mut visited = HashSet::new();
+    for root in roots {
+        walk(root, "", None, &children, &mut visited, &mut out);
+    }
+
+    // Conversations in a source cycle have no root; list them flat.
+    for i in 0..links.len() {
+        if !visited.contains(&i) {
+            out.push((i, String::new()));
+        }
+    }
+
+    out
+}
+
+fn walk(
+    node: usize,
+    indent: &str,
+    last: Option<bool>,
+    children: &HashMap<usize, Vec<usize>>,
+    visited: &mut HashSet<usize>,
+    out: &mut Vec<(usize, String)>,
+) {
+    if !visited.insert(node) {
+        return;
+    }
+
+    let (prefix, child_indent) = match last {
+        None => (String::new(), String::new()),
+        Some(true) => (format!("{indent}└─ "), format!("{indent}   ")),
+        Some(false) => (format!("{indent}├─ "), format!("{indent}│  ")),
+    };
+    out.push((node, prefix));
+
+    let kids = children.get(&node).map(Vec::as_slice).unwrap_or_default();
+    for (i, &child) in kids.iter().enumerate() {
+        let is_last = i + 1 == kids.len();
+        walk(child, &child_indent, Some(is_last), children, visited, out);
+    }
+}
+
+#[cfg(test)]
+#[path = "tree_tests.rs"]
+mod tests;
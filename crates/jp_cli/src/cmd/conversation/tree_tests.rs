@@ -0,0 +1,57 @@
+use super::*;
+
+fn id(n: i64) -> ConversationId {
+    ConversationId::try_from_deciseconds(17_000_000_000 + n).unwrap()
+}
+
+fn render(links: &[(ConversationId, Option<ConversationId>)]) -> Vec<String> {
+    tree_order(links)
+        .into_iter()
+        .map(|(index, prefix)| {
+            let n = links[index].0.as_deciseconds() - 17_000_000_000;
+            format!("{prefix}{n}")
+        })
+        .collect()
+}
+
+#[test]
+fn tree_order_places_forks_under_their_source() {
+    let links = [
+        (id(1), None),
+        (id(2), Some(id(1))),
+        (id(3), None),
+        (id(4), Some(id(2))),
+        (id(5), Some(id(1))),
+    ];
+
+    assert_eq!(render(&links), vec!["1", "├─ 2", "│  └─ 4", "└─ 5", "3"]);
+}
+
+#[test]
+fn tree_order_treats_forks_of_missing_sources_as_roots() {
+    let links = [(id(2), Some(id(1))), (id(3), Some(id(2)))];
+
+    assert_eq!(render(&links), vec!["2", "└─ 3"]);
+}
+
+#[test]
+fn tree_order_lists_cycles_flat() {
+    let links = [(id(1), Some(id(2))), (id(2), Some(id(1))), (id(3), None)];
+
+    assert_eq!(render(&links), vec!["3", "1", "2"]);
+}
+
+#[test]
+fn roots_follow_fork_chains() {
+    let links = [
+        (id(1), None),
+        (id(2), Some(id(1))),
+        (id(3), Some(id(2))),
+        (id(4), Some(id(9))),
+    ];
+
+    let roots = roots(&links);
+    assert_eq!(roots[&id(1)], id(1));
+    assert_eq!(roots[&id(3)], id(1));
+    assert_eq!(roots[&id(4)], id(4));
+}
@@ -292,7 +292,8 @@ fn source_ids(
             // Apply the picker's pinned/session sub-filter to the workspace
             // listing. `candidate_ids` is left unset here — the surviving set
             // is what we're computing.
-            (!sub_filter.pinned || c.is_pinned())
+            !c.hidden
+                && (!sub_filter.pinned || c.is_pinned())
                 && (!sub_filter.session || session_ids.contains(id))
         })
         .map(|(id, _)| *id)
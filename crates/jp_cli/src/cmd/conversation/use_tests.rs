@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use chrono::{DateTime, TimeZone as _, Utc};
-use jp_config::AppConfig;
+use jp_config::{AppConfig, env::Env};
 use jp_conversation::{Conversation, ConversationEvent, ConversationId, event::ChatRequest};
 use jp_printer::{OutputFormat, Printer};
 use jp_workspace::{
@@ -58,6 +58,7 @@ fn setup(id: ConversationId) -> Ctx {
         Runtime::new().unwrap(),
         Globals::default(),
         AppConfig::new_test(),
+        Env::process(),
         Some(test_session()),
         printer,
     );
@@ -166,6 +167,7 @@ fn setup_multi(entries: Vec<(ConversationId, Conversation, Vec<ConversationEvent
         Runtime::new().unwrap(),
         Globals::default(),
         AppConfig::new_test(),
+        Env::process(),
         Some(test_session()),
         printer,
     );
@@ -18,9 +18,11 @@
 use std::{borrow::Cow, ffi::OsStr};
 
 use clap::{Arg, ArgAction, ArgMatches, Command, FromArgMatches, builder::TypedValueParser};
+use clap_complete::engine::ArgValueCandidates;
 use crossterm::style::Stylize as _;
 
 use super::target::ConversationTarget;
+use crate::completion;
 
 /// Positional conversation ID arguments: `[ID]` or `[ID]...`
 ///
@@ -109,7 +111,8 @@ impl<const SESSION: bool, const MULTI: bool> clap::Args for PositionalIds<SESSIO
         let mut arg = Arg::new("id")
             .value_parser(TargetParser::<SESSION>)
             .help(short_help())
-            .long_help(long_help(SESSION, MULTI));
+            .long_help(long_help(SESSION, MULTI))
+            .add(ArgValueCandidates::new(completion::conversations));
 
         if MULTI {
             arg = arg.action(ArgAction::Append);
@@ -151,7 +154,8 @@ impl<const SESSION: bool, const MULTI: bool, const GLOBAL: bool> clap::Args
             .help(short_help())
             .long_help(long_help(SESSION, MULTI))
             .num_args(0..=1)
-            .default_missing_value("");
+            .default_missing_value("")
+            .add(ArgValueCandidates::new(completion::conversations));
 
         if MULTI {
             arg = arg.action(ArgAction::Append).value_delimiter(',');
@@ -0,0 +1,203 @@
+use std::{
+    env,
+    num::NonZeroUsize,
+    os::unix::process::CommandExt as _,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use chrono::Utc;
+use comfy_table::{Cell, CellAlignment, Row};
+use jp_printer::Printer;
+
+use super::Output;
+use crate::{
+    Globals,
+    daemon::{
+        DaemonPaths, client,
+        protocol::{ClientMessage, JobId, ServerMessage},
+        server,
+    },
+    output::print_table,
+};
+
+/// How long to wait for the daemon to start listening or to shut down.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Daemon {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+impl Daemon {
+    /// Run a daemon command.
+    ///
+    /// Like `init`, these run before the regular startup pipeline: the client
+    /// commands only talk to the daemon, and the daemon itself runs the
+    /// pipeline for every job it accepts.
+    pub(crate) fn run(
+        &self,
+        globals: &Globals,
+        threads: Option<NonZeroUsize>,
+        printer: &Printer,
+    ) -> Output {
+        let paths = DaemonPaths::resolve(globals.workspace.as_ref())?;
+
+        match &self.command {
+            Commands::Start(args) if args.foreground => {
+                server::serve(&paths, threads).map_err(Into::into)
+            }
+            Commands::Start(_) => start(&paths, printer),
+            Commands::Stop => stop(&paths, printer),
+            Commands::Jobs => list_jobs(&paths, printer),
+            Commands::Attach(args) => {
+                let job = match args.job {
+                    Some(job) => job,
+                    None => latest_job(&paths)?,
+                };
+                client::attach(&paths.socket, job)
+            }
+            Commands::Cancel(args) => {
+                client::request(&paths.socket, &ClientMessage::Cancel { job: args.job })?;
+                printer.println(format!("Cancelled job {}.", args.job));
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Commands {
+    /// Start the workspace daemon in the background.
+    Start(Start),
+
+    /// Stop the workspace daemon, cancelling its running jobs.
+    Stop,
+
+    /// List the jobs run by the workspace daemon.
+    #[command(visible_alias = "ls")]
+    Jobs,
+
+    /// Stream the output of a daemon job, from its start.
+    ///
+    /// Defaults to the most recent job.
+    Attach(Attach),
+
+    /// Cancel a running daemon job.
+    Cancel(Cancel),
+}
+
+#[derive(Debug, clap::Args)]
+struct Start {
+    /// Run the daemon in the foreground instead of detaching it.
+    #[arg(long)]
+    foreground: bool,
+}
+
+#[derive(Debug, clap::Args)]
+struct Attach {
+    /// The job to attach to.
+    job: Option<JobId>,
+}
+
+#[derive(Debug, clap::Args)]
+struct Cancel {
+    /// The job to cancel.
+    job: JobId,
+}
+
+fn start(paths: &DaemonPaths, printer: &Printer) -> Output {
+    if client::is_running(&paths.socket) {
+        printer.println(format!("Daemon already running for {}.", paths.root));
+        return Ok(());
+    }
+
+    // A socket without a listener is left over from a daemon that didn't shut
+    // down cleanly.
+    if paths.socket.exists() {
+        std::fs::remove_file(&paths.socket)?;
+    }
+
+    // Put the daemon in its own process group, so that signals meant for the
+    // terminal that started it (Ctrl-C) don't reach it.
+    Command::new(env::current_exe()?)
+        .arg("--log-file")
+        .arg(&paths.log)
+        .args(["daemon", "start", "--foreground"])
+        .current_dir(&paths.root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()?;
+
+    if !client::wait_until(&paths.socket, true, STARTUP_TIMEOUT) {
+        return Err(format!(
+            "Daemon did not start listening in time. See {} for details.",
+            paths.log
+        )
+        .into());
+    }
+
+    printer.println(format!("Daemon started for {}.", paths.root));
+    Ok(())
+}
+
+fn stop(paths: &DaemonPaths, printer: &Printer) -> Output {
+    if !client::is_running(&paths.socket) {
+        printer.println("Daemon is not running.");
+        return Ok(());
+    }
+
+    client::request(&paths.socket, &ClientMessage::Shutdown)?;
+    if !client::wait_until(&paths.socket, false, STARTUP_TIMEOUT) {
+        return Err("Daemon is still shutting down; running jobs are being cancelled.".into());
+    }
+
+    printer.println("Daemon stopped.");
+    Ok(())
+}
+
+fn list_jobs(paths: &DaemonPaths, printer: &Printer) -> Output {
+    let ServerMessage::Jobs { jobs } = client::request(&paths.socket, &ClientMessage::Jobs)? else {
+        return Err("Unexpected response from the daemon.".into());
+    };
+
+    let mut header = Row::new();
+    header.add_cell(Cell::new("ID"));
+    header.add_cell(Cell::new("Status"));
+    header.add_cell(Cell::new("Started"));
+    header.add_cell(Cell::new("Command"));
+
+    let rows = jobs
+        .into_iter()
+        .map(|job| {
+            let status = match job.code {
+                None => "running".to_owned(),
+                Some(code) => format!("exit {code}"),
+            };
+            let ago = (Utc::now() - job.started_at).to_std().unwrap_or_default();
+
+            let mut row = Row::new();
+            row.add_cell(Cell::new(job.id).set_alignment(CellAlignment::Right));
+            row.add_cell(Cell::new(status));
+            row.add_cell(Cell::new(timeago::Formatter::new().convert(ago)));
+            row.add_cell(Cell::new(job.command));
+            row
+        })
+        .collect();
+
+    print_table(printer, header, rows, false);
+    Ok(())
+}
+
+fn latest_job(paths: &DaemonPaths) -> Result<JobId, super::Error> {
+    match client::request(&paths.socket, &ClientMessage::Jobs)? {
+        ServerMessage::Jobs { jobs } => jobs
+            .last()
+            .map(|job| job.id)
+            .ok_or_else(|| "The daemon has not run any jobs yet.".into()),
+        _ => Err("Unexpected response from the daemon.".into()),
+    }
+}
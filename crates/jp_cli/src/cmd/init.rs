@@ -8,6 +8,7 @@ use inquire::{Select, Text};
 use jp_config::{
     PartialAppConfig,
     conversation::tool::RunMode,
+    env::Env,
     fs::{ConfigLoader, Format, user_global_config_dir},
     model::id::{ModelIdConfig, Name, ProviderId},
 };
@@ -385,6 +386,7 @@ impl IntoPartialAppConfig for Init {
         _workspace: Option<&Workspace>,
         partial: PartialAppConfig,
         _: Option<&PartialAppConfig>,
+        _: &Env,
     ) -> std::result::Result<PartialAppConfig, Box<dyn std::error::Error + Send + Sync>> {
         Ok(partial)
     }
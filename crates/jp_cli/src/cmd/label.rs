@@ -26,10 +26,13 @@ pub(crate) mod resolve;
 use std::{collections::BTreeMap, str::FromStr};
 
 use clap::{ArgAction, ArgMatches, Error, error::ErrorKind};
+use clap_complete::engine::ArgValueCompleter;
 use jp_config::conversation::label;
 use jp_conversation::ConversationId;
 use jp_printer::Printer;
 
+use crate::completion;
+
 /// The prefix marking a label argument as a reference to a configured rule.
 const ALIAS_PREFIX: char = ':';
 
@@ -191,7 +194,8 @@ impl<const ALIASES: bool, const RESET: bool> clap::Args for LabelDirectives<ALIA
                      conversation label` to manage labels on an existing conversation."
                 ))
                 .action(ArgAction::Append)
-                .num_args(1),
+                .num_args(1)
+                .add(ArgValueCompleter::new(completion::labels)),
         );
 
         if !RESET {
@@ -26,6 +26,7 @@ use camino::Utf8Path;
 use indexmap::IndexMap;
 use jp_config::{
     conversation::label::{LabelConfig, LabelRunMode, LabelValueRef},
+    env::Env,
     types::command::{CommandConfig, shell_command_line},
 };
 use jp_inquire::{InlineOption, prompt::PromptBackend};
@@ -52,6 +53,9 @@ pub(crate) struct Resolver<'a> {
     is_tty: bool,
     printer: &'a Printer,
     prompts: &'a dyn PromptBackend,
+
+    /// The environment label commands run with.
+    env: Env,
 }
 
 impl<'a> Resolver<'a> {
@@ -68,9 +72,16 @@ impl<'a> Resolver<'a> {
             is_tty,
             printer,
             prompts,
+            env: Env::process(),
         }
     }
 
+    /// Run label commands with `env` instead of the process environment.
+    #[must_use]
+    pub(crate) fn with_env(self, env: Env) -> Self {
+        Self { env, ..self }
+    }
+
     /// Resolve every rule that opts into `trigger`.
     ///
     /// # Errors
@@ -109,7 +120,7 @@ impl<'a> Resolver<'a> {
             }
         }
 
-        for (key, output) in run_all(pending, self.root).await {
+        for (key, output) in run_all(pending, self.root, &self.env).await {
             match output {
                 Ok(value) => {
                     resolved.insert(key, value);
@@ -161,7 +172,7 @@ impl<'a> Resolver<'a> {
                     .eprintln(format!("⚠ Skipping label '{key}': command not run."));
                 Ok(None)
             }
-            Approval::Approved => run_command(&cmd, self.root)
+            Approval::Approved => run_command(&cmd, self.root, &self.env)
                 .await
                 .map(|value| Some((key.to_owned(), value)))
                 .map_err(|error| Error::Label(format!("label ':{key}' failed: {error}"))),
@@ -231,9 +242,10 @@ enum Approval {
 async fn run_all(
     pending: Vec<(String, CommandConfig)>,
     root: &Utf8Path,
+    env: &Env,
 ) -> Vec<(String, std::result::Result<String, String>)> {
     let futures = pending.into_iter().map(|(key, cmd)| async move {
-        let result = run_command(&cmd, root).await;
+        let result = run_command(&cmd, root, env).await;
         (key, result)
     });
 
@@ -252,7 +264,11 @@ async fn run_all(
 /// their lifecycle through a cancellation token; a label command has no such
 /// handle, and blocking conversation creation on an unkillable command would be
 /// worse than losing the label.
-async fn run_command(cmd: &CommandConfig, root: &Utf8Path) -> std::result::Result<String, String> {
+async fn run_command(
+    cmd: &CommandConfig,
+    root: &Utf8Path,
+    env: &Env,
+) -> std::result::Result<String, String> {
     let mut command = if cmd.shell {
         let mut command = Command::new("sh");
         command
@@ -265,6 +281,10 @@ async fn run_command(cmd: &CommandConfig, root: &Utf8Path) -> std::result::Resul
         command
     };
 
+    if let Some(vars) = env.overrides() {
+        command.env_clear().envs(vars);
+    }
+
     let output = command
         .current_dir(root.as_std_path())
         .kill_on_drop(true)
@@ -97,6 +97,29 @@ async fn shell_commands_get_a_shell() {
     assert_eq!(resolved["piped"], "a-b-c");
 }
 
+#[tokio::test]
+async fn commands_run_with_the_given_env() {
+    let (rules, tmp, printer, _err, prompts) = setup(
+        r#"{
+            "origin": {
+                "value": {
+                    "cmd": { "program": "echo ${JP_LABEL_ORIGIN:-unset}", "args": [], "shell": true }
+                },
+                "run": "unattended"
+            }
+        }"#,
+    );
+    let env = Env::captured([
+        ("PATH".to_owned(), std::env::var("PATH").unwrap_or_default()),
+        ("JP_LABEL_ORIGIN".to_owned(), "client".to_owned()),
+    ]);
+    let resolver = Resolver::new(&rules, tmp.path(), false, &printer, &prompts).with_env(env);
+
+    let resolved = resolver.automatic(Trigger::New).await.unwrap();
+
+    assert_eq!(resolved["origin"], "client");
+}
+
 /// A rule the user asked for by name resolves regardless of `apply_on`.
 #[tokio::test]
 async fn alias_ignores_apply_on() {
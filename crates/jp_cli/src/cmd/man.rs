@@ -0,0 +1,93 @@
+use std::{fs, io::Write as _};
+
+use camino::Utf8PathBuf;
+use clap::Command;
+use clap_mangen::Man as Page;
+use jp_printer::Printer;
+
+use crate::cmd::Output;
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Man {
+    /// The command to print the man page for, e.g. `conversation ls`.
+    ///
+    /// Prints the man page of `jp` itself when omitted.
+    #[arg(conflicts_with = "all")]
+    command: Vec<String>,
+
+    /// Write the man pages of all commands, one file per command.
+    #[arg(long, requires = "output_dir")]
+    all: bool,
+
+    /// The directory to write the man pages to.
+    #[arg(long, requires = "all", value_name = "DIR")]
+    output_dir: Option<Utf8PathBuf>,
+}
+
+impl Man {
+    pub(crate) fn run(&self, mut cmd: Command, printer: &Printer) -> Output {
+        // Building the command propagates the names of nested commands, so
+        // their pages are titled `jp-conversation-ls`.
+        cmd.build();
+
+        if let Some(dir) = &self.output_dir {
+            fs::create_dir_all(dir)?;
+            for page in pages(&cmd) {
+                let path = dir.join(format!("{}.1", title(page)));
+                fs::write(&path, render(page)?)?;
+                printer.println(format!("Wrote {path}"));
+            }
+
+            return Ok(());
+        }
+
+        let page = find(&cmd, &self.command)?;
+        let mut out = printer.out_writer();
+        out.write_all(&render(page)?)?;
+        out.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Find the command at `path`, accepting command aliases.
+fn find<'a>(cmd: &'a Command, path: &[String]) -> Result<&'a Command, String> {
+    let mut current = cmd;
+    for name in path {
+        current = current
+            .find_subcommand(name)
+            .filter(|cmd| !cmd.is_hide_set())
+            .ok_or_else(|| {
+                let parent = current.get_bin_name().unwrap_or_else(|| current.get_name());
+                format!("Unknown command: {parent} {name}")
+            })?;
+    }
+
+    Ok(current)
+}
+
+/// The command and all of its visible subcommands, depth-first.
+fn pages(cmd: &Command) -> Vec<&Command> {
+    let mut all = vec![cmd];
+    for subcommand in cmd.get_subcommands().filter(|cmd| !cmd.is_hide_set()) {
+        all.extend(pages(subcommand));
+    }
+
+    all
+}
+
+/// The name of the man page, e.g. `jp-conversation-ls`.
+fn title(cmd: &Command) -> &str {
+    cmd.get_display_name().unwrap_or_else(|| cmd.get_name())
+}
+
+fn render(cmd: &Command) -> std::io::Result<Vec<u8>> {
+    let mut buf = vec![];
+    Page::new(cmd.clone()).render(&mut buf)?;
+
+    Ok(buf)
+}
+
+#[cfg(test)]
+#[path = "man_tests.rs"]
+mod tests;
@@ -0,0 +1,54 @@
+use clap::CommandFactory as _;
+
+use super::*;
+use crate::Cli;
+
+fn cli() -> Command {
+    let mut cmd = Cli::command();
+    cmd.build();
+    cmd
+}
+
+#[test]
+fn test_find_accepts_aliases() {
+    let cmd = cli();
+    let path = |path: &[&str]| path.iter().map(|&s| s.to_owned()).collect::<Vec<_>>();
+
+    assert_eq!(title(find(&cmd, &[]).unwrap()), "jp");
+    assert_eq!(
+        title(find(&cmd, &path(&["conversation", "ls"])).unwrap()),
+        "jp-conversation-ls"
+    );
+    assert_eq!(
+        title(find(&cmd, &path(&["c", "ls"])).unwrap()),
+        "jp-conversation-ls"
+    );
+    assert_eq!(
+        find(&cmd, &path(&["conversation", "nope"])).unwrap_err(),
+        "Unknown command: jp conversation nope"
+    );
+
+    // Hidden commands have no man page.
+    assert!(find(&cmd, &path(&["aa"])).is_err());
+}
+
+#[test]
+fn test_pages_cover_visible_commands() {
+    let cmd = cli();
+    let titles: Vec<_> = pages(&cmd).into_iter().map(title).collect();
+
+    assert_eq!(titles[0], "jp");
+    assert!(titles.contains(&"jp-query"));
+    assert!(titles.contains(&"jp-conversation-ls"));
+    assert!(!titles.contains(&"jp-aa"));
+}
+
+#[test]
+fn test_render() {
+    let cmd = cli();
+    let page = render(find(&cmd, &["query".to_owned()]).unwrap()).unwrap();
+    let page = String::from_utf8(page).unwrap();
+
+    assert!(page.contains(".TH"), "{page}");
+    assert!(page.contains("Query the assistant"), "{page}");
+}
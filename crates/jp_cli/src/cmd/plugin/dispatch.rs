@@ -17,6 +17,7 @@ use std::{
 use camino::{Utf8Path, Utf8PathBuf};
 use jp_config::{
     AppConfig,
+    env::Env,
     plugins::{
         PluginsConfig,
         command::{CommandPluginConfig, RunPolicy},
@@ -42,6 +43,7 @@ use crate::{Ctx, cmd, signals::SignalRouter};
 ///
 /// `binary` is the path to the plugin executable.
 /// `args` are the remaining CLI arguments to forward.
+/// The plugin runs with `env`.
 pub(crate) fn run_plugin(
     name: &str,
     binary: &Utf8Path,
@@ -51,6 +53,7 @@ pub(crate) fn run_plugin(
     user_storage_path: Option<&Utf8Path>,
     config: &Arc<AppConfig>,
     signals: &SignalRouter,
+    env: &Env,
     log_level: u8,
 ) -> Result<(), cmd::Error> {
     let config_json = serde_json::to_value(config.as_ref().to_partial())
@@ -94,6 +97,10 @@ pub(crate) fn run_plugin(
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
+    if let Some(vars) = env.overrides() {
+        cmd.env_clear().envs(vars);
+    }
+
     // Prevent the child from receiving SIGINT/SIGTERM directly. The host
     // sends `Shutdown` over the protocol instead, giving the plugin a
     // chance to exit gracefully.
@@ -280,6 +287,7 @@ fn message_loop(
 fn handle_list_conversations(workspace: &Workspace, req_id: Option<String>) -> HostToPlugin {
     let data: Vec<ConversationSummary> = workspace
         .conversations()
+        .filter(|(_, meta)| !meta.hidden)
         .map(|(id, meta)| ConversationSummary {
             id: id.as_deciseconds().to_string(),
             title: meta.title.clone(),
@@ -899,6 +907,7 @@ pub(crate) async fn run_external(args: &[String], ctx: &Ctx) -> cmd::Output {
         ctx.user_storage_path(),
         &config,
         &ctx.signals,
+        &ctx.env,
         ctx.term.args.verbose,
     )?;
     Ok(())
@@ -46,23 +46,30 @@
 //! [`ToolCallResponse`]: jp_conversation::event::ToolCallResponse
 //! [`TurnCoordinator`]: turn::coordinator::TurnCoordinator
 
+mod events;
 mod interrupt;
+mod named_template;
+mod resume;
 mod stream;
+mod sub_agent;
 pub(crate) mod tool;
 mod turn;
+mod turn_config;
 mod turn_loop;
 
 use std::{
     borrow::Cow,
     collections::HashSet,
-    env, fs,
+    fs,
     io::{self, IsTerminal},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use camino::{Utf8Path, Utf8PathBuf};
-use clap::{ArgAction, builder::TypedValueParser as _};
+use clap::ArgAction;
+use clap_complete::engine::ArgValueCandidates;
+use events::{EventSink, StreamEvent, TurnStatus};
 use indexmap::IndexMap;
 use jp_attachment::Attachment;
 use jp_config::{
@@ -79,6 +86,7 @@ use jp_config::{
             access::{AccessConfig, PartialAccessConfig, PartialFsRuleConfig},
         },
     },
+    env::Env,
     fs::{expand_tilde, load_partial},
     model::parameters::{PartialCustomReasoningConfig, PartialReasoningConfig, ReasoningConfig},
     style::{mcp_startup::McpStartupConfig, reasoning::ReasoningDisplayConfig},
@@ -86,14 +94,22 @@ use jp_config::{
 use jp_conversation::{
     Conversation, ConversationEvent, ConversationId, ConversationStream,
     event::{ChatRequest, ChatResponse},
+    stream::ConfigDelta,
     thread::{Thread, ThreadBuilder},
 };
 use jp_inquire::prompt::{PromptBackend, TerminalPromptBackend};
 use jp_llm::{
-    ToolError, provider,
+    ToolError,
+    provider::ProviderCache,
     tool::{
-        InvocationContext, ToolDefinition, ToolDocs,
-        builtin::{BuiltinExecutors, describe_tools::DescribeTools},
+        BackgroundTasks, InvocationContext, ToolDefinition, ToolDocs,
+        builtin::{
+            BuiltinExecutors,
+            background::{AwaitHandles, CancelHandle, PollHandle},
+            describe_tools::DescribeTools,
+            sub_agent::{SubAgent, SubAgentRunner},
+        },
+        pty::ToolTerminal,
         tool_definitions,
     },
 };
@@ -102,11 +118,13 @@ use jp_printer::Printer;
 use jp_storage::backend::Projection;
 use jp_task::task::TitleGeneratorTask;
 use jp_term::width::{display_width, truncate_to_width};
-use jp_workspace::{ConversationHandle, ConversationLock, Workspace};
+use jp_workspace::{ConversationHandle, ConversationLock, ScratchRecorder, Workspace};
 use minijinja::{Environment, UndefinedBehavior};
-use tool::{TerminalExecutorSource, ToolCoordinator};
+use resume::IncompleteTurn;
+use sub_agent::SubAgents;
+use tool::{TerminalExecutorSource, ToolCoordinator, UserTerminal};
 use tracing::{debug, trace, warn};
-use turn_loop::run_turn_loop;
+use turn_loop::{TurnEntry, run_turn_loop};
 
 use super::{
     ConversationLoadRequest, Output,
@@ -123,6 +141,7 @@ use crate::{
     },
     cmd::{
         self,
+        checkpoint::TurnCheckpoints,
         conversation::fork,
         label::{
             self, LabelDirectives,
@@ -130,6 +149,7 @@ use crate::{
         },
         lock::{LockRequest, acquire_lock},
     },
+    completion,
     ctx::IntoPartialAppConfig,
     editor,
     error::{Error, Result},
@@ -138,6 +158,7 @@ use crate::{
     render::TurnView,
     signals::SignalRouter,
     timer::spawn_line_timer,
+    usage::UsageRecorder,
 };
 
 type BoxedResult<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
@@ -151,6 +172,25 @@ pub(crate) struct Query {
     #[arg(short = '%', long)]
     template: bool,
 
+    /// Run the named template `NAME`.
+    ///
+    /// Templates are defined in the `template.named` config, or as markdown
+    /// files in `.jp/templates/`.
+    /// Query words of the form `PARAM=VALUE` set the template's parameters, any
+    /// other words are appended to the rendered template.
+    /// Parameters without a value or default are prompted for.
+    ///
+    /// The template's model, tools, attachments and schema apply to the query,
+    /// unless overridden by their own flags.
+    /// List the available templates with `jp template ls`.
+    #[arg(
+        short = 'N',
+        long = "named-template",
+        value_name = "NAME",
+        conflicts_with_all = ["template", "replay", "resume"],
+    )]
+    named_template: Option<String>,
+
     /// Constrain the assistant's response to match a JSON schema.
     ///
     /// Accepts either a full JSON Schema object or a concise DSL:
@@ -159,8 +199,8 @@ pub(crate) struct Query {
     /// types -s 'summary: a brief summary' → field with description
     ///
     /// See: <https://jp.computer/rfd/030-schema-dsl>
-    #[arg(short = 's', long, value_parser = string_or_path.try_map(parse_schema))]
-    schema: Option<schemars::Schema>,
+    #[arg(short = 's', long)]
+    schema: Option<String>,
 
     /// Replay the last message in the conversation.
     ///
@@ -171,6 +211,19 @@ pub(crate) struct Query {
     #[arg(long = "replay", conflicts_with = "new")]
     replay: bool,
 
+    /// Resume the conversation's last turn, if it was interrupted before it
+    /// completed.
+    ///
+    /// Picks up where the turn stopped: pending tool calls are run, and the
+    /// assistant is asked to continue from the last recorded event.
+    /// Use this before sending a new query, which discards the interrupted
+    /// work.
+    #[arg(
+        long = "resume",
+        conflicts_with_all = ["new", "replay", "fork", "query"],
+    )]
+    resume: bool,
+
     #[command(flatten)]
     target: FlagIds<false, false>,
 
@@ -221,7 +274,7 @@ pub(crate) struct Query {
     ///
     /// To change which editor is used, set `editor.cmd` in your config (or pass
     /// `--cfg editor.cmd=...`).
-    #[arg(short = 'e', long = "edit", conflicts_with = "no_edit")]
+    #[arg(short = 'e', long = "edit", conflicts_with_all = ["no_edit", "daemon"])]
     edit: bool,
 
     /// Do not edit the query.
@@ -230,11 +283,21 @@ pub(crate) struct Query {
     #[arg(short = 'E', long = "no-edit", conflicts_with = "edit")]
     no_edit: bool,
 
+    /// Open an editor to change the configuration of this query only.
+    ///
+    /// The editor shows the resolved configuration as TOML, with the commonly
+    /// changed settings (model, reasoning, tool choice and tools) left as-is
+    /// and all other settings commented out.
+    /// The changes apply to this turn only, and are recorded as such in the
+    /// conversation.
+    #[arg(long = "edit-config", conflicts_with = "daemon")]
+    edit_config: bool,
+
     #[command(flatten)]
     input: QueryInput,
 
     /// The model to use.
-    #[arg(short = 'm', long = "model")]
+    #[arg(short = 'm', long = "model", add = ArgValueCandidates::new(completion::models))]
     model: Option<String>,
 
     /// The model parameters to use.
@@ -330,6 +393,26 @@ pub(crate) struct Query {
     /// Repeat the flag to mount several paths.
     #[arg(long = "mount", value_name = "[TOOL:]NAME=PATH[:MODE]", action = ArgAction::Append)]
     mount: Vec<String>,
+
+    /// Run the query on the workspace daemon.
+    ///
+    /// The daemon keeps MCP servers warm between queries.
+    /// Output is streamed back until the query finishes; Ctrl-C detaches from
+    /// the query instead of interrupting it.
+    /// The daemon can't prompt, so queries with piped input, or with enabled
+    /// tools that ask for confirmation or input, are refused.
+    /// Tools ask before they run by default (`run = "ask"`), so enable only
+    /// tools configured to run unattended, for example with
+    /// `--cfg conversation.tools.*.run=unattended`.
+    /// Start the daemon with `jp daemon start`.
+    #[arg(long = "daemon")]
+    daemon: bool,
+
+    /// Print the daemon job ID and return without waiting for the query.
+    ///
+    /// Follow the query later with `jp daemon attach`.
+    #[arg(long = "detach", requires = "daemon")]
+    detach: bool,
 }
 
 impl Query {
@@ -348,7 +431,11 @@ impl Query {
         // Resolve the query before any conversation or session state is
         // touched: an unreadable `@path` must not leave a conversation created
         // and recorded as the session's active one.
-        let query = self.resolve_query()?;
+        let query = match &self.named_template {
+            Some(name) => Some(self.render_named_template(&cfg, name, ctx.term.is_tty)?),
+            None => self.resolve_query(&ctx.env)?,
+        };
+        let schema = self.schema(&cfg, &ctx.env)?;
 
         // Resolve the target conversation and acquire an exclusive lock.
         //
@@ -374,7 +461,13 @@ impl Query {
 
         // Create symlinks and seed approvals for any `--mount` flags before the
         // turn runs, so tools can reach the mounted paths.
-        create_mount_effects(&self.mount, &ctx.workspace, ctx.fs_backend.as_deref(), now)?;
+        create_mount_effects(
+            &self.mount,
+            &ctx.workspace,
+            ctx.fs_backend.as_deref(),
+            &ctx.env,
+            now,
+        )?;
 
         // The two flags are mutually exclusive (enforced by clap), and the
         // resolved conversation may be new, freshly forked (which clones the
@@ -397,6 +490,16 @@ impl Query {
             warn!(%error, "Failed to record activation.");
         }
 
+        // The `--cfg` delta is recorded in the conversation, the configuration
+        // of this turn (`--edit-config`, or that of the resumed turn) is
+        // layered on top of it for this turn only.
+        let cli_delta = get_config_delta_from_cli(&cfg, &lock)?;
+        let Some((cfg, turn_delta)) = self.turn_config(&lock, cfg)? else {
+            ctx.printer
+                .println("Configuration edit cancelled, ignoring query.");
+            return Ok(());
+        };
+
         // Fail fast on provider misconfiguration (e.g. a missing API key
         // environment variable) before any side-effectful work below:
         // pre-query compaction can run a full summary LLM round-trip, MCP
@@ -404,20 +507,39 @@ impl Query {
         // the request, and title generation and attachment loading are all
         // wasted — and the title task alone can hold the run open for
         // seconds at teardown — when the request can never be sent.
-        // `handle_turn` repeats this check implicitly when it constructs
-        // the live provider.
-        provider::preflight(
-            cfg.assistant.model.id.resolved().provider,
-            &cfg.providers.llm,
-        )
-        .map_err(Error::from)?;
+        // The provider is cached, so `handle_turn` reuses the one built here.
+        ctx.providers
+            .get(
+                cfg.assistant.model.id.resolved().provider,
+                &cfg.providers.llm,
+                &ctx.env,
+            )
+            .map_err(Error::from)?;
+
+        // Determine where an interrupted turn stopped before doing any work
+        // for it.
+        let incomplete = if self.resume {
+            let incomplete = lock
+                .with_events(IncompleteTurn::detect)
+                .ok_or(Error::NothingToResume(lock.id()))?;
+            Some(incomplete)
+        } else {
+            None
+        };
 
-        // Compact the conversation before querying, if requested.
-        if self.compact.should_compact() {
-            self.apply_pre_query_compaction(&lock, &cfg).await?;
+        // Compact the conversation before querying, if requested. A resumed
+        // turn is left as it was persisted.
+        if self.compact.should_compact() && incomplete.is_none() {
+            let scratch = ScratchRecorder::new(
+                ctx.workspace.conversation_factory(),
+                lock.id(),
+                lock.projection(),
+            );
+            self.apply_pre_query_compaction(&lock, &cfg, &ctx.env, &scratch)
+                .await?;
         }
 
-        let mcp_servers_handle = ctx.configure_active_mcp_servers().await?;
+        let mcp_servers_handle = ctx.configure_active_mcp_servers(&cfg).await?;
 
         let conv_title = lock.metadata().title.clone();
 
@@ -439,6 +561,230 @@ impl Query {
             |fs| fs.build_conversation_dir(&cid, conv_title.as_deref(), true),
         );
 
+        let turn = match incomplete {
+            Some(incomplete) => {
+                ctx.printer
+                    .eprintln(format!("Resuming turn in {cid}: {incomplete}."));
+
+                // The `--cfg` delta applies to the rest of the resumed turn,
+                // and so does a newly edited turn configuration.
+                lock.as_mut().update_events(|events| {
+                    if let Some(delta) = cli_delta {
+                        events.add_config_delta(delta);
+                    }
+                    if !turn_delta.is_empty() {
+                        events.add_config_delta(ConfigDelta::from(turn_delta).turn_scoped());
+                    }
+                });
+
+                Some((TurnEntry::Resume(incomplete), lock.events().clone()))
+            }
+            None => self.compose_turn(
+                ctx,
+                &lock,
+                &cfg,
+                (cli_delta, turn_delta),
+                query.as_deref(),
+                schema.as_ref(),
+                &conversation_path,
+            )?,
+        };
+
+        let Some((mut entry, stream)) = turn else {
+            return Ok(());
+        };
+
+        // Tools record the files they change in the checkpoint of this turn,
+        // for `jp undo`. A replayed turn keeps recording in the checkpoint of
+        // the turn it replaces, so undoing it restores the original state.
+        // A resumed turn that has no checkpoint ID records nothing, as does
+        // any turn without user storage to keep checkpoints in.
+        let checkpoint = ctx.fs_backend.as_deref().and_then(|fs| {
+            let events = lock.events();
+            let checkpoints = TurnCheckpoints::new(fs, &lock.id())?.with_turns(&events);
+            let replaced = stream.turn_count() < events.turn_count();
+            let id = match &mut entry {
+                TurnEntry::Resume(_) => checkpoints.id(stream.turn_count())?.to_owned(),
+                TurnEntry::Start { checkpoint, .. } => {
+                    let id = replaced
+                        .then(|| checkpoints.id(stream.turn_count() + 1))
+                        .flatten()
+                        .map_or_else(TurnCheckpoints::new_id, str::to_owned);
+
+                    *checkpoint = Some(id.clone());
+                    id
+                }
+            };
+
+            Some(checkpoints.checkpoint(&id))
+        });
+
+        // Wait for all MCP servers to finish loading, showing a timer line
+        // when the wait takes long enough to be noticeable.
+        await_mcp_servers(
+            mcp_servers_handle,
+            cfg.style.mcp_startup.clone(),
+            ctx.printer.clone(),
+            ctx.term.is_tty,
+            ctx.term.width,
+        )
+        .await?;
+
+        let forced_tool = cfg.assistant.tool_choice.function_name();
+        let tools =
+            tool_definitions(cfg.conversation.tools.iter(), &ctx.mcp_client, forced_tool).await?;
+
+        let attachment_urls: Vec<_> = cfg
+            .conversation
+            .attachments
+            .iter()
+            .map(jp_config::conversation::attachment::AttachmentConfig::to_url)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let attachments = load_conversation_attachments(ctx, attachment_urls).await?;
+
+        debug!(count = attachments.len(), "Attachments loaded.");
+
+        let thread = build_thread(stream, attachments, &cfg.assistant, !tools.is_empty())?;
+        let root = ctx.workspace.root().to_path_buf();
+        let approvals = Arc::new(load_approval_store(ctx.fs_backend.as_deref()));
+        let sub_agents = SubAgents::new(
+            &lock,
+            cfg.clone(),
+            ctx.workspace.conversation_factory(),
+            ctx.signals.clone(),
+            ctx.mcp_client.clone(),
+            root.clone(),
+            approvals.clone(),
+            ctx.workspace.id().to_string(),
+            ctx.env.clone(),
+            checkpoint.clone(),
+            ctx.providers.clone(),
+        );
+
+        // Sanitize any structural issues (orphaned tool calls, missing
+        // user messages, etc.) before sending the stream to the provider.
+        // A resumed turn skips this: its pending tool calls are not orphaned,
+        // they are about to run. The turn loop sanitizes before streaming.
+        if !matches!(entry, TurnEntry::Resume(_)) {
+            lock.as_mut().update_events(ConversationStream::sanitize);
+        }
+
+        // Tools that run in a pseudo-terminal share the user's terminal, if
+        // there is one to share.
+        let terminal = (ctx.term.is_tty && !ctx.printer.format().is_json())
+            .then(|| UserTerminal::new(ctx.printer.clone()));
+
+        // Background tools from earlier queries stopped when those exited.
+        let background = BackgroundTasks::default();
+        lock.as_mut()
+            .update_events(|stream| background.restore(stream));
+
+        let invocation = InvocationContext {
+            workspace_id: ctx.workspace.id().to_string(),
+            conversation_id: lock.id().to_string(),
+            terminal: terminal
+                .clone()
+                .map(|terminal| Arc::new(terminal) as Arc<dyn ToolTerminal>),
+            background: background.clone(),
+            checkpoint,
+            env: ctx.env.clone(),
+        };
+
+        let events = EventSink::new(&ctx.printer);
+        let turn_started = Instant::now();
+        let turn_result = self
+            .handle_turn(
+                &cfg,
+                &ctx.signals,
+                &ctx.mcp_client,
+                root,
+                ctx.term.is_tty,
+                &thread.attachments,
+                &lock,
+                cfg.assistant.tool_choice.clone(),
+                &tools,
+                ctx.printer.clone(),
+                approvals,
+                sub_agents,
+                events.clone(),
+                terminal,
+                ctx.usage.clone(),
+                entry,
+                invocation,
+                &ctx.providers,
+            )
+            .await
+            .map_err(|error| cmd::Error::from(error).with_persistence(true));
+        ctx.usage
+            .record_turn(&cfg, turn_started.elapsed(), turn_result.is_ok());
+
+        // Background tools do not outlive the query that started them.
+        background.shutdown().await;
+        lock.as_mut()
+            .update_events(|stream| background.persist(stream));
+
+        // Extract structured data from the conversation after the turn.
+        if schema.is_some() && turn_result.is_ok() {
+            let data = lock.events().iter().rev().find_map(|e| {
+                e.as_chat_response()
+                    .and_then(ChatResponse::as_structured_data)
+                    .cloned()
+            });
+
+            match data {
+                Some(data) if events.is_enabled() => {
+                    events.emit(&StreamEvent::StructuredResult { data });
+                }
+                Some(data) => print_json(&ctx.printer, &data),
+                None => return Err(Error::MissingStructuredData.into()),
+            }
+        }
+
+        // The `jsonl` stream always closes with the turn's outcome, and a turn
+        // that did not complete exits non-zero, so scripts can tell the two
+        // apart without parsing the events.
+        let status = events.end_turn(turn_result.as_ref().err());
+        let turn_result = match status {
+            TurnStatus::Truncated | TurnStatus::Refused | TurnStatus::Aborted
+                if turn_result.is_ok() =>
+            {
+                Err(cmd::Error::from((status.exit_code(), status.describe()))
+                    .with_persistence(true)
+                    .expected())
+            }
+            _ => turn_result,
+        };
+
+        // Clean up the query file, unless we got an error — on failure the
+        // file is the recovery copy of the request. The conversation
+        // directory may have been renamed mid-turn (e.g. a heading-derived
+        // title), so re-resolve the live directories rather than trusting the
+        // path captured before the turn ran.
+        if turn_result.is_ok() {
+            cleanup_query_message_file(ctx.fs_backend.as_deref(), &cid);
+        }
+
+        turn_result
+    }
+
+    /// Compose the request for a new turn, returning how to enter the turn and
+    /// the stream snapshot to build the thread from.
+    ///
+    /// `cli_delta` is the `--cfg` delta to record in the conversation,
+    /// `turn_config` the configuration of this turn only.
+    ///
+    /// Returns `None` when the composed query is empty.
+    fn compose_turn(
+        &self,
+        ctx: &mut Ctx,
+        lock: &ConversationLock,
+        cfg: &AppConfig,
+        (cli_delta, turn_config): (Option<PartialAppConfig>, PartialAppConfig),
+        query: Option<&str>,
+        schema: Option<&schemars::Schema>,
+        conversation_path: &Utf8Path,
+    ) -> Result<Option<(TurnEntry, ConversationStream)>> {
+        let cid = lock.id();
         let piped = read_piped_stdin()?;
 
         // Build the request read-only: replay resolution, quote seeding, and
@@ -452,7 +798,7 @@ impl Query {
             pending_trim,
             chat_request,
         } = lock.with_events(|stream| {
-            self.build_conversation(&piped, query.as_deref(), stream, &cfg, &conversation_path)
+            self.build_conversation(&piped, query, stream, cfg, conversation_path)
         })?;
 
         let Some(mut chat_request) = chat_request else {
@@ -462,7 +808,7 @@ impl Query {
                 cleanup_query_message_file(ctx.fs_backend.as_deref(), &cid);
             }
             ctx.printer.println("Query is empty, ignoring.");
-            return Ok(());
+            return Ok(None);
         };
 
         // Stamp the request with the configured user name so transcripts
@@ -473,7 +819,7 @@ impl Query {
 
         // If a schema is provided, set it on the ChatRequest so the
         // provider uses its native structured output API.
-        if let Some(schema) = &self.schema {
+        if let Some(schema) = schema {
             chat_request.schema = schema.as_object().cloned();
         }
 
@@ -498,7 +844,7 @@ impl Query {
                 .as_deref()
                 .is_some_and(|fs| fs.user_storage_path().is_some())
         {
-            preserve_query_message_file(&conversation_path, &chat_request.content);
+            preserve_query_message_file(conversation_path, &chat_request.content);
         }
 
         // Echo the request back through the same role-aware rendering
@@ -507,7 +853,7 @@ impl Query {
         // and the forthcoming assistant response is visually clear. Render
         // this before any post-edit work (MCP init, attachments, tools) so
         // that failures in those stages don't swallow the user's message.
-        if self.should_echo_request(query_source) {
+        if self.should_echo_request(query_source) && !ctx.printer.format().is_json() {
             let mut echo = TurnView::new(
                 ctx.printer.clone(),
                 cfg.style.clone(),
@@ -521,7 +867,7 @@ impl Query {
         // known to be non-empty. Recording it before the empty-query check
         // would leave a config event behind for a query that was ultimately
         // ignored.
-        if let Some(delta) = get_config_delta_from_cli(&cfg, &lock)? {
+        if let Some(delta) = cli_delta {
             lock.as_mut()
                 .update_events(|events| events.add_config_delta(delta));
         }
@@ -579,7 +925,13 @@ impl Query {
                     // assistant model. Skip the title instead of spawning a
                     // task that is doomed to fail after holding teardown
                     // open.
-                    match TitleGeneratorTask::new(cid, stream, &cfg, ctx.term.is_tty) {
+                    match TitleGeneratorTask::new(
+                        cid,
+                        stream,
+                        cfg,
+                        ctx.env.clone(),
+                        ctx.term.is_tty,
+                    ) {
                         Ok(task) => ctx.task_handler.spawn(task),
                         Err(error) => warn!(%error, "Skipping title generation."),
                     }
@@ -588,88 +940,51 @@ impl Query {
             }
         }
 
-        // Wait for all MCP servers to finish loading, showing a timer line
-        // when the wait takes long enough to be noticeable.
-        await_mcp_servers(
-            mcp_servers_handle,
-            cfg.style.mcp_startup.clone(),
-            ctx.printer.clone(),
-            ctx.term.is_tty,
-            ctx.term.width,
-        )
-        .await?;
-
-        let forced_tool = cfg.assistant.tool_choice.function_name();
-        let tools =
-            tool_definitions(cfg.conversation.tools.iter(), &ctx.mcp_client, forced_tool).await?;
-
-        let attachment_urls: Vec<_> = cfg
-            .conversation
-            .attachments
-            .iter()
-            .map(jp_config::conversation::attachment::AttachmentConfig::to_url)
-            .collect::<std::result::Result<Vec<_>, _>>()?;
-        let attachments = load_conversation_attachments(ctx, attachment_urls).await?;
-
-        debug!(count = attachments.len(), "Attachments loaded.");
-
-        let thread = build_thread(stream, attachments, &cfg.assistant, !tools.is_empty())?;
-        let root = ctx.workspace.root().to_path_buf();
-        let approvals = Arc::new(load_approval_store(ctx.fs_backend.as_deref()));
-
-        // Sanitize any structural issues (orphaned tool calls, missing
-        // user messages, etc.) before sending the stream to the provider.
-        lock.as_mut().update_events(ConversationStream::sanitize);
-
-        let invocation = InvocationContext {
-            workspace_id: ctx.workspace.id().to_string(),
-            conversation_id: lock.id().to_string(),
-        };
-
-        let turn_result = self
-            .handle_turn(
-                &cfg,
-                &ctx.signals,
-                &ctx.mcp_client,
-                root,
-                ctx.term.is_tty,
-                &thread.attachments,
-                &lock,
-                cfg.assistant.tool_choice.clone(),
-                &tools,
-                ctx.printer.clone(),
-                approvals,
-                chat_request,
-                invocation,
+        Ok(Some((
+            TurnEntry::Start {
+                request: chat_request,
                 pending_trim,
-            )
-            .await
-            .map_err(|error| cmd::Error::from(error).with_persistence(true));
+                turn_config,
+                checkpoint: None,
+            },
+            stream,
+        )))
+    }
 
-        // Extract structured data from the conversation after the turn.
-        if self.schema.is_some() && turn_result.is_ok() {
-            let data = lock.events().iter().rev().find_map(|e| {
-                e.as_chat_response()
-                    .and_then(ChatResponse::as_structured_data)
-                    .cloned()
-            });
+    /// Resolve the configuration of this turn, and the turn-scoped delta to
+    /// record for it.
+    ///
+    /// A resumed turn continues with the configuration it was started with,
+    /// `--edit-config` opens the editor to change it.
+    /// Returns `None` when the configuration edit is cancelled.
+    fn turn_config(
+        &self,
+        lock: &ConversationLock,
+        mut cfg: Arc<AppConfig>,
+    ) -> Result<Option<(Arc<AppConfig>, PartialAppConfig)>> {
+        if self.resume
+            && let Some(delta) = lock
+                .events()
+                .turn_config_delta()
+                .map_err(jp_conversation::Error::from)?
+        {
+            cfg = Arc::new(turn_config::apply(&cfg, delta)?);
+        }
 
-            match data {
-                Some(data) => print_json(&ctx.printer, &data),
-                None => return Err(Error::MissingStructuredData.into()),
-            }
+        if !self.edit_config {
+            return Ok(Some((cfg, PartialAppConfig::empty())));
         }
 
-        // Clean up the query file, unless we got an error — on failure the
-        // file is the recovery copy of the request. The conversation
-        // directory may have been renamed mid-turn (e.g. a heading-derived
-        // title), so re-resolve the live directories rather than trusting the
-        // path captured before the turn ran.
-        if turn_result.is_ok() {
-            cleanup_query_message_file(ctx.fs_backend.as_deref(), &cid);
+        let editor = editor::build_editor_backend(&cfg.editor).ok_or(Error::MissingEditor)?;
+        let Some(delta) = turn_config::edit(&cfg, editor.as_ref())? else {
+            return Ok(None);
+        };
+
+        if !delta.is_empty() {
+            cfg = Arc::new(turn_config::apply(&cfg, delta.clone())?);
         }
 
-        turn_result
+        Ok(Some((cfg, delta)))
     }
 
     /// Resolve the positional query into the text to send.
@@ -683,19 +998,52 @@ impl Query {
     /// # Errors
     ///
     /// Returns [`Error::ArgFile`] if the named file cannot be read.
-    fn resolve_query(&self) -> Result<Option<String>> {
+    fn resolve_query(&self, env: &Env) -> Result<Option<String>> {
         let Some(values) = &self.input.query else {
             return Ok(None);
         };
 
         let text = match query_file_path(values) {
-            Some(path) => read_arg_file(path)?,
+            Some(path) => read_arg_file(path, env)?,
             None => values.join(" "),
         };
 
         Ok(Some(text))
     }
 
+    /// Render the `--named-template` query from the query words.
+    fn render_named_template(&self, cfg: &AppConfig, name: &str, is_tty: bool) -> Result<String> {
+        let template = named_template::find(&cfg.template, name)?;
+        let words = self.input.query.as_deref().unwrap_or_default();
+        let (given, extra) = named_template::split_words(template, words);
+
+        let prompts = TerminalPromptBackend;
+        let prompts = is_tty.then_some(&prompts as &dyn PromptBackend);
+        let params = named_template::resolve_params(template, given, prompts)?;
+
+        named_template::render(&cfg.template, template, params, &extra)
+    }
+
+    /// The schema the response must match: `--schema`, or the schema of the
+    /// `--named-template`.
+    fn schema(&self, cfg: &AppConfig, env: &Env) -> Result<Option<schemars::Schema>> {
+        if let Some(schema) = &self.schema {
+            return string_or_path(schema, env).and_then(parse_schema).map(Some);
+        }
+
+        let Some(name) = &self.named_template else {
+            return Ok(None);
+        };
+
+        match named_template::find(&cfg.template, name)?.schema.clone() {
+            None => Ok(None),
+            Some(serde_json::Value::String(schema)) => parse_schema(schema).map(Some),
+            Some(schema) => schemars::Schema::try_from(schema)
+                .map(Some)
+                .map_err(Into::into),
+        }
+    }
+
     /// Declare what conversations this command needs.
     pub(crate) fn conversation_load_request(&self) -> ConversationLoadRequest {
         if self.is_new() {
@@ -954,31 +1302,32 @@ impl Query {
         tools: &[ToolDefinition],
         printer: Arc<Printer>,
         approvals: Arc<ApprovalStore>,
-        chat_request: ChatRequest,
+        sub_agents: SubAgents,
+        events: EventSink,
+        terminal: Option<UserTerminal>,
+        usage: UsageRecorder,
+        entry: TurnEntry,
         invocation: InvocationContext,
-        pending_trim: PendingStreamTrim,
+        providers: &ProviderCache,
     ) -> Result<()> {
         let model_id = cfg.assistant.model.id.resolved();
-        let provider: Arc<dyn jp_llm::Provider> = Arc::from(provider::get_provider(
-            model_id.provider,
-            &cfg.providers.llm,
-        )?);
+        let provider = providers.get(model_id.provider, &cfg.providers.llm, &invocation.env)?;
         debug!(model = %model_id, "Fetching model details.");
         let model = provider.model_details(&model_id.name).await?;
         debug!(model = model.name(), "Model details resolved.");
 
-        // Build docs map from the resolved definitions for describe_tools.
-        let docs_map: IndexMap<String, ToolDocs> = tools
-            .iter()
-            .map(|t| (t.name.clone(), t.docs.clone()))
-            .collect();
-        let builtin_executors =
-            BuiltinExecutors::new().register("describe_tools", DescribeTools::new(docs_map));
-        let executor_source =
-            TerminalExecutorSource::new(builtin_executors, tools, approvals, invocation.clone());
-        let tool_coordinator =
-            ToolCoordinator::new(cfg.conversation.tools.clone(), Box::new(executor_source))
-                .with_interrupt(cfg.interrupt.tool_call.clone());
+        let scratch = sub_agents.scratch();
+        let tool_coordinator = tool_coordinator(
+            cfg,
+            tools,
+            approvals,
+            invocation.clone(),
+            Arc::new(sub_agents),
+        )
+        .with_events(events)
+        .with_terminal(terminal)
+        .with_scratch(Some(scratch))
+        .with_usage(usage);
         let prompt_backend = Arc::new(TerminalPromptBackend);
 
         run_turn_loop(
@@ -996,9 +1345,9 @@ impl Query {
             printer,
             prompt_backend,
             tool_coordinator,
-            chat_request,
+            entry,
             invocation,
-            pending_trim,
+            providers,
         )
         .await
     }
@@ -1061,6 +1410,13 @@ impl Query {
         self.fork.is_none() && !self.replay && self.target.ids().is_empty()
     }
 
+    /// Whether to forward this query to the workspace daemon.
+    ///
+    /// Returns `Some(detach)` when `--daemon` is set.
+    pub(crate) fn daemon(&self) -> Option<bool> {
+        self.daemon.then_some(self.detach)
+    }
+
     #[must_use]
     fn expires_in_duration(&self) -> Option<Duration> {
         self.expires_in?
@@ -1076,6 +1432,8 @@ impl Query {
         &self,
         lock: &ConversationLock,
         cfg: &AppConfig,
+        env: &Env,
+        scratch: &ScratchRecorder,
     ) -> Result<()> {
         let events = lock.events().clone();
 
@@ -1089,12 +1447,14 @@ impl Query {
         let compactions = super::conversation::compact::build_compaction_events(
             &events,
             cfg,
+            env,
             &rules,
             crate::cmd::turn_range::Bound::Default,
             crate::cmd::turn_range::Bound::Default,
             // `--compact` on a query is a quick adjunct; apply it silently so
             // compaction details don't clutter the query output.
             None,
+            Some(scratch),
         )
         .await?;
 
@@ -1595,7 +1955,8 @@ impl clap::Args for ToolDirectives {
                 )
                 .action(ArgAction::Append)
                 .num_args(0..=1)
-                .default_missing_value(""),
+                .default_missing_value("")
+                .add(ArgValueCandidates::new(completion::tools)),
         )
         .arg(
             clap::Arg::new("no_tools")
@@ -1610,7 +1971,8 @@ impl clap::Args for ToolDirectives {
                 )
                 .action(ArgAction::Append)
                 .num_args(0..=1)
-                .default_missing_value(""),
+                .default_missing_value("")
+                .add(ArgValueCandidates::new(completion::tools)),
         )
     }
 
@@ -1649,6 +2011,7 @@ fn label_resolver<'a>(
         &ctx.printer,
         prompts,
     )
+    .with_env(ctx.env.clone())
 }
 
 /// Where the outgoing chat request's content came from.
@@ -1747,18 +2110,22 @@ impl IntoPartialAppConfig for Query {
         workspace: Option<&Workspace>,
         mut partial: PartialAppConfig,
         merged_config: Option<&PartialAppConfig>,
+        env: &Env,
     ) -> std::result::Result<PartialAppConfig, Box<dyn std::error::Error + Send + Sync>> {
         let Self {
             model,
             template: _,
+            named_template,
             schema: _,
             replay: _,
+            resume: _,
             new_conversation: _,
             local: _,
             no_local: _,
             attachments,
             edit: _,
             no_edit: _,
+            edit_config: _,
             input: _,
             tool_use,
             no_tool_use,
@@ -1776,10 +2143,10 @@ impl IntoPartialAppConfig for Query {
             no_title: _,
             labels: _,
             mount,
+            daemon: _,
+            detach: _,
         } = &self;
 
-        apply_model(&mut partial, model.as_deref(), merged_config);
-
         // Inject builtin tool configs before tool-enable processing.
         for (name, config) in tool::builtins::all() {
             partial
@@ -1790,14 +2157,17 @@ impl IntoPartialAppConfig for Query {
                 .or_insert(config);
         }
 
+        apply_named_template(&mut partial, named_template.as_deref(), workspace, env)?;
+        apply_model(&mut partial, model.as_deref(), merged_config);
+
         apply_enable_tools(&mut partial, tool_directives, merged_config)?;
         apply_tool_use(
             &mut partial,
             tool_use.as_ref().map(|v| v.as_deref()),
             *no_tool_use,
         )?;
-        apply_attachments(&mut partial, attachments, workspace)?;
-        apply_mounts(&mut partial, mount, workspace, merged_config)?;
+        apply_attachments(&mut partial, attachments, workspace, env)?;
+        apply_mounts(&mut partial, mount, workspace, merged_config, env)?;
         apply_reasoning(&mut partial, reasoning.as_ref(), *no_reasoning);
 
         for kv in parameters.clone() {
@@ -1882,6 +2252,49 @@ fn build_thread(
     Ok(thread_builder.build()?)
 }
 
+/// Apply the configuration overlay of a named template to the partial
+/// configuration.
+///
+/// This runs before the other CLI flags are applied, so `--model`, `--tool` and
+/// `--attachment` build on (or override) the template's configuration.
+fn apply_named_template(
+    partial: &mut PartialAppConfig,
+    name: Option<&str>,
+    workspace: Option<&Workspace>,
+    env: &Env,
+) -> BoxedResult<()> {
+    let Some(name) = name else {
+        return Ok(());
+    };
+
+    let template = partial
+        .template
+        .named
+        .get(name)
+        .cloned()
+        .ok_or_else(|| Error::NamedTemplateNotFound(name.to_owned()))?;
+
+    if let Some(model) = template.model.as_deref() {
+        apply_model(partial, Some(model), None);
+    }
+
+    if let Some(tools) = template.tools {
+        let directives = ToolDirectives(tools.into_iter().map(ToolDirective::Enable).collect());
+        apply_enable_tools(partial, &directives, None)?;
+    }
+
+    if let Some(attachments) = template.attachments {
+        // Parsing an `AttachmentUrlOrPath` is infallible.
+        let attachments: Vec<AttachmentUrlOrPath> = attachments
+            .iter()
+            .filter_map(|attachment| attachment.parse().ok())
+            .collect();
+        apply_attachments(partial, &attachments, workspace, env)?;
+    }
+
+    Ok(())
+}
+
 /// Apply the CLI model configuration to the partial configuration.
 ///
 /// `model` is the raw `--model` value: an alias or a full `provider/name` ID.
@@ -2076,11 +2489,12 @@ fn apply_attachments(
     partial: &mut PartialAppConfig,
     attachments: &[AttachmentUrlOrPath],
     workspace: Option<&Workspace>,
+    env: &Env,
 ) -> Result<()> {
     let root = workspace.map(Workspace::root);
     let attachments = attachments
         .iter()
-        .map(|v| v.parse(root))
+        .map(|v| v.parse(root, env))
         .collect::<Result<Vec<_>>>()?;
 
     partial
@@ -2137,6 +2551,7 @@ fn apply_mounts(
     mounts: &[String],
     workspace: Option<&Workspace>,
     merged_config: Option<&PartialAppConfig>,
+    env: &Env,
 ) -> BoxedResult<()> {
     if mounts.is_empty() {
         return Ok(());
@@ -2144,7 +2559,7 @@ fn apply_mounts(
 
     let workspace = workspace.ok_or("`--mount` requires a workspace")?;
     let root = workspace.root().to_owned();
-    let cwd = current_dir_utf8()?;
+    let cwd = env.cwd()?;
 
     // Resolve the tool set and the global enable default from the merged
     // config (the fully-layered view) so a bare mount expands over the tools
@@ -2206,6 +2621,7 @@ fn create_mount_effects(
     mounts: &[String],
     workspace: &Workspace,
     fs_backend: Option<&jp_storage::backend::FsStorageBackend>,
+    env: &Env,
     now: chrono::DateTime<chrono::Utc>,
 ) -> Result<()> {
     if mounts.is_empty() {
@@ -2214,7 +2630,7 @@ fn create_mount_effects(
 
     let root = workspace.root().to_owned();
     let root_canonical = root.canonicalize_utf8().unwrap_or_else(|_| root.clone());
-    let cwd = current_dir_utf8().map_err(|e| Error::CliConfig(e.to_string()))?;
+    let cwd = env.cwd().map_err(|e| Error::CliConfig(e.to_string()))?;
 
     let approvals_path = approval_store_path(fs_backend);
     let mut store = approvals_path
@@ -2230,8 +2646,10 @@ fn create_mount_effects(
             .map_err(|e| Error::CliConfig(e.to_string()))?;
         let link = root.join(&rule_path);
 
-        let target = expand_tilde(&spec.path, env::var("HOME").ok())
-            .unwrap_or_else(|| Utf8PathBuf::from(&spec.path));
+        let target = cwd.join(
+            expand_tilde(&spec.path, env.var("HOME"))
+                .unwrap_or_else(|| Utf8PathBuf::from(&spec.path)),
+        );
 
         // Resolve the target before creating the link so a missing target
         // fails cleanly instead of leaving a broken symlink behind.
@@ -2333,6 +2751,41 @@ fn approval_store_path(
 }
 
 /// Load the approval store, treating missing/in-memory storage as empty.
+/// Build the tool coordinator for a turn, with the builtin tools registered.
+fn tool_coordinator(
+    cfg: &AppConfig,
+    tools: &[ToolDefinition],
+    approvals: Arc<ApprovalStore>,
+    invocation: InvocationContext,
+    sub_agents: Arc<dyn SubAgentRunner>,
+) -> ToolCoordinator {
+    // Build docs map from the resolved definitions for describe_tools.
+    let docs_map: IndexMap<String, ToolDocs> = tools
+        .iter()
+        .map(|t| (t.name.clone(), t.docs.clone()))
+        .collect();
+    let builtin_executors = BuiltinExecutors::new()
+        .register("describe_tools", DescribeTools::new(docs_map))
+        .register("sub_agent", SubAgent::new(sub_agents))
+        .register(
+            "await_handles",
+            AwaitHandles::new(invocation.background.clone()),
+        )
+        .register(
+            "poll_handle",
+            PollHandle::new(invocation.background.clone()),
+        )
+        .register(
+            "cancel_handle",
+            CancelHandle::new(invocation.background.clone()),
+        );
+    let executor_source =
+        TerminalExecutorSource::new(builtin_executors, tools, approvals, invocation);
+
+    ToolCoordinator::new(cfg.conversation.tools.clone(), Box::new(executor_source))
+        .with_interrupt(cfg.interrupt.tool_call.clone())
+}
+
 fn load_approval_store(
     fs_backend: Option<&jp_storage::backend::FsStorageBackend>,
 ) -> ApprovalStore {
@@ -2449,12 +2902,6 @@ fn cleanup_query_message_file(
     }
 }
 
-fn current_dir_utf8() -> BoxedResult<Utf8PathBuf> {
-    let cwd = env::current_dir()?;
-    Utf8PathBuf::from_path_buf(cwd)
-        .map_err(|path| format!("current directory is not valid UTF-8: {}", path.display()).into())
-}
-
 /// Whether a tool's `access.fs` is empty across all merged layers.
 fn tool_access_empty(
     tools: &IndexMap<String, jp_config::conversation::tool::PartialToolConfig>,
@@ -2533,7 +2980,7 @@ fn parse_fork_turns(s: &str) -> std::result::Result<Option<usize>, String> {
 /// paragraph breaks, ...) are part of the message.
 /// Only the final line terminator is dropped, so request composition can add
 /// its own separators.
-fn read_piped_stdin() -> Result<String> {
+pub(crate) fn read_piped_stdin() -> Result<String> {
     let stdin = io::stdin();
     if stdin.is_terminal() {
         return Ok(String::new());
@@ -2554,9 +3001,9 @@ fn read_piped_stdin() -> Result<String> {
 ///
 /// A value starting with `@` is replaced by the contents of the file it names.
 /// Any other value is returned as-is.
-fn string_or_path(s: &str) -> Result<String> {
+fn string_or_path(s: &str, env: &Env) -> Result<String> {
     match arg_file_path(s) {
-        Some(path) => read_arg_file(path),
+        Some(path) => read_arg_file(path, env),
         None => Ok(s.to_owned()),
     }
 }
@@ -2582,10 +3029,16 @@ fn query_file_path(values: &[String]) -> Option<&str> {
 }
 
 /// Read the file an `@path` argument names, expanding a leading `~` to `$HOME`.
-fn read_arg_file(path: &str) -> Result<String> {
-    let path = expand_tilde(path, env::var("HOME").ok()).unwrap_or_else(|| Utf8PathBuf::from(path));
+///
+/// A relative path is relative to the working directory of `env`.
+fn read_arg_file(path: &str, env: &Env) -> Result<String> {
+    let path = expand_tilde(path, env.var("HOME")).unwrap_or_else(|| Utf8PathBuf::from(path));
+    let file = match env.cwd() {
+        Ok(cwd) => cwd.join(&path),
+        Err(_) => path.clone(),
+    };
 
-    fs::read_to_string(&path).map_err(|source| Error::ArgFile {
+    fs::read_to_string(&file).map_err(|source| Error::ArgFile {
         path: path.into_string(),
         source,
     })
@@ -0,0 +1,356 @@
+//! The `jsonl` event stream of a query turn.
+//!
+//! With `--format jsonl`, a query writes every event of its turn to stdout as
+//! it happens, one JSON object per line, tagged by its `type` field.
+//! Streamed content arrives as chunks, committed items (tool calls, tool
+//! results, inquiries) arrive whole, and the turn always ends with a `usage`
+//! and a `turn_end` event.
+//!
+//! The schema of each event is defined by [`StreamEvent`], and is documented in
+//! `docs/features.md`.
+//! Fields are only ever added, never renamed or removed.
+
+use std::sync::{Arc, Mutex};
+
+use jp_conversation::{
+    ConversationEvent, EventKind,
+    event::{InquiryQuestion, InquirySource},
+};
+use jp_llm::event::{EventPart, FinishReason, ToolCallPart};
+use jp_printer::Printer;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::cmd;
+
+/// One line of the `jsonl` event stream.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum StreamEvent {
+    /// The request that starts the turn, or a reply to an interrupted
+    /// response.
+    Request { content: String },
+
+    /// A chunk of the assistant's reasoning.
+    Reasoning { index: usize, chunk: String },
+
+    /// A chunk of the assistant's message.
+    Message { index: usize, chunk: String },
+
+    /// A chunk of the assistant's structured (JSON) response.
+    Structured { index: usize, chunk: String },
+
+    /// The assistant started a tool call.
+    ToolCallStart {
+        index: usize,
+        id: String,
+        name: String,
+    },
+
+    /// A raw JSON chunk of the arguments of the tool call at `index`.
+    ToolCallArguments { index: usize, chunk: String },
+
+    /// A complete tool call, with its parsed arguments.
+    ToolCall {
+        id: String,
+        name: String,
+        arguments: Map<String, Value>,
+    },
+
+    /// The result of a tool call.
+    ToolCallResult {
+        id: String,
+        content: String,
+        is_error: bool,
+    },
+
+    /// A tool asked a question.
+    Inquiry {
+        id: String,
+        source: InquirySource,
+        question: InquiryQuestion,
+    },
+
+    /// The answer to an inquiry.
+    InquiryAnswer { id: String, answer: Value },
+
+    /// The structured data of a response requested with a schema.
+    StructuredResult { data: Value },
+
+    /// What the turn used.
+    Usage { requests: usize, tool_calls: usize },
+
+    /// How the turn ended.
+    TurnEnd {
+        status: TurnStatus,
+        exit_code: u8,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+/// How a turn ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum TurnStatus {
+    /// The assistant finished its response.
+    #[default]
+    Completed,
+
+    /// The response was cut off at the model's output token limit.
+    Truncated,
+
+    /// The model declined the request.
+    Refused,
+
+    /// The turn was discarded from the interrupt menu.
+    Aborted,
+
+    /// The turn was stopped by Ctrl-C or SIGTERM.
+    Interrupted,
+
+    /// The turn failed with an error.
+    Failed,
+}
+
+impl TurnStatus {
+    /// The exit code of a query whose turn ended with this status.
+    ///
+    /// A failed turn exits with the code of its error instead.
+    pub(crate) const fn exit_code(self) -> u8 {
+        match self {
+            Self::Completed => 0,
+            Self::Failed => 1,
+            Self::Truncated => 3,
+            Self::Refused => 4,
+            Self::Aborted | Self::Interrupted => 130,
+        }
+    }
+
+    /// A short description of the status, used as the error message of a turn
+    /// that did not complete.
+    pub(crate) const fn describe(self) -> &'static str {
+        match self {
+            Self::Completed => "Turn completed",
+            Self::Truncated => "Response truncated at the model's max output tokens",
+            Self::Refused => "The model declined the request",
+            Self::Aborted => "Turn aborted",
+            Self::Interrupted => "Interrupted",
+            Self::Failed => "Turn failed",
+        }
+    }
+}
+
+/// What the turn has done so far, reported by the final events.
+#[derive(Debug, Default)]
+struct Tally {
+    requests: usize,
+    tool_calls: usize,
+    status: TurnStatus,
+}
+
+/// Writes the `jsonl` event stream of a turn.
+///
+/// Cloning is cheap; clones share the same tally.
+/// A sink for any format other than [`OutputFormat::Jsonl`] is disabled, and
+/// every method is a no-op.
+///
+/// [`OutputFormat::Jsonl`]: jp_printer::OutputFormat::Jsonl
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EventSink {
+    inner: Option<Arc<Inner>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    printer: Arc<Printer>,
+    tally: Mutex<Tally>,
+}
+
+impl EventSink {
+    /// A sink writing to `printer`, enabled when its format is `jsonl`.
+    pub(crate) fn new(printer: &Arc<Printer>) -> Self {
+        let inner = printer.format().is_jsonl().then(|| {
+            Arc::new(Inner {
+                printer: printer.clone(),
+                tally: Mutex::default(),
+            })
+        });
+
+        Self { inner }
+    }
+
+    pub(crate) const fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Write one event.
+    pub(crate) fn emit(&self, event: &StreamEvent) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+
+        match serde_json::to_string(event) {
+            Ok(line) => inner.printer.println_raw(&line),
+            Err(error) => tracing::warn!(%error, "Failed to serialize stream event, skipping."),
+        }
+    }
+
+    /// Emit a streamed part of the provider's response.
+    pub(crate) fn part(&self, index: usize, part: &EventPart) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let event = match part {
+            EventPart::Reasoning(chunk) => StreamEvent::Reasoning {
+                index,
+                chunk: chunk.clone(),
+            },
+            EventPart::Message(chunk) => StreamEvent::Message {
+                index,
+                chunk: chunk.clone(),
+            },
+            EventPart::Structured(chunk) => StreamEvent::Structured {
+                index,
+                chunk: chunk.clone(),
+            },
+            EventPart::ToolCall(ToolCallPart::Start { id, name }) => StreamEvent::ToolCallStart {
+                index,
+                id: id.clone(),
+                name: name.clone(),
+            },
+            EventPart::ToolCall(ToolCallPart::ArgumentChunk(chunk)) => {
+                StreamEvent::ToolCallArguments {
+                    index,
+                    chunk: chunk.clone(),
+                }
+            }
+        };
+
+        self.emit(&event);
+    }
+
+    /// Emit an event committed to the conversation.
+    ///
+    /// Assistant responses were already emitted chunk by chunk, so only
+    /// requests, tool calls and inquiries produce an event.
+    pub(crate) fn committed(&self, event: &ConversationEvent) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+
+        let event = match &event.kind {
+            EventKind::ChatRequest(request) => StreamEvent::Request {
+                content: request.content.clone(),
+            },
+            EventKind::ToolCallRequest(request) => StreamEvent::ToolCall {
+                id: request.id.clone(),
+                name: request.name.clone(),
+                arguments: request.arguments.clone(),
+            },
+            EventKind::ToolCallResponse(response) => {
+                inner.tally().tool_calls += 1;
+                StreamEvent::ToolCallResult {
+                    id: response.id.clone(),
+                    content: response.content().to_owned(),
+                    is_error: response.result.is_err(),
+                }
+            }
+            EventKind::InquiryRequest(request) => StreamEvent::Inquiry {
+                id: request.id.as_str().to_owned(),
+                source: request.source.clone(),
+                question: request.question.clone(),
+            },
+            EventKind::InquiryResponse(response) => StreamEvent::InquiryAnswer {
+                id: response.id.as_str().to_owned(),
+                answer: response.answer.clone(),
+            },
+            EventKind::TurnStart(_) | EventKind::ChatResponse(_) => return,
+        };
+
+        self.emit(&event);
+    }
+
+    /// Record the end of a provider response.
+    pub(crate) fn finished(&self, reason: &FinishReason) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+
+        let mut tally = inner.tally();
+        match reason {
+            FinishReason::Retry => {}
+            FinishReason::MaxTokens => {
+                tally.requests += 1;
+                tally.status = TurnStatus::Truncated;
+            }
+            FinishReason::Refused { .. } => {
+                tally.requests += 1;
+                tally.status = TurnStatus::Refused;
+            }
+            FinishReason::Completed | FinishReason::Other(_) => {
+                tally.requests += 1;
+                tally.status = TurnStatus::Completed;
+            }
+        }
+    }
+
+    /// Record that the turn was discarded.
+    pub(crate) fn aborted(&self) {
+        if let Some(inner) = &self.inner {
+            inner.tally().status = TurnStatus::Aborted;
+        }
+    }
+
+    /// Emit the closing `usage` and `turn_end` events, returning how the turn
+    /// ended.
+    ///
+    /// `error` is the error the turn failed with, if any.
+    pub(crate) fn end_turn(&self, error: Option<&cmd::Error>) -> TurnStatus {
+        let Some(inner) = &self.inner else {
+            return TurnStatus::default();
+        };
+
+        let (requests, tool_calls, status) = {
+            let tally = inner.tally();
+            (tally.requests, tally.tool_calls, tally.status)
+        };
+
+        let (status, exit_code, error) = match error {
+            Some(error) => {
+                let status = if error.code.get() == TurnStatus::Interrupted.exit_code() {
+                    TurnStatus::Interrupted
+                } else {
+                    TurnStatus::Failed
+                };
+                (status, error.code.get(), error.message.clone())
+            }
+            None => (status, status.exit_code(), None),
+        };
+
+        self.emit(&StreamEvent::Usage {
+            requests,
+            tool_calls,
+        });
+        self.emit(&StreamEvent::TurnEnd {
+            status,
+            exit_code,
+            error,
+        });
+
+        status
+    }
+}
+
+impl Inner {
+    fn tally(&self) -> std::sync::MutexGuard<'_, Tally> {
+        self.tally
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+#[path = "events_tests.rs"]
+mod tests;
@@ -0,0 +1,136 @@
+use jp_conversation::event::{ChatRequest, ChatResponse, InquiryRequest, ToolCallResponse};
+use jp_printer::{OutputFormat, SharedBuffer};
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+use super::*;
+
+fn sink() -> (EventSink, Arc<Printer>, SharedBuffer) {
+    let (printer, out, _) = Printer::memory(OutputFormat::Jsonl);
+    let printer = Arc::new(printer);
+
+    (EventSink::new(&printer), printer, out)
+}
+
+fn lines(printer: &Printer, out: &SharedBuffer) -> Vec<Value> {
+    printer.flush();
+    out.lock()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn disabled_for_other_formats() {
+    for format in [OutputFormat::Text, OutputFormat::Json] {
+        let (printer, out, _) = Printer::memory(format);
+        let printer = Arc::new(printer);
+        let events = EventSink::new(&printer);
+
+        events.part(0, &EventPart::Message("Hi".into()));
+        assert_eq!(events.end_turn(None), TurnStatus::Completed);
+
+        assert!(!events.is_enabled());
+        assert!(lines(&printer, &out).is_empty());
+    }
+}
+
+#[test]
+fn parts_are_emitted_as_chunks() {
+    let (events, printer, out) = sink();
+
+    events.part(0, &EventPart::Reasoning("Hmm".into()));
+    events.part(1, &EventPart::Message("Hi".into()));
+    events.part(
+        2,
+        &EventPart::ToolCall(ToolCallPart::Start {
+            id: "call_1".into(),
+            name: "fs_read_file".into(),
+        }),
+    );
+    events.part(
+        2,
+        &EventPart::ToolCall(ToolCallPart::ArgumentChunk("{\"path\":".into())),
+    );
+
+    assert_eq!(lines(&printer, &out), vec![
+        json!({"type": "reasoning", "index": 0, "chunk": "Hmm"}),
+        json!({"type": "message", "index": 1, "chunk": "Hi"}),
+        json!({"type": "tool_call_start", "index": 2, "id": "call_1", "name": "fs_read_file"}),
+        json!({"type": "tool_call_arguments", "index": 2, "chunk": "{\"path\":"}),
+    ]);
+}
+
+#[test]
+fn committed_events_skip_assistant_responses() {
+    let (events, printer, out) = sink();
+
+    events.committed(&ConversationEvent::now(ChatRequest::from("Hello")));
+    events.committed(&ConversationEvent::now(ChatResponse::message("Hi")));
+    events.committed(&ConversationEvent::now(ToolCallResponse {
+        id: "call_1".into(),
+        result: Err("No such file.".into()),
+    }));
+    events.committed(&ConversationEvent::now(InquiryRequest::new(
+        "call_1.overwrite",
+        InquirySource::tool("fs_create_file"),
+        InquiryQuestion::boolean("Overwrite the file?".into()),
+    )));
+
+    assert_eq!(lines(&printer, &out), vec![
+        json!({"type": "request", "content": "Hello"}),
+        json!({"type": "tool_call_result", "id": "call_1", "content": "No such file.", "is_error": true}),
+        json!({
+            "type": "inquiry",
+            "id": "call_1.overwrite",
+            "source": {"source": "tool", "name": "fs_create_file"},
+            "question": {"text": "Overwrite the file?", "answer_type": {"type": "boolean"}},
+        }),
+    ]);
+}
+
+#[test]
+fn end_turn_reports_usage_and_status() {
+    let (events, printer, out) = sink();
+
+    events.finished(&FinishReason::Completed);
+    events.committed(&ConversationEvent::now(ToolCallResponse {
+        id: "call_1".into(),
+        result: Ok("Done.".into()),
+    }));
+    events.finished(&FinishReason::Retry);
+    events.finished(&FinishReason::MaxTokens);
+
+    assert_eq!(events.end_turn(None), TurnStatus::Truncated);
+    assert_eq!(lines(&printer, &out)[1..], [
+        json!({"type": "usage", "requests": 2, "tool_calls": 1}),
+        json!({"type": "turn_end", "status": "truncated", "exit_code": 3}),
+    ]);
+}
+
+#[test]
+fn end_turn_with_error() {
+    let (events, printer, out) = sink();
+
+    events.finished(&FinishReason::Completed);
+    let status = events.end_turn(Some(&cmd::Error::interrupted()));
+
+    assert_eq!(status, TurnStatus::Interrupted);
+    assert_eq!(
+        lines(&printer, &out)[1],
+        json!({"type": "turn_end", "status": "interrupted", "exit_code": 130, "error": "Interrupted"})
+    );
+}
+
+#[test]
+fn aborted_turn() {
+    let (events, printer, out) = sink();
+
+    events.aborted();
+
+    assert_eq!(events.end_turn(None), TurnStatus::Aborted);
+    assert_eq!(
+        lines(&printer, &out)[1],
+        json!({"type": "turn_end", "status": "aborted", "exit_code": 130})
+    );
+}
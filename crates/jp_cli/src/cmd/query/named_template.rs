@@ -0,0 +1,137 @@
+//! Rendering named query templates (`jp query --named-template NAME`).
+//!
+//! A named template turns the query words into parameters: a `name=value` word
+//! whose name is one of the template's parameters sets that parameter, and any
+//! other words are appended to the rendered template as extra query text.
+//! Parameters that are not given fall back to their default, or are prompted
+//! for when running interactively.
+//!
+//! The template's configuration overlay (model, tools, attachments) is applied
+//! by [`Query::apply_cli_config`], and its schema when the turn is composed.
+//!
+//! [`Query::apply_cli_config`]: super::Query
+
+use std::io;
+
+use indexmap::IndexMap;
+use jp_config::template::{TemplateConfig, named::NamedTemplateConfig};
+use jp_inquire::prompt::PromptBackend;
+use minijinja::{Environment, UndefinedBehavior};
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+
+/// Look up the named template `name`.
+pub(super) fn find<'a>(config: &'a TemplateConfig, name: &str) -> Result<&'a NamedTemplateConfig> {
+    config
+        .named
+        .get(name)
+        .ok_or_else(|| Error::NamedTemplateNotFound(name.to_owned()))
+}
+
+/// Split the query words into the template's parameters and the remaining
+/// query text.
+pub(super) fn split_words(
+    template: &NamedTemplateConfig,
+    words: &[String],
+) -> (IndexMap<String, String>, Vec<String>) {
+    let mut params = IndexMap::new();
+    let mut rest = vec![];
+
+    for word in words {
+        match word.split_once('=') {
+            Some((name, value)) if template.params.contains_key(name) => {
+                params.insert(name.to_owned(), value.to_owned());
+            }
+            _ => rest.push(word.clone()),
+        }
+    }
+
+    (params, rest)
+}
+
+/// Resolve the value of every template parameter.
+///
+/// Given values are parsed as the parameter's type.
+/// Parameters that are not given use their default, or are asked for through
+/// `prompts` when set.
+pub(super) fn resolve_params(
+    template: &NamedTemplateConfig,
+    mut given: IndexMap<String, String>,
+    prompts: Option<&dyn PromptBackend>,
+) -> Result<IndexMap<String, Value>> {
+    let mut values = IndexMap::new();
+
+    for (name, param) in &template.params {
+        let invalid = |message| Error::TemplateParam {
+            name: name.clone(),
+            message,
+        };
+
+        let value = match (given.shift_remove(name), &param.default, prompts) {
+            (Some(raw), _, _) => param.kind.parse(&raw).map_err(invalid)?,
+            (None, Some(default), _) => default.clone(),
+            (None, None, Some(prompts)) => {
+                let message = match &param.description {
+                    Some(description) => format!("{name} ({description}):"),
+                    None => format!("{name}:"),
+                };
+                let raw = prompts.text(&message, None, &mut io::stderr())?;
+                param.kind.parse(&raw).map_err(invalid)?
+            }
+            (None, None, None) => {
+                return Err(invalid(format!(
+                    "missing value for required parameter, pass it as `{name}=<value>`"
+                )));
+            }
+        };
+
+        values.insert(name.clone(), value);
+    }
+
+    Ok(values)
+}
+
+/// Render the template with its parameters and the `template.values` config.
+///
+/// Parameters take precedence over `template.values` of the same name.
+/// `extra` is appended to the rendered template, separated by a blank line.
+pub(super) fn render(
+    config: &TemplateConfig,
+    template: &NamedTemplateConfig,
+    params: IndexMap<String, Value>,
+    extra: &[String],
+) -> Result<String> {
+    let mut context = Map::new();
+    for (name, value) in &config.values {
+        context.insert(name.clone(), value.0.clone());
+    }
+    context.extend(params);
+
+    let mut env = Environment::empty();
+    env.set_undefined_behavior(UndefinedBehavior::SemiStrict);
+    env.add_template("query", template.content.as_deref().unwrap_or_default())?;
+
+    let tmpl = env.get_template("query")?;
+    if let Some(var) = tmpl
+        .undeclared_variables(false)
+        .into_iter()
+        .find(|var| !context.contains_key(var))
+    {
+        return Err(Error::TemplateUndefinedVariable(var));
+    }
+
+    let mut query = tmpl.render(&context)?;
+    if !extra.is_empty() {
+        if !query.is_empty() {
+            query.push_str("\n\n");
+        }
+        query.push_str(&extra.join(" "));
+    }
+
+    Ok(query)
+}
+
+#[cfg(test)]
+#[path = "named_template_tests.rs"]
+mod tests;
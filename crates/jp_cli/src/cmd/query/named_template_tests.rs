@@ -0,0 +1,158 @@
+use indexmap::IndexMap;
+use jp_config::{
+    Config as _,
+    template::named::{PartialNamedTemplateConfig, TemplateParamType},
+    types::json_value::JsonValue,
+};
+use jp_inquire::prompt::MockPromptBackend;
+use serde_json::json;
+
+use super::*;
+
+fn template(toml: &str) -> NamedTemplateConfig {
+    let partial: PartialNamedTemplateConfig = toml::from_str(toml).unwrap();
+    NamedTemplateConfig::from_partial(partial, vec![]).unwrap()
+}
+
+fn review_pr() -> NamedTemplateConfig {
+    template(
+        r#"
+        content = "Review pull request #{{ pr }}, focusing on {{ focus }}."
+
+        [params.pr]
+        type = "integer"
+        description = "The pull request number"
+
+        [params.focus]
+        default = "correctness"
+        "#,
+    )
+}
+
+fn words(words: &[&str]) -> Vec<String> {
+    words.iter().map(|&word| word.to_owned()).collect()
+}
+
+#[test]
+fn find_unknown_template() {
+    let config = TemplateConfig {
+        values: IndexMap::new(),
+        named: IndexMap::from_iter([("review-pr".to_owned(), review_pr())]),
+    };
+
+    assert!(find(&config, "review-pr").is_ok());
+    assert!(matches!(
+        find(&config, "review"),
+        Err(Error::NamedTemplateNotFound(name)) if name == "review"
+    ));
+}
+
+#[test]
+fn split_words_takes_declared_params_only() {
+    let (params, rest) = split_words(
+        &review_pr(),
+        &words(&["pr=123", "also", "check", "docs=yes", "focus=a=b"]),
+    );
+
+    assert_eq!(
+        params,
+        IndexMap::from_iter([
+            ("pr".to_owned(), "123".to_owned()),
+            ("focus".to_owned(), "a=b".to_owned()),
+        ])
+    );
+    assert_eq!(rest, words(&["also", "check", "docs=yes"]));
+}
+
+#[test]
+fn resolve_params_parses_types_and_applies_defaults() {
+    let params = resolve_params(
+        &review_pr(),
+        IndexMap::from_iter([("pr".to_owned(), "123".to_owned())]),
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(
+        params,
+        IndexMap::from_iter([
+            ("pr".to_owned(), json!(123)),
+            ("focus".to_owned(), json!("correctness")),
+        ])
+    );
+}
+
+#[test]
+fn resolve_params_rejects_invalid_values() {
+    let error = resolve_params(
+        &review_pr(),
+        IndexMap::from_iter([("pr".to_owned(), "latest".to_owned())]),
+        None,
+    )
+    .unwrap_err();
+
+    assert!(matches!(
+        error,
+        Error::TemplateParam { name, message }
+            if name == "pr" && message == "expected an integer, got `latest`"
+    ));
+}
+
+#[test]
+fn resolve_params_requires_missing_values_without_prompts() {
+    let error = resolve_params(&review_pr(), IndexMap::new(), None).unwrap_err();
+
+    assert!(matches!(error, Error::TemplateParam { name, .. } if name == "pr"));
+}
+
+#[test]
+fn resolve_params_prompts_for_missing_values() {
+    let prompts = MockPromptBackend::new().with_text_responses(["42"]);
+
+    let params = resolve_params(&review_pr(), IndexMap::new(), Some(&prompts)).unwrap();
+
+    assert_eq!(params["pr"], json!(42));
+    assert_eq!(params["focus"], json!("correctness"));
+}
+
+#[test]
+fn render_with_params_values_and_extra_text() {
+    let config = TemplateConfig {
+        values: IndexMap::from_iter([
+            ("focus".to_owned(), JsonValue(json!("style"))),
+            ("repo".to_owned(), JsonValue(json!("jp"))),
+        ]),
+        named: IndexMap::new(),
+    };
+    let template = template(r#"content = "Review {{ repo }}#{{ pr }} for {{ focus }}.""#);
+
+    let query = render(
+        &config,
+        &template,
+        IndexMap::from_iter([
+            ("pr".to_owned(), json!(7)),
+            ("focus".to_owned(), json!("security")),
+        ]),
+        &words(&["Be", "brief."]),
+    )
+    .unwrap();
+
+    assert_eq!(query, "Review jp#7 for security.\n\nBe brief.");
+}
+
+#[test]
+fn render_rejects_undefined_variables() {
+    let config = TemplateConfig {
+        values: IndexMap::new(),
+        named: IndexMap::new(),
+    };
+
+    let error = render(&config, &review_pr(), IndexMap::new(), &[]).unwrap_err();
+    assert!(matches!(error, Error::TemplateUndefinedVariable(_)));
+}
+
+#[test]
+fn param_types_are_declared_on_the_template() {
+    assert_eq!(review_pr().params["pr"].kind, TemplateParamType::Integer);
+    assert_eq!(review_pr().params["focus"].kind, TemplateParamType::String);
+}
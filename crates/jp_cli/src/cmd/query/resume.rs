@@ -0,0 +1,179 @@
+//! Resuming a turn that was interrupted before it completed.
+//!
+//! A turn that runs to completion ends with the assistant's final response.
+//! If the process dies mid-turn (a crash, a lost connection, Ctrl-C during
+//! tool execution), the persisted stream ends somewhere inside the turn
+//! instead.
+//! [`IncompleteTurn::detect`] inspects the last turn and determines where it
+//! stopped, so `jp query --resume` can continue from exactly there rather than
+//! re-sending the whole turn.
+//!
+//! See `docs/rfd/023-resumable-conversation-turns.md`.
+
+use std::{collections::HashSet, fmt};
+
+use jp_conversation::{
+    ConversationStream,
+    event::{InquiryRequest, RESPONSE_FINISHED_KEY, ToolCallRequest},
+};
+use serde_json::Value;
+
+use super::turn::TurnPhase;
+
+/// The state an interrupted turn was left in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum IncompleteTurn {
+    /// The latest request never received a response.
+    PendingResponse,
+
+    /// Tool results were recorded, but never sent back to the assistant.
+    PendingFollowUp,
+
+    /// The response was cut off while the assistant was still reasoning or
+    /// answering, mid-stream or by the provider (e.g. at `max_tokens`).
+    TruncatedResponse,
+
+    /// Tool calls never received a result.
+    PendingTools {
+        /// The tool calls to run.
+        calls: Vec<ToolCallRequest>,
+
+        /// Questions those tools asked that were never answered.
+        inquiries: Vec<InquiryRequest>,
+    },
+}
+
+impl IncompleteTurn {
+    /// Inspect the last turn in the stream, returning how it is incomplete, if
+    /// it is.
+    ///
+    /// A turn is complete when every tool call has a result, and the assistant
+    /// responded to the latest input (the request, or the tool results) by
+    /// calling a tool or with a response it finished streaming.
+    /// In streams without any finished-response markers, which predate them,
+    /// any response beyond reasoning counts as finished.
+    pub(crate) fn detect(stream: &ConversationStream) -> Option<Self> {
+        let turn = stream.iter_turns().next_back()?;
+        let events: Vec<_> = turn.iter().map(|e| e.event).collect();
+
+        if !events.iter().any(|e| e.is_chat_request()) {
+            return None;
+        }
+
+        // Match responses across the whole stream, like the turn coordinator
+        // does when deciding whether there is work left to execute.
+        let responded: HashSet<&str> = stream
+            .iter()
+            .filter_map(|e| e.event.as_tool_call_response())
+            .map(|r| r.id.as_str())
+            .collect();
+
+        let calls: Vec<ToolCallRequest> = events
+            .iter()
+            .filter_map(|e| e.as_tool_call_request())
+            .filter(|r| !responded.contains(r.id.as_str()))
+            .cloned()
+            .collect();
+
+        if !calls.is_empty() {
+            let answered: HashSet<_> = events
+                .iter()
+                .filter_map(|e| e.as_inquiry_response())
+                .map(|r| &r.id)
+                .collect();
+
+            let inquiries = events
+                .iter()
+                .filter_map(|e| e.as_inquiry_request())
+                .filter(|r| !answered.contains(&r.id))
+                .cloned()
+                .collect();
+
+            return Some(Self::PendingTools { calls, inquiries });
+        }
+
+        // The assistant answers the latest input: the user's request (or an
+        // interrupt reply), or the results of the tools it called.
+        let input = events
+            .iter()
+            .rposition(|e| e.is_chat_request() || e.is_tool_call_response())?;
+
+        let output = &events[input + 1..];
+        if !output.iter().any(|e| e.is_chat_response()) {
+            return Some(if events[input].is_chat_request() {
+                Self::PendingResponse
+            } else {
+                Self::PendingFollowUp
+            });
+        }
+
+        // Text streamed before the connection dropped is persisted as it
+        // arrived, so only the marker recorded when the provider finished the
+        // response tells a complete answer from a partial one.
+        // Streams recorded before the marker existed have none at all; there,
+        // any answer beyond reasoning counts.
+        let marked = stream
+            .iter()
+            .any(|e| e.event.metadata.contains_key(RESPONSE_FINISHED_KEY));
+
+        let answered = output.iter().any(|e| {
+            e.is_tool_call_request()
+                || e.as_chat_response().is_some_and(|r| {
+                    if marked {
+                        e.metadata
+                            .get(RESPONSE_FINISHED_KEY)
+                            .and_then(Value::as_bool)
+                            .unwrap_or(false)
+                    } else {
+                        !r.is_reasoning()
+                    }
+                })
+        });
+
+        (!answered).then_some(Self::TruncatedResponse)
+    }
+
+    /// The turn phase to resume in.
+    pub(crate) const fn phase(&self) -> TurnPhase {
+        match self {
+            Self::PendingTools { .. } => TurnPhase::Executing,
+            Self::PendingResponse | Self::PendingFollowUp | Self::TruncatedResponse => {
+                TurnPhase::Streaming
+            }
+        }
+    }
+}
+
+impl fmt::Display for IncompleteTurn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PendingResponse => f.write_str("waiting for the assistant's response"),
+            Self::PendingFollowUp => f.write_str("waiting for a response to the tool results"),
+            Self::TruncatedResponse => f.write_str("the assistant's response was cut off"),
+            Self::PendingTools { calls, inquiries } => {
+                let names = calls
+                    .iter()
+                    .map(|call| call.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                write!(f, "waiting to run {names}")?;
+                if !inquiries.is_empty() {
+                    let questions = inquiries
+                        .iter()
+                        .map(|inquiry| format!("{:?}", inquiry.question.text))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    write!(f, " (unanswered: {questions})")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "resume_tests.rs"]
+mod tests;
@@ -0,0 +1,232 @@
+use std::sync::Arc;
+
+use jp_config::AppConfig;
+use jp_conversation::{
+    ConversationEvent,
+    event::{
+        ChatRequest, ChatResponse, InquiryQuestion, InquiryResponse, InquirySource,
+        ToolCallResponse,
+    },
+};
+use jp_llm::event::{Event, FinishReason};
+use jp_printer::{OutputFormat, Printer};
+use pretty_assertions::assert_eq;
+
+use super::*;
+use crate::cmd::query::turn::coordinator::TurnCoordinator;
+
+fn call(id: &str, name: &str) -> ToolCallRequest {
+    ToolCallRequest {
+        id: id.into(),
+        name: name.into(),
+        arguments: serde_json::Map::new(),
+    }
+}
+
+fn result(id: &str) -> ToolCallResponse {
+    ToolCallResponse {
+        id: id.into(),
+        result: Ok("done".into()),
+    }
+}
+
+/// A response the provider finished streaming.
+fn finished(response: ChatResponse) -> ConversationEvent {
+    ConversationEvent::from(response).with_metadata_field(RESPONSE_FINISHED_KEY, true)
+}
+
+fn stream(events: Vec<ConversationEvent>) -> ConversationStream {
+    let mut stream = ConversationStream::new_test().with_turn(ChatRequest::from("hello"));
+    stream.extend(events);
+    stream
+}
+
+#[test]
+fn empty_stream_has_nothing_to_resume() {
+    assert_eq!(
+        IncompleteTurn::detect(&ConversationStream::new_test()),
+        None
+    );
+}
+
+#[test]
+fn answered_turn_is_complete() {
+    let stream = stream(vec![
+        ChatResponse::reasoning("thinking").into(),
+        ChatResponse::message("hi").into(),
+    ]);
+
+    assert_eq!(IncompleteTurn::detect(&stream), None);
+}
+
+#[test]
+fn finished_response_completes_the_turn() {
+    let stream = stream(vec![
+        ChatResponse::reasoning("thinking").into(),
+        finished(ChatResponse::message("hi")),
+    ]);
+
+    assert_eq!(IncompleteTurn::detect(&stream), None);
+}
+
+#[test]
+fn legacy_stream_without_markers_is_complete_when_answered() {
+    let mut stream = stream(vec![ChatResponse::message("hi").into()]);
+    stream.start_turn(ChatRequest::from("read the file"));
+    stream.extend([
+        ConversationEvent::from(call("1", "fs_read_file")),
+        result("1").into(),
+        ChatResponse::reasoning("so the file says").into(),
+        ChatResponse::message("It says hello.").into(),
+    ]);
+
+    assert_eq!(IncompleteTurn::detect(&stream), None);
+}
+
+#[test]
+fn unanswered_request_resumes_streaming() {
+    let stream = stream(vec![]);
+
+    let incomplete = IncompleteTurn::detect(&stream);
+    assert_eq!(incomplete, Some(IncompleteTurn::PendingResponse));
+    assert_eq!(incomplete.unwrap().phase(), TurnPhase::Streaming);
+}
+
+#[test]
+fn only_the_last_turn_is_inspected() {
+    let mut stream = stream(vec![ChatResponse::message("hi").into()]);
+    stream.start_turn(ChatRequest::from("again"));
+    stream.extend([finished(ChatResponse::message("hi again"))]);
+
+    assert_eq!(IncompleteTurn::detect(&stream), None);
+}
+
+#[test]
+fn unsent_tool_results_resume_streaming() {
+    let stream = stream(vec![
+        ChatResponse::message("let me check").into(),
+        call("1", "fs_read_file").into(),
+        result("1").into(),
+    ]);
+
+    let incomplete = IncompleteTurn::detect(&stream);
+    assert_eq!(incomplete, Some(IncompleteTurn::PendingFollowUp));
+    assert_eq!(incomplete.unwrap().phase(), TurnPhase::Streaming);
+}
+
+#[test]
+fn reasoning_without_an_answer_is_truncated() {
+    let stream = stream(vec![
+        call("1", "fs_read_file").into(),
+        result("1").into(),
+        ChatResponse::reasoning("so the file says").into(),
+    ]);
+
+    assert_eq!(
+        IncompleteTurn::detect(&stream),
+        Some(IncompleteTurn::TruncatedResponse)
+    );
+}
+
+#[test]
+fn answer_cut_off_by_max_tokens_is_truncated() {
+    let mut stream = ConversationStream::new_test();
+    let (printer, _, _) = Printer::memory(OutputFormat::Text);
+    let mut coordinator = TurnCoordinator::new(
+        Arc::new(printer),
+        AppConfig::new_test().style,
+        None,
+        None,
+        None,
+    );
+
+    coordinator.start_turn(&mut stream, ChatRequest::from("hello"));
+    coordinator.handle_event(&mut stream, Event::message(0, "The answer is"));
+    coordinator.handle_event(&mut stream, Event::flush(0));
+    coordinator.handle_event(&mut stream, Event::Finished(FinishReason::MaxTokens));
+
+    assert_eq!(
+        IncompleteTurn::detect(&stream),
+        Some(IncompleteTurn::TruncatedResponse)
+    );
+}
+
+#[test]
+fn answer_cut_off_mid_stream_is_truncated() {
+    // The earlier turn shows the stream records finished responses.
+    let mut stream = stream(vec![finished(ChatResponse::message("hi"))]);
+    stream.start_turn(ChatRequest::from("and then?"));
+    stream.extend([
+        ChatResponse::reasoning("thinking").into(),
+        ConversationEvent::from(ChatResponse::message("The answer is")),
+    ]);
+
+    let incomplete = IncompleteTurn::detect(&stream);
+    assert_eq!(incomplete, Some(IncompleteTurn::TruncatedResponse));
+    assert_eq!(incomplete.unwrap().phase(), TurnPhase::Streaming);
+}
+
+#[test]
+fn tool_call_answers_the_latest_input() {
+    let stream = stream(vec![
+        ChatResponse::message("let me check").into(),
+        call("1", "fs_read_file").into(),
+        result("1").into(),
+        ChatResponse::message("let me check again").into(),
+        call("2", "fs_read_file").into(),
+        result("2").into(),
+        finished(ChatResponse::message("done")),
+    ]);
+
+    assert_eq!(IncompleteTurn::detect(&stream), None);
+}
+
+#[test]
+fn unanswered_tool_calls_resume_executing() {
+    let stream = stream(vec![
+        call("1", "cargo_check").into(),
+        call("2", "fs_modify_file").into(),
+        call("3", "fs_read_file").into(),
+        result("1").into(),
+        result("3").into(),
+    ]);
+
+    let incomplete = IncompleteTurn::detect(&stream).unwrap();
+    assert_eq!(incomplete.phase(), TurnPhase::Executing);
+    assert_eq!(incomplete, IncompleteTurn::PendingTools {
+        calls: vec![call("2", "fs_modify_file")],
+        inquiries: vec![],
+    });
+    assert_eq!(incomplete.to_string(), "waiting to run fs_modify_file");
+}
+
+#[test]
+fn pending_tools_include_unanswered_inquiries() {
+    let pending = InquiryRequest::new(
+        "2.overwrite",
+        InquirySource::tool("fs_modify_file"),
+        InquiryQuestion::boolean("Overwrite existing file?".into()),
+    );
+    let answered = InquiryRequest::new(
+        "2.create",
+        InquirySource::tool("fs_modify_file"),
+        InquiryQuestion::boolean("Create parent directories?".into()),
+    );
+
+    let stream = stream(vec![
+        call("2", "fs_modify_file").into(),
+        answered.into(),
+        InquiryResponse::boolean("2.create", true).into(),
+        pending.clone().into(),
+    ]);
+
+    let incomplete = IncompleteTurn::detect(&stream).unwrap();
+    assert_eq!(incomplete, IncompleteTurn::PendingTools {
+        calls: vec![call("2", "fs_modify_file")],
+        inquiries: vec![pending],
+    });
+    assert_eq!(
+        incomplete.to_string(),
+        "waiting to run fs_modify_file (unanswered: \"Overwrite existing file?\")"
+    );
+}
@@ -0,0 +1,347 @@
+//! Running `sub_agent` tool calls in child conversations.
+//!
+//! The `sub_agent` builtin lives in [`jp_llm::tool::builtin::sub_agent`], but
+//! running its task takes the same turn loop as `jp query`.
+//! [`SubAgents`] provides that: every task gets a new conversation, linked to
+//! its parent, which runs one turn to completion.
+//! The child's output is not rendered; `jp conversation print` shows it.
+//! Nor does the child prompt: a prompt would show up without the output that
+//! led to it, so tools that ask the user are refused.
+//!
+//! See `docs/rfd/051-sub-agent-workflows.md`.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use camino::Utf8PathBuf;
+use chrono::{DateTime, Utc};
+use jp_config::{
+    AppConfig,
+    conversation::tool::{AllowToggle, PartialEnableConfig},
+    env::Env,
+};
+use jp_conversation::{Conversation, ConversationId, event::ChatRequest};
+use jp_inquire::prompt::NoPromptBackend;
+use jp_llm::{
+    provider::ProviderCache,
+    tool::{
+        BackgroundTasks, InvocationContext,
+        builtin::sub_agent::{SubAgentAnswer, SubAgentRunner, SubAgentTask},
+        tool_definitions,
+    },
+};
+use jp_printer::Printer;
+use jp_storage::backend::Projection;
+use jp_tool::Checkpoint;
+use jp_workspace::{ConversationFactory, ConversationLock, ScratchRecorder};
+use tracing::debug;
+
+use super::{last_assistant_message, tool_coordinator, turn_loop::run_turn_loop};
+use crate::{access::approvals::ApprovalStore, signals::SignalRouter};
+
+/// How deep sub-agents may nest.
+///
+/// A sub-agent can delegate to sub-agents of its own, up to this depth.
+const MAX_DEPTH: usize = 3;
+
+/// Runs sub-agent tasks in child conversations of a parent conversation.
+#[derive(Clone)]
+pub(crate) struct SubAgents {
+    /// The conversation new sub-agents are children of.
+    parent: ConversationId,
+
+    /// How many sub-agents deep the parent conversation is.
+    depth: usize,
+
+    /// The parent's configuration, which sub-agents start from.
+    config: Arc<AppConfig>,
+
+    /// Creates the child conversations.
+    conversations: ConversationFactory,
+
+    /// The storage projection of the parent, shared by its children.
+    projection: Projection,
+
+    /// When the parent expires, which its children do too.
+    expires_at: Option<DateTime<Utc>>,
+
+    signals: SignalRouter,
+    mcp_client: jp_mcp::Client,
+    root: Utf8PathBuf,
+    approvals: Arc<ApprovalStore>,
+    workspace_id: String,
+
+    /// The environment the parent runs with, which its children share.
+    env: Env,
+
+    /// The checkpoint of the parent's turn, which the changes of sub-agents
+    /// are recorded in too.
+    checkpoint: Option<Checkpoint>,
+
+    /// The providers of the parent, which its children reuse.
+    providers: ProviderCache,
+}
+
+impl SubAgents {
+    #[expect(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        lock: &ConversationLock,
+        config: Arc<AppConfig>,
+        conversations: ConversationFactory,
+        signals: SignalRouter,
+        mcp_client: jp_mcp::Client,
+        root: Utf8PathBuf,
+        approvals: Arc<ApprovalStore>,
+        workspace_id: String,
+        env: Env,
+        checkpoint: Option<Checkpoint>,
+        providers: ProviderCache,
+    ) -> Self {
+        Self {
+            parent: lock.id(),
+            depth: 0,
+            config,
+            conversations,
+            projection: lock.projection(),
+            expires_at: lock.metadata().expires_at,
+            signals,
+            mcp_client,
+            root,
+            approvals,
+            workspace_id,
+            env,
+            checkpoint,
+            providers,
+        }
+    }
+
+    /// A recorder for the requests made on behalf of the parent conversation.
+    pub(crate) fn scratch(&self) -> ScratchRecorder {
+        ScratchRecorder::new(
+            self.conversations.clone(),
+            self.parent,
+            self.projection,
+            self.expires_at,
+        )
+    }
+
+    /// Run the task in a new child conversation, returning the final answer.
+    async fn run_child(&self, task: SubAgentTask) -> Result<SubAgentAnswer, String> {
+        if self.depth >= MAX_DEPTH {
+            return Err(format!(
+                "Sub-agents can be nested at most {MAX_DEPTH} levels deep. Complete this task \
+                 yourself."
+            ));
+        }
+
+        let config = Arc::new(child_config(&self.config, &task)?);
+        ensure_unattended(&config)?;
+        let lock = self
+            .conversations
+            .create_and_lock(
+                Conversation {
+                    parent: Some(self.parent),
+                    expires_at: self.expires_at,
+                    ..Conversation::default()
+                },
+                config.clone(),
+                self.projection,
+            )
+            .map_err(|error| error.to_string())?;
+        let id = lock.id();
+        debug!(%id, parent = %self.parent, depth = self.depth + 1, "Running sub-agent.");
+
+        let model_id = config.assistant.model.id.resolved();
+        let provider = self
+            .providers
+            .get(model_id.provider, &config.providers.llm, &self.env)
+            .map_err(|error| error.to_string())?;
+        let model = provider
+            .model_details(&model_id.name)
+            .await
+            .map_err(|error| error.to_string())?;
+
+        let forced_tool = config.assistant.tool_choice.function_name();
+        let tools = tool_definitions(
+            config.conversation.tools.iter(),
+            &self.mcp_client,
+            forced_tool,
+        )
+        .await
+        .map_err(|error| error.to_string())?;
+
+        let invocation = InvocationContext {
+            workspace_id: self.workspace_id.clone(),
+            conversation_id: id.to_string(),
+            // Sub-agent output is not shown, so its tools get no terminal.
+            terminal: None,
+            background: BackgroundTasks::default(),
+            checkpoint: self.checkpoint.clone(),
+            env: self.env.clone(),
+        };
+        let background = invocation.background.clone();
+
+        // The child's own sub-agents are its children, one level deeper.
+        let children = Self {
+            parent: id,
+            depth: self.depth + 1,
+            config: config.clone(),
+            ..self.clone()
+        };
+
+        let scratch = children.scratch();
+        let tool_coordinator = tool_coordinator(
+            &config,
+            &tools,
+            self.approvals.clone(),
+            invocation.clone(),
+            Arc::new(children),
+        )
+        .with_scratch(Some(scratch));
+
+        let result = run_turn_loop(
+            provider,
+            &model,
+            &config,
+            &self.signals,
+            &self.mcp_client,
+            &self.root,
+            false,
+            &[],
+            &lock,
+            config.assistant.tool_choice.clone(),
+            &tools,
+            Arc::new(Printer::sink()),
+            Arc::new(NoPromptBackend),
+            tool_coordinator,
+            ChatRequest::from(task.query).into(),
+            invocation,
+            &self.providers,
+        )
+        .await;
+
+        // The sub-agent's background tools stop when it answers.
+        background.shutdown().await;
+        lock.as_mut()
+            .update_events(|stream| background.persist(stream));
+
+        result.map_err(|error| format!("Sub-agent in conversation {id} failed: {error}"))?;
+
+        let content = lock
+            .with_events(|events| last_assistant_message(events).map(str::to_owned))
+            .ok_or_else(|| format!("Sub-agent in conversation {id} finished without answering."))?;
+
+        Ok(SubAgentAnswer {
+            conversation_id: id.to_string(),
+            content,
+        })
+    }
+}
+
+#[async_trait]
+impl SubAgentRunner for SubAgents {
+    async fn run(&self, task: SubAgentTask) -> Result<SubAgentAnswer, String> {
+        self.run_child(task).await
+    }
+}
+
+/// Derive a sub-agent's configuration from its parent's.
+///
+/// The task's model and instructions replace the parent's.
+/// A task that names its tools gets exactly those, plus any tool that cannot be
+/// toggled (such as `describe_tools`).
+/// It can only name tools enabled in the parent, so a sub-agent never gets a
+/// tool the user turned off.
+/// A task that names no tools gets the parent's tools that don't prompt the
+/// user, as a sub-agent can't.
+fn child_config(parent: &AppConfig, task: &SubAgentTask) -> Result<AppConfig, String> {
+    let mut partial = parent.to_partial();
+
+    if let Some(model) = &task.model {
+        partial.assistant.model.id = model.as_str().into();
+    }
+
+    if let Some(instructions) = &task.instructions {
+        partial.assistant.system_prompt = Some(instructions.clone());
+    }
+
+    let names: Vec<&str> = match &task.tools {
+        Some(names) => names.iter().map(String::as_str).collect(),
+        None => parent
+            .conversation
+            .tools
+            .iter()
+            .filter(|(_, tool)| tool.is_enabled() && !tool.prompts_user())
+            .map(|(name, _)| name)
+            .collect(),
+    };
+
+    let tools = &mut partial.conversation.tools.tools;
+    let unknown: Vec<_> = names
+        .iter()
+        .copied()
+        .filter(|name| !tools.contains_key(*name))
+        .collect();
+
+    if !unknown.is_empty() {
+        return Err(format!("Unknown tools: {}", unknown.join(", ")));
+    }
+
+    let disabled: Vec<_> = names
+        .iter()
+        .copied()
+        .filter(|name| {
+            parent
+                .conversation
+                .tools
+                .get(name)
+                .is_none_or(|tool| !tool.is_enabled())
+        })
+        .collect();
+
+    if !disabled.is_empty() {
+        return Err(format!(
+            "Tools not enabled in this conversation: {}",
+            disabled.join(", ")
+        ));
+    }
+
+    for (name, tool) in tools.iter_mut() {
+        let locked = tool
+            .enable
+            .as_ref()
+            .is_some_and(|enable| enable.allow_toggle == Some(AllowToggle::Never));
+
+        if !locked {
+            tool.enable = Some(PartialEnableConfig::from(names.contains(&name.as_str())));
+        }
+    }
+
+    let mut config =
+        AppConfig::from_partial_with_defaults(partial).map_err(|error| error.to_string())?;
+    config
+        .resolve_aliases()
+        .map_err(|error| error.to_string())?;
+
+    Ok(config)
+}
+
+/// Refuse a sub-agent whose tools would ask the user for confirmation or
+/// input.
+fn ensure_unattended(config: &AppConfig) -> Result<(), String> {
+    let tools = config.conversation.tools.prompting_tools();
+
+    if tools.is_empty() {
+        return Ok(());
+    }
+
+    Err(format!(
+        "Sub-agents cannot ask the user for confirmation or input, but these tools do: {}. Give \
+         the sub-agent only tools that run unattended, or do this task yourself.",
+        tools.join(", ")
+    ))
+}
+
+#[cfg(test)]
+#[path = "sub_agent_tests.rs"]
+mod tests;
@@ -0,0 +1,165 @@
+use indexmap::IndexMap;
+use jp_config::conversation::tool::{PartialToolConfig, RunMode, ToolSource};
+
+use super::*;
+
+fn tool(enable: PartialEnableConfig) -> PartialToolConfig {
+    PartialToolConfig {
+        source: Some(ToolSource::Builtin { tool: None }),
+        enable: Some(enable),
+        run: Some(RunMode::Unattended),
+        ..Default::default()
+    }
+}
+
+fn parent_config() -> AppConfig {
+    let mut partial = AppConfig::new_test().to_partial();
+    partial.assistant.system_prompt = Some("You are the main agent.".to_owned());
+    partial.conversation.tools.tools = IndexMap::from_iter([
+        ("fs_read_file".into(), tool(PartialEnableConfig::ON)),
+        ("fs_modify_file".into(), tool(PartialEnableConfig::ON)),
+        ("cargo_check".into(), tool(PartialEnableConfig::OFF)),
+        (
+            "describe_tools".into(),
+            tool(PartialEnableConfig::LOCKED_ON),
+        ),
+    ]);
+
+    AppConfig::from_partial_with_defaults(partial).unwrap()
+}
+
+fn enabled_tools(config: &AppConfig) -> Vec<&str> {
+    config
+        .conversation
+        .tools
+        .iter()
+        .filter(|(_, tool)| tool.effective_enable().is_enabled())
+        .map(|(name, _)| name)
+        .collect()
+}
+
+fn task() -> SubAgentTask {
+    SubAgentTask {
+        query: "Find the error types.".to_owned(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn child_inherits_the_parent_config() {
+    let parent = parent_config();
+    let child = child_config(&parent, &task()).unwrap();
+
+    assert_eq!(
+        child.assistant.model.id.resolved(),
+        parent.assistant.model.id.resolved()
+    );
+    assert_eq!(
+        child.assistant.system_prompt,
+        parent.assistant.system_prompt
+    );
+    assert_eq!(enabled_tools(&child), enabled_tools(&parent));
+}
+
+#[test]
+fn omitted_tools_default_to_the_parent_tools_that_run_unattended() {
+    let mut partial = parent_config().to_partial();
+    partial.conversation.tools.tools["fs_modify_file"].run = Some(RunMode::Ask);
+    let parent = AppConfig::from_partial_with_defaults(partial).unwrap();
+
+    let child = child_config(&parent, &task()).unwrap();
+
+    assert_eq!(enabled_tools(&child), vec![
+        "fs_read_file",
+        "describe_tools"
+    ]);
+    assert_eq!(ensure_unattended(&child), Ok(()));
+}
+
+#[test]
+fn task_overrides_model_and_instructions() {
+    let child = child_config(&parent_config(), &SubAgentTask {
+        model: Some("anthropic/haiku".to_owned()),
+        instructions: Some("You are a researcher.".to_owned()),
+        ..task()
+    })
+    .unwrap();
+
+    assert_eq!(
+        child.assistant.model.id.resolved().to_string(),
+        "anthropic/haiku"
+    );
+    assert_eq!(
+        child.assistant.system_prompt.as_deref(),
+        Some("You are a researcher.")
+    );
+}
+
+#[test]
+fn task_tools_replace_the_parent_tools() {
+    let child = child_config(&parent_config(), &SubAgentTask {
+        tools: Some(vec!["fs_read_file".to_owned()]),
+        ..task()
+    })
+    .unwrap();
+
+    // Locked tools stay enabled.
+    assert_eq!(enabled_tools(&child), vec![
+        "fs_read_file",
+        "describe_tools"
+    ]);
+}
+
+#[test]
+fn tools_disabled_in_the_parent_are_rejected() {
+    let error = child_config(&parent_config(), &SubAgentTask {
+        tools: Some(vec!["fs_read_file".to_owned(), "cargo_check".to_owned()]),
+        ..task()
+    })
+    .unwrap_err();
+
+    assert_eq!(error, "Tools not enabled in this conversation: cargo_check");
+}
+
+#[test]
+fn tools_that_ask_the_user_are_rejected() {
+    let mut partial = parent_config().to_partial();
+    partial.conversation.tools.tools["fs_modify_file"].run = Some(RunMode::Ask);
+    let parent = AppConfig::from_partial_with_defaults(partial).unwrap();
+
+    let reader = child_config(&parent, &SubAgentTask {
+        tools: Some(vec!["fs_read_file".to_owned()]),
+        ..task()
+    })
+    .unwrap();
+    assert_eq!(ensure_unattended(&reader), Ok(()));
+
+    let writer = child_config(&parent, &SubAgentTask {
+        tools: Some(vec!["fs_read_file".to_owned(), "fs_modify_file".to_owned()]),
+        ..task()
+    })
+    .unwrap();
+    let error = ensure_unattended(&writer).unwrap_err();
+    assert!(error.contains("these tools do: fs_modify_file."), "{error}");
+}
+
+#[test]
+fn unknown_tools_are_rejected() {
+    let error = child_config(&parent_config(), &SubAgentTask {
+        tools: Some(vec!["fs_read_file".to_owned(), "web_browse".to_owned()]),
+        ..task()
+    })
+    .unwrap_err();
+
+    assert_eq!(error, "Unknown tools: web_browse");
+}
+
+#[test]
+fn unknown_model_alias_is_rejected() {
+    let result = child_config(&parent_config(), &SubAgentTask {
+        model: Some("no-such-alias".to_owned()),
+        ..task()
+    });
+
+    assert!(result.is_err());
+}
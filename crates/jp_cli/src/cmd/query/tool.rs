@@ -4,15 +4,18 @@
 //! single-tool execution, interactive prompts, and terminal rendering.
 
 pub(crate) mod builtins;
+pub(crate) mod concurrency;
 pub(crate) mod coordinator;
 pub(crate) mod executor;
 pub(crate) mod inquiry;
 pub(crate) mod pending;
 pub(crate) mod prompter;
+pub(crate) mod terminal;
 
 pub(crate) use coordinator::{ToolCallDecision, ToolCallState, ToolCoordinator};
 pub(crate) use executor::TerminalExecutorSource;
 pub(crate) use pending::{PendingEntry, PendingTools, build_execution_plan};
 pub(crate) use prompter::ToolPrompter;
+pub(crate) use terminal::UserTerminal;
 
 pub(crate) use crate::{render::ToolRenderer, timer::spawn_line_timer};
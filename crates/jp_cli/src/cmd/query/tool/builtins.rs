@@ -1,7 +1,7 @@
 use indexmap::IndexMap;
 use jp_config::conversation::tool::{
-    PartialEnableConfig, PartialOneOrManyTypes, PartialToolConfig, PartialToolParameterConfig,
-    RunMode, ToolSource,
+    AllowToggle, PartialEnableConfig, PartialOneOrManyTypes, PartialToolConfig,
+    PartialToolParameterConfig, RunMode, ToolSource,
     style::{
         InlineResults, LinkStyle, ParametersStyle, PartialDisplayStyleConfig,
         PartialErrorStyleConfig,
@@ -9,7 +9,13 @@ use jp_config::conversation::tool::{
 };
 
 pub fn all() -> IndexMap<String, PartialToolConfig> {
-    IndexMap::from([("describe_tools".to_owned(), describe_tools())])
+    IndexMap::from([
+        ("describe_tools".to_owned(), describe_tools()),
+        ("sub_agent".to_owned(), sub_agent()),
+        ("await_handles".to_owned(), await_handles()),
+        ("poll_handle".to_owned(), poll_handle()),
+        ("cancel_handle".to_owned(), cancel_handle()),
+    ])
 }
 
 /// Returns the built-in `describe_tools` tool configuration.
@@ -42,3 +48,136 @@ pub fn describe_tools() -> PartialToolConfig {
         ..Default::default()
     }
 }
+
+/// Returns the built-in `sub_agent` tool configuration.
+///
+/// The tool is off unless enabled by name (`--tool sub_agent`), as every call
+/// starts a new, billed conversation.
+#[must_use]
+pub fn sub_agent() -> PartialToolConfig {
+    let string = |description: &str| PartialToolParameterConfig {
+        kind: PartialOneOrManyTypes::One("string".to_owned()),
+        description: Some(description.to_owned()),
+        ..Default::default()
+    };
+
+    PartialToolConfig {
+        source: Some(ToolSource::Builtin { tool: None }),
+        enable: Some(PartialEnableConfig {
+            state: Some(false),
+            allow_toggle: Some(AllowToggle::IfNamed),
+        }),
+        description: Some(
+            "Delegate a task to a sub-agent. The sub-agent runs in a separate conversation with \
+             its own model, instructions and tools, and returns its final answer. Use this for \
+             self-contained research or exploration that would otherwise fill your context with \
+             intermediate results."
+                .to_owned(),
+        ),
+        parameters: IndexMap::from([
+            ("query".to_owned(), PartialToolParameterConfig {
+                required: Some(true),
+                ..string(
+                    "The task for the sub-agent. It does not see this conversation, so include \
+                     all context it needs, and describe the answer you expect back.",
+                )
+            }),
+            (
+                "instructions".to_owned(),
+                string("System prompt for the sub-agent. Defaults to your own."),
+            ),
+            (
+                "model".to_owned(),
+                string("Model ID or alias for the sub-agent. Defaults to your own model."),
+            ),
+            ("tools".to_owned(), PartialToolParameterConfig {
+                kind: PartialOneOrManyTypes::One("array".to_owned()),
+                description: Some(
+                    "Names of the tools the sub-agent may use, out of your own tools. Defaults to \
+                     those of your own tools that run without asking the user."
+                        .to_owned(),
+                ),
+                items: Some(Box::new(PartialToolParameterConfig {
+                    kind: PartialOneOrManyTypes::One("string".to_owned()),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }),
+        ]),
+        run: Some(RunMode::Unattended),
+        ..Default::default()
+    }
+}
+
+/// Returns the built-in `await_handles` tool configuration.
+#[must_use]
+pub fn await_handles() -> PartialToolConfig {
+    PartialToolConfig {
+        source: Some(ToolSource::Builtin { tool: None }),
+        enable: Some(PartialEnableConfig::OFF),
+        description: Some(
+            "Wait for tools that continue in the background to finish, and return their results. \
+             Tools still running when the timeout expires are reported with their output so far."
+                .to_owned(),
+        ),
+        parameters: IndexMap::from([
+            ("handles".to_owned(), PartialToolParameterConfig {
+                kind: PartialOneOrManyTypes::One("array".to_owned()),
+                description: Some(
+                    "The handles to wait for. Defaults to all running handles.".to_owned(),
+                ),
+                items: Some(Box::new(PartialToolParameterConfig {
+                    kind: PartialOneOrManyTypes::One("string".to_owned()),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }),
+            ("timeout_secs".to_owned(), PartialToolParameterConfig {
+                kind: PartialOneOrManyTypes::One("integer".to_owned()),
+                description: Some("How many seconds to wait at most. Defaults to 300.".to_owned()),
+                ..Default::default()
+            }),
+        ]),
+        run: Some(RunMode::Unattended),
+        ..Default::default()
+    }
+}
+
+/// Returns the built-in `poll_handle` tool configuration.
+#[must_use]
+pub fn poll_handle() -> PartialToolConfig {
+    PartialToolConfig {
+        source: Some(ToolSource::Builtin { tool: None }),
+        enable: Some(PartialEnableConfig::OFF),
+        description: Some(
+            "Check on a tool that continues in the background, without waiting for it. Returns \
+             its output so far, or its result if it finished."
+                .to_owned(),
+        ),
+        parameters: IndexMap::from([("handle".to_owned(), handle_parameter())]),
+        run: Some(RunMode::Unattended),
+        ..Default::default()
+    }
+}
+
+/// Returns the built-in `cancel_handle` tool configuration.
+#[must_use]
+pub fn cancel_handle() -> PartialToolConfig {
+    PartialToolConfig {
+        source: Some(ToolSource::Builtin { tool: None }),
+        enable: Some(PartialEnableConfig::OFF),
+        description: Some("Stop a tool that continues in the background.".to_owned()),
+        parameters: IndexMap::from([("handle".to_owned(), handle_parameter())]),
+        run: Some(RunMode::Unattended),
+        ..Default::default()
+    }
+}
+
+fn handle_parameter() -> PartialToolParameterConfig {
+    PartialToolParameterConfig {
+        kind: PartialOneOrManyTypes::One("string".to_owned()),
+        required: Some(true),
+        description: Some("The handle returned by the tool.".to_owned()),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,262 @@
+//! Scheduling of parallel tool calls.
+//!
+//! The coordinator starts every tool call of a response at once. Before a call
+//! executes, it waits for a [`Slot`] in the [`ConcurrencyGate`], which holds it
+//! back while it would exceed a concurrency limit, or conflict with a call
+//! that is running or was requested before it.
+//!
+//! Calls that conflict run in the order the assistant requested them. A call
+//! that conflicts with nothing may overtake a waiting call, unless a shared
+//! limit is reached.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use jp_config::conversation::tool::ToolsConfig;
+use serde_json::{Map, Value};
+use tokio::sync::Notify;
+
+/// What a tool call needs from the gate before it can run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Claim {
+    /// The name of the tool.
+    tool: String,
+
+    /// The maximum number of calls of the tool that run at once.
+    tool_limit: Option<usize>,
+
+    /// The group of the tool, and its limit.
+    group: Option<(String, usize)>,
+
+    /// Whether the call runs on its own.
+    exclusive: bool,
+
+    /// The paths the call modifies.
+    paths: Vec<Utf8PathBuf>,
+}
+
+impl Claim {
+    /// Build the claim of a call to `tool` with the given arguments.
+    ///
+    /// Relative paths in the arguments are resolved against `root`.
+    pub(crate) fn new(
+        tool: &str,
+        arguments: &Map<String, Value>,
+        config: &ToolsConfig,
+        root: &Utf8Path,
+    ) -> Self {
+        let mut claim = Self {
+            tool: tool.to_owned(),
+            ..Self::default()
+        };
+
+        let Some(config) = config.get(tool) else {
+            return claim;
+        };
+
+        let Some(concurrency) = config.concurrency() else {
+            return claim;
+        };
+
+        claim.tool_limit = concurrency.limit.map(to_limit);
+        claim.exclusive = concurrency.is_exclusive();
+        claim.group = concurrency.group.as_ref().and_then(|group| {
+            config
+                .shared_concurrency()
+                .and_then(|shared| shared.group_limit(group))
+                .map(|limit| (group.clone(), to_limit(limit)))
+        });
+        claim.paths = concurrency
+            .mutates
+            .iter()
+            .filter_map(|name| arguments.get(name))
+            .flat_map(|value| match value {
+                Value::String(path) => vec![path.as_str()],
+                Value::Array(paths) => paths.iter().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            })
+            .map(|path| normalize(&root.join(path)))
+            .collect();
+
+        claim
+    }
+
+    /// Whether `self` may not run alongside `other`.
+    fn conflicts_with(&self, other: &Self) -> bool {
+        self.exclusive
+            || other.exclusive
+            || self.paths.iter().any(|path| other.paths.contains(path))
+    }
+}
+
+/// Holds tool calls back until they can run.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConcurrencyGate {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// The maximum number of calls that run at once.
+    limit: Option<usize>,
+    state: Mutex<State>,
+    notify: Notify,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next_id: u64,
+
+    /// Calls waiting to run, in request order.
+    waiting: BTreeMap<u64, Claim>,
+
+    /// Calls currently running.
+    running: HashMap<u64, Claim>,
+}
+
+impl ConcurrencyGate {
+    /// Create a gate that runs at most `limit` calls at once.
+    pub(crate) fn new(limit: Option<u32>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                limit: limit.map(to_limit),
+                ..Inner::default()
+            }),
+        }
+    }
+
+    /// Queue a call.
+    ///
+    /// The position of the call in the queue is fixed when it is queued, so
+    /// calls must be queued in the order they were requested.
+    pub(crate) fn queue(&self, claim: Claim) -> Slot {
+        let mut state = self.state();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.waiting.insert(id, claim);
+
+        Slot {
+            gate: self.clone(),
+            id,
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Move the call to the running set, if nothing holds it back.
+    fn try_admit(&self, id: u64) -> bool {
+        let mut state = self.state();
+        let Some(claim) = state.waiting.get(&id) else {
+            // Already admitted.
+            return state.running.contains_key(&id);
+        };
+
+        let ahead: Vec<&Claim> = state
+            .running
+            .values()
+            .chain(state.waiting.range(..id).map(|(_, claim)| claim))
+            .collect();
+
+        let blocked = self.inner.limit.is_some_and(|limit| ahead.len() >= limit)
+            || claim.tool_limit.is_some_and(|limit| {
+                ahead.iter().filter(|c| c.tool == claim.tool).count() >= limit
+            })
+            || claim.group.as_ref().is_some_and(|(group, limit)| {
+                ahead
+                    .iter()
+                    .filter(|c| c.group.as_ref().is_some_and(|(g, _)| g == group))
+                    .count()
+                    >= *limit
+            })
+            || ahead.iter().any(|other| claim.conflicts_with(other));
+
+        if blocked {
+            return false;
+        }
+
+        if let Some(claim) = state.waiting.remove(&id) {
+            state.running.insert(id, claim);
+        }
+
+        true
+    }
+
+    fn release(&self, id: u64) {
+        let mut state = self.state();
+        let removed = state.waiting.remove(&id).is_some() || state.running.remove(&id).is_some();
+        drop(state);
+
+        if removed {
+            self.inner.notify.notify_waiters();
+        }
+    }
+}
+
+/// The place of a tool call in the [`ConcurrencyGate`].
+///
+/// Dropping the slot frees it for the calls that wait on it.
+#[derive(Debug)]
+pub(crate) struct Slot {
+    gate: ConcurrencyGate,
+    id: u64,
+}
+
+impl Slot {
+    /// Wait until the call can run.
+    pub(crate) async fn acquire(&self) {
+        loop {
+            let notified = self.gate.inner.notify.notified();
+            tokio::pin!(notified);
+
+            // Register for notifications before checking, so a release
+            // between the check and the wait is not missed.
+            notified.as_mut().enable();
+            if self.gate.try_admit(self.id) {
+                return;
+            }
+
+            notified.await;
+        }
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.gate.release(self.id);
+    }
+}
+
+/// A limit of zero would never let a call run.
+fn to_limit(limit: u32) -> usize {
+    usize::try_from(limit).unwrap_or(usize::MAX).max(1)
+}
+
+/// Resolve `.` and `..` components without touching the filesystem.
+///
+/// The modified paths may not exist yet, so they can't be canonicalized.
+fn normalize(path: &Utf8Path) -> Utf8PathBuf {
+    let mut normalized = Utf8PathBuf::new();
+    for component in path.components() {
+        match component {
+            Utf8Component::CurDir => {}
+            Utf8Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+#[path = "concurrency_tests.rs"]
+mod tests;
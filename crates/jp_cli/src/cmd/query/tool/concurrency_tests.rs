@@ -0,0 +1,164 @@
+use std::time::Duration;
+
+use indexmap::IndexMap;
+use jp_config::{
+    AppConfig,
+    conversation::tool::{
+        PartialToolConfig, ToolSource,
+        concurrency::{PartialConcurrencyConfig, PartialToolsConcurrencyConfig},
+    },
+};
+use serde_json::json;
+
+use super::*;
+
+fn claim(tool: &str) -> Claim {
+    Claim {
+        tool: tool.to_owned(),
+        ..Claim::default()
+    }
+}
+
+fn writes(path: &str) -> Claim {
+    Claim {
+        paths: vec![path.into()],
+        ..claim("fs_modify_file")
+    }
+}
+
+#[test]
+fn calls_without_conflicts_run_in_parallel() {
+    let gate = ConcurrencyGate::default();
+    let a = gate.queue(claim("fs_read_file"));
+    let b = gate.queue(claim("fs_read_file"));
+
+    assert!(gate.try_admit(a.id));
+    assert!(gate.try_admit(b.id));
+}
+
+#[test]
+fn writes_to_the_same_path_run_in_order() {
+    let gate = ConcurrencyGate::default();
+    let first = gate.queue(writes("/w/src/lib.rs"));
+    let second = gate.queue(writes("/w/src/lib.rs"));
+    let other = gate.queue(writes("/w/src/main.rs"));
+
+    // The second write waits for the first, even before the first started.
+    assert!(!gate.try_admit(second.id));
+    assert!(gate.try_admit(other.id));
+    assert!(gate.try_admit(first.id));
+    assert!(!gate.try_admit(second.id));
+
+    drop(first);
+    assert!(gate.try_admit(second.id));
+}
+
+#[test]
+fn exclusive_call_runs_alone() {
+    let gate = ConcurrencyGate::default();
+    let read = gate.queue(claim("fs_read_file"));
+    let commit = gate.queue(Claim {
+        exclusive: true,
+        ..claim("git_commit")
+    });
+    let later = gate.queue(claim("fs_read_file"));
+
+    assert!(gate.try_admit(read.id));
+    assert!(!gate.try_admit(commit.id));
+    assert!(!gate.try_admit(later.id));
+
+    drop(read);
+    assert!(gate.try_admit(commit.id));
+    assert!(!gate.try_admit(later.id));
+
+    drop(commit);
+    assert!(gate.try_admit(later.id));
+}
+
+#[test]
+fn tool_and_group_limits_are_enforced() {
+    let gate = ConcurrencyGate::default();
+    let cargo = |tool: &str| Claim {
+        group: Some(("cargo".to_owned(), 1)),
+        ..claim(tool)
+    };
+
+    let test = gate.queue(cargo("cargo_test"));
+    let check = gate.queue(cargo("cargo_check"));
+    assert!(gate.try_admit(test.id));
+    assert!(!gate.try_admit(check.id));
+
+    let limited = |tool: &str| Claim {
+        tool_limit: Some(1),
+        ..claim(tool)
+    };
+    let a = gate.queue(limited("web_fetch"));
+    let b = gate.queue(limited("web_fetch"));
+    assert!(gate.try_admit(a.id));
+    assert!(!gate.try_admit(b.id));
+}
+
+#[test]
+fn global_limit_is_enforced() {
+    let gate = ConcurrencyGate::new(Some(1));
+    let a = gate.queue(claim("fs_read_file"));
+    let b = gate.queue(claim("fs_grep_files"));
+
+    assert!(gate.try_admit(a.id));
+    assert!(!gate.try_admit(b.id));
+}
+
+#[tokio::test]
+async fn acquire_waits_for_release() {
+    let gate = ConcurrencyGate::default();
+    let first = gate.queue(writes("/w/a"));
+    let second = gate.queue(writes("/w/a"));
+    first.acquire().await;
+
+    let waiting = tokio::spawn(async move {
+        second.acquire().await;
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(!waiting.is_finished());
+
+    drop(first);
+    tokio::time::timeout(Duration::from_secs(1), waiting)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[test]
+fn claim_resolves_mutated_paths() {
+    let mut partial = AppConfig::new_test().to_partial();
+    partial.conversation.tools.defaults.concurrency = Some(PartialToolsConcurrencyConfig {
+        limit: None,
+        groups: Some(IndexMap::from_iter([("cargo".to_owned(), 1)])),
+    });
+    partial.conversation.tools.tools =
+        IndexMap::from_iter([("fs_modify_file".into(), PartialToolConfig {
+            source: Some(ToolSource::Builtin { tool: None }),
+            concurrency: Some(PartialConcurrencyConfig {
+                group: Some("cargo".to_owned()),
+                mutates: Some(vec!["path".to_owned(), "paths".to_owned()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })]);
+    let config = AppConfig::from_partial_with_defaults(partial).unwrap();
+
+    let arguments = json!({ "path": "./src/../lib.rs", "paths": ["a.rs", "b.rs"] });
+    let claim = Claim::new(
+        "fs_modify_file",
+        arguments.as_object().unwrap(),
+        &config.conversation.tools,
+        Utf8Path::new("/w"),
+    );
+
+    assert_eq!(claim.paths, vec![
+        Utf8PathBuf::from("/w/lib.rs"),
+        Utf8PathBuf::from("/w/a.rs"),
+        Utf8PathBuf::from("/w/b.rs"),
+    ]);
+    assert_eq!(claim.group, Some(("cargo".to_owned(), 1)));
+}
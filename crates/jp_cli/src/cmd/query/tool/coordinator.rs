@@ -91,7 +91,7 @@ use jp_config::{
     interrupt::ToolInterruptConfig,
 };
 use jp_conversation::{
-    ConversationStream,
+    ConversationEvent, ConversationStream,
     event::{
         InquiryAnswerType, InquiryQuestion, InquiryRequest, InquiryResponse, InquirySource,
         SelectOption, ToolCallRequest, ToolCallResponse,
@@ -103,24 +103,27 @@ use jp_llm::tool::executor::{Executor, ExecutorResult, ExecutorSource, Permissio
 use jp_mcp::Client;
 use jp_printer::Printer;
 use jp_tool::{AnswerType, Question};
-use jp_workspace::ConversationMut;
+use jp_workspace::{ConversationMut, ScratchRecorder};
 use serde_json::{Map, Value};
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 use tracing::warn;
 
 use super::{
-    ToolRenderer,
+    ToolRenderer, UserTerminal,
+    concurrency::{Claim, ConcurrencyGate},
     inquiry::{self, InquiryBackend},
     prompter::{PermissionResult, ToolPrompter, permission_inquiry_id, tool_question_inquiry_id},
 };
 use crate::{
     cmd::query::{
+        events::EventSink,
         interrupt::signals::{ToolInterruptResult, handle_tool_interrupt},
         turn::{TurnCoordinator, state::TurnState},
     },
     render::tool::RenderOutcome,
     signals::SignalRouter,
+    usage::{ToolDecision, UsageRecorder},
 };
 
 #[derive(Debug)]
@@ -337,10 +340,29 @@ pub struct ToolCoordinator {
     /// Keyed by tool call ID.
     /// Drained by the turn loop to write into event metadata.
     rendered_arguments: HashMap<String, String>,
+    /// The `jsonl` event stream inquiries are written to.
+    events: EventSink,
+    /// The user's terminal, lent to tools that run in a pseudo-terminal.
+    terminal: Option<UserTerminal>,
+    /// Records the requests made to answer inquiries.
+    scratch: Option<ScratchRecorder>,
+    /// Holds tool calls back while they exceed a concurrency limit, or
+    /// conflict with another call.
+    concurrency: ConcurrencyGate,
+    /// Records the permission decisions for the usage log.
+    usage: UsageRecorder,
 }
 
 impl ToolCoordinator {
     pub fn new(tools_config: ToolsConfig, executor_source: Box<dyn ExecutorSource>) -> Self {
+        let concurrency = ConcurrencyGate::new(
+            tools_config
+                .defaults
+                .concurrency
+                .as_ref()
+                .and_then(|concurrency| concurrency.limit),
+        );
+
         Self {
             executors: Vec::new(),
             tool_states: HashMap::new(),
@@ -349,6 +371,11 @@ impl ToolCoordinator {
             executor_source,
             cancellation_token: CancellationToken::new(),
             rendered_arguments: HashMap::new(),
+            events: EventSink::default(),
+            terminal: None,
+            scratch: None,
+            concurrency,
+            usage: UsageRecorder::default(),
         }
     }
 
@@ -361,6 +388,60 @@ impl ToolCoordinator {
         self
     }
 
+    /// Set the `jsonl` event stream of the turn.
+    ///
+    /// Defaults to a disabled stream.
+    #[must_use]
+    pub(crate) fn with_events(mut self, events: EventSink) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Set the user's terminal, lent to tools that run in a pseudo-terminal.
+    ///
+    /// Input forwarding to those tools is paused while the user is prompted,
+    /// and no progress is shown while they use the terminal.
+    #[must_use]
+    pub(crate) fn with_terminal(mut self, terminal: Option<UserTerminal>) -> Self {
+        self.terminal = terminal;
+        self
+    }
+
+    /// Set the recorder for the LLM requests made to answer inquiries.
+    ///
+    /// Defaults to not recording them.
+    #[must_use]
+    pub(crate) fn with_scratch(mut self, scratch: Option<ScratchRecorder>) -> Self {
+        self.scratch = scratch;
+        self
+    }
+
+    /// Set the recorder for the permission decisions of tool calls.
+    ///
+    /// Defaults to a recorder that is never written to the usage log.
+    #[must_use]
+    pub(crate) fn with_usage(mut self, usage: UsageRecorder) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// Pause or resume forwarding input to tools running in a pseudo-terminal.
+    fn pause_terminal(&self, paused: bool) {
+        if let Some(terminal) = &self.terminal {
+            terminal.pause(paused);
+        }
+    }
+
+    /// The `jsonl` event stream of the turn.
+    pub(crate) fn events(&self) -> &EventSink {
+        &self.events
+    }
+
+    /// The recorder for the LLM requests made to answer inquiries.
+    pub(crate) fn scratch(&self) -> Option<&ScratchRecorder> {
+        self.scratch.as_ref()
+    }
+
     /// Drain accumulated rendered argument content.
     ///
     /// Returns `(tool_call_id, rendered_content)` pairs collected during the
@@ -487,14 +568,19 @@ impl ToolCoordinator {
         tool_renderer: &ToolRenderer,
     ) -> ToolCallDecision {
         // Step 1: decide.
+        let tool_name = executor.tool_name().to_owned();
         let decision = self.decide_permission(executor, is_tty, turn_state);
 
         // Step 2: handle prompt path. After this match, `executor` is
         // approved and `pre_rendered` is `Some(content)` if pre-rendering
         // already happened, `None` if a post-render is still needed.
         let (executor, pre_rendered) = match decision {
-            PermissionDecision::Approved(executor) => (executor, None),
+            PermissionDecision::Approved(executor) => {
+                self.usage.record_tool(tool_name, ToolDecision::Unattended);
+                (executor, None)
+            }
             PermissionDecision::Skipped(response) => {
+                self.usage.record_tool(tool_name, ToolDecision::Denied);
                 return ToolCallDecision::Skipped(response);
             }
             PermissionDecision::NeedsPrompt { executor, info } => {
@@ -527,8 +613,10 @@ impl ToolCoordinator {
                 let pre_edit_args = executor.arguments().clone();
 
                 let result = prompter.prompt_permission(&info);
+                let skipped = matches!(result, Ok(PermissionResult::Skip { .. }));
                 match self.apply_permission_result(result, &info, turn_state, executor) {
                     Ok(executor) => {
+                        self.usage.record_tool(tool_name, ToolDecision::Approved);
                         let pre = if executor.arguments() == &pre_edit_args {
                             pre
                         } else {
@@ -536,7 +624,12 @@ impl ToolCoordinator {
                         };
                         (executor, pre)
                     }
-                    Err(response) => return ToolCallDecision::Skipped(response),
+                    Err(response) => {
+                        if skipped {
+                            self.usage.record_tool(tool_name, ToolDecision::Denied);
+                        }
+                        return ToolCallDecision::Skipped(response);
+                    }
                 }
             }
         };
@@ -945,7 +1038,7 @@ impl ToolCoordinator {
 
             self.set_tool_state(&tool_id, ToolCallState::Running);
 
-            Self::spawn_tool_execution(
+            self.spawn_tool_execution(
                 index,
                 executor,
                 accumulated_answers,
@@ -1065,17 +1158,20 @@ impl ToolCoordinator {
                     Ok(answer) => {
                         if let Some(tool) = executing_tools.get_mut(&index) {
                             let id = inquiry::tool_call_inquiry_id(&tool.tool_id, &question_id);
+                            let response = InquiryResponse::new(id, answer.clone());
+                            self.events
+                                .committed(&ConversationEvent::now(response.clone()));
                             conv.update_events(|events| {
                                 events
                                     .current_turn_mut()
-                                    .add_inquiry_response(InquiryResponse::new(id, answer.clone()))
+                                    .add_inquiry_response(response)
                                     .build()
                                     .expect("Invalid ConversationStream state");
                             });
 
                             tool.accumulated_answers.insert(question_id, answer);
                             self.set_tool_state(&tool.tool_id, ToolCallState::Running);
-                            Self::spawn_tool_execution(
+                            self.spawn_tool_execution(
                                 index,
                                 tool.executor.clone(),
                                 tool.accumulated_answers.clone(),
@@ -1170,7 +1266,8 @@ impl ToolCoordinator {
                             tool_renderer.clear_progress();
                             progress_shown = false;
                         }
-                        match handle_tool_interrupt(
+                        self.pause_terminal(true);
+                        let interrupt_result = handle_tool_interrupt(
                             &cancellation_token,
                             turn_coordinator,
                             self.is_prompting(),
@@ -1179,7 +1276,10 @@ impl ToolCoordinator {
                             editor.clone(),
                             edit_mode,
                             &self.interrupt_config,
-                        ) {
+                        );
+                        self.pause_terminal(prompt_active);
+
+                        match interrupt_result {
                             ToolInterruptResult::Continue => {}
                             // A tool prompt is pending; let the next handler
                             // down the stack take the interrupt.
@@ -1211,13 +1311,19 @@ impl ToolCoordinator {
                     }
                 }
                 ExecutionEvent::ProgressTick { elapsed } => {
-                    if !prompt_active {
+                    // A tool running in a pseudo-terminal owns the screen.
+                    let terminal_active =
+                        self.terminal.as_ref().is_some_and(UserTerminal::is_active);
+                    if !prompt_active && !terminal_active {
                         tool_renderer.render_progress(elapsed);
                         progress_shown = true;
                     }
                 }
             }
 
+            if was_prompting != prompt_active {
+                self.pause_terminal(prompt_active);
+            }
             if !was_prompting && prompt_active && progress_shown {
                 tool_renderer.clear_progress();
                 progress_shown = false;
@@ -1230,6 +1336,7 @@ impl ToolCoordinator {
         // Deregister the tool interrupt handler; its forwarding task exits
         // when the notification channel closes.
         drop(interrupt_guard);
+        self.pause_terminal(false);
 
         if let Some(token) = progress_token {
             token.cancel();
@@ -1288,7 +1395,12 @@ impl ToolCoordinator {
         }
     }
 
+    /// Run the tool call once the concurrency gate admits it.
+    ///
+    /// The call is queued right away, so calls must be spawned in the order
+    /// they were requested.
     fn spawn_tool_execution(
+        &self,
         index: usize,
         executor: Arc<dyn Executor>,
         answers: IndexMap<String, Value>,
@@ -1297,8 +1409,23 @@ impl ToolCoordinator {
         token: CancellationToken,
         tx: mpsc::Sender<ExecutionEvent>,
     ) {
+        let claim = Claim::new(
+            executor.tool_name(),
+            executor.arguments(),
+            &self.tools_config,
+            &root,
+        );
+        let slot = self.concurrency.queue(claim);
+
         tokio::spawn(async move {
+            // A cancelled call still executes, to report its cancellation.
+            tokio::select! {
+                () = slot.acquire() => {}
+                () = token.cancelled() => {}
+            }
+
             let result = executor.execute(&answers, &client, &root, token).await;
+            drop(slot);
             let _err = tx.send(ExecutionEvent::ToolResult { index, result }).await;
         });
     }
@@ -1471,7 +1598,7 @@ impl ToolCoordinator {
                     .map(|r| r.answer.clone());
                 if let Some(answer) = persisted_answer {
                     tool.accumulated_answers.insert(question.id.clone(), answer);
-                    Self::spawn_tool_execution(
+                    self.spawn_tool_execution(
                         index,
                         tool.executor.clone(),
                         tool.accumulated_answers.clone(),
@@ -1485,7 +1612,7 @@ impl ToolCoordinator {
 
                 if let Some(answer) = self.static_answer(&tool_name, &question.id) {
                     tool.accumulated_answers.insert(question.id.clone(), answer);
-                    Self::spawn_tool_execution(
+                    self.spawn_tool_execution(
                         index,
                         tool.executor.clone(),
                         tool.accumulated_answers.clone(),
@@ -1528,14 +1655,17 @@ impl ToolCoordinator {
                     // snapshot.
                     let inquiry_id = inquiry::tool_call_inquiry_id(&tool_id, &question.id);
 
+                    let request = InquiryRequest::new(
+                        inquiry_id.clone(),
+                        InquirySource::tool(tool_name.clone()),
+                        tool_question_to_inquiry_question(&question),
+                    );
+                    self.events
+                        .committed(&ConversationEvent::now(request.clone()));
                     conv.update_events(|events| {
                         events
                             .current_turn_mut()
-                            .add_inquiry_request(InquiryRequest::new(
-                                inquiry_id.clone(),
-                                InquirySource::tool(tool_name.clone()),
-                                tool_question_to_inquiry_question(&question),
-                            ))
+                            .add_inquiry_request(request)
                             .build()
                             .expect("Invalid ConversationStream state");
                     });
@@ -1584,7 +1714,7 @@ impl ToolCoordinator {
             }
             tool.accumulated_answers.insert(question_id, answer);
             self.set_tool_state(&tool.tool_id, ToolCallState::Running);
-            Self::spawn_tool_execution(
+            self.spawn_tool_execution(
                 index,
                 tool.executor.clone(),
                 tool.accumulated_answers.clone(),
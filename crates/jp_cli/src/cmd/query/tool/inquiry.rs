@@ -36,6 +36,7 @@ use jp_llm::{
     tool::ToolDefinition,
 };
 use jp_tool::{AnswerType, Question};
+use jp_workspace::ScratchRecorder;
 use serde_json::{Map, Value, json};
 use tokio_util::sync::CancellationToken;
 use tracing::info;
@@ -173,6 +174,10 @@ pub struct LlmInquiryBackend {
     /// Included in the inquiry request (with `ToolChoice::None`) so that the
     /// Anthropic prompt cache prefix matches the normal turn requests.
     tools: Vec<ToolDefinition>,
+
+    /// Records each inquiry request and its response as a scratch
+    /// conversation of the conversation the tool runs in.
+    scratch: Option<ScratchRecorder>,
 }
 
 impl LlmInquiryBackend {
@@ -188,9 +193,17 @@ impl LlmInquiryBackend {
             overrides,
             attachments,
             tools,
+            scratch: None,
         }
     }
 
+    /// Record inquiries with the given [`ScratchRecorder`].
+    #[must_use]
+    pub fn with_scratch(mut self, scratch: Option<ScratchRecorder>) -> Self {
+        self.scratch = scratch;
+        self
+    }
+
     /// Look up the effective config for this tool/question pair.
     ///
     /// Returns the per-question override when one exists, otherwise the default
@@ -283,6 +296,11 @@ impl InquiryBackend for LlmInquiryBackend {
             events,
         };
 
+        let scratch = self
+            .scratch
+            .as_ref()
+            .map(|recorder| (recorder, thread.to_stream(&config.model.id)));
+
         let query = ChatQuery {
             thread,
             tools: self.tools.clone(),
@@ -328,6 +346,11 @@ impl InquiryBackend for LlmInquiryBackend {
             }
         }
 
+        if let Some((recorder, mut stream)) = scratch {
+            stream.extend(flushed.iter().cloned());
+            recorder.record(format!("Inquiry: {tool_name}"), stream);
+        }
+
         let mut structured_data = flushed
             .into_iter()
             .filter_map(ConversationEvent::into_chat_response)
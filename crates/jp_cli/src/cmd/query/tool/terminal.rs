@@ -0,0 +1,274 @@
+//! The user's terminal, lent to tools that run in a pseudo-terminal.
+//!
+//! A tool configured with `pty = true` mirrors its output to the terminal while
+//! it runs, and the user's keystrokes are forwarded to it.
+//! Forwarding puts the terminal in raw mode, so keys like Ctrl-C reach the tool
+//! instead of JP.
+//!
+//! Only one tool reads input at a time; tools that run in parallel with it
+//! still show their output.
+//! While JP prompts the user (e.g. to confirm another tool call), forwarding is
+//! [paused](UserTerminal::pause) so the prompt gets the keystrokes.
+
+use std::{
+    fmt::Write as _,
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, Receiver},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal,
+};
+use jp_llm::tool::pty::{TerminalInput, ToolTerminal};
+use jp_printer::Printer;
+use tracing::warn;
+
+/// How long the input thread waits for a key before checking whether it should
+/// stop.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// The user's terminal.
+///
+/// Cloning is cheap; clones share the same state.
+#[derive(Debug, Clone)]
+pub(crate) struct UserTerminal {
+    printer: Arc<Printer>,
+    state: Arc<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// The number of tools using the terminal.
+    sessions: AtomicUsize,
+
+    /// Whether a tool is reading the user's input.
+    reading: AtomicBool,
+
+    /// Whether input forwarding is paused, because JP is prompting the user.
+    paused: AtomicBool,
+
+    /// Output that does not form complete UTF-8 characters yet.
+    pending: Mutex<Vec<u8>>,
+}
+
+impl UserTerminal {
+    pub(crate) fn new(printer: Arc<Printer>) -> Self {
+        Self {
+            printer,
+            state: Arc::default(),
+        }
+    }
+
+    /// Whether any tool is using the terminal.
+    pub(crate) fn is_active(&self) -> bool {
+        self.state.sessions.load(Ordering::SeqCst) > 0
+    }
+
+    /// Pause or resume forwarding input to tools.
+    ///
+    /// Pausing leaves raw mode, so a prompt starts from a regular terminal.
+    pub(crate) fn pause(&self, paused: bool) {
+        if self.state.paused.swap(paused, Ordering::SeqCst) == paused {
+            return;
+        }
+
+        if self.state.reading.load(Ordering::SeqCst) {
+            set_raw_mode(!paused);
+        }
+    }
+}
+
+impl ToolTerminal for UserTerminal {
+    fn size(&self) -> (u16, u16) {
+        terminal::size().map_or((24, 80), |(cols, rows)| (rows, cols))
+    }
+
+    fn output(&self, bytes: &[u8]) {
+        let mut pending = self
+            .state
+            .pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        pending.extend_from_slice(bytes);
+
+        // Keep an incomplete character at the end for the next chunk.
+        let complete = match str::from_utf8(&pending) {
+            Ok(text) => text.len(),
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(_) => pending.len(),
+        };
+
+        let chunk = pending.drain(..complete).collect::<Vec<_>>();
+        let text = String::from_utf8_lossy(&chunk);
+        if !text.is_empty() {
+            let _ = self.printer.prompt_writer().write_str(&text);
+        }
+    }
+
+    fn input(&self) -> Box<dyn TerminalInput> {
+        self.state.sessions.fetch_add(1, Ordering::SeqCst);
+
+        let forwarder = (!self.state.reading.swap(true, Ordering::SeqCst))
+            .then(|| Forwarder::start(self.state.clone()));
+
+        Box::new(Input {
+            state: self.state.clone(),
+            forwarder,
+        })
+    }
+}
+
+/// A tool's handle on the user's input.
+struct Input {
+    state: Arc<State>,
+
+    /// The input thread, if this tool reads the user's input.
+    forwarder: Option<Forwarder>,
+}
+
+impl TerminalInput for Input {
+    fn try_read(&mut self) -> Option<Vec<u8>> {
+        self.forwarder.as_ref()?.keys.try_recv().ok()
+    }
+}
+
+impl Drop for Input {
+    fn drop(&mut self) {
+        if let Some(forwarder) = self.forwarder.take() {
+            forwarder.stop();
+            if !self.state.paused.load(Ordering::SeqCst) {
+                set_raw_mode(false);
+            }
+            self.state.reading.store(false, Ordering::SeqCst);
+        }
+
+        self.state.sessions.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A thread reading keys from the terminal.
+struct Forwarder {
+    stop: Arc<AtomicBool>,
+    keys: Receiver<Vec<u8>>,
+    thread: JoinHandle<()>,
+}
+
+impl Forwarder {
+    fn start(state: Arc<State>) -> Self {
+        if !state.paused.load(Ordering::SeqCst) {
+            set_raw_mode(true);
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let (tx, keys) = mpsc::channel();
+
+        let thread = thread::spawn({
+            let stop = stop.clone();
+            move || {
+                while !stop.load(Ordering::SeqCst) {
+                    // Leave the keys to the prompt while paused.
+                    if state.paused.load(Ordering::SeqCst) {
+                        thread::sleep(POLL_INTERVAL);
+                        continue;
+                    }
+
+                    match event::poll(POLL_INTERVAL) {
+                        Ok(true) if !state.paused.load(Ordering::SeqCst) => {}
+                        Ok(_) => continue,
+                        Err(_) => break,
+                    }
+
+                    let Ok(event) = event::read() else {
+                        break;
+                    };
+
+                    if let Some(bytes) = encode(&event)
+                        && tx.send(bytes).is_err()
+                    {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self { stop, keys, thread }
+    }
+
+    fn stop(self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if self.thread.join().is_err() {
+            warn!("Terminal input thread panicked.");
+        }
+    }
+}
+
+fn set_raw_mode(enabled: bool) {
+    let result = if enabled {
+        terminal::enable_raw_mode()
+    } else {
+        terminal::disable_raw_mode()
+    };
+
+    if let Err(error) = result {
+        warn!(%error, enabled, "Failed to set terminal raw mode.");
+    }
+}
+
+/// Encode a terminal event as the bytes a terminal sends for it.
+fn encode(event: &Event) -> Option<Vec<u8>> {
+    let Event::Key(KeyEvent {
+        code,
+        modifiers,
+        kind: KeyEventKind::Press | KeyEventKind::Repeat,
+        ..
+    }) = event
+    else {
+        return None;
+    };
+
+    let mut bytes = match code {
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+            match u8::try_from(c.to_ascii_lowercase()).ok()? {
+                byte @ b'a'..=b'z' => vec![byte & 0x1f],
+                b' ' | b'@' => vec![0],
+                b'[' => vec![0x1b],
+                b'\\' => vec![0x1c],
+                b']' => vec![0x1d],
+                _ => return None,
+            }
+        }
+        KeyCode::Char(c) => c.to_string().into_bytes(),
+        KeyCode::Enter => vec![b'\r'],
+        KeyCode::Tab => vec![b'\t'],
+        KeyCode::BackTab => b"\x1b[Z".to_vec(),
+        KeyCode::Backspace => vec![0x7f],
+        KeyCode::Esc => vec![0x1b],
+        KeyCode::Up => b"\x1b[A".to_vec(),
+        KeyCode::Down => b"\x1b[B".to_vec(),
+        KeyCode::Right => b"\x1b[C".to_vec(),
+        KeyCode::Left => b"\x1b[D".to_vec(),
+        KeyCode::Home => b"\x1b[H".to_vec(),
+        KeyCode::End => b"\x1b[F".to_vec(),
+        KeyCode::Insert => b"\x1b[2~".to_vec(),
+        KeyCode::Delete => b"\x1b[3~".to_vec(),
+        KeyCode::PageUp => b"\x1b[5~".to_vec(),
+        KeyCode::PageDown => b"\x1b[6~".to_vec(),
+        _ => return None,
+    };
+
+    if modifiers.contains(KeyModifiers::ALT) {
+        bytes.insert(0, 0x1b);
+    }
+
+    Some(bytes)
+}
+
+#[cfg(test)]
+#[path = "terminal_tests.rs"]
+mod tests;
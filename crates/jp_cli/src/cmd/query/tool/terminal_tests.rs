@@ -0,0 +1,69 @@
+use crossterm::event::KeyEventState;
+use jp_printer::OutputFormat;
+use pretty_assertions::assert_eq;
+
+use super::*;
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+    Event::Key(KeyEvent {
+        code,
+        modifiers,
+        kind: KeyEventKind::Press,
+        state: KeyEventState::NONE,
+    })
+}
+
+#[test]
+fn encode_keys() {
+    let cases: &[(Event, Option<&[u8]>)] = &[
+        (key(KeyCode::Char('y'), KeyModifiers::NONE), Some(b"y")),
+        (
+            key(KeyCode::Char('é'), KeyModifiers::NONE),
+            Some("é".as_bytes()),
+        ),
+        (
+            key(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Some(b"\x03"),
+        ),
+        (
+            key(KeyCode::Char('D'), KeyModifiers::CONTROL),
+            Some(b"\x04"),
+        ),
+        (key(KeyCode::Char('b'), KeyModifiers::ALT), Some(b"\x1bb")),
+        (key(KeyCode::Enter, KeyModifiers::NONE), Some(b"\r")),
+        (key(KeyCode::Backspace, KeyModifiers::NONE), Some(b"\x7f")),
+        (key(KeyCode::Up, KeyModifiers::NONE), Some(b"\x1b[A")),
+        (key(KeyCode::F(1), KeyModifiers::NONE), None),
+        (Event::FocusGained, None),
+    ];
+
+    for (event, expected) in cases {
+        assert_eq!(encode(event).as_deref(), *expected, "event: {event:?}");
+    }
+}
+
+#[test]
+fn encode_ignores_key_release() {
+    let event = Event::Key(KeyEvent {
+        code: KeyCode::Char('y'),
+        modifiers: KeyModifiers::NONE,
+        kind: KeyEventKind::Release,
+        state: KeyEventState::NONE,
+    });
+
+    assert_eq!(encode(&event), None);
+}
+
+#[test]
+fn output_keeps_split_characters_together() {
+    let (printer, out, _) = Printer::memory(OutputFormat::TextPretty);
+    let printer = Arc::new(printer);
+    let terminal = UserTerminal::new(printer.clone());
+
+    let bytes = "héllo".as_bytes();
+    terminal.output(&bytes[..2]);
+    terminal.output(&bytes[2..]);
+
+    printer.flush();
+    assert_eq!(*out.lock(), "héllo");
+}
@@ -3,7 +3,7 @@ use std::sync::{Arc, atomic::AtomicBool};
 use jp_config::style::StyleConfig;
 use jp_conversation::{
     ConversationEvent, ConversationStream,
-    event::{ChatRequest, ChatResponse, ToolCallRequest, ToolCallResponse},
+    event::{ChatRequest, ChatResponse, RESPONSE_FINISHED_KEY, ToolCallRequest, ToolCallResponse},
 };
 use jp_llm::{
     event::{Event, EventPart, FinishReason},
@@ -12,7 +12,7 @@ use jp_llm::{
 use jp_md::format::DefaultBackground;
 use jp_printer::Printer;
 
-use crate::cmd::query::{interrupt::InterruptAction, stream::TurnView};
+use crate::cmd::query::{events::EventSink, interrupt::InterruptAction, stream::TurnView};
 
 /// Phase of the turn state machine.
 ///
@@ -158,6 +158,9 @@ pub struct TurnCoordinator {
     /// When set, emit each completed event as NDJSON.
     json_emitter: Option<JsonEmitter>,
 
+    /// The `jsonl` event stream of the turn.
+    events: EventSink,
+
     /// Display name to stamp onto interrupt-reply [`ChatRequest`]s for
     /// transcript attribution.
     /// `None` means the request stays unattributed.
@@ -181,8 +184,11 @@ impl TurnCoordinator {
         model_id: Option<String>,
     ) -> Self {
         // In JSON mode, the renderer is unused; give it a sink so it doesn't
-        // accidentally write anything.
-        let (json_emitter, printer) = if printer.format().is_json() {
+        // accidentally write anything. The `jsonl` format writes its own
+        // event stream instead of the completed events.
+        let (json_emitter, printer) = if printer.format().is_jsonl() {
+            (None, Printer::sink().into())
+        } else if printer.format().is_json() {
             (Some(JsonEmitter { printer }), Printer::sink().into())
         } else {
             (None, printer.clone())
@@ -195,11 +201,17 @@ impl TurnCoordinator {
             event_builder: EventBuilder::new(),
             view,
             json_emitter,
+            events: EventSink::default(),
             author,
             printer,
         }
     }
 
+    /// Write the turn's events to the `jsonl` event stream.
+    pub(crate) fn set_events(&mut self, events: EventSink) {
+        self.events = events;
+    }
+
     /// Start a new turn, emitting [`TurnStart`] and the user's [`ChatRequest`]
     /// into the stream in the correct order.
     ///
@@ -221,6 +233,19 @@ impl TurnCoordinator {
         self.state = TurnPhase::Streaming;
     }
 
+    /// Re-enter a turn that was interrupted before it completed.
+    ///
+    /// The turn's events are already in the stream, so nothing is emitted; the
+    /// coordinator picks the turn up in `phase`.
+    ///
+    /// See [`IncompleteTurn`].
+    ///
+    /// [`IncompleteTurn`]: crate::cmd::query::resume::IncompleteTurn
+    pub fn resume_turn(&mut self, phase: TurnPhase) {
+        self.view.begin_turn();
+        self.state = phase;
+    }
+
     /// Process one event from a Provider stream.
     ///
     /// [`Event::Flush`] commits an indexed response to the Conversation but
@@ -281,6 +306,7 @@ impl TurnCoordinator {
                     }
                 }
 
+                self.events.part(index, &part);
                 self.event_builder.handle_part(index, part, metadata);
                 HandleEventOutcome::new(Action::Continue)
             }
@@ -313,6 +339,7 @@ impl TurnCoordinator {
                 // Capture tool-call buffers about to be discarded (e.g. a tool
                 // call truncated by max_tokens) so the notice can name them.
                 let dropped_tools = self.event_builder.incomplete_tool_calls();
+                self.events.finished(&reason);
 
                 if matches!(reason, FinishReason::Refused { .. }) {
                     // `FinishReason::Refused` contract: any partial output
@@ -325,6 +352,18 @@ impl TurnCoordinator {
                     for event in self.event_builder.drain() {
                         self.push_event(stream, event);
                     }
+
+                    // Mark the end of the response, so resuming the turn can
+                    // tell a complete one from one cut off mid-stream, or by
+                    // `max_tokens`.
+                    if let Some(last) = stream.last_mut()
+                        && last.event.is_chat_response()
+                    {
+                        let complete =
+                            matches!(reason, FinishReason::Completed | FinishReason::Other(_));
+                        last.event
+                            .add_metadata_field(RESPONSE_FINISHED_KEY, complete);
+                    }
                 }
 
                 self.view.flush();
@@ -527,7 +566,10 @@ impl TurnCoordinator {
                 self.state = TurnPhase::Complete;
             }
 
-            InterruptAction::Abort => self.state = TurnPhase::Aborted,
+            InterruptAction::Abort => {
+                self.events.aborted();
+                self.state = TurnPhase::Aborted;
+            }
 
             InterruptAction::Continue => {
                 for response in self.peek_partial_events() {
@@ -615,11 +657,13 @@ impl TurnCoordinator {
             .expect("Invalid ConversationStream state");
     }
 
-    /// Emit a conversation event as NDJSON if in JSON mode.
+    /// Emit a conversation event as NDJSON if in JSON mode, and to the `jsonl`
+    /// event stream.
     fn emit_json(&self, event: &ConversationEvent) {
         if let Some(emitter) = &self.json_emitter {
             emitter.emit(event);
         }
+        self.events.committed(event);
     }
 }
 
@@ -62,6 +62,38 @@ fn test_transitions_to_executing_on_tool_call() {
     assert_eq!(tool_calls[0].id, "call_1");
 }
 
+/// Only a response the provider finished is marked as such, so resuming the turn
+/// can tell a complete answer from one cut off mid-stream.
+#[test]
+fn finished_response_is_marked_complete() {
+    let mut stream = ConversationStream::new_test();
+    let (printer, _, _) = Printer::memory(OutputFormat::Text);
+    let mut coordinator = TurnCoordinator::new(
+        Arc::new(printer),
+        AppConfig::new_test().style,
+        None,
+        None,
+        None,
+    );
+
+    coordinator.start_turn(&mut stream, ChatRequest::from("hello"));
+    coordinator.handle_event(&mut stream, Event::message(0, "Hello"));
+    coordinator.handle_event(&mut stream, Event::flush(0));
+
+    let finished = |stream: &ConversationStream| {
+        stream
+            .last()
+            .is_some_and(|e| e.event.metadata.contains_key(RESPONSE_FINISHED_KEY))
+    };
+    assert!(
+        !finished(&stream),
+        "a flushed item is not a finished response"
+    );
+
+    coordinator.handle_event(&mut stream, Event::Finished(FinishReason::Completed));
+    assert!(finished(&stream));
+}
+
 /// Reasoning items split across several provider items form one region, so text
 /// broken mid-word across the split renders as a single word.
 ///
@@ -0,0 +1,292 @@
+//! Editing the configuration of a single turn (`jp query --edit-config`).
+//!
+//! The editor opens with the resolved configuration as TOML.
+//! Commonly changed settings (the model, reasoning, tool choice, and whether
+//! and how each tool runs) are left as-is, all other settings are commented
+//! out.
+//! The changed values form a configuration delta that applies to this turn
+//! only: it is recorded as a turn-scoped [`ConfigDelta`] in the conversation
+//! stream, which the next turn reverts.
+//!
+//! [`ConfigDelta`]: jp_conversation::stream::ConfigDelta
+
+use jp_config::{AppConfig, PartialAppConfig, PartialConfig as _, fs::load_partial, util::build};
+use jp_editor::{EditOutcome, EditorBackend};
+
+use crate::error::{Error, Result};
+
+const HEADER: &str = "\
+# Configuration for this query only.
+#
+# Change the values below to use them for this turn, later turns use the
+# conversation's configuration again.
+# Uncomment any other setting (and its table header) to change it as well.
+";
+
+/// The settings that are not commented out, as dotted key prefixes.
+///
+/// `*` matches any single key, i.e. any tool.
+const COMMON: &[&[&str]] = &[
+    &["assistant", "model", "id"],
+    &["assistant", "model", "parameters", "reasoning"],
+    &["assistant", "tool_choice"],
+    &["conversation", "tools", "*", "enable"],
+    &["conversation", "tools", "*", "run"],
+];
+
+/// Open the editor with the configuration of `config`, and return the changed
+/// values.
+///
+/// An invalid configuration reopens the editor with the error at the top.
+/// Returns `None` if the editor is cancelled.
+pub(super) fn edit(
+    config: &AppConfig,
+    editor: &dyn EditorBackend,
+) -> Result<Option<PartialAppConfig>> {
+    let mut content = template(config);
+
+    loop {
+        let (outcome, edited) = editor
+            .edit_text(&content)
+            .map_err(|error| Error::Editor(error.to_string()))?;
+
+        if outcome == EditOutcome::Cancelled {
+            return Ok(None);
+        }
+
+        match parse(config, &edited) {
+            Ok(delta) => return Ok(Some(delta)),
+            Err(error) => {
+                let edited = strip_error(&edited);
+                content = format!("{}{edited}", error_comment(&error));
+            }
+        }
+    }
+}
+
+/// Merge the turn configuration `delta` into `config`.
+pub(super) fn apply(config: &AppConfig, delta: PartialAppConfig) -> Result<AppConfig> {
+    let partial = load_partial(config.to_partial(), delta)?;
+    build(partial).map_err(Into::into)
+}
+
+/// Parse the edited document into the values that differ from `config`.
+fn parse(config: &AppConfig, content: &str) -> std::result::Result<PartialAppConfig, String> {
+    let mut edited =
+        toml::from_str::<PartialAppConfig>(content).map_err(|error| error.to_string())?;
+
+    // Keep the invariant that stream configs only contain resolved model IDs.
+    edited.resolve_model_aliases(&config.providers.llm.aliases);
+
+    let delta = config.to_partial().delta(edited);
+    if !delta.is_empty() {
+        apply(config, delta.clone()).map_err(|error| error.to_string())?;
+    }
+
+    Ok(delta)
+}
+
+/// The lines [`error_comment`] prepends start with this marker.
+const ERROR_MARKER: &str = "# ERROR: ";
+
+fn error_comment(error: &str) -> String {
+    let mut comment = String::new();
+    for line in error.lines() {
+        comment.push_str(ERROR_MARKER);
+        comment.push_str(line);
+        comment.push('\n');
+    }
+
+    comment.push('\n');
+    comment
+}
+
+/// Remove the error comment of a previous attempt.
+fn strip_error(content: &str) -> &str {
+    let mut rest = content;
+    while let Some(line_end) = rest.find('\n')
+        && rest.starts_with(ERROR_MARKER)
+    {
+        rest = &rest[line_end + 1..];
+    }
+
+    rest.strip_prefix('\n').unwrap_or(rest)
+}
+
+/// Render `config` as a TOML document, with all but the [`COMMON`] settings
+/// commented out.
+pub(super) fn template(config: &AppConfig) -> String {
+    let toml = toml::to_string_pretty(&config.to_partial()).unwrap_or_default();
+
+    let mut out = String::from(HEADER);
+    for section in sections(&toml) {
+        out.push('\n');
+        section.render(&mut out);
+    }
+
+    out
+}
+
+/// A table of a TOML document: its header, and the lines that follow it.
+#[derive(Debug, Default)]
+struct Section<'a> {
+    /// The header line, `None` for the keys before the first table.
+    header: Option<&'a str>,
+
+    /// The dotted path of the table.
+    path: Vec<String>,
+
+    /// Whether the table is an element of an array of tables.
+    array: bool,
+
+    /// The lines of the section, and whether each is a common setting.
+    lines: Vec<(&'a str, bool)>,
+}
+
+impl Section<'_> {
+    fn render(&self, out: &mut String) {
+        // A table without common settings is commented out as a whole, so it
+        // never ends up as an empty table in the edited configuration.
+        let keep_header = !self.array && self.lines.iter().any(|(_, keep)| *keep);
+
+        if let Some(header) = self.header {
+            push_line(out, header, keep_header);
+        }
+
+        for (line, keep) in &self.lines {
+            push_line(out, line, *keep);
+        }
+    }
+}
+
+fn push_line(out: &mut String, line: &str, keep: bool) {
+    if !keep && !line.trim().is_empty() {
+        out.push_str("# ");
+    }
+
+    out.push_str(line);
+    out.push('\n');
+}
+
+/// Split a TOML document, as serialized by [`toml::to_string_pretty`], into
+/// its tables.
+fn sections(toml: &str) -> Vec<Section<'_>> {
+    let mut sections = vec![Section::default()];
+
+    // Whether the current line is inside a multi-line string.
+    let mut in_string = false;
+    // Whether the current value is a common setting.
+    let mut keep = false;
+
+    for line in toml.lines() {
+        let trimmed = line.trim_start();
+
+        if !in_string && let Some(header) = header(trimmed) {
+            let (path, array) = header;
+            sections.push(Section {
+                header: Some(line),
+                path,
+                array,
+                lines: vec![],
+            });
+            keep = false;
+            continue;
+        }
+
+        let section = sections.last_mut().expect("at least one section");
+        if !in_string && let Some(key) = key(trimmed) {
+            let mut path = section.path.clone();
+            path.extend(key);
+            keep = !section.array && is_common(&path);
+        }
+
+        if line.matches("\"\"\"").count() % 2 == 1 || line.matches("'''").count() % 2 == 1 {
+            in_string = !in_string;
+        }
+
+        section.lines.push((line, keep && !trimmed.is_empty()));
+    }
+
+    sections.retain(|section| section.header.is_some() || !section.lines.is_empty());
+    sections
+}
+
+/// Parse a table header line into its path, and whether it is an array of
+/// tables.
+fn header(line: &str) -> Option<(Vec<String>, bool)> {
+    if let Some(inner) = line.strip_prefix("[[") {
+        let (inner, _) = inner.split_once("]]")?;
+        return Some((split_key(inner)?, true));
+    }
+
+    let inner = line.strip_prefix('[')?;
+    let (inner, _) = inner.rsplit_once(']')?;
+    Some((split_key(inner)?, false))
+}
+
+/// Parse the key of a `key = value` line.
+fn key(line: &str) -> Option<Vec<String>> {
+    let mut depth = None;
+    for (index, c) in line.char_indices() {
+        match (c, depth) {
+            ('"' | '\'', None) => depth = Some(c),
+            (c, Some(quote)) if c == quote => depth = None,
+            ('=', None) => return split_key(&line[..index]),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Split a dotted TOML key into its (unquoted) parts.
+fn split_key(key: &str) -> Option<Vec<String>> {
+    let mut parts = vec![];
+    let mut rest = key.trim();
+
+    loop {
+        let (part, remainder) = if let Some(quoted) = rest.strip_prefix('"') {
+            let (part, remainder) = quoted.split_once('"')?;
+            (part.replace("\\\"", "\""), remainder)
+        } else if let Some(quoted) = rest.strip_prefix('\'') {
+            let (part, remainder) = quoted.split_once('\'')?;
+            (part.to_owned(), remainder)
+        } else {
+            let end = rest.find('.').unwrap_or(rest.len());
+            let part = rest[..end].trim();
+            if part.is_empty()
+                || !part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return None;
+            }
+
+            (part.to_owned(), &rest[end..])
+        };
+
+        parts.push(part);
+
+        let remainder = remainder.trim_start();
+        if remainder.is_empty() {
+            return Some(parts);
+        }
+
+        rest = remainder.strip_prefix('.')?.trim_start();
+    }
+}
+
+/// Whether the setting at `path` is one of the [`COMMON`] settings.
+fn is_common(path: &[String]) -> bool {
+    COMMON.iter().any(|common| {
+        path.len() >= common.len()
+            && common
+                .iter()
+                .zip(path)
+                .all(|(common, part)| *common == "*" || common == part)
+    })
+}
+
+#[cfg(test)]
+#[path = "turn_config_tests.rs"]
+mod tests;
@@ -0,0 +1,118 @@
+use jp_config::assistant::tool_choice::ToolChoice;
+use jp_editor::MockEditorBackend;
+use pretty_assertions::assert_eq;
+
+use super::*;
+
+fn parts(parts: &[&str]) -> Vec<String> {
+    parts.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn test_split_key() {
+    assert_eq!(
+        split_key("assistant.model"),
+        Some(parts(&["assistant", "model"]))
+    );
+    assert_eq!(
+        split_key(r#" conversation . tools."fs.read" "#),
+        Some(parts(&["conversation", "tools", "fs.read"]))
+    );
+    assert_eq!(split_key("tools.'a b'"), Some(parts(&["tools", "a b"])));
+    assert_eq!(split_key("assistant..model"), None);
+    assert_eq!(split_key("a b"), None);
+}
+
+#[test]
+fn test_is_common() {
+    assert!(is_common(&parts(&["assistant", "model", "id"])));
+    assert!(is_common(&parts(&["assistant", "model", "id", "provider"])));
+    assert!(is_common(&parts(&[
+        "conversation",
+        "tools",
+        "fs_read_file",
+        "run"
+    ])));
+    assert!(!is_common(&parts(&[
+        "conversation",
+        "tools",
+        "fs_read_file",
+        "command"
+    ])));
+    assert!(!is_common(&parts(&["assistant", "name"])));
+}
+
+#[test]
+fn test_sections_comment_uncommon_settings() {
+    let toml = indoc::indoc! {r#"
+        [assistant]
+        name = "jp"
+        tool_choice = "auto"
+
+        [assistant.system_prompt_sections]
+        content = """
+        tool_choice = "none"
+        """
+
+        [conversation.tools.fs_read_file]
+        enable = true
+        command = "fs"
+
+        [[providers.mcp]]
+        run = "ask"
+    "#};
+
+    let mut out = String::new();
+    for section in sections(toml) {
+        section.render(&mut out);
+    }
+
+    assert_eq!(out, indoc::indoc! {r#"
+        [assistant]
+        # name = "jp"
+        tool_choice = "auto"
+
+        # [assistant.system_prompt_sections]
+        # content = """
+        # tool_choice = "none"
+        # """
+
+        [conversation.tools.fs_read_file]
+        enable = true
+        # command = "fs"
+
+        # [[providers.mcp]]
+        # run = "ask"
+    "#});
+}
+
+#[test]
+fn test_edit_unchanged_template_is_empty() {
+    let config = AppConfig::new_test();
+    let editor = MockEditorBackend::always(template(&config));
+
+    let delta = edit(&config, &editor).unwrap().unwrap();
+    assert!(delta.is_empty());
+}
+
+#[test]
+fn test_edit_retries_invalid_config() {
+    let config = AppConfig::new_test();
+    let editor = MockEditorBackend::with_responses([
+        "[assistant",
+        "# ERROR: previous attempt\n\n[assistant]\ntool_choice = \"none\"\n",
+    ]);
+
+    let delta = edit(&config, &editor).unwrap().unwrap();
+    assert_eq!(delta.assistant.tool_choice, Some(ToolChoice::None));
+
+    let config = apply(&config, delta).unwrap();
+    assert_eq!(config.assistant.tool_choice, ToolChoice::None);
+}
+
+#[test]
+fn test_strip_error() {
+    let content = format!("{}[assistant]\n", error_comment("invalid\nsecond line"));
+    assert_eq!(strip_error(&content), "[assistant]\n");
+    assert_eq!(strip_error("[assistant]\n"), "[assistant]\n");
+}
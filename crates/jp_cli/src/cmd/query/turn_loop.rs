@@ -18,12 +18,13 @@ use futures::{
 use indexmap::IndexMap;
 use jp_attachment::Attachment;
 use jp_config::{
-    AppConfig, PartialConfig, assistant::tool_choice::ToolChoice,
-    conversation::tool::QuestionTarget, model::id::ProviderId, style::streaming::StreamingConfig,
+    AppConfig, PartialAppConfig, PartialConfig, assistant::tool_choice::ToolChoice,
+    conversation::tool::QuestionTarget, env::Env, style::streaming::StreamingConfig,
 };
 use jp_conversation::{
     ConversationStream,
-    event::{ChatRequest, ToolCallRequest, ToolCallResponse},
+    event::{ChatRequest, TURN_CHECKPOINT_KEY, ToolCallRequest, ToolCallResponse},
+    stream::ConfigDelta,
 };
 use jp_inquire::prompt::PromptBackend;
 use jp_llm::{
@@ -32,9 +33,9 @@ use jp_llm::{
     event::{Event, EventPart, FinishReason, ToolCallPart},
     model::ModelDetails,
     output_limit_bytes,
-    provider::get_provider,
+    provider::ProviderCache,
     query::ChatQuery,
-    tool::{InvocationContext, ToolDefinition, executor::Executor},
+    tool::{BackgroundTasks, InvocationContext, ToolDefinition, executor::Executor},
     with_idle_timeout, with_output_limit,
 };
 use jp_printer::{ErrChannel, Printer};
@@ -49,6 +50,7 @@ use super::{
         LoopAction, StreamingInterruptResult, handle_llm_event, handle_streaming_interrupt,
         reply_edit_mode,
     },
+    resume::IncompleteTurn,
     stream::{
         ResponseBoundary, StreamErrorOutcome, StreamRetryState, commit_partial_response,
         handle_stream_error,
@@ -159,6 +161,38 @@ fn event_keeps_waiting_indicator(event: &StreamingLoopEvent) -> bool {
     }
 }
 
+/// How [`run_turn_loop`] enters its turn.
+pub(super) enum TurnEntry {
+    /// Start a new turn with the user's request.
+    Start {
+        request: ChatRequest,
+
+        /// Stream edits to commit together with the new request.
+        pending_trim: PendingStreamTrim,
+
+        /// The configuration changes of this turn only (`--edit-config`).
+        turn_config: PartialAppConfig,
+
+        /// The ID of the turn's file checkpoint, recorded on its `TurnStart`.
+        checkpoint: Option<String>,
+    },
+
+    /// Continue the interrupted last turn, whose events are already in the
+    /// stream.
+    Resume(IncompleteTurn),
+}
+
+impl From<ChatRequest> for TurnEntry {
+    fn from(request: ChatRequest) -> Self {
+        Self::Start {
+            request,
+            pending_trim: PendingStreamTrim::default(),
+            turn_config: PartialAppConfig::empty(),
+            checkpoint: None,
+        }
+    }
+}
+
 /// Runs the turn loop: streaming from LLM, handling signals, executing tools.
 ///
 /// This is extracted from `handle_turn` to enable integration testing without
@@ -193,9 +227,9 @@ pub(super) async fn run_turn_loop(
     printer: Arc<Printer>,
     prompt_backend: Arc<dyn PromptBackend>,
     mut tool_coordinator: ToolCoordinator,
-    chat_request: ChatRequest,
+    entry: TurnEntry,
     invocation: InvocationContext,
-    pending_trim: PendingStreamTrim,
+    providers: &ProviderCache,
 ) -> Result<(), Error> {
     // The turn-level interrupt handler (RFD 045) is the outermost handler
     // scope within the turn: it owns the gaps between phases (persistence,
@@ -211,6 +245,7 @@ pub(super) async fn run_turn_loop(
         secs => Some(Duration::from_secs(u64::from(secs))),
     };
     let output_limit = output_limit_bytes(cfg.assistant.request.max_response_bytes);
+    let background = invocation.background.clone();
     let mut turn_coordinator = TurnCoordinator::new(
         printer.clone(),
         cfg.style.clone(),
@@ -232,15 +267,22 @@ pub(super) async fn run_turn_loop(
     // Share the owed-separator flag so visible assistant content rendered by
     // the coordinator can cancel a blank line owed by a preceding tool result.
     turn_coordinator.set_tool_separator(tool_renderer.separator_flag());
-
-    let inquiry_backend: Arc<dyn InquiryBackend> = build_inquiry_backend(
-        cfg,
-        tools.to_vec(),
-        model.clone(),
-        provider.clone(),
-        attachments.to_vec(),
-    )
-    .await?;
+    // Both coordinators write to the same `jsonl` event stream.
+    turn_coordinator.set_events(tool_coordinator.events().clone());
+
+    let inquiry_backend: Arc<dyn InquiryBackend> = Arc::new(
+        build_inquiry_backend(
+            cfg,
+            tools.to_vec(),
+            model.clone(),
+            provider.clone(),
+            attachments.to_vec(),
+            providers,
+            &invocation.env,
+        )
+        .await?
+        .with_scratch(tool_coordinator.scratch().cloned()),
+    );
 
     info!(model = model.name(), "Starting conversation turn.");
 
@@ -275,17 +317,54 @@ pub(super) async fn run_turn_loop(
         }
 
         match turn_coordinator.current_phase() {
-            TurnPhase::Idle => {
-                // The turn-start commit point: any replay trim deferred while
-                // building the request (see [`PendingStreamTrim`]) is applied
-                // in the same `update_events` scope that appends the new
-                // request, so the durable stream never persists the removal
-                // without its replacement.
-                lock.as_mut().update_events(|stream| {
-                    pending_trim.apply(stream);
-                    turn_coordinator.start_turn(stream, chat_request.clone());
-                });
-            }
+            TurnPhase::Idle => match &entry {
+                TurnEntry::Start {
+                    request,
+                    pending_trim,
+                    turn_config,
+                    checkpoint,
+                } => {
+                    // The turn-start commit point: any replay trim deferred
+                    // while building the request (see [`PendingStreamTrim`])
+                    // is applied in the same `update_events` scope that
+                    // appends the new request, so the durable stream never
+                    // persists the removal without its replacement.
+                    //
+                    // The turn's own configuration follows its start, so the
+                    // next turn reverts it.
+                    lock.as_mut().update_events(|stream| {
+                        pending_trim.apply(stream);
+                        turn_coordinator.start_turn(stream, request.clone());
+                        if let Some(id) = checkpoint
+                            && let Some(start) = stream
+                                .iter_mut()
+                                .filter(|event| event.event.is_turn_start())
+                                .last()
+                        {
+                            start
+                                .event
+                                .add_metadata_field(TURN_CHECKPOINT_KEY, id.clone());
+                        }
+                        if !turn_config.is_empty() {
+                            stream.add_config_delta(
+                                ConfigDelta::from(turn_config.clone()).turn_scoped(),
+                            );
+                        }
+                    });
+                }
+
+                TurnEntry::Resume(incomplete) => {
+                    info!(%incomplete, "Resuming incomplete turn.");
+
+                    // The pending tool calls were never prepared by this
+                    // process. The executing phase walks the stream for them
+                    // and runs them through the permission pipeline, exactly
+                    // as it does after a user-initiated restart.
+                    let phase = incomplete.phase();
+                    restart_requested = phase == TurnPhase::Executing;
+                    turn_coordinator.resume_turn(phase);
+                }
+            },
 
             TurnPhase::Complete | TurnPhase::Aborted => return Ok(()),
 
@@ -827,6 +906,7 @@ pub(super) async fn run_turn_loop(
                             pre_resolved,
                             &mut tool_coordinator,
                             &mut turn_coordinator,
+                            &background,
                             &mut conv,
                         )?;
                         return Err(cmd::Error::interrupted().into());
@@ -844,6 +924,7 @@ pub(super) async fn run_turn_loop(
                             pre_resolved,
                             &mut tool_coordinator,
                             &mut turn_coordinator,
+                            &background,
                             &mut conv,
                         )?;
                         break;
@@ -861,6 +942,7 @@ pub(super) async fn run_turn_loop(
                             pre_resolved,
                             &mut tool_coordinator,
                             &mut turn_coordinator,
+                            &background,
                             &mut conv,
                         )? {
                             tool_choice = ToolChoice::Auto;
@@ -880,7 +962,9 @@ async fn build_inquiry_backend(
     model: ModelDetails,
     provider: Arc<dyn Provider>,
     attachments: Vec<Attachment>,
-) -> Result<Arc<LlmInquiryBackend>, Error> {
+    providers: &ProviderCache,
+    env: &Env,
+) -> Result<LlmInquiryBackend, Error> {
     let sections = build_sections(&cfg.assistant, !tools.is_empty());
     let inquiry_override = &cfg.conversation.inquiry.assistant;
 
@@ -911,9 +995,6 @@ async fn build_inquiry_backend(
         bytes => bytes,
     };
 
-    // Track providers we've already constructed to avoid duplicates.
-    let mut providers: IndexMap<ProviderId, Arc<dyn Provider>> = IndexMap::new();
-
     // Build the default InquiryConfig from the global inquiry override
     // merged with the parent assistant config.
     let default_config = if let Some(inquiry_model_cfg) = inquiry_override.model.as_ref() {
@@ -921,14 +1002,12 @@ async fn build_inquiry_backend(
         // Attribute failures to the override: without this, e.g. a missing
         // API key environment variable renders identically to a main-model
         // failure and points the user at the wrong config.
-        let inquiry_provider: Arc<dyn Provider> = Arc::from(
-            get_provider(inquiry_model_id.provider, &cfg.providers.llm).map_err(|source| {
-                Error::InquiryModelOverride {
-                    model: inquiry_model_id.to_string(),
-                    source,
-                }
-            })?,
-        );
+        let inquiry_provider = providers
+            .get(inquiry_model_id.provider, &cfg.providers.llm, env)
+            .map_err(|source| Error::InquiryModelOverride {
+                model: inquiry_model_id.to_string(),
+                source,
+            })?;
         debug!(model = %inquiry_model_id, "Fetching inquiry model details.");
         let inquiry_model = inquiry_provider
             .model_details(&inquiry_model_id.name)
@@ -951,8 +1030,6 @@ async fn build_inquiry_backend(
             "Using dedicated model for inquiries."
         );
 
-        providers.insert(inquiry_model_id.provider, Arc::clone(&inquiry_provider));
-
         InquiryConfig {
             provider: inquiry_provider,
             model: inquiry_model,
@@ -961,8 +1038,6 @@ async fn build_inquiry_backend(
             max_response_bytes: default_max_response_bytes,
         }
     } else {
-        providers.insert(model.id.provider, Arc::clone(&provider));
-
         InquiryConfig {
             provider: Arc::clone(&provider),
             model: model.clone(),
@@ -972,14 +1047,14 @@ async fn build_inquiry_backend(
         }
     };
 
-    let overrides = build_inquiry_overrides(cfg, &default_config, &mut providers).await?;
+    let overrides = build_inquiry_overrides(cfg, &default_config, providers, env).await?;
 
-    Ok(Arc::new(LlmInquiryBackend::new(
+    Ok(LlmInquiryBackend::new(
         default_config,
         overrides,
         attachments,
         tools,
-    )))
+    ))
 }
 
 /// Walk active tool configs to build per-question [`InquiryConfig`] overrides
@@ -987,7 +1062,8 @@ async fn build_inquiry_backend(
 async fn build_inquiry_overrides(
     cfg: &AppConfig,
     default_config: &InquiryConfig,
-    providers: &mut IndexMap<ProviderId, Arc<dyn Provider>>,
+    providers: &ProviderCache,
+    env: &Env,
 ) -> Result<IndexMap<(String, String), InquiryConfig>, Error> {
     let mut overrides = IndexMap::new();
 
@@ -1021,15 +1097,9 @@ async fn build_inquiry_overrides(
                     source: Box::new(source),
                 };
 
-                let prov = if let Some(p) = providers.get(&model_id.provider) {
-                    Arc::clone(p)
-                } else {
-                    let p: Arc<dyn Provider> = Arc::from(
-                        get_provider(model_id.provider, &cfg.providers.llm).map_err(wrap_err)?,
-                    );
-                    providers.insert(model_id.provider, Arc::clone(&p));
-                    p
-                };
+                let prov = providers
+                    .get(model_id.provider, &cfg.providers.llm, env)
+                    .map_err(wrap_err)?;
 
                 let details = prov.model_details(&model_id.name).await.map_err(wrap_err)?;
 
@@ -1090,6 +1160,7 @@ fn commit_tool_responses(
     pre_resolved: Vec<(usize, ToolCallResponse)>,
     tool: &mut ToolCoordinator,
     turn: &mut super::turn::TurnCoordinator,
+    background: &BackgroundTasks,
     conv: &mut ConversationMut,
 ) -> Result<bool, Error> {
     // Persist any rendered custom-argument output accumulated during the
@@ -1104,7 +1175,14 @@ fn commit_tool_responses(
     indexed.sort_by_key(|(idx, _)| *idx);
     let responses: Vec<_> = indexed.into_iter().map(|(_, r)| r).collect();
 
-    let action = conv.update_events(|stream| turn.handle_tool_responses(stream, responses));
+    let action = conv.update_events(|stream| {
+        let action = turn.handle_tool_responses(stream, responses);
+
+        // Record the handles of tools that continue in the background on their
+        // responses, which now exist, along with handles that changed since.
+        background.persist(stream);
+        action
+    });
     conv.flush()?;
 
     Ok(matches!(action, Action::SendFollowUp))
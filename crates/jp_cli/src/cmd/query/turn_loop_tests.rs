@@ -25,6 +25,7 @@ use jp_config::{
         CommandConfigOrString, QuestionConfig, QuestionTarget, RunMode, ToolConfig, ToolSource,
         style::{DisplayStyleConfig, ErrorStyleConfig, InlineResults, LinkStyle, ParametersStyle},
     },
+    env::Env,
     interrupt::ToolInterruptAction,
     model::id::{self, ProviderId},
 };
@@ -385,9 +386,9 @@ async fn test_interrupt_stop_during_streaming_persists_content() {
             printer.clone(),
             Arc::new(backend),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -487,9 +488,9 @@ async fn test_streaming_interrupt_menu_cancel_escalates() {
             printer.clone(),
             Arc::new(backend),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -570,9 +571,9 @@ async fn test_normal_completion_persists_content() {
         printer.clone(),
         Arc::new(MockPromptBackend::new()),
         ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-        chat_request.clone(),
+        chat_request.clone().into(),
         InvocationContext::default(),
-        PendingStreamTrim::default(),
+        &ProviderCache::default(),
     )
     .await
     .unwrap();
@@ -655,9 +656,9 @@ async fn premature_stream_end_without_finished_returns_error() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            ChatRequest::from("hi"),
+            ChatRequest::from("hi").into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         ),
     )
     .await
@@ -718,9 +719,9 @@ async fn premature_stream_end_exhausts_retry_budget() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            ChatRequest::from("hi"),
+            ChatRequest::from("hi").into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         ),
     )
     .await
@@ -797,9 +798,9 @@ async fn output_ceiling_ends_turn_without_re_requesting() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            ChatRequest::from("hi"),
+            ChatRequest::from("hi").into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         ),
     )
     .await
@@ -901,9 +902,9 @@ async fn orphan_tool_call_is_sanitized_before_provider_request() {
         printer.clone(),
         Arc::new(MockPromptBackend::new()),
         ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-        ChatRequest::from("new query"),
+        ChatRequest::from("new query").into(),
         InvocationContext::default(),
-        PendingStreamTrim::default(),
+        &ProviderCache::default(),
     )
     .await
     .unwrap();
@@ -985,9 +986,9 @@ async fn test_tool_call_cycle_completes_with_followup() {
         printer.clone(),
         Arc::new(MockPromptBackend::new()),
         ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-        chat_request.clone(),
+        chat_request.clone().into(),
         InvocationContext::default(),
-        PendingStreamTrim::default(),
+        &ProviderCache::default(),
     )
     .await;
 
@@ -1187,6 +1188,7 @@ async fn test_tool_interrupt_menu_cancel_escalates() {
             .insert("slow_tool".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Unattended),
                 format: None,
                 enable: None,
@@ -1199,6 +1201,7 @@ async fn test_tool_interrupt_menu_cancel_escalates() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -1268,9 +1271,9 @@ async fn test_tool_interrupt_menu_cancel_escalates() {
             printer.clone(),
             Arc::new(backend),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -1335,6 +1338,7 @@ async fn test_tool_stop_on_interrupt_commits_responses_without_follow_up() {
             .insert("slow_tool".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Unattended),
                 format: None,
                 enable: None,
@@ -1347,6 +1351,7 @@ async fn test_tool_stop_on_interrupt_commits_responses_without_follow_up() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: Some(CUSTOM_CANCELLATION_RESPONSE.to_string()),
             });
 
@@ -1417,9 +1422,9 @@ async fn test_tool_stop_on_interrupt_commits_responses_without_follow_up() {
             Arc::new(backend),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source))
                 .with_interrupt(config.interrupt.tool_call.clone()),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -1474,6 +1479,7 @@ async fn test_interrupt_during_tool_prompt_completes_turn_early() {
             .insert("question_tool".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Unattended),
                 format: None,
                 enable: None,
@@ -1489,6 +1495,7 @@ async fn test_interrupt_during_tool_prompt_completes_turn_early() {
                 })]),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -1559,9 +1566,9 @@ async fn test_interrupt_during_tool_prompt_completes_turn_early() {
             printer.clone(),
             Arc::new(backend),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -1670,9 +1677,9 @@ async fn test_multiple_tool_calls_in_sequence() {
         printer.clone(),
         Arc::new(MockPromptBackend::new()),
         ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-        chat_request.clone(),
+        chat_request.clone().into(),
         InvocationContext::default(),
-        PendingStreamTrim::default(),
+        &ProviderCache::default(),
     )
     .await;
 
@@ -1760,9 +1767,9 @@ async fn test_empty_tool_response_continues_cycle() {
         printer.clone(),
         Arc::new(MockPromptBackend::new()),
         ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-        chat_request.clone(),
+        chat_request.clone().into(),
         InvocationContext::default(),
-        PendingStreamTrim::default(),
+        &ProviderCache::default(),
     )
     .await;
 
@@ -1813,6 +1820,7 @@ async fn test_tool_restart_on_interrupt() {
             .insert("slow_tool".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Unattended),
                 format: None,
                 enable: None,
@@ -1825,6 +1833,7 @@ async fn test_tool_restart_on_interrupt() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -1905,9 +1914,9 @@ async fn test_tool_restart_on_interrupt() {
             printer.clone(),
             Arc::new(backend),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -1968,6 +1977,7 @@ async fn test_merged_stream_exits_after_tool_response() {
             .insert("echo_tool".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: Some(CommandConfigOrString::String("echo hello".to_string())),
+                pty: None,
                 run: Some(RunMode::Unattended),
                 format: None,
                 enable: None,
@@ -1980,6 +1990,7 @@ async fn test_merged_stream_exits_after_tool_response() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -2026,9 +2037,9 @@ async fn test_merged_stream_exits_after_tool_response() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -2080,6 +2091,7 @@ async fn test_tool_call_with_run_mode_ask_approves() {
             .insert("mock_tool".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None, // No real command
+                pty: None,
                 run: Some(RunMode::Ask),
                 format: None,
                 enable: None,
@@ -2092,6 +2104,7 @@ async fn test_tool_call_with_run_mode_ask_approves() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -2153,9 +2166,9 @@ async fn test_tool_call_with_run_mode_ask_approves() {
             printer.clone(),
             Arc::new(backend),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -2223,6 +2236,7 @@ async fn test_tool_call_with_run_mode_ask_skips() {
             .insert("mock_tool".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Ask),
                 format: None,
                 enable: None,
@@ -2235,6 +2249,7 @@ async fn test_tool_call_with_run_mode_ask_skips() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -2295,9 +2310,9 @@ async fn test_tool_call_with_run_mode_ask_skips() {
             printer.clone(),
             Arc::new(backend),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -2373,6 +2388,7 @@ async fn test_tool_call_with_run_mode_unattended() {
             .insert("mock_tool".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Unattended),
                 format: None,
                 enable: None,
@@ -2385,6 +2401,7 @@ async fn test_tool_call_with_run_mode_unattended() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -2441,9 +2458,9 @@ async fn test_tool_call_with_run_mode_unattended() {
             printer.clone(),
             Arc::new(backend),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -2511,6 +2528,7 @@ async fn test_tool_call_with_run_mode_skip() {
             .insert("mock_tool".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Skip),
                 format: None,
                 enable: None,
@@ -2523,6 +2541,7 @@ async fn test_tool_call_with_run_mode_skip() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -2588,9 +2607,9 @@ async fn test_tool_call_with_run_mode_skip() {
             printer.clone(),
             Arc::new(backend),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -2665,6 +2684,7 @@ async fn test_multiple_tools_with_different_run_modes() {
             .insert("tool_ask".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Ask),
                 format: None,
                 enable: None,
@@ -2677,6 +2697,7 @@ async fn test_multiple_tools_with_different_run_modes() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
         // tool_unattended runs automatically
@@ -2686,6 +2707,7 @@ async fn test_multiple_tools_with_different_run_modes() {
             .insert("tool_unattended".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Unattended),
                 format: None,
                 enable: None,
@@ -2698,6 +2720,7 @@ async fn test_multiple_tools_with_different_run_modes() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -2791,9 +2814,9 @@ async fn test_multiple_tools_with_different_run_modes() {
             printer.clone(),
             Arc::new(backend),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -2873,6 +2896,7 @@ async fn test_tool_call_returns_error() {
             .insert("failing_tool".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Unattended),
                 format: None,
                 enable: None,
@@ -2885,6 +2909,7 @@ async fn test_tool_call_returns_error() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -2939,9 +2964,9 @@ async fn test_tool_call_returns_error() {
             printer.clone(),
             Arc::new(backend),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -3169,9 +3194,9 @@ async fn test_waiting_indicator_shows_during_delay() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await
         .unwrap();
@@ -3269,9 +3294,9 @@ async fn test_waiting_indicator_survives_keep_alive_and_shows_status() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await
         .unwrap();
@@ -3383,9 +3408,9 @@ async fn test_waiting_indicator_cleared_before_retry_notice() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await
         .unwrap();
@@ -3468,9 +3493,9 @@ async fn test_waiting_indicator_not_shown_when_disabled() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await
         .unwrap();
@@ -3544,9 +3569,9 @@ async fn test_waiting_indicator_not_shown_for_non_tty() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await
         .unwrap();
@@ -3723,9 +3748,9 @@ async fn test_multi_part_tool_call_shows_preparing_spinner() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -3810,9 +3835,9 @@ async fn test_turn_start_event_is_emitted() {
         printer,
         Arc::new(MockPromptBackend::new()),
         ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-        chat_request.clone(),
+        chat_request.clone().into(),
         InvocationContext::default(),
-        PendingStreamTrim::default(),
+        &ProviderCache::default(),
     )
     .await
     .unwrap();
@@ -3873,9 +3898,9 @@ async fn test_turn_start_index_increments_across_turns() {
         printer,
         Arc::new(MockPromptBackend::new()),
         ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-        chat_request.clone(),
+        chat_request.clone().into(),
         InvocationContext::default(),
-        PendingStreamTrim::default(),
+        &ProviderCache::default(),
     )
     .await
     .unwrap();
@@ -3908,9 +3933,9 @@ async fn test_turn_start_index_increments_across_turns() {
         printer,
         Arc::new(MockPromptBackend::new()),
         ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-        chat_request.clone(),
+        chat_request.clone().into(),
         InvocationContext::default(),
-        PendingStreamTrim::default(),
+        &ProviderCache::default(),
     )
     .await
     .unwrap();
@@ -4002,9 +4027,9 @@ async fn test_markdown_flushed_before_tool_header() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await
         .unwrap();
@@ -4069,6 +4094,7 @@ async fn test_parallel_tool_calls_rendered_atomically() {
             .insert("tool_a".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Unattended),
                 format: None,
                 enable: None,
@@ -4081,6 +4107,7 @@ async fn test_parallel_tool_calls_rendered_atomically() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
         config
@@ -4089,6 +4116,7 @@ async fn test_parallel_tool_calls_rendered_atomically() {
             .insert("tool_b".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Unattended),
                 format: None,
                 enable: None,
@@ -4101,6 +4129,7 @@ async fn test_parallel_tool_calls_rendered_atomically() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -4186,9 +4215,9 @@ async fn test_parallel_tool_calls_rendered_atomically() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await
         .unwrap();
@@ -4261,6 +4290,7 @@ async fn test_single_tool_call_rendered_with_args() {
             .insert("fs_read_file".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Unattended),
                 format: None,
                 enable: None,
@@ -4273,6 +4303,7 @@ async fn test_single_tool_call_rendered_with_args() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -4345,9 +4376,9 @@ async fn test_single_tool_call_rendered_with_args() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request.clone(),
+            chat_request.clone().into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await
         .unwrap();
@@ -4497,6 +4528,7 @@ fn inquiry_tool_config(questions: &[&str]) -> ToolConfig {
     ToolConfig {
         source: ToolSource::Local { tool: None },
         command: None,
+        pty: None,
         run: Some(RunMode::Unattended),
         format: None,
         enable: None,
@@ -4517,6 +4549,7 @@ fn inquiry_tool_config(questions: &[&str]) -> ToolConfig {
             .collect(),
         options: IndexMap::default(),
         access: None,
+        concurrency: None,
         cancellation_response: None,
     }
 }
@@ -4545,7 +4578,7 @@ async fn inquiry_ceiling_honors_the_global_inquiry_override() {
     let provider: Arc<dyn Provider> = Arc::new(MockProvider::new(vec![]));
     let model = inquiry_mock_model();
 
-    let backend = build_inquiry_backend(&config, vec![], model, provider, vec![])
+    let backend = build_inquiry_backend(&config, vec![], model, provider, vec![], &Env::process())
         .await
         .expect("the inquiry backend builds");
 
@@ -4593,7 +4626,7 @@ async fn inquiry_ceiling_survives_a_sibling_only_request_override() {
     let provider: Arc<dyn Provider> = Arc::new(MockProvider::new(vec![]));
     let model = inquiry_mock_model();
 
-    let backend = build_inquiry_backend(&config, vec![], model, provider, vec![])
+    let backend = build_inquiry_backend(&config, vec![], model, provider, vec![], &Env::process())
         .await
         .expect("the inquiry backend builds");
 
@@ -4634,7 +4667,7 @@ async fn inquiry_ceiling_honors_the_per_question_override() {
     let provider: Arc<dyn Provider> = Arc::new(MockProvider::new(vec![]));
     let model = inquiry_mock_model();
 
-    let backend = build_inquiry_backend(&config, vec![], model, provider, vec![])
+    let backend = build_inquiry_backend(&config, vec![], model, provider, vec![], &Env::process())
         .await
         .expect("the inquiry backend builds");
 
@@ -4731,9 +4764,9 @@ async fn test_tool_with_single_inquiry() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request,
+            chat_request.into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -4869,9 +4902,9 @@ async fn test_tool_with_multiple_inquiries() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request,
+            chat_request.into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -4937,6 +4970,7 @@ async fn test_parallel_tools_one_with_inquiry() {
             .insert("normal_tool".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Unattended),
                 format: None,
                 enable: None,
@@ -4949,6 +4983,7 @@ async fn test_parallel_tools_one_with_inquiry() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -5021,9 +5056,9 @@ async fn test_parallel_tools_one_with_inquiry() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request,
+            chat_request.into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -5159,9 +5194,9 @@ async fn test_parallel_tools_both_with_inquiries() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request,
+            chat_request.into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -5309,9 +5344,9 @@ async fn test_retry_counter_resets_on_successful_event() {
             printer,
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            chat_request,
+            chat_request.into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -5369,6 +5404,7 @@ async fn test_unavailable_tool_before_approved_does_not_panic() {
             .insert("ok_tool".to_string(), ToolConfig {
                 source: ToolSource::Local { tool: None },
                 command: None,
+                pty: None,
                 run: Some(RunMode::Unattended),
                 format: None,
                 enable: None,
@@ -5381,6 +5417,7 @@ async fn test_unavailable_tool_before_approved_does_not_panic() {
                 questions: IndexMap::new(),
                 options: IndexMap::default(),
                 access: None,
+                concurrency: None,
                 cancellation_response: None,
             });
 
@@ -5448,9 +5485,9 @@ async fn test_unavailable_tool_before_approved_does_not_panic() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request,
+            chat_request.into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -5558,9 +5595,9 @@ async fn test_inquiry_failure_marks_tool_as_error() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-            chat_request,
+            chat_request.into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -5751,9 +5788,9 @@ async fn test_live_header_uses_configured_model_id_not_provider_returned() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            chat_request,
+            chat_request.into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await
         .unwrap();
@@ -5797,6 +5834,7 @@ async fn reasoning_before_a_tool_call_shades_the_tool_chrome() {
         .insert("mock_tool".to_string(), ToolConfig {
             source: ToolSource::Local { tool: None },
             command: None,
+            pty: None,
             run: Some(RunMode::Unattended),
             format: None,
             enable: None,
@@ -5809,6 +5847,7 @@ async fn reasoning_before_a_tool_call_shades_the_tool_chrome() {
             questions: IndexMap::new(),
             options: IndexMap::default(),
             access: None,
+            concurrency: None,
             cancellation_response: None,
         });
 
@@ -5866,9 +5905,9 @@ async fn reasoning_before_a_tool_call_shades_the_tool_chrome() {
         printer.clone(),
         Arc::new(MockPromptBackend::new()),
         ToolCoordinator::new(config.conversation.tools.clone(), Box::new(executor_source)),
-        ChatRequest::from("use the tool"),
+        ChatRequest::from("use the tool").into(),
         InvocationContext::default(),
-        PendingStreamTrim::default(),
+        &ProviderCache::default(),
     )
     .await
     .unwrap();
@@ -5994,9 +6033,9 @@ async fn test_rebuild_cap_stops_a_provider_that_keeps_requesting_rebuilds() {
         printer.clone(),
         Arc::new(MockPromptBackend::new()),
         ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-        ChatRequest::from("repair this"),
+        ChatRequest::from("repair this").into(),
         InvocationContext::default(),
-        PendingStreamTrim::default(),
+        &ProviderCache::default(),
     )
     .await;
 
@@ -6083,9 +6122,9 @@ async fn test_refused_rebuild_clears_the_retry_line() {
             printer.clone(),
             Arc::new(MockPromptBackend::new()),
             ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-            ChatRequest::from("answer this"),
+            ChatRequest::from("answer this").into(),
             InvocationContext::default(),
-            PendingStreamTrim::default(),
+            &ProviderCache::default(),
         )
         .await;
 
@@ -6167,9 +6206,9 @@ async fn test_refused_rebuild_persists_streamed_content() {
         printer.clone(),
         Arc::new(MockPromptBackend::new()),
         ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
-        ChatRequest::from("answer this"),
+        ChatRequest::from("answer this").into(),
         InvocationContext::default(),
-        PendingStreamTrim::default(),
+        &ProviderCache::default(),
     )
     .await;
 
@@ -6187,3 +6226,140 @@ async fn test_refused_rebuild_persists_streamed_content() {
         "streamed content must survive the abort.\nFile contents:\n{content}"
     );
 }
+
+/// Resuming a turn whose request never got a response sends the persisted
+/// request as-is, without starting a second turn.
+#[tokio::test]
+async fn resume_pending_response_reuses_the_persisted_request() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+
+    let config = AppConfig::new_test();
+    let fs = Arc::new(FsStorageBackend::new(&root.join(".jp")).expect("failed to create backend"));
+    let mut workspace = Workspace::in_memory(root).with_backend(fs.clone());
+
+    let lock = workspace
+        .create_and_lock_conversation(Conversation::default(), config.clone().into(), None)
+        .unwrap();
+    lock.as_mut()
+        .update_events(|stream| stream.start_turn(ChatRequest::from("What is 2+2?")));
+
+    let incomplete = IncompleteTurn::detect(&lock.events()).unwrap();
+    assert_eq!(incomplete, IncompleteTurn::PendingResponse);
+
+    let provider: Arc<dyn Provider> = Arc::new(MockProvider::with_message("The answer is 4."));
+    let model = provider
+        .model_details(&"test-model".parse().unwrap())
+        .await
+        .unwrap();
+    let (printer, _out, _err) = Printer::memory(OutputFormat::TextPretty);
+    let router = detached_router();
+
+    run_turn_loop(
+        Arc::clone(&provider),
+        &model,
+        &config,
+        &router,
+        &jp_mcp::Client::default(),
+        root,
+        false,
+        &[],
+        &lock,
+        ToolChoice::Auto,
+        &[],
+        Arc::new(printer),
+        Arc::new(MockPromptBackend::new()),
+        ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
+        TurnEntry::Resume(incomplete),
+        InvocationContext::default(),
+        &ProviderCache::default(),
+    )
+    .await
+    .unwrap();
+
+    let events = lock.events();
+    assert_eq!(events.turn_count(), 1);
+    assert_eq!(
+        events.iter().filter(|e| e.event.is_chat_request()).count(),
+        1
+    );
+    assert_eq!(
+        events
+            .iter()
+            .filter_map(|e| e.event.as_chat_response().cloned())
+            .next_back(),
+        Some(ChatResponse::message("The answer is 4."))
+    );
+    assert_eq!(IncompleteTurn::detect(&events), None);
+}
+
+/// Resuming a turn with unanswered tool calls runs them (here: reports them
+/// unavailable) and sends the results back before the turn completes.
+#[tokio::test]
+async fn resume_pending_tools_answers_every_call_before_following_up() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+
+    let config = AppConfig::new_test();
+    let fs = Arc::new(FsStorageBackend::new(&root.join(".jp")).expect("failed to create backend"));
+    let mut workspace = Workspace::in_memory(root).with_backend(fs.clone());
+
+    let lock = workspace
+        .create_and_lock_conversation(Conversation::default(), config.clone().into(), None)
+        .unwrap();
+    lock.as_mut().update_events(|stream| {
+        stream.start_turn(ChatRequest::from("Check the build."));
+        stream.extend([ConversationEvent::from(ToolCallRequest {
+            id: "call_1".into(),
+            name: "cargo_check".into(),
+            arguments: Map::new(),
+        })]);
+    });
+
+    let incomplete = IncompleteTurn::detect(&lock.events()).unwrap();
+    assert_eq!(incomplete.phase(), TurnPhase::Executing);
+
+    let provider: Arc<dyn Provider> = Arc::new(MockProvider::with_message("The build is fine."));
+    let model = provider
+        .model_details(&"test-model".parse().unwrap())
+        .await
+        .unwrap();
+    let (printer, _out, _err) = Printer::memory(OutputFormat::TextPretty);
+    let router = detached_router();
+
+    run_turn_loop(
+        Arc::clone(&provider),
+        &model,
+        &config,
+        &router,
+        &jp_mcp::Client::default(),
+        root,
+        false,
+        &[],
+        &lock,
+        ToolChoice::Auto,
+        &[],
+        Arc::new(printer),
+        Arc::new(MockPromptBackend::new()),
+        ToolCoordinator::new(config.conversation.tools.clone(), empty_executor_source()),
+        TurnEntry::Resume(incomplete),
+        InvocationContext::default(),
+        &ProviderCache::default(),
+    )
+    .await
+    .unwrap();
+
+    let events = lock.events();
+    let response = events
+        .find_tool_call_response("call_1")
+        .expect("the pending call is answered");
+    assert!(response.result.is_err());
+    assert_eq!(events.turn_count(), 1);
+    assert_eq!(
+        events
+            .iter()
+            .filter_map(|e| e.event.as_chat_response().cloned())
+            .next_back(),
+        Some(ChatResponse::message("The build is fine."))
+    );
+}
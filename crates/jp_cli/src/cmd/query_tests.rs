@@ -7,6 +7,7 @@ use indexmap::IndexMap;
 use jp_config::{
     AppConfig, PartialAppConfig, ToPartial,
     conversation::tool::{AllowToggle, Enable, PartialEnableConfig, PartialToolConfig},
+    env::Env,
     model::id::{ModelIdConfig, PartialModelIdConfig, ProviderId},
     util::build,
 };
@@ -126,7 +127,8 @@ fn build_query_config(
     query: &Query,
     handle: Option<&ConversationHandle>,
 ) -> AppConfig {
-    let pipeline = ConfigPipeline::new(base, cfg_args, Some(workspace), None).unwrap();
+    let pipeline =
+        ConfigPipeline::new(base, cfg_args, Some(workspace), None, &Env::process()).unwrap();
 
     let conversation_partial = handle.map(|handle| {
         query
@@ -141,7 +143,7 @@ fn build_query_config(
     .unwrap();
 
     partial = query
-        .apply_cli_config(Some(workspace), partial, None)
+        .apply_cli_config(Some(workspace), partial, None, &Env::process())
         .unwrap();
 
     build(partial).unwrap()
@@ -179,9 +181,9 @@ async fn run_mock_turn(
         printer,
         Arc::new(MockPromptBackend::new()),
         tool::ToolCoordinator::new(cfg.conversation.tools.clone(), empty_executor_source()),
-        ChatRequest::from(prompt),
+        ChatRequest::from(prompt).into(),
         InvocationContext::default(),
-        PendingStreamTrim::default(),
+        &ProviderCache::default(),
     )
     .await
     .unwrap();
@@ -201,6 +203,7 @@ fn test_query_tools_and_no_tools() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -220,6 +223,7 @@ fn test_query_tools_and_no_tools() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -239,6 +243,7 @@ fn test_query_tools_and_no_tools() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -256,6 +261,7 @@ fn test_query_tools_and_no_tools() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -278,6 +284,7 @@ fn test_query_tools_and_no_tools() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -298,6 +305,7 @@ fn test_query_tools_and_no_tools() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -320,6 +328,7 @@ fn test_explicit_tool_enabled_by_name() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -331,6 +340,86 @@ fn test_explicit_tool_enabled_by_name() {
     assert_eq!(explicit.allow_toggle, AllowToggle::IfNamed);
 }
 
+/// A partial config with a `review` template that sets a model and enables
+/// `explicit_tool`.
+fn make_partial_with_template() -> PartialAppConfig {
+    let mut partial = make_partial_with_tools();
+    partial.template.named.insert(
+        "review".into(),
+        toml::from_str(indoc::indoc! {r#"
+            content = "Review the changes."
+            model = "anthropic/haiku"
+            tools = ["explicit_tool"]
+        "#})
+        .unwrap(),
+    );
+    partial
+}
+
+#[test]
+fn test_named_template_applies_its_config() {
+    let partial = IntoPartialAppConfig::apply_cli_config(
+        &Query {
+            named_template: Some("review".into()),
+            ..Default::default()
+        },
+        None,
+        make_partial_with_template(),
+        None,
+        &Env::process(),
+    )
+    .unwrap();
+
+    assert_eq!(partial.assistant.model.id, "anthropic/haiku".into());
+    assert!(effective(&partial, "explicit_tool").state);
+}
+
+#[test]
+fn test_named_template_config_yields_to_flags() {
+    let partial = IntoPartialAppConfig::apply_cli_config(
+        &Query {
+            named_template: Some("review".into()),
+            model: Some("openai/gpt-5".into()),
+            tool_directives: directives(vec![ToolDirective::Disable("explicit_tool".into())]),
+            ..Default::default()
+        },
+        None,
+        make_partial_with_template(),
+        None,
+        &Env::process(),
+    )
+    .unwrap();
+
+    assert_eq!(partial.assistant.model.id, "openai/gpt-5".into());
+    assert!(!effective(&partial, "explicit_tool").state);
+}
+
+#[test]
+fn test_unknown_named_template() {
+    let error = IntoPartialAppConfig::apply_cli_config(
+        &Query {
+            named_template: Some("nope".into()),
+            ..Default::default()
+        },
+        None,
+        make_partial_with_template(),
+        None,
+        &Env::process(),
+    )
+    .unwrap_err();
+
+    assert_eq!(error.to_string(), "Template not found: nope");
+}
+
+#[test]
+fn test_named_template_short_flag() {
+    // `-T` is short for `--no-tool`, so named templates use `-N`.
+    let query = parse_query(&["-N", "review", "-T"]).unwrap();
+
+    assert_eq!(query.named_template.as_deref(), Some("review"));
+    assert_eq!(&*query.tool_directives, [ToolDirective::DisableAll]);
+}
+
 #[test]
 fn test_enable_all_and_explicit_by_name() {
     // `-t -t explicit_tool` should enable all non-explicit tools AND
@@ -348,6 +437,7 @@ fn test_enable_all_and_explicit_by_name() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -372,6 +462,7 @@ fn test_enable_all_skips_unnamed_explicit() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -403,6 +494,7 @@ fn test_interleaved_disable_all_then_enable_named() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -432,6 +524,7 @@ fn test_interleaved_enable_all_then_disable_named() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -458,6 +551,7 @@ fn test_interleaved_disable_all_then_enable_all() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -494,6 +588,7 @@ fn test_interleaved_three_step_composition() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -519,6 +614,7 @@ fn test_named_disable_of_locked_on_tool_errors() {
         None,
         make_partial_with_tools(),
         None,
+        &Env::process(),
     )
     .unwrap_err()
     .to_string();
@@ -547,6 +643,7 @@ fn test_named_enable_of_locked_off_tool_errors() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap_err()
     .to_string();
@@ -577,6 +674,7 @@ fn test_bulk_directives_skip_locked_tools() {
         None,
         partial,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -617,6 +715,7 @@ fn test_sticky_tool_named_disable_and_bulk_skip() {
         None,
         make(),
         None,
+        &Env::process(),
     )
     .unwrap();
     assert!(!effective(&partial, "ask_user").state);
@@ -630,6 +729,7 @@ fn test_sticky_tool_named_disable_and_bulk_skip() {
         None,
         make(),
         None,
+        &Env::process(),
     )
     .unwrap();
     let sticky = effective(&partial, "ask_user");
@@ -659,6 +759,7 @@ fn test_tool_use_accepts_tool_enabled_via_defaults() {
         None,
         partial,
         None,
+        &Env::process(),
     );
     assert!(result.is_ok(), "{:?}", result.err());
 }
@@ -674,6 +775,7 @@ fn test_tool_use_accepts_locked_on_builtin() {
         None,
         make_partial_with_tools(),
         None,
+        &Env::process(),
     );
     assert!(result.is_ok(), "{:?}", result.err());
 }
@@ -699,7 +801,7 @@ fn query_model_override_is_persisted_as_config_delta() {
     };
 
     let partial = query
-        .apply_cli_config(None, base_config.to_partial(), None)
+        .apply_cli_config(None, base_config.to_partial(), None, &Env::process())
         .unwrap();
     let runtime_config = build(partial).unwrap();
 
@@ -789,7 +891,9 @@ fn inline_compact_dsl_is_not_written_into_query_config() {
         ..Default::default()
     };
 
-    let partial = query.apply_cli_config(None, base, None).unwrap();
+    let partial = query
+        .apply_cli_config(None, base, None, &Env::process())
+        .unwrap();
     assert!(
         partial.conversation.compaction.rules.is_empty(),
         "inline -k DSL must not be written into the config partial",
@@ -1020,9 +1124,11 @@ fn no_title_does_not_persist_into_partial_config() {
         no_title: true,
         ..Default::default()
     }
-    .apply_cli_config(None, base.clone(), None)
+    .apply_cli_config(None, base.clone(), None, &Env::process())
     .unwrap();
-    let without_flag = Query::default().apply_cli_config(None, base, None).unwrap();
+    let without_flag = Query::default()
+        .apply_cli_config(None, base, None, &Env::process())
+        .unwrap();
 
     assert_eq!(
         with_flag.conversation.title.generate.auto,
@@ -1828,7 +1934,10 @@ fn read_arg_file_returns_file_contents() {
     let path = dir.path().join("notes.md");
     std::fs::write(&path, "# Notes\n\nbody\n").unwrap();
 
-    assert_eq!(read_arg_file(path.as_str()).unwrap(), "# Notes\n\nbody\n");
+    assert_eq!(
+        read_arg_file(path.as_str(), &Env::process()).unwrap(),
+        "# Notes\n\nbody\n"
+    );
 }
 
 /// Host for [`Query`]'s arguments, so tests can drive the real clap parser
@@ -1858,7 +1967,7 @@ fn built_request(args: &[&str]) -> String {
 /// Build the request for `args` against `stream`, with no piped stdin.
 fn built_request_against(args: &[&str], stream: &ConversationStream) -> String {
     let query = parse_query(args).unwrap();
-    let resolved = query.resolve_query().unwrap();
+    let resolved = query.resolve_query(&Env::process()).unwrap();
 
     query
         .build_conversation(
@@ -1982,6 +2091,7 @@ fn run_missing_at_path_query_leaves_conversation_and_session_untouched() {
         Runtime::new().unwrap(),
         Globals::default(),
         AppConfig::new_test(),
+        Env::process(),
         Some(session.clone()),
         printer,
     );
@@ -2033,6 +2143,7 @@ fn run_failing_alias_leaves_the_title_untouched() {
         Runtime::new().unwrap(),
         Globals::default(),
         AppConfig::new_test(),
+        Env::process(),
         None,
         printer,
     );
@@ -2070,7 +2181,7 @@ fn resolve_query_missing_at_path_errors() {
     let path = dir.path().join("missing.md");
     let query = parse_query(&[&format!("@{path}")]).unwrap();
 
-    let Err(error) = query.resolve_query() else {
+    let Err(error) = query.resolve_query(&Env::process()) else {
         panic!("a query naming a missing file must fail, not be sent verbatim");
     };
     assert_matches!(&error, Error::ArgFile { path: p, .. } if p == path.as_str());
@@ -2081,7 +2192,7 @@ fn read_arg_file_error_names_the_path() {
     let dir = camino_tempfile::tempdir().unwrap();
     let path = dir.path().join("missing.md");
 
-    let error = read_arg_file(path.as_str()).unwrap_err();
+    let error = read_arg_file(path.as_str(), &Env::process()).unwrap_err();
 
     assert_matches!(&error, Error::ArgFile { path: p, .. } if p == path.as_str());
     // The OS-supplied cause differs per platform, so pin the part we own: the
@@ -0,0 +1,298 @@
+//! `jp stats` summarizes the opt-in usage log.
+//!
+//! See [`crate::usage`] for what is recorded.
+
+use std::collections::BTreeMap;
+
+use comfy_table::{Cell, Row};
+use serde::Serialize;
+
+use crate::{
+    cmd::Output,
+    ctx::Ctx,
+    output::{print_json, print_table},
+    usage::{self, ToolDecision, UsageRecord},
+};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Stats {
+    /// The number of entries to show per section.
+    #[arg(short = 'n', long, default_value_t = 10)]
+    limit: usize,
+}
+
+impl Stats {
+    pub(crate) fn run(self, ctx: &mut Ctx) -> Output {
+        let path = usage::log_path().ok_or("cannot determine user data directory")?;
+        let records = usage::read(&path)?;
+
+        if records.is_empty() && !ctx.printer.format().is_json() {
+            let hint = if ctx.config().user.usage_log {
+                ""
+            } else {
+                "\nEnable it with `user.usage_log = true` in your user configuration."
+            };
+
+            ctx.printer
+                .println(format!("No usage recorded in {path}.{hint}"));
+            return Ok(());
+        }
+
+        let summary = Summary::new(&records).truncate(self.limit);
+        if ctx.printer.format().is_json() {
+            print_json(&ctx.printer, &serde_json::to_value(&summary)?);
+            return Ok(());
+        }
+
+        summary.print(ctx);
+        Ok(())
+    }
+}
+
+/// A summary of the usage log.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub(crate) struct Summary {
+    /// The commands that were run, most used first.
+    commands: Vec<Count>,
+
+    /// The flags commands were given, most used first.
+    flags: Vec<Count>,
+
+    /// The models that ran turns, most used first.
+    models: Vec<Count>,
+
+    /// The assistant names that ran turns, most used first.
+    personas: Vec<Count>,
+
+    /// The tools that were called, most used first.
+    tools: Vec<ToolStats>,
+
+    /// The providers that ran turns, most used first.
+    providers: Vec<ProviderStats>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Count {
+    name: String,
+    count: u64,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct ToolStats {
+    name: String,
+    calls: u64,
+    unattended: u64,
+    approved: u64,
+    denied: u64,
+}
+
+impl ToolStats {
+    /// The share of prompted calls the user approved, if any were prompted.
+    fn approval_rate(&self) -> Option<f64> {
+        let prompted = self.approved + self.denied;
+        (prompted > 0).then(|| ratio(self.approved, prompted))
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct ProviderStats {
+    name: String,
+    turns: u64,
+    failures: u64,
+    average_latency_ms: u64,
+}
+
+impl ProviderStats {
+    fn failure_rate(&self) -> f64 {
+        ratio(self.failures, self.turns)
+    }
+}
+
+#[expect(clippy::cast_precision_loss)]
+fn ratio(part: u64, total: u64) -> f64 {
+    part as f64 / total as f64
+}
+
+impl Summary {
+    pub(crate) fn new(records: &[UsageRecord]) -> Self {
+        let mut commands = BTreeMap::<&str, u64>::new();
+        let mut flags = BTreeMap::<&str, u64>::new();
+        let mut models = BTreeMap::<&str, u64>::new();
+        let mut personas = BTreeMap::<&str, u64>::new();
+        let mut tools = BTreeMap::<&str, ToolStats>::new();
+        let mut providers = BTreeMap::<&str, (ProviderStats, u64)>::new();
+
+        for record in records {
+            *commands.entry(&record.command).or_default() += 1;
+            for flag in &record.flags {
+                *flags.entry(flag).or_default() += 1;
+            }
+
+            for turn in &record.turns {
+                *models.entry(&turn.model).or_default() += 1;
+                if let Some(persona) = &turn.persona {
+                    *personas.entry(persona).or_default() += 1;
+                }
+
+                let (provider, total_latency) = providers.entry(&turn.provider).or_default();
+                provider.turns += 1;
+                provider.failures += u64::from(!turn.success);
+                *total_latency += turn.latency_ms;
+
+                for tool in &turn.tools {
+                    let stats = tools.entry(&tool.name).or_default();
+                    stats.calls += 1;
+                    match tool.decision {
+                        ToolDecision::Unattended => stats.unattended += 1,
+                        ToolDecision::Approved => stats.approved += 1,
+                        ToolDecision::Denied => stats.denied += 1,
+                    }
+                }
+            }
+        }
+
+        let mut tools = tools
+            .into_iter()
+            .map(|(name, stats)| ToolStats {
+                name: name.to_owned(),
+                ..stats
+            })
+            .collect::<Vec<_>>();
+        tools.sort_by(|a, b| b.calls.cmp(&a.calls));
+
+        let mut providers = providers
+            .into_iter()
+            .map(|(name, (stats, total_latency))| ProviderStats {
+                name: name.to_owned(),
+                average_latency_ms: total_latency / stats.turns,
+                ..stats
+            })
+            .collect::<Vec<_>>();
+        providers.sort_by(|a, b| b.turns.cmp(&a.turns));
+
+        Self {
+            commands: counts(commands),
+            flags: counts(flags),
+            models: counts(models),
+            personas: counts(personas),
+            tools,
+            providers,
+        }
+    }
+
+    /// Keep the first `limit` entries of every section.
+    fn truncate(mut self, limit: usize) -> Self {
+        self.commands.truncate(limit);
+        self.flags.truncate(limit);
+        self.models.truncate(limit);
+        self.personas.truncate(limit);
+        self.tools.truncate(limit);
+        self.providers.truncate(limit);
+        self
+    }
+
+    fn print(&self, ctx: &Ctx) {
+        let sections = [
+            ("Command", &self.commands),
+            ("Flag", &self.flags),
+            ("Model", &self.models),
+            ("Persona", &self.personas),
+        ];
+
+        for (title, counts) in sections {
+            if counts.is_empty() {
+                continue;
+            }
+
+            let rows = counts
+                .iter()
+                .map(|count| row([count.name.clone(), count.count.to_string()]))
+                .collect();
+
+            print_table(&ctx.printer, row([title, "Count"]), rows, false);
+        }
+
+        if !self.tools.is_empty() {
+            let rows = self
+                .tools
+                .iter()
+                .map(|tool| {
+                    row([
+                        tool.name.clone(),
+                        tool.calls.to_string(),
+                        tool.unattended.to_string(),
+                        tool.approved.to_string(),
+                        tool.denied.to_string(),
+                        tool.approval_rate().map(percent).unwrap_or_default(),
+                    ])
+                })
+                .collect();
+
+            print_table(
+                &ctx.printer,
+                row([
+                    "Tool",
+                    "Calls",
+                    "Unattended",
+                    "Approved",
+                    "Denied",
+                    "Approval rate",
+                ]),
+                rows,
+                false,
+            );
+        }
+
+        if !self.providers.is_empty() {
+            let rows = self
+                .providers
+                .iter()
+                .map(|provider| {
+                    row([
+                        provider.name.clone(),
+                        provider.turns.to_string(),
+                        format!("{:.1}s", ratio(provider.average_latency_ms, 1000)),
+                        percent(provider.failure_rate()),
+                    ])
+                })
+                .collect();
+
+            print_table(
+                &ctx.printer,
+                row(["Provider", "Turns", "Average latency", "Failure rate"]),
+                rows,
+                false,
+            );
+        }
+    }
+}
+
+/// Sort counts, most used first, and by name for equal counts.
+fn counts(counts: BTreeMap<&str, u64>) -> Vec<Count> {
+    let mut counts = counts
+        .into_iter()
+        .map(|(name, count)| Count {
+            name: name.to_owned(),
+            count,
+        })
+        .collect::<Vec<_>>();
+
+    counts.sort_by(|a, b| b.count.cmp(&a.count));
+    counts
+}
+
+fn row(cells: impl IntoIterator<Item = impl Into<String>>) -> Row {
+    let mut row = Row::new();
+    for cell in cells {
+        row.add_cell(Cell::new(cell.into()));
+    }
+    row
+}
+
+fn percent(ratio: f64) -> String {
+    format!("{:.0}%", ratio * 100.0)
+}
+
+#[cfg(test)]
+#[path = "stats_tests.rs"]
+mod tests;
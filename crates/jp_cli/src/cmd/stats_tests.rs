@@ -0,0 +1,164 @@
+use chrono::{TimeZone as _, Utc};
+use pretty_assertions::assert_eq;
+
+use super::*;
+use crate::usage::{ToolUsage, TurnUsage};
+
+fn turn(provider: &str, model: &str, latency_ms: u64, success: bool) -> TurnUsage {
+    TurnUsage {
+        provider: provider.to_owned(),
+        model: model.to_owned(),
+        persona: None,
+        latency_ms,
+        success,
+        tools: vec![],
+    }
+}
+
+fn record(command: &str, turns: Vec<TurnUsage>) -> UsageRecord {
+    UsageRecord {
+        timestamp: Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        command: command.to_owned(),
+        flags: vec![],
+        duration_ms: 0,
+        success: true,
+        turns,
+    }
+}
+
+fn tool(name: &str, decision: ToolDecision) -> ToolUsage {
+    ToolUsage {
+        name: name.to_owned(),
+        decision,
+    }
+}
+
+#[test]
+fn test_summary_empty() {
+    assert_eq!(Summary::new(&[]), Summary::default());
+}
+
+#[test]
+fn test_summary_counts_most_used_first() {
+    let mut persona_turn = turn("openai", "openai/gpt-5", 100, true);
+    persona_turn.persona = Some("reviewer".to_owned());
+
+    let records = vec![
+        record("config", vec![]),
+        record("query", vec![turn(
+            "anthropic",
+            "anthropic/claude",
+            100,
+            true,
+        )]),
+        record("query", vec![
+            turn("anthropic", "anthropic/claude", 300, false),
+            persona_turn,
+        ]),
+    ];
+
+    let summary = Summary::new(&records);
+    assert_eq!(summary.commands, vec![
+        Count {
+            name: "query".to_owned(),
+            count: 2,
+        },
+        Count {
+            name: "config".to_owned(),
+            count: 1,
+        },
+    ]);
+    assert_eq!(summary.models, vec![
+        Count {
+            name: "anthropic/claude".to_owned(),
+            count: 2,
+        },
+        Count {
+            name: "openai/gpt-5".to_owned(),
+            count: 1,
+        },
+    ]);
+    assert_eq!(summary.personas, vec![Count {
+        name: "reviewer".to_owned(),
+        count: 1,
+    }]);
+    assert_eq!(summary.providers, vec![
+        ProviderStats {
+            name: "anthropic".to_owned(),
+            turns: 2,
+            failures: 1,
+            average_latency_ms: 200,
+        },
+        ProviderStats {
+            name: "openai".to_owned(),
+            turns: 1,
+            failures: 0,
+            average_latency_ms: 100,
+        },
+    ]);
+    assert!((summary.providers[0].failure_rate() - 0.5).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_summary_tool_decisions() {
+    let mut first = turn("anthropic", "anthropic/claude", 0, true);
+    first.tools = vec![
+        tool("fs_modify_file", ToolDecision::Approved),
+        tool("fs_read_file", ToolDecision::Unattended),
+        tool("fs_modify_file", ToolDecision::Denied),
+    ];
+
+    let mut second = turn("anthropic", "anthropic/claude", 0, true);
+    second.tools = vec![tool("fs_modify_file", ToolDecision::Approved)];
+
+    let summary = Summary::new(&[record("query", vec![first, second])]);
+    assert_eq!(summary.tools, vec![
+        ToolStats {
+            name: "fs_modify_file".to_owned(),
+            calls: 3,
+            unattended: 0,
+            approved: 2,
+            denied: 1,
+        },
+        ToolStats {
+            name: "fs_read_file".to_owned(),
+            calls: 1,
+            unattended: 1,
+            approved: 0,
+            denied: 0,
+        },
+    ]);
+
+    let rate = summary.tools[0].approval_rate().unwrap();
+    assert!((rate - 2.0 / 3.0).abs() < f64::EPSILON);
+    assert_eq!(summary.tools[1].approval_rate(), None);
+}
+
+#[test]
+fn test_summary_flags() {
+    let mut first = record("query", vec![]);
+    first.flags = vec!["--model".to_owned(), "--new".to_owned()];
+
+    let mut second = record("query", vec![]);
+    second.flags = vec!["--model".to_owned()];
+
+    let summary = Summary::new(&[first, second, record("config", vec![])]);
+    assert_eq!(summary.flags, vec![
+        Count {
+            name: "--model".to_owned(),
+            count: 2,
+        },
+        Count {
+            name: "--new".to_owned(),
+            count: 1,
+        },
+    ]);
+}
+
+#[test]
+fn test_summary_truncate() {
+    let records = vec![record("query", vec![]), record("config", vec![])];
+
+    let summary = Summary::new(&records).truncate(1);
+    assert_eq!(summary.commands.len(), 1);
+}
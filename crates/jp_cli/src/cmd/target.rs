@@ -156,6 +156,19 @@ impl ConversationLoadRequest {
         }
     }
 
+    /// Use explicit targets with config loading if non-empty, otherwise no
+    /// conversations needed.
+    pub fn explicit_with_config_or_none(args: &dyn ConversationIds) -> Self {
+        if args.ids().is_empty() {
+            Self::none()
+        } else {
+            let mut req = Self::explicit_with_config(args.ids().to_vec());
+            req.multi = args.is_multi();
+            req.session = args.supports_session();
+            req
+        }
+    }
+
     /// Use explicit targets if non-empty, otherwise try the session's previous
     /// conversation, falling back to the interactive picker.
     pub fn explicit_or_previous(args: &dyn ConversationIds) -> Self {
@@ -320,6 +333,10 @@ impl PickerFilter {
         c: &jp_conversation::Conversation,
         is_session_conversation: bool,
     ) -> bool {
+        // Hidden conversations are only reachable by ID.
+        if c.hidden {
+            return false;
+        }
         if let Some(candidates) = &self.candidate_ids
             && !candidates.contains(&id)
         {
@@ -455,6 +472,7 @@ impl ConversationTarget {
             Self::Recent => {
                 let id = workspace
                     .conversations()
+                    .filter(|(_, c)| !c.hidden)
                     .max_by_key(|(_, c)| c.last_activated_at)
                     .map(|(id, _)| *id)
                     .ok_or_else(|| {
@@ -465,6 +483,7 @@ impl ConversationTarget {
             Self::Newest => {
                 let id = workspace
                     .conversations()
+                    .filter(|(_, c)| !c.hidden)
                     .max_by_key(|(id, _)| id.timestamp())
                     .map(|(id, _)| *id)
                     .ok_or_else(|| {
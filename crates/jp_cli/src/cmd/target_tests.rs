@@ -72,6 +72,34 @@ fn last_created_resolves() {
     );
 }
 
+#[test]
+fn recent_and_newest_skip_hidden_conversations() {
+    let mut ws = Workspace::in_memory(Utf8PathBuf::new());
+    let config = Arc::new(AppConfig::new_test());
+    let visible = make_id(1000);
+    let hidden = make_id(2000);
+    ws.create_conversation_with_id(visible, Conversation::default(), Arc::clone(&config));
+    ws.create_conversation_with_id(
+        hidden,
+        Conversation {
+            hidden: true,
+            ..Conversation::default()
+        },
+        config,
+    );
+
+    assert_eq!(ConversationTarget::Recent.resolve(&ws, None).unwrap(), [
+        visible
+    ]);
+    assert_eq!(ConversationTarget::Newest.resolve(&ws, None).unwrap(), [
+        visible
+    ]);
+    assert_eq!(
+        ConversationTarget::Id(hidden).resolve(&ws, None).unwrap(),
+        [hidden]
+    );
+}
+
 #[test]
 fn last_activated_empty_workspace_returns_none() {
     let ws = Workspace::in_memory(Utf8PathBuf::new());
@@ -0,0 +1,34 @@
+//! `jp template` subcommands for inspecting named query templates.
+//!
+//! Named templates are run with `jp query --named-template NAME`.
+
+mod ls;
+mod show;
+
+use crate::{cmd::Output, ctx::Ctx};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Template {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+impl Template {
+    pub(crate) fn run(self, ctx: &mut Ctx) -> Output {
+        match self.command {
+            Commands::List(args) => args.run(ctx),
+            Commands::Show(args) => args.run(ctx),
+        }
+    }
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Commands {
+    /// List the named query templates.
+    #[command(name = "ls", alias = "list")]
+    List(ls::Ls),
+
+    /// Show a named query template.
+    #[command(name = "show")]
+    Show(show::Show),
+}
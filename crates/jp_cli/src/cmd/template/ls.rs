@@ -0,0 +1,47 @@
+use comfy_table::{Cell, Row};
+
+use crate::{cmd::Output, ctx::Ctx, output::print_table};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Ls {}
+
+impl Ls {
+    #[expect(clippy::unused_self, clippy::unnecessary_wraps)]
+    pub(crate) fn run(self, ctx: &mut Ctx) -> Output {
+        let config = ctx.config();
+        let templates = &config.template.named;
+
+        if templates.is_empty() && !ctx.printer.format().is_json() {
+            ctx.printer.println("No templates defined.");
+            return Ok(());
+        }
+
+        let mut header = Row::new();
+        header.add_cell(Cell::new("Name"));
+        header.add_cell(Cell::new("Parameters"));
+        header.add_cell(Cell::new("Description"));
+
+        let rows = templates
+            .iter()
+            .map(|(name, template)| {
+                let params = template
+                    .params
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                let mut row = Row::new();
+                row.add_cell(Cell::new(name));
+                row.add_cell(Cell::new(params));
+                row.add_cell(Cell::new(
+                    template.description.as_deref().unwrap_or_default(),
+                ));
+                row
+            })
+            .collect();
+
+        print_table(&ctx.printer, header, rows, false);
+        Ok(())
+    }
+}
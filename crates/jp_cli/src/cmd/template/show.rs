@@ -0,0 +1,105 @@
+use jp_config::template::named::{NamedTemplateConfig, TemplateParamConfig};
+use jp_term::table::{DetailItem, DetailRow, Details};
+use serde_json::json;
+
+use crate::{cmd::Output, ctx::Ctx, error::Error, output::print_details};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Show {
+    /// The name of the template.
+    name: String,
+}
+
+impl Show {
+    pub(crate) fn run(self, ctx: &mut Ctx) -> Output {
+        let config = ctx.config();
+        let template = config
+            .template
+            .named
+            .get(&self.name)
+            .ok_or_else(|| Error::NamedTemplateNotFound(self.name.clone()))?;
+
+        print_details(
+            &ctx.printer,
+            Some(&self.name),
+            Details::Fields(rows(template)),
+        );
+        Ok(())
+    }
+}
+
+/// The details rows of a template, omitting unset fields.
+fn rows(template: &NamedTemplateConfig) -> Vec<DetailRow> {
+    let mut rows = vec![];
+
+    if let Some(description) = &template.description {
+        rows.push(DetailRow::scalar("Description", description));
+    }
+
+    if !template.params.is_empty() {
+        let params = template
+            .params
+            .iter()
+            .map(|(name, param)| param_item(name, param))
+            .collect();
+        rows.push(DetailRow::list("Parameters", params));
+    }
+
+    if let Some(model) = &template.model {
+        rows.push(DetailRow::scalar("Model", model));
+    }
+
+    for (label, values) in [
+        ("Tools", &template.tools),
+        ("Attachments", &template.attachments),
+    ] {
+        if let Some(values) = values {
+            let items = values.iter().map(DetailItem::plain).collect();
+            rows.push(DetailRow::list(label, items));
+        }
+    }
+
+    if let Some(schema) = &template.schema {
+        let schema = match schema {
+            serde_json::Value::String(dsl) => dsl.clone(),
+            schema => schema.to_string(),
+        };
+        rows.push(DetailRow::scalar("Schema", schema));
+    }
+
+    rows.push(DetailRow::scalar(
+        "Content",
+        template.content.as_deref().unwrap_or_default(),
+    ));
+
+    rows
+}
+
+/// A parameter, as `name (type, default: value): description`.
+fn param_item(name: &str, param: &TemplateParamConfig) -> DetailItem {
+    let kind = serde_json::to_value(param.kind).unwrap_or_default();
+    let kind = kind.as_str().unwrap_or_default();
+
+    let mut text = match &param.default {
+        Some(default) => format!("{name} ({kind}, default: {default})"),
+        None => format!("{name} ({kind})"),
+    };
+    if let Some(description) = &param.description {
+        text.push_str(": ");
+        text.push_str(description);
+    }
+
+    DetailItem::new(
+        text,
+        json!({
+            "name": name,
+            "type": kind,
+            "default": param.default,
+            "description": param.description,
+        }),
+    )
+}
+
+#[cfg(test)]
+#[path = "show_tests.rs"]
+mod tests;
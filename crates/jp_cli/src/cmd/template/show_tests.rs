@@ -0,0 +1,54 @@
+use jp_config::{Config as _, template::named::PartialNamedTemplateConfig};
+use jp_term::table::DetailValue;
+
+use super::*;
+
+fn template(toml: &str) -> NamedTemplateConfig {
+    let partial: PartialNamedTemplateConfig = toml::from_str(toml).unwrap();
+    NamedTemplateConfig::from_partial(partial, vec![]).unwrap()
+}
+
+fn labels(rows: &[DetailRow]) -> Vec<&str> {
+    rows.iter().map(|row| row.label.as_str()).collect()
+}
+
+#[test]
+fn rows_omit_unset_fields() {
+    let rows = rows(&template(r#"content = "Hello.""#));
+
+    assert_eq!(labels(&rows), vec!["Content"]);
+}
+
+#[test]
+fn rows_describe_params() {
+    let rows = rows(&template(
+        r#"
+        description = "Review a pull request."
+        content = "Review #{{ pr }}."
+        tools = ["fs_read_file"]
+
+        [params.pr]
+        type = "integer"
+        description = "The pull request number"
+
+        [params.focus]
+        default = "correctness"
+        "#,
+    ));
+
+    assert_eq!(labels(&rows), vec![
+        "Description",
+        "Parameters",
+        "Tools",
+        "Content"
+    ]);
+
+    let DetailValue::List(params) = &rows[1].value else {
+        panic!("expected a list of parameters");
+    };
+    let params: Vec<_> = params.iter().map(|item| item.text.as_str()).collect();
+    assert_eq!(params, vec![
+        "pr (integer): The pull request number",
+        "focus (string, default: \"correctness\")",
+    ]);
+}
@@ -0,0 +1,97 @@
+//! `jp undo`: restore the workspace files that tools changed.
+//!
+//! See [`super::checkpoint`] for how the changes are recorded.
+
+use std::fmt::Write as _;
+
+use inquire::Confirm;
+use jp_workspace::ConversationHandle;
+
+use super::checkpoint::{TurnCheckpoints, resolve_turn};
+use crate::{
+    cmd::{ConversationLoadRequest, Output, conversation_id::PositionalIds},
+    ctx::Ctx,
+    shared::confirm::ConfirmFlag,
+};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Undo {
+    #[command(flatten)]
+    target: PositionalIds<true, false>,
+
+    /// The 1-based turn to undo.
+    ///
+    /// The changes of this turn and every later turn are undone.
+    /// Defaults to the most recent turn that changed files.
+    #[arg(long)]
+    turn: Option<usize>,
+
+    /// Confirmation prompting: `--confirm`, `--no-confirm`, or `--yes`.
+    ///
+    /// Prompts by default when running in a terminal.
+    #[command(flatten)]
+    confirm: ConfirmFlag,
+}
+
+impl Undo {
+    pub(crate) fn conversation_load_request(&self) -> ConversationLoadRequest {
+        ConversationLoadRequest::explicit_or_session(&self.target)
+    }
+
+    pub(crate) fn run(self, ctx: &mut Ctx, handles: Vec<ConversationHandle>) -> Output {
+        let fs = ctx
+            .fs_backend
+            .as_deref()
+            .ok_or("no filesystem storage configured")?;
+
+        let Some(handle) = handles.into_iter().next() else {
+            return Ok(());
+        };
+
+        let checkpoints = TurnCheckpoints::new(fs, &handle.id())
+            .ok_or("no user storage configured, so no checkpoints are kept")?
+            .with_turns(&ctx.workspace.events(&handle)?);
+        let turn = resolve_turn(&checkpoints, self.turn)?;
+        let turns = checkpoints
+            .turns()
+            .map_err(|error| error.to_string())?
+            .into_iter()
+            .filter(|&t| t >= turn)
+            .collect::<Vec<_>>();
+
+        if turns.is_empty() {
+            ctx.printer
+                .println(format!("Turn {turn} and later turns changed no files."));
+            return Ok(());
+        }
+
+        let prompt = self.confirm.preference().unwrap_or(ctx.term.is_tty);
+        if prompt {
+            let mut summary = format!("Restoring files changed by turn {turn}");
+            if turns.len() > 1 {
+                summary.push_str(" and later turns");
+            }
+
+            writeln!(ctx.printer.prompt_writer(), "{summary}.\n")?;
+            let confirm = Confirm::new("Are you sure?")
+                .with_default(false)
+                .with_confirm_on_input(true)
+                .with_help_message("uncommitted edits to these files are overwritten");
+
+            match confirm.prompt_with_writer(&mut ctx.printer.prompt_writer()) {
+                Ok(true) => {}
+                Ok(false) | Err(_) => return Err(1.into()),
+            }
+        }
+
+        let restored = checkpoints
+            .undo(ctx.workspace.root(), turn)
+            .map_err(|error| error.to_string())?;
+
+        for path in &restored {
+            ctx.printer.println(format!("Restored {path}"));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,342 @@
+//! Dynamic shell completions.
+//!
+//! The script printed by `jp completions <shell>` registers `jp` as its own
+//! completer: when the user presses Tab, the shell calls `jp` again with
+//! [`COMPLETE_VAR`] set, and [`complete`] answers with the candidates for the
+//! word under the cursor, before the command line is parsed.
+//!
+//! Flags and subcommands come from the clap command tree.
+//! Values that depend on the workspace or the configuration are produced by the
+//! candidate functions in this module, which are attached to their arguments
+//! with `ArgValueCandidates` or `ArgValueCompleter`.
+//!
+//! Completion is best-effort.
+//! Outside a workspace, or with a configuration file that fails to load, a
+//! candidate function returns nothing rather than an error, as there is no
+//! sensible way to report one in the middle of a completion.
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsStr,
+    path::MAIN_SEPARATOR,
+};
+
+use camino::{Utf8Path, Utf8PathBuf, absolute_utf8};
+use clap::{Arg, ArgAction, Command, CommandFactory as _};
+use clap_complete::{CompleteEnv, engine::CompletionCandidate};
+use jp_config::{
+    AppConfig, PartialAppConfig, env::Env, model::id::PartialModelIdOrAliasConfig,
+    util::VALID_CONFIG_FILE_EXTS,
+};
+use jp_workspace::Workspace;
+
+use crate::{
+    Cli, cmd::plugin::dispatch::discover_plugins, config_pipeline::cfg_search_roots,
+    load_base_partial,
+};
+
+/// The environment variable the shell sets to request completions.
+pub(crate) const COMPLETE_VAR: &str = "JP_COMPLETE";
+
+/// How deep to look for configuration files below a `config_load_paths`
+/// directory.
+const MAX_CFG_DEPTH: usize = 4;
+
+/// Answer a completion request from the shell.
+///
+/// Prints the candidates and exits the process if [`COMPLETE_VAR`] is set,
+/// returns without doing anything otherwise.
+pub(crate) fn complete() {
+    CompleteEnv::with_factory(command)
+        .var(COMPLETE_VAR)
+        .complete();
+}
+
+/// The command tree to complete against, including installed command plugins.
+fn command() -> Command {
+    let mut cmd = Cli::command();
+    for (name, _) in discover_plugins() {
+        if cmd.find_subcommand(&name).is_some() {
+            continue;
+        }
+
+        cmd = cmd.subcommand(plugin_command(name));
+    }
+
+    cmd
+}
+
+/// A command plugin, accepting any arguments.
+///
+/// Plugins are not asked to describe themselves, as that runs the plugin
+/// binary on every Tab press.
+fn plugin_command(name: String) -> Command {
+    Command::new(name)
+        .about("Command plugin")
+        .disable_help_flag(true)
+        .arg(
+            Arg::new("args")
+                .action(ArgAction::Append)
+                .num_args(0..)
+                .trailing_var_arg(true)
+                .allow_hyphen_values(true),
+        )
+}
+
+/// Live conversations in the workspace, most recently activated first, with
+/// their titles as help.
+pub(crate) fn conversations() -> Vec<CompletionCandidate> {
+    let Some(workspace) = open_workspace() else {
+        return vec![];
+    };
+
+    let mut conversations: Vec<_> = workspace
+        .conversations()
+        .filter(|(_, conversation)| !conversation.hidden)
+        .map(|(id, conversation)| {
+            (
+                *id,
+                conversation.last_activated_at,
+                conversation.title.clone(),
+            )
+        })
+        .collect();
+    conversations.sort_by_key(|(_, activated, _)| Reverse(*activated));
+
+    conversations
+        .into_iter()
+        .map(|(id, _, title)| CompletionCandidate::new(id.to_string()).help(title.map(Into::into)))
+        .collect()
+}
+
+/// Label keys used in the workspace, or `key=value` pairs once the key is
+/// typed.
+pub(crate) fn labels(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return vec![];
+    };
+
+    label_candidates(&workspace_labels(), current)
+}
+
+/// Label keys used in the workspace.
+pub(crate) fn label_keys() -> Vec<CompletionCandidate> {
+    label_candidates(&workspace_labels(), "")
+}
+
+fn workspace_labels() -> Vec<BTreeMap<String, String>> {
+    let Some(workspace) = open_workspace() else {
+        return vec![];
+    };
+
+    workspace
+        .conversations()
+        .map(|(_, conversation)| conversation.labels.clone())
+        .collect()
+}
+
+fn label_candidates(
+    labels: &[BTreeMap<String, String>],
+    current: &str,
+) -> Vec<CompletionCandidate> {
+    let mut values: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for (key, value) in labels.iter().flatten() {
+        values.entry(key).or_default().insert(value);
+    }
+
+    if let Some((key, _)) = current.split_once('=') {
+        return values
+            .get(key)
+            .into_iter()
+            .flatten()
+            .filter(|value| !value.is_empty())
+            .map(|value| format!("{key}={value}"))
+            .filter(|label| label.starts_with(current))
+            .map(CompletionCandidate::new)
+            .collect();
+    }
+
+    values
+        .into_iter()
+        .filter(|(key, _)| key.starts_with(current))
+        .map(|(key, values)| {
+            let values: Vec<_> = values.into_iter().filter(|v| !v.is_empty()).collect();
+            let help = (!values.is_empty()).then(|| values.join(", ").into());
+
+            CompletionCandidate::new(key).help(help)
+        })
+        .collect()
+}
+
+/// Model aliases configured in `providers.llm.aliases`, with the model they
+/// point at as help.
+pub(crate) fn models() -> Vec<CompletionCandidate> {
+    load_config().map_or_else(Vec::new, |(_, partial)| model_candidates(&partial))
+}
+
+fn model_candidates(partial: &PartialAppConfig) -> Vec<CompletionCandidate> {
+    partial
+        .providers
+        .llm
+        .aliases
+        .iter()
+        .map(|(alias, target)| {
+            let help = match target {
+                PartialModelIdOrAliasConfig::Id(id) => id.to_string(),
+                PartialModelIdOrAliasConfig::Alias(alias) => alias.clone(),
+            };
+
+            CompletionCandidate::new(alias).help(Some(help.into()))
+        })
+        .collect()
+}
+
+/// Tools configured in `conversation.tools`, with their summaries as help.
+pub(crate) fn tools() -> Vec<CompletionCandidate> {
+    load_config().map_or_else(Vec::new, |(_, partial)| tool_candidates(&partial))
+}
+
+fn tool_candidates(partial: &PartialAppConfig) -> Vec<CompletionCandidate> {
+    partial
+        .conversation
+        .tools
+        .tools
+        .iter()
+        .map(|(name, tool)| {
+            CompletionCandidate::new(name).help(tool.summary.clone().map(Into::into))
+        })
+        .collect()
+}
+
+/// Configuration files under the `config_load_paths` directories, as they are
+/// passed to `--cfg`, and configuration keys once a `.` is typed.
+pub(crate) fn cfg(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return vec![];
+    };
+
+    // Assignments take arbitrary values, and `@` marks a path on disk rather
+    // than a file in a load path.
+    if current.contains('=') || current.starts_with(['{', '@']) {
+        return vec![];
+    }
+
+    let Some((workspace, partial)) = load_config() else {
+        return vec![];
+    };
+
+    let fs = workspace.as_ref().and_then(Workspace::fs_storage).cloned();
+    let roots = cfg_search_roots(
+        workspace.as_ref().map(Workspace::root),
+        fs.as_deref(),
+        &Env::process(),
+    );
+    let load_paths: Vec<_> = roots
+        .iter()
+        .flat_map(|root| {
+            partial
+                .config_load_paths
+                .iter()
+                .flatten()
+                .filter_map(|path| Utf8PathBuf::try_from(path.to_path(root)).ok())
+        })
+        .collect();
+
+    let mut candidates = cfg_file_candidates(&load_paths, current);
+    if current.contains('.') {
+        candidates.extend(
+            AppConfig::fields()
+                .into_iter()
+                .filter(|field| field.starts_with(current))
+                .map(|field| CompletionCandidate::new(format!("{field}="))),
+        );
+    }
+
+    candidates
+}
+
+/// The names of the configuration files below `load_paths` that start with
+/// `current`, relative to their load path and without extension.
+fn cfg_file_candidates(load_paths: &[Utf8PathBuf], current: &str) -> Vec<CompletionCandidate> {
+    let mut names = BTreeSet::new();
+    for load_path in load_paths {
+        collect_cfg_files(load_path, load_path, 0, &mut names);
+    }
+
+    names
+        .into_iter()
+        .filter(|name| name.starts_with(current))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+fn collect_cfg_files(
+    load_path: &Utf8Path,
+    dir: &Utf8Path,
+    depth: usize,
+    names: &mut BTreeSet<String>,
+) {
+    let Ok(entries) = dir.read_dir_utf8() else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().starts_with('.') {
+            continue;
+        }
+
+        if path.is_dir() {
+            if depth < MAX_CFG_DEPTH {
+                collect_cfg_files(load_path, path, depth + 1, names);
+            }
+            continue;
+        }
+
+        if !path
+            .extension()
+            .is_some_and(|ext| VALID_CONFIG_FILE_EXTS.contains(&ext))
+        {
+            continue;
+        }
+
+        let Ok(relative) = path
+            .with_extension("")
+            .strip_prefix(load_path)
+            .map(Utf8Path::to_owned)
+        else {
+            continue;
+        };
+
+        names.insert(relative.as_str().replace(MAIN_SEPARATOR, "/"));
+    }
+}
+
+/// Open the workspace containing the current directory, with its conversation
+/// index loaded.
+fn open_workspace() -> Option<Workspace> {
+    let cwd = absolute_utf8(".").ok()?;
+    let mut workspace = Workspace::open(&cwd).ok()?;
+    workspace.load_conversation_index();
+
+    Some(workspace)
+}
+
+/// Load the configuration from files and environment variables.
+///
+/// Works outside a workspace, in which case only the user-global and current
+/// directory files are loaded.
+fn load_config() -> Option<(Option<Workspace>, PartialAppConfig)> {
+    let workspace = absolute_utf8(".")
+        .ok()
+        .and_then(|cwd| Workspace::open(&cwd).ok());
+    let fs = workspace.as_ref().and_then(Workspace::fs_storage).cloned();
+    let partial = load_base_partial(fs.as_deref(), &Env::process()).ok()?;
+
+    Some((workspace, partial))
+}
+
+#[cfg(test)]
+#[path = "completion_tests.rs"]
+mod tests;
@@ -0,0 +1,139 @@
+use std::fs;
+
+use camino_tempfile::tempdir;
+use clap::CommandFactory as _;
+use clap_complete::engine::ArgValueCandidates;
+use pretty_assertions::assert_eq;
+
+use super::*;
+
+fn values(candidates: &[CompletionCandidate]) -> Vec<String> {
+    candidates
+        .iter()
+        .map(|c| c.get_value().to_string_lossy().into_owned())
+        .collect()
+}
+
+fn helps(candidates: &[CompletionCandidate]) -> Vec<Option<String>> {
+    candidates
+        .iter()
+        .map(|c| c.get_help().map(ToString::to_string))
+        .collect()
+}
+
+#[test]
+fn test_label_candidates() {
+    let labels = vec![
+        BTreeMap::from([
+            ("kind".to_owned(), "bug".to_owned()),
+            ("wip".to_owned(), String::new()),
+        ]),
+        BTreeMap::from([
+            ("kind".to_owned(), "feature".to_owned()),
+            ("team".to_owned(), "core".to_owned()),
+        ]),
+    ];
+
+    let candidates = label_candidates(&labels, "");
+    assert_eq!(values(&candidates), ["kind", "team", "wip"]);
+    assert_eq!(helps(&candidates), [
+        Some("bug, feature".to_owned()),
+        Some("core".to_owned()),
+        None
+    ]);
+
+    assert_eq!(values(&label_candidates(&labels, "t")), ["team"]);
+    assert_eq!(values(&label_candidates(&labels, "kind=")), [
+        "kind=bug",
+        "kind=feature"
+    ]);
+    assert_eq!(values(&label_candidates(&labels, "kind=f")), [
+        "kind=feature"
+    ]);
+    assert!(label_candidates(&labels, "wip=").is_empty());
+}
+
+#[test]
+fn test_model_and_tool_candidates() {
+    let partial: PartialAppConfig = toml::from_str(indoc::indoc! {r#"
+        [providers.llm.aliases]
+        opus = "anthropic/claude-opus-4-6"
+        coder = "opus"
+
+        [conversation.tools.'*']
+        run = "ask"
+
+        [conversation.tools.fs_read_file]
+        summary = "Read a file"
+
+        [conversation.tools.git_diff]
+    "#})
+    .unwrap();
+
+    let candidates = model_candidates(&partial);
+    assert_eq!(values(&candidates), ["opus", "coder"]);
+    assert_eq!(helps(&candidates), [
+        Some("anthropic/claude-opus-4-6".to_owned()),
+        Some("opus".to_owned())
+    ]);
+
+    let candidates = tool_candidates(&partial);
+    assert_eq!(values(&candidates), ["fs_read_file", "git_diff"]);
+    assert_eq!(helps(&candidates), [Some("Read a file".to_owned()), None]);
+}
+
+#[test]
+fn test_cfg_file_candidates() {
+    let tmp = tempdir().unwrap();
+    let global = tmp.path().join("global");
+    let workspace = tmp.path().join("workspace");
+
+    for path in [
+        global.join("persona/dev.toml"),
+        global.join("skill/web.yaml"),
+        workspace.join("persona/dev.json"),
+        workspace.join("persona/review.toml"),
+        workspace.join("persona/notes.md"),
+        workspace.join(".hidden/secret.toml"),
+    ] {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "").unwrap();
+    }
+
+    let load_paths = [global, workspace, tmp.path().join("missing")];
+
+    assert_eq!(values(&cfg_file_candidates(&load_paths, "")), [
+        "persona/dev",
+        "persona/review",
+        "skill/web"
+    ]);
+    assert_eq!(values(&cfg_file_candidates(&load_paths, "persona/r")), [
+        "persona/review"
+    ]);
+}
+
+#[test]
+fn test_plugins_complete_as_subcommands() {
+    let cmd = Cli::command().subcommand(plugin_command("serve".to_owned()));
+    cmd.clone().debug_assert();
+
+    let serve = cmd.find_subcommand("serve").unwrap();
+    assert_eq!(
+        serve.get_about().map(ToString::to_string).as_deref(),
+        Some("Command plugin")
+    );
+}
+
+#[test]
+fn test_value_completers_are_attached() {
+    let cmd = Cli::command();
+    let query = cmd.find_subcommand("query").unwrap();
+
+    for id in ["model", "tools", "no_tools", "id"] {
+        let arg = query
+            .get_arguments()
+            .find(|arg| arg.get_id() == id)
+            .unwrap();
+        assert!(arg.get::<ArgValueCandidates>().is_some(), "{id}");
+    }
+}
@@ -10,18 +10,25 @@
 //! Command-specific CLI overrides (`apply_cli_config`) are applied by the
 //! caller after each build — they're not part of the pipeline because they
 //! depend on the specific command struct.
+//!
+//! Files in `config_load_paths` whose activation conditions match are applied
+//! as if they were passed with `--cfg`, before any explicit `--cfg` argument.
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::Timelike as _;
+use duct::cmd;
 use jp_config::{
     PartialAppConfig,
+    activation::{ActivationContext, Condition, PartialActivationConfig},
     assignment::{AssignKeyValue as _, KvAssignment},
+    env::Env,
     fs::{load_partial, user_global_config_dir},
-    util::{find_file_in_load_path, load_partial_at_path},
+    util::{VALID_CONFIG_FILE_EXTS, find_file_in_load_path, load_partial_at_path},
 };
 use jp_storage::backend::FsStorageBackend;
 use jp_workspace::Workspace;
 use relative_path::RelativePath;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 use super::KeyValueOrPath;
 use crate::error::{Error, Result};
@@ -35,8 +42,9 @@ enum ResolvedCfgArg {
     /// A key=value assignment (e.g. `--cfg conversation.default_id=last`).
     KeyValue(KvAssignment),
 
-    /// One or more partials loaded from a config file path.
-    Partials(Vec<PartialAppConfig>),
+    /// One or more partials loaded from a config file path, with the file each
+    /// was loaded from.
+    Partials(Vec<(Utf8PathBuf, PartialAppConfig)>),
 }
 
 /// Config sources loaded once from disk, reusable for multiple builds.
@@ -57,13 +65,53 @@ impl ConfigPipeline {
     ///
     /// This is the only place where config files and `--cfg` file args are read
     /// from disk.
+    /// Activation conditions are evaluated against `env`.
     pub fn new(
         base: PartialAppConfig,
         overrides: &[KeyValueOrPath],
         workspace: Option<&Workspace>,
         fs: Option<&FsStorageBackend>,
+        env: &Env,
     ) -> Result<Self> {
-        let cfg_args = resolve_cfg_args(overrides, &base, workspace, fs)?;
+        let mut cfg_args = resolve_cfg_args(overrides, &base, workspace, fs, env)?;
+
+        let explicit = cfg_args
+            .iter()
+            .filter_map(|arg| match arg {
+                ResolvedCfgArg::Partials(partials) => Some(partials),
+                ResolvedCfgArg::KeyValue(_) => None,
+            })
+            .flatten()
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+
+        let root = workspace.map(Workspace::root);
+        let active = find_conditional_files(&base, &explicit, root, fs, env)
+            .into_iter()
+            .filter(ConditionalFile::is_active)
+            .map(|file| {
+                debug!(path = file.path.as_str(), "Activated configuration file.");
+                (file.path, file.partial)
+            })
+            .collect::<Vec<_>>();
+
+        if !active.is_empty() {
+            cfg_args.insert(0, ResolvedCfgArg::Partials(active));
+        }
+
+        // Activation conditions only decide whether a file is loaded. They are
+        // not part of the resulting configuration, which is persisted with
+        // each conversation.
+        let mut base = base;
+        base.activate = PartialActivationConfig::default();
+        for arg in &mut cfg_args {
+            if let ResolvedCfgArg::Partials(partials) = arg {
+                for (_, partial) in partials {
+                    partial.activate = PartialActivationConfig::default();
+                }
+            }
+        }
+
         Ok(Self { base, cfg_args })
     }
 
@@ -97,8 +145,8 @@ fn resolve_cfg_args(
     base: &PartialAppConfig,
     workspace: Option<&Workspace>,
     fs: Option<&FsStorageBackend>,
+    env: &Env,
 ) -> Result<Vec<ResolvedCfgArg>> {
-    let home = std::env::home_dir().and_then(|p| Utf8PathBuf::from_path_buf(p).ok());
     let mut resolved = Vec::with_capacity(overrides.len());
 
     for field in overrides {
@@ -106,33 +154,15 @@ fn resolve_cfg_args(
             KeyValueOrPath::Path(path) if path.exists() => {
                 let mut partials = Vec::new();
                 if let Some(p) = load_partial_at_path(path)? {
-                    partials.push(p);
+                    partials.push((path.clone(), p));
                 }
                 if !partials.is_empty() {
                     resolved.push(ResolvedCfgArg::Partials(partials));
                 }
             }
             KeyValueOrPath::Path(path) => {
-                // Build search roots in precedence order (lowest first).
-                //
-                // 1. User-global:    $XDG_CONFIG_HOME/jp/config/
-                // 2. Workspace:      <workspace_root>/
-                // 3. User-workspace: $XDG_DATA_HOME/jp/workspace/<name>-<id>/config/
-                let mut roots: Vec<Utf8PathBuf> = Vec::new();
-
-                if let Some(global_dir) = user_global_config_dir(home.as_deref()) {
-                    roots.push(global_dir.join("config"));
-                }
-                if let Some(w) = workspace {
-                    roots.push(w.root().to_owned());
-                }
-                if let Some(path) =
-                    fs.and_then(|f| f.user_storage_with_path(RelativePath::new("config")))
-                {
-                    roots.push(path);
-                }
-
-                let mut matches: Vec<PartialAppConfig> = Vec::new();
+                let roots = cfg_search_roots(workspace.map(Workspace::root), fs, env);
+                let mut matches: Vec<(Utf8PathBuf, PartialAppConfig)> = Vec::new();
                 let mut searched: Vec<Utf8PathBuf> = Vec::new();
 
                 // Search each root independently. Within a single root, the
@@ -166,9 +196,11 @@ fn resolve_cfg_args(
                             "Trying to load partial from config load path"
                         );
 
-                        if let Some(file) = find_file_in_load_path(path, load_path) {
-                            if let Some(p) = load_partial_at_path(file)? {
-                                matches.push(p);
+                        if let Some(file) = find_file_in_load_path(path, load_path)
+                            .and_then(|file| Utf8PathBuf::from_path_buf(file).ok())
+                        {
+                            if let Some(p) = load_partial_at_path(&file)? {
+                                matches.push((file, p));
                             }
 
                             break; // first match within this root
@@ -194,6 +226,264 @@ fn resolve_cfg_args(
     Ok(resolved)
 }
 
+/// A file in `config_load_paths` that declares activation conditions.
+#[derive(Debug, Clone)]
+pub(crate) struct ConditionalFile {
+    pub(crate) path: Utf8PathBuf,
+    pub(crate) partial: PartialAppConfig,
+
+    /// The conditions of the file, evaluated against the current environment.
+    pub(crate) conditions: Vec<Condition>,
+}
+
+impl ConditionalFile {
+    /// Whether all conditions of the file match.
+    pub fn is_active(&self) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.matched.is_some())
+    }
+}
+
+/// Find the files in `config_load_paths` that declare activation conditions,
+/// and evaluate their conditions.
+///
+/// This runs for every command, so only files that mention `activate` are
+/// parsed; the conditions of a file have to be declared in the file itself.
+/// Files in `explicit` are skipped, as they are already loaded with `--cfg`.
+/// Files that fail to load are skipped as well, as they might never be used.
+pub(crate) fn find_conditional_files(
+    base: &PartialAppConfig,
+    explicit: &[Utf8PathBuf],
+    workspace_root: Option<&Utf8Path>,
+    fs: Option<&FsStorageBackend>,
+    env: &Env,
+) -> Vec<ConditionalFile> {
+    let is_explicit = |path: &Utf8Path| {
+        let path = path.canonicalize_utf8().unwrap_or_else(|_| path.to_owned());
+        explicit
+            .iter()
+            .any(|p| p.canonicalize_utf8().unwrap_or_else(|_| p.clone()) == path)
+    };
+
+    let mut files: Vec<ConditionalFile> = vec![];
+    for root in cfg_search_roots(workspace_root, fs, env) {
+        for load_path in base.config_load_paths.iter().flatten() {
+            let Ok(dir) = Utf8PathBuf::try_from(load_path.to_path(&root)) else {
+                continue;
+            };
+
+            for path in config_files_in(&dir) {
+                if !mentions_activate(&path)
+                    || is_explicit(&path)
+                    || files.iter().any(|file| file.path == path)
+                {
+                    continue;
+                }
+
+                let partial = match load_partial_at_path(&path) {
+                    Ok(Some(partial)) => partial,
+                    Ok(None) => continue,
+                    Err(error) => {
+                        warn!(path = path.as_str(), %error, "Skipping invalid configuration file.");
+                        continue;
+                    }
+                };
+
+                if partial.activate.is_declared() {
+                    files.push(ConditionalFile {
+                        path,
+                        partial,
+                        conditions: vec![],
+                    });
+                }
+            }
+        }
+    }
+
+    if files.is_empty() {
+        return files;
+    }
+
+    let ctx = activation_context(workspace_root, &files, env);
+    for file in &mut files {
+        file.conditions = file.partial.activate.evaluate(&ctx);
+    }
+
+    files
+}
+
+/// Whether the contents of the file at `path` mention the `activate` key.
+///
+/// Much cheaper than parsing the file (and the files it extends), and rules
+/// out the files that are only meant to be loaded with `--cfg`.
+fn mentions_activate(path: &Utf8Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|content| content.contains("activate"))
+}
+
+/// The configuration files in `dir` and its subdirectories, in a stable order.
+fn config_files_in(dir: &Utf8Path) -> Vec<Utf8PathBuf> {
+    let Ok(entries) = dir.read_dir_utf8() else {
+        return vec![];
+    };
+
+    let mut paths = entries
+        .filter_map(Result::ok)
+        .map(camino::Utf8DirEntry::into_path)
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .flat_map(|path| {
+            if path.is_dir() {
+                config_files_in(&path)
+            } else if path
+                .extension()
+                .is_some_and(|ext| VALID_CONFIG_FILE_EXTS.contains(&ext))
+            {
+                vec![path]
+            } else {
+                vec![]
+            }
+        })
+        .collect()
+}
+
+/// Gather the environment that activation conditions are evaluated against.
+///
+/// The git branch and project languages are only looked up if any of `files`
+/// has a condition on them.
+fn activation_context(
+    workspace_root: Option<&Utf8Path>,
+    files: &[ConditionalFile],
+    env: &Env,
+) -> ActivationContext {
+    let declares = |f: fn(&PartialActivationConfig) -> bool| {
+        files.iter().any(|file| f(&file.partial.activate))
+    };
+
+    let cwd = env.cwd().ok();
+    let path = cwd.as_deref().map(|cwd| {
+        workspace_root
+            .and_then(|root| cwd.strip_prefix(root).ok())
+            .unwrap_or(cwd)
+            .to_owned()
+    });
+
+    let branch = if declares(|activate| activate.branches.is_some()) {
+        current_branch(env)
+    } else {
+        None
+    };
+
+    let languages = match cwd.as_deref() {
+        Some(cwd) if declares(|activate| activate.languages.is_some()) => {
+            detect_languages(cwd, workspace_root)
+        }
+        _ => vec![],
+    };
+
+    let now = chrono::Local::now();
+
+    ActivationContext {
+        path,
+        branch,
+        env: env.vars().into_iter().map(|(key, _)| key).collect(),
+        languages,
+        minute_of_day: u16::try_from(now.hour() * 60 + now.minute()).unwrap_or_default(),
+    }
+}
+
+/// The checked out git branch of the working directory, if any.
+fn current_branch(env: &Env) -> Option<String> {
+    let mut git = cmd!("git", "rev-parse", "--abbrev-ref", "HEAD").stderr_null();
+    if let Ok(cwd) = env.cwd() {
+        git = git.dir(cwd);
+    }
+    if let Some(vars) = env.overrides() {
+        git = git.full_env(vars);
+    }
+
+    git.read()
+        .ok()
+        .filter(|branch| !branch.is_empty() && branch != "HEAD")
+}
+
+/// Files that mark a project as using a language.
+const LANGUAGE_MARKERS: &[(&str, &str)] = &[
+    ("Cargo.toml", "rust"),
+    ("go.mod", "go"),
+    ("pyproject.toml", "python"),
+    ("setup.py", "python"),
+    ("requirements.txt", "python"),
+    ("package.json", "javascript"),
+    ("tsconfig.json", "typescript"),
+    ("deno.json", "typescript"),
+    ("Gemfile", "ruby"),
+    ("pom.xml", "java"),
+    ("build.gradle", "java"),
+    ("build.gradle.kts", "kotlin"),
+    ("mix.exs", "elixir"),
+    ("composer.json", "php"),
+    ("Package.swift", "swift"),
+    ("pubspec.yaml", "dart"),
+    ("build.zig", "zig"),
+];
+
+/// Detect the languages of the project, from the marker files in `cwd` and its
+/// parent directories, up to the workspace root.
+fn detect_languages(cwd: &Utf8Path, workspace_root: Option<&Utf8Path>) -> Vec<String> {
+    let mut languages: Vec<String> = vec![];
+    for dir in cwd.ancestors() {
+        for (file, language) in LANGUAGE_MARKERS {
+            if dir.join(file).is_file() && !languages.iter().any(|l| l == language) {
+                languages.push((*language).to_owned());
+            }
+        }
+
+        if workspace_root.is_none_or(|root| dir == root) {
+            break;
+        }
+    }
+
+    languages
+}
+
+/// The roots that `config_load_paths` entries are resolved against when
+/// searching for a `--cfg` file, in precedence order (lowest first).
+///
+/// 1. User-global:    `$XDG_CONFIG_HOME/jp/config/`
+/// 2. Workspace:      `<workspace_root>/`
+/// 3. User-workspace: `$XDG_DATA_HOME/jp/workspace/<name>-<id>/config/`
+///
+/// The home directory is read from `env`, as in [`ConfigFiles::new`].
+///
+/// [`ConfigFiles::new`]: crate::ConfigFiles::new
+pub(crate) fn cfg_search_roots(
+    workspace_root: Option<&Utf8Path>,
+    fs: Option<&FsStorageBackend>,
+    env: &Env,
+) -> Vec<Utf8PathBuf> {
+    let home = env
+        .var("HOME")
+        .filter(|home| !home.is_empty())
+        .map(Utf8PathBuf::from);
+    let mut roots = Vec::new();
+
+    if let Some(global_dir) = user_global_config_dir(home.as_deref()) {
+        roots.push(global_dir.join("config"));
+    }
+    if let Some(root) = workspace_root {
+        roots.push(root.to_owned());
+    }
+    if let Some(path) = fs.and_then(|f| f.user_storage_with_path(RelativePath::new("config"))) {
+        roots.push(path);
+    }
+
+    roots
+}
+
 /// Apply pre-resolved `--cfg` args onto a partial config.
 /// Pure in-memory merge.
 fn apply_cfg_args(
@@ -208,7 +498,7 @@ fn apply_cfg_args(
                     .map_err(|e| Error::CliConfig(e.to_string()))?;
             }
             ResolvedCfgArg::Partials(partials) => {
-                for p in partials {
+                for (_, p) in partials {
                     partial = load_partial(partial, p.clone())?;
                 }
             }
@@ -227,6 +517,7 @@ pub(crate) fn build_partial_from_cfg_args(
     base: &PartialAppConfig,
     workspace: Option<&Workspace>,
     fs: Option<&FsStorageBackend>,
+    env: &Env,
 ) -> Result<PartialAppConfig> {
     if args.is_empty() {
         return Err(Error::CliConfig(
@@ -234,10 +525,41 @@ pub(crate) fn build_partial_from_cfg_args(
         ));
     }
 
-    let resolved = resolve_cfg_args(args, base, workspace, fs)?;
+    let resolved = resolve_cfg_args(args, base, workspace, fs, env)?;
     apply_cfg_args(PartialAppConfig::empty(), &resolved)
 }
 
+/// Resolve each `--cfg` argument into the partials it applies, in order, using
+/// the same search-path resolution as the config pipeline.
+///
+/// Each partial is paired with the file it was loaded from, if any.
+/// Used by `config explain` to attribute values to individual arguments.
+pub(crate) fn resolve_cfg_partials(
+    args: &[KeyValueOrPath],
+    base: &PartialAppConfig,
+    workspace: Option<&Workspace>,
+    fs: Option<&FsStorageBackend>,
+    env: &Env,
+) -> Result<Vec<(Option<Utf8PathBuf>, PartialAppConfig)>> {
+    let mut partials = vec![];
+    for arg in resolve_cfg_args(args, base, workspace, fs, env)? {
+        match arg {
+            ResolvedCfgArg::KeyValue(kv) => {
+                let mut partial = PartialAppConfig::empty();
+                partial
+                    .assign(kv)
+                    .map_err(|e| Error::CliConfig(e.to_string()))?;
+                partials.push((None, partial));
+            }
+            ResolvedCfgArg::Partials(files) => {
+                partials.extend(files.into_iter().map(|(path, p)| (Some(path), p)));
+            }
+        }
+    }
+
+    Ok(partials)
+}
+
 #[cfg(test)]
 #[path = "config_pipeline_tests.rs"]
 mod tests;
@@ -53,3 +53,109 @@ fn conversation_layer_overrides_base() {
     let partial = pipeline.partial_with_conversation(conv).unwrap();
     assert_eq!(partial.conversation.start_local, Some(true));
 }
+
+fn write_file(path: &Utf8Path, content: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn conditional_files_are_found_and_evaluated() {
+    let tmp = camino_tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".jp/skills/always.toml"),
+        "activate.env = [\"PATH\"]\nassistant.name = \"always\"",
+    );
+    write_file(
+        &root.join(".jp/skills/nested/never.toml"),
+        "activate.env = [\"JP_TEST_UNSET_VARIABLE\"]\nassistant.name = \"never\"",
+    );
+    write_file(
+        &root.join(".jp/skills/explicit.toml"),
+        "activate.time = \"00:00-24:00\"",
+    );
+    write_file(
+        &root.join(".jp/skills/manual.toml"),
+        "assistant.name = \"manual\"",
+    );
+    write_file(&root.join(".jp/skills/invalid.toml"), "activate = 1");
+    write_file(
+        &root.join(".jp/skills/notes.md"),
+        "activate.env = [\"PATH\"]",
+    );
+
+    let mut base = PartialAppConfig::empty();
+    base.config_load_paths = Some(vec![".jp/skills".into()]);
+
+    let explicit = vec![root.join(".jp/skills/explicit.toml")];
+    let files = find_conditional_files(&base, &explicit, Some(root), None, &Env::process());
+
+    let found = files
+        .iter()
+        .map(|file| {
+            (
+                file.path.strip_prefix(root).unwrap().as_str(),
+                file.is_active(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(found, [
+        (".jp/skills/always.toml", true),
+        (".jp/skills/nested/never.toml", false),
+    ]);
+
+    assert_eq!(files[0].conditions[0].matched.as_deref(), Some("PATH"));
+}
+
+#[test]
+fn activation_conditions_are_not_part_of_the_config() {
+    let tmp = camino_tempfile::tempdir().unwrap();
+    let file = tmp.path().join("skill.toml");
+    write_file(
+        &file,
+        "activate.env = [\"PATH\"]\nassistant.name = \"skill\"",
+    );
+
+    let mut base = PartialAppConfig::empty();
+    base.activate.env = Some(vec!["PATH".to_owned()]);
+
+    let pipeline = ConfigPipeline::new(
+        base,
+        &[KeyValueOrPath::Path(file)],
+        None,
+        None,
+        &Env::process(),
+    )
+    .unwrap();
+    let partial = pipeline.partial_without_conversation().unwrap();
+
+    assert_eq!(partial.assistant.name.as_deref(), Some("skill"));
+    assert!(!partial.activate.is_declared());
+}
+
+#[test]
+fn languages_are_detected_up_to_the_workspace_root() {
+    let tmp = camino_tempfile::tempdir().unwrap();
+    let root = tmp.path().join("project");
+
+    write_file(&tmp.path().join("go.mod"), "");
+    write_file(&root.join("Cargo.toml"), "");
+    write_file(&root.join("web/package.json"), "");
+    write_file(&root.join("web/tsconfig.json"), "");
+    std::fs::create_dir_all(root.join("web/src")).unwrap();
+
+    assert_eq!(detect_languages(&root.join("web/src"), Some(&root)), [
+        "javascript",
+        "typescript",
+        "rust"
+    ]);
+
+    // Outside a workspace, only the current directory is checked.
+    assert_eq!(detect_languages(&root.join("web"), None), [
+        "javascript",
+        "typescript"
+    ]);
+}
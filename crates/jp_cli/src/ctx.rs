@@ -7,7 +7,8 @@ use std::{
 
 use camino::Utf8Path;
 use chrono::{DateTime, Utc};
-use jp_config::{AppConfig, PartialAppConfig, conversation::tool::ToolSource};
+use jp_config::{AppConfig, PartialAppConfig, conversation::tool::ToolSource, env::Env};
+use jp_llm::provider::ProviderCache;
 use jp_mcp::{StartupSet, id::McpServerId};
 use jp_printer::Printer;
 use jp_storage::backend::FsStorageBackend;
@@ -15,7 +16,7 @@ use jp_task::TaskHandler;
 use jp_workspace::{Workspace, session::Session};
 use tokio::runtime::{Handle, Runtime};
 
-use crate::{Globals, Result, signals::SignalRouter};
+use crate::{Globals, Result, signals::SignalRouter, usage::UsageRecorder};
 
 /// Context for the CLI application
 pub(crate) struct Ctx {
@@ -33,6 +34,13 @@ pub(crate) struct Ctx {
     /// Global CLI arguments.
     pub(crate) term: Term,
 
+    /// The environment of the invocation.
+    ///
+    /// Read environment variables from here rather than from the process, and
+    /// pass it on to the programs the command starts: a daemon job runs with
+    /// the environment of the client that sent it.
+    pub(crate) env: Env,
+
     /// The resolved terminal session identity, if any.
     ///
     /// `None` when no session could be detected (e.g. no controlling terminal,
@@ -45,12 +53,27 @@ pub(crate) struct Ctx {
     /// MCP client for interacting with MCP servers.
     pub(crate) mcp_client: jp_mcp::Client,
 
+    /// Runtime to run MCP servers on, if it should outlive this context.
+    ///
+    /// The daemon shares one MCP client between its jobs, so the servers it
+    /// starts must run on the daemon's runtime rather than the job's, which
+    /// is shut down when the job finishes. Defaults to [`Self::handle`].
+    pub(crate) mcp_runtime: Option<Handle>,
+
+    /// The LLM providers built so far, for reuse.
+    ///
+    /// The daemon shares these between its jobs.
+    pub(crate) providers: ProviderCache,
+
     pub(crate) task_handler: jp_task::TaskHandler,
 
     /// Routes OS signals: Ctrl-C escalation, scoped interrupt handlers, and the
     /// root shutdown token.
     pub(crate) signals: SignalRouter,
 
+    /// Collects the usage of this run, for the opt-in usage log.
+    pub(crate) usage: UsageRecorder,
+
     runtime: Runtime,
 
     #[cfg(test)]
@@ -86,13 +109,14 @@ impl Ctx {
         runtime: Runtime,
         args: Globals,
         config: impl Into<Arc<AppConfig>>,
+        env: Env,
         session: Option<Session>,
         printer: Printer,
     ) -> Self {
         let config = config.into();
         let escalation_cooldown =
             Duration::from_secs(config.interrupt.escalation_cooldown_secs.into());
-        let mcp_client = jp_mcp::Client::new(config.providers.mcp.clone());
+        let mcp_client = jp_mcp::Client::new(config.providers.mcp.clone()).with_env(env.clone());
 
         let is_tty = io::stdout().is_terminal();
         let width = printer.output_width().columns();
@@ -106,11 +130,15 @@ impl Ctx {
                 is_tty,
                 width,
             },
+            env,
             session,
             printer: Arc::new(printer),
             mcp_client,
+            mcp_runtime: None,
+            providers: ProviderCache::default(),
             task_handler: TaskHandler::default(),
             signals: SignalRouter::new(&runtime, escalation_cooldown),
+            usage: UsageRecorder::default(),
             runtime,
 
             #[cfg(test)]
@@ -167,12 +195,15 @@ impl Ctx {
         self.runtime.handle()
     }
 
-    /// Activate and deactivate MCP servers based on the active conversation
-    /// context.
-    pub(crate) async fn configure_active_mcp_servers(&mut self) -> Result<StartupSet> {
+    /// Activate and deactivate MCP servers based on the tools enabled in
+    /// `config`.
+    pub(crate) async fn configure_active_mcp_servers(
+        &mut self,
+        config: &AppConfig,
+    ) -> Result<StartupSet> {
         let mut server_ids = HashSet::new();
 
-        for (_name, cfg) in self.config.conversation.tools.iter() {
+        for (_name, cfg) in config.conversation.tools.iter() {
             if !cfg.is_enabled() {
                 continue;
             }
@@ -184,8 +215,13 @@ impl Ctx {
             server_ids.insert(McpServerId::new(server));
         }
 
+        let runtime = self
+            .mcp_runtime
+            .clone()
+            .unwrap_or_else(|| self.handle().clone());
+
         self.mcp_client
-            .run_services(server_ids, self.handle().clone())
+            .run_services(server_ids, runtime)
             .await
             .map_err(Into::into)
     }
@@ -197,11 +233,13 @@ pub(crate) trait IntoPartialAppConfig {
     ///
     /// `merged_config` may contain the full configuration for validation when
     /// `partial` is incomplete.
+    /// Relative paths in flags are relative to the working directory of `env`.
     fn apply_cli_config(
         &self,
         workspace: Option<&Workspace>,
         partial: PartialAppConfig,
         merged_config: Option<&PartialAppConfig>,
+        env: &Env,
     ) -> std::result::Result<PartialAppConfig, Box<dyn std::error::Error + Send + Sync>>;
 
     #[expect(unused_variables)]
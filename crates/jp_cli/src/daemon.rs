@@ -0,0 +1,59 @@
+//! The per-workspace daemon.
+//!
+//! Every `jp` invocation normally loads the workspace, resolves config, and
+//! boots its MCP servers from scratch.
+//! The daemon is an optional long-running process that runs queries on behalf
+//! of thin clients instead, keeping state that is expensive to rebuild warm
+//! between them.
+//!
+//! - [`protocol`] defines the newline-delimited JSON messages exchanged over
+//!   the workspace's Unix socket.
+//! - [`server`] is the daemon itself: it accepts connections, runs each query
+//!   as a job, and buffers job output so clients can detach and re-attach.
+//! - [`client`] forwards `jp query --daemon` to the daemon and streams the
+//!   job's output back to the terminal.
+
+pub(crate) mod client;
+pub(crate) mod protocol;
+pub(crate) mod server;
+
+use camino::Utf8PathBuf;
+use jp_config::env::Env;
+use jp_workspace::user_runtime_dir;
+use relative_path::RelativePath;
+
+use crate::{WorkspaceIdOrPath, error::Result, load_workspace};
+
+/// File name of the daemon log within (user-)workspace storage.
+pub(crate) const LOG_FILE: &str = "daemon.log";
+
+/// The workspace a daemon serves, and where its files live.
+pub(crate) struct DaemonPaths {
+    /// The workspace root.
+    pub root: Utf8PathBuf,
+
+    /// The Unix socket the daemon listens on.
+    pub socket: Utf8PathBuf,
+
+    /// The file the daemon writes its trace log to.
+    pub log: Utf8PathBuf,
+}
+
+impl DaemonPaths {
+    /// Locate the daemon files for the given (or current) workspace.
+    ///
+    /// The socket lives in a directory only the current user can access, as
+    /// anyone who can connect to it can run `jp` as that user.
+    /// The log lives in user-local workspace storage when available, so it
+    /// never ends up in a shared `.jp` directory.
+    pub(crate) fn resolve(workspace: Option<&WorkspaceIdOrPath>) -> Result<Self> {
+        let (workspace, fs) = load_workspace(workspace, true, &Env::process())?;
+        let fs = fs.ok_or(jp_workspace::Error::MissingStorage)?;
+
+        Ok(Self {
+            root: workspace.root().to_path_buf(),
+            socket: user_runtime_dir()?.join(format!("daemon-{}.sock", workspace.id())),
+            log: fs.user_or_root_with_path(RelativePath::new(LOG_FILE)),
+        })
+    }
+}
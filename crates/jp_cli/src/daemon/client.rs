@@ -0,0 +1,208 @@
+//! The client side of the daemon protocol.
+
+use std::{
+    env,
+    io::{self, Write as _},
+    time::Duration,
+};
+
+use camino::{Utf8Path, absolute_utf8};
+use tokio::{
+    io::BufReader,
+    net::{UnixStream, unix::OwnedReadHalf},
+    runtime,
+};
+
+use super::protocol::{
+    ClientMessage, JobId, OutputStream, ServerMessage, read_message, write_message,
+};
+use crate::cmd::{self, Output};
+
+type Result<T> = std::result::Result<T, cmd::Error>;
+
+/// Forward the current invocation to the daemon as a new job.
+///
+/// Unless `detach` is set, the job's output is streamed to stdout and stderr
+/// until it finishes, and its exit code becomes ours.
+/// Ctrl-C detaches from the job instead of interrupting it.
+pub(crate) fn forward(socket: &Utf8Path, detach: bool, is_tty: bool, width: Option<u16>) -> Output {
+    let args = env::args_os()
+        .map(|arg| {
+            arg.into_string().map_err(|arg| {
+                format!(
+                    "Cannot forward argument `{}` to the daemon, as it is not valid UTF-8. Run \
+                     the query without `--daemon`.",
+                    arg.to_string_lossy()
+                )
+            })
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let request = ClientMessage::Run {
+        args,
+        cwd: absolute_utf8(".")?,
+        is_tty,
+        width,
+        env: env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+            .collect(),
+    };
+
+    block_on(async {
+        let (mut read, job) = match start(socket, &request).await? {
+            (read, ServerMessage::Started { job }) => (read, job),
+            (_, message) => return Err(unexpected(message)),
+        };
+
+        if detach {
+            write_line(io::stdout(), &job.to_string())?;
+            return Ok(());
+        }
+
+        follow(&mut read, job).await
+    })
+}
+
+/// Stream the output of an existing job until it finishes.
+pub(crate) fn attach(socket: &Utf8Path, job: JobId) -> Output {
+    block_on(async {
+        let (mut read, first) = start(socket, &ClientMessage::Attach { job }).await?;
+        if let Some(result) = render(first, job)? {
+            return result;
+        }
+
+        follow(&mut read, job).await
+    })
+}
+
+/// Send a request that is answered with a single message.
+pub(crate) fn request(socket: &Utf8Path, message: &ClientMessage) -> Result<ServerMessage> {
+    block_on(async {
+        match start(socket, message).await? {
+            (_, ServerMessage::Error { message }) => Err(message.into()),
+            (_, message) => Ok(message),
+        }
+    })
+}
+
+/// Whether a daemon is accepting connections on `socket`.
+pub(crate) fn is_running(socket: &Utf8Path) -> bool {
+    std::os::unix::net::UnixStream::connect(socket).is_ok()
+}
+
+/// Wait up to `timeout` for the daemon on `socket` to be `running` (or not).
+pub(crate) fn wait_until(socket: &Utf8Path, running: bool, timeout: Duration) -> bool {
+    let step = Duration::from_millis(50);
+    let mut waited = Duration::ZERO;
+
+    while is_running(socket) != running {
+        if waited >= timeout {
+            return false;
+        }
+
+        std::thread::sleep(step);
+        waited += step;
+    }
+
+    true
+}
+
+async fn start(
+    socket: &Utf8Path,
+    message: &ClientMessage,
+) -> Result<(BufReader<OwnedReadHalf>, ServerMessage)> {
+    let stream = UnixStream::connect(socket).await.map_err(|error| {
+        cmd::Error::from(format!(
+            "No daemon is running for this workspace ({error}). Start one with `jp daemon start`."
+        ))
+    })?;
+
+    let (read, mut write) = stream.into_split();
+    write_message(&mut write, message).await?;
+
+    let mut read = BufReader::new(read);
+    match read_message(&mut read).await? {
+        Some(message) => Ok((read, message)),
+        None => Err("The daemon closed the connection without responding.".into()),
+    }
+}
+
+async fn follow(read: &mut BufReader<OwnedReadHalf>, job: JobId) -> Output {
+    loop {
+        tokio::select! {
+            biased;
+            _ = tokio::signal::ctrl_c() => {
+                write_line(
+                    io::stderr(),
+                    &format!(
+                        "\nDetached from job {job}, which keeps running. Re-attach with `jp daemon \
+                         attach {job}`."
+                    ),
+                )?;
+                return Ok(());
+            }
+            message = read_message(read) => {
+                let Some(message) = message? else {
+                    return Err("The daemon closed the connection.".into());
+                };
+
+                if let Some(result) = render(message, job)? {
+                    return result;
+                }
+            }
+        }
+    }
+}
+
+/// Write a job message to the terminal.
+///
+/// Returns the outcome of the job once it has finished.
+fn render(message: ServerMessage, job: JobId) -> io::Result<Option<Output>> {
+    match message {
+        ServerMessage::Output {
+            stream: OutputStream::Out,
+            text,
+            ..
+        } => write_all(io::stdout(), &text)?,
+        ServerMessage::Output {
+            stream: OutputStream::Err,
+            text,
+            ..
+        } => write_all(io::stderr(), &text)?,
+        ServerMessage::Finished { code: 0, .. } => return Ok(Some(Ok(()))),
+        ServerMessage::Finished { code, .. } => return Ok(Some(Err(cmd::Error::from(code)))),
+        ServerMessage::Error { message } => return Ok(Some(Err(message.into()))),
+        message => {
+            tracing::debug!(job, ?message, "Ignoring unexpected daemon message.");
+        }
+    }
+
+    Ok(None)
+}
+
+fn unexpected(message: ServerMessage) -> cmd::Error {
+    match message {
+        ServerMessage::Error { message } => message.into(),
+        message => format!("Unexpected response from the daemon: {message:?}").into(),
+    }
+}
+
+fn write_all(mut writer: impl io::Write, text: &str) -> io::Result<()> {
+    writer.write_all(text.as_bytes())?;
+    writer.flush()
+}
+
+fn write_line(writer: impl io::Write, text: &str) -> io::Result<()> {
+    write_all(writer, &format!("{text}\n"))
+}
+
+/// Run a client future on a small runtime of its own.
+///
+/// The client never runs the startup pipeline, so it doesn't need the
+/// multi-threaded runtime regular commands use.
+fn block_on<T>(future: impl Future<Output = Result<T>>) -> Result<T> {
+    runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(future)
+}
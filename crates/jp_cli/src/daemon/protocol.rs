@@ -0,0 +1,144 @@
+//! Wire protocol between `jp` clients and the workspace daemon.
+//!
+//! Messages are JSON objects, one per line, tagged by a `type` field.
+//! A connection carries a single conversation between client and daemon: the
+//! client sends one [`ClientMessage`], and the daemon answers with one or more
+//! [`ServerMessage`]s.
+//! Clients that stream a job's output keep the connection open until the job
+//! finishes or the client goes away; the job itself is unaffected by the
+//! client disconnecting.
+
+use std::{collections::BTreeMap, io};
+
+use camino::Utf8PathBuf;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt as _, AsyncWrite, AsyncWriteExt as _};
+
+/// Identifier of a job, unique for the lifetime of a daemon process.
+pub(crate) type JobId = u64;
+
+/// A request sent by a client to the daemon.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ClientMessage {
+    /// Run a `jp` invocation as a new job, and stream its output back.
+    Run {
+        /// The full argument list, including the binary name.
+        args: Vec<String>,
+
+        /// The directory the invocation was made from.
+        cwd: Utf8PathBuf,
+
+        /// Whether the client's stdout is a terminal.
+        ///
+        /// Used to resolve `--format=auto` the way the client would have.
+        is_tty: bool,
+
+        /// The width the client lays output out against, if known.
+        width: Option<u16>,
+
+        /// The client's environment variables.
+        ///
+        /// The job runs with these instead of the daemon's own, so that the
+        /// session, config overrides and secrets resolve as they would have
+        /// in the client.
+        #[serde(default)]
+        env: BTreeMap<String, String>,
+    },
+
+    /// Stream the output of an existing job, starting from its first line.
+    Attach { job: JobId },
+
+    /// Ask a running job to stop gracefully, as if it received SIGTERM.
+    Cancel { job: JobId },
+
+    /// List the jobs known to the daemon.
+    Jobs,
+
+    /// Stop the daemon after cancelling its running jobs.
+    Shutdown,
+}
+
+/// A message sent by the daemon to a client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ServerMessage {
+    /// A job was accepted; its output follows.
+    Started { job: JobId },
+
+    /// A chunk of output written by the job.
+    Output {
+        job: JobId,
+        stream: OutputStream,
+        text: String,
+    },
+
+    /// The job finished with the given exit code.
+    Finished { job: JobId, code: u8 },
+
+    /// The jobs known to the daemon, oldest first.
+    Jobs { jobs: Vec<JobInfo> },
+
+    /// The request was handled and has no further output.
+    Ack,
+
+    /// The request could not be handled.
+    Error { message: String },
+}
+
+/// The output stream a chunk of job output was written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum OutputStream {
+    Out,
+    Err,
+}
+
+/// A summary of a job, as reported by [`ServerMessage::Jobs`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct JobInfo {
+    /// The job ID.
+    pub id: JobId,
+
+    /// The invocation, without the binary name.
+    pub command: String,
+
+    /// When the daemon accepted the job.
+    pub started_at: DateTime<Utc>,
+
+    /// The exit code, once the job has finished.
+    pub code: Option<u8>,
+}
+
+/// Write a single message, followed by a newline.
+pub(crate) async fn write_message<W, T>(writer: &mut W, message: &T) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    writer.flush().await
+}
+
+/// Read a single message.
+///
+/// Returns `None` when the peer closed the connection.
+pub(crate) async fn read_message<R, T>(reader: &mut R) -> io::Result<Option<T>>
+where
+    R: AsyncBufRead + Unpin,
+    T: DeserializeOwned,
+{
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+
+    serde_json::from_str(&line).map(Some).map_err(Into::into)
+}
+
+#[cfg(test)]
+#[path = "protocol_tests.rs"]
+mod tests;
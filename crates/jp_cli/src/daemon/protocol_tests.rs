@@ -0,0 +1,92 @@
+use chrono::TimeZone as _;
+use pretty_assertions::assert_eq;
+use tokio::io::BufReader;
+
+use super::*;
+
+#[tokio::test]
+async fn messages_round_trip_one_per_line() {
+    let messages = vec![
+        ServerMessage::Started { job: 1 },
+        ServerMessage::Output {
+            job: 1,
+            stream: OutputStream::Out,
+            text: "hello\nworld\n".to_owned(),
+        },
+        ServerMessage::Jobs {
+            jobs: vec![JobInfo {
+                id: 1,
+                command: "query hello".to_owned(),
+                started_at: Utc.with_ymd_and_hms(2026, 10, 1, 12, 0, 0).unwrap(),
+                code: None,
+            }],
+        },
+        ServerMessage::Finished { job: 1, code: 0 },
+    ];
+
+    let mut buf = Vec::new();
+    for message in &messages {
+        write_message(&mut buf, message).await.unwrap();
+    }
+
+    // Embedded newlines are escaped, so every message is exactly one line.
+    assert_eq!(buf.iter().filter(|&&b| b == b'\n').count(), messages.len());
+
+    let mut reader = BufReader::new(buf.as_slice());
+    let mut read = vec![];
+    while let Some(message) = read_message::<_, ServerMessage>(&mut reader).await.unwrap() {
+        read.push(message);
+    }
+
+    assert_eq!(read, messages);
+}
+
+#[tokio::test]
+async fn client_messages_are_tagged_by_type() {
+    let mut buf = Vec::new();
+    write_message(&mut buf, &ClientMessage::Attach { job: 7 })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "{\"type\":\"attach\",\"job\":7}\n"
+    );
+}
+
+#[tokio::test]
+async fn read_message_returns_none_on_eof() {
+    let mut reader = BufReader::new(&b""[..]);
+    let message = read_message::<_, ClientMessage>(&mut reader).await.unwrap();
+
+    assert_eq!(message, None);
+}
+
+#[tokio::test]
+async fn read_message_rejects_malformed_lines() {
+    let mut reader = BufReader::new(&b"{\"type\":\"nope\"}\n"[..]);
+    let error = read_message::<_, ClientMessage>(&mut reader)
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[tokio::test]
+async fn run_message_env_defaults_to_empty() {
+    let line =
+        b"{\"type\":\"run\",\"args\":[\"jp\"],\"cwd\":\"/\",\"is_tty\":false,\"width\":null}\n";
+    let mut reader = BufReader::new(&line[..]);
+    let message = read_message::<_, ClientMessage>(&mut reader).await.unwrap();
+
+    assert_eq!(
+        message,
+        Some(ClientMessage::Run {
+            args: vec!["jp".to_owned()],
+            cwd: "/".into(),
+            is_tty: false,
+            width: None,
+            env: BTreeMap::new(),
+        })
+    );
+}
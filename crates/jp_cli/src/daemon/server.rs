@@ -0,0 +1,626 @@
+//! The daemon process.
+//!
+//! The daemon listens on the workspace socket and runs every `jp` invocation
+//! it receives as a *job*.
+//! A job runs the regular startup pipeline on its own thread and runtime, with
+//! a printer that appends everything it writes to the job's backlog instead of
+//! a terminal.
+//! Clients stream the backlog from the start and follow it until the job
+//! finishes; a client going away does not affect the job, and a later client
+//! can attach to it again.
+//!
+//! Jobs run concurrently. Two jobs on the same conversation are kept apart by
+//! the conversation lock, as two `jp` processes would be.
+//!
+//! State that outlives a single job is owned by the daemon, and kept warm for
+//! the next one: the opened workspace, the LLM providers, and the MCP clients
+//! with the servers they run.
+//! A job takes an idle MCP client and brings it in line with its own config,
+//! so servers keep running between jobs only while their configuration is
+//! unchanged.
+//!
+//! Jobs have no terminal to prompt on, so a job whose tools would ask for
+//! confirmation is refused before it runs.
+//!
+//! A job runs with the environment and working directory of the client that
+//! sent it.
+//! The daemon's own environment and working directory are never changed; the
+//! job's are passed along explicitly to everything that reads variables,
+//! resolves relative paths or starts programs on its behalf.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, Permissions},
+    io,
+    num::NonZeroUsize,
+    os::unix::fs::PermissionsExt as _,
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use jp_config::{AppConfig, env::Env};
+use jp_llm::provider::ProviderCache;
+use jp_printer::{OutputWidth, Printer};
+use jp_workspace::{DEFAULT_STORAGE_DIR, Workspace};
+use tokio::{
+    io::{AsyncWrite, BufReader},
+    net::{UnixListener, UnixStream},
+    runtime::Handle,
+    signal::unix::{SignalKind, signal},
+    sync::{mpsc, watch},
+};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use super::{
+    DaemonPaths,
+    protocol::{
+        ClientMessage, JobId, JobInfo, OutputStream, ServerMessage, read_message, write_message,
+    },
+};
+use crate::{
+    Cli, build_runtime, cmd,
+    ctx::Ctx,
+    error::Result,
+    parse_error, run_pipeline, runtime_with_workers,
+    signals::{OsSignal, SignalRouter},
+    worker_threads,
+};
+
+/// How many finished jobs the daemon keeps around for re-attaching.
+const MAX_FINISHED_JOBS: usize = 20;
+
+/// Run the daemon in the current process until it is asked to shut down.
+pub(crate) fn serve(paths: &DaemonPaths, threads: Option<NonZeroUsize>) -> Result<()> {
+    let workspace = Workspace::open(&paths.root)?;
+    let runtime = build_runtime(threads, "jp-daemon")?;
+    let server = Arc::new(Server::new(workspace, runtime.handle().clone()));
+
+    runtime.block_on(server.listen(&paths.socket))?;
+    Ok(())
+}
+
+struct Server {
+    /// Jobs by ID, oldest first.
+    jobs: Mutex<IndexMap<JobId, Arc<Job>>>,
+
+    /// Source for job IDs.
+    next_id: AtomicU64,
+
+    /// The workspace the daemon serves, opened once and reopened for every
+    /// job in it.
+    workspace: Workspace,
+
+    /// The LLM providers built by earlier jobs.
+    providers: ProviderCache,
+
+    /// MCP clients that no job is using, with the servers they run.
+    ///
+    /// A job takes one, replaces its servers with those of its own config,
+    /// and returns it when it finishes, so that servers started for one query
+    /// are still running for the next.
+    /// A job that finds none uses a client of its own, and adds that one.
+    mcp_clients: Mutex<Vec<jp_mcp::Client>>,
+
+    /// The daemon's own runtime, on which shared MCP servers run.
+    ///
+    /// Jobs get a runtime of their own that is dropped when the job ends, which
+    /// would take any server started on it down with it.
+    runtime: Handle,
+
+    /// Cancelled when a client requests a shutdown.
+    shutdown: CancellationToken,
+}
+
+impl Server {
+    fn new(workspace: Workspace, runtime: Handle) -> Self {
+        Self {
+            jobs: Mutex::new(IndexMap::new()),
+            next_id: AtomicU64::new(1),
+            workspace,
+            providers: ProviderCache::default(),
+            mcp_clients: Mutex::new(Vec::new()),
+            runtime,
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    async fn listen(self: Arc<Self>, socket: &Utf8Path) -> io::Result<()> {
+        if let Some(parent) = socket.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // The socket directory is already private, but the socket shouldn't
+        // rely on that: whoever can connect can run `jp` as this user.
+        let listener = UnixListener::bind(socket)?;
+        fs::set_permissions(socket, Permissions::from_mode(0o600))?;
+        let mut terminate = signal(SignalKind::terminate())?;
+        info!(%socket, "Daemon listening.");
+
+        loop {
+            tokio::select! {
+                () = self.shutdown.cancelled() => break,
+                _ = terminate.recv() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => drop(tokio::spawn(self.clone().handle(stream))),
+                    Err(error) => warn!(%error, "Failed to accept daemon connection."),
+                },
+            }
+        }
+
+        // Stop accepting new work before winding down the running jobs.
+        drop(listener);
+        if let Err(error) = fs::remove_file(socket) {
+            warn!(%socket, %error, "Failed to remove daemon socket.");
+        }
+
+        let jobs: Vec<_> = self.lock_jobs().values().cloned().collect();
+        for job in &jobs {
+            job.signal(OsSignal::Terminate);
+        }
+        for job in &jobs {
+            job.done.cancelled().await;
+        }
+
+        info!("Daemon stopped.");
+        Ok(())
+    }
+
+    /// Serve a single client connection.
+    async fn handle(self: Arc<Self>, stream: UnixStream) {
+        let (read, mut write) = stream.into_split();
+        let mut read = BufReader::new(read);
+
+        let result = match read_message(&mut read).await {
+            Ok(Some(message)) => self.dispatch(message, &mut write).await,
+            Ok(None) => Ok(()),
+            Err(error) => {
+                let message = ServerMessage::Error {
+                    message: format!("Invalid request: {error}"),
+                };
+                write_message(&mut write, &message).await
+            }
+        };
+
+        // Most errors here are clients going away mid-stream, which is
+        // expected: that is how a client detaches.
+        if let Err(error) = result {
+            debug!(%error, "Daemon connection closed.");
+        }
+    }
+
+    async fn dispatch<W: AsyncWrite + Unpin>(
+        self: &Arc<Self>,
+        message: ClientMessage,
+        write: &mut W,
+    ) -> io::Result<()> {
+        match message {
+            ClientMessage::Run {
+                args,
+                cwd,
+                is_tty,
+                width,
+                env,
+            } => {
+                let job = self.spawn_job(RunRequest {
+                    args,
+                    cwd,
+                    is_tty,
+                    width,
+                    env,
+                })?;
+
+                write_message(write, &ServerMessage::Started { job: job.id }).await?;
+                stream_job(&job, write).await
+            }
+            ClientMessage::Attach { job } => match self.job(job) {
+                Some(job) => stream_job(&job, write).await,
+                None => write_message(write, &unknown_job(job)).await,
+            },
+            ClientMessage::Cancel { job } => match self.job(job) {
+                Some(job) => {
+                    job.signal(OsSignal::Terminate);
+                    write_message(write, &ServerMessage::Ack).await
+                }
+                None => write_message(write, &unknown_job(job)).await,
+            },
+            ClientMessage::Jobs => {
+                let jobs = self.lock_jobs().values().map(|job| job.info()).collect();
+                write_message(write, &ServerMessage::Jobs { jobs }).await
+            }
+            ClientMessage::Shutdown => {
+                self.shutdown.cancel();
+                write_message(write, &ServerMessage::Ack).await
+            }
+        }
+    }
+
+    fn job(&self, id: JobId) -> Option<Arc<Job>> {
+        self.lock_jobs().get(&id).cloned()
+    }
+
+    fn lock_jobs(&self) -> MutexGuard<'_, IndexMap<JobId, Arc<Job>>> {
+        self.jobs.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Register a new job and start running it on a thread of its own.
+    fn spawn_job(self: &Arc<Self>, request: RunRequest) -> io::Result<Arc<Job>> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (signals_tx, signals_rx) = mpsc::unbounded_channel();
+        let job = Arc::new(Job::new(id, request.command(), signals_tx));
+
+        {
+            let mut jobs = self.lock_jobs();
+            jobs.insert(id, job.clone());
+            prune_finished(&mut jobs);
+        }
+
+        let server = self.clone();
+        let runner = job.clone();
+        thread::Builder::new()
+            .name(format!("jp-job-{id}"))
+            .spawn(move || {
+                let code = server.run_job(&runner, &request, signals_rx);
+                runner.finish(code);
+            })?;
+
+        Ok(job)
+    }
+
+    /// Run a job to completion, returning its exit code.
+    fn run_job(
+        &self,
+        job: &Arc<Job>,
+        request: &RunRequest,
+        signals: mpsc::UnboundedReceiver<OsSignal>,
+    ) -> u8 {
+        info!(job = job.id, command = %job.command, "Running daemon job.");
+
+        let cli = match Cli::parse_args(&request.args) {
+            Ok(cli) => cli,
+            Err(error) => {
+                job.output(OutputStream::Err, error.render().to_string());
+                return u8::try_from(error.exit_code()).unwrap_or(2);
+            }
+        };
+
+        if !request.cwd.is_absolute() || !request.cwd.is_dir() {
+            job.output(
+                OutputStream::Err,
+                format!("Cannot run in {}: not a directory\n", request.cwd),
+            );
+            return 1;
+        }
+
+        let format = cli.globals.format.resolve(request.is_tty);
+        let width = match request.width {
+            Some(columns) if request.is_tty => OutputWidth::Terminal(columns),
+            Some(columns) => OutputWidth::Declared(columns),
+            None => OutputWidth::Unknown,
+        };
+        let printer = Printer::new(
+            JobWriter::new(job.clone(), OutputStream::Out),
+            JobWriter::new(job.clone(), OutputStream::Err),
+            None,
+            format,
+        )
+        .with_output_width(width);
+
+        // Other worktrees of the workspace share its ID, and so its daemon,
+        // but not its root: jobs from those open their own workspace.
+        let workspace = (cli.globals.workspace.is_none()
+            && Workspace::find_root(request.cwd.clone(), DEFAULT_STORAGE_DIR).as_deref()
+                == Some(self.workspace.root()))
+        .then_some(&self.workspace);
+
+        let env = Env::captured(request.env.clone()).with_cwd(request.cwd.clone());
+        let workers = worker_threads().map_or(1, NonZeroUsize::get);
+        let mut mcp_client = None;
+        let result = runtime_with_workers(workers, "jp-job-worker").and_then(|runtime| {
+            run_pipeline(cli, printer, runtime, env, workspace, |ctx| {
+                ensure_unattended(&ctx.config())?;
+                mcp_client = Some(self.attach(ctx, signals));
+                Ok(())
+            })
+        });
+
+        if let Some(client) = mcp_client {
+            self.lock_mcp_clients().push(client);
+        }
+
+        match result {
+            Ok(()) => 0,
+            Err(error) => {
+                let (code, message) = parse_error(cmd::Error::from(error), format);
+                if !message.trim().is_empty() {
+                    job.output(OutputStream::Err, format!("{message}\n"));
+                }
+                code
+            }
+        }
+    }
+
+    fn lock_mcp_clients(&self) -> MutexGuard<'_, Vec<jp_mcp::Client>> {
+        self.mcp_clients
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Wire a job's context up to the daemon.
+    ///
+    /// Returns the MCP client the job uses, to return to the idle ones when the
+    /// job finishes.
+    fn attach(&self, ctx: &mut Ctx, signals: mpsc::UnboundedReceiver<OsSignal>) -> jp_mcp::Client {
+        let idle = self.lock_mcp_clients().pop();
+        if let Some(client) = idle {
+            // Jobs can come from other worktrees, override the MCP config with
+            // `--cfg`, or come from a terminal with a different environment.
+            let servers = ctx.config().providers.mcp.clone();
+            ctx.handle()
+                .block_on(client.sync_servers(servers, ctx.env.clone()));
+            ctx.mcp_client = client;
+        }
+        ctx.mcp_runtime = Some(self.runtime.clone());
+        ctx.providers = self.providers.clone();
+
+        // Nothing a job does may prompt on, or open an editor in, the terminal
+        // the daemon happens to run in. Questions that tools ask at runtime go
+        // to the assistant instead, as they do for any non-interactive query.
+        ctx.term.is_tty = false;
+
+        // A job has no terminal to receive signals from; it only sees the
+        // signals its clients send through the daemon. Escalating to a
+        // process exit would take the daemon down with it, so that rung of
+        // the ladder is ignored.
+        let cooldown = Duration::from_secs(ctx.config().interrupt.escalation_cooldown_secs.into());
+        ctx.signals = SignalRouter::with_signal_source(
+            ctx.handle(),
+            UnboundedReceiverStream::new(signals),
+            cooldown,
+            |code| warn!(code, "Ignoring process exit requested by a daemon job."),
+        );
+
+        ctx.mcp_client.clone()
+    }
+}
+
+/// Refuse a job whose tools would ask the user for confirmation or input.
+///
+/// Jobs have no terminal to prompt on, and a tool that can't ask would
+/// otherwise run, or have its result delivered, without anyone approving it,
+/// or fail partway through the turn, after it already changed things.
+fn ensure_unattended(config: &AppConfig) -> std::result::Result<(), cmd::Error> {
+    let tools = config.conversation.tools.prompting_tools();
+
+    if tools.is_empty() {
+        return Ok(());
+    }
+
+    Err(cmd::Error::from(format!(
+        "Daemon jobs cannot ask for confirmation or input, but these tools do: {}. Tools ask \
+         before they run unless configured otherwise, so run them unattended (e.g. `--cfg \
+         conversation.tools.*.run=unattended`), answer or route their questions to the assistant, \
+         disable them, or run the query without `--daemon`.",
+        tools.join(", ")
+    )))
+}
+
+/// Stream a job's backlog to a client, then follow it until the job finishes.
+async fn stream_job<W: AsyncWrite + Unpin>(job: &Job, write: &mut W) -> io::Result<()> {
+    let mut changes = job.len.subscribe();
+    let mut position = 0;
+
+    loop {
+        let messages = job.read_from(position);
+        position += messages.len();
+
+        for message in &messages {
+            write_message(write, message).await?;
+            if matches!(message, ServerMessage::Finished { .. }) {
+                return Ok(());
+            }
+        }
+
+        if changes.changed().await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+fn unknown_job(id: JobId) -> ServerMessage {
+    ServerMessage::Error {
+        message: format!("Unknown job {id}"),
+    }
+}
+
+/// Drop the oldest finished jobs beyond [`MAX_FINISHED_JOBS`].
+fn prune_finished(jobs: &mut IndexMap<JobId, Arc<Job>>) {
+    let finished: Vec<_> = jobs
+        .values()
+        .filter(|job| job.info().code.is_some())
+        .map(|job| job.id)
+        .collect();
+
+    for id in finished
+        .iter()
+        .take(finished.len().saturating_sub(MAX_FINISHED_JOBS))
+    {
+        jobs.shift_remove(id);
+    }
+}
+
+struct RunRequest {
+    args: Vec<String>,
+    cwd: Utf8PathBuf,
+    is_tty: bool,
+    width: Option<u16>,
+    env: BTreeMap<String, String>,
+}
+
+impl RunRequest {
+    /// The invocation without the binary name, for display.
+    fn command(&self) -> String {
+        self.args
+            .iter()
+            .skip(1)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A single invocation run by the daemon.
+struct Job {
+    id: JobId,
+    command: String,
+    started_at: DateTime<Utc>,
+
+    /// Everything the job has produced so far, and its exit code once done.
+    state: Mutex<JobState>,
+
+    /// The length of the backlog, watched by streaming clients.
+    len: watch::Sender<usize>,
+
+    /// Feeds the job's signal router.
+    signals: mpsc::UnboundedSender<OsSignal>,
+
+    /// Cancelled when the job has finished.
+    done: CancellationToken,
+}
+
+#[derive(Default)]
+struct JobState {
+    backlog: Vec<ServerMessage>,
+    code: Option<u8>,
+}
+
+impl Job {
+    fn new(id: JobId, command: String, signals: mpsc::UnboundedSender<OsSignal>) -> Self {
+        Self {
+            id,
+            command,
+            started_at: Utc::now(),
+            state: Mutex::new(JobState::default()),
+            len: watch::Sender::new(0),
+            signals,
+            done: CancellationToken::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, JobState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn push(&self, message: ServerMessage) {
+        let mut state = self.lock();
+        state.backlog.push(message);
+        self.len.send_replace(state.backlog.len());
+    }
+
+    fn output(&self, stream: OutputStream, text: String) {
+        self.push(ServerMessage::Output {
+            job: self.id,
+            stream,
+            text,
+        });
+    }
+
+    fn finish(&self, code: u8) {
+        {
+            let mut state = self.lock();
+            state.code = Some(code);
+            state
+                .backlog
+                .push(ServerMessage::Finished { job: self.id, code });
+            self.len.send_replace(state.backlog.len());
+        }
+
+        info!(job = self.id, code, "Daemon job finished.");
+        self.done.cancel();
+    }
+
+    fn read_from(&self, position: usize) -> Vec<ServerMessage> {
+        self.lock()
+            .backlog
+            .get(position..)
+            .map(<[_]>::to_vec)
+            .unwrap_or_default()
+    }
+
+    /// Deliver a signal to the job, if it is still running.
+    fn signal(&self, signal: OsSignal) {
+        if self.done.is_cancelled() {
+            return;
+        }
+
+        if self.signals.send(signal).is_err() {
+            debug!(job = self.id, "Job no longer receives signals.");
+        }
+    }
+
+    fn info(&self) -> JobInfo {
+        JobInfo {
+            id: self.id,
+            command: self.command.clone(),
+            started_at: self.started_at,
+            code: self.lock().code,
+        }
+    }
+}
+
+/// Forwards a job's printer output to its backlog.
+struct JobWriter {
+    job: Arc<Job>,
+    stream: OutputStream,
+
+    /// Bytes of a UTF-8 sequence that was split across writes.
+    partial: Vec<u8>,
+}
+
+impl JobWriter {
+    fn new(job: Arc<Job>, stream: OutputStream) -> Self {
+        Self {
+            job,
+            stream,
+            partial: vec![],
+        }
+    }
+}
+
+impl io::Write for JobWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.partial.extend_from_slice(buf);
+
+        // Hold back an incomplete sequence at the end of the buffer until the
+        // rest of it arrives; anything actually invalid is replaced.
+        let complete = match std::str::from_utf8(&self.partial) {
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            _ => self.partial.len(),
+        };
+
+        if complete > 0 {
+            let rest = self.partial.split_off(complete);
+            let text = String::from_utf8_lossy(&self.partial).into_owned();
+            self.partial = rest;
+            self.job.output(self.stream, text);
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "server_tests.rs"]
+mod tests;
@@ -0,0 +1,220 @@
+use std::io::Write as _;
+
+use jp_config::conversation::tool::{
+    QuestionConfig, QuestionTarget, ResultMode, RunMode, ToolConfig, ToolSource,
+};
+use pretty_assertions::assert_eq;
+use tokio::io::BufReader;
+
+use super::*;
+
+fn job(id: JobId) -> Arc<Job> {
+    let (tx, _rx) = mpsc::unbounded_channel();
+    Arc::new(Job::new(id, format!("query {id}"), tx))
+}
+
+fn texts(job: &Job) -> Vec<String> {
+    job.read_from(0)
+        .into_iter()
+        .filter_map(|message| match message {
+            ServerMessage::Output { text, .. } => Some(text),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn job_writer_holds_back_split_utf8_sequences() {
+    let job = job(1);
+    let mut writer = JobWriter::new(job.clone(), OutputStream::Out);
+
+    // "é" is two bytes; split it across two writes.
+    let bytes = "café".as_bytes();
+    let (head, tail) = bytes.split_at(bytes.len() - 1);
+    writer.write_all(head).unwrap();
+    writer.write_all(tail).unwrap();
+
+    assert_eq!(texts(&job), vec!["caf".to_owned(), "é".to_owned()]);
+}
+
+#[test]
+fn job_writer_replaces_invalid_bytes() {
+    let job = job(1);
+    let mut writer = JobWriter::new(job.clone(), OutputStream::Err);
+
+    writer.write_all(b"a\xffb").unwrap();
+
+    assert_eq!(texts(&job), vec!["a\u{fffd}b".to_owned()]);
+}
+
+#[test]
+fn finish_records_code_and_final_message() {
+    let job = job(3);
+    job.output(OutputStream::Out, "done\n".to_owned());
+    job.finish(2);
+
+    assert_eq!(job.info().code, Some(2));
+    assert_eq!(job.read_from(1), vec![ServerMessage::Finished {
+        job: 3,
+        code: 2
+    }]);
+    assert!(job.done.is_cancelled());
+}
+
+#[test]
+fn prune_finished_keeps_running_and_recent_jobs() {
+    let mut jobs = IndexMap::new();
+    let total = u64::try_from(MAX_FINISHED_JOBS).unwrap() + 5;
+    for id in 1..=total {
+        let job = job(id);
+        if id != 2 {
+            job.finish(0);
+        }
+        jobs.insert(id, job);
+    }
+
+    prune_finished(&mut jobs);
+
+    // Job 2 is still running, so it survives even though it is old.
+    assert!(jobs.contains_key(&2));
+    assert!(!jobs.contains_key(&1));
+    assert_eq!(jobs.len(), MAX_FINISHED_JOBS + 1);
+}
+
+#[tokio::test]
+async fn stream_job_replays_backlog_then_follows_until_finished() {
+    let job = job(7);
+    job.output(OutputStream::Out, "before attach\n".to_owned());
+
+    let follower = {
+        let job = job.clone();
+        tokio::spawn(async move {
+            let mut buf = Vec::new();
+            stream_job(&job, &mut buf).await.unwrap();
+            buf
+        })
+    };
+
+    tokio::task::yield_now().await;
+    job.output(OutputStream::Err, "after attach\n".to_owned());
+    job.finish(0);
+
+    let buf = follower.await.unwrap();
+    let mut reader = BufReader::new(buf.as_slice());
+    let mut messages = vec![];
+    while let Some(message) = read_message::<_, ServerMessage>(&mut reader).await.unwrap() {
+        messages.push(message);
+    }
+
+    assert_eq!(messages, vec![
+        ServerMessage::Output {
+            job: 7,
+            stream: OutputStream::Out,
+            text: "before attach\n".to_owned(),
+        },
+        ServerMessage::Output {
+            job: 7,
+            stream: OutputStream::Err,
+            text: "after attach\n".to_owned(),
+        },
+        ServerMessage::Finished { job: 7, code: 0 },
+    ]);
+}
+
+#[test]
+fn ensure_unattended_refuses_tools_that_ask() {
+    fn tool(run: RunMode, result: Option<ResultMode>) -> ToolConfig {
+        ToolConfig {
+            source: ToolSource::Local { tool: None },
+            command: None,
+            pty: None,
+            run: Some(run),
+            format: None,
+            enable: None,
+            summary: None,
+            description: None,
+            examples: None,
+            parameters: IndexMap::new(),
+            result,
+            style: None,
+            questions: IndexMap::new(),
+            options: IndexMap::default(),
+            access: None,
+            concurrency: None,
+            cancellation_response: None,
+        }
+    }
+
+    let mut config = AppConfig::new_test();
+    config
+        .conversation
+        .tools
+        .insert("quiet".to_owned(), tool(RunMode::Unattended, None));
+    assert!(ensure_unattended(&config).is_ok());
+
+    config
+        .conversation
+        .tools
+        .insert("asks".to_owned(), tool(RunMode::Ask, None));
+    config.conversation.tools.insert(
+        "reviews".to_owned(),
+        tool(RunMode::Unattended, Some(ResultMode::Edit)),
+    );
+    let error = ensure_unattended(&config).unwrap_err().to_string();
+    assert!(error.contains("asks, reviews"), "{error}");
+    assert!(!error.contains("quiet"), "{error}");
+}
+
+#[test]
+fn ensure_unattended_refuses_questions_for_the_user() {
+    fn tool(question: QuestionConfig) -> ToolConfig {
+        ToolConfig {
+            source: ToolSource::Local { tool: None },
+            command: None,
+            pty: None,
+            run: Some(RunMode::Unattended),
+            format: None,
+            enable: None,
+            summary: None,
+            description: None,
+            examples: None,
+            parameters: IndexMap::new(),
+            result: Some(ResultMode::Unattended),
+            style: None,
+            questions: IndexMap::from_iter([("confirm".to_owned(), question)]),
+            options: IndexMap::default(),
+            access: None,
+            concurrency: None,
+            cancellation_response: None,
+        }
+    }
+
+    let mut config = AppConfig::new_test();
+    config.conversation.tools.insert(
+        "answered".to_owned(),
+        tool(QuestionConfig {
+            target: QuestionTarget::User,
+            answer: Some(true.into()),
+        }),
+    );
+    config.conversation.tools.insert(
+        "delegated".to_owned(),
+        tool(QuestionConfig {
+            target: QuestionTarget::Assistant(Box::default()),
+            answer: None,
+        }),
+    );
+    assert!(ensure_unattended(&config).is_ok());
+
+    config.conversation.tools.insert(
+        "prompts".to_owned(),
+        tool(QuestionConfig {
+            target: QuestionTarget::User,
+            answer: None,
+        }),
+    );
+    let error = ensure_unattended(&config).unwrap_err().to_string();
+    assert!(error.contains("prompts"), "{error}");
+    assert!(!error.contains("answered"), "{error}");
+    assert!(!error.contains("delegated"), "{error}");
+}
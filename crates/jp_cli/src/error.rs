@@ -132,6 +132,12 @@ pub(crate) enum Error {
     #[error("Undefined template variable: {0}")]
     TemplateUndefinedVariable(String),
 
+    #[error("Template not found: {0}")]
+    NamedTemplateNotFound(String),
+
+    #[error("Invalid template parameter `{name}`: {message}")]
+    TemplateParam { name: String, message: String },
+
     #[error("JSON error")]
     Json(#[from] serde_json::Error),
 
@@ -172,6 +178,9 @@ pub(crate) enum Error {
     #[error("Cannot start a new conversation together with --fork, --replay, or --id")]
     NewConflictsWithTarget,
 
+    #[error("Conversation {0} has no incomplete turn to resume")]
+    NothingToResume(ConversationId),
+
     /// The user requested conversation target help.
     #[error("target help")]
     TargetHelp { session: bool, multi: bool },
@@ -1,7 +1,10 @@
 mod access;
 mod cmd;
+mod completion;
 mod config_pipeline;
 mod ctx;
+#[cfg(unix)]
+mod daemon;
 mod editor;
 mod error;
 mod format;
@@ -13,9 +16,12 @@ mod session;
 mod shared;
 mod signals;
 mod timer;
+mod usage;
 
 use std::{
-    env, fmt, fs,
+    env,
+    ffi::OsString,
+    fmt, fs,
     io::{self, IsTerminal as _, Write as _, stderr, stdout},
     num::{self, NonZeroUsize},
     process::ExitCode,
@@ -25,15 +31,17 @@ use std::{
         atomic::{AtomicUsize, Ordering},
     },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use camino::{FromPathBufError, Utf8PathBuf, absolute_utf8};
+use camino::{FromPathBufError, Utf8PathBuf};
 use camino_tempfile::NamedUtf8TempFile;
+use chrono::Utc;
 use clap::{
-    ArgAction, Parser,
+    ArgAction, CommandFactory as _, FromArgMatches as _, Parser,
     builder::{BoolValueParser, TypedValueParser as _},
 };
+use clap_complete::engine::ArgValueCompleter;
 use cmd::Commands;
 use crossterm::{style::Stylize as _, terminal};
 use ctx::{Ctx, IntoPartialAppConfig};
@@ -41,7 +49,9 @@ use error::{Error, Result};
 use jp_config::{
     AppConfig, PartialAppConfig,
     assignment::KvAssignment,
+    env::Env,
     fs::user_global_config_dir,
+    template::named::load_template_dir,
     util::{
         build, load_envs, load_partial_at_path, load_partial_at_path_recursive,
         load_partials_with_inheritance,
@@ -60,6 +70,7 @@ use tracing::{debug, info, trace, warn};
 
 use crate::{
     cmd::{
+        checkpoint::TurnCheckpoints,
         plugin::dispatch::{describe_plugin, discover_plugins},
         target::resolve_request,
     },
@@ -87,6 +98,26 @@ struct Cli {
 
     #[command(subcommand, next_help_heading = "Options")]
     command: Commands,
+
+    /// The names of the flags given on the command line, for the usage log.
+    #[arg(skip)]
+    flags: Vec<String>,
+}
+
+impl Cli {
+    /// Parse `args`, keeping the names of the flags that were given.
+    fn parse_args<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let mut cmd = Self::command();
+        let matches = cmd.clone().try_get_matches_from(args)?;
+        let mut cli = Self::from_arg_matches(&matches).map_err(|error| error.format(&mut cmd))?;
+        cli.flags = usage::flag_names(&cmd, &matches);
+
+        Ok(cli)
+    }
 }
 
 /// The root options for the CLI.
@@ -111,6 +142,7 @@ struct Globals {
         action = ArgAction::Append,
         value_name = "KEY=VALUE",
         value_parser = KeyValueOrPath::from_str,
+        add = ArgValueCompleter::new(completion::cfg),
     )]
     config: Vec<KeyValueOrPath>,
 
@@ -298,6 +330,10 @@ pub(crate) enum CliFormat {
 
     /// Pretty-printed multi-line JSON output.
     JsonPretty,
+
+    /// Newline-delimited JSON output.
+    /// Streaming commands (e.g. `query`) emit one JSON object per event.
+    Jsonl,
 }
 
 impl CliFormat {
@@ -311,6 +347,7 @@ impl CliFormat {
             Self::TextPretty => OutputFormat::TextPretty,
             Self::Json => OutputFormat::Json,
             Self::JsonPretty => OutputFormat::JsonPretty,
+            Self::Jsonl => OutputFormat::Jsonl,
         }
     }
 }
@@ -330,7 +367,10 @@ pub fn run() -> ExitCode {
     #[cfg(feature = "dhat")]
     let _profiler = run_dhat();
 
-    let cli = match Cli::try_parse() {
+    // Answer the shell, if it's asking for completions.
+    completion::complete();
+
+    let cli = match Cli::parse_args(env::args_os()) {
         Ok(cli) => cli,
         Err(e) => {
             if e.kind() == clap::error::ErrorKind::DisplayHelp && is_root_help_request() {
@@ -477,8 +517,81 @@ fn run_inner(cli: Cli, format: OutputFormat) -> Result<()> {
         return args.run(&printer).map_err(Into::into);
     }
 
-    let (mut workspace, fs_backend) =
-        load_workspace(cli.globals.workspace.as_ref(), cli.globals.persist)?;
+    // Completion scripts and man pages are generated from the command tree.
+    if let Commands::Completions(args) = &cli.command {
+        return args.run(&Cli::command(), &printer).map_err(Into::into);
+    }
+    if let Commands::Man(args) = &cli.command {
+        return args.run(Cli::command(), &printer).map_err(Into::into);
+    }
+
+    // Daemon commands either talk to the daemon, or are the daemon, which runs
+    // the pipeline once for every job it accepts.
+    #[cfg(unix)]
+    if let Commands::Daemon(args) = &cli.command {
+        return args
+            .run(&cli.globals, cli.root.threads, &printer)
+            .map_err(Into::into);
+    }
+
+    // `jp query --daemon` hands the whole invocation over to the daemon.
+    if let Commands::Query(args) = &cli.command
+        && let Some(detach) = args.daemon()
+    {
+        #[cfg(unix)]
+        {
+            // Jobs don't read the client's stdin, so refuse piped input
+            // rather than dropping it.
+            if !cmd::read_piped_stdin()?.trim().is_empty() {
+                return Err(cmd::Error::from(
+                    "Piped input is not supported with `--daemon`. Pass the query as an argument \
+                     instead.",
+                )
+                .into());
+            }
+
+            let paths = daemon::DaemonPaths::resolve(cli.globals.workspace.as_ref())?;
+            let is_tty = stdout().is_terminal();
+            let width = printer.output_width().columns();
+            return daemon::client::forward(&paths.socket, detach, is_tty, width)
+                .map_err(Into::into);
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = detach;
+            return Err(cmd::Error::from("The JP daemon is only supported on Unix.").into());
+        }
+    }
+
+    let runtime = build_runtime(cli.root.threads, "jp-worker")?;
+    run_pipeline(cli, printer, runtime, Env::process(), None, |_| Ok(()))
+}
+
+/// Run a command through the full startup pipeline.
+///
+/// Environment variables are read from `env`, not from the process.
+/// When `workspace` is given, it is reopened instead of finding the workspace
+/// on disk again.
+/// `setup` is called with the context right after it is created, before the
+/// command runs; an error from it aborts the command.
+fn run_pipeline(
+    cli: Cli,
+    printer: Printer,
+    runtime: Runtime,
+    env: Env,
+    workspace: Option<&Workspace>,
+    setup: impl FnOnce(&mut Ctx) -> Result<()>,
+) -> Result<()> {
+    let started_at = Utc::now();
+    let started = Instant::now();
+    let command = cli.command.name();
+    let flags = cli.flags.clone();
+
+    let (mut workspace, fs_backend) = match workspace {
+        Some(workspace) => prepare_workspace(workspace.reopen(), cli.globals.persist),
+        None => load_workspace(cli.globals.workspace.as_ref(), cli.globals.persist, &env)?,
+    };
 
     trace!("Sanitizing workspace.");
     let report = workspace.sanitize()?;
@@ -493,13 +606,13 @@ fn run_inner(cli: Cli, format: OutputFormat) -> Result<()> {
     }
 
     trace!("Resolving session identity.");
-    let session = session::resolve();
+    let session = session::resolve(&env);
 
     // Populate the conversation index. This does NOT load the contents of
     // individual conversations, this is done lazily as needed.
     workspace.load_conversation_index();
 
-    let base = load_base_partial(fs_backend.as_deref())?;
+    let base = load_base_partial(fs_backend.as_deref(), &env)?;
     let (config, handles, start_new) = resolve_config(
         &cli.command,
         base,
@@ -507,18 +620,20 @@ fn run_inner(cli: Cli, format: OutputFormat) -> Result<()> {
         &mut workspace,
         session.as_ref(),
         fs_backend.as_deref(),
+        &env,
     )?;
     let config = Arc::new(config);
-    let runtime = build_runtime(cli.root.threads, "jp-worker")?;
     let mut ctx = Ctx::new(
         workspace,
         fs_backend,
         runtime,
         cli.globals,
         config,
+        env,
         session,
         printer,
     );
+    setup(&mut ctx)?;
     let rt = ctx.handle().clone();
 
     // Run the requested command, racing it against the shutdown token.
@@ -571,11 +686,36 @@ fn run_inner(cli: Cli, format: OutputFormat) -> Result<()> {
     // Remove ephemeral conversations that are no longer needed, but protect
     // any conversation that is active in a terminal session.
     let active_ids = ctx.workspace.all_active_conversation_ids();
-    ctx.workspace.remove_ephemeral_conversations(&active_ids);
+    let expired = ctx.workspace.remove_ephemeral_conversations(&active_ids);
+    if let Some(fs) = ctx.fs_backend.as_deref() {
+        for id in &expired {
+            if let Some(checkpoints) = TurnCheckpoints::new(fs, id)
+                && let Err(error) = checkpoints.remove_all()
+            {
+                warn!(%id, %error, "Failed to remove conversation checkpoints.");
+            }
+        }
+    }
 
     // Remove orphaned lock files and stale session mappings.
     ctx.workspace.cleanup_stale_files(ctx.fs_backend.as_deref());
 
+    // Usage is only logged when opted in, and never fails the command.
+    if ctx.config().user.usage_log
+        && let Some(path) = usage::log_path()
+    {
+        let success = output
+            .as_ref()
+            .map_or_else(|error| error.expected, |()| true);
+        let record = ctx
+            .usage
+            .finish(command, flags, started_at, started.elapsed(), success);
+
+        if let Err(error) = usage::append(&path, &record) {
+            warn!(%path, %error, "Failed to write usage log.");
+        }
+    }
+
     output.map_err(Into::into)
 }
 
@@ -769,8 +909,9 @@ pub(crate) fn resolve_config(
     workspace: &mut Workspace,
     session: Option<&jp_workspace::session::Session>,
     fs: Option<&FsStorageBackend>,
+    env: &Env,
 ) -> Result<(AppConfig, Vec<jp_workspace::ConversationHandle>, bool)> {
-    let pipeline = ConfigPipeline::new(base, cfg_overrides, Some(workspace), fs)?;
+    let pipeline = ConfigPipeline::new(base, cfg_overrides, Some(workspace), fs, env)?;
 
     // Extract default_id — a loading-time concern consumed here, not
     // propagated to the runtime config.
@@ -813,7 +954,7 @@ pub(crate) fn resolve_config(
 
     // Phase 3: CLI flag overrides.
     partial = command
-        .apply_cli_config(Some(workspace), partial, None)
+        .apply_cli_config(Some(workspace), partial, None, env)
         .map_err(|error| Error::CliConfig(error.to_string()))?;
 
     // Consume default_id so it doesn't appear in the runtime config.
@@ -823,39 +964,54 @@ pub(crate) fn resolve_config(
     Ok((config, handles, outcome.start_new))
 }
 
-/// Load the base partial config from files and environment variables.
+/// Load the base partial config from files and the environment variables in
+/// `env`.
 ///
 /// This produces the `files + inheritance + env` layer that serves as input to
 /// [`ConfigPipeline`].
 /// No `--cfg` args or per-conversation config.
 ///
 /// See: <https://jp.computer/configuration>
-fn load_base_partial(fs: Option<&FsStorageBackend>) -> Result<PartialAppConfig> {
-    let partials = load_partial_configs_from_files(fs, absolute_utf8(".").ok())?;
+fn load_base_partial(fs: Option<&FsStorageBackend>, env: &Env) -> Result<PartialAppConfig> {
+    let partials = load_partial_configs_from_files(fs, env.cwd().ok(), env)?;
     let partial = load_partials_with_inheritance(partials)?;
 
-    load_envs(partial).map_err(|error| Error::CliConfig(error.to_string()))
+    load_envs(partial, env).map_err(|error| Error::CliConfig(error.to_string()))
 }
 
 fn load_partial_configs_from_files(
     fs: Option<&FsStorageBackend>,
     cwd: Option<Utf8PathBuf>,
+    env: &Env,
 ) -> Result<Vec<PartialAppConfig>> {
-    let config_path = RelativePath::new("config.toml");
+    let files = ConfigFiles::new(fs, cwd, env);
     let mut partials = vec![];
 
     // Load the user-global config file (see RFD D20).
-    let home = env::home_dir().and_then(|p| Utf8PathBuf::from_path_buf(p).ok());
-    if let Some(user_global_config) = user_global_config_dir(home.as_deref())
-        .and_then(|p| load_partial_at_path(p.join("config.toml")).transpose())
+    if let Some(user_global_config) = files
+        .user_global
+        .and_then(|p| load_partial_at_path(p).transpose())
         .transpose()?
     {
         partials.push(user_global_config);
     }
 
+    // Load `$WORKSPACE_ROOT/.jp/templates/*.md` as named query templates.
+    // Workspace config files can still override individual template fields.
+    if let Some(named) = files
+        .templates
+        .map(|p| load_template_dir(&p))
+        .transpose()?
+        .filter(|named| !named.is_empty())
+    {
+        let mut partial = PartialAppConfig::default();
+        partial.template.named = named;
+        partials.push(partial);
+    }
+
     // Load `$WORKSPACE_ROOT/.jp/config.{toml,json,yaml}`.
-    if let Some(workspace_config) = fs
-        .map(|f| f.root_with_path(config_path))
+    if let Some(workspace_config) = files
+        .workspace
         .and_then(|p| load_partial_at_path(p).transpose())
         .transpose()?
     {
@@ -864,22 +1020,17 @@ fn load_partial_configs_from_files(
 
     // Load `$CWD/.jp.{toml,json,yaml}`, recursing up the directory tree until
     // either the root of the workspace, or filesystem is reached.
-    if let Some(cwd_config) = cwd
-        .and_then(|cwd| {
-            load_partial_at_path_recursive(
-                cwd.join(".jp.toml"),
-                Workspace::find_root(cwd, DEFAULT_STORAGE_DIR).as_deref(),
-            )
-            .transpose()
-        })
+    if let Some(cwd_config) = files
+        .cwd
+        .and_then(|(path, root)| load_partial_at_path_recursive(path, root.as_deref()).transpose())
         .transpose()?
     {
         partials.push(cwd_config);
     }
 
     // Load `$XDG_DATA_HOME/jp/workspace/<name>-<id>/config.{toml,json,yaml}`.
-    if let Some(user_workspace_config) = fs
-        .and_then(|f| f.user_storage_with_path(config_path))
+    if let Some(user_workspace_config) = files
+        .user_workspace
         .and_then(|p| load_partial_at_path(p).transpose())
         .transpose()?
     {
@@ -889,22 +1040,65 @@ fn load_partial_configs_from_files(
     Ok(partials)
 }
 
-/// Find the workspace for the current directory.
+/// The locations of the configuration files merged into the base config, in
+/// merge order.
 ///
-/// When `persist` is `false` (`--no-persist`), the persist backend is swapped
-/// to [`NullPersistBackend`] and the lock backend to [`NullLockBackend`] so
-/// that ephemeral queries never write to disk and never block on lock
-/// contention.
-/// The session backend is wrapped in [`ReadOnlySessionBackend`] for the same
-/// reason: the run still needs to read which conversation the session is on,
-/// but must not record one that it never persisted.
+/// A file that does not exist is skipped when loading.
+#[derive(Debug, Default)]
+pub(crate) struct ConfigFiles {
+    /// `$XDG_CONFIG_HOME/jp/config.toml`
+    pub user_global: Option<Utf8PathBuf>,
+
+    /// `$WORKSPACE_ROOT/.jp/templates`
+    pub templates: Option<Utf8PathBuf>,
+
+    /// `$WORKSPACE_ROOT/.jp/config.toml`
+    pub workspace: Option<Utf8PathBuf>,
+
+    /// `$CWD/.jp.toml`, and the workspace root at which the upward search
+    /// stops.
+    pub cwd: Option<(Utf8PathBuf, Option<Utf8PathBuf>)>,
+
+    /// `$XDG_DATA_HOME/jp/workspace/<name>-<id>/config.toml`
+    pub user_workspace: Option<Utf8PathBuf>,
+}
+
+impl ConfigFiles {
+    /// Locate the configuration files.
+    ///
+    /// The home directory is read from `env`, so a daemon job finds the
+    /// user-global config of the client that sent it.
+    pub(crate) fn new(fs: Option<&FsStorageBackend>, cwd: Option<Utf8PathBuf>, env: &Env) -> Self {
+        let config_path = RelativePath::new("config.toml");
+        let home = env
+            .var("HOME")
+            .filter(|home| !home.is_empty())
+            .map(Utf8PathBuf::from);
+
+        Self {
+            user_global: user_global_config_dir(home.as_deref()).map(|p| p.join("config.toml")),
+            templates: fs.map(|f| f.storage_path().join("templates")),
+            workspace: fs.map(|f| f.root_with_path(config_path)),
+            cwd: cwd.map(|cwd| {
+                let path = cwd.join(".jp.toml");
+                (path, Workspace::find_root(cwd, DEFAULT_STORAGE_DIR))
+            }),
+            user_workspace: fs.and_then(|f| f.user_storage_with_path(config_path)),
+        }
+    }
+}
+
+/// Find the workspace for the working directory of `env`.
+///
+/// See [`prepare_workspace`] for `persist`.
 fn load_workspace(
     workspace: Option<&WorkspaceIdOrPath>,
     persist: bool,
+    env: &Env,
 ) -> Result<(Workspace, Option<Arc<FsStorageBackend>>)> {
     let cwd = match workspace {
-        None => absolute_utf8(".")?,
-        Some(WorkspaceIdOrPath::Path(path)) => path.clone(),
+        None => env.cwd()?,
+        Some(WorkspaceIdOrPath::Path(path)) => env.cwd()?.join(path),
 
         // TODO: Centralize this in a new `UserStorage` struct.
         Some(WorkspaceIdOrPath::Id(id)) => user_data_dir()?
@@ -924,7 +1118,7 @@ fn load_workspace(
             .try_into()
             .map_err(FromPathBufError::into_io_error)?,
     };
-    let mut workspace = Workspace::open(&cwd).map_err(|error| match error {
+    let workspace = Workspace::open(&cwd).map_err(|error| match error {
         jp_workspace::Error::WorkspaceNotFound(_) => Error::Command(cmd::Error::from(format!(
             "Could not locate workspace. Use `{}` to create a new workspace.",
             "jp init".bold().yellow()
@@ -932,6 +1126,22 @@ fn load_workspace(
         error => Error::Workspace(error),
     })?;
 
+    Ok(prepare_workspace(workspace, persist))
+}
+
+/// Prepare an opened workspace for a run.
+///
+/// When `persist` is `false` (`--no-persist`), the persist backend is swapped
+/// to [`NullPersistBackend`] and the lock backend to [`NullLockBackend`] so
+/// that ephemeral queries never write to disk and never block on lock
+/// contention.
+/// The session backend is wrapped in [`ReadOnlySessionBackend`] for the same
+/// reason: the run still needs to read which conversation the session is on,
+/// but must not record one that it never persisted.
+fn prepare_workspace(
+    mut workspace: Workspace,
+    persist: bool,
+) -> (Workspace, Option<Arc<FsStorageBackend>>) {
     let fs = workspace.fs_storage().cloned();
     if !persist {
         let sessions = Arc::new(ReadOnlySessionBackend::new(workspace.sessions().clone()));
@@ -942,7 +1152,7 @@ fn load_workspace(
     }
     info!(workspace = %workspace.root(), "Using existing workspace.");
 
-    Ok((workspace, fs))
+    (workspace, fs)
 }
 
 const JP_CRATES: &[&str] = &[
@@ -1149,15 +1359,26 @@ pub fn worker_threads() -> Option<NonZeroUsize> {
 ///
 /// Panics if called twice.
 pub(crate) fn build_runtime(threads: Option<NonZeroUsize>, thread_name: &str) -> Result<Runtime> {
-    let mut rt_builder = runtime::Builder::new_multi_thread();
-    rt_builder.max_blocking_threads(1024);
-    rt_builder.enable_all().thread_name(thread_name);
-
     let worker_threads = threads.unwrap_or_else(num_threads).get();
     WORKER_THREADS
         .compare_exchange(0, worker_threads, Ordering::Acquire, Ordering::Relaxed)
         .expect("double thread initialization");
-    rt_builder.worker_threads(worker_threads);
+
+    runtime_with_workers(worker_threads, thread_name)
+}
+
+/// Build an async runtime with a fixed number of worker threads.
+///
+/// Unlike [`build_runtime`], this does not record the thread count, so it can
+/// be called any number of times. The daemon uses it to give every job a
+/// runtime of its own.
+pub(crate) fn runtime_with_workers(worker_threads: usize, thread_name: &str) -> Result<Runtime> {
+    let mut rt_builder = runtime::Builder::new_multi_thread();
+    rt_builder.max_blocking_threads(1024);
+    rt_builder
+        .enable_all()
+        .thread_name(thread_name)
+        .worker_threads(worker_threads);
 
     debug!(worker_threads, "Building runtime.");
     rt_builder.build().map_err(Into::into)
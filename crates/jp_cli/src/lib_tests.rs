@@ -92,7 +92,8 @@ fn build_cfg(
     overrides: &[KeyValueOrPath],
     workspace: Option<&Workspace>,
 ) -> Result<PartialAppConfig> {
-    let pipeline = config_pipeline::ConfigPipeline::new(base, overrides, workspace, None)?;
+    let pipeline =
+        config_pipeline::ConfigPipeline::new(base, overrides, workspace, None, &Env::process())?;
     pipeline.partial_without_conversation()
 }
 
@@ -655,6 +656,7 @@ fn resolve_config_consumes_default_id() {
         &mut workspace,
         None,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -712,6 +714,7 @@ fn resolve_config_applies_the_compact_model_flag() {
         &mut workspace,
         None,
         None,
+        &Env::process(),
     )
     .unwrap();
 
@@ -15,12 +15,12 @@ use serde_json::{Value, to_string, to_string_pretty};
 ///
 /// - `TextPretty` → unicode box-drawing table
 /// - `Text` → pipe-delimited markdown table
-/// - `Json` / `JsonPretty` → JSON array of objects
+/// - `Json` / `Jsonl` / `JsonPretty` → JSON array of objects
 pub fn print_table(printer: &Printer, header: Row, rows: Vec<Row>, footer: bool) {
     let output = match printer.format() {
         OutputFormat::TextPretty => list(header, rows, footer),
         OutputFormat::Text => list_markdown(header, rows),
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Jsonl => {
             let json = list_json(header, rows);
             to_string(&json).unwrap_or_else(|_| json.to_string())
         }
@@ -39,12 +39,12 @@ pub fn print_table(printer: &Printer, header: Row, rows: Vec<Row>, footer: bool)
 ///
 /// - `TextPretty` → borderless aligned table with optional title
 /// - `Text` → pipe-delimited markdown table with optional title
-/// - `Json` / `JsonPretty` → JSON object
+/// - `Json` / `Jsonl` / `JsonPretty` → JSON object
 pub fn print_details(printer: &Printer, title: Option<&str>, body: Details) {
     let output = match printer.format() {
         OutputFormat::TextPretty => details(title, body),
         OutputFormat::Text => details_markdown(title, body),
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Jsonl => {
             let json = details_json(title, body);
             to_string(&json).unwrap_or_else(|_| json.to_string())
         }
@@ -98,11 +98,13 @@ pub fn print_outcome(printer: &Printer, text: &str, json: &Value) {
 /// Print a JSON value in the format dictated by the printer.
 ///
 /// - Text formats → `serde_json::to_string_pretty`
-/// - `Json` → compact JSON
+/// - `Json` / `Jsonl` → compact JSON
 /// - `JsonPretty` → indented JSON
 pub fn print_json(printer: &Printer, value: &Value) {
     let output = match printer.format() {
-        OutputFormat::Json => to_string(value).unwrap_or_else(|_| value.to_string()),
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            to_string(value).unwrap_or_else(|_| value.to_string())
+        }
         _ => to_string_pretty(value).unwrap_or_else(|_| value.to_string()),
     };
 
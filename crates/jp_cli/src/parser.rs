@@ -1,7 +1,8 @@
 use std::convert::Infallible;
 
-use camino::{FromPathBufError, Utf8Path, Utf8PathBuf, absolute_utf8};
+use camino::{FromPathBufError, Utf8Path, Utf8PathBuf};
 use clean_path::Clean as _;
+use jp_config::env::Env;
 use jp_id::Parts;
 use relative_path::RelativePathBuf;
 use tracing::trace;
@@ -16,7 +17,10 @@ pub(crate) enum AttachmentUrlOrPath {
 }
 
 impl AttachmentUrlOrPath {
-    pub fn parse(&self, root: Option<&Utf8Path>) -> Result<Url> {
+    /// Turn the attachment into a URL.
+    ///
+    /// Relative paths are relative to the working directory of `env`.
+    pub fn parse(&self, root: Option<&Utf8Path>, env: &Env) -> Result<Url> {
         let path = match &self {
             AttachmentUrlOrPath::Url(url) => return Ok(url.clone()),
             AttachmentUrlOrPath::Path(path) => path,
@@ -39,7 +43,7 @@ impl AttachmentUrlOrPath {
         let mut path = Utf8PathBuf::from(path);
         if let Some(root) = root {
             if path.is_relative() {
-                path = absolute_utf8(&path).map_err(|error| {
+                path = env.cwd().map(|cwd| cwd.join(&path)).map_err(|error| {
                     Error::Attachment(format!(
                         "Attachment path {path} is relative, but the current directory could not \
                          be determined: {error}",
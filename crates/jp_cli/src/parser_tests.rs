@@ -92,3 +92,15 @@ fn starts_with_known_param_matches_known_names() {
     assert!(!starts_with_known_param("a:-1"));
     assert!(!starts_with_known_param("selected=foo"));
 }
+
+#[test]
+fn relative_path_is_relative_to_env_cwd() {
+    let root = camino_tempfile::tempdir().unwrap();
+    std::fs::create_dir(root.path().join("src")).unwrap();
+    std::fs::write(root.path().join("src/main.rs"), "").unwrap();
+
+    let env = Env::process().with_cwd(root.path().join("src"));
+    let url = parse("main.rs").parse(Some(root.path()), &env).unwrap();
+
+    assert_eq!(url.path().trim_start_matches('/'), "src/main.rs");
+}
@@ -822,16 +822,23 @@ async fn format_args_custom(
         },
     });
 
-    let result = run_tool_command(cmd.clone(), ctx, root, CancellationToken::new(), None)
-        .await
-        .map_err(|e| {
-            warn!(
-                command = %cmd,
-                error = %e,
-                "Custom parameters formatter failed"
-            );
-            format!("Custom parameters formatter '{cmd}' failed: {e}")
-        })?;
+    let result = run_tool_command(
+        cmd.clone(),
+        ctx,
+        root,
+        &invocation.env,
+        CancellationToken::new(),
+        None,
+    )
+    .await
+    .map_err(|e| {
+        warn!(
+            command = %cmd,
+            error = %e,
+            "Custom parameters formatter failed"
+        );
+        format!("Custom parameters formatter '{cmd}' failed: {e}")
+    })?;
 
     match result {
         CommandResult::Success(content) => Ok(content.trim().to_owned()),
@@ -861,6 +868,15 @@ async fn format_args_custom(
             ))
         }
         CommandResult::Cancelled => Ok(String::new()),
+        CommandResult::Background(_) => {
+            warn!(
+                command = %cmd,
+                "Custom parameters formatter tried to continue in the background"
+            );
+            Err(format!(
+                "Custom parameters formatter '{cmd}' returned unexpected Handle"
+            ))
+        }
         CommandResult::RawOutput {
             stdout,
             success: true,
@@ -661,6 +661,7 @@ async fn test_format_args_custom_exposes_invocation_ids() {
     let invocation = jp_llm::tool::InvocationContext {
         workspace_id: "ws-abc".into(),
         conversation_id: "conv-xyz".into(),
+        ..Default::default()
     };
     let result = format_args_custom("my_tool", &args, cmd, root.path(), &invocation)
         .await
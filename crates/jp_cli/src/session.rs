@@ -9,8 +9,7 @@
 //!
 //! See: `docs/rfd/020-parallel-conversations.md`
 
-use std::env;
-
+use jp_config::env::Env;
 use jp_workspace::session::{Session, SessionId, SessionSource};
 use tracing::debug;
 
@@ -28,12 +27,13 @@ const TERMINAL_SESSION_VARS: &[(&str, &str)] = &[
     ("ITERM_SESSION_ID", "iTerm2"),
 ];
 
-/// Resolve the session identity for the current process.
+/// Resolve the session identity for the current process, reading session
+/// variables from `env`.
 ///
 /// Returns `None` if no session identity can be determined (e.g. no controlling
 /// terminal, no `$JP_SESSION`, and no recognized terminal env vars).
-pub(crate) fn resolve() -> Option<Session> {
-    if let Some(session) = from_env("JP_SESSION") {
+pub(crate) fn resolve(env: &Env) -> Option<Session> {
+    if let Some(session) = from_env(env, "JP_SESSION") {
         debug!(id = session.id.as_str(), "Session from $JP_SESSION.");
         return Some(session);
     }
@@ -44,7 +44,7 @@ pub(crate) fn resolve() -> Option<Session> {
     }
 
     for &(var, terminal) in TERMINAL_SESSION_VARS {
-        let Some(session) = from_env(var) else {
+        let Some(session) = from_env(env, var) else {
             continue;
         };
 
@@ -61,8 +61,8 @@ pub(crate) fn resolve() -> Option<Session> {
 }
 
 /// Try to build a session from an environment variable.
-fn from_env(key: &str) -> Option<Session> {
-    let val = env::var(key).ok()?;
+fn from_env(env: &Env, key: &str) -> Option<Session> {
+    let val = env.var(key)?;
     let id = SessionId::new(val)?;
     let source = SessionSource::env(key);
 
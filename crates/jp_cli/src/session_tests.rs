@@ -5,10 +5,21 @@ use super::*;
 #[test]
 fn from_env_returns_none_for_unset_var() {
     // Use a variable name that won't be set in any real environment.
-    let result = from_env("JP_TEST_NONEXISTENT_VAR_83927461");
+    let result = from_env(&Env::process(), "JP_TEST_NONEXISTENT_VAR_83927461");
     assert!(result.is_none());
 }
 
+#[test]
+fn from_env_reads_the_given_env() {
+    let env = Env::captured([("JP_SESSION".to_owned(), "job-session".to_owned())]);
+
+    let session = from_env(&env, "JP_SESSION").unwrap();
+    assert_eq!(session.id.as_str(), "job-session");
+    assert_eq!(session.source, SessionSource::Env {
+        key: "JP_SESSION".to_owned(),
+    });
+}
+
 #[test]
 fn from_env_source_is_env_with_key() {
     // We can't safely set env vars in Rust 2024 without unsafe, so test the
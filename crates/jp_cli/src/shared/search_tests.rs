@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use camino_tempfile::tempdir;
 use chrono::{TimeZone as _, Utc};
-use jp_config::AppConfig;
+use jp_config::{AppConfig, env::Env};
 use jp_conversation::{
     Conversation, ConversationEvent, ConversationId, EventKind,
     event::{ChatRequest, ChatResponse, ToolCallResponse},
@@ -35,6 +35,7 @@ fn setup_ctx_with_conversations(
         Runtime::new().unwrap(),
         Globals::default(),
         config,
+        Env::process(),
         None,
         printer,
     );
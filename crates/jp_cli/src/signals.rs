@@ -81,13 +81,15 @@ enum Routed {
 ///
 /// Created once at application startup; the embedded signal task lives for the
 /// duration of the process.
+/// Clones share the same routing state and signal task.
+#[derive(Clone)]
 pub struct SignalRouter {
     inner: Arc<RouterInner>,
 
     /// Keeps the signal-consuming task attached to the router.
     /// The task runs until the signal source ends (never, for the OS-backed
     /// source); the handle is never awaited or aborted.
-    _signal_task: JoinHandle<()>,
+    _signal_task: Arc<JoinHandle<()>>,
 }
 
 impl SignalRouter {
@@ -144,7 +146,7 @@ impl SignalRouter {
 
         Self {
             inner,
-            _signal_task: signal_task,
+            _signal_task: Arc::new(signal_task),
         }
     }
 
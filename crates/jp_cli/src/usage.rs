@@ -0,0 +1,299 @@
+//! Opt-in, local-only usage log.
+//!
+//! With `user.usage_log` enabled, every command appends a [`UsageRecord`] to
+//! [`LOG_FILE`] in the user data directory.
+//! A record holds the command that ran, the names of the flags it was given,
+//! whether it succeeded, and for queries the model, persona and tool approvals
+//! of every turn.
+//! No conversation content or flag values are recorded.
+//!
+//! Once the log reaches [`MAX_LOG_BYTES`], it is moved aside to a single
+//! rotated file, replacing the previous one.
+//!
+//! `jp stats` summarizes the log.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, BufRead as _, BufReader, Write as _},
+    mem,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, Utc};
+use clap::{ArgMatches, Command, parser::ValueSource};
+use jp_config::AppConfig;
+use jp_workspace::user_data_dir;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// The name of the usage log in the user data directory.
+pub(crate) const LOG_FILE: &str = "usage.jsonl";
+
+/// The size at which the usage log is rotated.
+///
+/// At a few hundred bytes per record, this holds tens of thousands of
+/// commands, and the rotated file as many again.
+pub(crate) const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// The path of the usage log, if the user data directory can be determined.
+pub(crate) fn log_path() -> Option<Utf8PathBuf> {
+    user_data_dir().ok().map(|dir| dir.join(LOG_FILE))
+}
+
+/// A single command invocation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct UsageRecord {
+    /// When the command started.
+    pub(crate) timestamp: DateTime<Utc>,
+
+    /// The name of the command, e.g. `query`.
+    pub(crate) command: String,
+
+    /// The flags the command was given, e.g. `--model`, without their values.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) flags: Vec<String>,
+
+    /// How long the command took, in milliseconds.
+    pub(crate) duration_ms: u64,
+
+    /// Whether the command succeeded.
+    pub(crate) success: bool,
+
+    /// The conversation turns the command ran.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) turns: Vec<TurnUsage>,
+}
+
+/// A single conversation turn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TurnUsage {
+    /// The provider of the model, e.g. `anthropic`.
+    pub(crate) provider: String,
+
+    /// The model, e.g. `anthropic/claude-sonnet-4-5`.
+    pub(crate) model: String,
+
+    /// The name of the assistant, if configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) persona: Option<String>,
+
+    /// How long the turn took, in milliseconds, including tool calls.
+    pub(crate) latency_ms: u64,
+
+    /// Whether the turn completed without an error.
+    pub(crate) success: bool,
+
+    /// The tool calls of the turn.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) tools: Vec<ToolUsage>,
+}
+
+/// A single tool call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ToolUsage {
+    /// The name of the tool.
+    pub(crate) name: String,
+
+    /// Whether the tool was allowed to run.
+    pub(crate) decision: ToolDecision,
+}
+
+/// The permission decision for a tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ToolDecision {
+    /// The tool ran without asking the user.
+    Unattended,
+
+    /// The user allowed the tool to run.
+    Approved,
+
+    /// The user, or a remembered answer, skipped the tool.
+    Denied,
+}
+
+/// Collects the usage of a single run.
+///
+/// Cheap to clone; all clones record into the same run.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UsageRecorder {
+    inner: Arc<Mutex<Recorded>>,
+}
+
+#[derive(Debug, Default)]
+struct Recorded {
+    /// The completed turns.
+    turns: Vec<TurnUsage>,
+
+    /// The tool calls of the turn in progress.
+    tools: Vec<ToolUsage>,
+}
+
+impl UsageRecorder {
+    fn recorded(&self) -> MutexGuard<'_, Recorded> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Record the permission decision for a tool call of the current turn.
+    pub(crate) fn record_tool(&self, name: impl Into<String>, decision: ToolDecision) {
+        let mut recorded = self.recorded();
+        recorded.tools.push(ToolUsage {
+            name: name.into(),
+            decision,
+        });
+    }
+
+    /// Complete the current turn, run with the model of `cfg`.
+    pub(crate) fn record_turn(&self, cfg: &AppConfig, latency: Duration, success: bool) {
+        let model = cfg.assistant.model.id.resolved();
+
+        let mut recorded = self.recorded();
+        let tools = mem::take(&mut recorded.tools);
+        recorded.turns.push(TurnUsage {
+            provider: model.provider.as_str().to_owned(),
+            model: model.to_string(),
+            persona: cfg.assistant.name.clone(),
+            latency_ms: millis(latency),
+            success,
+            tools,
+        });
+    }
+
+    /// Build the record of a finished command.
+    pub(crate) fn finish(
+        &self,
+        command: impl Into<String>,
+        flags: Vec<String>,
+        timestamp: DateTime<Utc>,
+        duration: Duration,
+        success: bool,
+    ) -> UsageRecord {
+        let mut recorded = self.recorded();
+
+        UsageRecord {
+            timestamp,
+            command: command.into(),
+            flags,
+            duration_ms: millis(duration),
+            success,
+            turns: mem::take(&mut recorded.turns),
+        }
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// The names of the flags given on the command line, in `matches` of `cmd`
+/// and its subcommands.
+///
+/// Flags are named by their long form, e.g. `--model`, or their short form if
+/// they have no long form.
+/// Positional arguments, and values in general, are left out.
+pub(crate) fn flag_names(cmd: &Command, matches: &ArgMatches) -> Vec<String> {
+    let mut flags = vec![];
+    let mut current = Some((cmd, matches));
+    while let Some((cmd, matches)) = current {
+        for id in matches.ids() {
+            if matches.value_source(id.as_str()) != Some(ValueSource::CommandLine) {
+                continue;
+            }
+
+            let Some(arg) = cmd.get_arguments().find(|arg| arg.get_id() == id) else {
+                continue;
+            };
+
+            let name = match (arg.get_long(), arg.get_short()) {
+                (Some(long), _) => format!("--{long}"),
+                (None, Some(short)) => format!("-{short}"),
+                (None, None) => continue,
+            };
+
+            if !flags.contains(&name) {
+                flags.push(name);
+            }
+        }
+
+        current = matches
+            .subcommand()
+            .and_then(|(name, matches)| Some((cmd.find_subcommand(name)?, matches)));
+    }
+
+    flags
+}
+
+/// The path the usage log at `path` is rotated to.
+fn rotated_path(path: &Utf8Path) -> Utf8PathBuf {
+    let mut rotated = path.as_str().to_owned();
+    rotated.push_str(".1");
+    rotated.into()
+}
+
+/// Append `record` to the usage log at `path`.
+///
+/// A log of [`MAX_LOG_BYTES`] or more is rotated first.
+pub(crate) fn append(path: &Utf8Path, record: &UsageRecord) -> io::Result<()> {
+    append_capped(path, record, MAX_LOG_BYTES)
+}
+
+fn append_capped(path: &Utf8Path, record: &UsageRecord, max_bytes: u64) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if fs::metadata(path).is_ok_and(|meta| meta.len() >= max_bytes) {
+        fs::rename(path, rotated_path(path))?;
+    }
+
+    // A single write per line keeps concurrent appends from interleaving.
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+/// Read all records from the usage log at `path`, including its rotated
+/// records.
+///
+/// A missing log has no records.
+/// Lines that can't be parsed are skipped with a warning.
+pub(crate) fn read(path: &Utf8Path) -> io::Result<Vec<UsageRecord>> {
+    let mut records = read_file(&rotated_path(path))?;
+    records.extend(read_file(path)?);
+
+    Ok(records)
+}
+
+fn read_file(path: &Utf8Path) -> io::Result<Vec<UsageRecord>> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(error) => return Err(error),
+    };
+
+    let mut records = vec![];
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str(&line) {
+            Ok(record) => records.push(record),
+            Err(error) => warn!(%path, line = index + 1, %error, "Skipping invalid usage record."),
+        }
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+#[path = "usage_tests.rs"]
+mod tests;
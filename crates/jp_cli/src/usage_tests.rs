@@ -0,0 +1,144 @@
+use std::fs;
+
+use camino_tempfile::tempdir;
+use chrono::TimeZone as _;
+use clap::{Arg, ArgAction};
+use pretty_assertions::assert_eq;
+
+use super::*;
+
+fn timestamp() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap()
+}
+
+fn record(command: &str) -> UsageRecord {
+    UsageRecord {
+        timestamp: timestamp(),
+        command: command.to_owned(),
+        flags: vec!["--model".to_owned()],
+        duration_ms: 1500,
+        success: true,
+        turns: vec![TurnUsage {
+            provider: "anthropic".to_owned(),
+            model: "anthropic/claude-sonnet-4-5".to_owned(),
+            persona: None,
+            latency_ms: 1200,
+            success: true,
+            tools: vec![ToolUsage {
+                name: "fs_read_file".to_owned(),
+                decision: ToolDecision::Approved,
+            }],
+        }],
+    }
+}
+
+#[test]
+fn test_finish_takes_recorded_turns() {
+    let recorder = UsageRecorder::default();
+    recorder
+        .recorded()
+        .turns
+        .push(record("query").turns.remove(0));
+
+    let finished = recorder.clone().finish(
+        "query",
+        vec!["--model".to_owned()],
+        timestamp(),
+        Duration::from_millis(1500),
+        true,
+    );
+    assert_eq!(finished, record("query"));
+
+    // A second finish starts from an empty run.
+    let finished = recorder.finish("query", vec![], timestamp(), Duration::ZERO, false);
+    assert!(finished.turns.is_empty());
+}
+
+#[test]
+fn test_record_tool_is_shared_between_clones() {
+    let recorder = UsageRecorder::default();
+    recorder
+        .clone()
+        .record_tool("fs_read_file", ToolDecision::Unattended);
+    recorder.record_tool("git_commit", ToolDecision::Denied);
+
+    let tools = recorder.recorded().tools.clone();
+    assert_eq!(tools, vec![
+        ToolUsage {
+            name: "fs_read_file".to_owned(),
+            decision: ToolDecision::Unattended,
+        },
+        ToolUsage {
+            name: "git_commit".to_owned(),
+            decision: ToolDecision::Denied,
+        },
+    ]);
+}
+
+#[test]
+fn test_append_and_read_round_trip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("nested").join(LOG_FILE);
+
+    assert_eq!(read(&path).unwrap(), vec![]);
+
+    append(&path, &record("query")).unwrap();
+    append(&path, &record("config")).unwrap();
+
+    let records = read(&path).unwrap();
+    assert_eq!(records, vec![record("query"), record("config")]);
+}
+
+#[test]
+fn test_append_rotates_full_log() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join(LOG_FILE);
+
+    append_capped(&path, &record("query"), 1).unwrap();
+    append_capped(&path, &record("config"), 1).unwrap();
+    append_capped(&path, &record("stats"), 1).unwrap();
+
+    // Only one rotated log is kept.
+    assert_eq!(read_file(&rotated_path(&path)).unwrap(), vec![record(
+        "config"
+    )]);
+    assert_eq!(read(&path).unwrap(), vec![
+        record("config"),
+        record("stats")
+    ]);
+}
+
+#[test]
+fn test_flag_names() {
+    let cmd = Command::new("jp")
+        .arg(Arg::new("verbose").short('v').action(ArgAction::Count))
+        .subcommand(
+            Command::new("query")
+                .arg(Arg::new("model").short('m').long("model"))
+                .arg(Arg::new("new").long("new").action(ArgAction::SetTrue))
+                .arg(Arg::new("limit").long("limit").default_value("10"))
+                .arg(Arg::new("query")),
+        );
+
+    let matches = cmd
+        .clone()
+        .try_get_matches_from(["jp", "-vv", "query", "-m", "secret", "--new", "hello"])
+        .unwrap();
+
+    assert_eq!(flag_names(&cmd, &matches), vec![
+        "-v".to_owned(),
+        "--model".to_owned(),
+        "--new".to_owned(),
+    ]);
+}
+
+#[test]
+fn test_read_skips_invalid_lines() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join(LOG_FILE);
+
+    let valid = serde_json::to_string(&record("query")).unwrap();
+    fs::write(&path, format!("{{\"broken\n\n{valid}\n")).unwrap();
+
+    assert_eq!(read(&path).unwrap(), vec![record("query")]);
+}
@@ -0,0 +1,347 @@
+//! Conditional activation of configuration files.
+//!
+//! A file in one of the `config_load_paths` directories is normally only loaded
+//! when requested with `--cfg`.
+//! If the file declares activation conditions, it is also loaded automatically
+//! whenever all of its conditions match:
+//!
+//! ```toml
+//! [activate]
+//! paths = ["crates/**"]     # the current directory, relative to the workspace
+//! branches = ["release/*"]  # the checked out git branch
+//! env = ["CI"]              # environment variables that are set
+//! time = "09:00-17:00"      # the local time of day
+//! languages = ["rust"]      # languages detected in the project
+//! ```
+//!
+//! Within a condition, any of the listed values has to match.
+//! The environment the conditions are evaluated against is gathered by the
+//! caller, see [`ActivationContext`].
+
+use std::{collections::BTreeSet, fmt, str::FromStr};
+
+use camino::Utf8PathBuf;
+use glob::{MatchOptions, Pattern};
+use schematic::{Config, Schema, SchemaBuilder, Schematic};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    assignment::{AssignKeyValue, AssignResult, KvAssignment, missing_key},
+    delta::{PartialConfigDelta, delta_opt, delta_opt_vec},
+    fill::FillDefaults,
+    partial::{ToPartial, partial_opts},
+};
+
+/// Conditions under which a configuration file is loaded automatically.
+#[derive(Debug, Clone, PartialEq, Config)]
+#[config(rename_all = "snake_case")]
+pub struct ActivationConfig {
+    /// Glob patterns matched against the current directory.
+    ///
+    /// Inside a workspace, the directory is relative to the workspace root,
+    /// e.g. `crates/**`.
+    /// A pattern also matches any directory below a matching directory, so
+    /// `crates` matches `crates/jp_cli/src` as well.
+    #[setting(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+
+    /// Glob patterns matched against the checked out git branch, e.g.
+    /// `release/*`.
+    #[setting(default, skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<String>,
+
+    /// Environment variables of which at least one has to be set.
+    #[setting(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+
+    /// The local time of day, e.g. `09:00-17:00`.
+    ///
+    /// A range that ends before it starts wraps around midnight.
+    pub time: Option<TimeRange>,
+
+    /// Languages detected in the project, e.g. `rust` or `python`.
+    #[setting(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+}
+
+impl AssignKeyValue for PartialActivationConfig {
+    fn assign(&mut self, kv: KvAssignment) -> AssignResult {
+        match kv.key_string().as_str() {
+            "" => kv.try_merge_object(self)?,
+            _ if kv.p("paths") => kv.try_some_vec_of_strings(&mut self.paths)?,
+            _ if kv.p("branches") => kv.try_some_vec_of_strings(&mut self.branches)?,
+            _ if kv.p("env") => kv.try_some_vec_of_strings(&mut self.env)?,
+            "time" => self.time = kv.try_some_from_str()?,
+            _ if kv.p("languages") => kv.try_some_vec_of_strings(&mut self.languages)?,
+            _ => return missing_key(&kv),
+        }
+
+        Ok(())
+    }
+}
+
+impl PartialConfigDelta for PartialActivationConfig {
+    fn delta(&self, next: Self) -> Self {
+        Self {
+            paths: delta_opt_vec(self.paths.as_ref(), next.paths),
+            branches: delta_opt_vec(self.branches.as_ref(), next.branches),
+            env: delta_opt_vec(self.env.as_ref(), next.env),
+            time: delta_opt(self.time.as_ref(), next.time),
+            languages: delta_opt_vec(self.languages.as_ref(), next.languages),
+        }
+    }
+}
+
+impl FillDefaults for PartialActivationConfig {
+    fn fill_from(self, defaults: Self) -> Self {
+        Self {
+            paths: self.paths.or(defaults.paths),
+            branches: self.branches.or(defaults.branches),
+            env: self.env.or(defaults.env),
+            time: self.time.or(defaults.time),
+            languages: self.languages.or(defaults.languages),
+        }
+    }
+}
+
+impl ToPartial for ActivationConfig {
+    fn to_partial(&self) -> Self::Partial {
+        Self::Partial {
+            paths: (!self.paths.is_empty()).then(|| self.paths.clone()),
+            branches: (!self.branches.is_empty()).then(|| self.branches.clone()),
+            env: (!self.env.is_empty()).then(|| self.env.clone()),
+            time: partial_opts(self.time.as_ref(), None),
+            languages: (!self.languages.is_empty()).then(|| self.languages.clone()),
+        }
+    }
+}
+
+impl PartialActivationConfig {
+    /// Whether any activation condition is declared.
+    #[must_use]
+    pub fn is_declared(&self) -> bool {
+        [&self.paths, &self.branches, &self.env, &self.languages]
+            .into_iter()
+            .any(|v| v.as_ref().is_some_and(|v| !v.is_empty()))
+            || self.time.is_some()
+    }
+
+    /// Evaluate the declared conditions against `ctx`.
+    ///
+    /// The configuration is active if the returned list is non-empty, and all
+    /// of its conditions match.
+    #[must_use]
+    pub fn evaluate(&self, ctx: &ActivationContext) -> Vec<Condition> {
+        let mut conditions = vec![];
+        let mut push = |name, patterns: &[String], matched| {
+            if !patterns.is_empty() {
+                conditions.push(Condition {
+                    name,
+                    pattern: patterns.join(", "),
+                    matched,
+                });
+            }
+        };
+
+        let paths = self.paths.as_deref().unwrap_or_default();
+        push(
+            "paths",
+            paths,
+            ctx.path.as_ref().and_then(|path| {
+                let options = MatchOptions {
+                    require_literal_separator: true,
+                    ..MatchOptions::new()
+                };
+
+                path.ancestors()
+                    .filter(|dir| !dir.as_str().is_empty())
+                    .find(|dir| any_match(paths, dir.as_str(), options))
+                    .map(|dir| dir.to_string())
+            }),
+        );
+
+        let branches = self.branches.as_deref().unwrap_or_default();
+        push(
+            "branches",
+            branches,
+            ctx.branch
+                .as_deref()
+                .filter(|branch| any_match(branches, branch, MatchOptions::new()))
+                .map(str::to_owned),
+        );
+
+        let env = self.env.as_deref().unwrap_or_default();
+        push(
+            "env",
+            env,
+            env.iter().find(|name| ctx.env.contains(*name)).cloned(),
+        );
+
+        let languages = self.languages.as_deref().unwrap_or_default();
+        push(
+            "languages",
+            languages,
+            languages
+                .iter()
+                .find(|language| {
+                    ctx.languages
+                        .iter()
+                        .any(|detected| detected.eq_ignore_ascii_case(language))
+                })
+                .cloned(),
+        );
+
+        if let Some(time) = &self.time {
+            push(
+                "time",
+                &[time.to_string()],
+                time.contains(ctx.minute_of_day)
+                    .then(|| format_minute_of_day(ctx.minute_of_day)),
+            );
+        }
+
+        conditions
+    }
+}
+
+/// Returns `true` if `value` matches any of the glob `patterns`.
+///
+/// Invalid patterns never match.
+fn any_match(patterns: &[String], value: &str, options: MatchOptions) -> bool {
+    patterns.iter().any(|pattern| {
+        Pattern::new(pattern).is_ok_and(|pattern| pattern.matches_with(value, options))
+    })
+}
+
+/// The environment activation conditions are evaluated against.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActivationContext {
+    /// The current directory, relative to the workspace root if it is inside
+    /// the workspace.
+    pub path: Option<Utf8PathBuf>,
+
+    /// The checked out git branch.
+    pub branch: Option<String>,
+
+    /// The names of the environment variables that are set.
+    pub env: BTreeSet<String>,
+
+    /// The languages detected in the project.
+    pub languages: Vec<String>,
+
+    /// The local time, in minutes since midnight.
+    pub minute_of_day: u16,
+}
+
+/// An evaluated activation condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    /// The name of the condition, e.g. `paths`.
+    pub name: &'static str,
+
+    /// The values the condition accepts, e.g. `crates/**`.
+    pub pattern: String,
+
+    /// The value that matched the condition, if any.
+    pub matched: Option<String>,
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.name, self.pattern)?;
+        match &self.matched {
+            Some(value) => write!(f, " (matched {value})"),
+            None => write!(f, " (not matched)"),
+        }
+    }
+}
+
+/// A range of the time of day, e.g. `09:00-17:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    /// The start of the range, in minutes since midnight, inclusive.
+    start: u16,
+
+    /// The end of the range, in minutes since midnight, exclusive.
+    end: u16,
+}
+
+impl TimeRange {
+    /// Whether the range contains the given minute of the day.
+    #[must_use]
+    pub const fn contains(&self, minute_of_day: u16) -> bool {
+        if self.start <= self.end {
+            self.start <= minute_of_day && minute_of_day < self.end
+        } else {
+            minute_of_day >= self.start || minute_of_day < self.end
+        }
+    }
+}
+
+impl FromStr for TimeRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |time: &str| {
+            let (hours, minutes) = time.trim().split_once(':')?;
+            let hours = hours.parse::<u16>().ok().filter(|v| *v <= 24)?;
+            let minutes = minutes.parse::<u16>().ok().filter(|v| *v < 60)?;
+            Some(hours * 60 + minutes).filter(|v| *v <= 24 * 60)
+        };
+
+        s.split_once('-')
+            .and_then(|(start, end)| {
+                Some(Self {
+                    start: parse(start)?,
+                    end: parse(end)?,
+                })
+            })
+            .ok_or_else(|| format!("invalid time range '{s}', expected e.g. '09:00-17:00'"))
+    }
+}
+
+impl fmt::Display for TimeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            format_minute_of_day(self.start),
+            format_minute_of_day(self.end)
+        )
+    }
+}
+
+/// Format minutes since midnight as `HH:MM`.
+fn format_minute_of_day(minute: u16) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+impl Serialize for TimeRange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeRange {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl Schematic for TimeRange {
+    fn schema_name() -> Option<String> {
+        Some("TimeRange".into())
+    }
+
+    fn build_schema(mut schema: SchemaBuilder) -> Schema {
+        let mut schema = schema.string(schematic::schema::StringType::default());
+        schema.set_description("A range of the local time of day, e.g. \"09:00-17:00\".");
+        schema
+    }
+}
+
+#[cfg(test)]
+#[path = "activation_tests.rs"]
+mod tests;
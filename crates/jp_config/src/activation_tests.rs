@@ -0,0 +1,178 @@
+use test_log::test;
+
+use super::*;
+
+fn ctx() -> ActivationContext {
+    ActivationContext {
+        path: Some("crates/jp_cli/src".into()),
+        branch: Some("release/1.2".to_owned()),
+        env: BTreeSet::from(["CI".to_owned()]),
+        languages: vec!["rust".to_owned()],
+        minute_of_day: 10 * 60,
+    }
+}
+
+#[test]
+fn test_is_declared() {
+    let mut activation = PartialActivationConfig::default();
+    assert!(!activation.is_declared());
+
+    activation.paths = Some(vec![]);
+    assert!(!activation.is_declared());
+
+    activation.env = Some(vec!["CI".to_owned()]);
+    assert!(activation.is_declared());
+}
+
+#[test]
+fn test_evaluate() {
+    struct TestCase {
+        activation: PartialActivationConfig,
+        want: Vec<(&'static str, Option<&'static str>)>,
+    }
+
+    let cases = vec![
+        ("nothing declared", TestCase {
+            activation: PartialActivationConfig::default(),
+            want: vec![],
+        }),
+        ("path glob", TestCase {
+            activation: PartialActivationConfig {
+                paths: Some(vec!["crates/*".to_owned()]),
+                ..Default::default()
+            },
+            want: vec![("paths", Some("crates/jp_cli"))],
+        }),
+        ("path prefix", TestCase {
+            activation: PartialActivationConfig {
+                paths: Some(vec!["docs".to_owned(), "crates".to_owned()]),
+                ..Default::default()
+            },
+            want: vec![("paths", Some("crates"))],
+        }),
+        ("path glob does not cross directories", TestCase {
+            activation: PartialActivationConfig {
+                paths: Some(vec!["*/src".to_owned()]),
+                ..Default::default()
+            },
+            want: vec![("paths", None)],
+        }),
+        ("branch glob crosses separators", TestCase {
+            activation: PartialActivationConfig {
+                branches: Some(vec!["main".to_owned(), "release*".to_owned()]),
+                ..Default::default()
+            },
+            want: vec![("branches", Some("release/1.2"))],
+        }),
+        ("env", TestCase {
+            activation: PartialActivationConfig {
+                env: Some(vec!["GITHUB_ACTIONS".to_owned(), "CI".to_owned()]),
+                ..Default::default()
+            },
+            want: vec![("env", Some("CI"))],
+        }),
+        ("languages", TestCase {
+            activation: PartialActivationConfig {
+                languages: Some(vec!["Rust".to_owned()]),
+                ..Default::default()
+            },
+            want: vec![("languages", Some("Rust"))],
+        }),
+        ("time", TestCase {
+            activation: PartialActivationConfig {
+                time: Some("09:00-17:00".parse().unwrap()),
+                ..Default::default()
+            },
+            want: vec![("time", Some("10:00"))],
+        }),
+        ("all conditions", TestCase {
+            activation: PartialActivationConfig {
+                paths: Some(vec!["crates".to_owned()]),
+                branches: Some(vec!["main".to_owned()]),
+                env: Some(vec!["CI".to_owned()]),
+                time: Some("22:00-06:00".parse().unwrap()),
+                languages: Some(vec!["python".to_owned()]),
+            },
+            want: vec![
+                ("paths", Some("crates")),
+                ("branches", None),
+                ("env", Some("CI")),
+                ("languages", None),
+                ("time", None),
+            ],
+        }),
+    ];
+
+    for (name, TestCase { activation, want }) in cases {
+        let got = activation
+            .evaluate(&ctx())
+            .into_iter()
+            .map(|condition| (condition.name, condition.matched))
+            .collect::<Vec<_>>();
+
+        let want = want
+            .into_iter()
+            .map(|(name, matched)| (name, matched.map(str::to_owned)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(got, want, "failed case: {name}");
+    }
+}
+
+#[test]
+fn test_evaluate_without_context() {
+    let activation = PartialActivationConfig {
+        paths: Some(vec!["**".to_owned()]),
+        branches: Some(vec!["*".to_owned()]),
+        ..Default::default()
+    };
+
+    let conditions = activation.evaluate(&ActivationContext::default());
+    assert!(
+        conditions
+            .iter()
+            .all(|condition| condition.matched.is_none())
+    );
+}
+
+#[test]
+fn test_time_range() {
+    let range: TimeRange = "09:00-17:30".parse().unwrap();
+    assert_eq!(range.to_string(), "09:00-17:30");
+    assert!(!range.contains(9 * 60 - 1));
+    assert!(range.contains(9 * 60));
+    assert!(range.contains(17 * 60 + 29));
+    assert!(!range.contains(17 * 60 + 30));
+
+    let range: TimeRange = "22:00 - 6:00".parse().unwrap();
+    assert_eq!(range.to_string(), "22:00-06:00");
+    assert!(range.contains(23 * 60));
+    assert!(range.contains(60));
+    assert!(!range.contains(12 * 60));
+
+    for invalid in ["", "09:00", "9-17", "09:00-25:00", "09:60-10:00"] {
+        assert!(invalid.parse::<TimeRange>().is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn test_condition_display() {
+    let condition = Condition {
+        name: "branches",
+        pattern: "main, release/*".to_owned(),
+        matched: Some("release/1.2".to_owned()),
+    };
+    assert_eq!(
+        condition.to_string(),
+        "branches = main, release/* (matched release/1.2)"
+    );
+
+    let condition = Condition {
+        matched: None,
+        ..condition
+    };
+    assert_eq!(
+        condition.to_string(),
+        "branches = main, release/* (not matched)"
+    );
+}
@@ -12,7 +12,10 @@ use schematic::PartialConfig;
 use serde::{Serialize, de::DeserializeOwned};
 use serde_json::{Value, from_str};
 
-use crate::{AppConfig, BoxedError};
+use crate::{
+    AppConfig, BoxedError,
+    reset::{self, Reset},
+};
 
 /// The result of assigning a key-value pair to a configuration.
 pub type AssignResult = Result<(), BoxedError>;
@@ -193,6 +196,34 @@ impl KvAssignment {
         matches!(self.strategy, Strategy::Merge)
     }
 
+    /// Whether the value is a reset marker, such as `!unset`.
+    ///
+    /// See [`crate::reset`].
+    #[must_use]
+    pub(crate) fn is_reset(&self) -> bool {
+        match &self.value {
+            KvValue::Json(Value::String(v)) | KvValue::String(v) => Reset::from_marker(v).is_some(),
+            KvValue::Json(_) => false,
+        }
+    }
+
+    /// Remove the reset markers from the value, returning the dotted paths of
+    /// the options they reset.
+    ///
+    /// If the value itself is a marker, the option at the key of the assignment
+    /// is reset, and the value is left as-is, see [`Self::is_reset`].
+    pub(crate) fn take_resets(&mut self) -> IndexMap<String, Reset> {
+        let path = self.key.dotted_path();
+
+        match &mut self.value {
+            KvValue::Json(Value::String(v)) | KvValue::String(v) => Reset::from_marker(v)
+                .map(|reset| (path, reset))
+                .into_iter()
+                .collect(),
+            KvValue::Json(value) => reset::take_markers(value, &path),
+        }
+    }
+
     /// Create a root-level JSON assignment with an empty key.
     ///
     /// When assigned via [`AssignKeyValue::assign`], the object's top-level
@@ -1128,6 +1159,20 @@ impl KvKey {
         self.path.is_empty()
     }
 
+    /// The full path of the key, delimited by dots.
+    ///
+    /// Keys delimited by underscores, such as those of environment variables,
+    /// are matched against the known configuration fields.
+    fn dotted_path(&self) -> String {
+        match self.delim {
+            KeyDelim::Dot => self.full_path.clone(),
+            KeyDelim::Underscore => AppConfig::fields()
+                .into_iter()
+                .find(|field| field.replace('.', "_") == self.full_path)
+                .unwrap_or_else(|| self.full_path.clone()),
+        }
+    }
+
     /// Trim the segment from the start of the key.
     ///
     /// For example, given the key `foo.bar.baz`, calling `trim_prefix("foo")`
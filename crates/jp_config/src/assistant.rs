@@ -176,6 +176,7 @@ fn default_instructions(_: &()) -> TransformResult<MergeableVec<PartialInstructi
     Ok(MergeableVec::Merged(MergedVec {
         strategy: None,
         dedup: None,
+        remove: vec![],
         discard_when_merged: true,
         value: vec![PartialInstructionsConfig {
             title: Some("How to respond to the user".into()),
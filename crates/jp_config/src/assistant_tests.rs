@@ -35,6 +35,7 @@ fn test_assistant_config_instructions() {
             // field is `true`, and when we do `try_from_cli` we trigger
             // `try_vec_of_nested` on `&mut [PartialInstructionsConfig]`,
             // NOT on the `MergeableVec<PartialInstructionsConfig>`. This
+            // means `remove: vec![],
             // means `discard_when_merged` is left untouched. This is
             // *correct*, but it might be confusing in some cases, so we
             // might want to change this in the future.
@@ -64,6 +65,7 @@ fn test_assistant_config_instructions() {
                     ..Default::default()
                 }
             ],
+            remove: vec![],
             discard_when_merged: true,
         })
     );
@@ -90,6 +92,7 @@ fn test_assistant_config_instructions() {
                     ..Default::default()
                 }
             ],
+            remove: vec![],
             discard_when_merged: true,
         })
     );
@@ -105,6 +108,7 @@ fn test_assistant_config_instructions() {
                 title: Some("qux".into()),
                 ..Default::default()
             }],
+            remove: vec![],
             discard_when_merged: true,
         })
     );
@@ -120,6 +124,7 @@ fn test_assistant_config_instructions() {
                 title: Some("boop".into()),
                 ..Default::default()
             }],
+            remove: vec![],
             discard_when_merged: true,
         })
     );
@@ -138,6 +143,7 @@ fn test_assistant_config_instructions() {
                 items: Some(vec!["one".into()]),
                 ..Default::default()
             }],
+            remove: vec![],
             discard_when_merged: true,
         })
     );
@@ -154,6 +160,7 @@ fn test_assistant_config_instructions() {
                 items: Some(vec!["two".into()]),
                 ..Default::default()
             }],
+            remove: vec![],
             discard_when_merged: true,
         })
     );
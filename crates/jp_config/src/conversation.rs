@@ -444,6 +444,7 @@ const fn default_attachments(
         value: vec![],
         strategy: None,
         dedup: Some(true),
+        remove: vec![],
         discard_when_merged: true,
     }))
 }
@@ -49,6 +49,7 @@ fn default_rules(_: &()) -> schematic::TransformResult<MergeableVec<PartialCompa
         value: PartialCompactionConfig::builtin_rules(),
         strategy: None,
         dedup: None,
+        remove: vec![],
         discard_when_merged: true,
     }))
 }
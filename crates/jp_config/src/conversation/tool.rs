@@ -16,6 +16,10 @@ use crate::{
     assistant::PartialAssistantConfig,
     conversation::tool::{
         access::{AccessConfig, PartialAccessConfig},
+        concurrency::{
+            ConcurrencyConfig, PartialConcurrencyConfig, PartialToolsConcurrencyConfig,
+            ToolsConcurrencyConfig,
+        },
         style::{DisplayStyleConfig, PartialDisplayStyleConfig},
     },
     delta::{PartialConfigDelta, delta_opt, delta_opt_partial, delta_opt_vec, delta_vec},
@@ -27,6 +31,7 @@ use crate::{
 };
 
 pub mod access;
+pub mod concurrency;
 pub mod style;
 
 /// Tools configuration.
@@ -137,6 +142,17 @@ impl ToolsConfig {
         })
     }
 
+    /// Names of the enabled tools that can prompt the user.
+    ///
+    /// See [`ToolConfigWithDefaults::prompts_user`].
+    #[must_use]
+    pub fn prompting_tools(&self) -> Vec<&str> {
+        self.iter()
+            .filter(|(_, tool)| tool.is_enabled() && tool.prompts_user())
+            .map(|(name, _)| name)
+            .collect()
+    }
+
     /// Iterate tool configurations mutably.
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut ToolConfig)> {
         self.tools.iter_mut().map(|(k, v)| (k.as_str(), v))
@@ -246,6 +262,12 @@ pub struct ToolsDefaultsConfig {
     /// How to display the results of the tool in the terminal.
     #[setting(nested)]
     pub style: DisplayStyleConfig,
+
+    /// Limits on how many tool calls run at the same time.
+    ///
+    /// See [`concurrency`] for details.
+    #[setting(nested)]
+    pub concurrency: Option<ToolsConcurrencyConfig>,
 }
 
 /// Default `cancellation_response`: a canned rejection notice that asks the
@@ -270,6 +292,7 @@ impl AssignKeyValue for PartialToolsDefaultsConfig {
             "result" => self.result = kv.try_some_from_str()?,
             "cancellation_response" => self.cancellation_response = kv.try_some_string()?,
             _ if kv.p("style") => self.style.assign(kv)?,
+            _ if kv.p("concurrency") => self.concurrency.assign(kv)?,
             _ => return missing_key(&kv),
         }
 
@@ -289,6 +312,7 @@ impl PartialConfigDelta for PartialToolsDefaultsConfig {
                 next.cancellation_response,
             ),
             style: self.style.delta(next.style),
+            concurrency: delta_opt_partial(self.concurrency.as_ref(), next.concurrency),
         }
     }
 }
@@ -304,6 +328,7 @@ impl FillDefaults for PartialToolsDefaultsConfig {
                 .cancellation_response
                 .or(defaults.cancellation_response),
             style: self.style.fill_from(defaults.style),
+            concurrency: self.concurrency.or(defaults.concurrency),
         }
     }
 }
@@ -322,6 +347,7 @@ impl ToPartial for ToolsDefaultsConfig {
                 defaults.cancellation_response,
             ),
             style: self.style.to_partial(),
+            concurrency: partial_opt_config(self.concurrency.as_ref(), defaults.concurrency),
         }
     }
 }
@@ -355,6 +381,15 @@ pub struct ToolConfig {
     #[setting(nested)]
     pub command: Option<CommandConfigOrString>,
 
+    /// Run the command in a pseudo-terminal.
+    /// Only used for local tools.
+    ///
+    /// For commands that need a terminal, such as interactive installers or
+    /// REPLs.
+    /// Their output is shown live, you can type into them while they run, and
+    /// the transcript of the session is the tool result.
+    pub pty: Option<bool>,
+
     /// A short summary of what the tool does.
     ///
     /// This is always included in the tool definition sent to the LLM.
@@ -461,6 +496,12 @@ pub struct ToolConfig {
     /// access; declaring any rule switches the tool to default-deny.
     #[setting(nested)]
     pub access: Option<AccessConfig>,
+
+    /// How calls of the tool run alongside other tool calls.
+    ///
+    /// See [`concurrency`] for details.
+    #[setting(nested)]
+    pub concurrency: Option<ConcurrencyConfig>,
 }
 
 impl AssignKeyValue for PartialToolConfig {
@@ -471,6 +512,7 @@ impl AssignKeyValue for PartialToolConfig {
             "enable" => self.enable = kv.try_some_object_bool_or_from_str()?,
             _ if kv.p("enable") => self.enable.assign(kv)?,
             _ if kv.p("command") => self.command.assign(kv)?,
+            "pty" => self.pty = kv.try_some_bool()?,
             "summary" => self.summary = kv.try_some_string()?,
             "description" => self.description = kv.try_some_string()?,
             "examples" => self.examples = kv.try_some_string()?,
@@ -483,6 +525,7 @@ impl AssignKeyValue for PartialToolConfig {
             "questions" => self.questions = kv.try_object()?,
             _ if kv.p("options") => kv.assign_to_entry(&mut self.options)?,
             _ if kv.p("access") => self.access.assign(kv)?,
+            _ if kv.p("concurrency") => self.concurrency.assign(kv)?,
             _ => return missing_key(&kv),
         }
 
@@ -496,6 +539,7 @@ impl PartialConfigDelta for PartialToolConfig {
             source: delta_opt(self.source.as_ref(), next.source),
             enable: delta_opt_partial(self.enable.as_ref(), next.enable),
             command: delta_opt_partial(self.command.as_ref(), next.command),
+            pty: delta_opt(self.pty.as_ref(), next.pty),
             summary: delta_opt(self.summary.as_ref(), next.summary),
             description: delta_opt(self.description.as_ref(), next.description),
             examples: delta_opt(self.examples.as_ref(), next.examples),
@@ -552,6 +596,7 @@ impl PartialConfigDelta for PartialToolConfig {
                 })
                 .collect(),
             access: delta_opt_partial(self.access.as_ref(), next.access),
+            concurrency: delta_opt_partial(self.concurrency.as_ref(), next.concurrency),
         }
     }
 }
@@ -564,6 +609,7 @@ impl ToPartial for ToolConfig {
             source: partial_opt(&self.source, defaults.source),
             enable: partial_opt_config(self.enable.as_ref(), defaults.enable),
             command: partial_opt_config(self.command.as_ref(), defaults.command),
+            pty: partial_opts(self.pty.as_ref(), defaults.pty),
             summary: partial_opts(self.summary.as_ref(), defaults.summary),
             description: partial_opts(self.description.as_ref(), defaults.description),
             examples: partial_opts(self.examples.as_ref(), defaults.examples),
@@ -591,6 +637,7 @@ impl ToPartial for ToolConfig {
                 .map(|(k, v)| (k.clone(), v.clone()))
                 .collect(),
             access: partial_opt_config(self.access.as_ref(), defaults.access),
+            concurrency: partial_opt_config(self.concurrency.as_ref(), defaults.concurrency),
         }
     }
 }
@@ -1104,6 +1151,12 @@ impl ToolConfigWithDefaults {
             .map(CommandConfigOrString::command)
     }
 
+    /// Return whether the command runs in a pseudo-terminal.
+    #[must_use]
+    pub fn pty(&self) -> bool {
+        self.tool.pty.unwrap_or(false)
+    }
+
     /// Return the source of the tool.
     #[must_use]
     pub const fn source(&self) -> &ToolSource {
@@ -1233,6 +1286,34 @@ impl ToolConfigWithDefaults {
         self.tool.access.as_ref()
     }
 
+    /// Return the concurrency declarations of the tool, if any.
+    #[must_use]
+    pub const fn concurrency(&self) -> Option<&ConcurrencyConfig> {
+        self.tool.concurrency.as_ref()
+    }
+
+    /// Return the concurrency limits shared by all tools, if any.
+    #[must_use]
+    pub const fn shared_concurrency(&self) -> Option<&ToolsConcurrencyConfig> {
+        self.defaults.concurrency.as_ref()
+    }
+
+    /// Whether running the tool can prompt the user.
+    ///
+    /// That is the case when the user is asked to approve or edit the call or
+    /// its result, or to answer one of the tool's questions that has no fixed
+    /// answer.
+    #[must_use]
+    pub fn prompts_user(&self) -> bool {
+        matches!(self.run(), RunMode::Ask | RunMode::Edit)
+            || matches!(self.result(), ResultMode::Ask | ResultMode::Edit)
+            || self
+                .tool
+                .questions
+                .values()
+                .any(|question| question.target.is_user() && question.answer.is_none())
+    }
+
     /// Return the question target for the given question ID.
     #[must_use]
     pub fn question_target(&self, question_id: &str) -> Option<&QuestionTarget> {
@@ -0,0 +1,178 @@
+//! Concurrency limits for tool calls.
+//!
+//! The tool calls of a single response run in parallel.
+//! `concurrency` limits how many of them run at once, and declares which calls
+//! conflict with each other.
+//! Conflicting calls run one after the other, in the order the assistant
+//! requested them.
+//!
+//! ```toml
+//! [conversation.tools.'*'.concurrency]
+//! limit = 8
+//! groups = { cargo = 1 }
+//!
+//! [conversation.tools.cargo_test.concurrency]
+//! group = "cargo"
+//!
+//! [conversation.tools.fs_modify_file.concurrency]
+//! mutates = ["path"]
+//!
+//! [conversation.tools.git_commit.concurrency]
+//! exclusive = true
+//! ```
+
+use indexmap::IndexMap;
+use schematic::Config;
+
+use crate::{
+    assignment::{AssignKeyValue, AssignResult, KvAssignment, missing_key},
+    delta::{PartialConfigDelta, delta_opt, delta_opt_vec},
+    partial::{ToPartial, partial_opt, partial_opts},
+};
+
+/// Concurrency limits shared by all tools.
+#[derive(Debug, Clone, PartialEq, Config)]
+#[config(rename_all = "snake_case")]
+pub struct ToolsConcurrencyConfig {
+    /// The maximum number of tool calls that run at the same time.
+    ///
+    /// When unset, all tool calls of a response may run at once.
+    pub limit: Option<u32>,
+
+    /// The maximum number of calls that run at the same time, per group.
+    ///
+    /// Tools join a group with `conversation.tools.<name>.concurrency.group`.
+    /// A group without a limit does not restrict its tools.
+    #[setting(default, merge = schematic::merge::merge_iter)]
+    pub groups: IndexMap<String, u32>,
+}
+
+impl ToolsConcurrencyConfig {
+    /// The limit of the given group, if it has one.
+    #[must_use]
+    pub fn group_limit(&self, group: &str) -> Option<u32> {
+        self.groups.get(group).copied()
+    }
+}
+
+impl AssignKeyValue for PartialToolsConcurrencyConfig {
+    fn assign(&mut self, mut kv: KvAssignment) -> AssignResult {
+        match kv.key_string().as_str() {
+            "" => kv.try_merge_object(self)?,
+            "limit" => self.limit = kv.try_some_u32()?,
+            "groups" => self.groups = Some(kv.try_object()?),
+            _ if kv.p("groups") => {
+                let Some(group) = kv.trim_prefix_any() else {
+                    return missing_key(&kv);
+                };
+                let limit = kv.try_u32()?;
+                self.groups.get_or_insert_default().insert(group, limit);
+            }
+            _ => return missing_key(&kv),
+        }
+
+        Ok(())
+    }
+}
+
+impl PartialConfigDelta for PartialToolsConcurrencyConfig {
+    fn delta(&self, next: Self) -> Self {
+        Self {
+            limit: delta_opt(self.limit.as_ref(), next.limit),
+            groups: delta_opt(self.groups.as_ref(), next.groups),
+        }
+    }
+}
+
+impl ToPartial for ToolsConcurrencyConfig {
+    fn to_partial(&self) -> Self::Partial {
+        let defaults = Self::Partial::default();
+
+        Self::Partial {
+            limit: partial_opts(self.limit.as_ref(), defaults.limit),
+            groups: partial_opt(&self.groups, defaults.groups),
+        }
+    }
+}
+
+/// Concurrency declarations of a single tool.
+#[derive(Debug, Clone, PartialEq, Config)]
+#[config(rename_all = "snake_case")]
+pub struct ConcurrencyConfig {
+    /// The maximum number of calls of this tool that run at the same time.
+    ///
+    /// Set to `1` to run the calls of the tool one after the other.
+    pub limit: Option<u32>,
+
+    /// The group the tool belongs to.
+    ///
+    /// The tools of a group share the group's limit, set in
+    /// `conversation.tools.'*'.concurrency.groups`.
+    pub group: Option<String>,
+
+    /// Run calls of this tool on their own.
+    ///
+    /// An exclusive call waits for all running calls to finish, and no other
+    /// call starts until it has finished.
+    pub exclusive: Option<bool>,
+
+    /// The arguments that name the paths a call modifies.
+    ///
+    /// Two calls that modify the same path, relative to the workspace root,
+    /// run one after the other.
+    /// An argument can hold a single path, or a list of paths.
+    /// Calls that modify no declared path keep running in parallel.
+    #[setting(default, merge = schematic::merge::append_vec)]
+    pub mutates: Vec<String>,
+}
+
+impl ConcurrencyConfig {
+    /// Whether calls of this tool run on their own.
+    #[must_use]
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive.unwrap_or(false)
+    }
+}
+
+impl AssignKeyValue for PartialConcurrencyConfig {
+    fn assign(&mut self, mut kv: KvAssignment) -> AssignResult {
+        match kv.key_string().as_str() {
+            "" => kv.try_merge_object(self)?,
+            "limit" => self.limit = kv.try_some_u32()?,
+            "group" => self.group = kv.try_some_string()?,
+            "exclusive" => self.exclusive = kv.try_some_bool()?,
+            _ if kv.p("mutates") => kv.try_some_vec_of_strings(&mut self.mutates)?,
+            _ => return missing_key(&kv),
+        }
+
+        Ok(())
+    }
+}
+
+impl PartialConfigDelta for PartialConcurrencyConfig {
+    fn delta(&self, next: Self) -> Self {
+        Self {
+            limit: delta_opt(self.limit.as_ref(), next.limit),
+            group: delta_opt(self.group.as_ref(), next.group),
+            exclusive: delta_opt(self.exclusive.as_ref(), next.exclusive),
+            mutates: delta_opt_vec(self.mutates.as_ref(), next.mutates),
+        }
+    }
+}
+
+impl ToPartial for ConcurrencyConfig {
+    fn to_partial(&self) -> Self::Partial {
+        let defaults = Self::Partial::default();
+
+        Self::Partial {
+            limit: partial_opts(self.limit.as_ref(), defaults.limit),
+            group: partial_opts(self.group.as_ref(), defaults.group),
+            exclusive: partial_opts(self.exclusive.as_ref(), defaults.exclusive),
+            mutates: partial_opt(&self.mutates, defaults.mutates),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "concurrency_tests.rs"]
+mod tests;
@@ -0,0 +1,56 @@
+use super::*;
+use crate::assignment::KvAssignment;
+
+#[test]
+fn exclusive_defaults_to_false() {
+    let config =
+        ConcurrencyConfig::from_partial(PartialConcurrencyConfig::default(), vec![]).unwrap();
+
+    assert!(!config.is_exclusive());
+    assert!(config.mutates.is_empty());
+}
+
+#[test]
+fn assigns_group_limit() {
+    let mut partial = PartialToolsConcurrencyConfig::default();
+
+    let kv = KvAssignment::try_from_cli("groups.cargo", "1").unwrap();
+    partial.assign(kv).unwrap();
+    let kv = KvAssignment::try_from_cli("limit", "8").unwrap();
+    partial.assign(kv).unwrap();
+
+    let config = ToolsConcurrencyConfig::from_partial(partial, vec![]).unwrap();
+    assert_eq!(config.limit, Some(8));
+    assert_eq!(config.group_limit("cargo"), Some(1));
+    assert_eq!(config.group_limit("npm"), None);
+}
+
+#[test]
+fn assigns_tool_declarations() {
+    let mut partial = PartialConcurrencyConfig::default();
+
+    let kv = KvAssignment::try_from_cli("mutates", "path").unwrap();
+    partial.assign(kv).unwrap();
+    let kv = KvAssignment::try_from_cli("exclusive", "true").unwrap();
+    partial.assign(kv).unwrap();
+
+    let config = ConcurrencyConfig::from_partial(partial, vec![]).unwrap();
+    assert_eq!(config.mutates, vec!["path".to_owned()]);
+    assert!(config.is_exclusive());
+}
+
+#[test]
+fn to_partial_round_trips() {
+    let config = ConcurrencyConfig {
+        limit: Some(2),
+        group: Some("cargo".to_owned()),
+        exclusive: None,
+        mutates: vec!["path".to_owned()],
+    };
+
+    let partial = config.to_partial();
+    assert_eq!(partial.limit, Some(2));
+    assert_eq!(partial.group.as_deref(), Some("cargo"));
+    assert_eq!(partial.exclusive, None);
+    assert_eq!(partial.mutates, Some(vec!["path".to_owned()]));
+}
@@ -70,6 +70,43 @@ fn access_on_local_tool_is_accepted_by_validation() {
     assert!(build(partial).is_ok());
 }
 
+#[test]
+fn prompting_tools_lists_enabled_tools_that_prompt() {
+    use crate::{PartialAppConfig, util::build};
+
+    let mut partial = PartialAppConfig::new_test();
+    let tool = |run, enable| PartialToolConfig {
+        source: Some(ToolSource::Local { tool: None }),
+        enable: Some(enable),
+        run: Some(run),
+        result: Some(ResultMode::Unattended),
+        ..Default::default()
+    };
+    partial.conversation.tools.tools.extend([
+        (
+            "ask".to_owned(),
+            tool(RunMode::Ask, PartialEnableConfig::ON),
+        ),
+        (
+            "edit".to_owned(),
+            tool(RunMode::Edit, PartialEnableConfig::ON),
+        ),
+        (
+            "off".to_owned(),
+            tool(RunMode::Ask, PartialEnableConfig::OFF),
+        ),
+        (
+            "unattended".to_owned(),
+            tool(RunMode::Unattended, PartialEnableConfig::ON),
+        ),
+    ]);
+
+    let config = build(partial).unwrap();
+    assert_eq!(config.conversation.tools.prompting_tools(), vec![
+        "ask", "edit"
+    ]);
+}
+
 #[test]
 fn test_enable_config_from_bool() {
     assert_eq!(PartialEnableConfig::from(true), PartialEnableConfig::ON);
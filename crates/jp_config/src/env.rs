@@ -0,0 +1,105 @@
+//! The environment variables and working directory a command runs with.
+
+use std::{collections::BTreeMap, io, sync::Arc};
+
+use camino::{Utf8PathBuf, absolute_utf8};
+
+/// The environment variables and working directory a command runs with.
+///
+/// This is the environment of the current process, unless it was captured
+/// elsewhere.
+/// The daemon, for example, runs every job with the environment and working
+/// directory of the client that sent it, without touching its own.
+///
+/// Everything that reads the environment on behalf of a command — config
+/// overrides, secret references, the programs it starts, relative paths —
+/// reads it from here.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Env {
+    /// The captured variables, or `None` for the process environment.
+    vars: Option<Arc<BTreeMap<String, String>>>,
+
+    /// The captured working directory, or `None` for that of the process.
+    cwd: Option<Utf8PathBuf>,
+}
+
+impl Env {
+    /// The environment of the current process.
+    #[must_use]
+    pub const fn process() -> Self {
+        Self {
+            vars: None,
+            cwd: None,
+        }
+    }
+
+    /// An environment with exactly `vars`.
+    #[must_use]
+    pub fn captured(vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        Self {
+            vars: Some(Arc::new(vars.into_iter().collect())),
+            cwd: None,
+        }
+    }
+
+    /// Run in `cwd` instead of the working directory of the process.
+    ///
+    /// `cwd` must be absolute.
+    #[must_use]
+    pub fn with_cwd(mut self, cwd: impl Into<Utf8PathBuf>) -> Self {
+        self.cwd = Some(cwd.into());
+        self
+    }
+
+    /// The absolute working directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the working directory of the process is used, and it
+    /// cannot be read or is not valid unicode.
+    pub fn cwd(&self) -> io::Result<Utf8PathBuf> {
+        match &self.cwd {
+            Some(cwd) => Ok(cwd.clone()),
+            None => absolute_utf8("."),
+        }
+    }
+
+    /// The value of the variable `key`, if it is set and valid unicode.
+    #[must_use]
+    pub fn var(&self, key: &str) -> Option<String> {
+        match &self.vars {
+            Some(vars) => vars.get(key).cloned(),
+            None => std::env::var(key).ok(),
+        }
+    }
+
+    /// All variables with a unicode name and value.
+    #[must_use]
+    pub fn vars(&self) -> Vec<(String, String)> {
+        match &self.vars {
+            Some(vars) => vars
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            None => std::env::vars_os()
+                .filter_map(|(key, value)| {
+                    Some((key.into_string().ok()?, value.into_string().ok()?))
+                })
+                .collect(),
+        }
+    }
+
+    /// The variables child processes must run with instead of inheriting the
+    /// process environment, if any.
+    ///
+    /// Programs started for a command clear their environment and set these
+    /// instead.
+    #[must_use]
+    pub fn overrides(&self) -> Option<&BTreeMap<String, String>> {
+        self.vars.as_deref()
+    }
+}
+
+#[cfg(test)]
+#[path = "env_tests.rs"]
+mod tests;
@@ -0,0 +1,35 @@
+use super::*;
+
+#[test]
+fn test_process_env() {
+    let env = Env::process();
+
+    assert_eq!(env.var("PATH"), std::env::var("PATH").ok());
+    assert!(env.vars().iter().any(|(key, _)| key == "PATH"));
+    assert_eq!(env.overrides(), None);
+}
+
+#[test]
+fn test_captured_env() {
+    let env = Env::captured([("JP_ENV_TESTS".to_owned(), "yes".to_owned())]);
+
+    assert_eq!(env.var("JP_ENV_TESTS").as_deref(), Some("yes"));
+    assert_eq!(env.var("PATH"), None);
+    assert_eq!(env.vars(), vec![(
+        "JP_ENV_TESTS".to_owned(),
+        "yes".to_owned()
+    )]);
+    assert_eq!(env.overrides().map(BTreeMap::len), Some(1));
+}
+
+#[test]
+fn test_cwd() {
+    assert_eq!(
+        Env::process().cwd().unwrap(),
+        camino::absolute_utf8(".").unwrap()
+    );
+
+    let env = Env::process().with_cwd("/tmp/jp");
+    assert_eq!(env.cwd().unwrap(), "/tmp/jp");
+    assert_eq!(env.overrides(), None);
+}
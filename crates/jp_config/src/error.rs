@@ -43,6 +43,33 @@ pub enum Error {
         chain: Vec<PathBuf>,
     },
 
+    /// A configuration file could not be parsed.
+    #[error("invalid configuration file {}: {error}", path.display())]
+    InvalidFile {
+        /// The path of the configuration file.
+        path: PathBuf,
+        /// The underlying error.
+        error: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// A template file could not be read or parsed.
+    #[error("invalid template file {}: {error}", path.display())]
+    TemplateFile {
+        /// The path of the template file.
+        path: PathBuf,
+        /// The underlying error.
+        error: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// An option could not be reset to `!unset` or `!default`.
+    #[error("unable to reset `{key}`: {error}")]
+    Reset {
+        /// The dotted path of the option.
+        key: String,
+        /// The underlying error.
+        error: Box<dyn std::error::Error + Send + Sync>,
+    },
+
     /// A custom configuration error.
     ///
     /// Used to wrap arbitrary error types from external sources (URL parsing,
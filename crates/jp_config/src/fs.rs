@@ -6,10 +6,10 @@ use camino::{Utf8Path, Utf8PathBuf};
 use clean_path::clean;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
-use toml_edit::{DocumentMut, Item, TableLike};
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, TableLike};
 use tracing::debug;
 
-use crate::{Error, PartialAppConfig};
+use crate::{Error, PartialAppConfig, reset};
 
 /// Application name for configuration file storage paths.
 const APPLICATION: &str = "jp";
@@ -102,9 +102,11 @@ pub struct ConfigFile {
 impl ConfigFile {
     /// Deserialize the file content into a valid type.
     ///
+    /// # Errors
+    ///
     /// Returns an error if the file content could not be deserialized into the
     /// provided type `T`.
-    fn deserialize<T: for<'de> Deserialize<'de>>(
+    pub fn deserialize<T: for<'de> Deserialize<'de>>(
         &self,
     ) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
         match self.format {
@@ -115,6 +117,29 @@ impl ConfigFile {
         }
     }
 
+    /// Deserialize the file content into a partial configuration.
+    ///
+    /// Unlike [`Self::deserialize`], options set to a reset marker, such as
+    /// `"!unset"`, are collected into the `reset` option, see [`crate::reset`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file content is not a valid partial
+    /// configuration.
+    pub fn deserialize_partial(
+        &self,
+    ) -> Result<PartialAppConfig, Box<dyn std::error::Error + Send + Sync>> {
+        let mut value = self.deserialize::<serde_json::Value>()?;
+        let resets = reset::take_markers(&mut value, "");
+
+        let mut partial: PartialAppConfig = serde_json::from_value(value)?;
+        if !resets.is_empty() {
+            partial.reset.get_or_insert_default().extend(resets);
+        }
+
+        Ok(partial)
+    }
+
     /// Edit the file content using the provided function.
     ///
     /// # Errors
@@ -146,25 +171,51 @@ impl ConfigFile {
     /// Only the keys present in the serialized `delta` are touched; all other
     /// content (comments, whitespace, key order) is preserved.
     ///
-    /// Currently only supports TOML files.
+    /// Supports TOML, JSON and JSON5 files.
     ///
     /// # Errors
     ///
-    /// Returns an error if the file is not TOML, or if parsing/serialization
-    /// fails.
+    /// Returns an error if the file is YAML, or if parsing/serialization fails.
     pub fn merge_delta<T: Serialize>(
         &mut self,
         delta: &T,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        if self.format != Format::Toml {
-            return Err("format-preserving merge is only supported for TOML files".into());
+        match self.format {
+            Format::Toml => {
+                let mut doc: DocumentMut = self.content.parse()?;
+                let delta_toml = toml::to_string_pretty(delta)?;
+                let delta_doc: DocumentMut = delta_toml.parse()?;
+                deep_merge_toml(doc.as_table_mut(), delta_doc.as_table());
+                self.content = doc.to_string();
+            }
+            Format::Json | Format::Json5 => {
+                let empty = self.content.trim().is_empty();
+                let content = if empty { "{}\n" } else { self.content.as_str() };
+
+                let doc = if self.format == Format::Json {
+                    json_edit::Document::parse(content)
+                } else {
+                    json_edit::Document::parse_json5(content)
+                }
+                .map_err(|errors| {
+                    errors
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })?;
+
+                let mut delta = serde_json::to_value(delta)?;
+                prune_empty(&mut delta);
+                json_edit::deep_merge(&doc, &delta)?;
+
+                self.content = doc.to_string();
+            }
+            Format::Yaml => {
+                return Err("format-preserving merge is not supported for YAML files".into());
+            }
         }
 
-        let mut doc: DocumentMut = self.content.parse()?;
-        let delta_toml = toml::to_string_pretty(delta)?;
-        let delta_doc: DocumentMut = delta_toml.parse()?;
-        deep_merge_toml(doc.as_table_mut(), delta_doc.as_table());
-        self.content = doc.to_string();
         Ok(())
     }
 
@@ -189,6 +240,21 @@ impl ConfigFile {
     }
 }
 
+/// Remove `null` values and objects left empty by doing so from `value`.
+///
+/// Nested partial configurations serialize to empty objects when none of
+/// their fields are set, which would otherwise be added to the document.
+fn prune_empty(value: &mut serde_json::Value) {
+    if let serde_json::Value::Object(map) = value {
+        map.values_mut().for_each(prune_empty);
+        map.retain(|_, value| match value {
+            serde_json::Value::Null => false,
+            serde_json::Value::Object(map) => !map.is_empty(),
+            _ => true,
+        });
+    }
+}
+
 /// Recursively merge `source` table entries into `target`.
 ///
 /// Only keys present in `source` are touched.
@@ -200,18 +266,66 @@ impl ConfigFile {
 /// value (e.g. `conversation.tools.foo.enable.state` over an inline `enable = {
 /// state = true, allow_toggle = "never" }`) deep-merges the subfield instead of
 /// replacing the whole inline table.
+///
+/// A value that replaces another value keeps the comments and whitespace
+/// around the key and the old value, such as a comment on the line above or a
+/// trailing comment.
 fn deep_merge_toml(target: &mut dyn TableLike, source: &dyn TableLike) {
     for (key, source_item) in source.iter() {
-        if let Some(target_table) = target.get_mut(key).and_then(Item::as_table_like_mut)
+        let Some(target_item) = target.get_mut(key) else {
+            target.insert(key, detach(source_item));
+            continue;
+        };
+
+        if let Some(target_table) = target_item.as_table_like_mut()
             && let Some(source_table) = source_item.as_table_like()
         {
             deep_merge_toml(target_table, source_table);
+        } else if let Item::Value(target_value) = target_item
+            && let Item::Value(source_value) = source_item
+        {
+            let decor = target_value.decor().clone();
+            *target_value = source_value.clone();
+            *target_value.decor_mut() = decor;
         } else {
-            target.insert(key, source_item.clone());
+            // The kind of item changes, e.g. from a table to a value, so the
+            // key is formatted anew.
+            target.insert(key, detach(source_item));
         }
     }
 }
 
+/// Copy `item` without the positions of its tables in the source document.
+///
+/// Tables are rendered in order of their position, so a copied table would
+/// otherwise be placed wherever its position falls among the existing tables.
+/// Without a position, a new table follows the table before it, keeping it
+/// next to the tables it is nested in.
+fn detach(item: &Item) -> Item {
+    match item {
+        Item::Table(table) => Item::Table(detach_table(table)),
+        Item::ArrayOfTables(array) => {
+            let mut detached = ArrayOfTables::new();
+            for table in array {
+                detached.push(detach_table(table));
+            }
+            Item::ArrayOfTables(detached)
+        }
+        Item::None | Item::Value(_) => item.clone(),
+    }
+}
+
+/// Copy `table` and its sub-tables without their positions, see [`detach`].
+fn detach_table(table: &Table) -> Table {
+    let mut detached = Table::new();
+    detached.set_implicit(table.is_implicit());
+    for (key, item) in table {
+        detached.insert(key, detach(item));
+    }
+
+    detached
+}
+
 /// A configuration file format.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Format {
@@ -380,16 +494,25 @@ pub fn expand_tilde<T: AsRef<str>>(path: impl AsRef<str>, home: Option<T>) -> Op
 
 /// Load a partial configuration, with optional fallback.
 ///
+/// Any options that `next` resets are reset in `prev` first, see
+/// [`crate::reset`].
+///
 /// # Errors
 ///
-/// Returns an error if merging the partials fails, which returns a
-/// [`schematic::MergeError`].
+/// Returns an error if resetting an option fails, or if merging the partials
+/// fails, which returns a [`schematic::MergeError`].
 pub fn load_partial(
     mut prev: PartialAppConfig,
     next: PartialAppConfig,
 ) -> Result<PartialAppConfig, Error> {
     use schematic::PartialConfig as _;
 
+    // Options reset by `next` are reset before merging, so that `next` can
+    // set a new value for them.
+    if let Some(resets) = &next.reset {
+        reset::apply(&mut prev, resets)?;
+    }
+
     prev.merge(&(), next)?;
     Ok(prev)
 }
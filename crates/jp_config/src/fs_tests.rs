@@ -213,6 +213,177 @@ fn deep_merge_recurses_inline_table_into_inline_table() {
     assert!(result.contains(r#"allow_toggle = "never""#), "{result}");
 }
 
+#[test]
+fn deep_merge_preserves_comment_above_replaced_key() {
+    let target = indoc! {r#"
+        [section]
+        # Why this is set.
+        key = "old"
+        other = 1
+    "#};
+    let result = merge(target, "[section]\nkey = \"new\"\n");
+    assert_eq!(result, indoc! {r#"
+        [section]
+        # Why this is set.
+        key = "new"
+        other = 1
+    "#});
+}
+
+#[test]
+fn deep_merge_preserves_trailing_comment_of_replaced_value() {
+    let target = "key = \"old\"   # Trailing comment\n";
+    let result = merge(target, "key = \"new\"\n");
+    assert_eq!(result, "key = \"new\"   # Trailing comment\n");
+}
+
+#[test]
+fn deep_merge_preserves_dotted_keys() {
+    let target = indoc! {r#"
+        assistant.name = "Jean-Pierre"
+        assistant.system_prompt = "Be brief."
+    "#};
+    let result = merge(target, "[assistant]\nname = \"JP\"\n");
+    assert_eq!(result, indoc! {r#"
+        assistant.name = "JP"
+        assistant.system_prompt = "Be brief."
+    "#});
+}
+
+#[test]
+fn deep_merge_appends_new_table_after_existing_tables() {
+    let target = indoc! {"
+        [a]
+        k = 1
+
+        [b]
+        k = 2
+    "};
+    let result = merge(target, "[c]\nk = 3\n");
+    assert_eq!(result, indoc! {"
+        [a]
+        k = 1
+
+        [b]
+        k = 2
+
+        [c]
+        k = 3
+    "});
+}
+
+#[test]
+fn deep_merge_places_new_table_after_its_siblings() {
+    let target = indoc! {"
+        [a.x]
+        k = 1
+
+        [b]
+        k = 2
+    "};
+    let result = merge(target, "[a.y]\nk = 3\n");
+    assert_eq!(result, indoc! {"
+        [a.x]
+        k = 1
+
+        [a.y]
+        k = 3
+
+        [b]
+        k = 2
+    "});
+}
+
+/// Documents covering the TOML syntax used in configuration files.
+const ROUNDTRIP_DOCUMENTS: &[&str] = &[
+    indoc! {r#"
+        # Leading comment
+
+        # Key comment
+        key = "value" # Trailing comment
+
+        [table] # Header comment
+        nested = 1
+    "#},
+    indoc! {r#"
+        assistant.name = "JP"
+        'quoted key' = true
+        "double quoted" = 1
+    "#},
+    indoc! {r#"
+        enable = { state = true, allow_toggle = "never" }
+        list = [
+            "a", # first
+            "b",
+        ]
+    "#},
+    indoc! {r#"
+        [[extends]]
+        path = "a.toml"
+
+        [[extends]]
+        path = "b.toml"
+        strategy = "after"
+    "#},
+    indoc! {r#"
+        basic = "tab\t"
+        literal = 'C:\path'
+        multi = """
+        line one
+        line two
+        """
+        raw = '''
+          kept as is
+        '''
+    "#},
+    "key   =   1\n\n  [indented]\n  value=2\n",
+    "",
+];
+
+#[test]
+fn deep_merge_empty_source_is_lossless() {
+    for document in ROUNDTRIP_DOCUMENTS {
+        assert_eq!(merge(document, ""), *document);
+    }
+}
+
+#[test]
+fn deep_merge_unchanged_values_is_lossless() {
+    // Arrays of tables are replaced as a whole, so they are left out.
+    for document in ROUNDTRIP_DOCUMENTS
+        .iter()
+        .filter(|document| !document.contains("[["))
+    {
+        assert_eq!(merge(document, document), *document);
+    }
+}
+
+#[test]
+fn merge_delta_preserves_comments_of_replaced_keys() {
+    let mut config = ConfigFile {
+        path: "test.toml".into(),
+        format: Format::Toml,
+        content: indoc! {"
+            # Conversation defaults.
+            [conversation]
+            # Keep new conversations out of version control.
+            start_local = false # for now
+        "}
+        .to_owned(),
+    };
+
+    let mut delta = PartialAppConfig::default();
+    delta.conversation.start_local = Some(true);
+
+    config.merge_delta(&delta).unwrap();
+    assert_eq!(config.content, indoc! {"
+        # Conversation defaults.
+        [conversation]
+        # Keep new conversations out of version control.
+        start_local = true # for now
+    "});
+}
+
 #[test]
 fn merge_delta_preserves_formatting() {
     let original = indoc! {r#"
@@ -327,10 +498,64 @@ fn merge_delta_into_empty_file() {
 }
 
 #[test]
-fn merge_delta_rejects_non_toml() {
+fn merge_delta_preserves_json5_comments() {
+    let mut config = ConfigFile {
+        path: "test.json5".into(),
+        format: Format::Json5,
+        content: indoc! {r#"
+            {
+              // Keep this comment.
+              assistant: { name: "Jean-Pierre" },
+              conversation: { start_local: false },
+            }
+        "#}
+        .to_owned(),
+    };
+
+    let mut delta = PartialAppConfig::default();
+    delta.conversation.start_local = Some(true);
+
+    config.merge_delta(&delta).unwrap();
+    assert!(
+        config.content.contains("// Keep this comment."),
+        "{}",
+        config.content
+    );
+    assert!(
+        config.content.contains(r#"name: "Jean-Pierre""#),
+        "{}",
+        config.content
+    );
+    assert!(config.content.contains("true"), "{}", config.content);
+    assert!(!config.content.contains("false"), "{}", config.content);
+
+    // Unset nested configurations are not added to the file.
+    assert!(!config.content.contains("providers"), "{}", config.content);
+}
+
+#[test]
+fn merge_delta_into_empty_json_file() {
     let mut config = ConfigFile {
         path: "test.json".into(),
         format: Format::Json,
+        content: String::new(),
+    };
+
+    let mut delta = PartialAppConfig::default();
+    delta.conversation.start_local = Some(true);
+
+    config.merge_delta(&delta).unwrap();
+    assert_eq!(
+        config.deserialize::<serde_json::Value>().unwrap(),
+        serde_json::json!({ "conversation": { "start_local": true } })
+    );
+}
+
+#[test]
+fn merge_delta_rejects_yaml() {
+    let mut config = ConfigFile {
+        path: "test.yaml".into(),
+        format: Format::Yaml,
         content: "{}".to_owned(),
     };
 
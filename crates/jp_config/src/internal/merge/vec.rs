@@ -4,6 +4,7 @@
 
 use schematic::{MergeResult, Schematic};
 use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
 
 use crate::types::vec::{MergeableVec, MergedVec, MergedVecStrategy};
 
@@ -36,17 +37,26 @@ where
         return Ok(Some(next));
     }
 
-    let mut prev_value = match prev {
-        MergeableVec::Vec(v) => v,
-        MergeableVec::Merged(v) => v.value,
+    let (mut prev_value, mut remove) = match prev {
+        MergeableVec::Vec(v) => (v, vec![]),
+        MergeableVec::Merged(v) => (v.value, v.remove),
     };
 
     let next_is_merged = matches!(next, MergeableVec::Merged(_));
-    let (strategy, mut next_value, discard_when_merged) = match next {
-        MergeableVec::Vec(v) => (None, v, false),
-        MergeableVec::Merged(v) => (v.strategy, v.value, v.discard_when_merged),
+    let (strategy, mut next_value, next_remove, discard_when_merged) = match next {
+        MergeableVec::Vec(v) => (None, v, vec![], false),
+        MergeableVec::Merged(v) => (v.strategy, v.value, v.remove, v.discard_when_merged),
     };
 
+    // Remove the items of prev that next removes, and keep all removals around,
+    // to remove the same items when the result is merged into another value.
+    prev_value.retain(|item| !next_remove.iter().any(|pattern| is_match(item, pattern)));
+    for pattern in next_remove {
+        if !remove.contains(&pattern) {
+            remove.push(pattern);
+        }
+    }
+
     let mut value = match strategy {
         None | Some(MergedVecStrategy::Append) => {
             prev_value.append(&mut next_value);
@@ -66,12 +76,14 @@ where
     // Carry forward as Option<bool>: Some(true) when active, None otherwise.
     let resolved_dedup = if dedup { Some(true) } else { None };
 
-    // When dedup is active, always use Merged to carry the flag forward.
-    Ok(Some(if next_is_merged || dedup {
+    // When dedup is active or items are removed, always use Merged to carry
+    // them forward.
+    Ok(Some(if next_is_merged || dedup || !remove.is_empty() {
         MergeableVec::Merged(MergedVec {
             value,
             strategy,
             dedup: resolved_dedup,
+            remove,
             discard_when_merged,
         })
     } else {
@@ -79,6 +91,30 @@ where
     }))
 }
 
+/// Returns `true` if `item` has every value that `pattern` sets.
+///
+/// Both are compared by their JSON representation, in which unset values of
+/// partial configurations are either omitted, `null`, or empty.
+fn is_match<T: Serialize>(item: &T, pattern: &T) -> bool {
+    match (serde_json::to_value(item), serde_json::to_value(pattern)) {
+        (Ok(item), Ok(pattern)) => contains(&item, &pattern),
+        _ => false,
+    }
+}
+
+/// See [`is_match`].
+fn contains(value: &Value, pattern: &Value) -> bool {
+    match (value, pattern) {
+        (_, Value::Null) => true,
+        (_, Value::Array(pattern)) if pattern.is_empty() => true,
+        (_, Value::Object(pattern)) if pattern.is_empty() => true,
+        (Value::Object(value), Value::Object(pattern)) => pattern
+            .iter()
+            .all(|(key, pattern)| contains(value.get(key).unwrap_or(&Value::Null), pattern)),
+        _ => value == pattern,
+    }
+}
+
 /// Extract the explicit dedup flag from a `MergeableVec`.
 const fn dedup_flag<T>(v: &MergeableVec<T>) -> Option<bool> {
     match v {
@@ -94,6 +130,7 @@ fn ensure_dedup<T>(v: MergeableVec<T>) -> MergeableVec<T> {
             value,
             strategy: None,
             dedup: Some(true),
+            remove: vec![],
             discard_when_merged: false,
         }),
         MergeableVec::Merged(mut m) => {
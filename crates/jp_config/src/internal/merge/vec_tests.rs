@@ -1,3 +1,4 @@
+use serde_json::{Value, json};
 use test_log::test;
 
 use super::*;
@@ -23,12 +24,14 @@ fn test_vec_with_strategy() {
                 value: vec![4, 5, 6],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
             expected: MergeableVec::Merged(MergedVec {
                 value: vec![1, 2, 3, 4, 5, 6],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
         },
@@ -38,12 +41,14 @@ fn test_vec_with_strategy() {
                 value: vec![4, 5, 6],
                 strategy: Some(MergedVecStrategy::Replace),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
             expected: MergeableVec::Merged(MergedVec {
                 value: vec![4, 5, 6],
                 strategy: Some(MergedVecStrategy::Replace),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
         },
@@ -52,6 +57,7 @@ fn test_vec_with_strategy() {
                 value: vec![1, 2, 3],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
             next: MergeableVec::Vec(vec![4, 5, 6]),
@@ -62,18 +68,21 @@ fn test_vec_with_strategy() {
                 value: vec![1, 2, 3],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
             next: MergeableVec::Merged(MergedVec {
                 value: vec![4, 5, 6],
                 strategy: Some(MergedVecStrategy::Replace),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
             expected: MergeableVec::Merged(MergedVec {
                 value: vec![4, 5, 6],
                 strategy: Some(MergedVecStrategy::Replace),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
         },
@@ -82,18 +91,21 @@ fn test_vec_with_strategy() {
                 value: vec![1, 2, 3],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
             next: MergeableVec::Merged(MergedVec {
                 value: vec![4, 5, 6],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
             expected: MergeableVec::Merged(MergedVec {
                 value: vec![1, 2, 3, 4, 5, 6],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
         },
@@ -102,18 +114,21 @@ fn test_vec_with_strategy() {
                 value: vec![1, 2, 3],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
             next: MergeableVec::Merged(MergedVec {
                 value: vec![4, 5, 6],
                 strategy: Some(MergedVecStrategy::Replace),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
             expected: MergeableVec::Merged(MergedVec {
                 value: vec![4, 5, 6],
                 strategy: Some(MergedVecStrategy::Replace),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
         },
@@ -130,6 +145,69 @@ fn test_vec_with_strategy() {
     }
 }
 
+#[test]
+fn test_vec_with_strategy_remove() {
+    let prev = MergeableVec::Vec(vec![1, 2, 3, 2]);
+    let next = MergeableVec::Merged(MergedVec {
+        value: vec![2, 4],
+        strategy: None,
+        dedup: None,
+        remove: vec![2, 5],
+        discard_when_merged: false,
+    });
+
+    // Removals only apply to the previous items, and are carried forward.
+    let merged = vec_with_strategy(prev, next, &()).unwrap().unwrap();
+    assert_eq!(
+        merged,
+        MergeableVec::Merged(MergedVec {
+            value: vec![1, 3, 2, 4],
+            strategy: None,
+            dedup: None,
+            remove: vec![2, 5],
+            discard_when_merged: false,
+        })
+    );
+
+    let next = MergeableVec::Merged(MergedVec {
+        value: vec![],
+        strategy: None,
+        dedup: None,
+        remove: vec![1, 2],
+        discard_when_merged: false,
+    });
+
+    let merged = vec_with_strategy(merged, next, &()).unwrap().unwrap();
+    assert_eq!(
+        merged,
+        MergeableVec::Merged(MergedVec {
+            value: vec![3, 4],
+            strategy: None,
+            dedup: None,
+            remove: vec![2, 5, 1],
+            discard_when_merged: false,
+        })
+    );
+}
+
+#[test]
+fn test_is_match() {
+    let item = json!({ "title": "foo", "items": ["a"] });
+
+    assert!(contains(&item, &json!({ "title": "foo" })));
+    assert!(contains(
+        &item,
+        &json!({ "title": "foo", "description": null })
+    ));
+    assert!(contains(&item, &Value::Null));
+    assert!(!contains(&item, &json!({ "title": "bar" })));
+    assert!(!contains(&item, &json!({ "description": "foo" })));
+    assert!(!contains(&item, &json!({ "items": ["a", "b"] })));
+    assert!(contains(&item, &json!({ "title": "foo", "examples": [] })));
+    assert!(is_match(&"foo", &"foo"));
+    assert!(!is_match(&"foo", &"bar"));
+}
+
 #[test]
 fn test_default_vec() {
     struct TestCase {
@@ -144,6 +222,7 @@ fn test_default_vec() {
                 value: vec![1],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: true,
             }),
             next: MergeableVec::Vec(vec![2]),
@@ -154,18 +233,21 @@ fn test_default_vec() {
                 value: vec![1],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: true,
             }),
             next: MergeableVec::Merged(MergedVec {
                 value: vec![2],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
             expected: MergeableVec::Merged(MergedVec {
                 value: vec![2],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
         }),
@@ -174,18 +256,21 @@ fn test_default_vec() {
                 value: vec![1],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: true,
             }),
             next: MergeableVec::Merged(MergedVec {
                 value: vec![2],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: true,
             }),
             expected: MergeableVec::Merged(MergedVec {
                 value: vec![2],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: true,
             }),
         }),
@@ -194,18 +279,21 @@ fn test_default_vec() {
                 value: vec![1],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: false,
             }),
             next: MergeableVec::Merged(MergedVec {
                 value: vec![2],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: true,
             }),
             expected: MergeableVec::Merged(MergedVec {
                 value: vec![1, 2],
                 strategy: Some(MergedVecStrategy::Append),
                 dedup: None,
+                remove: vec![],
                 discard_when_merged: true,
             }),
         }),
@@ -231,6 +319,7 @@ fn test_dedup_removes_duplicates_on_append() {
         value: vec![1, 2, 3],
         strategy: None,
         dedup: Some(true),
+        remove: vec![],
         discard_when_merged: false,
     });
     let next = MergeableVec::Vec(vec![2, 3, 4]);
@@ -248,6 +337,7 @@ fn test_discard_inherits_dedup_when_next_has_no_opinion() {
         value: vec![],
         strategy: None,
         dedup: Some(true),
+        remove: vec![],
         discard_when_merged: true,
     });
     let config = MergeableVec::Vec(vec![1, 2, 1, 3]);
@@ -264,12 +354,14 @@ fn test_discard_does_not_inherit_dedup_when_next_opts_out() {
         value: vec![],
         strategy: None,
         dedup: Some(true),
+        remove: vec![],
         discard_when_merged: true,
     });
     let config = MergeableVec::Merged(MergedVec {
         value: vec![1, 2, 1, 3],
         strategy: None,
         dedup: Some(false),
+        remove: vec![],
         discard_when_merged: false,
     });
 
@@ -285,6 +377,7 @@ fn test_dedup_sticky_across_non_discarded_merges() {
         value: vec![1, 2],
         strategy: None,
         dedup: Some(true),
+        remove: vec![],
         discard_when_merged: false,
     });
     let overlay = MergeableVec::Vec(vec![2, 3]);
@@ -327,6 +420,7 @@ fn test_is_empty_merged_with_dedup() {
         value: vec![],
         strategy: None,
         dedup: Some(true),
+        remove: vec![],
         discard_when_merged: false,
     });
     assert!(!v.is_empty(), "metadata-only Merged should not be empty");
@@ -338,6 +432,7 @@ fn test_is_empty_merged_with_strategy() {
         value: vec![],
         strategy: Some(MergedVecStrategy::Replace),
         dedup: None,
+        remove: vec![],
         discard_when_merged: false,
     });
     assert!(!v.is_empty());
@@ -349,6 +444,7 @@ fn test_is_empty_merged_no_metadata() {
         value: vec![],
         strategy: None,
         dedup: None,
+        remove: vec![],
         discard_when_merged: false,
     });
     assert!(v.is_empty());
@@ -0,0 +1,324 @@
+//! JSON Schema for configuration files.
+//!
+//! Converts the [`Schema`] of a configuration type into a [JSON Schema]
+//! (draft-07) document, for editors to validate and complete configuration
+//! files with, such as [Taplo] for TOML files.
+//!
+//! The schema describes *partial* files: no field is required, since any file
+//! can set any subset of the configuration. Unknown fields are rejected,
+//! except where a type accepts arbitrary keys, such as the map of tools in
+//! `conversation.tools`.
+//!
+//! [JSON Schema]: https://json-schema.org
+//! [Taplo]: https://taplo.tamasfe.dev
+
+use std::collections::BTreeSet;
+
+use schematic::schema::{
+    EnumType, LiteralValue, ObjectType, Schema, SchemaField, SchemaType, StructType, UnionOperator,
+};
+use serde_json::{Map, Value, json};
+
+/// The JSON Schema dialect of the rendered schema.
+pub const DIALECT: &str = "http://json-schema.org/draft-07/schema#";
+
+/// Render `schema` as a JSON Schema document.
+///
+/// Recursive types are rendered once in `definitions` and referenced with
+/// `$ref`, all other types are rendered inline.
+#[must_use]
+pub fn render(schema: &Schema) -> Value {
+    let mut referenced = BTreeSet::new();
+    collect_references(schema, &mut referenced);
+
+    let mut renderer = Renderer {
+        referenced,
+        definitions: Map::new(),
+    };
+
+    let mut root = Map::new();
+    root.insert("$schema".into(), DIALECT.into());
+    if let Value::Object(body) = renderer.schema(schema) {
+        root.extend(body);
+    }
+
+    if !renderer.definitions.is_empty() {
+        root.insert("definitions".into(), renderer.definitions.into());
+    }
+
+    root.into()
+}
+
+/// Renders schemas, collecting the definitions of referenced types.
+struct Renderer {
+    /// The names of the types that are referenced with `$ref`.
+    referenced: BTreeSet<String>,
+
+    /// The rendered definitions of the referenced types.
+    definitions: Map<String, Value>,
+}
+
+impl Renderer {
+    /// Render a schema, or a reference to it if it is a recursive type.
+    fn schema(&mut self, schema: &Schema) -> Value {
+        let mut out = self.ty(&schema.ty);
+
+        if let Some(description) = &schema.description {
+            out.entry("description")
+                .or_insert_with(|| description.as_str().into());
+        }
+        if schema.deprecated.is_some() {
+            out.insert("deprecated".into(), true.into());
+        }
+        if let Some(default) = schema.ty.get_default() {
+            out.insert("default".into(), literal(default));
+        }
+
+        match &schema.name {
+            Some(name) if self.referenced.contains(name) => {
+                self.definitions
+                    .entry(name.clone())
+                    .or_insert_with(|| out.into());
+
+                reference(name)
+            }
+            _ => out.into(),
+        }
+    }
+
+    /// Render a struct field, with its doc comment as description.
+    fn field(&mut self, field: &SchemaField) -> Value {
+        let mut out = self.schema(&field.schema);
+        if let Value::Object(out) = &mut out {
+            if let Some(comment) = &field.comment {
+                out.insert("description".into(), comment.as_str().into());
+            }
+            if field.deprecated.is_some() {
+                out.insert("deprecated".into(), true.into());
+            }
+        }
+
+        out
+    }
+
+    /// Render the type of a schema, without its metadata.
+    fn ty(&mut self, ty: &SchemaType) -> Map<String, Value> {
+        let out = match ty {
+            SchemaType::Null => json!({ "type": "null" }),
+            SchemaType::Unknown => json!({}),
+            SchemaType::Array(array) => {
+                let mut out = json!({
+                    "type": "array",
+                    "items": self.schema(&array.items_type),
+                });
+                insert_opt(&mut out, "minItems", array.min_length);
+                insert_opt(&mut out, "maxItems", array.max_length);
+                insert_opt(&mut out, "uniqueItems", array.unique);
+                out
+            }
+            SchemaType::Boolean(_) => json!({ "type": "boolean" }),
+            SchemaType::Enum(enum_type) => self.enumeration(enum_type),
+            SchemaType::Float(float) => {
+                let mut out = json!({ "type": "number" });
+                insert_opt(&mut out, "enum", float.enum_values.clone());
+                insert_opt(&mut out, "minimum", float.min);
+                insert_opt(&mut out, "maximum", float.max);
+                insert_opt(&mut out, "exclusiveMinimum", float.min_exclusive);
+                insert_opt(&mut out, "exclusiveMaximum", float.max_exclusive);
+                insert_opt(&mut out, "multipleOf", float.multiple_of);
+                out
+            }
+            SchemaType::Integer(integer) => {
+                let mut out = json!({ "type": "integer" });
+                let min = integer
+                    .min
+                    .or_else(|| integer.kind.is_unsigned().then_some(0));
+                insert_opt(&mut out, "enum", integer.enum_values.clone());
+                insert_opt(&mut out, "minimum", min);
+                insert_opt(&mut out, "maximum", integer.max);
+                insert_opt(&mut out, "exclusiveMinimum", integer.min_exclusive);
+                insert_opt(&mut out, "exclusiveMaximum", integer.max_exclusive);
+                insert_opt(&mut out, "multipleOf", integer.multiple_of);
+                out
+            }
+            SchemaType::Literal(lit) => json!({ "const": literal(&lit.value) }),
+            SchemaType::Object(object) => self.object(object),
+            SchemaType::Reference(reference_type) => reference(&reference_type.name),
+            SchemaType::Struct(struct_type) => self.structure(struct_type),
+            SchemaType::String(string) => {
+                let mut out = json!({ "type": "string" });
+                insert_opt(&mut out, "enum", string.enum_values.clone());
+                insert_opt(&mut out, "format", string.format.clone());
+                insert_opt(&mut out, "pattern", string.pattern.clone());
+                insert_opt(&mut out, "minLength", string.min_length);
+                insert_opt(&mut out, "maxLength", string.max_length);
+                out
+            }
+            SchemaType::Tuple(tuple) => {
+                let items: Vec<_> = tuple.items_types.iter().map(|s| self.schema(s)).collect();
+                json!({
+                    "type": "array",
+                    "minItems": items.len(),
+                    "maxItems": items.len(),
+                    "items": items,
+                })
+            }
+            SchemaType::Union(union) => {
+                let variants: Vec<_> = union
+                    .variants_types
+                    .iter()
+                    .map(|s| self.schema(s))
+                    .collect();
+
+                match union.operator {
+                    UnionOperator::AnyOf => json!({ "anyOf": variants }),
+                    UnionOperator::OneOf => json!({ "oneOf": variants }),
+                }
+            }
+        };
+
+        match out {
+            Value::Object(out) => out,
+            _ => Map::new(),
+        }
+    }
+
+    /// Render a map, with its keys as property names.
+    fn object(&mut self, object: &ObjectType) -> Value {
+        let mut out = json!({
+            "type": "object",
+            "additionalProperties": self.schema(&object.value_type),
+        });
+
+        let keys = self.schema(&object.key_type);
+        if keys != json!({ "type": "string" }) {
+            out["propertyNames"] = keys;
+        }
+        insert_opt(&mut out, "minProperties", object.min_length);
+        insert_opt(&mut out, "maxProperties", object.max_length);
+        out
+    }
+
+    /// Render a struct, with its fields as properties.
+    fn structure(&mut self, struct_type: &StructType) -> Value {
+        let mut properties = Map::new();
+        let mut additional = Value::Bool(false);
+        self.fields(struct_type, &mut properties, &mut additional);
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            "additionalProperties": additional,
+        })
+    }
+
+    /// Render an enum as a list of values, or as a union of its variants if
+    /// any of them carries data.
+    fn enumeration(&mut self, enum_type: &EnumType) -> Value {
+        let data_variants = enum_type.variants.as_ref().filter(|variants| {
+            variants
+                .values()
+                .any(|field| !matches!(field.schema.ty, SchemaType::Literal(_)))
+        });
+
+        data_variants.map_or_else(
+            || json!({ "enum": enum_type.values.iter().map(literal).collect::<Vec<_>>() }),
+            |variants| {
+                let variants: Vec<_> = variants.values().map(|field| self.field(field)).collect();
+                json!({ "anyOf": variants })
+            },
+        )
+    }
+
+    /// Collect the properties of a struct.
+    ///
+    /// Fields of flattened structs are added to `properties`, and a flattened
+    /// map allows additional properties of its value type.
+    fn fields(
+        &mut self,
+        struct_type: &StructType,
+        properties: &mut Map<String, Value>,
+        additional: &mut Value,
+    ) {
+        for (name, field) in &struct_type.fields {
+            if !field.flatten {
+                let schema = self.field(field);
+                for alias in &field.aliases {
+                    properties.insert(alias.clone(), schema.clone());
+                }
+                properties.insert(name.clone(), schema);
+                continue;
+            }
+
+            match &field.schema.ty {
+                SchemaType::Struct(inner) => self.fields(inner, properties, additional),
+                SchemaType::Object(object) => *additional = self.schema(&object.value_type),
+                _ => *additional = Value::Bool(true),
+            }
+        }
+    }
+}
+
+/// Collect the names of all types referenced in `schema`.
+fn collect_references(schema: &Schema, names: &mut BTreeSet<String>) {
+    if let SchemaType::Reference(reference_type) = &schema.ty {
+        names.insert(reference_type.name.clone());
+        return;
+    }
+
+    let mut visit = |schema: &Schema| collect_references(schema, names);
+    match &schema.ty {
+        SchemaType::Array(array) => visit(&array.items_type),
+        SchemaType::Enum(enum_type) => enum_type
+            .variants
+            .iter()
+            .flat_map(|variants| variants.values())
+            .for_each(|field| visit(&field.schema)),
+        SchemaType::Object(object) => {
+            visit(&object.key_type);
+            visit(&object.value_type);
+        }
+        SchemaType::Struct(struct_type) => struct_type
+            .fields
+            .values()
+            .for_each(|field| visit(&field.schema)),
+        SchemaType::Tuple(tuple) => tuple.items_types.iter().for_each(|s| visit(s)),
+        SchemaType::Union(union) => union.variants_types.iter().for_each(|s| visit(s)),
+        SchemaType::Null
+        | SchemaType::Unknown
+        | SchemaType::Reference(_)
+        | SchemaType::Boolean(_)
+        | SchemaType::Float(_)
+        | SchemaType::Integer(_)
+        | SchemaType::Literal(_)
+        | SchemaType::String(_) => {}
+    }
+}
+
+/// A reference to the definition of a recursive type.
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/definitions/{name}") })
+}
+
+/// Convert a literal value to JSON.
+fn literal(value: &LiteralValue) -> Value {
+    match value {
+        LiteralValue::Bool(v) => (*v).into(),
+        LiteralValue::F32(v) => (*v).into(),
+        LiteralValue::F64(v) => (*v).into(),
+        LiteralValue::Int(v) => (*v).into(),
+        LiteralValue::UInt(v) => (*v).into(),
+        LiteralValue::String(v) => v.as_str().into(),
+    }
+}
+
+/// Insert `value` at `key` of the `out` object, if it is set.
+fn insert_opt(out: &mut Value, key: &str, value: Option<impl Into<Value>>) {
+    if let (Value::Object(out), Some(value)) = (out, value) {
+        out.insert(key.into(), value.into());
+    }
+}
+
+#[cfg(test)]
+#[path = "json_schema_tests.rs"]
+mod tests;
@@ -0,0 +1,129 @@
+use pretty_assertions::assert_eq;
+use schematic::schema::{
+    ArrayType, BooleanType, IntegerKind, IntegerType, ObjectType, ReferenceType, StringType,
+    StructType,
+};
+
+use super::*;
+use crate::AppConfig;
+
+fn field(schema: Schema, comment: &str) -> SchemaField {
+    SchemaField {
+        comment: Some(comment.to_owned()),
+        ..SchemaField::new(schema)
+    }
+}
+
+#[test]
+fn test_render_struct() {
+    let schema = Schema::structure(StructType::new([
+        ("name".to_owned(), SchemaField {
+            aliases: vec!["title".to_owned()],
+            ..field(Schema::string(StringType::default()), "The name.")
+        }),
+        (
+            "count".to_owned(),
+            field(
+                Schema::integer(IntegerType::new_kind(IntegerKind::U8)),
+                "The count.",
+            ),
+        ),
+        ("rest".to_owned(), SchemaField {
+            flatten: true,
+            ..SchemaField::new(Schema::object(ObjectType::new(
+                Schema::string(StringType::default()),
+                Schema::boolean(BooleanType::default()),
+            )))
+        }),
+    ]));
+
+    assert_eq!(
+        render(&schema),
+        json!({
+            "$schema": DIALECT,
+            "type": "object",
+            "properties": {
+                "count": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "The count.",
+                },
+                "name": { "type": "string", "description": "The name." },
+                "title": { "type": "string", "description": "The name." },
+            },
+            "additionalProperties": { "type": "boolean" },
+        })
+    );
+}
+
+#[test]
+fn test_render_enum() {
+    let schema = Schema::enumerable(EnumType::new([
+        LiteralValue::String("ask".to_owned()),
+        LiteralValue::String("unattended".to_owned()),
+    ]));
+
+    assert_eq!(
+        render(&schema),
+        json!({
+            "$schema": DIALECT,
+            "enum": ["ask", "unattended"],
+        })
+    );
+}
+
+#[test]
+fn test_render_recursive_type() {
+    let mut node = Schema::structure(StructType::new([(
+        "children".to_owned(),
+        SchemaField::new(Schema::array(ArrayType::new(Schema::new(
+            SchemaType::Reference(Box::new(ReferenceType {
+                name: "Node".to_owned(),
+            })),
+        )))),
+    )]));
+    node.set_name("Node");
+
+    let schema = Schema::structure(StructType::new([(
+        "root".to_owned(),
+        SchemaField::new(node),
+    )]));
+
+    assert_eq!(
+        render(&schema),
+        json!({
+            "$schema": DIALECT,
+            "type": "object",
+            "properties": {
+                "root": { "$ref": "#/definitions/Node" },
+            },
+            "additionalProperties": false,
+            "definitions": {
+                "Node": {
+                    "type": "object",
+                    "properties": {
+                        "children": {
+                            "type": "array",
+                            "items": { "$ref": "#/definitions/Node" },
+                        },
+                    },
+                    "additionalProperties": false,
+                },
+            },
+        })
+    );
+}
+
+#[test]
+fn test_app_config_json_schema() {
+    let schema = AppConfig::json_schema();
+
+    assert_eq!(schema["$schema"], DIALECT);
+    assert_eq!(schema["additionalProperties"], false);
+    assert!(schema["properties"]["assistant"]["description"].is_string());
+
+    // Tools are configured by name, next to the `*` defaults.
+    let tools = &schema["properties"]["conversation"]["properties"]["tools"];
+    assert!(tools["properties"]["*"].is_object());
+    assert!(tools["additionalProperties"].is_object());
+}
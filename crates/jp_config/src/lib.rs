@@ -32,20 +32,25 @@
     reason = "we don't host the docs, and use them mainly for LSP integration"
 )]
 
+pub mod activation;
 pub mod assignment;
 pub mod assistant;
 pub mod conversation;
 mod delta;
 pub mod editor;
+pub mod env;
 pub mod error;
 pub(crate) mod fill;
 pub mod fs;
 pub(crate) mod internal;
 pub mod interrupt;
+pub mod json_schema;
 pub mod model;
 mod partial;
 pub mod plugins;
 pub mod providers;
+pub mod reset;
+pub mod secret;
 pub mod style;
 pub mod template;
 pub mod types;
@@ -68,15 +73,17 @@ use serde_json::Value;
 pub use validate::Validator;
 
 use crate::{
+    activation::{ActivationConfig, PartialActivationConfig},
     assignment::{AssignKeyValue, AssignResult, KvAssignment, missing_key, type_error},
     assistant::{AssistantConfig, PartialAssistantConfig},
     conversation::{ConversationConfig, PartialConversationConfig},
-    delta::{PartialConfigDelta, delta_opt_vec},
+    delta::{PartialConfigDelta, delta_opt, delta_opt_vec},
     editor::{EditorConfig, PartialEditorConfig},
     interrupt::{InterruptConfig, PartialInterruptConfig},
     partial::partial_opt,
     plugins::{PartialPluginsConfig, PluginsConfig},
     providers::{PartialProviderConfig, ProviderConfig},
+    reset::Reset,
     style::{PartialStyleConfig, StyleConfig},
     template::{PartialTemplateConfig, TemplateConfig},
     types::extending_path::ExtendingRelativePath,
@@ -103,7 +110,7 @@ pub struct AppConfig {
     /// Directories to search for additional configuration files.
     ///
     /// Files in these directories can be loaded on demand using the `--cfg`
-    /// flag.
+    /// flag, or automatically if they declare `activate` conditions.
     /// Use this to organize reusable configurations, such as personas or tool
     /// sets.
     ///
@@ -120,10 +127,30 @@ pub struct AppConfig {
     /// the other configuration roots searched by `--cfg <name>` are consulted.
     ///
     /// Extended files are loaded unconditionally, unlike files in
-    /// `config_load_paths`, which load only when requested with `--cfg`.
+    /// `config_load_paths`, which load only when requested with `--cfg`, or
+    /// when their `activate` conditions match.
     #[setting(default = vec!["config.d/**/*".into()], merge = schematic::merge::preserve)]
     pub extends: Vec<ExtendingRelativePath>,
 
+    /// Options to reset before this configuration is merged.
+    ///
+    /// The key is the dotted path of the option, and the value is either
+    /// `unset` or `default`.
+    /// Options set to `"!unset"` or `"!default"` are collected here when the
+    /// configuration is loaded, see [`reset`] for details.
+    #[setting(optional, merge = schematic::merge::merge_iter)]
+    pub reset: IndexMap<String, Reset>,
+
+    /// Conditions under which this file is loaded automatically.
+    ///
+    /// Only used by files in `config_load_paths`, which are otherwise loaded
+    /// only when requested with `--cfg`.
+    /// The conditions are removed when the file is applied, so they are never
+    /// part of the resulting configuration.
+    /// See [`activation`] for the supported conditions.
+    #[setting(nested)]
+    pub activate: ActivationConfig,
+
     /// Assistant configuration.
     ///
     /// The assistant is the component that takes user input, and uses an LLM to
@@ -196,10 +223,17 @@ pub struct AppConfig {
 
 impl AssignKeyValue for PartialAppConfig {
     fn assign(&mut self, mut kv: KvAssignment) -> AssignResult {
+        // Reset markers apply to this configuration directly, and are recorded
+        // to reset any configuration this one is merged into.
+        self.reset(kv.take_resets())?;
+
         match kv.key_string().as_str() {
+            _ if kv.is_reset() => {}
             // Root-level JSON object: merge each top-level key individually.
             "" => return kv.try_merge_object(self),
             "inherit" => self.inherit = kv.try_some_bool()?,
+            "reset" => self.reset(kv.try_object()?)?,
+            _ if kv.p("activate") => self.activate.assign(kv)?,
             _ if kv.p("config_load_paths") => {
                 let parser = |kv: KvAssignment| match kv.value.clone().into_value() {
                     Value::String(v) => Ok(RelativePathBuf::from(v)),
@@ -238,6 +272,12 @@ impl PartialConfigDelta for PartialAppConfig {
             // `inherit` value of `true`.
             inherit: None,
 
+            reset: delta_opt(self.reset.as_ref(), next.reset),
+
+            // Activation conditions are interpreted when the configuration
+            // files are loaded, and have no meaning after that.
+            activate: PartialActivationConfig::default(),
+
             config_load_paths: delta_opt_vec(
                 self.config_load_paths.as_ref(),
                 next.config_load_paths,
@@ -262,6 +302,8 @@ impl FillDefaults for PartialAppConfig {
             inherit: self.inherit.or(defaults.inherit),
             config_load_paths: self.config_load_paths.or(defaults.config_load_paths),
             extends: self.extends.or(defaults.extends),
+            reset: self.reset.or(defaults.reset),
+            activate: self.activate.fill_from(defaults.activate),
             assistant: self.assistant.fill_from(defaults.assistant),
             conversation: self.conversation.fill_from(defaults.conversation),
             style: self.style.fill_from(defaults.style),
@@ -283,6 +325,10 @@ impl ToPartial for AppConfig {
             inherit: partial_opt(&self.inherit, defaults.inherit),
             config_load_paths: partial_opt(&self.config_load_paths, defaults.config_load_paths),
             extends: partial_opt(&self.extends, defaults.extends),
+            reset: (!self.reset.is_empty()).then(|| self.reset.clone()),
+            // Activation conditions are interpreted when the configuration
+            // files are loaded, and are not part of the configuration.
+            activate: PartialActivationConfig::default(),
             assistant: self.assistant.to_partial(),
             conversation: self.conversation.to_partial(),
             style: self.style.to_partial(),
@@ -346,6 +392,19 @@ impl AppConfig {
         Self::build_schema(SchemaBuilder::default())
     }
 
+    /// Build the [JSON Schema] of configuration files.
+    ///
+    /// See [`crate::json_schema`] for how the schema is rendered.
+    ///
+    /// [JSON Schema]: https://json-schema.org
+    #[must_use]
+    pub fn json_schema() -> Value {
+        let mut schema = json_schema::render(&Self::schema());
+        schema["title"] = "Jean-Pierre configuration".into();
+
+        schema
+    }
+
     /// Return a list of all fields in the configuration.
     ///
     /// The fields are returned in alphabetical order, with nested fields
@@ -358,8 +417,8 @@ impl AppConfig {
     ///     "config_load_paths",
     ///     "extends",
     ///     "inherit",
+    ///     "reset",
     ///     "user.name",
-    ///     "template.values",
     /// ]);
     /// ```
     #[must_use]
@@ -408,11 +467,8 @@ impl AppConfig {
     ///     ),
     ///     ("extends".to_owned(), "JP_CFG_EXTENDS".to_owned()),
     ///     ("inherit".to_owned(), "JP_CFG_INHERIT".to_owned()),
+    ///     ("reset".to_owned(), "JP_CFG_RESET".to_owned()),
     ///     ("user.name".to_owned(), "JP_CFG_USER_NAME".to_owned()),
-    ///     (
-    ///         "template.values".to_owned(),
-    ///         "JP_CFG_TEMPLATE_VALUES".to_owned()
-    ///     ),
     /// ]);
     /// ```
     #[must_use]
@@ -501,6 +557,7 @@ impl Validator for AppConfig {
     fn validate(&self) -> Result<(), ConfigError> {
         self.assistant.validate()?;
         self.conversation.validate()?;
+        self.template.validate()?;
         reject_locked_off_tool_choice(self)
     }
 }
@@ -541,18 +598,18 @@ impl PartialAppConfig {
         <Self as PartialConfig>::empty()
     }
 
-    /// Create a new partial configuration from environment variables.
+    /// Create a new partial configuration from the variables in `env`.
     ///
     /// # Errors
     ///
     /// Returns an error if the value of an environment variable is not a valid
     /// for the given field.
-    pub fn from_envs() -> Result<Self, BoxedError> {
+    pub fn from_envs(env: &env::Env) -> Result<Self, BoxedError> {
         let mut partial = Self::empty();
 
-        let envs = std::env::vars_os()
-            .filter_map(|(k, v)| k.into_string().ok().map(|k| (k, v)))
-            .filter_map(|(k, v)| v.into_string().ok().map(|v| (k, v)))
+        let envs = env
+            .vars()
+            .into_iter()
             .filter_map(|(k, v)| k.strip_prefix(ENV_PREFIX).map(|k| (k.to_owned(), v)))
             .map(|(k, v)| (k.to_ascii_lowercase(), v));
 
@@ -564,6 +621,24 @@ impl PartialAppConfig {
         Ok(partial)
     }
 
+    /// Reset options of this configuration, and record them to be reset in
+    /// any configuration this one is merged into.
+    ///
+    /// See [`reset`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an option can't be reset.
+    pub fn reset(&mut self, resets: IndexMap<String, Reset>) -> Result<(), Error> {
+        if resets.is_empty() {
+            return Ok(());
+        }
+
+        reset::apply(self, &resets)?;
+        self.reset.get_or_insert_default().extend(resets);
+        Ok(())
+    }
+
     /// See [`PartialConfigDelta::delta`].
     #[must_use]
     pub fn delta(&self, next: Self) -> Self {
@@ -14,6 +14,9 @@ use crate::{
 #[config(rename_all = "snake_case")]
 pub struct GoogleConfig {
     /// Environment variable that contains the API key.
+    ///
+    /// Can also be a [secret reference](crate::secret), e.g.
+    /// `secret:cmd:pass show api-key`, to read the key from elsewhere.
     #[setting(default = "GEMINI_API_KEY")]
     pub api_key_env: String,
 
@@ -77,6 +77,9 @@ pub struct StdioConfig {
     pub command: PathBuf,
 
     /// The arguments to pass to the command.
+    ///
+    /// Arguments that are [secret references](crate::secret) are resolved
+    /// when the server is started.
     #[setting(default, merge = schematic::merge::append_vec)]
     pub arguments: Vec<String>,
 
@@ -85,6 +88,11 @@ pub struct StdioConfig {
     /// By default, the command inherits the environment of the parent process.
     /// You can use this to add additional environment variables, or override
     /// existing ones.
+    ///
+    /// A `NAME` entry passes through the variable of the parent process, a
+    /// `NAME=VALUE` entry sets it to `VALUE`.
+    /// The value can be a [secret reference](crate::secret), e.g.
+    /// `GITHUB_TOKEN=secret:keyring:github/jp`.
     #[setting(default, merge = schematic::merge::append_vec)]
     pub variables: Vec<String>,
 
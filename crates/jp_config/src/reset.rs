@@ -0,0 +1,186 @@
+//! Reset markers.
+//!
+//! Any option can be set to a reset marker, to discard the value set by earlier
+//! configuration instead of merging a new value into it:
+//!
+//! - `"!unset"` removes the value, as if it was never set.
+//! - `"!default"` replaces the value with its default.
+//!
+//! Markers are collected into the `reset` option when a configuration file or
+//! `--cfg` assignment is loaded (see [`take_markers`]), and applied to the
+//! previous configuration when the two are merged (see
+//! [`crate::fs::load_partial`]).
+//! The options of the file itself are merged afterwards, so a file can reset an
+//! option and set a new value for it in one go.
+
+use indexmap::IndexMap;
+use schematic::{ConfigEnum, PartialConfig as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{Error, PartialAppConfig};
+
+/// How an option is reset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ConfigEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Reset {
+    /// Remove the value, as if it was never set.
+    Unset,
+
+    /// Replace the value with its default.
+    Default,
+}
+
+impl Reset {
+    /// Parse a reset marker, e.g. `"!unset"`.
+    #[must_use]
+    pub fn from_marker(value: &str) -> Option<Self> {
+        match value {
+            "!unset" => Some(Self::Unset),
+            "!default" => Some(Self::Default),
+            _ => None,
+        }
+    }
+}
+
+/// Remove the reset markers from a configuration object, returning the dotted
+/// paths of the options they reset.
+///
+/// `prefix` is the path of `value` itself.
+/// Markers are only recognized as the value of an option, not as an item of an
+/// array.
+pub fn take_markers(value: &mut Value, prefix: &str) -> IndexMap<String, Reset> {
+    let mut resets = IndexMap::new();
+    if let Value::Object(map) = value {
+        collect(map, prefix, &mut resets);
+    }
+
+    resets
+}
+
+/// See [`take_markers`].
+fn collect(map: &mut Map<String, Value>, prefix: &str, resets: &mut IndexMap<String, Reset>) {
+    map.retain(|key, value| {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+
+        if let Some(reset) = value.as_str().and_then(Reset::from_marker) {
+            resets.insert(path, reset);
+            return false;
+        }
+
+        if let Value::Object(map) = value {
+            collect(map, &path, resets);
+        }
+
+        true
+    });
+}
+
+/// Reset the options in `resets` of `partial`.
+///
+/// # Errors
+///
+/// Returns an error if an option can't be reset, because the result is not a
+/// valid partial configuration.
+pub fn apply(
+    partial: &mut PartialAppConfig,
+    resets: &IndexMap<String, Reset>,
+) -> Result<(), Error> {
+    let mut defaults = None;
+    for (key, reset) in resets {
+        let error = |error: Box<dyn std::error::Error + Send + Sync>| Error::Reset {
+            key: key.clone(),
+            error,
+        };
+
+        let default = match reset {
+            Reset::Unset => None,
+            Reset::Default => {
+                let defaults = match &mut defaults {
+                    Some(defaults) => defaults,
+                    None => {
+                        let values = PartialAppConfig::default_values(&())
+                            .map_err(|err| error(err.into()))?
+                            .unwrap_or_default();
+
+                        defaults
+                            .insert(serde_json::to_value(values).map_err(|err| error(err.into()))?)
+                    }
+                };
+
+                find(defaults, key).cloned()
+            }
+        };
+
+        // Partial configurations are reset through their JSON representation,
+        // the same representation they are stored in conversations with, as
+        // there is no typed access to an option by its path.
+        let mut value = serde_json::to_value(&*partial).map_err(|err| error(err.into()))?;
+        if let Value::Object(map) = &mut value {
+            replace(map, key, default);
+        }
+
+        *partial = serde_json::from_value(value).map_err(|err| error(err.into()))?;
+    }
+
+    Ok(())
+}
+
+/// Find the option at `key` in `value`.
+fn find<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    let map = value.as_object()?;
+    if let Some(value) = map.get(key) {
+        return Some(value);
+    }
+
+    map.iter().find_map(|(name, value)| {
+        let rest = key.strip_prefix(name.as_str())?.strip_prefix('.')?;
+        find(value, rest)
+    })
+}
+
+/// Replace the option at `key` in `map` with `value`, or remove it if `value`
+/// is `None`.
+///
+/// Each segment of `key` is matched against the keys of the object it is
+/// looked up in, so that names containing a dot, such as model aliases, can be
+/// reset as well.
+fn replace(map: &mut Map<String, Value>, key: &str, value: Option<Value>) {
+    if map.contains_key(key) || !key.contains('.') {
+        match value {
+            Some(value) => map.insert(key.to_owned(), value),
+            None => map.remove(key),
+        };
+
+        return;
+    }
+
+    let nested = map.iter().find_map(|(name, value)| {
+        let rest = key.strip_prefix(name.as_str())?.strip_prefix('.')?;
+        value.is_object().then(|| (name.clone(), rest.to_owned()))
+    });
+
+    let (name, rest) = match nested {
+        Some(nested) => nested,
+
+        // Options that aren't set don't need to be unset, but a default value
+        // is set explicitly.
+        None if value.is_some() => match key.split_once('.') {
+            Some((name, rest)) => (name.to_owned(), rest.to_owned()),
+            None => return,
+        },
+        None => return,
+    };
+
+    if let Value::Object(map) = map.entry(name).or_insert_with(|| Value::Object(Map::new())) {
+        replace(map, &rest, value);
+    }
+}
+
+#[cfg(test)]
+#[path = "reset_tests.rs"]
+mod tests;
@@ -0,0 +1,96 @@
+use serde_json::json;
+use test_log::test;
+
+use super::*;
+
+#[test]
+fn test_take_markers() {
+    let mut value = json!({
+        "assistant": {
+            "name": "!unset",
+            "system_prompt": "!default",
+            "tool_choice": "auto",
+        },
+        "providers": { "llm": { "aliases": { "gpt-4.1": "!unset" } } },
+        "conversation": { "attachments": ["!unset"] },
+    });
+
+    let resets = take_markers(&mut value, "");
+    assert_eq!(
+        resets,
+        IndexMap::from([
+            ("assistant.name".to_owned(), Reset::Unset),
+            ("assistant.system_prompt".to_owned(), Reset::Default),
+            ("providers.llm.aliases.gpt-4.1".to_owned(), Reset::Unset),
+        ])
+    );
+
+    assert_eq!(
+        value,
+        json!({
+            "assistant": { "tool_choice": "auto" },
+            "providers": { "llm": { "aliases": {} } },
+            "conversation": { "attachments": ["!unset"] },
+        })
+    );
+}
+
+#[test]
+fn test_take_markers_with_prefix() {
+    let mut value = json!({ "name": "!unset" });
+
+    let resets = take_markers(&mut value, "assistant");
+    assert_eq!(
+        resets,
+        IndexMap::from([("assistant.name".to_owned(), Reset::Unset)])
+    );
+    assert_eq!(value, json!({}));
+}
+
+#[test]
+fn test_apply_unset() {
+    let mut partial: PartialAppConfig = serde_json::from_value(json!({
+        "assistant": { "name": "Jean-Pierre", "system_prompt": "foo" },
+        "providers": { "llm": { "aliases": {
+            "gpt-4.1": "openai/gpt-4.1",
+            "haiku": "anthropic/claude-haiku-4-5",
+        } } },
+    }))
+    .unwrap();
+
+    apply(
+        &mut partial,
+        &IndexMap::from([
+            ("assistant.name".to_owned(), Reset::Unset),
+            ("providers.llm.aliases.gpt-4.1".to_owned(), Reset::Unset),
+            ("style.code.color".to_owned(), Reset::Unset),
+        ]),
+    )
+    .unwrap();
+
+    assert_eq!(partial.assistant.name, None);
+    assert_eq!(partial.assistant.system_prompt.as_deref(), Some("foo"));
+
+    let aliases = partial.providers.llm.aliases.unwrap();
+    assert_eq!(aliases.keys().collect::<Vec<_>>(), ["haiku"]);
+    assert_eq!(partial.style.code.color, None);
+}
+
+#[test]
+fn test_apply_default() {
+    let mut partial = PartialAppConfig::empty();
+    partial.assistant.system_prompt = Some("foo".to_owned());
+
+    apply(
+        &mut partial,
+        &IndexMap::from([("assistant.system_prompt".to_owned(), Reset::Default)]),
+    )
+    .unwrap();
+
+    let defaults = PartialAppConfig::default_values(&()).unwrap().unwrap();
+    assert!(defaults.assistant.system_prompt.is_some());
+    assert_eq!(
+        partial.assistant.system_prompt,
+        defaults.assistant.system_prompt
+    );
+}
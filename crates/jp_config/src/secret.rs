@@ -0,0 +1,328 @@
+//! Secret references in configuration values.
+//!
+//! Instead of the secret itself, the text values of provider configurations
+//! can hold a reference to where the secret is stored:
+//!
+//! ```toml
+//! [providers.llm.anthropic]
+//! api_key_env = "secret:cmd:pass show anthropic"
+//! ```
+//!
+//! The supported stores are:
+//!
+//! - `secret:env:NAME`: the environment variable `NAME`.
+//! - `secret:file:PATH`: the contents of the file at `PATH`, where a leading
+//!   `~` is the home directory.
+//! - `secret:cmd:COMMAND`: the output of `COMMAND`, which is split into
+//!   arguments like a shell would, but not run by a shell.
+//! - `secret:keyring:SERVICE/USER`: the system keyring entry of `USER` in
+//!   `SERVICE`.
+//!
+//! References are kept as-is in the configuration, and are only resolved when
+//! the value is used, e.g. when a provider or MCP server is constructed.
+//! This way, resolved secrets never end up in persisted configuration.
+//!
+//! Secrets are resolved with the [`Env`] of the command that uses them, which
+//! is also the environment secret commands run with.
+
+use std::{borrow::Cow, ffi::OsStr, fmt, fs, io, str::FromStr};
+
+use camino::Utf8PathBuf;
+use serde_json::Value;
+
+use crate::{env::Env, fs::expand_tilde};
+
+/// The prefix of a secret reference.
+pub const PREFIX: &str = "secret:";
+
+/// A reference to a secret stored outside of the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretRef {
+    /// An environment variable.
+    Env(String),
+
+    /// The contents of a file.
+    ///
+    /// A leading `~` is expanded to the home directory.
+    File(Utf8PathBuf),
+
+    /// The standard output of a command.
+    ///
+    /// The command is split into arguments with shell quoting rules, and run
+    /// directly; pipes, redirects and variables need an explicit `sh -c`.
+    Cmd(String),
+
+    /// An entry in the system keyring.
+    Keyring {
+        /// The service the entry belongs to.
+        service: String,
+
+        /// The user the entry belongs to.
+        user: String,
+    },
+}
+
+impl SecretRef {
+    /// Whether `value` is a secret reference.
+    ///
+    /// This does not validate the reference.
+    #[must_use]
+    pub fn is_reference(value: &str) -> bool {
+        value.starts_with(PREFIX)
+    }
+
+    /// The name of the store the secret is kept in, e.g. `cmd`.
+    #[must_use]
+    pub const fn store(&self) -> &'static str {
+        match self {
+            Self::Env(_) => "env",
+            Self::File(_) => "file",
+            Self::Cmd(_) => "cmd",
+            Self::Keyring { .. } => "keyring",
+        }
+    }
+
+    /// Resolve the secret from its store.
+    ///
+    /// A single trailing newline is stripped from file contents and command
+    /// output.
+    pub fn resolve(&self, env: &Env) -> Result<String, SecretError> {
+        match self {
+            Self::Env(name) => env
+                .var(name)
+                .ok_or_else(|| SecretError::MissingEnv(name.clone())),
+            Self::File(path) => {
+                let path = expand_tilde(path, env.var("HOME")).unwrap_or_else(|| path.clone());
+
+                fs::read_to_string(&path)
+                    .map(trim_newline)
+                    .map_err(|error| SecretError::File { path, error })
+            }
+            Self::Cmd(command) => {
+                let args = shlex::split(command).unwrap_or_default();
+                let Some((program, args)) = args.split_first() else {
+                    return Err(SecretError::Invalid(self.to_string()));
+                };
+
+                run(program, args, env).map_err(|error| SecretError::Command {
+                    command: command.clone(),
+                    error,
+                })
+            }
+            Self::Keyring { service, user } => {
+                let (service, user) = (service.as_str(), user.as_str());
+                let (program, args) = if cfg!(target_os = "macos") {
+                    ("security", vec![
+                        "find-generic-password",
+                        "-s",
+                        service,
+                        "-a",
+                        user,
+                        "-w",
+                    ])
+                } else {
+                    ("secret-tool", vec![
+                        "lookup", "service", service, "username", user,
+                    ])
+                };
+
+                run(program, &args, env).map_err(|error| SecretError::Keyring {
+                    service: service.to_owned(),
+                    user: user.to_owned(),
+                    error,
+                })
+            }
+        }
+    }
+}
+
+impl FromStr for SecretRef {
+    type Err = SecretError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SecretError::Invalid(s.to_owned());
+        let (store, value) = s
+            .strip_prefix(PREFIX)
+            .and_then(|s| s.split_once(':'))
+            .filter(|(_, value)| !value.trim().is_empty())
+            .ok_or_else(invalid)?;
+
+        match store {
+            "env" => Ok(Self::Env(value.to_owned())),
+            "file" => Ok(Self::File(value.into())),
+            "cmd" => Ok(Self::Cmd(value.to_owned())),
+            "keyring" => value
+                .split_once('/')
+                .filter(|(service, user)| !service.is_empty() && !user.is_empty())
+                .map(|(service, user)| Self::Keyring {
+                    service: service.to_owned(),
+                    user: user.to_owned(),
+                })
+                .ok_or_else(invalid),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for SecretRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{PREFIX}{}:", self.store())?;
+        match self {
+            Self::Env(name) => write!(f, "{name}"),
+            Self::File(path) => write!(f, "{path}"),
+            Self::Cmd(command) => write!(f, "{command}"),
+            Self::Keyring { service, user } => write!(f, "{service}/{user}"),
+        }
+    }
+}
+
+/// Resolve `value` if it is a secret reference, or return it unchanged.
+pub fn resolve<'a>(value: &'a str, env: &Env) -> Result<Cow<'a, str>, SecretError> {
+    if !SecretRef::is_reference(value) {
+        return Ok(Cow::Borrowed(value));
+    }
+
+    value.parse::<SecretRef>()?.resolve(env).map(Cow::Owned)
+}
+
+/// [`resolve`] every value in `values`.
+pub fn resolve_all(values: &[String], env: &Env) -> Result<Vec<String>, SecretError> {
+    values
+        .iter()
+        .map(|value| resolve(value, env).map(Cow::into_owned))
+        .collect()
+}
+
+/// Resolve a value that names an environment variable, such as
+/// `api_key_env`.
+///
+/// A plain value is read from `env`, a secret reference is resolved from its
+/// store.
+pub fn resolve_env(value: &str, env: &Env) -> Result<String, SecretError> {
+    if SecretRef::is_reference(value) {
+        return value.parse::<SecretRef>()?.resolve(env);
+    }
+
+    SecretRef::Env(value.to_owned()).resolve(env)
+}
+
+/// Hide the location of the secret in a secret reference.
+///
+/// Only the store is kept, e.g. `secret:cmd:***`.
+/// Other values are returned unchanged.
+#[must_use]
+pub fn redact(value: &str) -> Cow<'_, str> {
+    if !SecretRef::is_reference(value) {
+        return Cow::Borrowed(value);
+    }
+
+    match value.parse::<SecretRef>() {
+        Ok(secret) => Cow::Owned(format!("{PREFIX}{}:***", secret.store())),
+        Err(_) => Cow::Owned(format!("{PREFIX}***")),
+    }
+}
+
+/// [`redact`] all strings in a JSON value.
+pub fn redact_json(value: &mut Value) {
+    match value {
+        Value::String(s) => {
+            if let Cow::Owned(redacted) = redact(s) {
+                *s = redacted;
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_json),
+        Value::Object(map) => map.values_mut().for_each(redact_json),
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+/// Run `program` with `env`, and return its standard output.
+fn run<T: AsRef<OsStr>>(program: &str, args: &[T], env: &Env) -> Result<String, String> {
+    let mut cmd = duct::cmd(program, args.iter().map(AsRef::<OsStr>::as_ref));
+    if let Some(vars) = env.overrides() {
+        cmd = cmd.full_env(vars);
+    }
+    if let Ok(cwd) = env.cwd() {
+        cmd = cmd.dir(cwd);
+    }
+
+    let output = cmd
+        .stdout_capture()
+        .stderr_capture()
+        .unchecked()
+        .run()
+        .map_err(|error| error.to_string())?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(match stderr.trim() {
+            "" => output.status.to_string(),
+            stderr => stderr.to_owned(),
+        });
+    }
+
+    String::from_utf8(output.stdout)
+        .map(trim_newline)
+        .map_err(|error| error.to_string())
+}
+
+/// Strip a single trailing newline.
+fn trim_newline(mut value: String) -> String {
+    if value.ends_with('\n') {
+        value.pop();
+        if value.ends_with('\r') {
+            value.pop();
+        }
+    }
+
+    value
+}
+
+/// An error resolving a secret reference.
+#[derive(Debug, thiserror::Error)]
+pub enum SecretError {
+    /// The secret reference could not be parsed.
+    #[error(
+        "invalid secret reference `{0}`, expected e.g. `secret:env:NAME`, `secret:file:PATH`, \
+         `secret:cmd:COMMAND` or `secret:keyring:SERVICE/USER`"
+    )]
+    Invalid(String),
+
+    /// The environment variable is not set.
+    #[error("Missing environment variable: {0}")]
+    MissingEnv(String),
+
+    /// The secret file could not be read.
+    #[error("unable to read secret file {path}")]
+    File {
+        /// The path of the file.
+        path: Utf8PathBuf,
+        /// The underlying error.
+        #[source]
+        error: io::Error,
+    },
+
+    /// The secret command failed.
+    #[error("secret command `{command}` failed: {error}")]
+    Command {
+        /// The command that was run.
+        command: String,
+        /// The error, or the standard error output of the command.
+        error: String,
+    },
+
+    /// The secret could not be read from the system keyring.
+    #[error("unable to read secret for `{user}` in keyring service `{service}`: {error}")]
+    Keyring {
+        /// The keyring service.
+        service: String,
+        /// The keyring user.
+        user: String,
+        /// The error, or the standard error output of the keyring tool.
+        error: String,
+    },
+}
+
+#[cfg(test)]
+#[path = "secret_tests.rs"]
+mod tests;
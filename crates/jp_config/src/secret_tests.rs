@@ -0,0 +1,180 @@
+use std::fs;
+
+use camino_tempfile::tempdir;
+use serde_json::json;
+use test_log::test;
+
+use super::*;
+
+#[test]
+fn test_parse() {
+    let cases = [
+        (
+            "secret:env:API_KEY",
+            Some(SecretRef::Env("API_KEY".to_owned())),
+        ),
+        (
+            "secret:file:~/.keys/openai",
+            Some(SecretRef::File("~/.keys/openai".into())),
+        ),
+        (
+            "secret:cmd:pass show x",
+            Some(SecretRef::Cmd("pass show x".to_owned())),
+        ),
+        (
+            "secret:keyring:jp/anthropic",
+            Some(SecretRef::Keyring {
+                service: "jp".to_owned(),
+                user: "anthropic".to_owned(),
+            }),
+        ),
+        ("secret:env:", None),
+        ("secret:vault:foo", None),
+        ("secret:keyring:jp", None),
+        ("secret:keyring:/user", None),
+        ("API_KEY", None),
+    ];
+
+    for (value, want) in cases {
+        let got = value.parse::<SecretRef>().ok();
+        assert_eq!(got, want, "failed case: {value}");
+
+        if let Some(secret) = got {
+            assert_eq!(secret.to_string(), value);
+        }
+    }
+}
+
+#[test]
+fn test_resolve() {
+    let env = Env::process();
+    let tmp = tempdir().unwrap();
+    let path = tmp.path().join("key");
+    fs::write(&path, "sk-file\n").unwrap();
+
+    assert_eq!(resolve("plain", &env).unwrap(), "plain");
+    assert_eq!(
+        resolve(&format!("secret:file:{path}"), &env).unwrap(),
+        "sk-file"
+    );
+    assert_eq!(resolve("secret:cmd:echo 'sk cmd'", &env).unwrap(), "sk cmd");
+    assert_eq!(
+        resolve("secret:env:PATH", &env).unwrap(),
+        std::env::var("PATH").unwrap()
+    );
+
+    assert!(matches!(
+        resolve("secret:env:JP_SECRET_TESTS_UNSET", &env),
+        Err(SecretError::MissingEnv(name)) if name == "JP_SECRET_TESTS_UNSET"
+    ));
+    assert!(matches!(
+        resolve("secret:cmd:false", &env),
+        Err(SecretError::Command { .. })
+    ));
+    assert!(matches!(
+        resolve(&format!("secret:file:{}", tmp.path().join("missing")), &env),
+        Err(SecretError::File { .. })
+    ));
+    assert!(matches!(
+        resolve("secret:foo", &env),
+        Err(SecretError::Invalid(_))
+    ));
+}
+
+#[test]
+fn test_resolve_file_in_home() {
+    let env = Env::process();
+    let Ok(home) = std::env::var("HOME") else {
+        return;
+    };
+
+    let result = resolve("secret:file:~/jp-secret-tests-missing", &env);
+    assert!(matches!(
+        result,
+        Err(SecretError::File { path, .. }) if path == Utf8PathBuf::from(home).join("jp-secret-tests-missing")
+    ));
+}
+
+#[test]
+fn test_resolve_cmd_without_shell() {
+    let env = Env::process();
+
+    // Arguments are quoted like in a shell, but shell syntax is passed as-is.
+    assert_eq!(resolve("secret:cmd:echo 'a | b'", &env).unwrap(), "a | b");
+    assert_eq!(
+        resolve("secret:cmd:echo a | tr a b", &env).unwrap(),
+        "a | tr a b"
+    );
+    assert_eq!(
+        resolve("secret:cmd:sh -c 'echo a | tr a b'", &env).unwrap(),
+        "b"
+    );
+}
+
+#[test]
+fn test_resolve_env() {
+    let env = Env::process();
+    assert_eq!(
+        resolve_env("PATH", &env).unwrap(),
+        std::env::var("PATH").unwrap()
+    );
+    assert_eq!(resolve_env("secret:cmd:echo sk", &env).unwrap(), "sk");
+    assert!(matches!(
+        resolve_env("JP_SECRET_TESTS_UNSET", &env),
+        Err(SecretError::MissingEnv(_))
+    ));
+}
+
+#[test]
+fn test_resolve_with_captured_env() {
+    let env = Env::captured([
+        ("JP_SECRET".to_owned(), "sk-captured".to_owned()),
+        ("PATH".to_owned(), std::env::var("PATH").unwrap_or_default()),
+    ]);
+
+    assert_eq!(
+        resolve("secret:env:JP_SECRET", &env).unwrap(),
+        "sk-captured"
+    );
+    assert_eq!(resolve_env("JP_SECRET", &env).unwrap(), "sk-captured");
+    assert!(matches!(
+        resolve_env("HOME", &env),
+        Err(SecretError::MissingEnv(_))
+    ));
+
+    // Commands run with the captured environment, not the process one.
+    assert_eq!(
+        resolve("secret:cmd:sh -c 'echo $JP_SECRET'", &env).unwrap(),
+        "sk-captured"
+    );
+}
+
+#[test]
+fn test_redact() {
+    assert_eq!(redact("plain"), "plain");
+    assert_eq!(redact("secret:cmd:echo sk-123"), "secret:cmd:***");
+    assert_eq!(redact("secret:keyring:jp/openai"), "secret:keyring:***");
+    assert_eq!(redact("secret:sk-123"), "secret:***");
+
+    let mut value = json!({
+        "providers": { "llm": { "openai": {
+            "api_key_env": "secret:env:OPENAI_KEY",
+            "base_url": "https://api.openai.com",
+        } } },
+        "args": ["--token", "secret:file:/run/token"],
+        "timeout": 10,
+    });
+
+    redact_json(&mut value);
+    assert_eq!(
+        value,
+        json!({
+            "providers": { "llm": { "openai": {
+                "api_key_env": "secret:env:***",
+                "base_url": "https://api.openai.com",
+            } } },
+            "args": ["--token", "secret:file:***"],
+            "timeout": 10,
+        })
+    );
+}
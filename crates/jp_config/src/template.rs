@@ -1,15 +1,19 @@
 //! Template configuration for Jean-Pierre.
 
+pub mod named;
+
 use indexmap::IndexMap;
-use schematic::Config;
+use schematic::{Config, ConfigError, HandlerError};
 
 use crate::{
     assignment::{AssignKeyValue, KvAssignment, missing_key},
     delta::PartialConfigDelta,
     fill::FillDefaults,
     partial::ToPartial,
+    template::named::NamedTemplateConfig,
     types::json_value::JsonValue,
     util::merge_nested_indexmap,
+    validate::Validator,
 };
 
 /// Template configuration.
@@ -19,6 +23,35 @@ pub struct TemplateConfig {
     /// Template variable values used to render query templates.
     #[setting(nested, merge = merge_nested_indexmap)]
     pub values: IndexMap<String, JsonValue>,
+
+    /// Named query templates, keyed by name (e.g. `review-pr`).
+    ///
+    /// Templates are also loaded from `.jp/templates/<name>.md`.
+    /// See [`NamedTemplateConfig`].
+    #[setting(nested, merge = merge_nested_indexmap)]
+    pub named: IndexMap<String, NamedTemplateConfig>,
+}
+
+impl Validator for TemplateConfig {
+    /// Rejects a named template parameter whose default is not of the
+    /// parameter's type.
+    fn validate(&self) -> Result<(), ConfigError> {
+        for (name, template) in &self.named {
+            for (param, config) in &template.params {
+                let Some(default) = &config.default else {
+                    continue;
+                };
+
+                config.kind.check(default).map_err(|error| {
+                    HandlerError::new(format!(
+                        "template.named.{name}.params.{param}.default: {error}"
+                    ))
+                })?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl AssignKeyValue for PartialTemplateConfig {
@@ -26,6 +59,10 @@ impl AssignKeyValue for PartialTemplateConfig {
         match kv.key_string().as_str() {
             "" => kv.try_merge_object(self)?,
             _ if kv.p("values") => kv.assign_to_entry(&mut self.values)?,
+            _ if kv.p("named") => match kv.trim_prefix_any() {
+                Some(name) => self.named.entry(name).or_default().assign(kv)?,
+                None => return missing_key(&kv),
+            },
             _ => return missing_key(&kv),
         }
 
@@ -46,6 +83,18 @@ impl PartialConfigDelta for PartialTemplateConfig {
                     Some((name, next))
                 })
                 .collect(),
+            named: next
+                .named
+                .into_iter()
+                .filter_map(|(name, next)| {
+                    let next = match self.named.get(&name) {
+                        Some(prev) if prev == &next => return None,
+                        Some(prev) => prev.delta(next),
+                        None => next,
+                    };
+                    Some((name, next))
+                })
+                .collect(),
         }
     }
 }
@@ -64,6 +113,11 @@ impl ToPartial for TemplateConfig {
                 .iter()
                 .map(|(k, v)| (k.clone(), v.clone()))
                 .collect(),
+            named: self
+                .named
+                .iter()
+                .map(|(k, v)| (k.clone(), v.to_partial()))
+                .collect(),
         }
     }
 }
@@ -0,0 +1,374 @@
+//! Named query template configuration.
+//!
+//! A named template is a reusable query: a Jinja2 template, the parameters it
+//! takes, and a configuration overlay (model, tools, attachments and schema)
+//! applied whenever the template is used.
+//!
+//! Templates are defined in configuration under `template.named.<name>`, or as
+//! markdown files in the workspace's `.jp/templates` directory.
+
+use std::{fs, path::PathBuf};
+
+use camino::Utf8Path;
+use indexmap::IndexMap;
+use schematic::Config;
+use serde_json::Value;
+
+use crate::{
+    assignment::{AssignKeyValue, AssignResult, KvAssignment, missing_key},
+    delta::{PartialConfigDelta, delta_opt, delta_opt_vec},
+    error::Error,
+    partial::{ToPartial, partial_opt, partial_opts},
+    util::merge_nested_indexmap,
+};
+
+/// The delimiter of the TOML front matter in a template file.
+const FRONT_MATTER_DELIMITER: &str = "+++";
+
+/// The type of a template parameter.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    serde::Serialize,
+    serde::Deserialize,
+    schematic::ConfigEnum,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateParamType {
+    /// Any text.
+    #[default]
+    String,
+
+    /// A whole number.
+    Integer,
+
+    /// Any number.
+    Number,
+
+    /// `true` or `false`.
+    Boolean,
+}
+
+impl TemplateParamType {
+    /// Parse a raw command-line or prompt value as this type.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message describing the expected type if the value does not
+    /// parse.
+    pub fn parse(self, raw: &str) -> Result<Value, String> {
+        let raw = raw.trim();
+        let value = match self {
+            Self::String => Some(Value::String(raw.to_owned())),
+            Self::Integer => raw.parse::<i64>().ok().map(Value::from),
+            Self::Number => raw
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number),
+            Self::Boolean => raw.parse::<bool>().ok().map(Value::Bool),
+        };
+
+        value.ok_or_else(|| format!("expected {}, got `{raw}`", self.describe()))
+    }
+
+    /// Check that a configured value, such as a parameter's default, is of this
+    /// type.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message describing the expected type if the value is of a
+    /// different type.
+    pub fn check(self, value: &Value) -> Result<(), String> {
+        let valid = match self {
+            Self::String => value.is_string(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Number => value.is_number(),
+            Self::Boolean => value.is_boolean(),
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("expected {}, got `{value}`", self.describe()))
+        }
+    }
+
+    /// A short description of the type, for error messages.
+    #[must_use]
+    pub const fn describe(self) -> &'static str {
+        match self {
+            Self::String => "a string",
+            Self::Integer => "an integer",
+            Self::Number => "a number",
+            Self::Boolean => "`true` or `false`",
+        }
+    }
+}
+
+/// A parameter of a named template.
+#[derive(Debug, Clone, PartialEq, Config)]
+#[config(rename_all = "snake_case")]
+pub struct TemplateParamConfig {
+    /// The type of the parameter.
+    ///
+    /// Values given on the command line are parsed as this type before the
+    /// template is rendered.
+    #[setting(default, rename = "type")]
+    pub kind: TemplateParamType,
+
+    /// The value used when the parameter is not given.
+    ///
+    /// Parameters without a default are required, and are prompted for when
+    /// running interactively.
+    /// The default must be of the parameter's type.
+    pub default: Option<Value>,
+
+    /// A short description of the parameter.
+    pub description: Option<String>,
+}
+
+impl AssignKeyValue for PartialTemplateParamConfig {
+    fn assign(&mut self, mut kv: KvAssignment) -> AssignResult {
+        match kv.key_string().as_str() {
+            "" => kv.try_merge_object(self)?,
+            "type" => self.kind = kv.try_some_from_str()?,
+            "default" => self.default = Some(kv.value.into_value()),
+            "description" => self.description = kv.try_some_string()?,
+            _ => return missing_key(&kv),
+        }
+
+        Ok(())
+    }
+}
+
+impl PartialConfigDelta for PartialTemplateParamConfig {
+    fn delta(&self, next: Self) -> Self {
+        Self {
+            kind: delta_opt(self.kind.as_ref(), next.kind),
+            default: delta_opt(self.default.as_ref(), next.default),
+            description: delta_opt(self.description.as_ref(), next.description),
+        }
+    }
+}
+
+impl ToPartial for TemplateParamConfig {
+    fn to_partial(&self) -> Self::Partial {
+        let defaults = Self::Partial::default();
+
+        Self::Partial {
+            kind: partial_opt(&self.kind, defaults.kind),
+            default: partial_opts(self.default.as_ref(), defaults.default),
+            description: partial_opts(self.description.as_ref(), defaults.description),
+        }
+    }
+}
+
+/// A named, reusable query template.
+///
+/// Example:
+///
+/// ```toml
+/// [template.named.review-pr]
+/// description = "Review a pull request."
+/// content = "Review pull request #{{ pr }}, focusing on {{ focus }}."
+/// model = "anthropic/opus"
+/// tools = ["fs_read_file", "github_pulls"]
+///
+/// [template.named.review-pr.params.pr]
+/// type = "integer"
+/// description = "The pull request number."
+///
+/// [template.named.review-pr.params.focus]
+/// default = "correctness"
+/// ```
+#[derive(Debug, Clone, PartialEq, Config)]
+#[config(rename_all = "snake_case")]
+pub struct NamedTemplateConfig {
+    /// A short description of what the template is for.
+    pub description: Option<String>,
+
+    /// The Jinja2 template rendered into the query.
+    ///
+    /// Parameters, and the values in `template.values`, are available as
+    /// template variables.
+    pub content: Option<String>,
+
+    /// The parameters of the template, keyed by name.
+    #[setting(nested, merge = merge_nested_indexmap)]
+    pub params: IndexMap<String, TemplateParamConfig>,
+
+    /// The model (or model alias) to use for the query.
+    pub model: Option<String>,
+
+    /// The tools to enable for the query.
+    pub tools: Option<Vec<String>>,
+
+    /// The attachments to add to the query.
+    pub attachments: Option<Vec<String>>,
+
+    /// The JSON schema (or schema DSL string) the response must match.
+    pub schema: Option<Value>,
+}
+
+impl AssignKeyValue for PartialNamedTemplateConfig {
+    fn assign(&mut self, mut kv: KvAssignment) -> AssignResult {
+        match kv.key_string().as_str() {
+            "" => kv.try_merge_object(self)?,
+            "description" => self.description = kv.try_some_string()?,
+            "content" => self.content = kv.try_some_string()?,
+            _ if kv.p("params") => match kv.trim_prefix_any() {
+                Some(name) => self.params.entry(name).or_default().assign(kv)?,
+                None => return missing_key(&kv),
+            },
+            "model" => self.model = kv.try_some_string()?,
+            _ if kv.p("tools") => kv.try_some_vec_of_strings(&mut self.tools)?,
+            _ if kv.p("attachments") => kv.try_some_vec_of_strings(&mut self.attachments)?,
+            "schema" => self.schema = Some(kv.value.into_value()),
+            _ => return missing_key(&kv),
+        }
+
+        Ok(())
+    }
+}
+
+impl PartialConfigDelta for PartialNamedTemplateConfig {
+    fn delta(&self, next: Self) -> Self {
+        Self {
+            description: delta_opt(self.description.as_ref(), next.description),
+            content: delta_opt(self.content.as_ref(), next.content),
+            params: next
+                .params
+                .into_iter()
+                .filter_map(|(name, next)| {
+                    let next = match self.params.get(&name) {
+                        Some(prev) if prev == &next => return None,
+                        Some(prev) => prev.delta(next),
+                        None => next,
+                    };
+                    Some((name, next))
+                })
+                .collect(),
+            model: delta_opt(self.model.as_ref(), next.model),
+            tools: delta_opt_vec(self.tools.as_ref(), next.tools),
+            attachments: delta_opt_vec(self.attachments.as_ref(), next.attachments),
+            schema: delta_opt(self.schema.as_ref(), next.schema),
+        }
+    }
+}
+
+impl ToPartial for NamedTemplateConfig {
+    fn to_partial(&self) -> Self::Partial {
+        let defaults = Self::Partial::default();
+
+        Self::Partial {
+            description: partial_opts(self.description.as_ref(), defaults.description),
+            content: partial_opts(self.content.as_ref(), defaults.content),
+            params: self
+                .params
+                .iter()
+                .map(|(k, v)| (k.clone(), v.to_partial()))
+                .collect(),
+            model: partial_opts(self.model.as_ref(), defaults.model),
+            tools: partial_opts(self.tools.as_ref(), defaults.tools),
+            attachments: partial_opts(self.attachments.as_ref(), defaults.attachments),
+            schema: partial_opts(self.schema.as_ref(), defaults.schema),
+        }
+    }
+}
+
+/// Parse a template file.
+///
+/// The file is markdown, with optional TOML front matter between `+++` lines
+/// holding the template's settings.
+/// The markdown body becomes the template's `content`.
+///
+/// # Errors
+///
+/// Returns an error if the front matter is not terminated, or is not a valid
+/// template configuration.
+pub fn parse_template_file(
+    content: &str,
+) -> Result<PartialNamedTemplateConfig, Box<dyn std::error::Error + Send + Sync>> {
+    let Some(rest) = content
+        .strip_prefix(FRONT_MATTER_DELIMITER)
+        .and_then(|rest| {
+            rest.strip_prefix('\n')
+                .or_else(|| rest.strip_prefix("\r\n"))
+        })
+    else {
+        return Ok(PartialNamedTemplateConfig {
+            content: Some(content.trim().to_owned()),
+            ..Default::default()
+        });
+    };
+
+    // The front matter ends at the first line that is exactly `+++`.
+    let (front_matter, body) = rest
+        .split_inclusive('\n')
+        .scan(0, |start, line| {
+            let line_start = *start;
+            *start += line.len();
+            Some((line_start, line))
+        })
+        .find(|(_, line)| line.trim_end_matches(['\r', '\n']) == FRONT_MATTER_DELIMITER)
+        .map(|(start, line)| (&rest[..start], &rest[start + line.len()..]))
+        .ok_or("front matter is missing its closing `+++` line")?;
+
+    let mut template: PartialNamedTemplateConfig = toml::from_str(front_matter)?;
+    template.content = Some(body.trim().to_owned());
+
+    Ok(template)
+}
+
+/// Load the template files (`*.md`) in `dir`, keyed by file stem.
+///
+/// A missing directory has no templates.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be read, or a template file fails
+/// to parse.
+pub fn load_template_dir(
+    dir: &Utf8Path,
+) -> Result<IndexMap<String, PartialNamedTemplateConfig>, Error> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(IndexMap::new()),
+        Err(error) => return Err(Error::Custom(Box::new(error))),
+    };
+
+    let mut paths = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "md"))
+        .collect::<Vec<PathBuf>>();
+    paths.sort();
+
+    let mut templates = IndexMap::new();
+    for path in paths {
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+
+        let template = fs::read_to_string(&path)
+            .map_err(Into::into)
+            .and_then(|content| parse_template_file(&content))
+            .map_err(|error| Error::TemplateFile {
+                path: path.clone(),
+                error,
+            })?;
+
+        templates.insert(name.to_owned(), template);
+    }
+
+    Ok(templates)
+}
+
+#[cfg(test)]
+#[path = "named_tests.rs"]
+mod tests;
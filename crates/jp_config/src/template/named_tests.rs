@@ -0,0 +1,217 @@
+use camino_tempfile::tempdir;
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+use super::*;
+
+#[test]
+fn param_type_parse() {
+    assert_eq!(TemplateParamType::String.parse(" 42 "), Ok(json!("42")));
+    assert_eq!(TemplateParamType::Integer.parse("42"), Ok(json!(42)));
+    assert_eq!(TemplateParamType::Number.parse("4.2"), Ok(json!(4.2)));
+    assert_eq!(TemplateParamType::Boolean.parse("true"), Ok(json!(true)));
+
+    assert_eq!(
+        TemplateParamType::Integer.parse("4.2"),
+        Err("expected an integer, got `4.2`".to_owned())
+    );
+    assert_eq!(
+        TemplateParamType::Boolean.parse("yes"),
+        Err("expected `true` or `false`, got `yes`".to_owned())
+    );
+}
+
+#[test]
+fn param_type_check() {
+    assert_eq!(TemplateParamType::String.check(&json!("42")), Ok(()));
+    assert_eq!(TemplateParamType::Integer.check(&json!(42)), Ok(()));
+    assert_eq!(TemplateParamType::Number.check(&json!(42)), Ok(()));
+    assert_eq!(TemplateParamType::Number.check(&json!(4.2)), Ok(()));
+    assert_eq!(TemplateParamType::Boolean.check(&json!(false)), Ok(()));
+
+    assert_eq!(
+        TemplateParamType::Integer.check(&json!(4.2)),
+        Err("expected an integer, got `4.2`".to_owned())
+    );
+    assert_eq!(
+        TemplateParamType::String.check(&json!(42)),
+        Err("expected a string, got `42`".to_owned())
+    );
+}
+
+#[test]
+fn named_template_from_toml() {
+    let toml = indoc! {r#"
+        description = "Review a pull request."
+        content = "Review #{{ pr }}."
+        model = "anthropic/opus"
+        tools = ["fs_read_file"]
+        schema = "summary, approved bool"
+
+        [params.pr]
+        type = "integer"
+        description = "The pull request number."
+
+        [params.focus]
+        default = "correctness"
+    "#};
+
+    let partial: PartialNamedTemplateConfig = toml::from_str(toml).unwrap();
+    assert_eq!(
+        partial.description.as_deref(),
+        Some("Review a pull request.")
+    );
+    assert_eq!(partial.model.as_deref(), Some("anthropic/opus"));
+    assert_eq!(partial.tools, Some(vec!["fs_read_file".to_owned()]));
+    assert_eq!(partial.schema, Some(json!("summary, approved bool")));
+    assert_eq!(partial.params["pr"].kind, Some(TemplateParamType::Integer));
+    assert_eq!(partial.params["focus"].kind, None);
+    assert_eq!(partial.params["focus"].default, Some(json!("correctness")));
+}
+
+#[test]
+fn assign_params_and_overlay() {
+    let mut p = PartialNamedTemplateConfig::default();
+
+    let kv = KvAssignment::try_from_cli("params.pr.type", "integer").unwrap();
+    p.assign(kv).unwrap();
+    let kv = KvAssignment::try_from_cli("tools", "fs_read_file,fs_grep_files").unwrap();
+    p.assign(kv).unwrap();
+    let kv = KvAssignment::try_from_cli("model", "haiku").unwrap();
+    p.assign(kv).unwrap();
+
+    assert_eq!(p.params["pr"].kind, Some(TemplateParamType::Integer));
+    assert_eq!(
+        p.tools,
+        Some(vec!["fs_read_file".to_owned(), "fs_grep_files".to_owned()])
+    );
+    assert_eq!(p.model.as_deref(), Some("haiku"));
+}
+
+#[test]
+fn delta_keeps_changed_params_only() {
+    let prev: PartialNamedTemplateConfig = toml::from_str(indoc! {r#"
+        content = "Review #{{ pr }}."
+        [params.pr]
+        type = "integer"
+        [params.focus]
+        default = "correctness"
+    "#})
+    .unwrap();
+
+    let mut next = prev.clone();
+    next.params["focus"].default = Some(json!("performance"));
+
+    let delta = prev.delta(next);
+    assert_eq!(delta.content, None);
+    assert_eq!(delta.params.keys().collect::<Vec<_>>(), vec!["focus"]);
+    assert_eq!(delta.params["focus"].default, Some(json!("performance")));
+}
+
+#[test]
+fn parse_template_file_with_front_matter() {
+    let content = indoc! {r#"
+        +++
+        description = "Review a pull request."
+
+        [params.pr]
+        type = "integer"
+        +++
+
+        Review pull request #{{ pr }}.
+    "#};
+
+    let template = parse_template_file(content).unwrap();
+    assert_eq!(
+        template.description.as_deref(),
+        Some("Review a pull request.")
+    );
+    assert_eq!(template.params["pr"].kind, Some(TemplateParamType::Integer));
+    assert_eq!(
+        template.content.as_deref(),
+        Some("Review pull request #{{ pr }}.")
+    );
+}
+
+#[test]
+fn parse_template_file_without_front_matter() {
+    let template = parse_template_file("Summarize {{ topic }}.\n").unwrap();
+
+    assert_eq!(template.content.as_deref(), Some("Summarize {{ topic }}."));
+    assert!(template.params.is_empty());
+}
+
+#[test]
+fn parse_template_file_unterminated_front_matter() {
+    let error = parse_template_file("+++\nmodel = \"haiku\"\n\nHello.").unwrap_err();
+
+    assert!(error.to_string().contains("closing `+++`"));
+}
+
+#[test]
+fn parse_template_file_with_empty_front_matter() {
+    let template = parse_template_file("+++\n+++\nHello.\n").unwrap();
+
+    assert_eq!(template.content.as_deref(), Some("Hello."));
+    assert!(template.params.is_empty());
+}
+
+#[test]
+fn parse_template_file_only_closes_on_an_exact_delimiter_line() {
+    let content = indoc! {r#"
+        +++
+        description = """
+        +++ not the end
+        ++++
+        """
+        +++
+        Hello.
+    "#};
+
+    let template = parse_template_file(content).unwrap();
+    assert_eq!(
+        template.description.as_deref(),
+        Some("+++ not the end\n++++\n")
+    );
+    assert_eq!(template.content.as_deref(), Some("Hello."));
+
+    let error = parse_template_file("+++\nmodel = \"haiku\"\n++++\nHello.").unwrap_err();
+    assert!(error.to_string().contains("closing `+++`"));
+}
+
+#[test]
+fn load_template_dir_reads_markdown_files() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("review-pr.md"), "Review #{{ pr }}.").unwrap();
+    fs::write(dir.path().join("explain.md"), "Explain {{ path }}.").unwrap();
+    fs::write(dir.path().join("notes.txt"), "Not a template.").unwrap();
+
+    let templates = load_template_dir(dir.path()).unwrap();
+    assert_eq!(templates.keys().collect::<Vec<_>>(), vec![
+        "explain",
+        "review-pr"
+    ]);
+    assert_eq!(
+        templates["review-pr"].content.as_deref(),
+        Some("Review #{{ pr }}.")
+    );
+}
+
+#[test]
+fn load_template_dir_missing_directory() {
+    let dir = tempdir().unwrap();
+
+    let templates = load_template_dir(&dir.path().join("templates")).unwrap();
+    assert!(templates.is_empty());
+}
+
+#[test]
+fn load_template_dir_reports_invalid_file() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("broken.md"), "+++\nparams = 1\n+++\nHi.").unwrap();
+
+    let error = load_template_dir(dir.path()).unwrap_err();
+    assert!(matches!(error, Error::TemplateFile { .. }));
+    assert!(error.to_string().contains("broken.md"));
+}
@@ -1,7 +1,10 @@
 use serde_json::json;
 
 use super::*;
-use crate::assignment::KvAssignment;
+use crate::{
+    assignment::KvAssignment,
+    template::named::{TemplateParamConfig, TemplateParamType},
+};
 
 #[test]
 fn assign_flat_value() {
@@ -35,3 +38,64 @@ fn assign_preserves_existing() {
     assert_eq!(p.values["a"], JsonValue(json!("1")));
     assert_eq!(p.values["b"], JsonValue(json!("2")));
 }
+
+#[test]
+fn assign_named_template() {
+    let mut p = PartialTemplateConfig::default();
+
+    let kv = KvAssignment::try_from_cli("named.review-pr.content", "Review #{{ pr }}.").unwrap();
+    p.assign(kv).unwrap();
+    let kv = KvAssignment::try_from_cli("named.review-pr.params.pr.type", "integer").unwrap();
+    p.assign(kv).unwrap();
+
+    let template = &p.named["review-pr"];
+    assert_eq!(template.content.as_deref(), Some("Review #{{ pr }}."));
+    assert_eq!(
+        template.params["pr"].kind,
+        Some(named::TemplateParamType::Integer)
+    );
+}
+
+fn template_config(kind: TemplateParamType, default: serde_json::Value) -> TemplateConfig {
+    let param = TemplateParamConfig {
+        kind,
+        default: Some(default),
+        description: None,
+    };
+
+    TemplateConfig {
+        values: IndexMap::new(),
+        named: IndexMap::from([("review-pr".to_owned(), NamedTemplateConfig {
+            description: None,
+            content: None,
+            params: IndexMap::from([("pr".to_owned(), param)]),
+            model: None,
+            tools: None,
+            attachments: None,
+            schema: None,
+        })]),
+    }
+}
+
+#[test]
+fn validate_rejects_default_of_the_wrong_type() {
+    let error = template_config(TemplateParamType::Integer, json!("123"))
+        .validate()
+        .unwrap_err();
+
+    assert!(
+        error.to_string().contains(
+            r#"template.named.review-pr.params.pr.default: expected an integer, got `"123"`"#
+        ),
+        "got: {error}"
+    );
+}
+
+#[test]
+fn validate_allows_default_of_the_declared_type() {
+    assert!(
+        template_config(TemplateParamType::Integer, json!(123))
+            .validate()
+            .is_ok()
+    );
+}
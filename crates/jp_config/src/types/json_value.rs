@@ -177,6 +177,7 @@ fn merge_as_vec(base: &mut Value, next: Value) {
             value: from_value(next.clone()).unwrap_or_default(),
             strategy: Some(MergedVecStrategy::Replace),
             dedup: None,
+            remove: vec![],
             discard_when_merged: false,
         })
     } else {
@@ -105,6 +105,7 @@ impl<T> MergeableVec<T> {
                 v.value.is_empty()
                     && v.strategy.is_none()
                     && v.dedup.is_none()
+                    && v.remove.is_empty()
                     && !v.discard_when_merged
             }
         }
@@ -155,6 +156,7 @@ impl<T: Config + Clone + PartialEq + Serialize + DeserializeOwned + ToPartial>
             value,
             strategy: Some(MergedVecStrategy::Replace),
             dedup: None,
+            remove: vec![],
             discard_when_merged: false,
         })
     }
@@ -170,6 +172,7 @@ pub fn vec_to_mergeable_partial<T: ToPartial>(items: &[T]) -> MergeableVec<T::Pa
         value: items.iter().map(ToPartial::to_partial).collect(),
         strategy: Some(MergedVecStrategy::Replace),
         dedup: None,
+        remove: vec![],
         discard_when_merged: false,
     })
 }
@@ -221,6 +224,7 @@ impl<T> FillDefaults for MergeableVec<T> {
                 value: items,
                 strategy: default_merged.strategy,
                 dedup: default_merged.dedup,
+                remove: vec![],
                 discard_when_merged: false,
             });
         }
@@ -320,6 +324,19 @@ pub struct MergedVec<T> {
     )]
     pub dedup: Option<bool>,
 
+    /// Items to remove from the previous value, before merging.
+    ///
+    /// An item is removed if it has every value that one of these entries
+    /// sets, so `{ title = "Style" }` removes any instruction titled "Style",
+    /// regardless of its other fields.
+    ///
+    /// Removals carry over when the merged value is merged into another value,
+    /// so that a configuration file removes an item from all configurations it
+    /// is merged into, not just the one it extends.
+    #[setting(default = vec![], skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remove: Vec<T>,
+
     /// Whether the value is discarded when another value is merged in,
     /// regardless of the merge strategy of the other value.
     ///
@@ -345,6 +362,7 @@ where
             value: Some(self.value.clone()),
             strategy: self.strategy,
             dedup: self.dedup,
+            remove: (!self.remove.is_empty()).then(|| self.remove.clone()),
             discard_when_merged: Some(self.discard_when_merged),
         }
     }
@@ -6,7 +6,7 @@ use crate::{
     assignment::{AssignKeyValue, AssignResult, KvAssignment, missing_key},
     delta::{PartialConfigDelta, delta_opt},
     fill::FillDefaults,
-    partial::{ToPartial, partial_opts},
+    partial::{ToPartial, partial_opt, partial_opts},
 };
 
 /// User-specific configuration for conversations.
@@ -26,6 +26,17 @@ pub struct UserConfig {
     /// setup).
     /// When unset, transcripts fall back to a generic `"user"` label.
     pub name: Option<String>,
+
+    /// Whether to keep a local log of how `jp` is used.
+    ///
+    /// When enabled, every command appends a record of the command and the
+    /// names of its flags, and for queries the models, tools and tool
+    /// approvals involved, to `usage.jsonl` in the user data directory.
+    /// The log never leaves your machine, and contains no conversation
+    /// content or flag values.
+    /// Run `jp stats` to summarize it.
+    #[setting(default)]
+    pub usage_log: bool,
 }
 
 impl AssignKeyValue for PartialUserConfig {
@@ -33,6 +44,7 @@ impl AssignKeyValue for PartialUserConfig {
         match kv.key_string().as_str() {
             "" => kv.try_merge_object(self)?,
             "name" => self.name = kv.try_some_string()?,
+            "usage_log" => self.usage_log = kv.try_some_bool()?,
             _ => return missing_key(&kv),
         }
 
@@ -44,6 +56,7 @@ impl PartialConfigDelta for PartialUserConfig {
     fn delta(&self, next: Self) -> Self {
         Self {
             name: delta_opt(self.name.as_ref(), next.name),
+            usage_log: delta_opt(self.usage_log.as_ref(), next.usage_log),
         }
     }
 }
@@ -52,14 +65,18 @@ impl FillDefaults for PartialUserConfig {
     fn fill_from(self, defaults: Self) -> Self {
         Self {
             name: self.name.or(defaults.name),
+            usage_log: self.usage_log.or(defaults.usage_log),
         }
     }
 }
 
 impl ToPartial for UserConfig {
     fn to_partial(&self) -> Self::Partial {
+        let defaults = Self::Partial::default();
+
         Self::Partial {
             name: partial_opts(self.name.as_ref(), None),
+            usage_log: partial_opt(&self.usage_log, defaults.usage_log),
         }
     }
 }
@@ -13,12 +13,16 @@ use schematic::{ConfigLoader, MergeError, MergeResult, PartialConfig, TransformR
 use tracing::{debug, error, info, trace, warn};
 
 use crate::{
-    AppConfig, BoxedError, PartialAppConfig, error::Error,
+    AppConfig, BoxedError, PartialAppConfig,
+    env::Env,
+    error::Error,
+    fs::{ConfigFile, Format, load_partial},
+    reset,
     types::extending_path::ExtendingRelativePath,
 };
 
 /// Valid file extensions for configuration files.
-const VALID_CONFIG_FILE_EXTS: &[&str] = &["toml", "json", "json5", "yaml", "yml"];
+pub const VALID_CONFIG_FILE_EXTS: &[&str] = &["toml", "json", "json5", "yaml", "yml"];
 
 /// Maximum `extends` recursion depth.
 ///
@@ -39,6 +43,8 @@ struct ExtendsStack {
     ancestors: Vec<PathBuf>,
     /// Hard cap on nesting depth.
     max_depth: u8,
+    /// Files loaded so far, with their partial configuration, in merge order.
+    loaded: Vec<(PathBuf, PartialAppConfig)>,
 }
 
 impl ExtendsStack {
@@ -47,6 +53,7 @@ impl ExtendsStack {
         Self {
             ancestors: Vec::new(),
             max_depth,
+            loaded: Vec::new(),
         }
     }
 
@@ -101,24 +108,23 @@ pub fn load_partials_with_inheritance(
             break;
         }
 
-        partial.merge(&(), p)?;
+        partial = load_partial(partial, p)?;
     }
 
     Ok(partial)
 }
 
-/// Load environment variables into a partial configuration.
+/// Load the variables in `env` into a partial configuration.
 ///
 /// # Errors
 ///
 /// Returns an error if merging the partials fails, which returns a
 /// [`schematic::MergeError`].
-pub fn load_envs(mut base: PartialAppConfig) -> Result<PartialAppConfig, BoxedError> {
+pub fn load_envs(base: PartialAppConfig, env: &Env) -> Result<PartialAppConfig, BoxedError> {
     trace!("Loading environment variable configuration.");
-    let envs = PartialAppConfig::from_envs()?;
-    base.merge(&(), envs)?;
+    let envs = PartialAppConfig::from_envs(env)?;
 
-    Ok(base)
+    load_partial(base, envs).map_err(Into::into)
 }
 
 /// Tries to find a configuration file in a load path.
@@ -191,15 +197,43 @@ fn load_partial_at_path_with_max_depth<P: Into<PathBuf>>(
     path: P,
     max_depth: u8,
 ) -> Result<Option<PartialAppConfig>, Error> {
-    let mut loader = ConfigLoader::<AppConfig>::new();
     let mut stack = ExtendsStack::new(max_depth);
-    match load_config_file_at_path(path, &mut loader, false, &mut stack) {
+    match load_config_file_at_path(path, &mut stack) {
         Ok(()) => {}
         Err(Error::Schematic(schematic::ConfigError::MissingFile(_))) => return Ok(None),
         Err(error) => return Err(error),
     }
 
-    loader.load_partial(&()).map(Some).map_err(Into::into)
+    stack
+        .loaded
+        .into_iter()
+        .try_fold(PartialAppConfig::empty(), |prev, (_, next)| {
+            load_partial(prev, next)
+        })
+        .map(Some)
+}
+
+/// Load the files that [`load_partial_at_path`] merges for `path`, without
+/// merging them.
+///
+/// Returns the partial configuration of each file, including the files it
+/// `extends`, in merge order.
+/// Returns an empty list if the file does not exist.
+///
+/// # Errors
+///
+/// See [`load_partial_at_path`].
+pub fn load_partials_at_path_by_file<P: Into<PathBuf>>(
+    path: P,
+) -> Result<Vec<(PathBuf, PartialAppConfig)>, Error> {
+    let mut stack = ExtendsStack::new(MAX_EXTENDS_DEPTH);
+    match load_config_file_at_path(path, &mut stack) {
+        Ok(()) => {}
+        Err(Error::Schematic(schematic::ConfigError::MissingFile(_))) => return Ok(vec![]),
+        Err(error) => return Err(error),
+    }
+
+    Ok(stack.loaded)
 }
 
 /// Load a partial configuration from a file at `path`, walking upwards until
@@ -218,11 +252,34 @@ pub fn load_partial_at_path_recursive<P: Into<PathBuf>>(
     root: Option<&Utf8Path>,
 ) -> Result<Option<PartialAppConfig>, Error> {
     let path: PathBuf = path.into();
+    if path.file_name().is_none() {
+        return load_partial_at_path(&path).map(|p| p.filter(|_| path.is_file()));
+    }
+
+    // Load and merge from shallowest to deepest, so that deeper (more specific)
+    // paths take precedence.
+    let mut result: Option<PartialAppConfig> = None;
+
+    for candidate in recursive_candidate_paths(&path, root) {
+        let partial = load_partial_at_path(&candidate)?;
 
+        result = match (result, partial) {
+            (Some(base), Some(specific)) => Some(load_partial(base, specific)?),
+            (base, specific) => base.or(specific),
+        };
+    }
+
+    Ok(result)
+}
+
+/// The paths [`load_partial_at_path_recursive`] tries for `path`, from
+/// shallowest to deepest.
+#[must_use]
+pub fn recursive_candidate_paths(path: &Path, root: Option<&Utf8Path>) -> Vec<PathBuf> {
     // Extract the file name component (e.g. `config.toml`) that we'll look
     // for at every ancestor directory.
     let Some(file_name) = path.file_name().map(OsStr::to_os_string) else {
-        return load_partial_at_path(&path).map(|p| p.filter(|_| path.is_file()));
+        return vec![path.to_path_buf()];
     };
 
     // Collect candidate paths from deepest to shallowest.
@@ -232,7 +289,7 @@ pub fn load_partial_at_path_recursive<P: Into<PathBuf>>(
     // `Prefix("C:")` and `RootDir("\\"`) are separate components in
     // `Path::iter()` — stripping the root dir leaves the prefix, and
     // re-joining with the file name recreates the original absolute path.
-    let mut candidates = vec![path.clone()];
+    let mut candidates = vec![path.to_path_buf()];
     let mut dir = path.parent();
 
     while let Some(current) = dir {
@@ -249,23 +306,8 @@ pub fn load_partial_at_path_recursive<P: Into<PathBuf>>(
         dir = Some(parent);
     }
 
-    // Load and merge from shallowest to deepest, so that deeper (more specific)
-    // paths take precedence.
-    let mut result: Option<PartialAppConfig> = None;
-
-    for candidate in candidates.into_iter().rev() {
-        let partial = load_partial_at_path(&candidate)?;
-
-        result = match (result, partial) {
-            (Some(mut base), Some(specific)) => {
-                base.merge(&(), specific)?;
-                Some(base)
-            }
-            (base, specific) => base.or(specific),
-        };
-    }
-
-    Ok(result)
+    candidates.reverse();
+    candidates
 }
 
 /// Build a final configuration from merged partial configurations.
@@ -317,8 +359,6 @@ pub fn build(partial: PartialAppConfig) -> Result<AppConfig, Error> {
 /// Can error if file parsing fails, or if partial validation fails.
 fn load_config_file_at_path<P: Into<PathBuf>>(
     path: P,
-    loader: &mut ConfigLoader<AppConfig>,
-    optional: bool,
     stack: &mut ExtendsStack,
 ) -> Result<(), Error> {
     let mut path: PathBuf = path.into();
@@ -341,7 +381,7 @@ fn load_config_file_at_path<P: Into<PathBuf>>(
     // `ExtendsStack` protects against cycles slipping through in that case.
     let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
     stack.try_push(canonical)?;
-    let result = load_config_file_with_extends(&path, loader, optional, stack);
+    let result = load_config_file_with_extends(&path, stack);
     stack.pop();
     result
 }
@@ -349,40 +389,62 @@ fn load_config_file_at_path<P: Into<PathBuf>>(
 /// Load a configuration file at `path`, assuming it exists.
 ///
 /// If the file configures `extends`, those will be loaded as well.
-fn load_config_file_with_extends(
-    path: &Path,
-    loader: &mut ConfigLoader<AppConfig>,
-    optional: bool,
-    stack: &mut ExtendsStack,
-) -> Result<(), Error> {
+fn load_config_file_with_extends(path: &Path, stack: &mut ExtendsStack) -> Result<(), Error> {
     let root = path.parent().map(Path::to_path_buf);
+    let partial = load_config_file(path)?;
 
-    let (before, after): (Vec<_>, Vec<_>) = ConfigLoader::<AppConfig>::new()
-        .file(path)?
-        .load_partial(&())?
+    let (before, after): (Vec<_>, Vec<_>) = partial
         .extends
-        .into_iter()
+        .iter()
         .flatten()
+        .cloned()
         .partition(ExtendingRelativePath::is_before);
 
-    load_optional_paths(before, root.as_deref(), loader, stack)?;
+    load_optional_paths(before, root.as_deref(), stack)?;
+    stack.loaded.push((path.to_path_buf(), partial));
+    load_optional_paths(after, root.as_deref(), stack)?;
 
-    if optional {
-        loader.file_optional(path)?;
-    } else {
-        loader.file(path)?;
-    }
+    Ok(())
+}
 
-    load_optional_paths(after, root.as_deref(), loader, stack)?;
+/// Load the partial configuration of the file at `path`, without the files it
+/// extends.
+///
+/// Options set to a reset marker, such as `"!unset"`, are collected into the
+/// `reset` option, see [`crate::reset`].
+fn load_config_file(path: &Path) -> Result<PartialAppConfig, Error> {
+    let file = Utf8Path::from_path(path).and_then(|utf8_path| {
+        Some(ConfigFile {
+            path: utf8_path.to_owned(),
+            format: utf8_path.extension().and_then(Format::from_extension)?,
+            content: fs::read_to_string(path).ok()?,
+        })
+    });
+
+    // Files without reset markers are left to the loader, which reports the
+    // location of invalid values.
+    if let Some(file) = file
+        && let Ok(mut value) = file.deserialize::<serde_json::Value>()
+        && !reset::take_markers(&mut value, "").is_empty()
+    {
+        return file
+            .deserialize_partial()
+            .map_err(|error| Error::InvalidFile {
+                path: path.to_path_buf(),
+                error,
+            });
+    }
 
-    Ok(())
+    ConfigLoader::<AppConfig>::new()
+        .file(path)?
+        .load_partial(&())
+        .map_err(Into::into)
 }
 
 /// Load the optional paths.
 fn load_optional_paths(
     extends: impl IntoIterator<Item = ExtendingRelativePath>,
     root: Option<&Path>,
-    loader: &mut ConfigLoader<AppConfig>,
     stack: &mut ExtendsStack,
 ) -> Result<(), Error> {
     for path in extends {
@@ -410,7 +472,7 @@ fn load_optional_paths(
                 }
             };
 
-            load_config_file_at_path(&path, loader, true, stack)?;
+            load_config_file_at_path(&path, stack)?;
         }
     }
 
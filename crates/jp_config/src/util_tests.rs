@@ -88,13 +88,27 @@ fn test_load_partials_with_inheritance() {
 fn test_load_envs() {
     let _env = EnvVarGuard::set("JP_CFG_PROVIDERS_LLM_OPENROUTER_API_KEY_ENV", "ENV1");
 
-    let partial = load_envs(PartialAppConfig::empty()).unwrap();
+    let partial = load_envs(PartialAppConfig::empty(), &Env::process()).unwrap();
     assert_eq!(
         partial.providers.llm.openrouter.api_key_env,
         Some("ENV1".to_owned())
     );
 }
 
+#[test]
+fn test_load_envs_from_captured_env() {
+    let env = Env::captured([(
+        "JP_CFG_PROVIDERS_LLM_OPENROUTER_API_KEY_ENV".to_owned(),
+        "CAPTURED".to_owned(),
+    )]);
+
+    let partial = load_envs(PartialAppConfig::empty(), &env).unwrap();
+    assert_eq!(
+        partial.providers.llm.openrouter.api_key_env,
+        Some("CAPTURED".to_owned())
+    );
+}
+
 #[test]
 #[serial(env_vars)]
 fn test_load_envs_overrides_file_config() {
@@ -103,7 +117,7 @@ fn test_load_envs_overrides_file_config() {
     let mut file_config = PartialAppConfig::empty();
     file_config.providers.llm.openrouter.api_key_env = Some("FROM_FILE".to_owned());
 
-    let merged = load_envs(file_config).unwrap();
+    let merged = load_envs(file_config, &Env::process()).unwrap();
     assert_eq!(
         merged.providers.llm.openrouter.api_key_env,
         Some("FROM_ENV".to_owned()),
@@ -191,6 +205,7 @@ fn test_build_sorted_instructions() {
         ],
         strategy: Some(MergedVecStrategy::Replace),
         dedup: None,
+        remove: vec![],
         discard_when_merged: false,
     }
     .into();
@@ -736,6 +751,95 @@ fn test_load_partial_at_path_diamond_is_not_a_cycle() {
     assert!(partial.is_some());
 }
 
+#[test]
+fn test_load_partials_at_path_by_file() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write_config(
+        &root.join("a.toml"),
+        indoc::indoc!(
+            r#"
+                extends = ["b.toml", { path = "c.toml", strategy = "after" }]
+                assistant.system_prompt = "a"
+            "#
+        ),
+    );
+    write_config(&root.join("b.toml"), "assistant.system_prompt = \"b\"");
+    write_config(&root.join("c.toml"), "assistant.system_prompt = \"c\"");
+
+    let files = load_partials_at_path_by_file(root.join("a.toml")).unwrap();
+    let names: Vec<_> = files
+        .iter()
+        .map(|(path, _)| path.file_name().unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(names, ["b.toml", "a.toml", "c.toml"]);
+
+    let prompts: Vec<_> = files
+        .iter()
+        .map(|(_, partial)| partial.assistant.system_prompt.as_deref().unwrap())
+        .collect();
+    assert_eq!(prompts, ["b", "a", "c"]);
+
+    assert!(
+        load_partials_at_path_by_file(root.join("missing.toml"))
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_load_partial_at_path_with_resets() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write_config(
+        &root.join("a.toml"),
+        indoc::indoc!(
+            r#"
+                extends = ["b.toml"]
+                assistant.name = "!unset"
+                assistant.system_prompt = "!default"
+                assistant.instructions = { remove = [{ title = "foo" }] }
+                providers.llm.aliases.fast = "!unset"
+            "#
+        ),
+    );
+    write_config(
+        &root.join("b.toml"),
+        indoc::indoc!(
+            r#"
+                assistant.name = "b"
+                assistant.system_prompt = "b"
+                assistant.instructions = [{ title = "foo" }, { title = "bar" }]
+                providers.llm.aliases = { fast = "openai/gpt-4.1-mini", slow = "openai/o3" }
+            "#
+        ),
+    );
+
+    let partial = load_partial_at_path(root.join("a.toml")).unwrap().unwrap();
+    let defaults = PartialAppConfig::default_values(&()).unwrap().unwrap();
+
+    assert_eq!(partial.assistant.name, None);
+    assert_eq!(
+        partial.assistant.system_prompt,
+        defaults.assistant.system_prompt
+    );
+
+    let titles: Vec<_> = partial
+        .assistant
+        .instructions
+        .iter()
+        .filter_map(|v| v.title.as_deref())
+        .collect();
+    assert_eq!(titles, ["bar"]);
+
+    let aliases = partial.providers.llm.aliases.as_ref().unwrap();
+    assert_eq!(aliases.keys().collect::<Vec<_>>(), ["slow"]);
+
+    // The resets are kept, to reset the same options in configurations loaded
+    // before this file.
+    assert_eq!(partial.reset.unwrap().len(), 3);
+}
+
 #[test]
 fn test_vec_dedup_preserves_order() {
     let result = vec_dedup(vec![3, 1, 2, 1, 3, 4], &()).unwrap();
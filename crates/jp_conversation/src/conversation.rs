@@ -77,6 +77,29 @@ pub struct Conversation {
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub labels: BTreeMap<String, String>,
 
+    /// The conversation this one was created from, if any.
+    ///
+    /// Set on child conversations, such as the one a sub-agent runs its task
+    /// in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<ConversationId>,
+
+    /// The conversation this one was forked from, if any.
+    ///
+    /// Unlike [`Self::parent`], a fork is an independent branch of its source:
+    /// it starts with a copy of (part of) the source's history and continues
+    /// on its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<ForkOrigin>,
+
+    /// Whether the conversation is hidden from listings and pickers.
+    ///
+    /// Set on the scratch conversations that record the LLM requests JP makes
+    /// on its own behalf, such as generating a title or answering a tool's
+    /// question.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+
     /// The time of the last event, or `None` if the conversation is empty.
     #[serde(skip)]
     pub last_event_at: Option<DateTime<Utc>>,
@@ -95,6 +118,9 @@ impl Default for Conversation {
             archived_at: None,
             expires_at: None,
             labels: BTreeMap::new(),
+            parent: None,
+            forked_from: None,
+            hidden: false,
             last_event_at: None,
             events_count: 0,
         }
@@ -132,6 +158,19 @@ impl Conversation {
     }
 }
 
+/// The point in another conversation a fork branched off from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkOrigin {
+    /// The conversation the fork was created from.
+    pub conversation: ConversationId,
+
+    /// The number of the source conversation's turns that precede the branch.
+    ///
+    /// The fork shares the source's history up to this turn, and its own turns
+    /// are alternatives to the source's turns from this one on.
+    pub turn: usize,
+}
+
 /// ID wrapper for Conversation
 #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct ConversationId(#[serde(with = "jp_id::serde")] DateTime<Utc>);
@@ -11,6 +11,9 @@ fn test_conversation_serialization() {
         archived_at: None,
         expires_at: None,
         labels: BTreeMap::new(),
+        parent: None,
+        forked_from: None,
+        hidden: false,
         last_event_at: None,
         events_count: 0,
     };
@@ -40,3 +43,38 @@ fn labels_round_trip_through_metadata() {
     assert_eq!(conv.labels.get("draft").map(String::as_str), Some(""));
     assert_eq!(serde_json::to_string(&conv).unwrap(), json);
 }
+
+#[test]
+fn fork_origin_round_trips_through_metadata() {
+    let source = ConversationId::try_from_deciseconds(17_000_000_000).unwrap();
+    let conv = Conversation {
+        forked_from: Some(ForkOrigin {
+            conversation: source,
+            turn: 3,
+        }),
+        ..Conversation::default()
+    };
+
+    let json = serde_json::to_value(&conv).unwrap();
+    assert_eq!(json["forked_from"]["conversation"], 17_000_000_000_i64);
+    assert_eq!(json["forked_from"]["turn"], 3);
+
+    let back: Conversation = serde_json::from_value(json).unwrap();
+    assert_eq!(back.forked_from, conv.forked_from);
+}
+
+#[test]
+fn hidden_is_omitted_unless_set() {
+    let visible = serde_json::to_value(Conversation::default()).unwrap();
+    assert!(visible.get("hidden").is_none());
+
+    let hidden = Conversation {
+        hidden: true,
+        ..Conversation::default()
+    };
+    let json = serde_json::to_value(&hidden).unwrap();
+    assert_eq!(json["hidden"], true);
+
+    let back: Conversation = serde_json::from_value(json).unwrap();
+    assert!(back.hidden);
+}
@@ -34,6 +34,31 @@ pub const CACHE_BREAKPOINT_KEY: &str = "cache_breakpoint";
 /// reproduce the display without re-running the formatter.
 pub const RENDERED_ARGUMENTS_KEY: &str = "rendered_arguments";
 
+/// Key used in `ConversationEvent::metadata` to store the state of a background
+/// tool call.
+///
+/// When a tool continues in the background, its tool call response is the
+/// handle the assistant waits on.
+/// The response's metadata records which tool runs, what it does, and whether
+/// it is still running, so that later queries know what became of it.
+pub const BACKGROUND_HANDLE_KEY: &str = "background_handle";
+
+/// Key used in `ConversationEvent::metadata` of a `TurnStart` event to store the
+/// ID of the turn's file checkpoint.
+///
+/// Turn numbers change when earlier turns are removed, so checkpoints are
+/// stored by this ID instead.
+pub const TURN_CHECKPOINT_KEY: &str = "checkpoint";
+
+/// Key used in `ConversationEvent::metadata` to mark the last response event
+/// of a finished stream.
+///
+/// The provider signalled the end of its response after this event. The value
+/// is `true` if the response is complete, and `false` if the provider cut it
+/// off, e.g. at `max_tokens`. A response without the marker was cut off while
+/// it was still streaming.
+pub const RESPONSE_FINISHED_KEY: &str = "response_finished";
+
 /// A single event in a conversation.
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ConversationEvent {
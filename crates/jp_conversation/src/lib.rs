@@ -40,7 +40,7 @@ pub use compaction::{
     Compaction, CompactionRange, RangeBound, ReasoningPolicy, SummaryPolicy, ToolCallPolicy,
     resolve_range,
 };
-pub use conversation::{Conversation, ConversationId};
+pub use conversation::{Conversation, ConversationId, ForkOrigin};
 pub use error::Error;
 pub use event::{ConversationEvent, EventKind};
 pub use storage::{decode_event_value, rfc3339};
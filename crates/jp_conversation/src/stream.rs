@@ -165,9 +165,42 @@ pub struct ConfigDelta {
 
     /// The configuration delta.
     pub delta: Box<PartialAppConfig>,
+
+    /// The events the delta applies to.
+    #[serde(default, skip_serializing_if = "DeltaScope::is_conversation")]
+    pub scope: DeltaScope,
+}
+
+/// The events a [`ConfigDelta`] applies to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeltaScope {
+    /// The delta applies to all events that follow it.
+    #[default]
+    Conversation,
+
+    /// The delta applies to the events that follow it in the same turn.
+    ///
+    /// The next [`TurnStart`] reverts the delta.
+    Turn,
+}
+
+impl DeltaScope {
+    /// Returns `true` if the delta applies to the rest of the conversation.
+    #[must_use]
+    pub const fn is_conversation(&self) -> bool {
+        matches!(self, Self::Conversation)
+    }
 }
 
 impl ConfigDelta {
+    /// Limit the delta to the turn it is added to.
+    #[must_use]
+    pub const fn turn_scoped(mut self) -> Self {
+        self.scope = DeltaScope::Turn;
+        self
+    }
+
     /// Get the [`PartialAppConfig`] delta.
     #[must_use]
     pub fn into_inner(self) -> Box<PartialAppConfig> {
@@ -206,6 +239,7 @@ impl From<PartialAppConfig> for ConfigDelta {
         Self {
             timestamp: Utc::now(),
             delta: Box::new(config),
+            scope: DeltaScope::Conversation,
         }
     }
 }
@@ -227,9 +261,65 @@ pub(crate) fn deserialize_config_delta(value: &Value) -> ConfigDelta {
         .and_then(|s| crate::parse_dt(s).ok())
         .unwrap_or_else(Utc::now);
 
+    let scope = value
+        .get("scope")
+        .and_then(|scope| DeltaScope::deserialize(scope).ok())
+        .unwrap_or_default();
+
     ConfigDelta {
         timestamp,
         delta: Box::new(delta),
+        scope,
+    }
+}
+
+/// The configuration state while walking the events of a stream.
+#[derive(Debug, Clone)]
+struct ConfigState {
+    /// The configuration, without turn-scoped deltas.
+    conversation: PartialAppConfig,
+
+    /// The configuration of the current turn, if it has turn-scoped deltas.
+    turn: Option<PartialAppConfig>,
+}
+
+impl ConfigState {
+    fn new(base: PartialAppConfig) -> Self {
+        Self {
+            conversation: base,
+            turn: None,
+        }
+    }
+
+    /// Update the state with the next event in the stream.
+    fn apply(&mut self, event: &InternalEvent) {
+        match event {
+            InternalEvent::ConfigDelta(delta) => {
+                let partial = PartialAppConfig::clone(delta);
+                let result = match delta.scope {
+                    DeltaScope::Conversation => self
+                        .turn
+                        .as_mut()
+                        .map_or(Ok(()), |turn| turn.merge(&(), partial.clone()))
+                        .and_then(|()| self.conversation.merge(&(), partial)),
+                    DeltaScope::Turn => self
+                        .turn
+                        .get_or_insert_with(|| self.conversation.clone())
+                        .merge(&(), partial),
+                };
+
+                if let Err(error) = result {
+                    error!(%error, "Failed to merge config delta.");
+                }
+            }
+            InternalEvent::Event(event) if event.is_turn_start() => self.turn = None,
+            InternalEvent::Event(_) | InternalEvent::Compaction(_) | InternalEvent::Unknown(_) => {}
+        }
+    }
+
+    /// The configuration of the next event.
+    fn current(&self) -> &PartialAppConfig {
+        self.turn.as_ref().unwrap_or(&self.conversation)
     }
 }
 
@@ -318,6 +408,11 @@ impl ConversationStream {
     /// in the stream from first to last, including any delta's that come
     /// *after* the last conversation event.
     ///
+    /// Turn-scoped deltas are left out, as they only apply to the events of
+    /// their turn.
+    /// Use [`ConversationStream::turn_config_delta`] to get those of the last
+    /// turn.
+    ///
     /// If you need the configuration state of the last event in the stream, use
     /// [`ConversationStream::last`], which returns a
     /// [`ConversationEventWithConfig`]. containing the `config` field for that
@@ -329,7 +424,10 @@ impl ConversationStream {
     pub fn config(&self) -> Result<AppConfig, StreamError> {
         let mut partial = self.base_config.to_partial();
         let iter = self.events.iter().filter_map(|event| match event {
-            InternalEvent::ConfigDelta(delta) => Some(delta.clone()),
+            InternalEvent::ConfigDelta(delta) if delta.scope.is_conversation() => {
+                Some(delta.clone())
+            }
+            InternalEvent::ConfigDelta(_) => None,
             InternalEvent::Event(_) | InternalEvent::Compaction(_) | InternalEvent::Unknown(_) => {
                 None
             }
@@ -342,6 +440,38 @@ impl ConversationStream {
         AppConfig::from_partial_with_defaults(partial).map_err(Into::into)
     }
 
+    /// Get the merged turn-scoped deltas of the last turn.
+    ///
+    /// Returns `None` if the last turn has no turn-scoped deltas.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the deltas cannot be merged.
+    pub fn turn_config_delta(&self) -> Result<Option<PartialAppConfig>, StreamError> {
+        let turn_start = self
+            .events
+            .iter()
+            .rposition(|event| {
+                event
+                    .as_event()
+                    .is_some_and(ConversationEvent::is_turn_start)
+            })
+            .unwrap_or(0);
+
+        let mut merged = None::<PartialAppConfig>;
+        for event in &self.events[turn_start..] {
+            if let InternalEvent::ConfigDelta(delta) = event
+                && delta.scope == DeltaScope::Turn
+            {
+                merged
+                    .get_or_insert_with(PartialAppConfig::empty)
+                    .merge(&(), PartialAppConfig::clone(delta))?;
+            }
+        }
+
+        Ok(merged)
+    }
+
     /// Removes all events from the end of the stream, until a [`ChatRequest`]
     /// is found, returning that request.
     ///
@@ -380,11 +510,25 @@ impl ConversationStream {
 
     /// Add a config delta to the stream.
     ///
+    /// Only the values that differ from the current configuration are kept,
+    /// which for a turn-scoped delta includes the turn-scoped deltas already
+    /// added to the last turn.
+    ///
     /// This is a no-op if the delta is empty.
     pub fn add_config_delta(&mut self, delta: impl Into<ConfigDelta>) {
-        let ConfigDelta { delta, timestamp } = delta.into();
-        let delta = match self.config() {
-            Ok(config) => config.to_partial().delta(*delta),
+        let ConfigDelta {
+            delta,
+            timestamp,
+            scope,
+        } = delta.into();
+
+        let current = match scope {
+            DeltaScope::Conversation => self.config().map(|config| config.to_partial()),
+            DeltaScope::Turn => Ok(self.config_state().current().clone()),
+        };
+
+        let delta = match current {
+            Ok(current) => current.delta(*delta),
             Err(error) => {
                 error!(%error, "Unable to get valid config from conversation stream.");
                 return;
@@ -398,9 +542,20 @@ impl ConversationStream {
         self.events.push(InternalEvent::ConfigDelta(ConfigDelta {
             delta: Box::new(delta),
             timestamp,
+            scope,
         }));
     }
 
+    /// The configuration state after the last event in the stream.
+    fn config_state(&self) -> ConfigState {
+        let mut state = ConfigState::new(self.base_config.to_partial());
+        for event in &self.events {
+            state.apply(event);
+        }
+
+        state
+    }
+
     /// Add a config delta to the stream.
     #[must_use]
     pub fn with_config_delta(mut self, delta: impl Into<ConfigDelta>) -> Self {
@@ -1231,7 +1386,7 @@ impl ConversationStream {
     pub fn iter(&self) -> impl DoubleEndedIterator<Item = ConversationEventWithConfigRef<'_>> {
         Iter {
             stream: self,
-            front_config: self.base_config.to_partial(),
+            front_config: ConfigState::new(self.base_config.to_partial()),
             front: 0,
             back: self.events.len(),
         }
@@ -1242,7 +1397,7 @@ impl ConversationStream {
     pub fn iter_mut(&mut self) -> impl Iterator<Item = ConversationEventWithConfigMut<'_>> {
         IterMut {
             iter: self.events.iter_mut(),
-            front_config: self.base_config.to_partial(),
+            front_config: ConfigState::new(self.base_config.to_partial()),
         }
     }
 
@@ -1281,6 +1436,7 @@ impl Extend<ConversationEventWithConfig> for ConversationStream {
                 self.add_config_delta(ConfigDelta {
                     delta: Box::new(config_delta),
                     timestamp: event.timestamp,
+                    scope: DeltaScope::Conversation,
                 });
             }
 
@@ -1305,7 +1461,7 @@ impl IntoIterator for ConversationStream {
 
     fn into_iter(self) -> Self::IntoIter {
         IntoIter {
-            current_config: self.base_config.to_partial(),
+            current_config: ConfigState::new(self.base_config.to_partial()),
             inner_iter: self.events.into_iter(),
         }
     }
@@ -1314,7 +1470,7 @@ impl IntoIterator for ConversationStream {
 /// An owned iterator over the events in a conversation stream.
 pub struct IntoIter {
     /// The configuration state for the next event in the iterator.
-    current_config: PartialAppConfig,
+    current_config: ConfigState,
 
     /// The iterator over the events in the stream.
     inner_iter: std::vec::IntoIter<InternalEvent>,
@@ -1326,20 +1482,13 @@ impl Iterator for IntoIter {
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             let event = self.inner_iter.next()?;
+            self.current_config.apply(&event);
 
-            match event {
-                InternalEvent::ConfigDelta(delta) => {
-                    if let Err(error) = self.current_config.merge(&(), delta.into()) {
-                        error!(%error, "Failed to merge config delta.");
-                    }
-                }
-                InternalEvent::Event(event) => {
-                    return Some(ConversationEventWithConfig {
-                        event: *event,
-                        config: self.current_config.clone(),
-                    });
-                }
-                InternalEvent::Compaction(_) | InternalEvent::Unknown(_) => {}
+            if let InternalEvent::Event(event) = event {
+                return Some(ConversationEventWithConfig {
+                    event: *event,
+                    config: self.current_config.current().clone(),
+                });
             }
         }
     }
@@ -1367,16 +1516,17 @@ impl DoubleEndedIterator for IntoIter {
                     // them) to apply all pending deltas to our temporary
                     // config.
                     for internal_event in self.inner_iter.as_slice() {
-                        if let InternalEvent::ConfigDelta(delta) = internal_event
-                            && let Err(error) = config.merge(&(), delta.clone().into())
-                        {
-                            error!(%error, "Failed to merge config delta.");
-                        }
+                        config.apply(internal_event);
+                    }
+
+                    // The event itself can start a new turn.
+                    if event.is_turn_start() {
+                        config.turn = None;
                     }
 
                     return Some(ConversationEventWithConfig {
                         event: *event,
-                        config,
+                        config: config.current().clone(),
                     });
                 }
             }
@@ -1390,7 +1540,7 @@ struct Iter<'a> {
     stream: &'a ConversationStream,
 
     /// The configuration state for the first, next event in the iterator.
-    front_config: PartialAppConfig,
+    front_config: ConfigState,
 
     /// The index of the `next` event in the iterator.
     front: usize,
@@ -1406,20 +1556,13 @@ impl<'a> Iterator for Iter<'a> {
         while self.front < self.back {
             let event = &self.stream.events[self.front];
             self.front += 1;
+            self.front_config.apply(event);
 
-            match event {
-                InternalEvent::ConfigDelta(delta) => {
-                    if let Err(error) = self.front_config.merge(&(), delta.clone().into()) {
-                        error!(%error, "Failed to merge config delta.");
-                    }
-                }
-                InternalEvent::Event(event) => {
-                    return Some(ConversationEventWithConfigRef {
-                        event,
-                        config: self.front_config.clone(),
-                    });
-                }
-                InternalEvent::Compaction(_) | InternalEvent::Unknown(_) => {}
+            if let InternalEvent::Event(event) = event {
+                return Some(ConversationEventWithConfigRef {
+                    event,
+                    config: self.front_config.current().clone(),
+                });
             }
         }
 
@@ -1437,16 +1580,15 @@ impl DoubleEndedIterator for Iter<'_> {
                 continue;
             };
 
-            let mut config = self.stream.base_config.to_partial();
-            for internal_event in &self.stream.events[..self.back] {
-                if let InternalEvent::ConfigDelta(delta) = internal_event
-                    && let Err(error) = config.merge(&(), delta.clone().into())
-                {
-                    error!(%error, "Failed to merge config delta.");
-                }
+            let mut config = ConfigState::new(self.stream.base_config.to_partial());
+            for internal_event in &self.stream.events[..=self.back] {
+                config.apply(internal_event);
             }
 
-            return Some(ConversationEventWithConfigRef { event, config });
+            return Some(ConversationEventWithConfigRef {
+                event,
+                config: config.current().clone(),
+            });
         }
 
         None
@@ -1456,7 +1598,7 @@ impl DoubleEndedIterator for Iter<'_> {
 /// An iterator over the mutable events in a conversation stream.
 pub struct IterMut<'a> {
     /// The configuration state for the first, next event in the iterator.
-    front_config: PartialAppConfig,
+    front_config: ConfigState,
 
     /// The iterator over the events in the stream.
     iter: std::slice::IterMut<'a, InternalEvent>,
@@ -1467,19 +1609,13 @@ impl<'a> Iterator for IterMut<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         for event in self.iter.by_ref() {
-            match event {
-                InternalEvent::ConfigDelta(delta) => {
-                    if let Err(error) = self.front_config.merge(&(), delta.clone().into()) {
-                        error!(%error, "Failed to merge config delta.");
-                    }
-                }
-                InternalEvent::Event(event) => {
-                    return Some(ConversationEventWithConfigMut {
-                        event,
-                        config: self.front_config.clone(),
-                    });
-                }
-                InternalEvent::Compaction(_) | InternalEvent::Unknown(_) => {}
+            self.front_config.apply(event);
+
+            if let InternalEvent::Event(event) = event {
+                return Some(ConversationEventWithConfigMut {
+                    event,
+                    config: self.front_config.current().clone(),
+                });
             }
         }
 
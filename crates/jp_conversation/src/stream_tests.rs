@@ -1656,6 +1656,7 @@ fn extend_into_empty_preserves_observed_iter_and_serialized_shape() {
     source.add_config_delta(ConfigDelta {
         delta: Box::new(partial1),
         timestamp: Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap(),
+        scope: DeltaScope::Conversation,
     });
     source.push(ConversationEvent::new(
         TurnStart,
@@ -1672,6 +1673,7 @@ fn extend_into_empty_preserves_observed_iter_and_serialized_shape() {
     source.add_config_delta(ConfigDelta {
         delta: Box::new(partial2),
         timestamp: Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 3).unwrap(),
+        scope: DeltaScope::Conversation,
     });
     source.push(ConversationEvent::new(
         TurnStart,
@@ -1702,3 +1704,87 @@ fn extend_into_empty_preserves_observed_iter_and_serialized_shape() {
     let dest_parts = dest.to_parts().unwrap();
     assert_eq!(source_parts, dest_parts);
 }
+
+#[test]
+fn test_turn_scoped_delta_applies_to_its_turn_only() {
+    let mut overlay = jp_config::PartialAppConfig::empty();
+    overlay.conversation.tools.defaults.run = Some(RunMode::Skip);
+
+    let mut stream = ConversationStream::new_test();
+    let base_run = stream.config().unwrap().conversation.tools.defaults.run;
+    assert_ne!(base_run, RunMode::Skip);
+
+    stream.add_config_delta(ConfigDelta::from(overlay.clone()).turn_scoped());
+    stream.start_turn(ChatRequest::from("Q1"));
+    stream.push(ChatResponse::message("A1"));
+
+    // A delta added before a turn starts is reverted by it.
+    assert_eq!(
+        stream.config().unwrap().conversation.tools.defaults.run,
+        base_run
+    );
+    assert_eq!(stream.turn_config_delta().unwrap(), None);
+
+    let mut stream = ConversationStream::new_test();
+    stream.start_turn(ChatRequest::from("Q1"));
+    stream.add_config_delta(ConfigDelta::from(overlay).turn_scoped());
+    stream.push(ChatResponse::message("A1"));
+
+    let delta = stream.turn_config_delta().unwrap().unwrap();
+    assert_eq!(delta.conversation.tools.defaults.run, Some(RunMode::Skip));
+    assert_eq!(
+        stream.config().unwrap().conversation.tools.defaults.run,
+        base_run
+    );
+
+    let last = stream.last().unwrap();
+    assert_eq!(
+        last.config.conversation.tools.defaults.run,
+        Some(RunMode::Skip)
+    );
+
+    // The next turn reverts the delta.
+    stream.start_turn(ChatRequest::from("Q2"));
+    assert_eq!(stream.turn_config_delta().unwrap(), None);
+
+    let runs: Vec<_> = stream
+        .iter()
+        .map(|event| event.config.conversation.tools.defaults.run)
+        .collect();
+    assert_eq!(runs, vec![
+        Some(base_run),
+        Some(base_run),
+        Some(RunMode::Skip),
+        Some(base_run),
+        Some(base_run),
+    ]);
+
+    let reversed: Vec<_> = stream
+        .iter()
+        .rev()
+        .map(|event| event.config.conversation.tools.defaults.run)
+        .collect();
+    assert_eq!(reversed, runs.iter().copied().rev().collect::<Vec<_>>());
+
+    let owned: Vec<_> = stream
+        .clone()
+        .into_iter()
+        .map(|event| event.config.conversation.tools.defaults.run)
+        .collect();
+    assert_eq!(owned, runs);
+}
+
+#[test]
+fn test_roundtrip_turn_scoped_delta() {
+    let mut partial = jp_config::PartialAppConfig::empty();
+    partial.style.code.color = Some(false);
+
+    let original = ConfigDelta::from(partial.clone()).turn_scoped();
+    let json = serde_json::to_value(InternalEvent::ConfigDelta(original.clone())).unwrap();
+    assert_eq!(json["scope"], "turn");
+    assert_eq!(roundtrip_delta(original).scope, DeltaScope::Turn);
+
+    let json =
+        serde_json::to_value(InternalEvent::ConfigDelta(ConfigDelta::from(partial))).unwrap();
+    assert!(json.get("scope").is_none());
+}
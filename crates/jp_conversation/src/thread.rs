@@ -1,7 +1,12 @@
 //! See [`Thread`].
 
+use std::sync::Arc;
+
 use jp_attachment::Attachment;
-use jp_config::assistant::sections::SectionConfig;
+use jp_config::{
+    assistant::sections::SectionConfig,
+    model::id::{ModelIdConfig, ModelIdOrAliasConfig},
+};
 use quick_xml::se::TextFormat;
 use serde::Serialize;
 use tracing::trace;
@@ -181,6 +186,43 @@ impl Thread {
     }
 }
 
+impl Thread {
+    /// The thread as a conversation stream, as it is sent to `model`.
+    ///
+    /// The system prompt, sections and model become the base configuration of
+    /// the stream, and its events are the ones [`Thread::into_parts`] keeps.
+    /// Attachments are not included.
+    ///
+    /// Used to record the requests JP makes on its own behalf, such as
+    /// generating a title, so they can be inspected later.
+    #[must_use]
+    pub fn to_stream(&self, model: &ModelIdConfig) -> ConversationStream {
+        let mut config = self
+            .events
+            .config()
+            .unwrap_or_else(|_| self.events.base_config().as_ref().clone());
+        config
+            .assistant
+            .system_prompt
+            .clone_from(&self.system_prompt);
+        config
+            .assistant
+            .system_prompt_sections
+            .clone_from(&self.sections);
+        config.assistant.instructions.clear();
+        config.assistant.model.id = ModelIdOrAliasConfig::Id(model.clone());
+
+        let mut events = self.events.clone();
+        events.apply_projection();
+        events.retain(|e| e.is_turn_start() || e.kind.is_provider_visible());
+
+        let mut stream =
+            ConversationStream::new(Arc::new(config)).with_created_at(events.created_at);
+        stream.extend(events.iter().map(|e| e.event.clone()));
+        stream
+    }
+}
+
 /// Serialize text attachments to an XML `<documents>` block.
 ///
 /// Binary attachments are silently skipped.
@@ -264,3 +306,7 @@ pub struct Document {
     /// The content of the document.
     content: String,
 }
+
+#[cfg(test)]
+#[path = "thread_tests.rs"]
+mod tests;
@@ -0,0 +1,53 @@
+use jp_config::{
+    assistant::sections::SectionConfig,
+    model::id::{ModelIdConfig, ModelIdOrAliasConfig, ProviderId},
+};
+
+use super::*;
+use crate::event::{ChatResponse, EventKind, InquiryQuestion, InquiryRequest, InquirySource};
+
+#[test]
+fn to_stream_records_request_as_sent() {
+    let mut events = ConversationStream::new_test().with_turn("hello");
+    events
+        .current_turn_mut()
+        .add_inquiry_request(InquiryRequest::new(
+            "iq1",
+            InquirySource::tool("read_file"),
+            InquiryQuestion::boolean("proceed?".into()),
+        ))
+        .add_chat_response(ChatResponse::message("hi"))
+        .build()
+        .unwrap();
+
+    let thread = ThreadBuilder::new()
+        .with_system_prompt("Be brief.")
+        .add_section(SectionConfig::default().with_content("Context."))
+        .with_events(events)
+        .build()
+        .unwrap();
+
+    let model = ModelIdConfig {
+        provider: ProviderId::Test,
+        name: "title-model".parse().unwrap(),
+    };
+    let stream = thread.to_stream(&model);
+
+    let config = stream.base_config();
+    assert_eq!(config.assistant.system_prompt.as_deref(), Some("Be brief."));
+    assert_eq!(config.assistant.system_prompt_sections, thread.sections);
+    assert_eq!(config.assistant.model.id, ModelIdOrAliasConfig::Id(model));
+    assert_eq!(stream.created_at, thread.events.created_at);
+
+    // The inquiry is JP-internal and never reaches the provider.
+    let kinds = stream
+        .iter()
+        .map(|e| match &e.event.kind {
+            EventKind::TurnStart(_) => "turn_start",
+            EventKind::ChatRequest(_) => "chat_request",
+            EventKind::ChatResponse(_) => "chat_response",
+            _ => "other",
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, ["turn_start", "chat_request", "chat_response"]);
+}
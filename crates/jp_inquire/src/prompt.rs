@@ -168,6 +168,55 @@ impl PromptBackend for TerminalPromptBackend {
     }
 }
 
+/// Prompt backend for runs that must never prompt.
+///
+/// Every prompt fails with [`InquireError::NotTTY`], as it would without a
+/// terminal, instead of reaching a terminal the run's output is not shown on.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoPromptBackend;
+
+impl PromptBackend for NoPromptBackend {
+    fn inline_select(
+        &self,
+        _message: &str,
+        _options: Vec<InlineOption>,
+        _default: Option<char>,
+        _writer: &mut dyn Write,
+    ) -> Result<char, InquireError> {
+        Err(InquireError::NotTTY)
+    }
+
+    fn inline_reply(
+        &self,
+        _message: &str,
+        _initial_text: &str,
+        _edit_mode: ReplyEditMode,
+        _editor_escape: bool,
+        _output: Box<dyn Write + Send>,
+    ) -> Result<ReplyOutcome, InquireError> {
+        Err(InquireError::NotTTY)
+    }
+
+    fn text(
+        &self,
+        _message: &str,
+        _default: Option<&str>,
+        _writer: &mut dyn Write,
+    ) -> Result<String, InquireError> {
+        Err(InquireError::NotTTY)
+    }
+
+    fn select(
+        &self,
+        _message: &str,
+        _options: Vec<String>,
+        _default: Option<usize>,
+        _writer: &mut dyn Write,
+    ) -> Result<String, InquireError> {
+        Err(InquireError::NotTTY)
+    }
+}
+
 /// Mock prompt backend for testing.
 ///
 /// Pre-load the responses returned by the prompt methods (`inline_select`,
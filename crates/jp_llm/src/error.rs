@@ -337,6 +337,9 @@ pub enum Error {
     #[error("Missing environment variable: {0}")]
     MissingEnv(String),
 
+    #[error("Secret error: {0}")]
+    Secret(jp_config::secret::SecretError),
+
     #[error("Invalid URL: {0}")]
     Url(#[from] url::ParseError),
 
@@ -394,6 +397,15 @@ pub enum Error {
     ModelId(#[from] jp_config::model::id::ModelIdError),
 }
 
+impl From<jp_config::secret::SecretError> for Error {
+    fn from(error: jp_config::secret::SecretError) -> Self {
+        match error {
+            jp_config::secret::SecretError::MissingEnv(name) => Self::MissingEnv(name),
+            error => Self::Secret(error),
+        }
+    }
+}
+
 #[cfg(test)]
 impl PartialEq for Error {
     fn eq(&self, other: &Self) -> bool {
@@ -9,13 +9,17 @@ pub mod ollama;
 pub mod openai;
 pub mod openrouter;
 
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{
+    Arc, Mutex, PoisonError,
+    atomic::{AtomicU64, Ordering},
+};
 
 use anthropic::Anthropic;
 use async_trait::async_trait;
 use cerebras::Cerebras;
 use google::Google;
 use jp_config::{
+    env::Env,
     model::id::{Name, ProviderId},
     providers::llm::LlmProviderConfig,
 };
@@ -46,15 +50,21 @@ pub trait Provider: Send + Sync {
 }
 
 /// Get a provider by ID.
-pub fn get_provider(id: ProviderId, config: &LlmProviderConfig) -> Result<Box<dyn Provider>> {
+///
+/// Secret references in the provider's configuration are resolved with `env`.
+pub fn get_provider(
+    id: ProviderId,
+    config: &LlmProviderConfig,
+    env: &Env,
+) -> Result<Box<dyn Provider>> {
     let provider: Box<dyn Provider> = match id {
-        ProviderId::Anthropic => Box::new(Anthropic::try_from(&config.anthropic)?),
-        ProviderId::Cerebras => Box::new(Cerebras::try_from(&config.cerebras)?),
-        ProviderId::Google => Box::new(Google::try_from(&config.google)?),
-        ProviderId::Llamacpp => Box::new(Llamacpp::try_from(&config.llamacpp)?),
-        ProviderId::Ollama => Box::new(Ollama::try_from(&config.ollama)?),
-        ProviderId::Openai => Box::new(Openai::try_from(&config.openai)?),
-        ProviderId::Openrouter => Box::new(Openrouter::try_from(&config.openrouter)?),
+        ProviderId::Anthropic => Box::new(Anthropic::try_from((&config.anthropic, env))?),
+        ProviderId::Cerebras => Box::new(Cerebras::try_from((&config.cerebras, env))?),
+        ProviderId::Google => Box::new(Google::try_from((&config.google, env))?),
+        ProviderId::Llamacpp => Box::new(Llamacpp::try_from((&config.llamacpp, env))?),
+        ProviderId::Ollama => Box::new(Ollama::try_from((&config.ollama, env))?),
+        ProviderId::Openai => Box::new(Openai::try_from((&config.openai, env))?),
+        ProviderId::Openrouter => Box::new(Openrouter::try_from((&config.openrouter, env))?),
 
         ProviderId::Deepseek => todo!(),
         ProviderId::Xai => todo!(),
@@ -65,6 +75,61 @@ pub fn get_provider(id: ProviderId, config: &LlmProviderConfig) -> Result<Box<dy
     Ok(provider)
 }
 
+/// Providers that were already built, for reuse.
+///
+/// Building a provider resolves the secrets in its configuration and sets up
+/// its HTTP client, so a process that runs many requests keeps them around.
+/// A provider is reused only for the same configuration and environment
+/// variables it was built with.
+///
+/// Clones share the same providers.
+#[derive(Clone, Default)]
+pub struct ProviderCache {
+    providers: Arc<Mutex<Vec<CachedProvider>>>,
+}
+
+struct CachedProvider {
+    id: ProviderId,
+    config: LlmProviderConfig,
+    env: Env,
+    provider: Arc<dyn Provider>,
+}
+
+impl ProviderCache {
+    /// Get the provider with the given ID, building it if it is not cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`get_provider`].
+    pub fn get(
+        &self,
+        id: ProviderId,
+        config: &LlmProviderConfig,
+        env: &Env,
+    ) -> Result<Arc<dyn Provider>> {
+        let mut providers = self
+            .providers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let cached = providers.iter().find(|cached| {
+            cached.id == id && cached.config == *config && cached.env.overrides() == env.overrides()
+        });
+        if let Some(cached) = cached {
+            return Ok(cached.provider.clone());
+        }
+
+        let provider: Arc<dyn Provider> = Arc::from(get_provider(id, config, env)?);
+        providers.push(CachedProvider {
+            id,
+            config: config.clone(),
+            env: env.clone(),
+            provider: provider.clone(),
+        });
+
+        Ok(provider)
+    }
+}
+
 /// Validate that a provider is able to accept requests: credentials present,
 /// configuration well-formed.
 ///
@@ -81,8 +146,8 @@ pub fn get_provider(id: ProviderId, config: &LlmProviderConfig) -> Result<Box<dy
 /// the provider's API key environment variable is unset.
 ///
 /// [`Error::MissingEnv`]: crate::Error::MissingEnv
-pub fn preflight(id: ProviderId, config: &LlmProviderConfig) -> Result<()> {
-    get_provider(id, config).map(drop)
+pub fn preflight(id: ProviderId, config: &LlmProviderConfig, env: &Env) -> Result<()> {
+    get_provider(id, config, env).map(drop)
 }
 
 /// Build the provider-native chat request for `query` and serialize it to JSON,
@@ -100,17 +165,22 @@ pub(crate) fn build_request_value(
     model: &ModelDetails,
     query: ChatQuery,
 ) -> Result<serde_json::Value> {
+    let env = Env::process();
     match id {
         ProviderId::Anthropic => {
-            Anthropic::try_from(&config.anthropic)?.request_value(model, query)
+            Anthropic::try_from((&config.anthropic, &env))?.request_value(model, query)
+        }
+        ProviderId::Cerebras => {
+            Cerebras::try_from((&config.cerebras, &env))?.request_value(model, query)
+        }
+        ProviderId::Google => Google::try_from((&config.google, &env))?.request_value(model, query),
+        ProviderId::Llamacpp => {
+            Llamacpp::try_from((&config.llamacpp, &env))?.request_value(model, query)
         }
-        ProviderId::Cerebras => Cerebras::try_from(&config.cerebras)?.request_value(model, query),
-        ProviderId::Google => Google::try_from(&config.google)?.request_value(model, query),
-        ProviderId::Llamacpp => Llamacpp::try_from(&config.llamacpp)?.request_value(model, query),
-        ProviderId::Ollama => Ollama::try_from(&config.ollama)?.request_value(model, query),
-        ProviderId::Openai => Openai::try_from(&config.openai)?.request_value(model, query),
+        ProviderId::Ollama => Ollama::try_from((&config.ollama, &env))?.request_value(model, query),
+        ProviderId::Openai => Openai::try_from((&config.openai, &env))?.request_value(model, query),
         ProviderId::Openrouter => {
-            Openrouter::try_from(&config.openrouter)?.request_value(model, query)
+            Openrouter::try_from((&config.openrouter, &env))?.request_value(model, query)
         }
         ProviderId::Test | ProviderId::Deepseek | ProviderId::Xai => {
             unreachable!("{id:?} is not part of the request snapshot suite")
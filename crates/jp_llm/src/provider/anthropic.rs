@@ -1,4 +1,4 @@
-use std::{env, mem, ops::RangeInclusive, time::Duration};
+use std::{mem, ops::RangeInclusive, time::Duration};
 
 use async_anthropic::{
     Client,
@@ -17,11 +17,13 @@ use futures::{StreamExt as _, TryStreamExt as _, pin_mut, stream};
 use jp_attachment::AttachmentContent;
 use jp_config::{
     assistant::{request::CachePolicy, tool_choice::ToolChoice},
+    env::Env,
     model::{
         id::{Name, ProviderId},
         parameters::{ReasoningConfig, ReasoningEffort},
     },
     providers::llm::anthropic::AnthropicConfig,
+    secret,
 };
 use jp_conversation::{
     ConversationStream,
@@ -1869,25 +1871,26 @@ fn map_event(
     }
 }
 
-impl TryFrom<&AnthropicConfig> for Anthropic {
+impl TryFrom<(&AnthropicConfig, &Env)> for Anthropic {
     type Error = Error;
 
-    fn try_from(config: &AnthropicConfig) -> Result<Self> {
-        let api_key = env::var(&config.api_key_env)
-            .map_err(|_| Error::MissingEnv(config.api_key_env.clone()))?;
+    fn try_from((config, env): (&AnthropicConfig, &Env)) -> Result<Self> {
+        let api_key = secret::resolve_env(&config.api_key_env, env)?;
+        let base_url = secret::resolve(&config.base_url, env)?;
+        let beta_headers = secret::resolve_all(&config.beta_headers, env)?;
 
         let mut builder = Client::builder();
         builder
             .api_key(api_key)
-            .base_url(config.base_url.clone())
+            .base_url(base_url.into_owned())
             .version("2023-06-01");
 
-        if !config.beta_headers.is_empty() {
-            builder.beta(config.beta_headers.join(","));
+        if !beta_headers.is_empty() {
+            builder.beta(beta_headers.join(","));
         }
 
         Ok(Anthropic {
-            beta: BetaFeatures(config.beta_headers.clone()),
+            beta: BetaFeatures(beta_headers),
             chain_on_max_tokens: config.chain_on_max_tokens,
             client: builder
                 .build()
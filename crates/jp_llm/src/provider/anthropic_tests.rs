@@ -3217,3 +3217,31 @@ fn test_resolve_cache_control_custom_rounds_at_30_minutes() {
         Some(types::CacheControlTtl::Ttl1Hour)
     );
 }
+
+#[test]
+fn secret_references_resolve_in_base_url_and_beta_headers() {
+    let mut config = jp_config::AppConfig::new_test().providers.llm.anthropic;
+    config.api_key_env = "JP_TEST_KEY".into();
+    config.base_url = "secret:env:JP_TEST_URL".into();
+    config.beta_headers = vec!["secret:env:JP_TEST_BETA".into()];
+
+    let env = Env::captured(
+        [
+            ("JP_TEST_KEY", "key"),
+            ("JP_TEST_URL", "http://localhost:1"),
+            ("JP_TEST_BETA", "files-api"),
+        ]
+        .map(|(key, value)| (key.to_owned(), value.to_owned())),
+    );
+    let provider = Anthropic::try_from((&config, &env)).unwrap();
+    assert_eq!(provider.beta.0, vec!["files-api".to_owned()]);
+
+    let env = Env::captured(
+        [("JP_TEST_KEY", "key"), ("JP_TEST_BETA", "files-api")]
+            .map(|(key, value)| (key.to_owned(), value.to_owned())),
+    );
+    assert!(matches!(
+        Anthropic::try_from((&config, &env)),
+        Err(Error::MissingEnv(name)) if name == "JP_TEST_URL"
+    ));
+}
@@ -1,14 +1,16 @@
-use std::{collections::HashMap, env, time::Duration};
+use std::{collections::HashMap, time::Duration};
 
 use async_trait::async_trait;
 use futures::{Stream, StreamExt as _, future, stream};
 use jp_config::{
     assistant::tool_choice::ToolChoice,
+    env::Env,
     model::{
         id::{ModelIdConfig, Name, ProviderId},
         parameters::{ReasoningConfig, ReasoningEffort},
     },
     providers::llm::cerebras::CerebrasConfig,
+    secret,
 };
 use jp_conversation::{
     ConversationStream,
@@ -170,12 +172,11 @@ where
         .boxed()
 }
 
-impl TryFrom<&CerebrasConfig> for Cerebras {
+impl TryFrom<(&CerebrasConfig, &Env)> for Cerebras {
     type Error = Error;
 
-    fn try_from(config: &CerebrasConfig) -> Result<Self> {
-        let api_key = env::var(&config.api_key_env)
-            .map_err(|_| Error::MissingEnv(config.api_key_env.clone()))?;
+    fn try_from((config, env): (&CerebrasConfig, &Env)) -> Result<Self> {
+        let api_key = secret::resolve_env(&config.api_key_env, env)?;
 
         let client = reqwest::Client::builder()
             .default_headers(HeaderMap::from_iter([(
@@ -187,7 +188,7 @@ impl TryFrom<&CerebrasConfig> for Cerebras {
 
         Ok(Cerebras {
             client,
-            base_url: config.base_url.clone(),
+            base_url: secret::resolve(&config.base_url, env)?.into_owned(),
         })
     }
 }
@@ -685,3 +685,20 @@ fn convert_events_coalesces_parallel_tool_calls() {
     assert_eq!(messages[2]["role"], "tool");
     assert_eq!(messages[2]["tool_call_id"], "call_b");
 }
+
+#[test]
+fn secret_references_resolve_in_base_url() {
+    let mut config = jp_config::AppConfig::new_test().providers.llm.cerebras;
+    config.api_key_env = "JP_TEST_KEY".into();
+    config.base_url = "secret:env:JP_TEST_URL".into();
+
+    let env = Env::captured(
+        [
+            ("JP_TEST_KEY", "key"),
+            ("JP_TEST_URL", "http://localhost:1"),
+        ]
+        .map(|(key, value)| (key.to_owned(), value.to_owned())),
+    );
+    let provider = Cerebras::try_from((&config, &env)).unwrap();
+    assert_eq!(provider.base_url, "http://localhost:1");
+}
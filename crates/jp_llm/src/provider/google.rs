@@ -1,4 +1,4 @@
-use std::{collections::HashMap, env};
+use std::collections::HashMap;
 
 use async_stream::stream;
 use async_trait::async_trait;
@@ -10,11 +10,13 @@ use indexmap::IndexMap;
 use jp_attachment::AttachmentContent;
 use jp_config::{
     assistant::tool_choice::ToolChoice,
+    env::Env,
     model::{
         id::{ModelIdConfig, Name, ProviderId},
         parameters::{ReasoningConfig, ReasoningEffort},
     },
     providers::llm::google::GoogleConfig,
+    secret,
 };
 use jp_conversation::{
     ConversationStream,
@@ -849,15 +851,15 @@ fn map_candidate(
     Ok(events)
 }
 
-impl TryFrom<&GoogleConfig> for Google {
+impl TryFrom<(&GoogleConfig, &Env)> for Google {
     type Error = Error;
 
-    fn try_from(config: &GoogleConfig) -> Result<Self> {
-        let api_key = env::var(&config.api_key_env)
-            .map_err(|_| Error::MissingEnv(config.api_key_env.clone()))?;
+    fn try_from((config, env): (&GoogleConfig, &Env)) -> Result<Self> {
+        let api_key = secret::resolve_env(&config.api_key_env, env)?;
+        let base_url = secret::resolve(&config.base_url, env)?;
 
         Ok(Google {
-            client: GeminiClient::new(api_key).with_api_url(config.base_url.clone()),
+            client: GeminiClient::new(api_key).with_api_url(base_url.into_owned()),
         })
     }
 }
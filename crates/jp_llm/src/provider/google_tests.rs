@@ -949,3 +949,27 @@ mod transform_schema {
         );
     }
 }
+
+#[test]
+fn secret_references_resolve_in_base_url() {
+    let mut config = jp_config::AppConfig::new_test().providers.llm.google;
+    config.api_key_env = "JP_TEST_KEY".into();
+    config.base_url = "secret:env:JP_TEST_URL".into();
+
+    let env = Env::captured(
+        [
+            ("JP_TEST_KEY", "key"),
+            ("JP_TEST_URL", "http://localhost:1"),
+        ]
+        .map(|(key, value)| (key.to_owned(), value.to_owned())),
+    );
+    assert!(Google::try_from((&config, &env)).is_ok());
+
+    let env = Env::captured(
+        [("JP_TEST_KEY", "key")].map(|(key, value)| (key.to_owned(), value.to_owned())),
+    );
+    assert!(matches!(
+        Google::try_from((&config, &env)),
+        Err(Error::MissingEnv(name)) if name == "JP_TEST_URL"
+    ));
+}
@@ -6,11 +6,13 @@ use futures::{Stream, StreamExt as _, future, stream};
 use jp_attachment::AttachmentContent;
 use jp_config::{
     assistant::tool_choice::ToolChoice,
+    env::Env,
     model::{
         id::{ModelIdConfig, Name, ProviderId},
         parameters::ReasoningConfig,
     },
     providers::llm::llamacpp::LlamacppConfig,
+    secret,
 };
 use jp_conversation::{
     ConversationStream,
@@ -793,12 +795,12 @@ fn map_model(model: &LlamacppModel, served_context: Option<u32>) -> Result<Model
     })
 }
 
-impl TryFrom<&LlamacppConfig> for Llamacpp {
+impl TryFrom<(&LlamacppConfig, &Env)> for Llamacpp {
     type Error = Error;
 
-    fn try_from(config: &LlamacppConfig) -> Result<Self, Self::Error> {
+    fn try_from((config, env): (&LlamacppConfig, &Env)) -> Result<Self, Self::Error> {
         let reqwest_client = reqwest::Client::builder().build()?;
-        let base_url = config.base_url.clone();
+        let base_url = secret::resolve(&config.base_url, env)?.into_owned();
 
         Ok(Llamacpp {
             reqwest_client,
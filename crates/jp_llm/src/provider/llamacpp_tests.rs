@@ -382,3 +382,16 @@ fn length_finish_reason_drops_pending_tool_calls() {
         "expected Finished(MaxTokens), got {last:?}"
     );
 }
+
+#[test]
+fn secret_references_resolve_in_base_url() {
+    let mut config = jp_config::AppConfig::new_test().providers.llm.llamacpp;
+    config.base_url = "secret:env:JP_TEST_URL".into();
+
+    let env = Env::captured(
+        [("JP_TEST_URL", "http://localhost:1")]
+            .map(|(key, value)| (key.to_owned(), value.to_owned())),
+    );
+    let provider = Llamacpp::try_from((&config, &env)).unwrap();
+    assert_eq!(provider.base_url, "http://localhost:1");
+}
@@ -6,8 +6,10 @@ use futures::{StreamExt as _, TryStreamExt as _, stream};
 use jp_attachment::AttachmentContent;
 use jp_config::{
     assistant::tool_choice::ToolChoice,
+    env::Env,
     model::id::{ModelIdConfig, Name, ProviderId},
     providers::llm::ollama::OllamaConfig,
+    secret,
 };
 use jp_conversation::{
     ConversationStream,
@@ -394,11 +396,11 @@ fn create_request(model: &ModelDetails, query: ChatQuery) -> Result<(ChatMessage
     Ok((request, is_structured))
 }
 
-impl TryFrom<&OllamaConfig> for Ollama {
+impl TryFrom<(&OllamaConfig, &Env)> for Ollama {
     type Error = Error;
 
-    fn try_from(config: &OllamaConfig) -> Result<Self> {
-        let url = Url::from_str(&config.base_url)?;
+    fn try_from((config, env): (&OllamaConfig, &Env)) -> Result<Self> {
+        let url = Url::from_str(&secret::resolve(&config.base_url, env)?)?;
         let port = url.port().unwrap_or(11434);
         let client = reqwest::Client::new();
 
@@ -142,3 +142,20 @@ fn map_model_without_reported_capabilities_stays_unknown() {
     assert_eq!(details.reasoning, None);
     assert_eq!(details.context_window, None);
 }
+
+#[test]
+fn secret_references_resolve_in_base_url() {
+    let mut config = jp_config::AppConfig::new_test().providers.llm.ollama;
+    config.base_url = "secret:env:JP_TEST_URL".into();
+
+    let env = Env::captured(
+        [("JP_TEST_URL", "http://localhost:1")]
+            .map(|(key, value)| (key.to_owned(), value.to_owned())),
+    );
+    assert!(Ollama::try_from((&config, &env)).is_ok());
+
+    assert!(matches!(
+        Ollama::try_from((&config, &Env::captured([]))),
+        Err(Error::MissingEnv(name)) if name == "JP_TEST_URL"
+    ));
+}
@@ -1,4 +1,4 @@
-use std::{env, time::Duration};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use base64::Engine as _;
@@ -9,11 +9,13 @@ use jp_attachment::AttachmentContent;
 use jp_config::{
     assistant::tool_choice::ToolChoice,
     conversation::tool::{OneOrManyTypes, ToolParameterConfig},
+    env::Env,
     model::{
         id::{Name, ProviderId},
         parameters::{CustomReasoningConfig, ReasoningConfig, ReasoningEffort},
     },
     providers::llm::openai::OpenaiConfig,
+    secret,
 };
 use jp_conversation::{
     ConversationStream,
@@ -1580,12 +1582,11 @@ fn classify_stream_error(error: types::response::Error) -> StreamError {
     }
 }
 
-impl TryFrom<&OpenaiConfig> for Openai {
+impl TryFrom<(&OpenaiConfig, &Env)> for Openai {
     type Error = Error;
 
-    fn try_from(config: &OpenaiConfig) -> Result<Self> {
-        let api_key = env::var(&config.api_key_env)
-            .map_err(|_| Error::MissingEnv(config.api_key_env.clone()))?;
+    fn try_from((config, env): (&OpenaiConfig, &Env)) -> Result<Self> {
+        let api_key = secret::resolve_env(&config.api_key_env, env)?;
 
         let reqwest_client = reqwest::Client::builder()
             .default_headers(HeaderMap::from_iter([(
@@ -1595,15 +1596,17 @@ impl TryFrom<&OpenaiConfig> for Openai {
             )]))
             .build()?;
 
-        let base_url =
-            std::env::var(&config.base_url_env).unwrap_or_else(|_| config.base_url.clone());
+        let base_url = secret::resolve(&config.base_url, env)?.into_owned();
+        let client_base_url = env
+            .var(&config.base_url_env)
+            .unwrap_or_else(|| base_url.clone());
 
-        let client = Client::new(&api_key)?.with_base_url(base_url);
+        let client = Client::new(&api_key)?.with_base_url(client_base_url);
 
         Ok(Openai {
             reqwest_client,
             client,
-            base_url: config.base_url.clone(),
+            base_url,
         })
     }
 }
@@ -1932,3 +1932,34 @@ mod classify_stream_error {
         assert_eq!(classified.retry_after, None);
     }
 }
+
+mod try_from {
+    use jp_config::{AppConfig, env::Env};
+
+    use super::super::Openai;
+    use crate::error::Error;
+
+    #[test]
+    fn secret_references_resolve_in_base_url() {
+        let mut config = AppConfig::new_test().providers.llm.openai;
+        config.api_key_env = "JP_TEST_KEY".into();
+        config.base_url = "secret:env:JP_TEST_URL".into();
+        config.base_url_env = "JP_TEST_BASE_URL".into();
+
+        let env = Env::captured(
+            [
+                ("JP_TEST_KEY", "key"),
+                ("JP_TEST_URL", "http://localhost:1"),
+            ]
+            .map(|(key, value)| (key.to_owned(), value.to_owned())),
+        );
+        let provider = Openai::try_from((&config, &env)).unwrap();
+        assert_eq!(provider.base_url, "http://localhost:1");
+
+        let env = Env::captured([("JP_TEST_KEY".to_owned(), "key".to_owned())]);
+        assert!(matches!(
+            Openai::try_from((&config, &env)),
+            Err(Error::MissingEnv(name)) if name == "JP_TEST_URL"
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{env, time::Duration};
+use std::{borrow::Cow, time::Duration};
 
 use async_stream::try_stream;
 use async_trait::async_trait;
@@ -8,11 +8,13 @@ use futures::{StreamExt as _, TryStreamExt as _, pin_mut, stream};
 use jp_attachment::AttachmentContent;
 use jp_config::{
     assistant::tool_choice::ToolChoice,
+    env::Env,
     model::{
         id::{ModelIdConfig, Name, ProviderId},
         parameters::ReasoningEffort,
     },
     providers::llm::openrouter::OpenrouterConfig,
+    secret,
 };
 use jp_conversation::{
     ConversationStream,
@@ -977,19 +979,24 @@ impl From<types::response::ErrorResponse> for Error {
     }
 }
 
-impl TryFrom<&OpenrouterConfig> for Openrouter {
+impl TryFrom<(&OpenrouterConfig, &Env)> for Openrouter {
     type Error = Error;
 
-    fn try_from(config: &OpenrouterConfig) -> Result<Self> {
-        let api_key = env::var(&config.api_key_env)
-            .map_err(|_| Error::MissingEnv(config.api_key_env.clone()))?;
+    fn try_from((config, env): (&OpenrouterConfig, &Env)) -> Result<Self> {
+        let api_key = secret::resolve_env(&config.api_key_env, env)?;
+
+        let app_referrer = config
+            .app_referrer
+            .as_deref()
+            .map(|referrer| secret::resolve(referrer, env).map(Cow::into_owned))
+            .transpose()?;
 
         let client = Openrouter::new(
             api_key,
-            Some(config.app_name.clone()),
-            config.app_referrer.clone(),
+            Some(secret::resolve(&config.app_name, env)?.into_owned()),
+            app_referrer,
         )
-        .with_base_url(config.base_url.clone());
+        .with_base_url(secret::resolve(&config.base_url, env)?.into_owned());
 
         Ok(client)
     }
@@ -675,3 +675,41 @@ async fn run_test(
     )
     .await
 }
+
+#[test]
+fn secret_references_resolve_in_app_details_and_base_url() {
+    let mut config = LlmProviderConfig::default().openrouter;
+    config.api_key_env = "JP_TEST_KEY".into();
+    config.app_name = "secret:env:JP_TEST_APP".into();
+    config.app_referrer = Some("secret:env:JP_TEST_REFERRER".into());
+    config.base_url = "secret:env:JP_TEST_URL".into();
+
+    let env = Env::captured(
+        [
+            ("JP_TEST_KEY", "key"),
+            ("JP_TEST_APP", "my-app"),
+            ("JP_TEST_REFERRER", "https://example.com"),
+            ("JP_TEST_URL", "http://localhost:1"),
+        ]
+        .map(|(key, value)| (key.to_owned(), value.to_owned())),
+    );
+    let provider = Openrouter::try_from((&config, &env)).unwrap();
+    assert_eq!(provider.client.app_name.as_deref(), Some("my-app"));
+    assert_eq!(
+        provider.client.app_referrer.as_deref(),
+        Some("https://example.com")
+    );
+
+    let env = Env::captured(
+        [
+            ("JP_TEST_KEY", "key"),
+            ("JP_TEST_APP", "my-app"),
+            ("JP_TEST_REFERRER", "https://example.com"),
+        ]
+        .map(|(key, value)| (key.to_owned(), value.to_owned())),
+    );
+    assert!(matches!(
+        Openrouter::try_from((&config, &env)),
+        Err(Error::MissingEnv(name)) if name == "JP_TEST_URL"
+    ));
+}
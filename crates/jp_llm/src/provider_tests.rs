@@ -247,7 +247,7 @@ fn preflight_reports_missing_credentials() {
     let mut config = LlmProviderConfig::default();
     config.openrouter.api_key_env = "JP_TEST_PREFLIGHT_UNSET_VAR".to_owned();
 
-    let error = preflight(ProviderId::Openrouter, &config).unwrap_err();
+    let error = preflight(ProviderId::Openrouter, &config, &Env::process()).unwrap_err();
     assert!(
         matches!(
             &error,
@@ -265,9 +265,37 @@ fn preflight_passes_when_credentials_present() {
     let mut config = LlmProviderConfig::default();
     config.openrouter.api_key_env = env;
 
-    preflight(ProviderId::Openrouter, &config).unwrap();
+    preflight(ProviderId::Openrouter, &config, &Env::process()).unwrap();
     // Llamacpp requires no credentials at all.
-    preflight(ProviderId::Llamacpp, &config).unwrap();
+    preflight(ProviderId::Llamacpp, &config, &Env::process()).unwrap();
+}
+
+#[test]
+fn provider_cache_reuses_providers_for_the_same_config_and_env() {
+    let mut config = LlmProviderConfig::default();
+    config.openrouter.api_key_env = "JP_TEST_CACHE_KEY".to_owned();
+    let env = Env::captured([("JP_TEST_CACHE_KEY".to_owned(), "one".to_owned())]);
+
+    let cache = ProviderCache::default();
+    let first = cache.get(ProviderId::Openrouter, &config, &env).unwrap();
+    let again = cache
+        .clone()
+        .get(ProviderId::Openrouter, &config, &env)
+        .unwrap();
+    assert!(Arc::ptr_eq(&first, &again));
+
+    // A different key must not be served the provider built for the first.
+    let other = Env::captured([("JP_TEST_CACHE_KEY".to_owned(), "two".to_owned())]);
+    let second = cache.get(ProviderId::Openrouter, &config, &other).unwrap();
+    assert!(!Arc::ptr_eq(&first, &second));
+
+    config.openrouter.base_url = "http://localhost:1234".to_owned();
+    let third = cache.get(ProviderId::Openrouter, &config, &env).unwrap();
+    assert!(!Arc::ptr_eq(&first, &third));
+
+    // Failures are not cached.
+    let unset = Env::captured([]);
+    assert!(cache.get(ProviderId::Openrouter, &config, &unset).is_err());
 }
 
 test_all_providers![
@@ -7,6 +7,7 @@ use jp_config::{
     AppConfig, Config as _, PartialAppConfig, ToPartial as _,
     assistant::tool_choice::ToolChoice,
     conversation::tool::ToolParameterConfig,
+    env::Env,
     model::{
         id::{ModelIdConfig, ModelIdOrAliasConfig, Name, PartialModelIdOrAliasConfig, ProviderId},
         parameters::{
@@ -392,7 +393,7 @@ pub async fn run_chat_completion(
                 }
             }
 
-            let provider = get_provider(provider_id, &config).unwrap();
+            let provider = get_provider(provider_id, &config, &Env::process()).unwrap();
             let has_chat_request = requests
                 .iter()
                 .any(|v| matches!(v, TestRequest::Chat { .. }));
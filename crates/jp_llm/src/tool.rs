@@ -1,10 +1,13 @@
 //! Tool call utilities.
 
+pub mod background;
 pub mod builtin;
 pub mod executor;
+pub mod pty;
 
 use std::{ffi::OsStr, process::Stdio, sync::Arc};
 
+pub use background::{BackgroundProcess, BackgroundTasks};
 pub use builtin::BuiltinTool;
 use camino::Utf8Path;
 use indexmap::IndexMap;
@@ -12,6 +15,7 @@ use jp_config::{
     conversation::tool::{
         CommandConfig, OneOrManyTypes, ToolConfigWithDefaults, ToolParameterConfig, ToolSource,
     },
+    env::Env,
     types::command::shell_command_line,
 };
 use jp_conversation::event::ToolCallResponse;
@@ -267,6 +271,9 @@ pub enum CommandResult {
     /// Tool was cancelled via the cancellation token.
     Cancelled,
 
+    /// Tool reported a [`Outcome::Handle`], and continues in the background.
+    Background(BackgroundProcess),
+
     /// stdout wasn't valid `Outcome` JSON.
     ///
     /// Falls back to treating stdout as plain text.
@@ -305,6 +312,8 @@ impl CommandResult {
     /// - `FatalError` → `Err(raw json)`
     /// - `NeedsInput` → handled separately by callers (this panics)
     /// - `Cancelled` → `Ok(cancellation message)`
+    /// - `Background` → `Err(message)`, stopping the tool, for callers that
+    ///   cannot track it
     /// - `RawOutput` → `Ok(stdout)` if success, `Err(json)` if failure
     pub fn into_tool_result(self, name: &str) -> Result<String, String> {
         match self {
@@ -316,6 +325,9 @@ impl CommandResult {
             .to_string()),
             Self::FatalError(raw) => Err(raw),
             Self::Cancelled => Ok("Tool execution cancelled by user.".to_string()),
+            Self::Background(_) => Err(format!(
+                "Tool '{name}' tried to continue in the background, which is not supported here."
+            )),
             Self::RawOutput {
                 stdout,
                 stderr,
@@ -394,7 +406,8 @@ fn format_tool_template_value(
 /// Run a tool command asynchronously with cancellation support.
 ///
 /// This is the **single entry point** for running tool commands (both execution
-/// and argument formatting).
+/// and argument formatting), except for tools that run in a pseudo-terminal
+/// (see [`pty::run_tool_command`]).
 /// It handles:
 ///
 /// 1. Template rendering via [`minijinja`]
@@ -403,6 +416,10 @@ fn format_tool_template_value(
 /// 4. Parsing stdout as [`jp_tool::Outcome`]
 /// 5. Forwarding the child's stderr to tracing (when `trace_as` is `Some`)
 ///
+/// If the first line of stdout is an [`Outcome::Handle`], the command returns
+/// early with a [`CommandResult::Background`] holding the still running
+/// process.
+///
 /// # Panics
 ///
 /// Panics if tokio fails to attach the piped stdout/stderr handles to the
@@ -413,48 +430,17 @@ pub async fn run_tool_command(
     command: CommandConfig,
     ctx: Value,
     root: &Utf8Path,
+    env: &Env,
     cancellation_token: CancellationToken,
     trace_as: Option<ToolTrace<'_>>,
 ) -> Result<CommandResult, ToolError> {
-    let CommandConfig {
-        program,
-        args,
-        shell,
-    } = command;
-
-    let mut env = Environment::new();
-    env.set_formatter(format_tool_template_value);
-    let tmpl = Arc::new(env);
+    let (program, args) = render_command(command, &ctx)?;
 
-    let program = tmpl
-        .render_str(&program, &ctx)
-        .map_err(|error| ToolError::TemplateError {
-            data: program.clone(),
-            error,
-        })?;
-
-    let args = args
-        .iter()
-        .map(|s| tmpl.render_str(s, &ctx))
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|error| ToolError::TemplateError {
-            data: args.join(" "),
-            error,
-        })?;
-
-    let mut cmd = if shell {
-        // `program` is shell syntax and used verbatim; `args` are shell-quoted
-        // so multi-word arguments keep their boundaries.
-        let shell_cmd = shell_command_line(&program, &args);
-
-        let mut cmd = Command::new("sh");
-        cmd.arg("-c").arg(&shell_cmd);
-        cmd
-    } else {
-        let mut cmd = Command::new(&program);
-        cmd.args(&args);
-        cmd
-    };
+    let mut cmd = Command::new(&program);
+    cmd.args(&args);
+    if let Some(vars) = env.overrides() {
+        cmd.env_clear().envs(vars);
+    }
 
     // Isolate the child from JP's process group so terminal signals
     // (Ctrl+C / SIGINT) don't kill it. JP manages tool lifecycle via
@@ -484,38 +470,98 @@ pub async fn run_tool_command(
             error,
         })?;
 
-    let stdout = child.stdout.take().expect("stdout piped");
+    let mut stdout = BufReader::new(child.stdout.take().expect("stdout piped"));
     let stderr = child.stderr.take().expect("stderr piped");
 
+    // Stderr is drained on its own task, as it outlives this function when
+    // the tool continues in the background.
+    let trace_as = trace_as.map(|ToolTrace { id, name }| (id.to_owned(), name.to_owned()));
+    let mut stderr = tokio::spawn(forward_stderr(stderr, trace_as));
+
     let run = async {
-        tokio::try_join!(
-            read_all(stdout),
-            forward_stderr(stderr, trace_as),
-            child.wait(),
-        )
+        let mut output = Vec::new();
+        stdout.read_until(b'\n', &mut output).await?;
+        if let Some(description) = parse_handle(&output) {
+            return Ok(Err(description));
+        }
+
+        stdout.read_to_end(&mut output).await?;
+        let status = child.wait().await?;
+        let stderr = (&mut stderr).await.map_err(std::io::Error::other)??;
+        Ok::<_, std::io::Error>(Ok((output, stderr, status)))
     };
 
-    tokio::select! {
+    let result = tokio::select! {
         biased;
-        () = cancellation_token.cancelled() => Ok(CommandResult::Cancelled),
-        result = run => Ok(match result {
-            Ok((stdout, stderr, status)) => {
-                parse_command_output(&stdout, &stderr, status.success())
-            }
-            Err(error) => CommandResult::RawOutput {
-                stdout: String::new(),
-                stderr: error.to_string(),
-                success: false,
-            },
+        () = cancellation_token.cancelled() => return Ok(CommandResult::Cancelled),
+        result = run => result,
+    };
+
+    Ok(match result {
+        Ok(Ok((stdout, stderr, status))) => {
+            parse_command_output(&stdout, &stderr, status.success())
+        }
+        Ok(Err(description)) => CommandResult::Background(BackgroundProcess {
+            description,
+            child,
+            stdout,
+            stderr,
         }),
+        Err(error) => CommandResult::RawOutput {
+            stdout: String::new(),
+            stderr: error.to_string(),
+            success: false,
+        },
+    })
+}
+
+/// Return the description of an [`Outcome::Handle`] printed on a line of its
+/// own.
+fn parse_handle(line: &[u8]) -> Option<String> {
+    match serde_json::from_slice::<Outcome>(line.trim_ascii()) {
+        Ok(Outcome::Handle { description }) => Some(description),
+        _ => None,
     }
 }
 
-/// Drain a child pipe into a byte buffer.
-async fn read_all(mut pipe: impl tokio::io::AsyncRead + Unpin) -> std::io::Result<Vec<u8>> {
-    let mut buf = Vec::new();
-    pipe.read_to_end(&mut buf).await?;
-    Ok(buf)
+/// Render the program and arguments of a tool command.
+///
+/// A shell command is returned as an invocation of `sh -c`.
+fn render_command(command: CommandConfig, ctx: &Value) -> Result<(String, Vec<String>), ToolError> {
+    let CommandConfig {
+        program,
+        args,
+        shell,
+    } = command;
+
+    let mut env = Environment::new();
+    env.set_formatter(format_tool_template_value);
+    let tmpl = Arc::new(env);
+
+    let program = tmpl
+        .render_str(&program, ctx)
+        .map_err(|error| ToolError::TemplateError {
+            data: program.clone(),
+            error,
+        })?;
+
+    let args = args
+        .iter()
+        .map(|s| tmpl.render_str(s, ctx))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| ToolError::TemplateError {
+            data: args.join(" "),
+            error,
+        })?;
+
+    if shell {
+        // `program` is shell syntax and used verbatim; `args` are shell-quoted
+        // so multi-word arguments keep their boundaries.
+        let shell_cmd = shell_command_line(&program, &args);
+        return Ok(("sh".to_owned(), vec!["-c".to_owned(), shell_cmd]));
+    }
+
+    Ok((program, args))
 }
 
 /// Drain a child's stderr into a byte buffer, optionally forwarding each line
@@ -525,7 +571,7 @@ async fn read_all(mut pipe: impl tokio::io::AsyncRead + Unpin) -> std::io::Resul
 /// forwarder.
 async fn forward_stderr(
     pipe: impl tokio::io::AsyncRead + Unpin,
-    trace_as: Option<ToolTrace<'_>>,
+    trace_as: Option<(String, String)>,
 ) -> std::io::Result<Vec<u8>> {
     let mut reader = BufReader::new(pipe);
     let mut all = Vec::new();
@@ -537,11 +583,11 @@ async fn forward_stderr(
             break;
         }
 
-        if let Some(ToolTrace { id, name }) = trace_as {
+        if let Some((id, name)) = &trace_as {
             let text = String::from_utf8_lossy(&line);
             let trimmed = text.trim_end_matches(['\n', '\r']);
             if !trimmed.is_empty() {
-                trace!(target: "tool::stderr", tool_id = id, tool_name = name, "{trimmed}");
+                trace!(target: "tool::stderr", tool_id = id.as_str(), tool_name = name.as_str(), "{trimmed}");
             }
         }
 
@@ -572,6 +618,11 @@ fn parse_command_output(stdout: &[u8], stderr: &[u8], success: bool) -> CommandR
             }
         }
         Ok(Outcome::NeedsInput { question }) => CommandResult::NeedsInput(question),
+        Ok(Outcome::Handle { .. }) => CommandResult::FatalError(
+            "The tool reported a background handle, but not as the first line of its output. \
+             Tools running in a pseudo-terminal cannot continue in the background."
+                .to_owned(),
+        ),
         Err(_) => CommandResult::RawOutput {
             stdout: stdout_str.into_owned(),
             stderr: String::from_utf8_lossy(stderr).into_owned(),
@@ -589,6 +640,23 @@ fn parse_command_output(stdout: &[u8], stderr: &[u8], success: bool) -> CommandR
 pub struct InvocationContext {
     pub workspace_id: String,
     pub conversation_id: String,
+
+    /// The user's terminal, lent to tools that run in a pseudo-terminal.
+    ///
+    /// `None` when there is no interactive terminal.
+    pub terminal: Option<Arc<dyn pty::ToolTerminal>>,
+
+    /// The tools that continue in the background.
+    pub background: BackgroundTasks,
+
+    /// Where local tools record the files they change, so the turn can be
+    /// undone.
+    ///
+    /// `None` when the changes are not recorded.
+    pub checkpoint: Option<jp_tool::Checkpoint>,
+
+    /// The environment local tools run with.
+    pub env: Env,
 }
 
 /// The definition of a tool.
@@ -770,6 +838,7 @@ impl ToolDefinition {
                 "access": access,
                 "workspace_id": &invocation.workspace_id,
                 "conversation_id": &invocation.conversation_id,
+                "checkpoint": &invocation.checkpoint,
             },
         });
 
@@ -777,9 +846,31 @@ impl ToolDefinition {
             return Err(ToolError::MissingCommand);
         };
 
-        let trace_as = ToolTrace { id: &id, name };
+        let result = if config.pty() {
+            let terminal = invocation.terminal.clone();
+            pty::run_tool_command(
+                command,
+                ctx,
+                root,
+                &invocation.env,
+                cancellation_token,
+                terminal,
+            )
+            .await?
+        } else {
+            let trace_as = ToolTrace { id: &id, name };
+            run_tool_command(
+                command,
+                ctx,
+                root,
+                &invocation.env,
+                cancellation_token,
+                Some(trace_as),
+            )
+            .await?
+        };
 
-        match run_tool_command(command, ctx, root, cancellation_token, Some(trace_as)).await? {
+        match result {
             CommandResult::Success(content) => Ok(ExecutionOutcome::Completed {
                 id,
                 result: Ok(content),
@@ -788,6 +879,17 @@ impl ToolDefinition {
                 Ok(ExecutionOutcome::NeedsInput { id, question })
             }
             CommandResult::Cancelled => Ok(ExecutionOutcome::Cancelled { id }),
+            CommandResult::Background(process) => {
+                let result = Ok(format!(
+                    "The tool continues in the background: {}\n\nHandle: `{id}`\n\nCall \
+                     `await_handles` to wait for its result, `poll_handle` to see its output so \
+                     far, or `cancel_handle` to stop it.",
+                    process.description
+                ));
+
+                invocation.background.start(id.clone(), name, process);
+                Ok(ExecutionOutcome::Completed { id, result })
+            }
             other => Ok(ExecutionOutcome::Completed {
                 id,
                 result: other.into_tool_result(name),
@@ -886,6 +988,10 @@ impl ToolDefinition {
             jp_tool::Outcome::NeedsInput { question } => {
                 ExecutionOutcome::NeedsInput { id, question }
             }
+            jp_tool::Outcome::Handle { .. } => ExecutionOutcome::Completed {
+                id,
+                result: Err("Builtin tools cannot continue in the background.".to_owned()),
+            },
         })
     }
 
@@ -0,0 +1,460 @@
+//! Tool calls that continue in the background.
+//!
+//! A local tool can print [`Outcome::Handle`] as the first line of its stdout
+//! to return early, while it keeps running.
+//! The tool call then completes with a *handle*, named after the ID of the tool
+//! call, which the assistant passes to the `await_handles`, `poll_handle` and
+//! `cancel_handle` builtins (see [`super::builtin::background`]).
+//!
+//! The state of each handle is recorded in the metadata of the tool call
+//! response that returned it (see [`BACKGROUND_HANDLE_KEY`]).
+//! Background tools never outlive the query that started them:
+//! [`BackgroundTasks::shutdown`] stops the ones still running, and handles that
+//! an earlier query left running are marked as [lost](HandleStatus::Lost).
+//!
+//! [`Outcome::Handle`]: jp_tool::Outcome::Handle
+
+use std::{
+    fmt, io,
+    process::ExitStatus,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use indexmap::{IndexMap, IndexSet};
+use jp_conversation::{ConversationStream, event::BACKGROUND_HANDLE_KEY};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncReadExt as _, BufReader},
+    process::{Child, ChildStdout},
+    sync::watch,
+    task::JoinHandle,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use super::{CommandResult, parse_command_output};
+
+/// How much of a background tool's stdout is shown while it runs, from the end.
+///
+/// A tool can run for as long as the query does, so the output reported by
+/// [`BackgroundTasks::poll`] is capped; older output is dropped first.
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
+/// How much of a background tool's stdout is kept to parse its outcome from,
+/// from the end.
+///
+/// Larger than [`MAX_OUTPUT_BYTES`], so that a tool can report an outcome that
+/// does not fit the preview, but still bounded, so that a chatty tool can't
+/// grow the memory of the query without limit.
+/// Output beyond it is dropped, and the result reports that it was.
+const MAX_RESULT_BYTES: usize = 8 * 1024 * 1024;
+
+/// Put in front of a result whose earlier output was dropped.
+const TRUNCATED_MARKER: &str = "[earlier output truncated]\n";
+
+/// The state of a background handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandleStatus {
+    /// The tool is still running.
+    Running,
+
+    /// The tool exited, and reported a result.
+    Completed,
+
+    /// The tool exited, and reported an error.
+    Failed,
+
+    /// The tool was cancelled before it exited.
+    Cancelled,
+
+    /// JP exited while the tool was running.
+    Lost,
+}
+
+impl HandleStatus {
+    /// Whether the tool is still running.
+    #[must_use]
+    pub const fn is_running(self) -> bool {
+        matches!(self, Self::Running)
+    }
+}
+
+impl fmt::Display for HandleStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+            Self::Lost => "lost",
+        })
+    }
+}
+
+/// The persisted state of a background handle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandleRecord {
+    /// The name of the tool that runs in the background.
+    pub tool: String,
+
+    /// What the tool is doing, as reported by the tool.
+    pub description: String,
+
+    /// The state of the handle.
+    pub status: HandleStatus,
+}
+
+/// A snapshot of a background handle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandleReport {
+    /// The handle, which is the ID of the tool call that started it.
+    pub id: String,
+
+    /// The state of the handle.
+    pub record: HandleRecord,
+
+    /// The stdout of the tool so far, up to the last [`MAX_OUTPUT_BYTES`].
+    pub output: String,
+
+    /// The result of the tool, once it has exited.
+    pub result: Option<Result<String, String>>,
+}
+
+/// A tool process that continues in the background.
+///
+/// Returned by [`super::run_tool_command`] when the tool reports a handle, and
+/// started with [`BackgroundTasks::start`].
+/// The process is killed if this is dropped before it is started.
+#[derive(Debug)]
+pub struct BackgroundProcess {
+    /// What the tool is doing, as reported by the tool.
+    pub description: String,
+
+    pub(super) child: Child,
+
+    /// The rest of the tool's stdout, after the handle.
+    pub(super) stdout: BufReader<ChildStdout>,
+
+    /// The task draining the tool's stderr.
+    pub(super) stderr: JoinHandle<io::Result<Vec<u8>>>,
+}
+
+/// The background tool calls of a query.
+///
+/// Cloning is cheap; clones share the same handles.
+#[derive(Debug, Clone, Default)]
+pub struct BackgroundTasks {
+    inner: Arc<Mutex<Registry>>,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    handles: IndexMap<String, Entry>,
+
+    /// Handles whose state changed since they were last persisted.
+    changed: IndexSet<String>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    record: HandleRecord,
+    output: Vec<u8>,
+    result: Option<Result<String, String>>,
+    cancel: CancellationToken,
+
+    /// Becomes `true` once the tool is no longer running.
+    done: watch::Receiver<bool>,
+}
+
+impl Entry {
+    fn report(&self, id: &str) -> HandleReport {
+        HandleReport {
+            id: id.to_owned(),
+            record: self.record.clone(),
+            output: String::from_utf8_lossy(&self.output).into_owned(),
+            result: self.result.clone(),
+        }
+    }
+}
+
+impl BackgroundTasks {
+    /// Track a tool process under the handle `id`, until it exits.
+    pub fn start(
+        &self,
+        id: impl Into<String>,
+        tool: impl Into<String>,
+        process: BackgroundProcess,
+    ) {
+        let id = id.into();
+        let tool = tool.into();
+        let cancel = CancellationToken::new();
+        let (done_tx, done) = watch::channel(false);
+
+        debug!(
+            id,
+            tool,
+            description = process.description,
+            "Tool continues in the background."
+        );
+
+        self.with_registry(|registry| {
+            registry.handles.insert(id.clone(), Entry {
+                record: HandleRecord {
+                    tool: tool.clone(),
+                    description: process.description.clone(),
+                    status: HandleStatus::Running,
+                },
+                output: Vec::new(),
+                result: None,
+                cancel: cancel.clone(),
+                done,
+            });
+            registry.changed.insert(id.clone());
+        });
+
+        tokio::spawn(supervise(self.clone(), id, tool, process, cancel, done_tx));
+    }
+
+    /// Return the current state of a handle.
+    #[must_use]
+    pub fn poll(&self, id: &str) -> Option<HandleReport> {
+        self.with_registry(|registry| registry.handles.get(id).map(|entry| entry.report(id)))
+    }
+
+    /// Wait for a handle's tool to exit, and return its final state.
+    pub async fn wait(&self, id: &str) -> Option<HandleReport> {
+        let mut done = self
+            .with_registry(|registry| registry.handles.get(id).map(|entry| entry.done.clone()))?;
+
+        // An error means the supervisor is gone, which also means it is done.
+        drop(done.wait_for(|done| *done).await);
+        self.poll(id)
+    }
+
+    /// Cancel a handle's tool, and return its final state.
+    pub async fn cancel(&self, id: &str) -> Option<HandleReport> {
+        self.with_registry(|registry| registry.handles.get(id).map(|entry| entry.cancel.cancel()))?;
+        self.wait(id).await
+    }
+
+    /// The handles whose tools are still running.
+    #[must_use]
+    pub fn running(&self) -> Vec<String> {
+        self.with_registry(|registry| {
+            registry
+                .handles
+                .iter()
+                .filter(|(_, entry)| entry.record.status.is_running())
+                .map(|(id, _)| id.clone())
+                .collect()
+        })
+    }
+
+    /// Cancel every tool that is still running, and wait for them to exit.
+    pub async fn shutdown(&self) {
+        for id in self.running() {
+            debug!(id, "Stopping background tool.");
+            drop(self.cancel(&id).await);
+        }
+    }
+
+    /// Record the state of changed handles in the stream.
+    ///
+    /// The state is stored in the metadata of the tool call response that
+    /// returned the handle.
+    /// Handles whose response is not in the stream yet stay pending, and are
+    /// recorded by a later call.
+    pub fn persist(&self, stream: &mut ConversationStream) {
+        self.with_registry(|registry| {
+            if registry.changed.is_empty() {
+                return;
+            }
+
+            for event in stream.iter_mut() {
+                let Some(id) = event.event.as_tool_call_response().map(|r| r.id.clone()) else {
+                    continue;
+                };
+
+                if !registry.changed.shift_remove(&id) {
+                    continue;
+                }
+
+                let Some(entry) = registry.handles.get(&id) else {
+                    continue;
+                };
+
+                match serde_json::to_value(&entry.record) {
+                    Ok(value) => event.event.add_metadata_field(BACKGROUND_HANDLE_KEY, value),
+                    Err(error) => warn!(id, %error, "Failed to serialize background handle."),
+                }
+            }
+        });
+    }
+
+    /// Load the handles recorded in the stream.
+    ///
+    /// Handles recorded as running belong to an earlier query, whose tools
+    /// stopped when it exited. They are marked as lost, in the stream as well.
+    pub fn restore(&self, stream: &mut ConversationStream) {
+        self.with_registry(|registry| {
+            for event in stream.iter_mut() {
+                let Some(id) = event.event.as_tool_call_response().map(|r| r.id.clone()) else {
+                    continue;
+                };
+
+                if registry.handles.contains_key(&id) {
+                    continue;
+                }
+
+                let Some(mut record) = event
+                    .event
+                    .metadata
+                    .get(BACKGROUND_HANDLE_KEY)
+                    .and_then(|value| serde_json::from_value::<HandleRecord>(value.clone()).ok())
+                else {
+                    continue;
+                };
+
+                if record.status.is_running() {
+                    record.status = HandleStatus::Lost;
+                    if let Ok(value) = serde_json::to_value(&record) {
+                        event.event.add_metadata_field(BACKGROUND_HANDLE_KEY, value);
+                    }
+                }
+
+                registry.handles.insert(id, Entry {
+                    record,
+                    output: Vec::new(),
+                    result: None,
+                    cancel: CancellationToken::new(),
+                    done: watch::channel(true).1,
+                });
+            }
+        });
+    }
+
+    fn with_registry<R>(&self, f: impl FnOnce(&mut Registry) -> R) -> R {
+        f(&mut self.inner.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn append_output(&self, id: &str, bytes: &[u8]) {
+        self.with_registry(|registry| {
+            if let Some(entry) = registry.handles.get_mut(id) {
+                entry.output.extend_from_slice(bytes);
+                keep_tail(&mut entry.output, MAX_OUTPUT_BYTES);
+            }
+        });
+    }
+
+    fn finish(&self, id: &str, status: HandleStatus, result: Option<Result<String, String>>) {
+        self.with_registry(|registry| {
+            if let Some(entry) = registry.handles.get_mut(id) {
+                entry.record.status = status;
+                entry.result = result;
+                registry.changed.insert(id.to_owned());
+            }
+        });
+    }
+}
+
+/// Drop the start of `bytes` until at most `max` bytes are left, returning
+/// whether anything was dropped.
+///
+/// Only whole characters are dropped, so valid UTF-8 stays valid.
+fn keep_tail(bytes: &mut Vec<u8>, max: usize) -> bool {
+    let mut excess = bytes.len().saturating_sub(max);
+    if excess == 0 {
+        return false;
+    }
+
+    while bytes
+        .get(excess)
+        .is_some_and(|byte| byte & 0b1100_0000 == 0b1000_0000)
+    {
+        excess += 1;
+    }
+    bytes.drain(..excess);
+    true
+}
+
+/// Collect a background tool's output until it exits or is cancelled.
+async fn supervise(
+    tasks: BackgroundTasks,
+    id: String,
+    tool: String,
+    mut process: BackgroundProcess,
+    cancel: CancellationToken,
+    done: watch::Sender<bool>,
+) {
+    let run = async {
+        let mut stdout = vec![];
+        let mut truncated = false;
+        let mut buf = vec![0; 8192];
+        loop {
+            let n = process.stdout.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+
+            stdout.extend_from_slice(&buf[..n]);
+            tasks.append_output(&id, &buf[..n]);
+
+            // Trimmed in batches, so the kept output isn't moved on every read.
+            if stdout.len() > 2 * MAX_RESULT_BYTES {
+                truncated |= keep_tail(&mut stdout, MAX_RESULT_BYTES);
+            }
+        }
+
+        truncated |= keep_tail(&mut stdout, MAX_RESULT_BYTES);
+        if truncated {
+            stdout.splice(..0, TRUNCATED_MARKER.bytes());
+        }
+
+        let status = process.child.wait().await?;
+        let stderr = (&mut process.stderr).await.map_err(io::Error::other)??;
+        Ok::<(ExitStatus, Vec<u8>, Vec<u8>), io::Error>((status, stdout, stderr))
+    };
+
+    let exited = tokio::select! {
+        biased;
+        () = cancel.cancelled() => None,
+        result = run => Some(result),
+    };
+
+    let (status, result) = match exited {
+        None => {
+            if let Err(error) = process.child.kill().await {
+                warn!(id, %error, "Failed to kill background tool.");
+            }
+            (HandleStatus::Cancelled, None)
+        }
+        Some(Err(error)) => (HandleStatus::Failed, Some(Err(error.to_string()))),
+        Some(Ok((exit, stdout, stderr))) => {
+            let result = match parse_command_output(&stdout, &stderr, exit.success()) {
+                CommandResult::NeedsInput(question) => Err(format!(
+                    "The tool asked a question while running in the background, which cannot be \
+                     answered: {}",
+                    question.text
+                )),
+                result => result.into_tool_result(&tool),
+            };
+
+            let status = if result.is_ok() {
+                HandleStatus::Completed
+            } else {
+                HandleStatus::Failed
+            };
+
+            (status, Some(result))
+        }
+    };
+
+    debug!(id, %status, "Background tool finished.");
+    tasks.finish(&id, status, result);
+    drop(done.send(true));
+}
+
+#[cfg(test)]
+#[path = "background_tests.rs"]
+mod tests;
@@ -0,0 +1,287 @@
+use jp_config::{conversation::tool::CommandConfig, env::Env};
+use jp_conversation::{
+    ConversationStream,
+    event::{ChatRequest, ToolCallRequest, ToolCallResponse},
+};
+use serde_json::{Map, json};
+use tokio_util::sync::CancellationToken;
+
+use super::*;
+use crate::tool::run_tool_command;
+
+const HANDLE: &str = r#"echo '{"type":"handle","description":"counting"}'"#;
+
+async fn spawn(script: &str) -> BackgroundProcess {
+    let command = CommandConfig {
+        program: format!("{HANDLE}; {script}"),
+        args: vec![],
+        shell: true,
+    };
+
+    let result = run_tool_command(
+        command,
+        json!({}),
+        "/tmp".into(),
+        &Env::process(),
+        CancellationToken::new(),
+        None,
+    )
+    .await
+    .unwrap();
+
+    match result {
+        CommandResult::Background(process) => process,
+        other => panic!("Expected Background, got: {other:?}"),
+    }
+}
+
+fn stream_with_response(id: &str) -> ConversationStream {
+    let mut stream = ConversationStream::new_test();
+    stream.start_turn(ChatRequest::from("test"));
+    stream
+        .current_turn_mut()
+        .add_tool_call_request(ToolCallRequest::new(
+            id.to_owned(),
+            "counter".to_owned(),
+            Map::new(),
+        ))
+        .add_tool_call_response(ToolCallResponse {
+            id: id.to_owned(),
+            result: Ok("handle".to_owned()),
+        })
+        .build()
+        .unwrap();
+
+    stream
+}
+
+fn persisted(stream: &ConversationStream, id: &str) -> Option<HandleRecord> {
+    stream.iter().find_map(|event| {
+        event
+            .event
+            .as_tool_call_response()
+            .filter(|response| response.id == id)?;
+
+        let value = event.event.metadata.get(BACKGROUND_HANDLE_KEY)?;
+        serde_json::from_value(value.clone()).ok()
+    })
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_completed_handle_reports_remaining_output() {
+    let tasks = BackgroundTasks::default();
+    let process = spawn("echo one; echo two").await;
+    assert_eq!(process.description, "counting");
+
+    tasks.start("call_1", "counter", process);
+    let report = tasks.wait("call_1").await.unwrap();
+
+    assert_eq!(report.record, HandleRecord {
+        tool: "counter".to_owned(),
+        description: "counting".to_owned(),
+        status: HandleStatus::Completed,
+    });
+    assert_eq!(report.output, "one\ntwo\n");
+    assert_eq!(report.result, Some(Ok("one\ntwo\n".to_owned())));
+    assert!(tasks.running().is_empty());
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_output_keeps_the_most_recent_bytes() {
+    let tasks = BackgroundTasks::default();
+    let process = spawn(&format!(
+        "head -c {} /dev/zero | tr '\\0' x; echo; echo last",
+        MAX_OUTPUT_BYTES * 2
+    ))
+    .await;
+
+    tasks.start("call_1", "counter", process);
+    let report = tasks.wait("call_1").await.unwrap();
+
+    assert_eq!(report.output.len(), MAX_OUTPUT_BYTES);
+    assert!(report.output.ends_with("x\nlast\n"));
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_final_outcome_is_parsed() {
+    let tasks = BackgroundTasks::default();
+    let process = spawn(r#"echo '{"type":"success","content":"all good"}'"#).await;
+
+    tasks.start("call_1", "counter", process);
+    let report = tasks.wait("call_1").await.unwrap();
+
+    assert_eq!(report.record.status, HandleStatus::Completed);
+    assert_eq!(report.result, Some(Ok("all good".to_owned())));
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_outcome_is_parsed_from_output_above_the_cap() {
+    // Two-byte characters, with an odd number of bytes around them, so that
+    // capping the output by bytes alone would split one.
+    let count = MAX_OUTPUT_BYTES / 2 + 1000;
+    let tasks = BackgroundTasks::default();
+    let process = spawn(&format!(
+        r#"printf '{{"type":"success","content":"'; yes é | head -n {count} | tr -d '\n'; printf 'a"}}'"#
+    ))
+    .await;
+
+    tasks.start("call_1", "counter", process);
+    let report = tasks.wait("call_1").await.unwrap();
+
+    assert!(report.output.len() <= MAX_OUTPUT_BYTES);
+    assert!(report.output.starts_with('é'));
+    assert!(!report.output.contains(char::REPLACEMENT_CHARACTER));
+    assert_eq!(report.record.status, HandleStatus::Completed);
+    assert_eq!(report.result, Some(Ok(format!("{}a", "é".repeat(count)))));
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_result_keeps_the_end_of_output_above_the_result_cap() {
+    let count = MAX_RESULT_BYTES * 2 + 1000;
+    let tasks = BackgroundTasks::default();
+    let process = spawn(&format!(
+        "head -c {count} /dev/zero | tr '\\0' x; printf end"
+    ))
+    .await;
+
+    tasks.start("call_1", "chatty", process);
+    let report = tasks.wait("call_1").await.unwrap();
+    let result = report.result.unwrap().unwrap();
+
+    assert_eq!(report.record.status, HandleStatus::Completed);
+    assert!(result.starts_with(TRUNCATED_MARKER));
+    assert!(result.ends_with("xxend"));
+    assert_eq!(result.len(), TRUNCATED_MARKER.len() + MAX_RESULT_BYTES);
+}
+
+#[test]
+fn test_keep_tail_drops_whole_characters() {
+    let mut bytes = "aéé".as_bytes().to_vec();
+    assert!(!keep_tail(&mut bytes, 5));
+    assert!(keep_tail(&mut bytes, 3));
+    assert_eq!(bytes, "é".as_bytes());
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_failing_tool_fails_the_handle() {
+    let tasks = BackgroundTasks::default();
+    let process = spawn("echo broken >&2; exit 3").await;
+
+    tasks.start("call_1", "counter", process);
+    let report = tasks.wait("call_1").await.unwrap();
+
+    assert_eq!(report.record.status, HandleStatus::Failed);
+    let error = report.result.unwrap().unwrap_err();
+    assert!(error.contains("broken"), "{error}");
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_poll_and_cancel_running_handle() {
+    let tasks = BackgroundTasks::default();
+    let process = spawn("echo started; sleep 30").await;
+
+    tasks.start("call_1", "counter", process);
+    assert_eq!(tasks.running(), vec!["call_1".to_owned()]);
+
+    // Wait for the first line of output to be collected.
+    let mut report = tasks.poll("call_1").unwrap();
+    while report.output.is_empty() {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        report = tasks.poll("call_1").unwrap();
+    }
+
+    assert_eq!(report.record.status, HandleStatus::Running);
+    assert_eq!(report.output, "started\n");
+    assert_eq!(report.result, None);
+
+    let report = tasks.cancel("call_1").await.unwrap();
+    assert_eq!(report.record.status, HandleStatus::Cancelled);
+    assert!(tasks.running().is_empty());
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_shutdown_cancels_running_handles() {
+    let tasks = BackgroundTasks::default();
+    tasks.start("call_1", "counter", spawn("sleep 30").await);
+    tasks.start("call_2", "counter", spawn("true").await);
+
+    tasks.shutdown().await;
+
+    assert_eq!(
+        tasks.poll("call_1").unwrap().record.status,
+        HandleStatus::Cancelled
+    );
+    assert!(tasks.running().is_empty());
+}
+
+#[tokio::test]
+async fn test_unknown_handle() {
+    let tasks = BackgroundTasks::default();
+
+    assert_eq!(tasks.poll("nope"), None);
+    assert_eq!(tasks.wait("nope").await, None);
+    assert_eq!(tasks.cancel("nope").await, None);
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_persist_records_state_on_response() {
+    let tasks = BackgroundTasks::default();
+    tasks.start("call_1", "counter", spawn("true").await);
+
+    // The response is not in the stream yet, so the change stays pending.
+    let mut stream = ConversationStream::new_test();
+    tasks.persist(&mut stream);
+
+    let mut stream = stream_with_response("call_1");
+    tasks.persist(&mut stream);
+    assert_eq!(
+        persisted(&stream, "call_1").map(|record| record.status),
+        Some(HandleStatus::Running)
+    );
+
+    tasks.wait("call_1").await;
+    tasks.persist(&mut stream);
+    assert_eq!(
+        persisted(&stream, "call_1"),
+        Some(HandleRecord {
+            tool: "counter".to_owned(),
+            description: "counting".to_owned(),
+            status: HandleStatus::Completed,
+        })
+    );
+}
+
+#[test]
+fn test_restore_marks_running_handles_as_lost() {
+    let mut stream = stream_with_response("call_1");
+    stream.iter_mut().for_each(|event| {
+        if event.event.is_tool_call_response() {
+            event.event.add_metadata_field(
+                BACKGROUND_HANDLE_KEY,
+                json!({ "tool": "counter", "description": "counting", "status": "running" }),
+            );
+        }
+    });
+
+    let tasks = BackgroundTasks::default();
+    tasks.restore(&mut stream);
+
+    assert_eq!(
+        persisted(&stream, "call_1").map(|record| record.status),
+        Some(HandleStatus::Lost)
+    );
+    assert_eq!(
+        tasks.poll("call_1").unwrap().record.status,
+        HandleStatus::Lost
+    );
+    assert!(tasks.running().is_empty());
+}
@@ -2,7 +2,9 @@
 //!
 //! Maps tool names to their Rust implementations.
 
+pub mod background;
 pub mod describe_tools;
+pub mod sub_agent;
 
 use std::{collections::HashMap, sync::Arc};
 
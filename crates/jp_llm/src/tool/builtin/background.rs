@@ -0,0 +1,209 @@
+//! The `await_handles`, `poll_handle` and `cancel_handle` builtin
+//! implementations.
+//!
+//! These let the assistant synchronize on tools that continue in the
+//! background (see [`crate::tool::background`]).
+
+use std::{fmt::Write as _, time::Duration};
+
+use async_trait::async_trait;
+use indexmap::IndexMap;
+use jp_tool::Outcome;
+use serde_json::Value;
+use tokio::time::{Instant, timeout_at};
+
+use crate::tool::{
+    BuiltinTool,
+    background::{BackgroundTasks, HandleReport, HandleStatus},
+};
+
+/// How long `await_handles` waits, unless the assistant asks otherwise.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How much of a running tool's output is shown, from the end.
+const OUTPUT_TAIL: usize = 4000;
+
+/// Wait for background tools to exit.
+pub struct AwaitHandles {
+    tasks: BackgroundTasks,
+}
+
+impl AwaitHandles {
+    #[must_use]
+    pub fn new(tasks: BackgroundTasks) -> Self {
+        Self { tasks }
+    }
+}
+
+#[async_trait]
+impl BuiltinTool for AwaitHandles {
+    async fn execute(&self, arguments: &Value, _answers: &IndexMap<String, Value>) -> Outcome {
+        let handles = match arguments.get("handles").and_then(Value::as_array) {
+            Some(handles) => handles
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_owned)
+                .collect::<Vec<_>>(),
+            None => vec![],
+        };
+
+        let handles = if handles.is_empty() {
+            self.tasks.running()
+        } else {
+            handles
+        };
+
+        if handles.is_empty() {
+            return "No tools are running in the background.".into();
+        }
+
+        let timeout = arguments
+            .get("timeout_secs")
+            .and_then(Value::as_u64)
+            .map_or(DEFAULT_TIMEOUT, Duration::from_secs);
+        let deadline = Instant::now() + timeout;
+
+        let mut reports = vec![];
+        for id in &handles {
+            // Past the deadline, report the handles that are still running.
+            let report = timeout_at(deadline, self.tasks.wait(id))
+                .await
+                .unwrap_or_else(|_| self.tasks.poll(id));
+
+            match report {
+                Some(report) => reports.push(report),
+                None => return unknown_handle(id),
+            }
+        }
+
+        let content = reports
+            .iter()
+            .map(format_report)
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        content.into()
+    }
+}
+
+/// Show the state and output of a background tool, without waiting.
+pub struct PollHandle {
+    tasks: BackgroundTasks,
+}
+
+impl PollHandle {
+    #[must_use]
+    pub fn new(tasks: BackgroundTasks) -> Self {
+        Self { tasks }
+    }
+}
+
+#[async_trait]
+impl BuiltinTool for PollHandle {
+    async fn execute(&self, arguments: &Value, _answers: &IndexMap<String, Value>) -> Outcome {
+        let Some(id) = arguments.get("handle").and_then(Value::as_str) else {
+            return missing_handle();
+        };
+
+        match self.tasks.poll(id) {
+            Some(report) => format_report(&report).into(),
+            None => unknown_handle(id),
+        }
+    }
+}
+
+/// Stop a background tool.
+pub struct CancelHandle {
+    tasks: BackgroundTasks,
+}
+
+impl CancelHandle {
+    #[must_use]
+    pub fn new(tasks: BackgroundTasks) -> Self {
+        Self { tasks }
+    }
+}
+
+#[async_trait]
+impl BuiltinTool for CancelHandle {
+    async fn execute(&self, arguments: &Value, _answers: &IndexMap<String, Value>) -> Outcome {
+        let Some(id) = arguments.get("handle").and_then(Value::as_str) else {
+            return missing_handle();
+        };
+
+        match self.tasks.cancel(id).await {
+            Some(report) => format_report(&report).into(),
+            None => unknown_handle(id),
+        }
+    }
+}
+
+fn missing_handle() -> Outcome {
+    Outcome::Error {
+        message: "Missing or invalid `handle` parameter.".to_owned(),
+        trace: vec![],
+        transient: false,
+    }
+}
+
+fn unknown_handle(id: &str) -> Outcome {
+    Outcome::Error {
+        message: format!("Unknown handle `{id}`."),
+        trace: vec![],
+        transient: false,
+    }
+}
+
+/// Format a handle's state for the assistant.
+fn format_report(report: &HandleReport) -> String {
+    let HandleReport {
+        id,
+        record,
+        output,
+        result,
+    } = report;
+
+    let mut out = format!(
+        "## `{id}` ({status})\n\nTool `{tool}`: {description}\n",
+        status = record.status,
+        tool = record.tool,
+        description = record.description,
+    );
+
+    match (record.status, result) {
+        (_, Some(Ok(content))) => {
+            let _ = write!(out, "\n### Result\n\n{content}\n");
+        }
+        (_, Some(Err(error))) => {
+            let _ = write!(out, "\n### Error\n\n{error}\n");
+        }
+        (HandleStatus::Running | HandleStatus::Cancelled, None) => {
+            let tail = tail(output, OUTPUT_TAIL);
+            if tail.is_empty() {
+                out.push_str("\nNo output yet.\n");
+            } else {
+                let _ = write!(out, "\n### Output\n\n{tail}\n");
+            }
+        }
+        (HandleStatus::Lost, None) => {
+            out.push_str("\nThe tool stopped when an earlier session of JP exited.\n");
+        }
+        (HandleStatus::Completed | HandleStatus::Failed, None) => {}
+    }
+
+    out
+}
+
+/// Return the last `max` bytes of `text`, at a character boundary.
+fn tail(text: &str, max: usize) -> &str {
+    let mut start = text.len().saturating_sub(max);
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+
+    &text[start..]
+}
+
+#[cfg(test)]
+#[path = "background_tests.rs"]
+mod tests;
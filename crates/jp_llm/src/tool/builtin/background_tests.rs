@@ -0,0 +1,174 @@
+use indexmap::IndexMap;
+use jp_config::{conversation::tool::CommandConfig, env::Env};
+use jp_tool::Outcome;
+use serde_json::{Value, json};
+use tokio_util::sync::CancellationToken;
+
+use super::*;
+use crate::tool::{CommandResult, background::HandleRecord, run_tool_command};
+
+fn no_answers() -> IndexMap<String, Value> {
+    IndexMap::new()
+}
+
+fn report(status: HandleStatus, result: Option<Result<&str, &str>>) -> HandleReport {
+    HandleReport {
+        id: "call_1".to_owned(),
+        record: HandleRecord {
+            tool: "cargo_test".to_owned(),
+            description: "Running the test suite".to_owned(),
+            status,
+        },
+        output: "running 3 tests\n".to_owned(),
+        result: result.map(|result| result.map(str::to_owned).map_err(str::to_owned)),
+    }
+}
+
+async fn start(tasks: &BackgroundTasks, id: &str, script: &str) {
+    let command = CommandConfig {
+        program: format!(r#"echo '{{"type":"handle","description":"working"}}'; {script}"#),
+        args: vec![],
+        shell: true,
+    };
+
+    let result = run_tool_command(
+        command,
+        json!({}),
+        "/tmp".into(),
+        &Env::process(),
+        CancellationToken::new(),
+        None,
+    )
+    .await
+    .unwrap();
+
+    match result {
+        CommandResult::Background(process) => tasks.start(id, "worker", process),
+        other => panic!("Expected Background, got: {other:?}"),
+    }
+}
+
+#[test]
+fn test_format_running_report() {
+    let out = format_report(&report(HandleStatus::Running, None));
+    assert_eq!(
+        out,
+        "## `call_1` (running)\n\nTool `cargo_test`: Running the test suite\n\n### \
+         Output\n\nrunning 3 tests\n\n"
+    );
+}
+
+#[test]
+fn test_format_completed_report() {
+    let out = format_report(&report(HandleStatus::Completed, Some(Ok("3 passed"))));
+    assert_eq!(
+        out,
+        "## `call_1` (completed)\n\nTool `cargo_test`: Running the test suite\n\n### Result\n\n3 \
+         passed\n"
+    );
+}
+
+#[test]
+fn test_format_failed_report() {
+    let out = format_report(&report(HandleStatus::Failed, Some(Err("1 failed"))));
+    assert!(out.ends_with("### Error\n\n1 failed\n"), "{out}");
+}
+
+#[test]
+fn test_format_lost_report() {
+    let out = format_report(&report(HandleStatus::Lost, None));
+    assert!(out.ends_with("earlier session of JP exited.\n"), "{out}");
+}
+
+#[test]
+fn test_tail_respects_char_boundaries() {
+    assert_eq!(tail("hello", 10), "hello");
+    assert_eq!(tail("hello", 3), "llo");
+    assert_eq!(tail("héllo", 4), "llo");
+}
+
+#[tokio::test]
+async fn test_await_without_handles() {
+    let tool = AwaitHandles::new(BackgroundTasks::default());
+    let outcome = tool.execute(&json!({}), &no_answers()).await;
+
+    assert_eq!(
+        outcome.unwrap_content(),
+        "No tools are running in the background."
+    );
+}
+
+#[tokio::test]
+async fn test_unknown_handle_is_an_error() {
+    let tasks = BackgroundTasks::default();
+    let args = json!({ "handle": "nope" });
+
+    for outcome in [
+        PollHandle::new(tasks.clone())
+            .execute(&args, &no_answers())
+            .await,
+        CancelHandle::new(tasks.clone())
+            .execute(&args, &no_answers())
+            .await,
+        AwaitHandles::new(tasks)
+            .execute(&json!({ "handles": ["nope"] }), &no_answers())
+            .await,
+    ] {
+        assert_eq!(outcome, Outcome::Error {
+            message: "Unknown handle `nope`.".to_owned(),
+            trace: vec![],
+            transient: false,
+        });
+    }
+}
+
+#[tokio::test]
+async fn test_missing_handle_parameter() {
+    let tool = PollHandle::new(BackgroundTasks::default());
+    let outcome = tool.execute(&json!({}), &no_answers()).await;
+
+    assert!(matches!(outcome, Outcome::Error { .. }));
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_await_all_running_handles() {
+    let tasks = BackgroundTasks::default();
+    start(&tasks, "call_1", "echo one").await;
+    start(&tasks, "call_2", "echo two").await;
+
+    let outcome = AwaitHandles::new(tasks.clone())
+        .execute(&json!({}), &no_answers())
+        .await;
+    let content = outcome.unwrap_content();
+
+    assert!(content.contains("## `call_1` (completed)"), "{content}");
+    assert!(content.contains("## `call_2` (completed)"), "{content}");
+    assert!(content.contains("### Result\n\ntwo\n"), "{content}");
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn test_await_times_out_on_running_handle() {
+    let tasks = BackgroundTasks::default();
+    start(&tasks, "call_1", "sleep 30").await;
+
+    let outcome = AwaitHandles::new(tasks.clone())
+        .execute(
+            &json!({ "handles": ["call_1"], "timeout_secs": 0 }),
+            &no_answers(),
+        )
+        .await;
+
+    assert!(
+        outcome.unwrap_content().contains("## `call_1` (running)"),
+        "handle should still be running"
+    );
+
+    let outcome = CancelHandle::new(tasks.clone())
+        .execute(&json!({ "handle": "call_1" }), &no_answers())
+        .await;
+
+    assert!(outcome.unwrap_content().contains("## `call_1` (cancelled)"));
+    assert!(tasks.running().is_empty());
+}
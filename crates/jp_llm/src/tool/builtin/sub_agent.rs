@@ -0,0 +1,136 @@
+//! The `sub_agent` builtin implementation.
+//!
+//! The tool delegates a task to a child conversation and returns the child's
+//! final answer.
+//! Running the child turn requires the application's turn loop, which this
+//! crate does not own, so the work is handed to a [`SubAgentRunner`] provided
+//! by the caller.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use indexmap::IndexMap;
+use jp_tool::Outcome;
+use serde_json::Value;
+
+use crate::tool::BuiltinTool;
+
+/// A task delegated to a sub-agent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubAgentTask {
+    /// The request sent to the sub-agent.
+    pub query: String,
+
+    /// The system prompt of the sub-agent, replacing the parent's.
+    pub instructions: Option<String>,
+
+    /// The model (or model alias) the sub-agent runs on.
+    ///
+    /// Defaults to the parent conversation's model.
+    pub model: Option<String>,
+
+    /// The tools available to the sub-agent.
+    ///
+    /// Defaults to the tools enabled in the parent conversation.
+    pub tools: Option<Vec<String>>,
+}
+
+/// The final answer of a sub-agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubAgentAnswer {
+    /// The ID of the child conversation the task ran in.
+    pub conversation_id: String,
+
+    /// The sub-agent's final message.
+    pub content: String,
+}
+
+/// Runs a [`SubAgentTask`] in a child conversation, to completion.
+#[async_trait]
+pub trait SubAgentRunner: Send + Sync {
+    /// Run the task, returning the sub-agent's final answer, or an error
+    /// message to report back to the assistant.
+    async fn run(&self, task: SubAgentTask) -> Result<SubAgentAnswer, String>;
+}
+
+pub struct SubAgent {
+    runner: Arc<dyn SubAgentRunner>,
+}
+
+impl SubAgent {
+    #[must_use]
+    pub fn new(runner: Arc<dyn SubAgentRunner>) -> Self {
+        Self { runner }
+    }
+
+    fn parse_task(arguments: &Value) -> Result<SubAgentTask, String> {
+        let query = match arguments.get("query").and_then(Value::as_str) {
+            Some(query) if !query.trim().is_empty() => query.to_owned(),
+            Some(_) => return Err("The `query` parameter must not be empty.".to_owned()),
+            None => return Err("Missing or invalid `query` parameter.".to_owned()),
+        };
+
+        let optional_string = |name: &str| match arguments.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(value)) => Ok(Some(value.clone())),
+            Some(_) => Err(format!("The `{name}` parameter must be a string.")),
+        };
+
+        let tools = match arguments.get("tools") {
+            None | Some(Value::Null) => None,
+            Some(Value::Array(tools)) => Some(
+                tools
+                    .iter()
+                    .map(|tool| tool.as_str().map(str::to_owned))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or("The `tools` parameter must be an array of tool names.")?,
+            ),
+            Some(_) => {
+                return Err("The `tools` parameter must be an array of tool names.".to_owned());
+            }
+        };
+
+        Ok(SubAgentTask {
+            query,
+            instructions: optional_string("instructions")?,
+            model: optional_string("model")?,
+            tools,
+        })
+    }
+}
+
+#[async_trait]
+impl BuiltinTool for SubAgent {
+    async fn execute(&self, arguments: &Value, _answers: &IndexMap<String, Value>) -> Outcome {
+        let task = match Self::parse_task(arguments) {
+            Ok(task) => task,
+            Err(message) => {
+                return Outcome::Error {
+                    message,
+                    trace: vec![],
+                    transient: false,
+                };
+            }
+        };
+
+        match self.runner.run(task).await {
+            Ok(SubAgentAnswer {
+                conversation_id,
+                content,
+            }) => Outcome::Success {
+                content: format!(
+                    "<response conversation_id=\"{conversation_id}\">\n{content}\n</response>"
+                ),
+            },
+            Err(message) => Outcome::Error {
+                message,
+                trace: vec![],
+                transient: false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "sub_agent_tests.rs"]
+mod tests;
@@ -0,0 +1,151 @@
+use std::sync::Mutex;
+
+use indexmap::IndexMap;
+use jp_tool::Outcome;
+use serde_json::{Value, json};
+
+use super::*;
+
+/// Records the tasks it receives, and answers with a fixed result.
+struct RecordingRunner {
+    tasks: Mutex<Vec<SubAgentTask>>,
+    answer: Result<SubAgentAnswer, String>,
+}
+
+impl RecordingRunner {
+    fn answering(content: &str) -> Arc<Self> {
+        Arc::new(Self {
+            tasks: Mutex::new(vec![]),
+            answer: Ok(SubAgentAnswer {
+                conversation_id: "jp-c17528831000".to_owned(),
+                content: content.to_owned(),
+            }),
+        })
+    }
+
+    fn failing(message: &str) -> Arc<Self> {
+        Arc::new(Self {
+            tasks: Mutex::new(vec![]),
+            answer: Err(message.to_owned()),
+        })
+    }
+
+    fn tasks(&self) -> Vec<SubAgentTask> {
+        self.tasks.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl SubAgentRunner for RecordingRunner {
+    async fn run(&self, task: SubAgentTask) -> Result<SubAgentAnswer, String> {
+        self.tasks.lock().unwrap().push(task);
+        self.answer.clone()
+    }
+}
+
+fn no_answers() -> IndexMap<String, Value> {
+    IndexMap::new()
+}
+
+#[tokio::test]
+async fn test_execute_missing_query_argument() {
+    let runner = RecordingRunner::answering("unused");
+    let tool = SubAgent::new(runner.clone());
+
+    let result = tool.execute(&json!({}), &no_answers()).await;
+    let Outcome::Error {
+        message, transient, ..
+    } = result
+    else {
+        panic!("expected Outcome::Error");
+    };
+    assert!(message.contains("`query`"));
+    assert!(!transient);
+    assert!(runner.tasks().is_empty());
+}
+
+#[tokio::test]
+async fn test_execute_blank_query() {
+    let tool = SubAgent::new(RecordingRunner::answering("unused"));
+
+    let result = tool.execute(&json!({"query": "  "}), &no_answers()).await;
+    let Outcome::Error { message, .. } = result else {
+        panic!("expected Outcome::Error");
+    };
+    assert!(message.contains("must not be empty"));
+}
+
+#[tokio::test]
+async fn test_execute_invalid_tools_argument() {
+    let tool = SubAgent::new(RecordingRunner::answering("unused"));
+
+    for tools in [json!("fs_read_file"), json!(["fs_read_file", 1])] {
+        let result = tool
+            .execute(&json!({"query": "q", "tools": tools}), &no_answers())
+            .await;
+        assert!(
+            matches!(result, Outcome::Error { .. }),
+            "`tools: {tools}` should be an error"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_execute_passes_task_to_runner() {
+    let runner = RecordingRunner::answering("unused");
+    let tool = SubAgent::new(runner.clone());
+
+    let arguments = json!({
+        "query": "Find the error types.",
+        "instructions": "You are a researcher.",
+        "model": "haiku",
+        "tools": ["fs_read_file", "fs_grep_files"],
+    });
+    tool.execute(&arguments, &no_answers()).await;
+
+    assert_eq!(runner.tasks(), vec![SubAgentTask {
+        query: "Find the error types.".to_owned(),
+        instructions: Some("You are a researcher.".to_owned()),
+        model: Some("haiku".to_owned()),
+        tools: Some(vec!["fs_read_file".to_owned(), "fs_grep_files".to_owned()]),
+    }]);
+}
+
+#[tokio::test]
+async fn test_execute_optional_arguments_default_to_none() {
+    let runner = RecordingRunner::answering("unused");
+    let tool = SubAgent::new(runner.clone());
+
+    tool.execute(&json!({"query": "q", "model": null}), &no_answers())
+        .await;
+
+    assert_eq!(runner.tasks(), vec![SubAgentTask {
+        query: "q".to_owned(),
+        ..Default::default()
+    }]);
+}
+
+#[tokio::test]
+async fn test_execute_wraps_answer_with_conversation_id() {
+    let tool = SubAgent::new(RecordingRunner::answering("Two error types."));
+
+    let result = tool.execute(&json!({"query": "q"}), &no_answers()).await;
+    let Outcome::Success { content } = result else {
+        panic!("expected Outcome::Success");
+    };
+    assert_eq!(
+        content,
+        "<response conversation_id=\"jp-c17528831000\">\nTwo error types.\n</response>"
+    );
+}
+
+#[tokio::test]
+async fn test_execute_reports_runner_errors() {
+    let tool = SubAgent::new(RecordingRunner::failing("Unknown tool: nope"));
+
+    let result = tool.execute(&json!({"query": "q"}), &no_answers()).await;
+    let Outcome::Error { message, .. } = result else {
+        panic!("expected Outcome::Error");
+    };
+    assert_eq!(message, "Unknown tool: nope");
+}
@@ -0,0 +1,158 @@
+//! Running local tools in a pseudo-terminal.
+//!
+//! Tools configured with `pty = true` see a terminal on their stdin, stdout
+//! and stderr, instead of pipes.
+//! While such a tool runs, its output is mirrored to the user's terminal, and
+//! the user's keystrokes are forwarded to it, so interactive programs
+//! (installers, REPLs, editors) work as they would in a shell.
+//! The transcript of the session becomes the tool result.
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use camino::Utf8Path;
+use jp_config::{conversation::tool::CommandConfig, env::Env};
+use jp_tool::pty::{PtyCommand, PtySession};
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
+use tracing::{trace, warn};
+
+use super::{CommandResult, parse_command_output, render_command};
+use crate::error::ToolError;
+
+/// How long to wait for output before checking for input, exit and
+/// cancellation again.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How long to keep reading output after the tool exited.
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// The user's terminal, lent to tools running in a pseudo-terminal.
+pub trait ToolTerminal: Send + Sync + fmt::Debug {
+    /// The size of the terminal, in rows and columns.
+    fn size(&self) -> (u16, u16);
+
+    /// Show raw output of the tool, including escape sequences.
+    fn output(&self, bytes: &[u8]);
+
+    /// Start forwarding the user's input to the tool.
+    ///
+    /// Forwarding stops when the returned handle is dropped.
+    fn input(&self) -> Box<dyn TerminalInput>;
+}
+
+/// Input typed into a [`ToolTerminal`].
+pub trait TerminalInput: Send {
+    /// Return the input typed since the last call, if any.
+    fn try_read(&mut self) -> Option<Vec<u8>>;
+}
+
+/// Run a tool command in a pseudo-terminal.
+///
+/// Like [`super::run_tool_command`], except that stdout and stderr are merged
+/// into a single transcript, which is parsed as the command's stdout.
+/// A tool can still report a [`jp_tool::Outcome`] this way.
+///
+/// With a `terminal`, the tool's output is mirrored to it while it runs, and
+/// the user's input is forwarded to the tool.
+/// Without one, the tool still runs in a pseudo-terminal, but nobody can type
+/// into it.
+pub async fn run_tool_command(
+    command: CommandConfig,
+    ctx: Value,
+    root: &Utf8Path,
+    env: &Env,
+    cancellation_token: CancellationToken,
+    terminal: Option<Arc<dyn ToolTerminal>>,
+) -> Result<CommandResult, ToolError> {
+    let (program, args) = render_command(command, &ctx)?;
+    let (rows, cols) = terminal
+        .as_ref()
+        .map_or((24, 80), |terminal| terminal.size());
+
+    let mut pty = PtyCommand::new(&program)
+        .args(&args)
+        .cwd(root.to_path_buf())
+        .size(rows, cols);
+    if let Some(vars) = env.overrides() {
+        pty = pty.env_clear().envs(vars.clone());
+    }
+
+    let session = pty.spawn().map_err(|error| ToolError::SpawnError {
+        command: format!("{program} {}", args.join(" ")),
+        error,
+    })?;
+
+    // The session runs on a blocking thread, which outlives this future if it
+    // is dropped. Cancelling a private token on drop stops (and kills) it.
+    let token = cancellation_token.child_token();
+    let _guard = token.clone().drop_guard();
+
+    let result =
+        tokio::task::spawn_blocking(move || drive(session, terminal.as_deref(), &token)).await;
+
+    Ok(result.unwrap_or_else(|error| CommandResult::RawOutput {
+        stdout: String::new(),
+        stderr: error.to_string(),
+        success: false,
+    }))
+}
+
+/// Mirror output and forward input until the tool exits or is cancelled.
+fn drive(
+    mut session: PtySession,
+    terminal: Option<&dyn ToolTerminal>,
+    token: &CancellationToken,
+) -> CommandResult {
+    let mut input = terminal.map(|terminal| terminal.input());
+
+    let code = loop {
+        if token.is_cancelled() {
+            if let Err(error) = session.kill() {
+                warn!(%error, "Failed to kill tool process.");
+            }
+            return CommandResult::Cancelled;
+        }
+
+        if let Some(chunk) = session.read(POLL_INTERVAL)
+            && let Some(terminal) = terminal
+        {
+            terminal.output(&chunk);
+        }
+
+        if let Some(input) = &mut input {
+            while let Some(bytes) = input.try_read() {
+                if let Err(error) = session.send(&bytes) {
+                    trace!(%error, "Failed to forward input to tool.");
+                    break;
+                }
+            }
+        }
+
+        match session.try_wait() {
+            Ok(Some(code)) => break code,
+            Ok(None) => {}
+            Err(error) => {
+                return CommandResult::RawOutput {
+                    stdout: session.transcript(),
+                    stderr: error.to_string(),
+                    success: false,
+                };
+            }
+        }
+    };
+
+    // Stop forwarding input before the last output is shown.
+    drop(input);
+
+    while let Some(chunk) = session.read(DRAIN_TIMEOUT) {
+        if let Some(terminal) = terminal {
+            terminal.output(&chunk);
+        }
+    }
+
+    parse_command_output(session.transcript().as_bytes(), &[], code == 0)
+}
+
+#[cfg(test)]
+#[path = "pty_tests.rs"]
+mod tests;
@@ -0,0 +1,139 @@
+use std::sync::Mutex;
+
+use jp_config::conversation::tool::CommandConfig;
+use serde_json::json;
+
+use super::*;
+
+/// A terminal that records the tool's output, and types scripted input.
+#[derive(Debug, Default)]
+struct MockTerminal {
+    output: Mutex<Vec<u8>>,
+    input: Vec<&'static str>,
+}
+
+impl ToolTerminal for MockTerminal {
+    fn size(&self) -> (u16, u16) {
+        (24, 80)
+    }
+
+    fn output(&self, bytes: &[u8]) {
+        self.output.lock().unwrap().extend_from_slice(bytes);
+    }
+
+    fn input(&self) -> Box<dyn TerminalInput> {
+        Box::new(MockInput(self.input.clone()))
+    }
+}
+
+struct MockInput(Vec<&'static str>);
+
+impl TerminalInput for MockInput {
+    fn try_read(&mut self) -> Option<Vec<u8>> {
+        (!self.0.is_empty()).then(|| self.0.remove(0).as_bytes().to_vec())
+    }
+}
+
+fn shell(script: &str) -> CommandConfig {
+    CommandConfig {
+        program: script.to_owned(),
+        args: vec![],
+        shell: true,
+    }
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn transcript_is_the_result() {
+    let terminal = Arc::new(MockTerminal::default());
+
+    let result = run_tool_command(
+        shell("test -t 1 && printf '\\033[32mtty\\033[0m\\n'"),
+        json!({}),
+        "/tmp".into(),
+        &Env::process(),
+        CancellationToken::new(),
+        Some(terminal.clone()),
+    )
+    .await
+    .unwrap();
+
+    match result {
+        CommandResult::RawOutput {
+            stdout, success, ..
+        } => {
+            assert!(success);
+            assert_eq!(stdout, "tty\n");
+        }
+        other => panic!("Expected RawOutput, got: {other:?}"),
+    }
+
+    // The terminal sees the raw output, escape sequences included.
+    let output = terminal.output.lock().unwrap();
+    assert!(output.starts_with(b"\x1b[32mtty"));
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn input_is_forwarded() {
+    let terminal = Arc::new(MockTerminal {
+        input: vec!["jp\r"],
+        ..Default::default()
+    });
+
+    let result = run_tool_command(
+        shell("read name; echo \"hello $name\""),
+        json!({}),
+        "/tmp".into(),
+        &Env::process(),
+        CancellationToken::new(),
+        Some(terminal),
+    )
+    .await
+    .unwrap();
+
+    match result {
+        CommandResult::RawOutput { stdout, .. } => assert!(stdout.contains("hello jp")),
+        other => panic!("Expected RawOutput, got: {other:?}"),
+    }
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn outcome_is_parsed() {
+    let result = run_tool_command(
+        shell(r#"echo '{"type":"success","content":"done"}'"#),
+        json!({}),
+        "/tmp".into(),
+        &Env::process(),
+        CancellationToken::new(),
+        None,
+    )
+    .await
+    .unwrap();
+
+    match result {
+        CommandResult::Success(content) => assert_eq!(content, "done"),
+        other => panic!("Expected Success, got: {other:?}"),
+    }
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn cancellation_kills_the_tool() {
+    let token = CancellationToken::new();
+    token.cancel();
+
+    let result = run_tool_command(
+        shell("sleep 10"),
+        json!({}),
+        "/tmp".into(),
+        &Env::process(),
+        token,
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert!(matches!(result, CommandResult::Cancelled));
+}
@@ -692,9 +692,16 @@ async fn test_run_tool_command_renders_null_args_as_valid_json() {
         shell: false,
     };
 
-    let result = run_tool_command(command, ctx, "/tmp".into(), CancellationToken::new(), None)
-        .await
-        .unwrap();
+    let result = run_tool_command(
+        command,
+        ctx,
+        "/tmp".into(),
+        &Env::process(),
+        CancellationToken::new(),
+        None,
+    )
+    .await
+    .unwrap();
 
     let stdout = match result {
         CommandResult::RawOutput { stdout, .. } => stdout,
@@ -733,9 +740,16 @@ async fn test_run_tool_command_renders_scalar_strings_raw() {
         shell: false,
     };
 
-    let result = run_tool_command(command, ctx, "/tmp".into(), CancellationToken::new(), None)
-        .await
-        .unwrap();
+    let result = run_tool_command(
+        command,
+        ctx,
+        "/tmp".into(),
+        &Env::process(),
+        CancellationToken::new(),
+        None,
+    )
+    .await
+    .unwrap();
 
     let stdout = match result {
         CommandResult::RawOutput { stdout, .. } => stdout,
@@ -764,9 +778,16 @@ async fn test_run_tool_command_renders_null_scalar_as_literal_null() {
         shell: false,
     };
 
-    let result = run_tool_command(command, ctx, "/tmp".into(), CancellationToken::new(), None)
-        .await
-        .unwrap();
+    let result = run_tool_command(
+        command,
+        ctx,
+        "/tmp".into(),
+        &Env::process(),
+        CancellationToken::new(),
+        None,
+    )
+    .await
+    .unwrap();
 
     let stdout = match result {
         CommandResult::RawOutput { stdout, .. } => stdout,
@@ -806,9 +827,16 @@ async fn test_run_tool_command_rfd_draft_title_rendering() {
         shell: false,
     };
 
-    let result = run_tool_command(command, ctx, "/tmp".into(), CancellationToken::new(), None)
-        .await
-        .unwrap();
+    let result = run_tool_command(
+        command,
+        ctx,
+        "/tmp".into(),
+        &Env::process(),
+        CancellationToken::new(),
+        None,
+    )
+    .await
+    .unwrap();
 
     let stdout = match result {
         CommandResult::RawOutput { stdout, .. } => stdout,
@@ -840,9 +868,16 @@ async fn test_run_tool_command_tojson_filter_on_scalar_still_works() {
         shell: false,
     };
 
-    let result = run_tool_command(command, ctx, "/tmp".into(), CancellationToken::new(), None)
-        .await
-        .unwrap();
+    let result = run_tool_command(
+        command,
+        ctx,
+        "/tmp".into(),
+        &Env::process(),
+        CancellationToken::new(),
+        None,
+    )
+    .await
+    .unwrap();
 
     let stdout = match result {
         CommandResult::RawOutput { stdout, .. } => stdout,
@@ -888,6 +923,7 @@ async fn test_execute_local_exposes_invocation_ids_in_context() {
     let invocation = InvocationContext {
         workspace_id: "ws-abc".to_owned(),
         conversation_id: "conv-xyz".to_owned(),
+        ..Default::default()
     };
     let mcp_client = jp_mcp::Client::new(IndexMap::new());
     let builtins = builtin::BuiltinExecutors::new();
@@ -919,6 +955,81 @@ async fn test_execute_local_exposes_invocation_ids_in_context() {
     }
 }
 
+/// A local tool that prints a handle first completes right away, and keeps
+/// running as a background handle of the invocation.
+#[tokio::test]
+#[cfg(unix)]
+async fn test_execute_local_starts_background_handle() {
+    use jp_config::{
+        AppConfig, Config,
+        conversation::tool::{PartialToolConfig, ToolConfig},
+    };
+
+    let partial: PartialToolConfig = serde_json::from_value(json!({
+        "source": "local",
+        "command": {
+            "program": r#"echo '{"type":"handle","description":"waiting"}'; echo done"#,
+            "shell": true,
+        },
+    }))
+    .expect("valid partial tool config");
+    let tool = ToolConfig::from_partial(partial, vec![]).expect("resolved tool config");
+
+    let mut cfg = AppConfig::new_test();
+    cfg.conversation.tools.insert("waiter".to_owned(), tool);
+    let config = cfg.conversation.tools.get("waiter").expect("tool present");
+
+    let definition = ToolDefinition {
+        name: "waiter".to_owned(),
+        docs: ToolDocs::default(),
+        parameters: IndexMap::new(),
+    };
+    let invocation = InvocationContext::default();
+    let mcp_client = jp_mcp::Client::new(IndexMap::new());
+    let builtins = builtin::BuiltinExecutors::new();
+
+    let outcome = definition
+        .execute(
+            "call-1".to_owned(),
+            json!({}),
+            &IndexMap::new(),
+            &config,
+            &mcp_client,
+            Utf8Path::new("/tmp"),
+            CancellationToken::new(),
+            &builtins,
+            None,
+            &invocation,
+        )
+        .await
+        .expect("execution succeeds");
+
+    match outcome {
+        ExecutionOutcome::Completed {
+            result: Ok(out), ..
+        } => assert!(out.contains("Handle: `call-1`"), "{out}"),
+        other => panic!("expected completed success, got: {other:?}"),
+    }
+
+    let report = invocation.background.wait("call-1").await.unwrap();
+    assert_eq!(report.record.description, "waiting");
+    assert_eq!(report.result, Some(Ok("done\n".to_owned())));
+}
+
+/// A handle is only recognized on the first line of output.
+#[test]
+fn test_parse_command_output_rejects_late_handle() {
+    let stdout = br#"{
+        "type": "handle",
+        "description": "too late"
+    }"#;
+
+    assert!(matches!(
+        parse_command_output(stdout, b"", true),
+        CommandResult::FatalError(_)
+    ));
+}
+
 /// Regression for RFD 081: `tool_definitions` keeps a *forced* tool that is
 /// merely disabled (`OFF`), but always drops a locked-off tool (`state =
 /// false`, `allow_toggle = never`) even when it is forced.
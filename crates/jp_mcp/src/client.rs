@@ -1,14 +1,17 @@
 use std::{
     collections::{HashMap, HashSet, VecDeque},
-    env,
-    path::Path,
+    path::{Path, PathBuf},
     process::Stdio,
     sync::{Arc, Mutex},
     time::Duration,
 };
 
 use indexmap::IndexMap;
-use jp_config::providers::mcp::{AlgorithmConfig, McpProviderConfig};
+use jp_config::{
+    env::Env,
+    providers::mcp::{AlgorithmConfig, McpProviderConfig, StdioConfig},
+    secret::{self, SecretRef},
+};
 use rmcp::{
     model::{
         CallToolRequestParams, CallToolResult, ReadResourceRequestParams, Resource,
@@ -72,6 +75,9 @@ pub struct Client {
 
     /// Running MCP services.
     services: Arc<RwLock<HashMap<McpServerId, RunningService<RoleClient, ()>>>>,
+
+    /// The environment server configurations are resolved with.
+    env: Arc<RwLock<Env>>,
 }
 
 impl std::fmt::Debug for Client {
@@ -79,6 +85,7 @@ impl std::fmt::Debug for Client {
         f.debug_struct("Client")
             .field("servers", &self.servers)
             .field("services", &self.services.blocking_read().keys())
+            .field("env", &self.env)
             .finish()
     }
 }
@@ -95,9 +102,51 @@ impl Client {
         Self {
             services: Arc::new(RwLock::new(HashMap::new())),
             servers: Arc::new(RwLock::new(servers)),
+            env: Arc::default(),
         }
     }
 
+    /// Resolve server configurations with `env` instead of the process
+    /// environment.
+    #[must_use]
+    pub fn with_env(self, env: Env) -> Self {
+        Self {
+            env: Arc::new(RwLock::new(env)),
+            ..self
+        }
+    }
+
+    /// Replace the known servers with `providers`, resolved with `env`.
+    ///
+    /// Running servers that are no longer configured, or whose configuration
+    /// or referenced environment variables changed, are stopped;
+    /// [`Self::run_services`] starts them again when they are needed.
+    pub async fn sync_servers(&self, providers: IndexMap<String, McpProviderConfig>, env: Env) {
+        let next: IndexMap<_, _> = providers
+            .into_iter()
+            .map(|(name, config)| (McpServerId::new(name), config))
+            .collect();
+
+        let mut servers = self.servers.write().await;
+        let mut current_env = self.env.write().await;
+        self.services.write().await.retain(|id, _| {
+            let unchanged = match (servers.get(id), next.get(id)) {
+                (Some(current), Some(config)) => {
+                    current == config
+                        && referenced_env(current, &current_env) == referenced_env(config, &env)
+                }
+                _ => false,
+            };
+            if !unchanged {
+                trace!(%id, "Stopping MCP server with changed configuration.");
+            }
+            unchanged
+        });
+
+        *servers = next;
+        *current_env = env;
+    }
+
     /// Look up a tool definition on a specific MCP server.
     ///
     /// The server must be configured (i.e. present in the [`Client`]'s server
@@ -116,7 +165,8 @@ impl Client {
             client.peer().list_all_tools().await?
         } else {
             drop(running);
-            match Self::try_create_client(server_id, server).await? {
+            let env = self.env.read().await.clone();
+            match Self::try_create_client(server_id, server, &env).await? {
                 SpawnOutcome::Started(client) => client.list_all_tools().await?,
                 SpawnOutcome::OptionalFailed => return Err(Error::UnknownTool(id.to_string())),
             }
@@ -216,13 +266,15 @@ impl Client {
             joins.spawn({
                 let servers = self.servers.clone();
                 let clients = self.services.clone();
+                let env = self.env.clone();
                 async move {
                     let servers = servers.read().await;
                     let server = servers
                         .get(&server_id)
                         .ok_or(Error::UnknownServer(server_id.clone()))?;
 
-                    match Self::try_create_client(&server_id, server).await? {
+                    let env = env.read().await.clone();
+                    match Self::try_create_client(&server_id, server, &env).await? {
                         SpawnOutcome::Started(client) => {
                             clients.write().await.insert(server_id.clone(), client);
                         }
@@ -261,8 +313,9 @@ impl Client {
     async fn try_create_client(
         id: &McpServerId,
         config: &McpProviderConfig,
+        env: &Env,
     ) -> Result<SpawnOutcome> {
-        match Self::create_client(id, config).await {
+        match Self::create_client(id, config, env).await {
             Ok(client) => Ok(SpawnOutcome::Started(client)),
             Err(error) if config.optional() => {
                 warn!(
@@ -278,43 +331,46 @@ impl Client {
     }
 
     /// Create a new MCP client for a server configuration
+    ///
+    /// The server inherits the process environment, plus the configured
+    /// variables resolved with `env`.
     async fn create_client(
         id: &McpServerId,
         config: &McpProviderConfig,
+        env: &Env,
     ) -> Result<RunningService<RoleClient, ()>> {
         match config {
             McpProviderConfig::Stdio(config) => {
-                if let Some(checksum) = &config.checksum {
-                    verify_file_checksum(
-                        id.as_str(),
-                        &config.command,
-                        &checksum.value,
-                        checksum.algorithm,
-                    )?;
-                }
-
-                // Build environment variables
-                let vars = config
-                    .variables
-                    .iter()
-                    .filter_map(|key| {
-                        env::var(key)
-                            .inspect_err(|error| {
+                let command = resolve_command(id, config, env)?;
+
+                // Build environment variables. A `NAME=VALUE` entry sets the
+                // variable to the (possibly secret) value, a bare `NAME` passes
+                // through the variable from `env`.
+                let mut vars = HashMap::new();
+                for variable in &config.variables {
+                    let (key, value) = match variable.split_once('=') {
+                        Some((key, value)) => (key, secret::resolve(value, env)?.into_owned()),
+                        None => match env.var(variable) {
+                            Some(value) => (variable.as_str(), value),
+                            None => {
                                 warn!(
-                                    key,
-                                    error = error.to_string(),
+                                    key = variable,
                                     server = id.to_string(),
-                                    "Failed to read MCP server environment variable"
+                                    "MCP server environment variable is not set"
                                 );
-                            })
-                            .ok()
-                            .map(|value| (key.to_owned(), value))
-                    })
-                    .collect::<HashMap<_, _>>();
+                                continue;
+                            }
+                        },
+                    };
+
+                    vars.insert(key.to_owned(), value);
+                }
 
                 // Create command
-                let mut cmd = Command::new(&config.command);
-                cmd.args(&config.arguments);
+                let mut cmd = Command::new(&command);
+                for argument in &config.arguments {
+                    cmd.arg(secret::resolve(argument, env)?.as_ref());
+                }
 
                 // Put the MCP server in its own process group so terminal
                 // signals (Ctrl+C / SIGINT) don't kill it. JP manages the
@@ -330,8 +386,9 @@ impl Client {
 
                 // Build a human-readable command line (program + args) so
                 // diagnostic errors include enough context to reproduce the
-                // failure.
-                let cmd_display = render_command(&cmd);
+                // failure. Secret references are rendered redacted, never
+                // resolved.
+                let cmd_display = render_command(&config.command, &config.arguments);
 
                 // Create the child process transport. Stderr is piped so we
                 // can forward it to tracing; dropping it would close the pipe
@@ -393,16 +450,75 @@ impl Client {
     }
 }
 
+/// The program a server configuration runs, with secret references resolved
+/// using `env`.
+///
+/// If the configuration has a checksum, the program is verified against it.
+fn resolve_command(id: &McpServerId, config: &StdioConfig, env: &Env) -> Result<PathBuf> {
+    let command = match config.command.to_str() {
+        Some(command) => PathBuf::from(secret::resolve(command, env)?.as_ref()),
+        None => config.command.clone(),
+    };
+
+    if let Some(checksum) = &config.checksum {
+        verify_file_checksum(
+            id.as_str(),
+            &command,
+            &secret::resolve(&checksum.value, env)?,
+            checksum.algorithm,
+        )?;
+    }
+
+    Ok(command)
+}
+
 /// Maximum number of stderr lines retained for diagnostic error reporting.
 const STDERR_TAIL_LINES: usize = 100;
 
+/// The values of the environment variables a server configuration reads from
+/// `env`.
+///
+/// A running server is only reused while these stay the same.
+fn referenced_env(config: &McpProviderConfig, env: &Env) -> Vec<Option<String>> {
+    let McpProviderConfig::Stdio(config) = config;
+
+    // Values that are secret references, which may read from the environment.
+    let references = config
+        .variables
+        .iter()
+        .filter_map(|variable| variable.split_once('=').map(|(_, value)| value))
+        .chain(config.arguments.iter().map(String::as_str))
+        .chain(config.command.to_str())
+        .chain(
+            config
+                .checksum
+                .iter()
+                .map(|checksum| checksum.value.as_str()),
+        )
+        .filter_map(|value| match value.parse() {
+            Ok(SecretRef::Env(name)) => Some(name),
+            _ => None,
+        });
+
+    // Bare variable names, which pass the variable through.
+    config
+        .variables
+        .iter()
+        .filter(|variable| !variable.contains('='))
+        .cloned()
+        .chain(references)
+        .map(|name| env.var(&name))
+        .collect()
+}
+
 /// Render a command (program + arguments) as a single human-readable line.
-fn render_command(cmd: &Command) -> String {
-    let std_cmd = cmd.as_std();
-    let prog = std_cmd.get_program().to_string_lossy();
-    let args = std_cmd
-        .get_args()
-        .map(|a| a.to_string_lossy().into_owned())
+///
+/// Arguments that are secret references are redacted.
+fn render_command(program: &Path, args: &[String]) -> String {
+    let prog = program.to_string_lossy();
+    let args = args
+        .iter()
+        .map(|arg| secret::redact(arg))
         .collect::<Vec<_>>();
 
     if args.is_empty() {
@@ -1,14 +1,17 @@
 use std::{
     collections::{HashSet, VecDeque},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 
 use indexmap::IndexMap;
-use jp_config::providers::mcp::{McpProviderConfig, StdioConfig};
-use tokio::{process::Command, runtime::Handle};
+use jp_config::{
+    env::Env,
+    providers::mcp::{AlgorithmConfig, ChecksumConfig, McpProviderConfig, StdioConfig},
+};
+use tokio::runtime::Handle;
 
-use super::{render_command, render_stderr_tail};
+use super::{referenced_env, render_command, render_stderr_tail, resolve_command};
 use crate::{Client, Error, id::McpServerId};
 
 fn stdio_config(command: &str, optional: bool) -> McpProviderConfig {
@@ -24,30 +27,36 @@ fn stdio_config(command: &str, optional: bool) -> McpProviderConfig {
 
 #[test]
 fn render_command_program_only() {
-    let cmd = Command::new("just");
-    assert_eq!(render_command(&cmd), "just");
+    assert_eq!(render_command(Path::new("just"), &[]), "just");
 }
 
 #[test]
 fn render_command_program_with_args() {
-    let mut cmd = Command::new("just");
-    cmd.arg("serve-bookworm");
-    assert_eq!(render_command(&cmd), "just serve-bookworm");
+    let args = ["serve-bookworm".to_owned()];
+    assert_eq!(
+        render_command(Path::new("just"), &args),
+        "just serve-bookworm"
+    );
 }
 
 #[test]
 fn render_command_program_with_multiple_args() {
-    let mut cmd = Command::new("cargo");
-    cmd.arg("build")
-        .arg("--release")
-        .arg("--package")
-        .arg("bookworm");
+    let args = ["build", "--release", "--package", "bookworm"].map(str::to_owned);
     assert_eq!(
-        render_command(&cmd),
+        render_command(Path::new("cargo"), &args),
         "cargo build --release --package bookworm"
     );
 }
 
+#[test]
+fn render_command_redacts_secrets() {
+    let args = ["--token", "secret:cmd:pass show github"].map(str::to_owned);
+    assert_eq!(
+        render_command(Path::new("server"), &args),
+        "server --token secret:cmd:***"
+    );
+}
+
 #[test]
 fn render_stderr_tail_empty_returns_empty_string() {
     let buffer = Arc::new(Mutex::new(VecDeque::<String>::new()));
@@ -147,7 +156,7 @@ async fn startup_timeout_attaches_stderr_tail() {
         startup_timeout_secs: 1,
     });
 
-    let error = Client::create_client(&McpServerId::new("slow"), &config)
+    let error = Client::create_client(&McpServerId::new("slow"), &config, &Env::process())
         .await
         .expect_err("a server that never completes the handshake must time out");
 
@@ -181,7 +190,7 @@ async fn zero_startup_timeout_disables_timeout() {
         startup_timeout_secs: 0,
     });
 
-    let error = Client::create_client(&McpServerId::new("instant"), &config)
+    let error = Client::create_client(&McpServerId::new("instant"), &config, &Env::process())
         .await
         .expect_err("a child that exits without a handshake fails initialization");
 
@@ -258,3 +267,123 @@ async fn required_server_failure_propagates() {
         "failed required server is also not registered as running"
     );
 }
+
+#[tokio::test]
+async fn sync_servers_replaces_known_servers() {
+    let server_id = McpServerId::new("missing");
+    let mut client = Client::new(IndexMap::new());
+
+    let mut providers = IndexMap::new();
+    providers.insert("missing".to_owned(), stdio_config(MISSING_BINARY, false));
+    client.sync_servers(providers, Env::process()).await;
+
+    let mut startup = client
+        .run_services(HashSet::from([server_id.clone()]), Handle::current())
+        .await
+        .unwrap();
+
+    // The server is known now, so starting it fails on the missing binary
+    // rather than on an unknown server.
+    while let Some(joined) = startup.joins.join_next().await {
+        let error = joined.expect("task did not panic").unwrap_err();
+        assert!(
+            !matches!(error, Error::UnknownServer(_)),
+            "synced server must be known, got: {error:?}"
+        );
+    }
+
+    client.sync_servers(IndexMap::new(), Env::process()).await;
+    let mut startup = client
+        .run_services(HashSet::from([server_id.clone()]), Handle::current())
+        .await
+        .unwrap();
+
+    while let Some(joined) = startup.joins.join_next().await {
+        let error = joined.expect("task did not panic").unwrap_err();
+        assert!(matches!(error, Error::UnknownServer(_)), "got: {error:?}");
+    }
+}
+
+#[test]
+fn referenced_env_tracks_passed_and_referenced_variables() {
+    let config = McpProviderConfig::Stdio(StdioConfig {
+        command: PathBuf::from("server"),
+        arguments: vec!["--token".to_owned(), "secret:env:TOKEN".to_owned()],
+        variables: vec!["HOME".to_owned(), "MODE=fast".to_owned()],
+        checksum: None,
+        optional: false,
+        startup_timeout_secs: 60,
+    });
+
+    let env = |home: &str, token: &str, other: &str| {
+        Env::captured([
+            ("HOME".to_owned(), home.to_owned()),
+            ("TOKEN".to_owned(), token.to_owned()),
+            ("OTHER".to_owned(), other.to_owned()),
+        ])
+    };
+
+    assert_eq!(referenced_env(&config, &env("/a", "t", "x")), vec![
+        Some("/a".to_owned()),
+        Some("t".to_owned()),
+    ]);
+    assert_eq!(
+        referenced_env(&config, &env("/a", "t", "x")),
+        referenced_env(&config, &env("/a", "t", "y")),
+    );
+    assert_ne!(
+        referenced_env(&config, &env("/a", "t", "x")),
+        referenced_env(&config, &env("/b", "t", "x")),
+    );
+    assert_ne!(
+        referenced_env(&config, &env("/a", "t", "x")),
+        referenced_env(&config, &env("/a", "u", "x")),
+    );
+}
+
+#[test]
+fn secret_references_resolve_in_command_and_checksum() {
+    let config = StdioConfig {
+        command: PathBuf::from("secret:env:JP_TEST_COMMAND"),
+        arguments: vec![],
+        variables: vec![],
+        checksum: Some(ChecksumConfig {
+            algorithm: AlgorithmConfig::Sha256,
+            value: "secret:env:JP_TEST_CHECKSUM".to_owned(),
+        }),
+        optional: false,
+        startup_timeout_secs: 60,
+    };
+    let id = McpServerId::new("server");
+
+    let env = Env::captured([("JP_TEST_COMMAND".to_owned(), "sh".to_owned())]);
+    assert!(matches!(
+        resolve_command(&id, &config, &env),
+        Err(Error::Secret(_))
+    ));
+
+    let env = Env::captured([
+        ("JP_TEST_COMMAND".to_owned(), "sh".to_owned()),
+        ("JP_TEST_CHECKSUM".to_owned(), "abc".to_owned()),
+    ]);
+    match resolve_command(&id, &config, &env) {
+        Err(Error::ChecksumMismatch { path, expected, .. }) => {
+            assert_eq!(path.file_name(), Some("sh".as_ref()));
+            assert_eq!(expected, "abc");
+        }
+        other => panic!("expected a checksum mismatch, got {other:?}"),
+    }
+
+    let config = StdioConfig {
+        checksum: None,
+        ..config
+    };
+    assert_eq!(
+        resolve_command(&id, &config, &env).unwrap(),
+        PathBuf::from("sh")
+    );
+    assert_eq!(
+        referenced_env(&McpProviderConfig::Stdio(config), &env),
+        vec![Some("sh".to_owned())]
+    );
+}
@@ -34,6 +34,9 @@ pub enum Error {
     #[error("Missing environment variable: {0}")]
     MissingEnv(#[from] std::env::VarError),
 
+    #[error("Secret error: {0}")]
+    Secret(#[from] jp_config::secret::SecretError),
+
     #[error("Checksum mismatch for server: {server} ({}), expected {expected}, got {got}", path.display())]
     ChecksumMismatch {
         server: String,
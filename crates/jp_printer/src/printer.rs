@@ -814,6 +814,13 @@ pub enum OutputFormat {
     /// Pretty-printed JSON.
     /// Indented, but no ANSI colors.
     JsonPretty,
+
+    /// Newline-delimited JSON.
+    /// Like [`Json`], but commands that stream (e.g. `query`) emit each event
+    /// as its own line as it happens.
+    ///
+    /// [`Json`]: Self::Json
+    Jsonl,
 }
 
 impl OutputFormat {
@@ -826,7 +833,13 @@ impl OutputFormat {
     /// Whether this format produces JSON output.
     #[must_use]
     pub const fn is_json(self) -> bool {
-        matches!(self, Self::Json | Self::JsonPretty)
+        matches!(self, Self::Json | Self::JsonPretty | Self::Jsonl)
+    }
+
+    /// Whether this format streams events as newline-delimited JSON.
+    #[must_use]
+    pub const fn is_jsonl(self) -> bool {
+        matches!(self, Self::Jsonl)
     }
 
     /// Whether JSON output should be indented.
@@ -3,6 +3,7 @@ use std::error::Error;
 use async_trait::async_trait;
 use jp_config::{
     AppConfig,
+    env::Env,
     model::{
         ModelConfig,
         id::ModelIdConfig,
@@ -22,7 +23,7 @@ use jp_llm::{
     retry::{RetryConfig, collect_with_retry},
     title,
 };
-use jp_workspace::Workspace;
+use jp_workspace::{ScratchRecorder, Workspace};
 use tokio_util::sync::CancellationToken;
 use tracing::{trace, warn};
 
@@ -33,6 +34,8 @@ pub struct TitleGeneratorTask {
     pub conversation_id: ConversationId,
     pub model_id: ModelIdConfig,
     pub providers: LlmProviderConfig,
+    /// The environment the provider is created with.
+    pub env: Env,
     pub events: ConversationStream,
     pub title: Option<String>,
     /// Output ceiling for the title request, from
@@ -42,6 +45,9 @@ pub struct TitleGeneratorTask {
     /// When `false`, the OSC-2 title-update side effect on task sync is
     /// suppressed — the bytes would otherwise leak into a captured pipe.
     pub is_tty: bool,
+    /// The title request and its response, recorded as a scratch conversation
+    /// of the titled conversation on sync.
+    pub scratch: Option<ConversationStream>,
 }
 
 impl TitleGeneratorTask {
@@ -49,6 +55,7 @@ impl TitleGeneratorTask {
         conversation_id: ConversationId,
         events: ConversationStream,
         config: &AppConfig,
+        env: Env,
         is_tty: bool,
     ) -> Result<Self, Box<dyn Error + Send + Sync>> {
         // Prefer the title generation model id, otherwise use the assistant
@@ -70,7 +77,7 @@ impl TitleGeneratorTask {
         // key environment variable). Without this, the failure only surfaces
         // inside the spawned task, after the query has already committed to
         // waiting for it at teardown.
-        provider::preflight(model_id.provider, &config.providers.llm)?;
+        provider::preflight(model_id.provider, &config.providers.llm, &env)?;
 
         // If reasoning is explicitly enabled for title generation, use it,
         // otherwise limit it to low effort.
@@ -88,17 +95,19 @@ impl TitleGeneratorTask {
             conversation_id,
             model_id,
             providers: config.providers.llm.clone(),
+            env,
             events,
             title: None,
             max_response_bytes: config.assistant.request.max_response_bytes,
             is_tty,
+            scratch: None,
         })
     }
 
     async fn update_title(&mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
         trace!(conversation_id = %self.conversation_id, "Updating conversation title.");
 
-        let provider = provider::get_provider(self.model_id.provider, &self.providers)?;
+        let provider = provider::get_provider(self.model_id.provider, &self.providers, &self.env)?;
         let model = provider.model_details(&self.model_id.name).await?;
 
         let sections = title::title_instructions(1, &[]);
@@ -121,6 +130,8 @@ impl TitleGeneratorTask {
             tool_choice: jp_config::assistant::tool_choice::ToolChoice::default(),
         };
 
+        let mut scratch = query.thread.to_stream(&self.model_id);
+
         let retry_config = RetryConfig::default().with_max_response_bytes(self.max_response_bytes);
         let llm_events =
             collect_with_retry(provider.as_ref(), &model, query, &retry_config).await?;
@@ -147,6 +158,9 @@ impl TitleGeneratorTask {
             }
         }
 
+        scratch.extend(flushed.iter().cloned());
+        self.scratch = Some(scratch);
+
         let structured_data = flushed
             .into_iter()
             .filter_map(ConversationEvent::into_chat_response)
@@ -214,6 +228,11 @@ impl Task for TitleGeneratorTask {
                     return Ok(());
                 }
             };
+            if let Some(scratch) = self.scratch.clone() {
+                ScratchRecorder::new(ctx.conversation_factory(), lock.id(), lock.projection())
+                    .record("Title generation", scratch);
+            }
+
             let mut conv = lock.into_mut();
             conv.update_metadata(|m| m.title = self.title.clone());
             if let Err(e) = conv.flush() {
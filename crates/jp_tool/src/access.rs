@@ -501,15 +501,28 @@ impl crate::Context {
             .access
             .as_ref()
             .is_none_or(|policy| policy.permits(capability, &match_key));
-        if granted {
-            Ok(canonical)
-        } else {
-            Err(FsAccessError::Denied {
+        if !granted {
+            return Err(FsAccessError::Denied {
                 capability: capability.as_str(),
                 target: match_key,
                 grants: rules.iter().map(|r| r.lexical_path.clone()).collect(),
-            })
+            });
         }
+
+        // Record the state of an in-workspace target before the tool changes
+        // it, so the turn can be undone. External targets are not part of the
+        // workspace, and are never restored.
+        let changes = !matches!(capability, Capability::Read | Capability::Execute);
+        if let Some(checkpoint) = self.checkpoint.as_ref().filter(|_| changes && inside) {
+            checkpoint
+                .record(&self.root, &match_key)
+                .map_err(|e| FsAccessError::Io {
+                    path: canonical.clone(),
+                    message: e.to_string(),
+                })?;
+        }
+
+        Ok(canonical)
     }
 
     fn resolve<'a>(
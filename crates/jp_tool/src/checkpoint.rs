@@ -0,0 +1,425 @@
+//! Pre-images of the files that tools change.
+//!
+//! JP gives the tools of each conversation turn a checkpoint directory (see
+//! [`Context::checkpoint`]).
+//! Before a tool creates, updates or deletes a file, the state of that file is
+//! recorded there, so that JP can later show what the turn changed, or undo it.
+//! [`Context::check_create`] and friends record the state on the tool's
+//! behalf; tools that check access themselves call [`Checkpoint::record`].
+//!
+//! Only the first recorded state of a file is kept, which is the state of the
+//! file before the turn started changing it.
+//!
+//! [`Context::checkpoint`]: crate::Context::checkpoint
+//! [`Context::check_create`]: crate::Context::check_create
+
+use std::{
+    fs,
+    io::{self, BufRead as _, BufReader, Read as _, Write as _},
+};
+
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use camino_tempfile::NamedUtf8TempFile;
+use serde::{Deserialize, Serialize};
+
+use crate::util::stable_hash;
+
+/// A directory of file pre-images, one per changed file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Checkpoint {
+    dir: Utf8PathBuf,
+}
+
+/// The state of a file before a tool changed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreImage {
+    /// The workspace-relative path of the file.
+    pub path: Utf8PathBuf,
+
+    /// The state of the file.
+    pub state: FileState,
+}
+
+/// The recorded state of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileState {
+    /// The file did not exist.
+    Absent,
+
+    /// A regular file.
+    File {
+        /// The content of the file.
+        content: Vec<u8>,
+
+        /// The Unix permission bits, if known.
+        mode: Option<u32>,
+    },
+
+    /// A symbolic link.
+    Symlink {
+        /// Where the link pointed to.
+        target: Utf8PathBuf,
+    },
+}
+
+impl FileState {
+    /// Read the current state of the file at `path`.
+    ///
+    /// Returns `None` if `path` is a directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn load(path: &Utf8Path) -> io::Result<Option<Self>> {
+        let state = match fs::symlink_metadata(path) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Self::Absent,
+            Err(error) => return Err(error),
+            Ok(meta) if meta.is_dir() => return Ok(None),
+            Ok(meta) if meta.is_symlink() => Self::Symlink {
+                target: fs::read_link(path)?.try_into().map_err(io::Error::other)?,
+            },
+            Ok(meta) => Self::File {
+                content: fs::read(path)?,
+                mode: mode(&meta),
+            },
+        };
+
+        Ok(Some(state))
+    }
+}
+
+/// The first line of a pre-image file, followed by the file content.
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    path: Utf8PathBuf,
+    #[serde(flatten)]
+    kind: Kind,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Kind {
+    Absent,
+    File {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<u32>,
+    },
+    Symlink {
+        target: Utf8PathBuf,
+    },
+}
+
+impl Checkpoint {
+    /// A checkpoint stored in `dir`.
+    ///
+    /// The directory is created when the first pre-image is recorded.
+    #[must_use]
+    pub fn new(dir: impl Into<Utf8PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The directory the pre-images are stored in.
+    #[must_use]
+    pub fn dir(&self) -> &Utf8Path {
+        &self.dir
+    }
+
+    /// Record the current state of the workspace-relative `path`, unless an
+    /// earlier state was already recorded.
+    ///
+    /// A directory is recorded as the files below it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, or the pre-image cannot be
+    /// written.
+    pub fn record(&self, root: &Utf8Path, path: &Utf8Path) -> io::Result<()> {
+        let absolute = root.join(path);
+        let Some(state) = FileState::load(&absolute)? else {
+            for relative in files_below(&absolute)? {
+                self.record(root, &path.join(relative))?;
+            }
+            return Ok(());
+        };
+
+        self.write(path, &state)
+    }
+
+    /// Record the files that moving `from` to `to` changes.
+    ///
+    /// When `from` is a directory, this records each file below it, and where
+    /// that file ends up.
+    ///
+    /// # Errors
+    ///
+    /// See [`Checkpoint::record`].
+    pub fn record_move(&self, root: &Utf8Path, from: &Utf8Path, to: &Utf8Path) -> io::Result<()> {
+        let absolute = root.join(from);
+        if fs::symlink_metadata(&absolute).is_ok_and(|meta| meta.is_dir()) {
+            for relative in files_below(&absolute)? {
+                self.record(root, &from.join(&relative))?;
+                self.record(root, &to.join(&relative))?;
+            }
+
+            return Ok(());
+        }
+
+        self.record(root, from)?;
+        self.record(root, to)
+    }
+
+    /// The recorded pre-images, ordered by path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkpoint directory cannot be read, or a
+    /// pre-image names a path outside the workspace.
+    pub fn entries(&self) -> io::Result<Vec<PreImage>> {
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(error) => return Err(error),
+        };
+
+        let mut entries = vec![];
+        for entry in dir {
+            let entry = entry?;
+
+            // Pre-images are written to hidden temporary files first.
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if hidden || !entry.file_type()?.is_file() {
+                continue;
+            }
+
+            entries.push(read_entry(&entry.path())?);
+        }
+
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(entries)
+    }
+
+    /// Restore every recorded file to its pre-image, and return the restored
+    /// paths.
+    ///
+    /// Files that did not exist are removed, along with any directories that
+    /// are left empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkpoint cannot be read, or a file cannot be
+    /// restored.
+    pub fn restore(&self, root: &Utf8Path) -> io::Result<Vec<Utf8PathBuf>> {
+        let entries = self.entries()?;
+        for PreImage { path, state } in &entries {
+            restore(root, path, state)?;
+        }
+
+        Ok(entries.into_iter().map(|entry| entry.path).collect())
+    }
+
+    fn write(&self, path: &Utf8Path, state: &FileState) -> io::Result<()> {
+        // Only the first state of a file is kept.
+        let target = self.dir.join(entry_name(path));
+        if fs::symlink_metadata(&target).is_ok() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.dir)?;
+
+        let (kind, content) = match state {
+            FileState::Absent => (Kind::Absent, &[][..]),
+            FileState::File { content, mode } => (Kind::File { mode: *mode }, content.as_slice()),
+            FileState::Symlink { target } => (
+                Kind::Symlink {
+                    target: target.clone(),
+                },
+                &[][..],
+            ),
+        };
+
+        let header = Header {
+            path: path.to_owned(),
+            kind,
+        };
+
+        // The pre-image is written in full before it is moved into place, so
+        // an interrupted write never leaves a truncated pre-image behind.
+        let mut file = NamedUtf8TempFile::new_in(&self.dir)?;
+        serde_json::to_writer(&mut file, &header)?;
+        file.write_all(b"\n")?;
+        file.write_all(content)?;
+
+        // Moving it without replacing an existing pre-image keeps the first
+        // state when tools that run in parallel record the same path.
+        match file.persist_noclobber(&target) {
+            Ok(_) => Ok(()),
+            Err(error) if error.error.kind() == io::ErrorKind::AlreadyExists => Ok(()),
+            Err(error) => Err(error.error),
+        }
+    }
+}
+
+fn read_entry(path: &std::path::Path) -> io::Result<PreImage> {
+    let mut reader = BufReader::new(fs::File::open(path)?);
+
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let header: Header = serde_json::from_str(&line)?;
+
+    // Restoring joins the path to the workspace root, so a tampered or
+    // corrupted pre-image must not be able to point outside of it.
+    let relative = header
+        .path
+        .components()
+        .all(|component| matches!(component, Utf8Component::Normal(_) | Utf8Component::CurDir));
+    if !relative || header.path.as_str().is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("pre-image path is outside the workspace: {}", header.path),
+        ));
+    }
+
+    let state = match header.kind {
+        Kind::Absent => FileState::Absent,
+        Kind::Symlink { target } => FileState::Symlink { target },
+        Kind::File { mode } => {
+            let mut content = vec![];
+            reader.read_to_end(&mut content)?;
+            FileState::File { content, mode }
+        }
+    };
+
+    Ok(PreImage {
+        path: header.path,
+        state,
+    })
+}
+
+fn restore(root: &Utf8Path, path: &Utf8Path, state: &FileState) -> io::Result<()> {
+    let absolute = root.join(path);
+
+    // Replace whatever is there now. A directory is left alone: it holds
+    // files that are restored on their own.
+    match fs::symlink_metadata(&absolute) {
+        Ok(meta) if meta.is_dir() => return Ok(()),
+        Ok(_) => fs::remove_file(&absolute)?,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+        Err(error) => return Err(error),
+    }
+
+    match state {
+        FileState::Absent => remove_empty_parents(root, path),
+        FileState::File { content, mode } => {
+            if let Some(parent) = absolute.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            fs::write(&absolute, content)?;
+            set_mode(&absolute, *mode)
+        }
+        FileState::Symlink { target } => {
+            if let Some(parent) = absolute.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            symlink(target, &absolute)
+        }
+    }
+}
+
+/// Remove the parent directories of `path` that are empty, up to the root.
+fn remove_empty_parents(root: &Utf8Path, path: &Utf8Path) -> io::Result<()> {
+    let mut parent = path.parent();
+    while let Some(dir) = parent.filter(|dir| !dir.as_str().is_empty()) {
+        let absolute = root.join(dir);
+        match fs::read_dir(&absolute) {
+            Ok(mut entries) if entries.next().is_none() => fs::remove_dir(&absolute)?,
+            Ok(_) => break,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+
+        parent = dir.parent();
+    }
+
+    Ok(())
+}
+
+/// The paths of the files below `dir`, relative to `dir`.
+fn files_below(dir: &Utf8Path) -> io::Result<Vec<Utf8PathBuf>> {
+    let mut files = vec![];
+    let mut pending = vec![Utf8PathBuf::new()];
+
+    while let Some(relative) = pending.pop() {
+        for entry in dir.join(&relative).read_dir_utf8()? {
+            let entry = entry?;
+            let path = relative.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// The file name of the pre-image of `path`.
+///
+/// Paths can be longer than a file name can be, so the name is a stable hash of
+/// the path, which is stored in the pre-image itself.
+fn entry_name(path: &Utf8Path) -> String {
+    format!("{:016x}", stable_hash(path.as_str()))
+}
+
+#[cfg(unix)]
+#[expect(clippy::unnecessary_wraps)]
+fn mode(meta: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    Some(meta.permissions().mode())
+}
+
+#[cfg(not(unix))]
+fn mode(_meta: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Utf8Path, mode: Option<u32>) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    match mode {
+        Some(mode) => fs::set_permissions(path, fs::Permissions::from_mode(mode)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Utf8Path, _mode: Option<u32>) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink(target: &Utf8Path, link: &Utf8Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink(target: &Utf8Path, link: &Utf8Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn symlink(_target: &Utf8Path, _link: &Utf8Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "symlinks are not supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+#[path = "checkpoint_tests.rs"]
+mod tests;
@@ -0,0 +1,164 @@
+use camino_tempfile::Utf8TempDir;
+
+use super::*;
+
+fn setup() -> (Utf8TempDir, Utf8TempDir, Checkpoint) {
+    let root = Utf8TempDir::new().unwrap();
+    let store = Utf8TempDir::new().unwrap();
+    let checkpoint = Checkpoint::new(store.path().join("turn"));
+    (root, store, checkpoint)
+}
+
+#[test]
+fn keeps_the_first_state() {
+    let (root, _store, checkpoint) = setup();
+    fs::write(root.path().join("a.txt"), "one").unwrap();
+
+    checkpoint.record(root.path(), "a.txt".into()).unwrap();
+    fs::write(root.path().join("a.txt"), "two").unwrap();
+    checkpoint.record(root.path(), "a.txt".into()).unwrap();
+
+    let entries = checkpoint.entries().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].path, "a.txt");
+    assert!(
+        matches!(&entries[0].state, FileState::File { content, .. } if content == b"one"),
+        "{entries:?}"
+    );
+}
+
+#[test]
+fn restores_changed_created_and_deleted_files() {
+    let (root, _store, checkpoint) = setup();
+    let root = root.path();
+    fs::write(root.join("changed.txt"), "before").unwrap();
+    fs::write(root.join("deleted.txt"), "gone").unwrap();
+
+    for path in ["changed.txt", "deleted.txt", "new/dir/created.txt"] {
+        checkpoint.record(root, path.into()).unwrap();
+    }
+
+    fs::write(root.join("changed.txt"), "after").unwrap();
+    fs::remove_file(root.join("deleted.txt")).unwrap();
+    fs::create_dir_all(root.join("new/dir")).unwrap();
+    fs::write(root.join("new/dir/created.txt"), "hello").unwrap();
+
+    let restored = checkpoint.restore(root).unwrap();
+
+    assert_eq!(restored, vec![
+        Utf8PathBuf::from("changed.txt"),
+        Utf8PathBuf::from("deleted.txt"),
+        Utf8PathBuf::from("new/dir/created.txt"),
+    ]);
+    assert_eq!(
+        fs::read_to_string(root.join("changed.txt")).unwrap(),
+        "before"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("deleted.txt")).unwrap(),
+        "gone"
+    );
+    assert!(!root.join("new").exists());
+}
+
+#[test]
+fn records_moved_directories_file_by_file() {
+    let (root, _store, checkpoint) = setup();
+    let root = root.path();
+    fs::create_dir_all(root.join("old/sub")).unwrap();
+    fs::write(root.join("old/a.txt"), "a").unwrap();
+    fs::write(root.join("old/sub/b.txt"), "b").unwrap();
+
+    checkpoint
+        .record_move(root, "old".into(), "new".into())
+        .unwrap();
+    fs::rename(root.join("old"), root.join("new")).unwrap();
+
+    let paths = checkpoint
+        .entries()
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.path)
+        .collect::<Vec<_>>();
+    assert_eq!(paths, vec![
+        Utf8PathBuf::from("new/a.txt"),
+        Utf8PathBuf::from("new/sub/b.txt"),
+        Utf8PathBuf::from("old/a.txt"),
+        Utf8PathBuf::from("old/sub/b.txt"),
+    ]);
+
+    checkpoint.restore(root).unwrap();
+    assert_eq!(fs::read_to_string(root.join("old/sub/b.txt")).unwrap(), "b");
+    assert!(!root.join("new").exists());
+}
+
+#[test]
+#[cfg(unix)]
+fn restores_symlinks_and_modes() {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let (root, _store, checkpoint) = setup();
+    let root = root.path();
+    fs::write(root.join("run.sh"), "#!/bin/sh").unwrap();
+    fs::set_permissions(root.join("run.sh"), fs::Permissions::from_mode(0o755)).unwrap();
+    std::os::unix::fs::symlink("run.sh", root.join("link")).unwrap();
+
+    checkpoint.record(root, "run.sh".into()).unwrap();
+    checkpoint.record(root, "link".into()).unwrap();
+    fs::remove_file(root.join("run.sh")).unwrap();
+    fs::remove_file(root.join("link")).unwrap();
+    fs::write(root.join("link"), "not a link").unwrap();
+
+    checkpoint.restore(root).unwrap();
+
+    let mode = fs::metadata(root.join("run.sh"))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o755);
+    assert_eq!(fs::read_link(root.join("link")).unwrap(), "run.sh");
+}
+
+#[test]
+fn ignores_unfinished_writes() {
+    let (root, store, checkpoint) = setup();
+    fs::write(root.path().join("a.txt"), "one").unwrap();
+    checkpoint.record(root.path(), "a.txt".into()).unwrap();
+    fs::write(store.path().join("turn/.tmpAbC123"), "{").unwrap();
+
+    let paths = checkpoint
+        .entries()
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.path)
+        .collect::<Vec<_>>();
+    assert_eq!(paths, vec![Utf8PathBuf::from("a.txt")]);
+}
+
+#[test]
+fn rejects_paths_outside_the_workspace() {
+    let (root, store, checkpoint) = setup();
+    checkpoint.record(root.path(), "a.txt".into()).unwrap();
+    let entry = fs::read_dir(store.path().join("turn"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let original = fs::read_to_string(&entry).unwrap();
+
+    for path in ["../a.txt", "/tmp/a.txt", "b/../../a.txt"] {
+        let tampered = original.replace(r#""path":"a.txt""#, &format!(r#""path":"{path}""#));
+        assert_ne!(tampered, original);
+        fs::write(&entry, tampered).unwrap();
+
+        let error = checkpoint.restore(root.path()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{path}");
+    }
+}
+
+#[test]
+fn missing_checkpoint_has_no_entries() {
+    let (_root, _store, checkpoint) = setup();
+    assert_eq!(checkpoint.entries().unwrap(), vec![]);
+}
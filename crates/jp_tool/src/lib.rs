@@ -3,10 +3,16 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 mod access;
+mod checkpoint;
+#[cfg(feature = "pty")]
+pub mod pty;
+mod util;
+
 pub use access::{
     AccessPolicy, Capability, EnvRule, FsAccessError, FsRule, NetRule,
     canonicalize_workspace_target, lexical_workspace_relative,
 };
+pub use checkpoint::{Checkpoint, FileState, PreImage};
 
 /// The result of a tool call.
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -29,6 +35,18 @@ pub enum Outcome {
 
     /// The tool requires additional input before it can complete the request.
     NeedsInput { question: Question },
+
+    /// The tool continues its work in the background.
+    ///
+    /// A local tool reports this by printing it as the first line of its
+    /// stdout, and then keeps running.
+    /// The assistant gets a handle to wait on, poll or cancel the tool, and the
+    /// rest of the tool's output becomes the result of the handle once the tool
+    /// exits.
+    Handle {
+        /// What the tool is doing, shown to the assistant and the user.
+        description: String,
+    },
 }
 
 impl Outcome {
@@ -62,12 +80,22 @@ impl Outcome {
         }
     }
 
+    /// Continue the tool call in the background.
+    ///
+    /// See [`Outcome::Handle`].
+    #[must_use]
+    pub fn handle(description: impl Into<String>) -> Self {
+        Self::Handle {
+            description: description.into(),
+        }
+    }
+
     /// Returns the content of the outcome if it is a success.
     #[must_use]
     pub fn into_content(self) -> Option<String> {
         match self {
             Outcome::Success { content } => Some(content),
-            Outcome::NeedsInput { .. } | Outcome::Error { .. } => None,
+            Outcome::NeedsInput { .. } | Outcome::Error { .. } | Outcome::Handle { .. } => None,
         }
     }
 
@@ -205,7 +233,7 @@ pub enum AnswerType {
 }
 
 /// Contextual information available to a tool.
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Context {
     /// The root path that the tool should run in.
     pub root: Utf8PathBuf,
@@ -233,6 +261,16 @@ pub struct Context {
     /// Combined with `workspace_id`, this lets a tool scope persisted state to
     /// a single conversation.
     pub conversation_id: String,
+
+    /// Where the files that the tool changes are recorded, so that the turn
+    /// can be undone.
+    ///
+    /// When `Some`, [`Context::check_create`], [`Context::check_update`] and
+    /// [`Context::check_delete`] record the target before granting access.
+    /// Tools that check access without these methods should call
+    /// [`Checkpoint::record`] before changing a file.
+    #[serde(default)]
+    pub checkpoint: Option<Checkpoint>,
 }
 
 impl From<String> for Outcome {
@@ -264,7 +302,7 @@ pub enum PersistLevel {
 }
 
 /// The action that a tool is being run for.
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Action {
     /// Run the tool.
@@ -0,0 +1,468 @@
+//! Drive programs in a pseudo-terminal.
+//!
+//! Some programs only behave when attached to a terminal: they prompt for
+//! input, page their output, or refuse to run at all when stdout is a pipe.
+//! [`PtyCommand`] spawns such a program in a pseudo-terminal (PTY), and the
+//! returned [`PtySession`] lets a tool wait for output, answer prompts, and
+//! collect a readable transcript of the session.
+//!
+//! ```ignore
+//! let mut session = PtyCommand::new("npm").arg("init").spawn()?;
+//! session.expect("package name:", Duration::from_secs(5))?;
+//! session.send_line("my-package")?;
+//!
+//! let exit = session.wait()?;
+//! println!("{}", exit.transcript);
+//! ```
+
+use std::{
+    io::{self, Read as _, Write},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use camino::Utf8PathBuf;
+use portable_pty::{Child, CommandBuilder, MasterPty, PtySize, native_pty_system};
+
+/// How long [`PtySession::wait`] keeps reading once the program exited.
+///
+/// Processes spawned by the program can keep the terminal open after it
+/// exits, so the remaining output is drained until the terminal is quiet for
+/// this long, instead of until it closes.
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// A program to run in a pseudo-terminal.
+#[derive(Debug, Clone)]
+pub struct PtyCommand {
+    program: String,
+    args: Vec<String>,
+    cwd: Option<Utf8PathBuf>,
+    env: Vec<(String, String)>,
+    env_clear: bool,
+    rows: u16,
+    cols: u16,
+}
+
+impl PtyCommand {
+    /// A command running `program`, in a 24x80 terminal.
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+            args: vec![],
+            cwd: None,
+            env: vec![],
+            env_clear: false,
+            rows: 24,
+            cols: 80,
+        }
+    }
+
+    /// Add an argument.
+    #[must_use]
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Add arguments.
+    #[must_use]
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the working directory of the program.
+    #[must_use]
+    pub fn cwd(mut self, cwd: impl Into<Utf8PathBuf>) -> Self {
+        self.cwd = Some(cwd.into());
+        self
+    }
+
+    /// Set an environment variable, on top of the inherited environment.
+    #[must_use]
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Set environment variables, on top of the inherited environment.
+    #[must_use]
+    pub fn envs<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env.extend(
+            vars.into_iter()
+                .map(|(key, value)| (key.into(), value.into())),
+        );
+        self
+    }
+
+    /// Don't inherit the environment, only pass the variables that are set
+    /// explicitly.
+    #[must_use]
+    pub const fn env_clear(mut self) -> Self {
+        self.env_clear = true;
+        self
+    }
+
+    /// Set the size of the terminal, in rows and columns.
+    #[must_use]
+    pub const fn size(mut self, rows: u16, cols: u16) -> Self {
+        self.rows = rows;
+        self.cols = cols;
+        self
+    }
+
+    /// Spawn the program.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pseudo-terminal cannot be opened, or the program
+    /// cannot be spawned.
+    pub fn spawn(self) -> io::Result<PtySession> {
+        let Self {
+            program,
+            args,
+            cwd,
+            env,
+            env_clear,
+            rows,
+            cols,
+        } = self;
+
+        let pair = native_pty_system()
+            .openpty(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(io::Error::other)?;
+
+        let mut cmd = CommandBuilder::new(program);
+        cmd.args(args);
+        if let Some(cwd) = cwd {
+            cmd.cwd(cwd.as_str());
+        }
+        if env_clear {
+            cmd.env_clear();
+        }
+        for (key, value) in env {
+            cmd.env(key, value);
+        }
+
+        let child = pair.slave.spawn_command(cmd).map_err(io::Error::other)?;
+
+        // The program holds its own handle to the terminal. Dropping ours
+        // ensures reads see the end of the output once the program exits.
+        drop(pair.slave);
+
+        let mut reader = pair.master.try_clone_reader().map_err(io::Error::other)?;
+        let writer = pair.master.take_writer().map_err(io::Error::other)?;
+
+        let (tx, output) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buf = [0; 4096];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        if tx.send(buf[..n].to_vec()).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(PtySession {
+            child,
+            master: pair.master,
+            writer,
+            output,
+            transcript: vec![],
+            unmatched: vec![],
+            exit_code: None,
+            closed: false,
+        })
+    }
+}
+
+/// A program running in a pseudo-terminal.
+///
+/// The program is killed when the session is dropped before it exits.
+pub struct PtySession {
+    child: Box<dyn Child + Send + Sync>,
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    output: Receiver<Vec<u8>>,
+
+    /// All output read so far.
+    transcript: Vec<u8>,
+
+    /// Raw output that [`Self::expect`] has not consumed yet.
+    ///
+    /// Kept raw, as an escape sequence, line ending or character can be split
+    /// across reads, and only cleans up correctly once it is complete.
+    unmatched: Vec<u8>,
+
+    /// The exit code, once the program exited.
+    exit_code: Option<u32>,
+
+    /// Whether the terminal closed, and no more output will arrive.
+    closed: bool,
+}
+
+impl std::fmt::Debug for PtySession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PtySession")
+            .field("pid", &self.child.process_id())
+            .field("exit_code", &self.exit_code)
+            .finish_non_exhaustive()
+    }
+}
+
+/// How a [`PtySession`] ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PtyExit {
+    /// The exit code of the program.
+    pub code: u32,
+
+    /// The cleaned transcript of the session, see [`clean_transcript`].
+    pub transcript: String,
+}
+
+impl PtyExit {
+    /// Whether the program exited successfully.
+    #[must_use]
+    pub const fn success(&self) -> bool {
+        self.code == 0
+    }
+}
+
+impl PtySession {
+    /// Read the next chunk of raw output, waiting at most `timeout`.
+    ///
+    /// Returns `None` if no output arrived in time, or the terminal closed.
+    pub fn read(&mut self, timeout: Duration) -> Option<Vec<u8>> {
+        match self.output.recv_timeout(timeout) {
+            Ok(chunk) => {
+                self.transcript.extend_from_slice(&chunk);
+                self.unmatched.extend_from_slice(&chunk);
+                Some(chunk)
+            }
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => {
+                self.closed = true;
+                None
+            }
+        }
+    }
+
+    /// Wait until the output contains `needle`, and return the cleaned output
+    /// up to and including it.
+    ///
+    /// Output returned by an earlier call is not matched again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::ErrorKind::TimedOut`] if `needle` did not appear within
+    /// `timeout`, or [`io::ErrorKind::UnexpectedEof`] if the terminal closed
+    /// before it appeared.
+    pub fn expect(&mut self, needle: &str, timeout: Duration) -> io::Result<String> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            if let Some(matched) = take_match(&mut self.unmatched, needle) {
+                return Ok(matched);
+            }
+
+            if self.closed {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("terminal closed before {needle:?} appeared"),
+                ));
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("timed out waiting for {needle:?}"),
+                ));
+            }
+
+            self.read(remaining);
+        }
+    }
+
+    /// Send raw input to the program, as if typed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the terminal is closed.
+    pub fn send(&mut self, input: impl AsRef<[u8]>) -> io::Result<()> {
+        self.writer.write_all(input.as_ref())?;
+        self.writer.flush()
+    }
+
+    /// Send a line of input to the program, followed by Enter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the terminal is closed.
+    pub fn send_line(&mut self, line: &str) -> io::Result<()> {
+        self.send(format!("{line}\r"))
+    }
+
+    /// Resize the terminal.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the terminal cannot be resized.
+    pub fn resize(&self, rows: u16, cols: u16) -> io::Result<()> {
+        self.master
+            .resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(io::Error::other)
+    }
+
+    /// Return the exit code of the program, if it exited.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the status of the program cannot be read.
+    pub fn try_wait(&mut self) -> io::Result<Option<u32>> {
+        if self.exit_code.is_none() {
+            self.exit_code = self.child.try_wait()?.map(|status| status.exit_code());
+        }
+
+        Ok(self.exit_code)
+    }
+
+    /// Kill the program.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the program cannot be killed.
+    pub fn kill(&mut self) -> io::Result<()> {
+        if self.exit_code.is_some() {
+            return Ok(());
+        }
+
+        self.child.kill()
+    }
+
+    /// Wait for the program to exit, and return its exit code and transcript.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the status of the program cannot be read.
+    pub fn wait(mut self) -> io::Result<PtyExit> {
+        let code = loop {
+            if let Some(code) = self.try_wait()? {
+                break code;
+            }
+
+            if self.read(DRAIN_TIMEOUT).is_none() && self.closed {
+                self.exit_code = Some(self.child.wait()?.exit_code());
+            }
+        };
+
+        while self.read(DRAIN_TIMEOUT).is_some() {}
+
+        Ok(PtyExit {
+            code,
+            transcript: self.transcript(),
+        })
+    }
+
+    /// The raw output read so far, including terminal escape sequences.
+    #[must_use]
+    pub fn raw_transcript(&self) -> &[u8] {
+        &self.transcript
+    }
+
+    /// The cleaned output read so far, see [`clean_transcript`].
+    #[must_use]
+    pub fn transcript(&self) -> String {
+        clean_transcript(&self.transcript)
+    }
+}
+
+impl Drop for PtySession {
+    fn drop(&mut self) {
+        if matches!(self.try_wait(), Ok(None)) {
+            drop(self.child.kill());
+        }
+    }
+}
+
+/// Remove the output up to and including the first `needle` from `raw`, and
+/// return it cleaned.
+///
+/// Returns `None`, and leaves `raw` as is, if the cleaned output does not
+/// contain `needle`.
+fn take_match(raw: &mut Vec<u8>, needle: &str) -> Option<String> {
+    let contains = |len: usize| clean_transcript(&raw[..len]).contains(needle);
+    if !contains(raw.len()) {
+        return None;
+    }
+
+    // Find the shortest prefix of the output that contains the needle. The
+    // byte that completes the needle ends it, so the rest of the output never
+    // starts in the middle of a character or escape sequence.
+    let (mut without, mut with) = (0, raw.len());
+    if contains(0) {
+        with = 0;
+    }
+    while with - without > 1 {
+        let mid = without + (with - without) / 2;
+        if contains(mid) {
+            with = mid;
+        } else {
+            without = mid;
+        }
+    }
+
+    let rest = raw.split_off(with);
+    Some(clean_transcript(&std::mem::replace(raw, rest)))
+}
+
+/// Turn raw terminal output into plain text.
+///
+/// Escape sequences (colors, cursor movement) are removed, and line endings
+/// are normalized to `\n`.
+/// A carriage return that is not part of a line ending rewinds the line, as it
+/// does on a terminal, so only the final state of progress bars and spinners
+/// is kept.
+#[must_use]
+pub fn clean_transcript(raw: &[u8]) -> String {
+    // Carriage returns are handled first, as stripping escape sequences also
+    // removes them.
+    String::from_utf8_lossy(raw)
+        .split('\n')
+        .map(|line| {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let line = line.rsplit('\r').next().unwrap_or(line);
+            strip_ansi_escapes::strip_str(line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+#[path = "pty_tests.rs"]
+mod tests;
@@ -0,0 +1,113 @@
+use super::*;
+
+#[test]
+fn clean_transcript_normalizes_output() {
+    let cases = [
+        ("hello\r\nworld\r\n", "hello\nworld\n"),
+        ("\x1b[1;32mok\x1b[0m\r\n", "ok\n"),
+        ("10%\r50%\r100%\r\ndone", "100%\ndone"),
+        ("plain", "plain"),
+    ];
+
+    for (raw, expected) in cases {
+        assert_eq!(clean_transcript(raw.as_bytes()), expected, "raw: {raw:?}");
+    }
+}
+
+#[test]
+fn take_match_handles_output_split_across_reads() {
+    // An SGR sequence and a UTF-8 character, each split across two chunks.
+    let mut raw = b"\x1b[1;3".to_vec();
+    assert_eq!(take_match(&mut raw, "caf\u{e9}:"), None);
+
+    raw.extend_from_slice(b"2mcaf\xc3");
+    assert_eq!(take_match(&mut raw, "caf\u{e9}:"), None);
+
+    raw.extend_from_slice(b"\xa9:\x1b[0m \r");
+    assert_eq!(
+        take_match(&mut raw, "caf\u{e9}:").as_deref(),
+        Some("caf\u{e9}:")
+    );
+
+    // The rest is kept for the next match, with the `\r\n` pair completed by
+    // the next read.
+    raw.extend_from_slice(b"\nnext> ");
+    assert_eq!(take_match(&mut raw, "next>").as_deref(), Some(" \nnext>"));
+    assert_eq!(raw, b" ");
+}
+
+#[test]
+fn pty_exit_success() {
+    let exit = |code| PtyExit {
+        code,
+        transcript: String::new(),
+    };
+
+    assert!(exit(0).success());
+    assert!(!exit(2).success());
+}
+
+#[cfg(unix)]
+#[test]
+fn program_sees_a_terminal() {
+    let exit = PtyCommand::new("sh")
+        .args(["-c", "test -t 0 && test -t 1 && echo tty"])
+        .spawn()
+        .unwrap()
+        .wait()
+        .unwrap();
+
+    assert!(exit.success());
+    assert_eq!(exit.transcript.trim(), "tty");
+}
+
+#[cfg(unix)]
+#[test]
+fn expect_and_answer_a_prompt() {
+    let mut session = PtyCommand::new("sh")
+        .args([
+            "-c",
+            "printf 'name: '; read name; echo \"hello $name\"; exit 3",
+        ])
+        .spawn()
+        .unwrap();
+
+    let prompt = session.expect("name:", Duration::from_secs(5)).unwrap();
+    assert_eq!(prompt, "name:");
+
+    session.send_line("jp").unwrap();
+    session.expect("hello jp", Duration::from_secs(5)).unwrap();
+
+    let exit = session.wait().unwrap();
+    assert_eq!(exit.code, 3);
+    assert!(exit.transcript.contains("name: jp\nhello jp"));
+}
+
+#[cfg(unix)]
+#[test]
+fn env_clear_only_passes_explicit_variables() {
+    let exit = PtyCommand::new("/bin/sh")
+        .args(["-c", "echo \"${JP_PTY_TEST:-unset} ${HOME:-unset}\""])
+        .env_clear()
+        .envs([("JP_PTY_TEST", "set")])
+        .spawn()
+        .unwrap()
+        .wait()
+        .unwrap();
+
+    assert_eq!(exit.transcript.trim(), "set unset");
+}
+
+#[cfg(unix)]
+#[test]
+fn expect_times_out() {
+    let mut session = PtyCommand::new("sh")
+        .args(["-c", "sleep 5"])
+        .spawn()
+        .unwrap();
+
+    let error = session
+        .expect("never", Duration::from_millis(50))
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+}
@@ -0,0 +1,19 @@
+//! Helpers shared by the modules of this crate.
+
+/// A 64-bit FNV-1a hash of `bytes`.
+///
+/// Unlike the standard library's hasher, the hash is the same across Rust
+/// releases, so it can name files that outlive the build that created them.
+#[must_use]
+pub(crate) fn stable_hash(bytes: impl AsRef<[u8]>) -> u64 {
+    bytes
+        .as_ref()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        })
+}
+
+#[cfg(test)]
+#[path = "util_tests.rs"]
+mod tests;
@@ -0,0 +1,8 @@
+use super::*;
+
+#[test]
+fn test_stable_hash() {
+    assert_eq!(stable_hash(""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(stable_hash("a"), 0xaf63_dc4c_8601_ec8c);
+    assert_ne!(stable_hash("src/a.rs"), stable_hash("src/b.rs"));
+}
@@ -2,7 +2,9 @@
 //! symlink resolution and nested-escape boundary introduced by RFD D43.
 
 use camino::Utf8PathBuf;
-use jp_tool::{AccessPolicy, Action, Capability, Context, FsAccessError, FsRule};
+use jp_tool::{
+    AccessPolicy, Action, Capability, Checkpoint, Context, FileState, FsAccessError, FsRule,
+};
 
 fn ctx(root: Utf8PathBuf, access: Option<AccessPolicy>) -> Context {
     Context {
@@ -11,6 +13,7 @@ fn ctx(root: Utf8PathBuf, access: Option<AccessPolicy>) -> Context {
         access,
         workspace_id: "test".into(),
         conversation_id: "test".into(),
+        checkpoint: None,
     }
 }
 
@@ -92,6 +95,38 @@ fn restricted_default_deny_and_capabilities() {
     ));
 }
 
+#[test]
+fn granted_writes_record_the_target_in_the_checkpoint() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    let root = dir.path().join("workspace");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/lib.rs"), "before").unwrap();
+    std::fs::write(root.join("secret.txt"), "x").unwrap();
+
+    let policy = AccessPolicy {
+        fs: vec![FsRule::new("src").with_read(true).with_write(true)],
+        ..AccessPolicy::default()
+    };
+    let checkpoint = Checkpoint::new(dir.path().join("checkpoint"));
+    let mut ctx = ctx(root, Some(policy));
+    ctx.checkpoint = Some(checkpoint.clone());
+
+    // Reads and denied writes record nothing.
+    assert!(ctx.check_read("src/lib.rs".into()).is_ok());
+    assert!(ctx.check_update("secret.txt".into()).is_err());
+    assert_eq!(checkpoint.entries().unwrap(), vec![]);
+
+    assert!(ctx.check_update("src/lib.rs".into()).is_ok());
+    assert!(ctx.check_create("src/new.rs".into()).is_ok());
+
+    let entries = checkpoint.entries().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].path, "src/lib.rs");
+    assert!(matches!(&entries[0].state, FileState::File { content, .. } if content == b"before"));
+    assert_eq!(entries[1].path, "src/new.rs");
+    assert_eq!(entries[1].state, FileState::Absent);
+}
+
 #[cfg(unix)]
 #[test]
 fn internal_symlink_cannot_bypass_specific_deny() {
@@ -8,6 +8,8 @@ mod conversation_lock;
 mod error;
 mod handle;
 mod id;
+#[cfg(unix)]
+mod runtime_dir;
 mod sanitize;
 pub mod session;
 pub(crate) mod session_mapping;
@@ -19,6 +21,7 @@ use std::{
 };
 
 use camino::{FromPathBufError, Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, TimeDelta, Utc};
 pub use conversation_lock::{ConversationLock, ConversationMut, LockResult};
 pub use error::Error;
 use error::Result;
@@ -28,13 +31,15 @@ use jp_config::AppConfig;
 use jp_conversation::{Conversation, ConversationId, ConversationStream};
 use jp_storage::{
     backend::{
-        ConversationFilter, ConversationIndexEntry, FsStorageBackend, InMemoryStorageBackend,
-        LoadBackend, LockBackend, NullPersistBackend, PersistBackend, Projection, SessionBackend,
-        StoragePresence,
+        ConversationFilter, ConversationIndexEntry, ConversationLockGuard, FsStorageBackend,
+        InMemoryStorageBackend, LoadBackend, LockBackend, NullPersistBackend, PersistBackend,
+        Projection, SessionBackend, StoragePresence,
     },
     lock::LockInfo,
 };
 use parking_lot::{ArcRwLockReadGuard, RawRwLock, RwLock, RwLockReadGuard};
+#[cfg(unix)]
+pub use runtime_dir::user_runtime_dir;
 pub use sanitize::{SanitizeReport, TrashedConversation};
 use state::State;
 use tracing::{debug, trace, warn};
@@ -199,6 +204,29 @@ impl Workspace {
         Ok(workspace)
     }
 
+    /// Open the same workspace again, sharing this one's storage.
+    ///
+    /// Unlike [`open`], this does not touch the disk: the new workspace uses
+    /// the backends of this one, but starts with an empty index, like a freshly
+    /// opened workspace.
+    /// A long-running process opens a workspace once, and reopens it for every
+    /// command it runs.
+    ///
+    /// [`open`]: Self::open
+    #[must_use]
+    pub fn reopen(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            id: self.id.clone(),
+            persist: self.persist.clone(),
+            loader: self.loader.clone(),
+            locker: self.locker.clone(),
+            sessions: self.sessions.clone(),
+            fs: self.fs.clone(),
+            state: State::default(),
+        }
+    }
+
     /// Get the root path of the workspace.
     #[must_use]
     pub fn root(&self) -> &Utf8Path {
@@ -352,19 +380,61 @@ impl Workspace {
     /// is in the past, then removes them through the persist backend.
     /// If persistence is disabled (`NullPersistBackend`), the removes are
     /// no-ops.
-    pub fn remove_ephemeral_conversations(&mut self, skip: &[ConversationId]) {
-        let expired = self
-            .loader
-            .load_expired_conversation_ids(chrono::Utc::now());
+    ///
+    /// The children of an expired conversation are removed with it.
+    ///
+    /// Returns the IDs of the removed conversations, so that data stored
+    /// outside the conversation directory can be removed with them.
+    pub fn remove_ephemeral_conversations(
+        &mut self,
+        skip: &[ConversationId],
+    ) -> Vec<ConversationId> {
+        let mut expired = self.loader.load_expired_conversation_ids(Utc::now());
+        expired.retain(|id| !skip.contains(id));
+
+        if !expired.is_empty() {
+            let children = expired
+                .iter()
+                .flat_map(|id| self.child_conversation_ids(id))
+                .filter(|id| !skip.contains(id))
+                .collect::<Vec<_>>();
+            expired.extend(children);
+            expired.sort();
+            expired.dedup();
+        }
 
+        let mut removed = vec![];
         for id in expired {
-            if skip.contains(&id) {
-                continue;
+            match self.persist.remove(&id) {
+                Ok(()) => removed.push(id),
+                Err(e) => warn!(%id, %e, "Failed to remove ephemeral conversation."),
             }
-            if let Err(e) = self.persist.remove(&id) {
-                warn!(%id, %e, "Failed to remove ephemeral conversation.");
+        }
+
+        removed
+    }
+
+    /// Returns the conversations whose `parent` is `id`, and their children in
+    /// turn.
+    #[must_use]
+    pub fn child_conversation_ids(&self, id: &ConversationId) -> Vec<ConversationId> {
+        let parents = self
+            .conversations()
+            .filter_map(|(child, conversation)| conversation.parent.map(|parent| (*child, parent)))
+            .collect::<Vec<_>>();
+
+        let mut children = vec![];
+        let mut pending = vec![*id];
+        while let Some(parent) = pending.pop() {
+            for (child, _) in parents.iter().filter(|(_, p)| *p == parent) {
+                if !children.contains(child) {
+                    children.push(*child);
+                    pending.push(*child);
+                }
             }
         }
+
+        children
     }
 
     /// Returns an iterator over all conversations.
@@ -490,13 +560,15 @@ impl Workspace {
         session: Option<&Session>,
         projection: Projection,
     ) -> Result<ConversationLock> {
-        let id = self.create_conversation_with_projection(
-            ConversationId::default(),
-            conversation,
-            config,
-            projection,
-        );
-        self.lock_new_conversation(id, session)
+        let (id, lock_guard) = reserve_conversation_id(
+            &*self.locker,
+            &*self.loader,
+            session.map(|s| s.id.as_str()),
+            |id| self.state.conversations.contains_key(id),
+        )?;
+
+        self.create_conversation_with_projection(id, conversation, config, projection);
+        Ok(self.new_conversation_lock(id, lock_guard))
     }
 
     /// Create a new conversation with a specific ID and acquire an exclusive
@@ -516,6 +588,18 @@ impl Workspace {
         self.lock_new_conversation(id, session)
     }
 
+    /// A factory for creating conversations from outside the workspace.
+    ///
+    /// See [`ConversationFactory`].
+    #[must_use]
+    pub fn conversation_factory(&self) -> ConversationFactory {
+        ConversationFactory {
+            persist: Arc::clone(&self.persist),
+            loader: Arc::clone(&self.loader),
+            locker: Arc::clone(&self.locker),
+        }
+    }
+
     /// Lock a just-created conversation.
     ///
     /// Returns an error if the lock cannot be acquired.
@@ -533,6 +617,15 @@ impl Workspace {
             .try_lock(&id.to_string(), session_str)?
             .ok_or_else(|| Error::LockFailed(id.to_string()))?;
 
+        Ok(self.new_conversation_lock(id, lock_guard))
+    }
+
+    /// Wrap a just-created conversation and the guard of its lock.
+    fn new_conversation_lock(
+        &self,
+        id: ConversationId,
+        lock_guard: Box<dyn ConversationLockGuard>,
+    ) -> ConversationLock {
         let metadata = self
             .state
             .conversations
@@ -551,14 +644,14 @@ impl Workspace {
 
         let handle = ConversationHandle::new(id);
         let projection = self.lock_projection(&id);
-        Ok(ConversationLock::new(
+        ConversationLock::new(
             handle,
             metadata,
             events,
             Arc::clone(&self.persist),
             lock_guard,
             projection,
-        ))
+        )
     }
 
     /// Resolve the write projection for a conversation from its stored
@@ -928,6 +1021,177 @@ fn maybe_init_events(
 /// The path is JP-specific: no `jp` suffix is appended.
 const USER_DATA_DIR_ENV_VAR: &str = "JP_USER_DATA_DIR";
 
+/// Creates conversations without borrowing the [`Workspace`].
+///
+/// Work that runs while a turn holds the workspace, such as a sub-agent
+/// delegating to a child conversation, cannot create conversations through
+/// [`Workspace::create_and_lock_conversation`].
+/// The factory shares the workspace's persist and lock backends, so the
+/// conversations it creates are locked and persisted like any other, but they
+/// are not added to the in-memory state of the workspace that produced it.
+#[derive(Clone)]
+pub struct ConversationFactory {
+    persist: Arc<dyn PersistBackend>,
+    loader: Arc<dyn LoadBackend>,
+    locker: Arc<dyn LockBackend>,
+}
+
+impl ConversationFactory {
+    /// Create a new conversation and acquire an exclusive lock on it.
+    ///
+    /// Nothing is written until the returned lock's [`ConversationMut`] is
+    /// flushed or dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no conversation ID can be locked.
+    pub fn create_and_lock(
+        &self,
+        conversation: Conversation,
+        config: Arc<AppConfig>,
+        projection: Projection,
+    ) -> Result<ConversationLock> {
+        let (id, lock_guard) =
+            reserve_conversation_id(&*self.locker, &*self.loader, None, |_| false)?;
+
+        let events = ConversationStream::new(config).with_created_at(id.timestamp());
+
+        Ok(ConversationLock::new(
+            ConversationHandle::new(id),
+            Arc::new(RwLock::new(conversation)),
+            Arc::new(RwLock::new(events)),
+            Arc::clone(&self.persist),
+            lock_guard,
+            projection,
+        ))
+    }
+}
+
+/// How many IDs [`reserve_conversation_id`] tries before giving up.
+const MAX_ID_ATTEMPTS: u32 = 100;
+
+/// Pick an unused conversation ID and lock it.
+///
+/// IDs have decisecond resolution, so conversations created in quick
+/// succession would otherwise share one.
+/// Starting at the current time, the ID is bumped by a decisecond until its
+/// lock can be taken and, while holding it, the ID is neither `indexed` nor
+/// stored by `loader`.
+fn reserve_conversation_id(
+    locker: &dyn LockBackend,
+    loader: &dyn LoadBackend,
+    session: Option<&str>,
+    indexed: impl Fn(&ConversationId) -> bool,
+) -> Result<(ConversationId, Box<dyn ConversationLockGuard>)> {
+    let mut id = ConversationId::default();
+    for _ in 0..MAX_ID_ATTEMPTS {
+        if let Some(guard) = locker.try_lock(&id.to_string(), session)?
+            && !indexed(&id)
+            && !is_stored(loader, &id)
+        {
+            return Ok((id, guard));
+        }
+
+        id = ConversationId::try_from(id.timestamp() + TimeDelta::milliseconds(100))?;
+    }
+
+    Err(Error::LockFailed(id.to_string()))
+}
+
+/// Whether `loader` holds a conversation with the given ID, live or archived.
+///
+/// A conversation that fails to load for any reason other than being missing
+/// still exists.
+fn is_stored(loader: &dyn LoadBackend, id: &ConversationId) -> bool {
+    match loader.load_conversation_metadata(id) {
+        Ok(_) => true,
+        Err(error) => !error.kind().is_missing(),
+    }
+}
+
+/// Records the LLM requests JP makes on its own behalf.
+///
+/// Each request, such as generating a title or answering a tool's question, is
+/// stored as a hidden child conversation of the conversation it was made for,
+/// so it can be inspected with the regular conversation commands.
+#[derive(Clone)]
+pub struct ScratchRecorder {
+    factory: ConversationFactory,
+    parent: ConversationId,
+    projection: Projection,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl ScratchRecorder {
+    /// Create a recorder for requests made on behalf of `parent`.
+    ///
+    /// Scratch conversations share the parent's `projection`, so the requests
+    /// of a `--local` conversation stay out of the workspace.
+    /// They also share the parent's `expires_at`, so they don't outlive a
+    /// temporary conversation.
+    #[must_use]
+    pub const fn new(
+        factory: ConversationFactory,
+        parent: ConversationId,
+        projection: Projection,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            factory,
+            parent,
+            projection,
+            expires_at,
+        }
+    }
+
+    /// The conversation the recorded requests are made on behalf of.
+    #[must_use]
+    pub const fn parent(&self) -> ConversationId {
+        self.parent
+    }
+
+    /// Store `events` as a new scratch conversation with the given `title`.
+    ///
+    /// Recording is best-effort: a failure is logged, and `None` returned,
+    /// rather than failing the request it records.
+    pub fn record(
+        &self,
+        title: impl Into<String>,
+        events: ConversationStream,
+    ) -> Option<ConversationId> {
+        let conversation = Conversation {
+            title: Some(title.into()),
+            parent: Some(self.parent),
+            hidden: true,
+            expires_at: self.expires_at,
+            ..Conversation::default()
+        };
+
+        let lock =
+            match self
+                .factory
+                .create_and_lock(conversation, events.base_config(), self.projection)
+            {
+                Ok(lock) => lock,
+                Err(error) => {
+                    warn!(parent = %self.parent, %error, "Failed to create scratch conversation.");
+                    return None;
+                }
+            };
+
+        let id = lock.id();
+        let mut conv = lock.into_mut();
+        conv.update_events(|stream| *stream = events.with_created_at(id.timestamp()));
+        if let Err(error) = conv.flush() {
+            warn!(%id, %error, "Failed to persist scratch conversation.");
+            return None;
+        }
+
+        debug!(parent = %self.parent, scratch = %id, "Recorded scratch conversation.");
+        Some(id)
+    }
+}
+
 /// Returns the directory JP stores its per-user data in.
 ///
 /// Resolution order:
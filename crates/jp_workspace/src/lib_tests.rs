@@ -210,6 +210,148 @@ fn test_workspace_persist_via_lock() {
     let _metadata: Conversation = read_json(&metadata_path).unwrap();
 }
 
+#[test]
+fn factory_conversations_persist_to_workspace_storage() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path().join("root");
+    let storage = root.join("storage");
+
+    let workspace = workspace_with_fs(&root, &FsStorageBackend::new(&storage).unwrap());
+    let parent = ConversationId::try_from(Utc::now() - Duration::from_secs(1)).unwrap();
+
+    let lock = workspace
+        .conversation_factory()
+        .create_and_lock(
+            Conversation {
+                parent: Some(parent),
+                ..Conversation::default()
+            },
+            AppConfig::new_test().into(),
+            Projection::Projected,
+        )
+        .unwrap();
+    let id = lock.id();
+
+    // The factory does not touch the workspace's in-memory state.
+    assert!(!workspace.state.conversations.contains_key(&id));
+
+    let mut conv = lock.into_mut();
+    conv.update_metadata(|_| {});
+    conv.flush().unwrap();
+    drop(conv);
+
+    let fs = FsStorageBackend::new(&storage).unwrap();
+    let metadata: Conversation = read_json(&fs.conversation_metadata_path(&id).unwrap()).unwrap();
+    assert_eq!(metadata.parent, Some(parent));
+}
+
+#[test]
+fn back_to_back_conversations_get_distinct_ids() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path().join("root");
+    let storage = root.join("storage");
+
+    let mut workspace = workspace_with_fs(&root, &FsStorageBackend::new(&storage).unwrap());
+    let config = Arc::new(AppConfig::new_test());
+
+    // Each lock is released before the next conversation is created, so only
+    // the index and the stored conversations keep the IDs apart.
+    let mut ids = vec![];
+    for _ in 0..2 {
+        let lock = workspace
+            .create_and_lock_conversation(Conversation::default(), config.clone(), None)
+            .unwrap();
+        ids.push(lock.id());
+
+        let mut conv = lock.into_mut();
+        conv.update_metadata(|_| {});
+        conv.flush().unwrap();
+    }
+
+    let factory = workspace.conversation_factory();
+    for _ in 0..2 {
+        let lock = factory
+            .create_and_lock(
+                Conversation::default(),
+                config.clone(),
+                Projection::Projected,
+            )
+            .unwrap();
+        ids.push(lock.id());
+
+        let mut conv = lock.into_mut();
+        conv.update_metadata(|_| {});
+        conv.flush().unwrap();
+    }
+
+    let mut unique = ids.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), ids.len(), "{ids:?}");
+}
+
+#[test]
+fn scratch_recorder_stores_hidden_child_conversation() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path().join("root");
+    let storage = root.join("storage");
+
+    let workspace = workspace_with_fs(&root, &FsStorageBackend::new(&storage).unwrap());
+    let parent = ConversationId::try_from(Utc::now() - Duration::from_secs(1)).unwrap();
+    let expires_at = Utc::now() + Duration::from_secs(3600);
+    let recorder = ScratchRecorder::new(
+        workspace.conversation_factory(),
+        parent,
+        Projection::Projected,
+        Some(expires_at),
+    );
+
+    let events = ConversationStream::new_test().with_turn("Generate a title.");
+    let id = recorder.record("Title generation", events).unwrap();
+
+    let fs = FsStorageBackend::new(&storage).unwrap();
+    let metadata: Conversation = read_json(&fs.conversation_metadata_path(&id).unwrap()).unwrap();
+    assert_eq!(metadata.parent, Some(parent));
+    assert_eq!(metadata.title.as_deref(), Some("Title generation"));
+    assert!(metadata.hidden);
+    assert_eq!(
+        metadata.expires_at.map(|at| at.timestamp()),
+        Some(expires_at.timestamp())
+    );
+    assert!(fs.conversation_events_path(&id).unwrap().is_file());
+}
+
+#[test]
+fn scratch_recorder_records_back_to_back_requests_separately() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path().join("root");
+    let storage = root.join("storage");
+
+    let workspace = workspace_with_fs(&root, &FsStorageBackend::new(&storage).unwrap());
+    let parent = ConversationId::try_from(Utc::now() - Duration::from_secs(1)).unwrap();
+    let recorder = ScratchRecorder::new(
+        workspace.conversation_factory(),
+        parent,
+        Projection::Projected,
+        None,
+    );
+
+    let first = recorder
+        .record("First", ConversationStream::new_test().with_turn("One"))
+        .unwrap();
+    let second = recorder
+        .record("Second", ConversationStream::new_test().with_turn("Two"))
+        .unwrap();
+    assert_ne!(first, second);
+
+    let fs = FsStorageBackend::new(&storage).unwrap();
+    for (id, title) in [(first, "First"), (second, "Second")] {
+        let metadata: Conversation =
+            read_json(&fs.conversation_metadata_path(&id).unwrap()).unwrap();
+        assert_eq!(metadata.title.as_deref(), Some(title));
+    }
+}
+
 #[test]
 fn test_workspace_conversations() {
     let mut workspace = Workspace::in_memory(Utf8PathBuf::new());
@@ -569,6 +711,17 @@ fn test_remove_ephemeral_conversations() {
     // No expiration: should be kept.
     ws.create_conversation_with_id(permanent_id, Conversation::default(), config.clone());
 
+    // Child of an expired conversation: should be removed with it.
+    let child_id = ConversationId::try_from(datetime!(2024-01-06 00:00:00 Z)).unwrap();
+    ws.create_conversation_with_id(
+        child_id,
+        Conversation {
+            parent: Some(expired_id),
+            ..Default::default()
+        },
+        config.clone(),
+    );
+
     // Flush all to disk so the filesystem scanner can see them.
     for &id in &[
         expired_id,
@@ -576,6 +729,7 @@ fn test_remove_ephemeral_conversations() {
         expired_titled_id,
         skipped_id,
         permanent_id,
+        child_id,
     ] {
         let h = ws.acquire_conversation(&id).unwrap();
         let mut conv = ws.test_lock(h).into_mut();
@@ -583,7 +737,11 @@ fn test_remove_ephemeral_conversations() {
         conv.flush().unwrap();
     }
 
-    ws.remove_ephemeral_conversations(&[skipped_id]);
+    let mut removed = ws.remove_ephemeral_conversations(&[skipped_id]);
+    removed.sort();
+    let mut expected = vec![expired_id, expired_titled_id, child_id];
+    expected.sort();
+    assert_eq!(removed, expected);
 
     let fs_check = FsStorageBackend::new(&storage).unwrap();
     assert!(
@@ -606,6 +764,42 @@ fn test_remove_ephemeral_conversations() {
         fs_check.find_conversation_dir(&permanent_id).is_some(),
         "permanent conversation should be kept"
     );
+    assert!(
+        fs_check.find_conversation_dir(&child_id).is_none(),
+        "child of an expired conversation should be removed"
+    );
+}
+
+#[test]
+fn test_child_conversation_ids() {
+    let mut ws = Workspace::in_memory(Utf8PathBuf::new());
+    let config = Arc::new(AppConfig::new_test());
+
+    let parent = ConversationId::try_from(datetime!(2024-01-01 00:00:00 Z)).unwrap();
+    let child = ConversationId::try_from(datetime!(2024-01-02 00:00:00 Z)).unwrap();
+    let grandchild = ConversationId::try_from(datetime!(2024-01-03 00:00:00 Z)).unwrap();
+    let unrelated = ConversationId::try_from(datetime!(2024-01-04 00:00:00 Z)).unwrap();
+
+    for (id, parent) in [
+        (parent, None),
+        (child, Some(parent)),
+        (grandchild, Some(child)),
+        (unrelated, None),
+    ] {
+        ws.create_conversation_with_id(
+            id,
+            Conversation {
+                parent,
+                ..Default::default()
+            },
+            config.clone(),
+        );
+    }
+
+    let mut children = ws.child_conversation_ids(&parent);
+    children.sort();
+    assert_eq!(children, vec![child, grandchild]);
+    assert_eq!(ws.child_conversation_ids(&unrelated), vec![]);
 }
 
 /// Verify that `NullLockBackend` (used for `--no-persist`) allows multiple
@@ -1025,6 +1219,32 @@ fn open_errors_when_no_store_exists_above_dir() {
     );
 }
 
+#[test]
+fn reopen_shares_storage_but_not_the_index() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path().join("root");
+    let storage = root.join("storage");
+
+    let mut workspace = workspace_with_fs(&root, &FsStorageBackend::new(&storage).unwrap());
+    let mut reopened = workspace.reopen();
+    assert_eq!(reopened.root(), workspace.root());
+    assert_eq!(reopened.id(), workspace.id());
+
+    // A conversation persisted through one shows up in the other once its
+    // index is loaded, but not before.
+    let id = workspace.create_conversation(Conversation::default(), AppConfig::new_test().into());
+    let h = workspace.acquire_conversation(&id).unwrap();
+    let mut conv = workspace.test_lock(h).into_mut();
+    conv.update_metadata(|_| {});
+    conv.flush().unwrap();
+    drop(conv);
+
+    assert_eq!(reopened.conversations().count(), 0);
+    reopened.load_conversation_index();
+    let ids: Vec<_> = reopened.conversations().map(|(id, _)| *id).collect();
+    assert_eq!(ids, vec![id]);
+}
+
 #[test]
 fn in_memory_workspace_has_no_fs_storage() {
     assert!(Workspace::in_memory("root").fs_storage().is_none());
@@ -0,0 +1,65 @@
+//! The per-user runtime directory, which holds the sockets of JP processes.
+
+use std::{
+    env,
+    fs::{self, DirBuilder},
+    io,
+    os::unix::fs::{DirBuilderExt as _, MetadataExt as _},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::{APPLICATION, error::Result};
+
+/// Returns the directory JP creates its sockets in, creating it if needed.
+///
+/// This is `jp` in `$XDG_RUNTIME_DIR`, or `jp-<uid>` in the temporary
+/// directory if that is not set.
+/// Anyone who can connect to a socket can talk to the process behind it, so the
+/// directory is private to the current user.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be created, or if it exists but is
+/// not a directory private to the current user.
+pub fn user_runtime_dir() -> Result<Utf8PathBuf> {
+    // SAFETY: `getuid` is always successful.
+    let uid = unsafe { libc::getuid() };
+
+    let dir = match env::var("XDG_RUNTIME_DIR") {
+        Ok(dir) if !dir.is_empty() => Utf8PathBuf::from(dir).join(APPLICATION),
+        _ => Utf8PathBuf::try_from(env::temp_dir())
+            .unwrap_or_else(|_| "/tmp".into())
+            .join(format!("{APPLICATION}-{uid}")),
+    };
+
+    private_dir(&dir, uid)
+        .map_err(|error| io::Error::new(error.kind(), format!("{dir}: {error}")))?;
+
+    Ok(dir)
+}
+
+/// Create `dir` accessible only by the user `uid`, or make sure that it is.
+///
+/// Other users can create files in the shared temporary directory, so an
+/// existing directory is only used if it is private to the user.
+fn private_dir(dir: &Utf8Path, uid: u32) -> io::Result<()> {
+    match DirBuilder::new().mode(0o700).create(dir) {
+        Err(error) if error.kind() != io::ErrorKind::AlreadyExists => return Err(error),
+        _ => {}
+    }
+
+    let meta = fs::symlink_metadata(dir)?;
+    if !meta.is_dir() || meta.uid() != uid || meta.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "not a directory private to the current user",
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "runtime_dir_tests.rs"]
+mod tests;
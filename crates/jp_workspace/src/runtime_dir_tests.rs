@@ -0,0 +1,44 @@
+use std::os::unix::fs::PermissionsExt as _;
+
+use serial_test::serial;
+
+use super::*;
+
+#[test]
+fn test_private_dir() {
+    let tmp = camino_tempfile::tempdir().unwrap();
+    let uid = fs::metadata(tmp.path()).unwrap().uid();
+
+    let dir = tmp.path().join("new");
+    private_dir(&dir, uid).unwrap();
+    let mode = fs::metadata(&dir).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o700);
+
+    // Reusing the directory is fine.
+    private_dir(&dir, uid).unwrap();
+
+    // A directory other users can enter is not.
+    let shared = tmp.path().join("shared");
+    fs::create_dir(&shared).unwrap();
+    fs::set_permissions(&shared, fs::Permissions::from_mode(0o755)).unwrap();
+    assert!(private_dir(&shared, uid).is_err());
+
+    // Nor is one owned by another user.
+    assert!(private_dir(&dir, uid + 1).is_err());
+}
+
+#[test]
+#[serial(env_vars)]
+fn test_user_runtime_dir_is_jp_in_xdg_runtime_dir() {
+    let tmp = camino_tempfile::tempdir().unwrap();
+    let previous = env::var_os("XDG_RUNTIME_DIR");
+    unsafe { env::set_var("XDG_RUNTIME_DIR", tmp.path()) };
+
+    let dir = user_runtime_dir();
+
+    match previous {
+        Some(value) => unsafe { env::set_var("XDG_RUNTIME_DIR", value) },
+        None => unsafe { env::remove_var("XDG_RUNTIME_DIR") },
+    }
+    assert_eq!(dir.unwrap(), tmp.path().join("jp"));
+}